@@ -9,17 +9,50 @@
 // 4. Conectamos todo mediante inyección de dependencias
 // 5. Arrancamos el servidor
 
+use api_rest::{ConfigAuth, ConfigLimites};
+use caldav::ConfigCalDav;
+use chrono::FixedOffset;
 use reservas_adapters::{
-    InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+    CachedEmpleadoRepository, CachedReservaRepository, ClienteWebhookHttp, ConfigOidc,
+    InMemoryCuentaRepository, InMemoryEmpleadoRepository, InMemoryRegistroAuditoria,
+    InMemoryReservaRepository, InMemorySalaRepository, InMemoryVersionCalendarioRepository,
+    InMemoryWebhookRepository, MeasuredEmpleadoRepository, MeasuredReservaRepository,
+    MeasuredSalaRepository, MetricasPrometheus, ProveedorOidc,
 };
-use reservas_application::{EmpleadoServiceImpl, ReservaServiceImpl, SalaServiceImpl};
+use reservas_application::{
+    AuditoriaServiceImpl, CalendarioServiceImpl, CargaMasivaServiceImpl, CuentaServiceImpl,
+    DisponibilidadEnVivoServiceImpl, EmpleadoServiceImpl, ImportacionServiceImpl,
+    InformeServiceImpl, IntegridadServiceImpl, MatrizDisponibilidadServiceImpl,
+    PoliticaDesactivacion, ReservaServiceImpl, RespaldoServiceImpl, SalaServiceImpl,
+    SaludServiceImpl, SsoServiceImpl, WebhookServiceImpl,
+};
+use reservas_domain::{Actor, Rol};
+use reservas_ports::out::auditoria::RegistroAuditoria;
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::metricas::Metricas;
+use reservas_ports::out::notificador::NotificadorCambios;
 use reservas_ports::r#in::reserva_service::ReservaService;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use uuid::Uuid;
 
+use reservas_ports::r#in::auditoria_service::AuditoriaService;
+use reservas_ports::r#in::calendario_service::CalendarioService;
+use reservas_ports::r#in::carga_masiva_service::CargaMasivaService;
+use reservas_ports::r#in::cuenta_service::{CuentaService, NuevaCuenta};
+use reservas_ports::r#in::disponibilidad_en_vivo_service::DisponibilidadEnVivoService;
 use reservas_ports::r#in::empleado_service::EmpleadoService;
+use reservas_ports::r#in::importacion_service::ImportacionService;
+use reservas_ports::r#in::informe_service::InformeService;
+use reservas_ports::r#in::integridad_service::IntegridadService;
+use reservas_ports::r#in::matriz_disponibilidad_service::MatrizDisponibilidadService;
+use reservas_ports::r#in::respaldo_service::RespaldoService;
 use reservas_ports::r#in::sala_service::SalaService;
+use reservas_ports::r#in::salud_service::SaludService;
+use reservas_ports::r#in::sso_service::SsoService;
+use reservas_ports::r#in::webhook_service::WebhookService;
 
 #[tokio::main]
 async fn main() {
@@ -35,35 +68,244 @@ async fn main() {
     info!("🚀 Sistema de Reservas - API REST");
     info!("📦 Arquitectura Hexagonal (Puertos y Adaptadores)");
 
+    // Métricas de peticiones, reservas y repositorios, expuestas en /metrics
+    let metricas = Arc::new(MetricasPrometheus::new());
+
     // 1. ADAPTADORES DE SALIDA: Repositorios en memoria
     info!("🔧 Configurando adaptadores de salida (repositorios)");
     let empleado_repo: InMemoryEmpleadoRepository = InMemoryEmpleadoRepository::new();
     let reserva_repo: InMemoryReservaRepository = InMemoryReservaRepository::new();
     let sala_repository: InMemorySalaRepository = InMemorySalaRepository::new();
 
+    // Los tiempos se miden sobre el repositorio real, por debajo de la caché
+    let medidor = Arc::clone(&metricas) as Arc<dyn Metricas>;
+    let empleado_repo = MeasuredEmpleadoRepository::new(empleado_repo, Arc::clone(&medidor));
+    let reserva_repo = MeasuredReservaRepository::new(reserva_repo, Arc::clone(&medidor));
+    let sala_repository = MeasuredSalaRepository::new(sala_repository, medidor);
+
+    // Caché de lecturas: la disponibilidad lista TODOS los empleados y reservas
+    // en cada petición. TTL configurable con RESERVAS_CACHE_TTL (segundos).
+    let ttl = std::env::var("RESERVAS_CACHE_TTL")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(30));
+    info!("🗄️  Caché de repositorios con TTL de {:?}", ttl);
+    let empleado_repo = CachedEmpleadoRepository::new(empleado_repo, ttl);
+    let reserva_repo = CachedReservaRepository::new(reserva_repo, ttl);
+    metricas.registrar_cache("empleados", empleado_repo.metricas());
+    metricas.registrar_cache("reservas", reserva_repo.metricas());
+
+    // Los repositorios se comparten entre los casos de uso que los necesitan
+    let empleado_repo = Arc::new(empleado_repo);
+    let reserva_repo = Arc::new(reserva_repo);
+    let sala_repository = Arc::new(sala_repository);
+    // Intentos denegados por falta de permisos (los últimos 1000)
+    let auditoria: Arc<dyn RegistroAuditoria> = Arc::new(InMemoryRegistroAuditoria::new());
+
     // 2. SERVICIOS DE APLICACIÓN: Casos de uso
     info!("⚙️  Configurando servicios de aplicación");
-    let empleado_service: Arc<dyn EmpleadoService> =
-        Arc::new(EmpleadoServiceImpl::new(empleado_repo)) as Arc<dyn EmpleadoService>;
-    let reserva_service: Arc<dyn ReservaService> =
-        Arc::new(ReservaServiceImpl::new(reserva_repo)) as Arc<dyn ReservaService>;
-    let sala_service: Arc<dyn SalaService> =
-        Arc::new(SalaServiceImpl::new(sala_repository)) as Arc<dyn SalaService>;
+    // Los cambios en las reservas se difunden a las pantallas suscritas
+    let en_vivo = Arc::new(DisponibilidadEnVivoServiceImpl::new(
+        Arc::clone(&reserva_repo),
+        Arc::clone(&empleado_repo),
+    ));
+    // ...y a los sistemas externos suscritos con webhooks
+    let webhooks = Arc::new(
+        WebhookServiceImpl::new(
+            Arc::new(InMemoryWebhookRepository::new()),
+            Arc::new(ClienteWebhookHttp::new()),
+        )
+        .con_auditoria(Arc::clone(&auditoria)),
+    );
+    // Qué hacer con las reservas futuras al desactivar un empleado:
+    // RESERVAS_POLITICA_DESACTIVACION=cancelar|bloquear|reasignar:<empleado_id>
+    let politica: PoliticaDesactivacion = match std::env::var("RESERVAS_POLITICA_DESACTIVACION") {
+        Ok(valor) => valor.parse().unwrap_or_else(|e| panic!("{}", e)),
+        Err(_) => PoliticaDesactivacion::default(),
+    };
+    info!("👤 Política al desactivar empleados: {:?}", politica);
+    let reserva_service: Arc<dyn ReservaService> = Arc::new(
+        ReservaServiceImpl::new(
+            Arc::clone(&reserva_repo),
+            Arc::clone(&empleado_repo),
+            Arc::clone(&sala_repository),
+        )
+        .con_auditoria(Arc::clone(&auditoria))
+        .con_metricas(Arc::clone(&metricas) as Arc<dyn Metricas>)
+        .con_notificador(Arc::clone(&en_vivo) as Arc<dyn NotificadorCambios>)
+        .con_notificador(Arc::clone(&webhooks) as Arc<dyn NotificadorCambios>),
+    ) as Arc<dyn ReservaService>;
+    // Las reservas de un empleado que se desactiva cambian a través del
+    // servicio de reservas, para que se enteren los notificadores
+    let empleado_service: Arc<dyn EmpleadoService> = Arc::new(
+        EmpleadoServiceImpl::new(Arc::clone(&empleado_repo), Arc::clone(&reserva_service))
+            .con_politica(politica)
+            .con_auditoria(Arc::clone(&auditoria)),
+    ) as Arc<dyn EmpleadoService>;
+    let en_vivo: Arc<dyn DisponibilidadEnVivoService> = en_vivo;
+    let webhook_service: Arc<dyn WebhookService> = webhooks;
+    let sala_service: Arc<dyn SalaService> = Arc::new(
+        SalaServiceImpl::new(Arc::clone(&sala_repository), Arc::clone(&reserva_repo))
+            .con_auditoria(Arc::clone(&auditoria)),
+    ) as Arc<dyn SalaService>;
+    let respaldo_service: Arc<dyn RespaldoService> = Arc::new(
+        RespaldoServiceImpl::new(
+            Arc::clone(&empleado_repo),
+            Arc::clone(&sala_repository),
+            Arc::clone(&reserva_repo),
+            Arc::clone(&reserva_service),
+        )
+        .con_auditoria(Arc::clone(&auditoria)),
+    ) as Arc<dyn RespaldoService>;
+    let cuenta_service: Arc<dyn CuentaService> = Arc::new(
+        CuentaServiceImpl::new(InMemoryCuentaRepository::new(), Arc::clone(&empleado_repo))
+            .con_auditoria(Arc::clone(&auditoria)),
+    ) as Arc<dyn CuentaService>;
+    let calendario_service: Arc<dyn CalendarioService> = Arc::new(
+        CalendarioServiceImpl::new(
+            Arc::clone(&empleado_repo),
+            Arc::clone(&sala_repository),
+            Arc::clone(&reserva_repo),
+            InMemoryVersionCalendarioRepository::new(),
+            secreto_calendarios(),
+        )
+        .con_auditoria(Arc::clone(&auditoria)),
+    ) as Arc<dyn CalendarioService>;
+    let importacion_service: Arc<dyn ImportacionService> = Arc::new(ImportacionServiceImpl::new(
+        Arc::clone(&empleado_repo),
+        Arc::clone(&reserva_service),
+    )) as Arc<dyn ImportacionService>;
+    let carga_masiva_service: Arc<dyn CargaMasivaService> = Arc::new(
+        CargaMasivaServiceImpl::new(
+            Arc::clone(&empleado_service),
+            Arc::clone(&sala_service),
+            Arc::clone(&reserva_service),
+        )
+        .con_auditoria(Arc::clone(&auditoria)),
+    ) as Arc<dyn CargaMasivaService>;
+    let informe_service: Arc<dyn InformeService> = Arc::new(InformeServiceImpl::new(
+        Arc::clone(&empleado_repo),
+        Arc::clone(&sala_repository),
+        Arc::clone(&reserva_repo),
+    )) as Arc<dyn InformeService>;
+    let matriz_service: Arc<dyn MatrizDisponibilidadService> =
+        Arc::new(MatrizDisponibilidadServiceImpl::new(
+            Arc::clone(&empleado_repo),
+            Arc::clone(&sala_repository),
+            Arc::clone(&reserva_repo),
+        )) as Arc<dyn MatrizDisponibilidadService>;
+    let salud_service: Arc<dyn SaludService> = Arc::new(SaludServiceImpl::new(
+        Arc::clone(&empleado_repo),
+        Arc::clone(&sala_repository),
+        Arc::clone(&reserva_repo),
+    )) as Arc<dyn SaludService>;
+    let sso_service = configurar_oidc(Arc::clone(&empleado_repo)).await;
+    let integridad_service: Arc<dyn IntegridadService> = Arc::new(
+        IntegridadServiceImpl::new(
+            empleado_repo,
+            sala_repository,
+            reserva_repo,
+            Arc::clone(&reserva_service),
+        )
+        .con_auditoria(Arc::clone(&auditoria)),
+    ) as Arc<dyn IntegridadService>;
+    let auditoria_service: Arc<dyn AuditoriaService> =
+        Arc::new(AuditoriaServiceImpl::new(auditoria)) as Arc<dyn AuditoriaService>;
+
+    // Verificación de integridad al arrancar. Con RESERVAS_FSCK_REPARAR=1
+    // además se cancelan las reservas activas afectadas.
+    let reparar = std::env::var("RESERVAS_FSCK_REPARAR").is_ok_and(|v| v == "1");
+    match integridad_service
+        .verificar(&Actor::sistema(), reparar)
+        .await
+    {
+        Ok(informe) if informe.es_consistente() => {
+            info!(
+                "🩺 Integridad verificada: {} reservas sin incidencias",
+                informe.reservas_revisadas
+            );
+        }
+        Ok(informe) => {
+            for incidencia in &informe.incidencias {
+                warn!(
+                    "🩺 Reserva {} ({:?}): {}{}",
+                    incidencia.reserva_id,
+                    incidencia.tipo,
+                    incidencia.detalle,
+                    if incidencia.reparada {
+                        " [cancelada]"
+                    } else {
+                        ""
+                    }
+                );
+            }
+            warn!(
+                "🩺 {} incidencias de integridad ({} reparadas)",
+                informe.incidencias.len(),
+                informe.reparadas()
+            );
+        }
+        Err(e) => warn!("🩺 No se pudo verificar la integridad: {}", e),
+    }
 
     // 3. ADAPTADORES DE ENTRADA: API REST + Web UI
     info!("🌐 Configurando adaptadores de entrada");
+    let auth = configurar_auth(sso_service.clone());
     let api_router = api_rest::crear_router(
         Arc::clone(&empleado_service),
         Arc::clone(&reserva_service),
         Arc::clone(&sala_service),
+        respaldo_service,
+        integridad_service,
+        auditoria_service,
+        Arc::clone(&en_vivo),
+        webhook_service,
+        calendario_service,
+        importacion_service,
+        Arc::clone(&carga_masiva_service),
+        Arc::clone(&informe_service),
+        matriz_service,
+        auth,
+        configurar_limites(),
     );
+    crear_cuenta_admin(cuenta_service.as_ref()).await;
+    // Con RESERVAS_WEB_COOKIE_SEGURA=1 la cookie de sesión solo viaja por HTTPS
+    let sesiones = web_ui::AlmacenSesiones::new()
+        .con_cookie_segura(std::env::var("RESERVAS_WEB_COOKIE_SEGURA").is_ok_and(|v| v == "1"));
     let web_router = web_ui::crear_router_web(
         Arc::clone(&empleado_service),
         Arc::clone(&reserva_service),
         Arc::clone(&sala_service),
+        Arc::clone(&cuenta_service),
+        carga_masiva_service,
+        informe_service,
+        en_vivo,
+        Arc::new(sesiones),
+        sso_service,
+    );
+    let caldav_router = caldav::crear_router_caldav(
+        configurar_caldav(),
+        empleado_service,
+        reserva_service,
+        sala_service,
+        cuenta_service,
     );
-    // Combinar ambos routers: Web UI en la raíz, API REST bajo /api
-    let app = web_router.merge(axum::Router::new().nest("/api", api_router));
+    // Combinar los routers: Web UI en la raíz, API REST bajo /api, CalDAV
+    // bajo /caldav y salud y métricas en /health, /ready y /metrics. Todas
+    // las peticiones se miden.
+    let metricas = metricas as Arc<dyn Metricas>;
+    let app = web_router
+        .merge(axum::Router::new().nest("/api", api_router))
+        .merge(caldav_router)
+        .merge(api_rest::crear_router_salud(
+            salud_service,
+            Arc::clone(&metricas),
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            metricas,
+            api_rest::medir_peticiones,
+        ));
 
     // 4. Arrancar el servidor
     let addr = "0.0.0.0:3000";
@@ -73,7 +315,169 @@ async fn main() {
     info!("🌐 Interfaz Web: http://{}/", addr);
     info!("📖 Swagger UI: http://{}/api/swagger-ui", addr);
     info!("📖 OpenAPI JSON: http://{}/api/api-docs/openapi.json", addr);
+    info!("📅 CalDAV: http://{}/caldav/", addr);
+    info!("🩺 Salud: http://{}/health, http://{}/ready", addr, addr);
+    info!("📈 Métricas: http://{}/metrics", addr);
     warn!("🎯 Presiona Ctrl+C para detener el servidor");
 
-    axum::serve(listener, app).await.unwrap();
+    // La IP de origen identifica a los clientes cuando la API no exige credenciales
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await
+    .unwrap();
+}
+
+/// Credenciales de la API REST:
+/// - RESERVAS_API_KEYS=nombre:clave,nombre:clave
+/// - RESERVAS_JWT_SECRETO=<secreto HS256>
+/// - RESERVAS_JWT_CLAVE_PUBLICA=<fichero PEM con la clave pública RS256>
+/// - RESERVAS_JWT_EMISOR / RESERVAS_JWT_AUDIENCIA: `iss` / `aud` exigidos en los JWT
+/// - RESERVAS_API_SIN_AUTH=1 deja la API abierta (solo para desarrollo)
+///
+/// Con OIDC configurado se aceptan también los access tokens del proveedor.
+fn configurar_auth(sso: Option<Arc<dyn SsoService>>) -> ConfigAuth {
+    if std::env::var("RESERVAS_API_SIN_AUTH").is_ok_and(|v| v == "1") {
+        warn!("🔓 API REST SIN AUTENTICACIÓN (RESERVAS_API_SIN_AUTH=1)");
+        return ConfigAuth::desactivada();
+    }
+
+    let mut auth = ConfigAuth::new();
+    if let Ok(claves) = std::env::var("RESERVAS_API_KEYS") {
+        auth = auth
+            .con_claves_api(&claves)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Ok(secreto) = std::env::var("RESERVAS_JWT_SECRETO") {
+        auth = auth.con_jwt_hs256(secreto.as_bytes());
+    }
+    if let Ok(ruta) = std::env::var("RESERVAS_JWT_CLAVE_PUBLICA") {
+        let pem = std::fs::read(&ruta)
+            .unwrap_or_else(|e| panic!("No se pudo leer la clave pública {}: {}", ruta, e));
+        auth = auth.con_jwt_rs256(&pem).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Ok(emisor) = std::env::var("RESERVAS_JWT_EMISOR") {
+        auth = auth.con_emisor(emisor);
+    }
+    if let Ok(audiencia) = std::env::var("RESERVAS_JWT_AUDIENCIA") {
+        auth = auth.con_audiencia(audiencia);
+    }
+    if let Some(sso) = sso {
+        auth = auth.con_oidc(sso);
+    }
+
+    if auth.tiene_credenciales() {
+        info!("🔐 API REST protegida con claves de API / JWT / OIDC");
+    } else {
+        warn!("🔐 Sin credenciales configuradas: la API REST rechazará todas las peticiones");
+    }
+    auth
+}
+
+/// Límite por IP si no se configura otro
+const LIMITES_IP: &str = "*=600/min";
+
+/// Variable de entorno con valor (docker-compose pasa vacías las no definidas)
+fn variable(nombre: &str) -> Option<String> {
+    std::env::var(nombre).ok().filter(|valor| !valor.is_empty())
+}
+
+/// Límites de peticiones a la API REST por cliente:
+/// RESERVAS_API_LIMITES=[MÉTODO ]ruta=N/unidad,... (p. ej. `*=300/min,POST *=60/min`)
+/// y por IP antes de autenticar, para frenar a quien prueba credenciales:
+/// RESERVAS_API_LIMITES_IP con el mismo formato
+fn configurar_limites() -> ConfigLimites {
+    let por_ip = variable("RESERVAS_API_LIMITES_IP").unwrap_or_else(|| LIMITES_IP.to_string());
+    let mut limites = ConfigLimites::new()
+        .con_reglas_por_ip(&por_ip)
+        .unwrap_or_else(|e| panic!("{}", e));
+    info!("🚦 Límites de peticiones por IP: {}", por_ip);
+    if let Some(reglas) = variable("RESERVAS_API_LIMITES") {
+        limites = limites
+            .con_reglas(&reglas)
+            .unwrap_or_else(|e| panic!("{}", e));
+        info!("🚦 Límites de peticiones: {}", reglas);
+    }
+    limites
+}
+
+/// Servidor CalDAV: RESERVAS_CALDAV_DESFASE=+01:00 es el desfase con el que
+/// se leen las horas locales (TZID) de los eventos que envían los clientes.
+/// Por defecto, UTC.
+fn configurar_caldav() -> ConfigCalDav {
+    let mut config = ConfigCalDav::default();
+    if let Some(desfase) = variable("RESERVAS_CALDAV_DESFASE") {
+        config.desfase = desfase.parse::<FixedOffset>().unwrap_or_else(|_| {
+            panic!("RESERVAS_CALDAV_DESFASE debe ser ±HH:MM, no '{}'", desfase)
+        });
+    }
+    config
+}
+
+/// Secreto que firma las URLs privadas de los calendarios:
+/// RESERVAS_CALENDARIO_SECRETO. Cambiarlo invalida todas las URLs; sin él se
+/// genera uno al arrancar y las URLs dejan de valer en cada reinicio.
+fn secreto_calendarios() -> String {
+    if let Some(secreto) = variable("RESERVAS_CALENDARIO_SECRETO") {
+        return secreto;
+    }
+    warn!("📅 Sin RESERVAS_CALENDARIO_SECRETO: las URLs de los calendarios cambiarán al reiniciar");
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Inicio de sesión con un proveedor OpenID Connect (web y API):
+/// - RESERVAS_OIDC_EMISOR=<issuer>; sin él no se usa OIDC
+/// - RESERVAS_OIDC_CLIENTE_ID / RESERVAS_OIDC_CLIENTE_SECRETO
+/// - RESERVAS_OIDC_REDIRECCION: URL de vuelta registrada en el proveedor
+/// - RESERVAS_OIDC_AUDIENCIA: `aud` de los access tokens (por defecto, el cliente)
+/// - RESERVAS_OIDC_CLAIM_ROLES: claim con los roles (por defecto, `roles`)
+async fn configurar_oidc<E>(empleados: E) -> Option<Arc<dyn SsoService>>
+where
+    E: EmpleadoRepository + Send + Sync + 'static,
+{
+    let emisor = variable("RESERVAS_OIDC_EMISOR")?;
+    let cliente_id = variable("RESERVAS_OIDC_CLIENTE_ID")
+        .unwrap_or_else(|| panic!("RESERVAS_OIDC_EMISOR exige RESERVAS_OIDC_CLIENTE_ID"));
+    let redireccion = variable("RESERVAS_OIDC_REDIRECCION")
+        .unwrap_or_else(|| "http://localhost:3000/login/oidc/callback".to_string());
+
+    let mut config = ConfigOidc::new(emisor.as_str(), cliente_id, redireccion);
+    if let Some(secreto) = variable("RESERVAS_OIDC_CLIENTE_SECRETO") {
+        config = config.con_secreto(secreto);
+    }
+    if let Some(audiencia) = variable("RESERVAS_OIDC_AUDIENCIA") {
+        config = config.con_audiencia(audiencia);
+    }
+    if let Some(claim) = variable("RESERVAS_OIDC_CLAIM_ROLES") {
+        config = config.con_claim_roles(claim);
+    }
+    let proveedor = ProveedorOidc::descubrir(config)
+        .await
+        .unwrap_or_else(|e| panic!("No se pudo configurar OIDC: {}", e));
+    info!("🔑 Inicio de sesión con el proveedor OIDC {}", emisor);
+    Some(Arc::new(SsoServiceImpl::new(Arc::new(proveedor), empleados)) as Arc<dyn SsoService>)
+}
+
+/// Cuenta de administrador de la interfaz web:
+/// RESERVAS_WEB_ADMIN=usuario:contraseña. Sin ella nadie puede entrar, porque
+/// las cuentas solo las da de alta un administrador.
+async fn crear_cuenta_admin(cuentas: &dyn CuentaService) {
+    let Some(valor) = variable("RESERVAS_WEB_ADMIN") else {
+        warn!("👤 Sin RESERVAS_WEB_ADMIN: no hay cuentas locales para entrar en la interfaz web");
+        return;
+    };
+    let (usuario, password) = valor
+        .split_once(':')
+        .unwrap_or_else(|| panic!("RESERVAS_WEB_ADMIN debe ser usuario:contraseña"));
+    let nueva = NuevaCuenta {
+        usuario: usuario.to_string(),
+        password: password.to_string(),
+        empleado_id: None,
+        roles: vec![Rol::Admin],
+    };
+    match cuentas.registrar(&Actor::sistema(), nueva).await {
+        Ok(cuenta) => info!("👤 Cuenta de administrador web: {}", cuenta.usuario),
+        Err(e) => panic!("No se pudo crear la cuenta RESERVAS_WEB_ADMIN: {}", e),
+    }
 }
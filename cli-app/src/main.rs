@@ -4,23 +4,37 @@
 use clap::Parser;
 use colored::Colorize;
 use reservas_cli::cli_args::SalaCommands;
-use reservas_cli::{commands, ApiClient, Cli, Commands, EmpleadoCommands, ReservaCommands};
+use reservas_cli::{
+    commands, AdminCommands, ApiClient, Cli, Commands, ConfigCli, EmpleadoCommands, ReservaCommands,
+};
+
+const URL_POR_DEFECTO: &str = "http://localhost:3000/api";
 
 fn main() {
     let cli = Cli::parse();
 
+    // Argumentos y variables de entorno mandan sobre el fichero de configuración
+    let config = ConfigCli::cargar().unwrap_or_else(|e| salir_con_error(&e));
+    let url = cli
+        .url
+        .or(config.url)
+        .unwrap_or_else(|| URL_POR_DEFECTO.to_string());
+    let token = cli.token.or(config.token);
+
     println!("{}", "Sistema de Reservas - CLI".cyan().bold());
-    println!("{} {}\n", "API URL:".bold(), cli.url);
+    println!("{} {}\n", "API URL:".bold(), url);
 
-    let client = ApiClient::new(cli.url);
+    let client = ApiClient::new(url)
+        .con_token(token)
+        .unwrap_or_else(|e| salir_con_error(&e));
 
     match cli.command {
         Commands::Empleado(cmd) => match cmd {
             EmpleadoCommands::Crear { nombre, email } => {
                 commands::crear_empleado(&client, nombre, email);
             }
-            EmpleadoCommands::Listar => {
-                commands::listar_empleados(&client);
+            EmpleadoCommands::Listar(filtros) => {
+                commands::listar_empleados(&client, filtros);
             }
             EmpleadoCommands::Obtener { id } => {
                 commands::obtener_empleado(&client, id);
@@ -31,6 +45,23 @@ fn main() {
             EmpleadoCommands::Desactivar { id } => {
                 commands::desactivar_empleado(&client, id);
             }
+            EmpleadoCommands::Editar {
+                id,
+                nombre,
+                email,
+                responsable,
+            } => {
+                commands::editar_empleado(&client, id, nombre, email, responsable);
+            }
+            EmpleadoCommands::Eliminar { id } => {
+                commands::eliminar_empleado(&client, id);
+            }
+            EmpleadoCommands::Importar(args) => {
+                commands::importar_hoja(&client, "empleados", args);
+            }
+            EmpleadoCommands::Exportar(args) => {
+                commands::exportar_hoja(&client, "empleados", args);
+            }
         },
 
         Commands::Reserva(cmd) => match cmd {
@@ -39,11 +70,12 @@ fn main() {
                 fecha,
                 hora,
                 descripcion,
+                sala_id,
             } => {
-                commands::crear_reserva(&client, empleado_id, fecha, hora, descripcion);
+                commands::crear_reserva(&client, empleado_id, fecha, hora, descripcion, sala_id);
             }
-            ReservaCommands::Listar => {
-                commands::listar_reservas(&client);
+            ReservaCommands::Listar(filtros) => {
+                commands::listar_reservas(&client, filtros);
             }
             ReservaCommands::ListarEmpleado { empleado_id } => {
                 commands::listar_reservas_empleado(&client, empleado_id);
@@ -54,20 +86,85 @@ fn main() {
             ReservaCommands::Cancelar { id } => {
                 commands::cancelar_reserva(&client, id);
             }
+            ReservaCommands::Reprogramar { id, fecha, hora } => {
+                commands::reprogramar_reserva(&client, id, fecha, hora);
+            }
+            ReservaCommands::Editar { id, descripcion } => {
+                commands::editar_reserva(&client, id, descripcion);
+            }
+            ReservaCommands::Eliminar { id } => {
+                commands::eliminar_reserva(&client, id);
+            }
+            ReservaCommands::Importar {
+                entrada,
+                simular,
+                desfase,
+            } => {
+                commands::importar_reservas(&client, entrada, simular, desfase);
+            }
+            ReservaCommands::Exportar(args) => {
+                commands::exportar_hoja(&client, "reservas", args);
+            }
         },
 
-        Commands::Disponibilidad { fecha } => {
-            commands::ver_disponibilidad(&client, fecha);
+        Commands::Disponibilidad(args) => {
+            commands::ver_disponibilidad(&client, args);
         }
 
         Commands::Sala(cmd) => match cmd {
-            SalaCommands::Listar => {
-                commands::listar_salas(&client);
+            SalaCommands::Listar(filtros) => {
+                commands::listar_salas(&client, filtros);
             }
 
             SalaCommands::Crear { nombre, capacidad } => {
                 commands::crear_sala(&client, nombre, capacidad);
             }
+            SalaCommands::Editar {
+                id,
+                nombre,
+                capacidad,
+            } => {
+                commands::editar_sala(&client, id, nombre, capacidad);
+            }
+            SalaCommands::Eliminar { id } => {
+                commands::eliminar_sala(&client, id);
+            }
+            SalaCommands::Importar(args) => {
+                commands::importar_hoja(&client, "salas", args);
+            }
+            SalaCommands::Exportar(args) => {
+                commands::exportar_hoja(&client, "salas", args);
+            }
+            SalaCommands::Disponibilidad(args) => {
+                commands::disponibilidad_salas(&client, args);
+            }
+        },
+
+        Commands::Informe(args) => {
+            commands::informe(&client, args);
+        }
+
+        Commands::Admin(cmd) => match cmd {
+            AdminCommands::Backup { salida } => {
+                commands::backup(&client, salida);
+            }
+            AdminCommands::Restore { entrada, simular } => {
+                commands::restore(&client, entrada, simular);
+            }
+            AdminCommands::Fsck { fix } => {
+                commands::fsck(&client, fix);
+            }
+            AdminCommands::Auditoria => {
+                commands::auditoria(&client);
+            }
+            AdminCommands::Limites => {
+                commands::limites(&client);
+            }
         },
     }
 }
+
+fn salir_con_error(mensaje: &str) -> ! {
+    eprintln!("{} {}", "✗ Error:".red(), mensaje);
+    std::process::exit(1);
+}
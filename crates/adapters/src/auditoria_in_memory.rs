@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use reservas_ports::out::auditoria::{IntentoDenegado, RegistroAuditoria};
+use std::collections::VecDeque;
+use tokio::sync::RwLock;
+
+/// Intentos que se conservan por defecto; al llenarse se descartan los más antiguos
+pub const CAPACIDAD_AUDITORIA: usize = 1000;
+
+/// Auditoría en memoria con capacidad limitada
+pub struct InMemoryRegistroAuditoria {
+    intentos: RwLock<VecDeque<IntentoDenegado>>,
+    capacidad: usize,
+}
+
+impl Default for InMemoryRegistroAuditoria {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryRegistroAuditoria {
+    pub fn new() -> Self {
+        Self::con_capacidad(CAPACIDAD_AUDITORIA)
+    }
+
+    pub fn con_capacidad(capacidad: usize) -> Self {
+        Self {
+            intentos: RwLock::new(VecDeque::new()),
+            capacidad,
+        }
+    }
+}
+
+#[async_trait]
+impl RegistroAuditoria for InMemoryRegistroAuditoria {
+    async fn registrar(&self, intento: IntentoDenegado) -> Result<(), String> {
+        let mut intentos = self.intentos.write().await;
+        if intentos.len() == self.capacidad {
+            intentos.pop_front();
+        }
+        intentos.push_back(intento);
+        Ok(())
+    }
+
+    async fn listar(&self) -> Result<Vec<IntentoDenegado>, String> {
+        Ok(self.intentos.read().await.iter().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use reservas_domain::Accion;
+
+    fn intento(recurso: &str) -> IntentoDenegado {
+        IntentoDenegado {
+            fecha: Utc::now(),
+            sujeto: "juan".to_string(),
+            accion: Accion::CancelarReserva,
+            recurso: recurso.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_descarta_los_mas_antiguos() {
+        let registro = InMemoryRegistroAuditoria::con_capacidad(2);
+        for recurso in ["a", "b", "c"] {
+            registro.registrar(intento(recurso)).await.unwrap();
+        }
+
+        let recursos: Vec<String> = registro
+            .listar()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.recurso)
+            .collect();
+        assert_eq!(recursos, ["b", "c"]);
+    }
+}
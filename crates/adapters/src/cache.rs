@@ -0,0 +1,408 @@
+// Decoradores de caché para los repositorios
+//
+// Envuelven CUALQUIER implementación de `ReservaRepository` o
+// `EmpleadoRepository` y guardan en memoria las lecturas más frecuentes
+// (`listar` y `obtener`) durante un TTL. Toda escritura que pasa por el
+// decorador invalida la caché, así nunca se sirven datos que este mismo
+// proceso ya ha modificado. Para cambios hechos por otros procesos se puede
+// llamar a `invalidar()` desde el evento correspondiente.
+//
+// Cada invalidación sube una generación. Una lectura del repositorio
+// interior solo se guarda si la generación no ha cambiado desde que empezó:
+// si entretanto se ha escrito, el valor leído puede ser ya viejo.
+//
+// Es un ADAPTADOR que implementa el mismo puerto que decora: el resto del
+// sistema no sabe si hay caché o no.
+
+use async_trait::async_trait;
+use reservas_domain::{Email, Empleado, Reserva, Slot};
+use reservas_ports::consulta::{ConsultaEmpleados, ConsultaReservas, Pagina};
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::reserva_repository::ReservaRepository;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// Contadores de uso de una caché. Se comparten con `Arc` para poder
+/// consultarlos después de mover el repositorio al servicio.
+#[derive(Debug, Default)]
+pub struct MetricasCache {
+    aciertos: AtomicU64,
+    fallos: AtomicU64,
+    invalidaciones: AtomicU64,
+}
+
+impl MetricasCache {
+    pub fn aciertos(&self) -> u64 {
+        self.aciertos.load(Ordering::Relaxed)
+    }
+
+    pub fn fallos(&self) -> u64 {
+        self.fallos.load(Ordering::Relaxed)
+    }
+
+    pub fn invalidaciones(&self) -> u64 {
+        self.invalidaciones.load(Ordering::Relaxed)
+    }
+
+    /// Proporción de lecturas servidas desde la caché (0.0 si no hubo lecturas)
+    pub fn tasa_aciertos(&self) -> f64 {
+        let total = self.aciertos() + self.fallos();
+        if total == 0 {
+            0.0
+        } else {
+            self.aciertos() as f64 / total as f64
+        }
+    }
+}
+
+/// Estado interno común a ambos decoradores
+struct Cache<T> {
+    ttl: Duration,
+    generacion: AtomicU64,
+    listado: RwLock<Option<(Instant, Vec<T>)>>,
+    por_id: RwLock<HashMap<String, (Instant, Option<T>)>>,
+    metricas: Arc<MetricasCache>,
+}
+
+impl<T: Clone> Cache<T> {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            generacion: AtomicU64::new(0),
+            listado: RwLock::new(None),
+            por_id: RwLock::new(HashMap::new()),
+            metricas: Arc::new(MetricasCache::default()),
+        }
+    }
+
+    fn vigente(&self, guardado: Instant) -> bool {
+        guardado.elapsed() < self.ttl
+    }
+
+    async fn listado(&self) -> Option<Vec<T>> {
+        let listado = self.listado.read().await;
+        let vigente = listado
+            .as_ref()
+            .filter(|(guardado, _)| self.vigente(*guardado))
+            .map(|(_, valores)| valores.clone());
+        self.contar(vigente.is_some());
+        vigente
+    }
+
+    /// Se toma antes de leer del repositorio interior
+    fn generacion(&self) -> u64 {
+        self.generacion.load(Ordering::SeqCst)
+    }
+
+    async fn guardar_listado(&self, generacion: u64, valores: &[T]) {
+        let mut listado = self.listado.write().await;
+        if self.generacion() == generacion {
+            *listado = Some((Instant::now(), valores.to_vec()));
+        }
+    }
+
+    async fn por_id(&self, id: &str) -> Option<Option<T>> {
+        let por_id = self.por_id.read().await;
+        let vigente = por_id
+            .get(id)
+            .filter(|(guardado, _)| self.vigente(*guardado))
+            .map(|(_, valor)| valor.clone());
+        self.contar(vigente.is_some());
+        vigente
+    }
+
+    async fn guardar_por_id(&self, generacion: u64, id: &str, valor: &Option<T>) {
+        let mut por_id = self.por_id.write().await;
+        if self.generacion() == generacion {
+            por_id.insert(id.to_string(), (Instant::now(), valor.clone()));
+        }
+    }
+
+    /// La generación sube antes de vaciar: un `guardar_*` que llegue
+    /// después ya no coincide, y uno anterior queda borrado
+    async fn invalidar(&self) {
+        self.generacion.fetch_add(1, Ordering::SeqCst);
+        *self.listado.write().await = None;
+        self.por_id.write().await.clear();
+        self.metricas.invalidaciones.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn contar(&self, acierto: bool) {
+        let contador = if acierto {
+            &self.metricas.aciertos
+        } else {
+            &self.metricas.fallos
+        };
+        contador.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// ============= Reservas =============
+
+/// Caché con TTL sobre cualquier repositorio de reservas
+pub struct CachedReservaRepository<R: ReservaRepository> {
+    inner: R,
+    cache: Cache<Reserva>,
+}
+
+impl<R: ReservaRepository> CachedReservaRepository<R> {
+    pub fn new(inner: R, ttl: Duration) -> Self {
+        Self {
+            inner,
+            cache: Cache::new(ttl),
+        }
+    }
+
+    pub fn metricas(&self) -> Arc<MetricasCache> {
+        Arc::clone(&self.cache.metricas)
+    }
+
+    /// Descarta todo lo cacheado (p. ej. ante un cambio hecho por otro proceso)
+    pub async fn invalidar(&self) {
+        self.cache.invalidar().await;
+    }
+}
+
+#[async_trait]
+impl<R: ReservaRepository + Send + Sync> ReservaRepository for CachedReservaRepository<R> {
+    async fn guardar(&self, reserva: &Reserva) -> Result<(), String> {
+        let resultado = self.inner.guardar(reserva).await;
+        self.cache.invalidar().await;
+        resultado
+    }
+
+    async fn obtener(&self, id: &str) -> Result<Option<Reserva>, String> {
+        if let Some(reserva) = self.cache.por_id(id).await {
+            return Ok(reserva);
+        }
+        let generacion = self.cache.generacion();
+        let reserva = self.inner.obtener(id).await?;
+        self.cache.guardar_por_id(generacion, id, &reserva).await;
+        Ok(reserva)
+    }
+
+    async fn listar(&self) -> Result<Vec<Reserva>, String> {
+        if let Some(reservas) = self.cache.listado().await {
+            return Ok(reservas);
+        }
+        let generacion = self.cache.generacion();
+        let reservas = self.inner.listar().await?;
+        self.cache.guardar_listado(generacion, &reservas).await;
+        Ok(reservas)
+    }
+
+    async fn buscar(&self, consulta: &ConsultaReservas) -> Result<Pagina<Reserva>, String> {
+        self.inner.buscar(consulta).await
+    }
+
+    async fn listar_por_empleado(&self, empleado_id: &str) -> Result<Vec<Reserva>, String> {
+        self.inner.listar_por_empleado(empleado_id).await
+    }
+
+    async fn listar_por_slot(&self, slot: &Slot) -> Result<Vec<Reserva>, String> {
+        self.inner.listar_por_slot(slot).await
+    }
+
+    async fn actualizar(&self, reserva: &Reserva) -> Result<(), String> {
+        let resultado = self.inner.actualizar(reserva).await;
+        self.cache.invalidar().await;
+        resultado
+    }
+
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        let resultado = self.inner.eliminar(id).await;
+        self.cache.invalidar().await;
+        resultado
+    }
+
+    async fn existe(&self, id: &str) -> Result<bool, String> {
+        self.inner.existe(id).await
+    }
+
+    async fn existe_para_empleado_en_slot(
+        &self,
+        empleado_id: &str,
+        slot: &Slot,
+    ) -> Result<bool, String> {
+        self.inner
+            .existe_para_empleado_en_slot(empleado_id, slot)
+            .await
+    }
+}
+
+// ============= Empleados =============
+
+/// Caché con TTL sobre cualquier repositorio de empleados
+pub struct CachedEmpleadoRepository<R: EmpleadoRepository> {
+    inner: R,
+    cache: Cache<Empleado>,
+}
+
+impl<R: EmpleadoRepository> CachedEmpleadoRepository<R> {
+    pub fn new(inner: R, ttl: Duration) -> Self {
+        Self {
+            inner,
+            cache: Cache::new(ttl),
+        }
+    }
+
+    pub fn metricas(&self) -> Arc<MetricasCache> {
+        Arc::clone(&self.cache.metricas)
+    }
+
+    /// Descarta todo lo cacheado (p. ej. ante un cambio hecho por otro proceso)
+    pub async fn invalidar(&self) {
+        self.cache.invalidar().await;
+    }
+}
+
+#[async_trait]
+impl<R: EmpleadoRepository + Send + Sync> EmpleadoRepository for CachedEmpleadoRepository<R> {
+    async fn guardar(&self, empleado: &Empleado) -> Result<(), String> {
+        let resultado = self.inner.guardar(empleado).await;
+        self.cache.invalidar().await;
+        resultado
+    }
+
+    async fn obtener(&self, id: &str) -> Result<Option<Empleado>, String> {
+        if let Some(empleado) = self.cache.por_id(id).await {
+            return Ok(empleado);
+        }
+        let generacion = self.cache.generacion();
+        let empleado = self.inner.obtener(id).await?;
+        self.cache.guardar_por_id(generacion, id, &empleado).await;
+        Ok(empleado)
+    }
+
+    /// Sin caché: se usa para comprobar que el email está libre
+    async fn obtener_por_email(&self, email: &Email) -> Result<Option<Empleado>, String> {
+        self.inner.obtener_por_email(email).await
+    }
+
+    async fn listar(&self) -> Result<Vec<Empleado>, String> {
+        if let Some(empleados) = self.cache.listado().await {
+            return Ok(empleados);
+        }
+        let generacion = self.cache.generacion();
+        let empleados = self.inner.listar().await?;
+        self.cache.guardar_listado(generacion, &empleados).await;
+        Ok(empleados)
+    }
+
+    async fn buscar(&self, consulta: &ConsultaEmpleados) -> Result<Pagina<Empleado>, String> {
+        self.inner.buscar(consulta).await
+    }
+
+    async fn actualizar(&self, empleado: &Empleado) -> Result<(), String> {
+        let resultado = self.inner.actualizar(empleado).await;
+        self.cache.invalidar().await;
+        resultado
+    }
+
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        let resultado = self.inner.eliminar(id).await;
+        self.cache.invalidar().await;
+        resultado
+    }
+
+    async fn existe(&self, id: &str) -> Result<bool, String> {
+        self.inner.existe(id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InMemoryEmpleadoRepository, InMemoryReservaRepository};
+    use reservas_ports::testing;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn test_contrato_reserva_repository_con_cache() {
+        testing::reserva_repository::verificar_contrato(|| {
+            CachedReservaRepository::new(InMemoryReservaRepository::new(), TTL)
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_contrato_empleado_repository_con_cache() {
+        testing::empleado_repository::verificar_contrato(|| {
+            CachedEmpleadoRepository::new(InMemoryEmpleadoRepository::new(), TTL)
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_aciertos_y_fallos() {
+        let repo = CachedEmpleadoRepository::new(InMemoryEmpleadoRepository::new(), TTL);
+        let metricas = repo.metricas();
+
+        repo.listar().await.unwrap();
+        repo.listar().await.unwrap();
+        repo.obtener("emp-001").await.unwrap();
+        repo.obtener("emp-001").await.unwrap();
+
+        assert_eq!(metricas.fallos(), 2);
+        assert_eq!(metricas.aciertos(), 2);
+        assert_eq!(metricas.tasa_aciertos(), 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_escritura_invalida_cache() {
+        let repo = CachedEmpleadoRepository::new(InMemoryEmpleadoRepository::new(), TTL);
+        assert!(repo.listar().await.unwrap().is_empty());
+        assert_eq!(repo.obtener("emp-001").await.unwrap(), None);
+
+        let empleado = Empleado::new(
+            "emp-001".to_string(),
+            "Juan".to_string(),
+            "juan@empresa.com".to_string(),
+        )
+        .unwrap();
+        repo.guardar(&empleado).await.unwrap();
+
+        assert_eq!(repo.listar().await.unwrap(), vec![empleado.clone()]);
+        assert_eq!(repo.obtener("emp-001").await.unwrap(), Some(empleado));
+        assert_eq!(repo.metricas().invalidaciones(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_lectura_anterior_a_invalidar_no_se_guarda() {
+        let cache: Cache<String> = Cache::new(TTL);
+        let generacion = cache.generacion();
+        // Una escritura invalida mientras la lectura estaba en curso
+        cache.invalidar().await;
+        cache
+            .guardar_listado(generacion, &["viejo".to_string()])
+            .await;
+        cache
+            .guardar_por_id(generacion, "id", &Some("viejo".to_string()))
+            .await;
+
+        assert_eq!(cache.listado().await, None);
+        assert_eq!(cache.por_id("id").await, None);
+
+        let generacion = cache.generacion();
+        cache
+            .guardar_listado(generacion, &["nuevo".to_string()])
+            .await;
+        assert_eq!(cache.listado().await, Some(vec!["nuevo".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_ttl_caducado_vuelve_al_repositorio() {
+        let repo = CachedReservaRepository::new(InMemoryReservaRepository::new(), Duration::ZERO);
+        let metricas = repo.metricas();
+
+        repo.listar().await.unwrap();
+        repo.listar().await.unwrap();
+
+        assert_eq!(metricas.aciertos(), 0);
+        assert_eq!(metricas.fallos(), 2);
+    }
+}
@@ -0,0 +1,75 @@
+// Adaptador de salida: cuentas locales en memoria
+
+use async_trait::async_trait;
+use reservas_domain::Cuenta;
+use reservas_ports::out::cuenta_repository::CuentaRepository;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+#[derive(Default)]
+pub struct InMemoryCuentaRepository {
+    storage: RwLock<HashMap<String, Cuenta>>,
+}
+
+impl InMemoryCuentaRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CuentaRepository for InMemoryCuentaRepository {
+    async fn guardar(&self, cuenta: &Cuenta) -> Result<(), String> {
+        let mut storage = self.storage.write().await;
+        if storage.contains_key(&cuenta.usuario) {
+            return Err(format!("El usuario {} ya existe", cuenta.usuario));
+        }
+        storage.insert(cuenta.usuario.clone(), cuenta.clone());
+        Ok(())
+    }
+
+    async fn obtener(&self, usuario: &str) -> Result<Option<Cuenta>, String> {
+        let storage = self.storage.read().await;
+        Ok(storage.get(usuario).cloned())
+    }
+
+    async fn actualizar(&self, cuenta: &Cuenta) -> Result<(), String> {
+        let mut storage = self.storage.write().await;
+        match storage.get_mut(&cuenta.usuario) {
+            Some(actual) => {
+                *actual = cuenta.clone();
+                Ok(())
+            }
+            None => Err(format!("La cuenta {} no existe", cuenta.usuario)),
+        }
+    }
+
+    async fn listar(&self) -> Result<Vec<Cuenta>, String> {
+        let storage = self.storage.read().await;
+        let mut cuentas: Vec<Cuenta> = storage.values().cloned().collect();
+        cuentas.sort_by(|a, b| a.usuario.cmp(&b.usuario));
+        Ok(cuentas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cuenta(usuario: &str) -> Cuenta {
+        Cuenta::new(usuario.to_string(), "hash".to_string(), None, vec![]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_usuario_unico() {
+        let repo = InMemoryCuentaRepository::new();
+        repo.guardar(&cuenta("ana")).await.unwrap();
+        assert!(repo.guardar(&cuenta("ana")).await.is_err());
+
+        let mut ana = repo.obtener("ana").await.unwrap().unwrap();
+        ana.activa = false;
+        repo.actualizar(&ana).await.unwrap();
+        assert!(!repo.obtener("ana").await.unwrap().unwrap().activa);
+        assert!(repo.actualizar(&cuenta("otro")).await.is_err());
+    }
+}
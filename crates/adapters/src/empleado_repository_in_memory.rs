@@ -1,7 +1,8 @@
 // Adaptador de salida: Repositorio de empleados en memoria
 
 use async_trait::async_trait;
-use reservas_domain::Empleado;
+use reservas_domain::{Email, Empleado};
+use reservas_ports::consulta::{ConsultaEmpleados, Pagina};
 use reservas_ports::out::empleado_repository::EmpleadoRepository;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -25,22 +26,64 @@ impl InMemoryEmpleadoRepository {
     }
 }
 
+/// Hace de índice único sobre el email: falla si lo usa otro empleado
+fn comprobar_email_unico(
+    storage: &HashMap<String, Empleado>,
+    empleado: &Empleado,
+) -> Result<(), String> {
+    match storage
+        .values()
+        .find(|e| e.email == empleado.email && e.id != empleado.id)
+    {
+        Some(otro) => Err(format!(
+            "El email {} ya lo usa el empleado {}",
+            empleado.email, otro.id
+        )),
+        None => Ok(()),
+    }
+}
+
 #[async_trait]
 impl EmpleadoRepository for InMemoryEmpleadoRepository {
     async fn guardar(&self, empleado: &Empleado) -> Result<(), String> {
         let mut storage = self.storage.write().await;
+        comprobar_email_unico(&storage, empleado)?;
         storage.insert(empleado.id.clone(), empleado.clone());
         Ok(())
     }
 
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        let mut storage = self.storage.write().await;
+
+        storage
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| "Empleado no encontrado".to_string())
+    }
+
     async fn obtener(&self, id: &str) -> Result<Option<Empleado>, String> {
         let storage = self.storage.read().await;
         Ok(storage.get(id).cloned())
     }
 
+    async fn obtener_por_email(&self, email: &Email) -> Result<Option<Empleado>, String> {
+        let storage = self.storage.read().await;
+        Ok(storage.values().find(|e| e.email == *email).cloned())
+    }
+
     async fn listar(&self) -> Result<Vec<Empleado>, String> {
         let storage = self.storage.read().await;
-        Ok(storage.values().cloned().collect())
+        let mut empleados: Vec<Empleado> = storage.values().cloned().collect();
+        empleados.sort_by(|a, b| (&a.nombre, &a.id).cmp(&(&b.nombre, &b.id)));
+        Ok(empleados)
+    }
+
+    async fn buscar(&self, consulta: &ConsultaEmpleados) -> Result<Pagina<Empleado>, String> {
+        let storage = self.storage.read().await;
+
+        let candidatos: Vec<&Empleado> = storage.values().filter(|e| consulta.cumple(e)).collect();
+
+        consulta.paginar(candidatos)
     }
 
     async fn actualizar(&self, empleado: &Empleado) -> Result<(), String> {
@@ -49,6 +92,7 @@ impl EmpleadoRepository for InMemoryEmpleadoRepository {
         if !storage.contains_key(&empleado.id) {
             return Err("Empleado no encontrado".to_string());
         }
+        comprobar_email_unico(&storage, empleado)?;
 
         storage.insert(empleado.id.clone(), empleado.clone());
         Ok(())
@@ -59,3 +103,14 @@ impl EmpleadoRepository for InMemoryEmpleadoRepository {
         Ok(storage.contains_key(id))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reservas_ports::testing;
+
+    #[tokio::test]
+    async fn test_contrato_empleado_repository() {
+        testing::empleado_repository::verificar_contrato(InMemoryEmpleadoRepository::new).await;
+    }
+}
@@ -8,10 +8,29 @@
 //
 // Implementan los puertos (traits) usando tecnología real
 
+pub mod auditoria_in_memory;
+pub mod cache;
+pub mod cuenta_repository_in_memory;
 pub mod empleado_repository_in_memory;
+pub mod metricas;
+pub mod oidc;
 pub mod repository_in_memory;
 pub mod sala_repository_memory;
+pub mod version_calendario_repository_in_memory;
+pub mod webhook_http;
+pub mod webhooks_in_memory;
 
+pub use auditoria_in_memory::InMemoryRegistroAuditoria;
+pub use cache::{CachedEmpleadoRepository, CachedReservaRepository, MetricasCache};
+pub use cuenta_repository_in_memory::InMemoryCuentaRepository;
 pub use empleado_repository_in_memory::InMemoryEmpleadoRepository;
+pub use metricas::{
+    MeasuredEmpleadoRepository, MeasuredReservaRepository, MeasuredSalaRepository,
+    MetricasPrometheus,
+};
+pub use oidc::{ConfigOidc, ProveedorOidc};
 pub use repository_in_memory::InMemoryReservaRepository;
 pub use sala_repository_memory::InMemorySalaRepository;
+pub use version_calendario_repository_in_memory::InMemoryVersionCalendarioRepository;
+pub use webhook_http::ClienteWebhookHttp;
+pub use webhooks_in_memory::InMemoryWebhookRepository;
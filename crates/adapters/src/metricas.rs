@@ -0,0 +1,699 @@
+// Métricas en formato Prometheus
+//
+// `MetricasPrometheus` implementa el puerto `Metricas` acumulando contadores
+// e histogramas en memoria y los expone en el formato de texto que lee
+// Prometheus. No hace falta ninguna librería: el formato son líneas
+// `nombre{etiqueta="valor"} número`.
+//
+// Los tiempos de los repositorios se miden con decoradores que envuelven
+// CUALQUIER implementación de los puertos de salida, igual que la caché:
+// el repositorio medido no sabe que lo miden y el resto del sistema no sabe
+// si hay medición o no.
+
+use async_trait::async_trait;
+use reservas_domain::{Email, Empleado, Reserva, Sala, Slot};
+use reservas_ports::consulta::{ConsultaEmpleados, ConsultaReservas, ConsultaSalas, Pagina};
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::metricas::{Metricas, RecursoEnConflicto};
+use reservas_ports::out::notificador::TipoCambio;
+use reservas_ports::out::reserva_repository::ReservaRepository;
+use reservas_ports::out::sala_repository::SalaRepository;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::MetricasCache;
+
+/// Límites superiores (en segundos) de las cubetas de los histogramas. Cubren
+/// desde una lectura en memoria hasta una petición HTTP lenta.
+const CUBETAS: [f64; 14] = [
+    0.0001, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+#[derive(Debug, Clone, Default)]
+struct Histograma {
+    /// Observaciones en cada cubeta (no acumuladas)
+    cubetas: [u64; CUBETAS.len()],
+    suma: f64,
+    cuenta: u64,
+}
+
+impl Histograma {
+    fn observar(&mut self, duracion: Duration) {
+        let segundos = duracion.as_secs_f64();
+        if let Some(i) = CUBETAS.iter().position(|limite| segundos <= *limite) {
+            self.cubetas[i] += 1;
+        }
+        self.suma += segundos;
+        self.cuenta += 1;
+    }
+
+    /// Líneas `_bucket`, `_sum` y `_count`; `etiquetas` ya viene formateado
+    fn exponer(&self, salida: &mut String, nombre: &str, etiquetas: &str) {
+        let mut acumulado = 0;
+        for (limite, cuenta) in CUBETAS.iter().zip(self.cubetas) {
+            acumulado += cuenta;
+            let _ = writeln!(
+                salida,
+                "{}_bucket{{{},le=\"{}\"}} {}",
+                nombre, etiquetas, limite, acumulado
+            );
+        }
+        let _ = writeln!(
+            salida,
+            "{}_bucket{{{},le=\"+Inf\"}} {}",
+            nombre, etiquetas, self.cuenta
+        );
+        let _ = writeln!(salida, "{}_sum{{{}}} {}", nombre, etiquetas, self.suma);
+        let _ = writeln!(salida, "{}_count{{{}}} {}", nombre, etiquetas, self.cuenta);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Registro {
+    /// (método, ruta, estado)
+    peticiones: BTreeMap<(String, String, u16), u64>,
+    /// (método, ruta)
+    duracion_http: BTreeMap<(String, String), Histograma>,
+    cambios: BTreeMap<&'static str, u64>,
+    conflictos: BTreeMap<&'static str, u64>,
+    /// (repositorio, operación)
+    duracion_repositorio: BTreeMap<(String, String), Histograma>,
+    errores_repositorio: BTreeMap<(String, String), u64>,
+    /// Cachés de repositorio cuyos contadores se exponen
+    caches: Vec<(String, Arc<MetricasCache>)>,
+}
+
+/// Valor de etiqueta con `\`, `"` y saltos de línea escapados
+fn escapar(valor: &str) -> String {
+    valor
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn cabecera(salida: &mut String, nombre: &str, tipo: &str, ayuda: &str) {
+    let _ = writeln!(salida, "# HELP {} {}", nombre, ayuda);
+    let _ = writeln!(salida, "# TYPE {} {}", nombre, tipo);
+}
+
+/// Registro de métricas en memoria del proceso
+pub struct MetricasPrometheus {
+    registro: Mutex<Registro>,
+}
+
+impl MetricasPrometheus {
+    pub fn new() -> Self {
+        let mut registro = Registro::default();
+        // Los contadores de negocio salen desde el principio, aunque sea a 0
+        for tipo in TipoCambio::TODOS {
+            registro.cambios.insert(tipo.as_str(), 0);
+        }
+        for recurso in [RecursoEnConflicto::Empleado, RecursoEnConflicto::Sala] {
+            registro.conflictos.insert(recurso.as_str(), 0);
+        }
+        Self {
+            registro: Mutex::new(registro),
+        }
+    }
+
+    /// Expone también los aciertos y fallos de la caché del repositorio
+    /// `repositorio`. Se registra después de crear la caché, que suele
+    /// envolver a un repositorio que ya se mide con este mismo registro.
+    pub fn registrar_cache(&self, repositorio: &str, metricas: Arc<MetricasCache>) {
+        self.registro()
+            .caches
+            .push((repositorio.to_string(), metricas));
+    }
+
+    fn registro(&self) -> std::sync::MutexGuard<'_, Registro> {
+        // Un pánico a medio anotar no deja nada inconsistente que importe
+        self.registro
+            .lock()
+            .unwrap_or_else(|envenenado| envenenado.into_inner())
+    }
+}
+
+impl Default for MetricasPrometheus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metricas for MetricasPrometheus {
+    fn peticion_http(&self, metodo: &str, ruta: &str, estado: u16, duracion: Duration) {
+        let mut registro = self.registro();
+        *registro
+            .peticiones
+            .entry((metodo.to_string(), ruta.to_string(), estado))
+            .or_default() += 1;
+        registro
+            .duracion_http
+            .entry((metodo.to_string(), ruta.to_string()))
+            .or_default()
+            .observar(duracion);
+    }
+
+    fn cambio_reserva(&self, tipo: TipoCambio) {
+        *self.registro().cambios.entry(tipo.as_str()).or_default() += 1;
+    }
+
+    fn conflicto_rechazado(&self, recurso: RecursoEnConflicto) {
+        *self
+            .registro()
+            .conflictos
+            .entry(recurso.as_str())
+            .or_default() += 1;
+    }
+
+    fn operacion_repositorio(
+        &self,
+        repositorio: &str,
+        operacion: &str,
+        duracion: Duration,
+        exito: bool,
+    ) {
+        let clave = (repositorio.to_string(), operacion.to_string());
+        let mut registro = self.registro();
+        if !exito {
+            *registro
+                .errores_repositorio
+                .entry(clave.clone())
+                .or_default() += 1;
+        }
+        registro
+            .duracion_repositorio
+            .entry(clave)
+            .or_default()
+            .observar(duracion);
+    }
+
+    fn exponer(&self) -> String {
+        let registro = self.registro();
+        let mut salida = String::new();
+
+        let nombre = "reservas_http_peticiones_total";
+        cabecera(&mut salida, nombre, "counter", "Peticiones HTTP atendidas");
+        for ((metodo, ruta, estado), cuenta) in &registro.peticiones {
+            let _ = writeln!(
+                salida,
+                "{}{{metodo=\"{}\",ruta=\"{}\",estado=\"{}\"}} {}",
+                nombre,
+                escapar(metodo),
+                escapar(ruta),
+                estado,
+                cuenta
+            );
+        }
+        let nombre = "reservas_http_duracion_segundos";
+        cabecera(
+            &mut salida,
+            nombre,
+            "histogram",
+            "Duración de las peticiones HTTP",
+        );
+        for ((metodo, ruta), histograma) in &registro.duracion_http {
+            let etiquetas = format!("metodo=\"{}\",ruta=\"{}\"", escapar(metodo), escapar(ruta));
+            histograma.exponer(&mut salida, nombre, &etiquetas);
+        }
+
+        let nombre = "reservas_cambios_total";
+        cabecera(
+            &mut salida,
+            nombre,
+            "counter",
+            "Reservas creadas, confirmadas, canceladas, modificadas, reprogramadas, reasignadas o eliminadas",
+        );
+        for (cambio, cuenta) in &registro.cambios {
+            let _ = writeln!(salida, "{}{{cambio=\"{}\"}} {}", nombre, cambio, cuenta);
+        }
+        let nombre = "reservas_conflictos_total";
+        cabecera(
+            &mut salida,
+            nombre,
+            "counter",
+            "Reservas rechazadas porque el empleado o la sala ya estaban ocupados",
+        );
+        for (recurso, cuenta) in &registro.conflictos {
+            let _ = writeln!(salida, "{}{{recurso=\"{}\"}} {}", nombre, recurso, cuenta);
+        }
+
+        let nombre = "reservas_repositorio_duracion_segundos";
+        cabecera(
+            &mut salida,
+            nombre,
+            "histogram",
+            "Duración de las operaciones de los repositorios",
+        );
+        for ((repositorio, operacion), histograma) in &registro.duracion_repositorio {
+            let etiquetas = format!(
+                "repositorio=\"{}\",operacion=\"{}\"",
+                escapar(repositorio),
+                escapar(operacion)
+            );
+            histograma.exponer(&mut salida, nombre, &etiquetas);
+        }
+        let nombre = "reservas_repositorio_errores_total";
+        cabecera(
+            &mut salida,
+            nombre,
+            "counter",
+            "Operaciones de los repositorios que devolvieron error",
+        );
+        for ((repositorio, operacion), cuenta) in &registro.errores_repositorio {
+            let _ = writeln!(
+                salida,
+                "{}{{repositorio=\"{}\",operacion=\"{}\"}} {}",
+                nombre,
+                escapar(repositorio),
+                escapar(operacion),
+                cuenta
+            );
+        }
+
+        if !registro.caches.is_empty() {
+            for (nombre, ayuda, valor) in [
+                (
+                    "reservas_cache_aciertos_total",
+                    "Lecturas servidas desde la caché",
+                    MetricasCache::aciertos as fn(&MetricasCache) -> u64,
+                ),
+                (
+                    "reservas_cache_fallos_total",
+                    "Lecturas que tuvieron que ir al repositorio",
+                    MetricasCache::fallos,
+                ),
+                (
+                    "reservas_cache_invalidaciones_total",
+                    "Veces que se vació la caché",
+                    MetricasCache::invalidaciones,
+                ),
+            ] {
+                cabecera(&mut salida, nombre, "counter", ayuda);
+                for (repositorio, metricas) in &registro.caches {
+                    let _ = writeln!(
+                        salida,
+                        "{}{{repositorio=\"{}\"}} {}",
+                        nombre,
+                        escapar(repositorio),
+                        valor(metricas)
+                    );
+                }
+            }
+        }
+        salida
+    }
+}
+
+/// Ejecuta una operación de un repositorio anotando cuánto tarda
+async fn medir<T>(
+    metricas: &dyn Metricas,
+    repositorio: &str,
+    operacion: &str,
+    futuro: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    let inicio = Instant::now();
+    let resultado = futuro.await;
+    metricas.operacion_repositorio(repositorio, operacion, inicio.elapsed(), resultado.is_ok());
+    resultado
+}
+
+// ============= Reservas =============
+
+/// Mide los tiempos de cualquier repositorio de reservas
+pub struct MeasuredReservaRepository<R: ReservaRepository> {
+    inner: R,
+    metricas: Arc<dyn Metricas>,
+}
+
+impl<R: ReservaRepository> MeasuredReservaRepository<R> {
+    const NOMBRE: &'static str = "reservas";
+
+    pub fn new(inner: R, metricas: Arc<dyn Metricas>) -> Self {
+        Self { inner, metricas }
+    }
+}
+
+#[async_trait]
+impl<R: ReservaRepository + Send + Sync> ReservaRepository for MeasuredReservaRepository<R> {
+    async fn guardar(&self, reserva: &Reserva) -> Result<(), String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "guardar",
+            self.inner.guardar(reserva),
+        )
+        .await
+    }
+
+    async fn obtener(&self, id: &str) -> Result<Option<Reserva>, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "obtener",
+            self.inner.obtener(id),
+        )
+        .await
+    }
+
+    async fn listar(&self) -> Result<Vec<Reserva>, String> {
+        medir(&*self.metricas, Self::NOMBRE, "listar", self.inner.listar()).await
+    }
+
+    async fn buscar(&self, consulta: &ConsultaReservas) -> Result<Pagina<Reserva>, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "buscar",
+            self.inner.buscar(consulta),
+        )
+        .await
+    }
+
+    async fn listar_por_empleado(&self, empleado_id: &str) -> Result<Vec<Reserva>, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "listar_por_empleado",
+            self.inner.listar_por_empleado(empleado_id),
+        )
+        .await
+    }
+
+    async fn listar_por_slot(&self, slot: &Slot) -> Result<Vec<Reserva>, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "listar_por_slot",
+            self.inner.listar_por_slot(slot),
+        )
+        .await
+    }
+
+    async fn actualizar(&self, reserva: &Reserva) -> Result<(), String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "actualizar",
+            self.inner.actualizar(reserva),
+        )
+        .await
+    }
+
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "eliminar",
+            self.inner.eliminar(id),
+        )
+        .await
+    }
+
+    async fn existe(&self, id: &str) -> Result<bool, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "existe",
+            self.inner.existe(id),
+        )
+        .await
+    }
+
+    async fn existe_para_empleado_en_slot(
+        &self,
+        empleado_id: &str,
+        slot: &Slot,
+    ) -> Result<bool, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "existe_para_empleado_en_slot",
+            self.inner.existe_para_empleado_en_slot(empleado_id, slot),
+        )
+        .await
+    }
+}
+
+// ============= Empleados =============
+
+/// Mide los tiempos de cualquier repositorio de empleados
+pub struct MeasuredEmpleadoRepository<R: EmpleadoRepository> {
+    inner: R,
+    metricas: Arc<dyn Metricas>,
+}
+
+impl<R: EmpleadoRepository> MeasuredEmpleadoRepository<R> {
+    const NOMBRE: &'static str = "empleados";
+
+    pub fn new(inner: R, metricas: Arc<dyn Metricas>) -> Self {
+        Self { inner, metricas }
+    }
+}
+
+#[async_trait]
+impl<R: EmpleadoRepository + Send + Sync> EmpleadoRepository for MeasuredEmpleadoRepository<R> {
+    async fn guardar(&self, empleado: &Empleado) -> Result<(), String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "guardar",
+            self.inner.guardar(empleado),
+        )
+        .await
+    }
+
+    async fn obtener(&self, id: &str) -> Result<Option<Empleado>, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "obtener",
+            self.inner.obtener(id),
+        )
+        .await
+    }
+
+    async fn obtener_por_email(&self, email: &Email) -> Result<Option<Empleado>, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "obtener_por_email",
+            self.inner.obtener_por_email(email),
+        )
+        .await
+    }
+
+    async fn listar(&self) -> Result<Vec<Empleado>, String> {
+        medir(&*self.metricas, Self::NOMBRE, "listar", self.inner.listar()).await
+    }
+
+    async fn buscar(&self, consulta: &ConsultaEmpleados) -> Result<Pagina<Empleado>, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "buscar",
+            self.inner.buscar(consulta),
+        )
+        .await
+    }
+
+    async fn actualizar(&self, empleado: &Empleado) -> Result<(), String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "actualizar",
+            self.inner.actualizar(empleado),
+        )
+        .await
+    }
+
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "eliminar",
+            self.inner.eliminar(id),
+        )
+        .await
+    }
+
+    async fn existe(&self, id: &str) -> Result<bool, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "existe",
+            self.inner.existe(id),
+        )
+        .await
+    }
+}
+
+// ============= Salas =============
+
+/// Mide los tiempos de cualquier repositorio de salas
+pub struct MeasuredSalaRepository<R: SalaRepository> {
+    inner: R,
+    metricas: Arc<dyn Metricas>,
+}
+
+impl<R: SalaRepository> MeasuredSalaRepository<R> {
+    const NOMBRE: &'static str = "salas";
+
+    pub fn new(inner: R, metricas: Arc<dyn Metricas>) -> Self {
+        Self { inner, metricas }
+    }
+}
+
+#[async_trait]
+impl<R: SalaRepository + Send + Sync> SalaRepository for MeasuredSalaRepository<R> {
+    async fn guardar(&self, sala: &Sala) -> Result<(), String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "guardar",
+            self.inner.guardar(sala),
+        )
+        .await
+    }
+
+    async fn obtener(&self, id: &str) -> Result<Option<Sala>, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "obtener",
+            self.inner.obtener(id),
+        )
+        .await
+    }
+
+    async fn listar(&self) -> Result<Vec<Sala>, String> {
+        medir(&*self.metricas, Self::NOMBRE, "listar", self.inner.listar()).await
+    }
+
+    async fn buscar(&self, consulta: &ConsultaSalas) -> Result<Pagina<Sala>, String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "buscar",
+            self.inner.buscar(consulta),
+        )
+        .await
+    }
+
+    async fn actualizar(&self, sala: &Sala) -> Result<(), String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "actualizar",
+            self.inner.actualizar(sala),
+        )
+        .await
+    }
+
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        medir(
+            &*self.metricas,
+            Self::NOMBRE,
+            "eliminar",
+            self.inner.eliminar(id),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CachedEmpleadoRepository, InMemoryEmpleadoRepository, InMemoryReservaRepository,
+        InMemorySalaRepository,
+    };
+    use reservas_ports::testing;
+
+    fn metricas() -> Arc<dyn Metricas> {
+        Arc::new(MetricasPrometheus::new())
+    }
+
+    #[tokio::test]
+    async fn test_contrato_repositorios_medidos() {
+        testing::reserva_repository::verificar_contrato(|| {
+            MeasuredReservaRepository::new(InMemoryReservaRepository::new(), metricas())
+        })
+        .await;
+        testing::empleado_repository::verificar_contrato(|| {
+            MeasuredEmpleadoRepository::new(InMemoryEmpleadoRepository::new(), metricas())
+        })
+        .await;
+        testing::sala_repository::verificar_contrato(|| {
+            MeasuredSalaRepository::new(InMemorySalaRepository::new(), metricas())
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_tiempos_y_errores_de_repositorio() {
+        let metricas = Arc::new(MetricasPrometheus::new());
+        let repo = MeasuredReservaRepository::new(
+            InMemoryReservaRepository::new(),
+            Arc::clone(&metricas) as Arc<dyn Metricas>,
+        );
+        repo.listar().await.unwrap();
+        repo.listar().await.unwrap();
+        assert!(repo.eliminar("no-existe").await.is_err());
+
+        let texto = metricas.exponer();
+        let etiquetas = "repositorio=\"reservas\",operacion=\"listar\"";
+        assert!(texto.contains(&format!(
+            "reservas_repositorio_duracion_segundos_count{{{}}} 2",
+            etiquetas
+        )));
+        assert!(texto.contains(&format!(
+            "reservas_repositorio_duracion_segundos_bucket{{{},le=\"+Inf\"}} 2",
+            etiquetas
+        )));
+        assert!(texto.contains(
+            "reservas_repositorio_errores_total{repositorio=\"reservas\",operacion=\"eliminar\"} 1"
+        ));
+    }
+
+    #[test]
+    fn test_exposicion_prometheus() {
+        let cache = CachedEmpleadoRepository::new(
+            InMemoryEmpleadoRepository::new(),
+            Duration::from_secs(60),
+        );
+        let metricas = MetricasPrometheus::new();
+        metricas.registrar_cache("empleados", cache.metricas());
+        metricas.peticion_http("GET", "/api/reservas/:id", 200, Duration::from_millis(3));
+        metricas.peticion_http("GET", "/api/reservas/:id", 200, Duration::from_millis(30));
+        metricas.peticion_http("GET", "/api/reservas/:id", 404, Duration::from_millis(2));
+        metricas.cambio_reserva(TipoCambio::Creada);
+        metricas.conflicto_rechazado(RecursoEnConflicto::Sala);
+
+        let texto = metricas.exponer();
+        assert!(texto.contains("# TYPE reservas_http_peticiones_total counter\n"));
+        assert!(texto.contains(
+            "reservas_http_peticiones_total{metodo=\"GET\",ruta=\"/api/reservas/:id\",estado=\"200\"} 2\n"
+        ));
+        let etiquetas = "metodo=\"GET\",ruta=\"/api/reservas/:id\"";
+        // Las cubetas son acumuladas
+        for (limite, cuenta) in [("0.001", 0), ("0.0025", 1), ("0.005", 2), ("0.05", 3)] {
+            assert!(texto.contains(&format!(
+                "reservas_http_duracion_segundos_bucket{{{},le=\"{}\"}} {}\n",
+                etiquetas, limite, cuenta
+            )));
+        }
+        assert!(texto.contains(&format!(
+            "reservas_http_duracion_segundos_count{{{}}} 3\n",
+            etiquetas
+        )));
+
+        assert!(texto.contains("reservas_cambios_total{cambio=\"creada\"} 1\n"));
+        assert!(texto.contains("reservas_cambios_total{cambio=\"cancelada\"} 0\n"));
+        assert!(texto.contains("reservas_conflictos_total{recurso=\"sala\"} 1\n"));
+        assert!(texto.contains("reservas_cache_fallos_total{repositorio=\"empleados\"} 0\n"));
+        assert_eq!(escapar("a\"b\\c"), "a\\\"b\\\\c");
+    }
+}
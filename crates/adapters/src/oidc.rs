@@ -0,0 +1,686 @@
+// Proveedor de identidad OpenID Connect
+//
+// Al crearlo se lee el documento de descubrimiento del emisor
+// (`/.well-known/openid-configuration`) y sus claves públicas (JWKS). Los
+// tokens se validan en local con esas claves; si llega uno firmado con una
+// clave desconocida se vuelven a descargar, por si el proveedor las ha rotado.
+
+use async_trait::async_trait;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use reqwest::Url;
+use reservas_domain::Rol;
+use reservas_ports::out::proveedor_identidad::{IdentidadExterna, ProveedorIdentidad};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Algoritmos de firma aceptados (nunca HS*: el secreto sería el del cliente)
+const ALGORITMOS: [Algorithm; 8] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+];
+
+/// Tiempo mínimo entre dos descargas de las claves, para que tokens con un
+/// `kid` inventado no provoquen una petición al proveedor cada vez
+const ESPERA_RECARGA_CLAVES: Duration = Duration::from_secs(60);
+
+const TIEMPO_MAXIMO_PETICION: Duration = Duration::from_secs(10);
+
+/// Cliente registrado en el proveedor
+#[derive(Debug, Clone)]
+pub struct ConfigOidc {
+    emisor: String,
+    cliente_id: String,
+    cliente_secreto: Option<String>,
+    url_redireccion: String,
+    audiencia: Option<String>,
+    claim_roles: String,
+}
+
+impl ConfigOidc {
+    /// `url_redireccion` es la URL de vuelta registrada en el proveedor
+    /// (en la interfaz web, `.../login/oidc/callback`)
+    pub fn new(
+        emisor: impl Into<String>,
+        cliente_id: impl Into<String>,
+        url_redireccion: impl Into<String>,
+    ) -> Self {
+        Self {
+            emisor: emisor.into(),
+            cliente_id: cliente_id.into(),
+            cliente_secreto: None,
+            url_redireccion: url_redireccion.into(),
+            audiencia: None,
+            claim_roles: "roles".to_string(),
+        }
+    }
+
+    /// Secreto de cliente confidencial (se envía con HTTP Basic al canjear el código)
+    pub fn con_secreto(mut self, secreto: impl Into<String>) -> Self {
+        self.cliente_secreto = Some(secreto.into());
+        self
+    }
+
+    /// `aud` exigido en los access tokens; por defecto, el id de cliente
+    pub fn con_audiencia(mut self, audiencia: impl Into<String>) -> Self {
+        self.audiencia = Some(audiencia.into());
+        self
+    }
+
+    /// Claim con los roles; admite rutas con puntos (`realm_access.roles`)
+    pub fn con_claim_roles(mut self, claim: impl Into<String>) -> Self {
+        self.claim_roles = claim.into();
+        self
+    }
+}
+
+/// Campos del documento de descubrimiento que se usan
+#[derive(Debug, Deserialize)]
+struct Descubrimiento {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+    #[serde(default)]
+    userinfo_endpoint: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RespuestaToken {
+    id_token: String,
+    #[serde(default)]
+    access_token: Option<String>,
+}
+
+/// Claims de los tokens y de la respuesta de userinfo
+#[derive(Debug, Deserialize)]
+struct ClaimsOidc {
+    sub: String,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: Option<bool>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    nonce: Option<String>,
+    /// El resto, para leer el claim de roles
+    #[serde(flatten)]
+    resto: Map<String, Value>,
+}
+
+struct Claves {
+    jwks: JwkSet,
+    descargadas: Instant,
+}
+
+pub struct ProveedorOidc {
+    config: ConfigOidc,
+    documento: Descubrimiento,
+    url_autorizacion: Url,
+    http: reqwest::Client,
+    claves: RwLock<Claves>,
+}
+
+async fn obtener_json<T: DeserializeOwned>(http: &reqwest::Client, url: &str) -> Result<T, String> {
+    http.get(url)
+        .send()
+        .await
+        .and_then(|respuesta| respuesta.error_for_status())
+        .map_err(|e| format!("No se pudo consultar {}: {}", url, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Respuesta inválida de {}: {}", url, e))
+}
+
+/// Roles del claim indicado (lista o texto separado por espacios); los
+/// desconocidos se ignoran
+fn roles_de(claims: &Map<String, Value>, ruta: &str) -> Vec<Rol> {
+    let mut partes = ruta.split('.');
+    let Some(valor) = partes.next().and_then(|primera| claims.get(primera)) else {
+        return Vec::new();
+    };
+    let Some(valor) = partes.try_fold(valor, |valor, parte| valor.get(parte)) else {
+        return Vec::new();
+    };
+    let nombres: Vec<&str> = match valor {
+        Value::Array(lista) => lista.iter().filter_map(Value::as_str).collect(),
+        Value::String(texto) => texto.split_whitespace().collect(),
+        _ => Vec::new(),
+    };
+    nombres
+        .into_iter()
+        .filter_map(|nombre| nombre.parse::<Rol>().ok())
+        .collect()
+}
+
+impl ProveedorOidc {
+    /// Lee la configuración del emisor y sus claves públicas
+    pub async fn descubrir(config: ConfigOidc) -> Result<Self, String> {
+        let http = reqwest::Client::builder()
+            .timeout(TIEMPO_MAXIMO_PETICION)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let emisor = config.emisor.trim_end_matches('/');
+        let documento: Descubrimiento = obtener_json(
+            &http,
+            &format!("{}/.well-known/openid-configuration", emisor),
+        )
+        .await?;
+        if documento.issuer.trim_end_matches('/') != emisor {
+            return Err(format!(
+                "El proveedor se identifica como '{}' y no como '{}'",
+                documento.issuer, config.emisor
+            ));
+        }
+        let url_autorizacion = Url::parse(&documento.authorization_endpoint)
+            .map_err(|e| format!("authorization_endpoint inválido: {}", e))?;
+        let jwks: JwkSet = obtener_json(&http, &documento.jwks_uri).await?;
+
+        Ok(Self {
+            config,
+            documento,
+            url_autorizacion,
+            http,
+            claves: RwLock::new(Claves {
+                jwks,
+                descargadas: Instant::now(),
+            }),
+        })
+    }
+
+    fn buscar_clave(&self, kid: Option<&str>) -> Option<Jwk> {
+        let claves = self.claves.read().expect("claves envenenadas");
+        match kid {
+            Some(kid) => claves.jwks.find(kid).cloned(),
+            None if claves.jwks.keys.len() == 1 => claves.jwks.keys.first().cloned(),
+            None => None,
+        }
+    }
+
+    async fn clave(&self, kid: Option<&str>) -> Result<Jwk, String> {
+        if let Some(jwk) = self.buscar_clave(kid) {
+            return Ok(jwk);
+        }
+        let recargar = self
+            .claves
+            .read()
+            .expect("claves envenenadas")
+            .descargadas
+            .elapsed()
+            >= ESPERA_RECARGA_CLAVES;
+        if recargar {
+            let jwks: JwkSet = obtener_json(&self.http, &self.documento.jwks_uri).await?;
+            *self.claves.write().expect("claves envenenadas") = Claves {
+                jwks,
+                descargadas: Instant::now(),
+            };
+        }
+        self.buscar_clave(kid)
+            .ok_or_else(|| "Token firmado con una clave desconocida".to_string())
+    }
+
+    /// Comprueba firma, caducidad, emisor y audiencia
+    async fn validar_jwt(&self, token: &str, audiencia: &str) -> Result<ClaimsOidc, String> {
+        let cabecera = decode_header(token).map_err(|_| "Token no reconocido".to_string())?;
+        if !ALGORITMOS.contains(&cabecera.alg) {
+            return Err(format!("Algoritmo no aceptado: {:?}", cabecera.alg));
+        }
+        let jwk = self.clave(cabecera.kid.as_deref()).await?;
+        let clave = DecodingKey::from_jwk(&jwk).map_err(|e| format!("Clave inválida: {}", e))?;
+
+        let mut validacion = Validation::new(cabecera.alg);
+        validacion.set_issuer(&[&self.documento.issuer]);
+        validacion.set_audience(&[audiencia]);
+        decode::<ClaimsOidc>(token, &clave, &validacion)
+            .map(|datos| datos.claims)
+            .map_err(|e| format!("Token inválido: {}", e))
+    }
+
+    /// Si el token no trae el email, lo pide a userinfo con el access token
+    async fn completar_con_userinfo(
+        &self,
+        mut claims: ClaimsOidc,
+        access_token: &str,
+    ) -> Result<ClaimsOidc, String> {
+        let Some(url) = &self.documento.userinfo_endpoint else {
+            return Ok(claims);
+        };
+        if claims.email.is_some() {
+            return Ok(claims);
+        }
+        let info: ClaimsOidc = self
+            .http
+            .get(url)
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .and_then(|respuesta| respuesta.error_for_status())
+            .map_err(|e| format!("No se pudo consultar userinfo: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Respuesta inválida de userinfo: {}", e))?;
+        if info.sub != claims.sub {
+            return Err("userinfo devuelve otro sujeto".to_string());
+        }
+        claims.email = info.email;
+        claims.email_verified = info.email_verified;
+        claims.name = claims.name.or(info.name);
+        Ok(claims)
+    }
+
+    fn identidad(&self, claims: ClaimsOidc) -> Result<IdentidadExterna, String> {
+        if claims.email_verified == Some(false) {
+            return Err("El proveedor no ha verificado el email".to_string());
+        }
+        Ok(IdentidadExterna {
+            roles: roles_de(&claims.resto, &self.config.claim_roles),
+            sujeto: claims.sub,
+            email: claims.email,
+            email_verificado: claims.email_verified == Some(true),
+            nombre: claims.name,
+        })
+    }
+}
+
+#[async_trait]
+impl ProveedorIdentidad for ProveedorOidc {
+    fn url_autorizacion(&self, estado: &str, nonce: &str, reto_pkce: &str) -> String {
+        let mut url = self.url_autorizacion.clone();
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.cliente_id)
+            .append_pair("redirect_uri", &self.config.url_redireccion)
+            .append_pair("scope", "openid email profile")
+            .append_pair("state", estado)
+            .append_pair("nonce", nonce)
+            .append_pair("code_challenge", reto_pkce)
+            .append_pair("code_challenge_method", "S256");
+        url.into()
+    }
+
+    async fn canjear_codigo(
+        &self,
+        codigo: &str,
+        verificador_pkce: &str,
+        nonce: &str,
+    ) -> Result<IdentidadExterna, String> {
+        let formulario = [
+            ("grant_type", "authorization_code"),
+            ("code", codigo),
+            ("redirect_uri", &self.config.url_redireccion),
+            ("client_id", &self.config.cliente_id),
+            ("code_verifier", verificador_pkce),
+        ];
+        let mut peticion = self.http.post(&self.documento.token_endpoint);
+        if let Some(secreto) = &self.config.cliente_secreto {
+            peticion = peticion.basic_auth(&self.config.cliente_id, Some(secreto));
+        }
+        let respuesta = peticion
+            .form(&formulario)
+            .send()
+            .await
+            .map_err(|e| format!("No se pudo canjear el código: {}", e))?;
+        if !respuesta.status().is_success() {
+            let estado = respuesta.status();
+            let detalle = respuesta.text().await.unwrap_or_default();
+            return Err(format!(
+                "El proveedor rechazó el código ({}): {}",
+                estado, detalle
+            ));
+        }
+        let tokens: RespuestaToken = respuesta
+            .json()
+            .await
+            .map_err(|e| format!("Respuesta inválida del proveedor: {}", e))?;
+
+        let claims = self
+            .validar_jwt(&tokens.id_token, &self.config.cliente_id)
+            .await?;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err("El nonce del ID token no coincide".to_string());
+        }
+        let claims = match &tokens.access_token {
+            Some(access_token) => self.completar_con_userinfo(claims, access_token).await?,
+            None => claims,
+        };
+        self.identidad(claims)
+    }
+
+    async fn validar_token(&self, token: &str) -> Result<IdentidadExterna, String> {
+        let audiencia = self
+            .config
+            .audiencia
+            .as_deref()
+            .unwrap_or(&self.config.cliente_id);
+        let claims = self.validar_jwt(token, audiencia).await?;
+        let claims = self.completar_con_userinfo(claims, token).await?;
+        self.identidad(claims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        extract::{Query, State},
+        http::HeaderMap,
+        response::Redirect,
+        routing::{get, post},
+        Form, Json, Router,
+    };
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+    use sha2::{Digest, Sha256};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    const CLAVE_PRIVADA: &str = include_str!("../testdata/oidc_rs256.pem");
+    const JWKS: &str = include_str!("../testdata/oidc_jwks.json");
+    const CLIENTE: &str = "reservas-web";
+    const REDIRECCION: &str = "http://localhost:3000/login/oidc/callback";
+
+    /// Proveedor OIDC mínimo que se levanta en un puerto local: el usuario
+    /// de `/authorize` se da siempre por autenticado
+    struct Simulado {
+        emisor: String,
+        /// Incluir el email en los tokens (si no, solo lo da userinfo)
+        email_en_tokens: bool,
+        /// código → (reto PKCE, nonce)
+        codigos: Mutex<HashMap<String, (String, String)>>,
+    }
+
+    fn reto(verificador: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(verificador.as_bytes()))
+    }
+
+    fn firmar(claims: Value) -> String {
+        let mut cabecera = Header::new(Algorithm::RS256);
+        cabecera.kid = Some("clave-prueba".to_string());
+        encode(
+            &cabecera,
+            &claims,
+            &EncodingKey::from_rsa_pem(CLAVE_PRIVADA.as_bytes()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn claims(emisor: &str, audiencia: &str, email: bool, exp_en_segundos: i64) -> Value {
+        let mut claims = json!({
+            "iss": emisor,
+            "sub": "usuario-42",
+            "aud": audiencia,
+            "exp": chrono::Utc::now().timestamp() + exp_en_segundos,
+            "name": "Ana García",
+            "realm_access": { "roles": ["responsable", "offline_access"] },
+        });
+        if email {
+            claims["email"] = json!("ana@empresa.com");
+            claims["email_verified"] = json!(true);
+        }
+        claims
+    }
+
+    async fn descubrimiento(State(simulado): State<Arc<Simulado>>) -> Json<Value> {
+        let base = &simulado.emisor;
+        Json(json!({
+            "issuer": base,
+            "authorization_endpoint": format!("{}/authorize", base),
+            "token_endpoint": format!("{}/token", base),
+            "jwks_uri": format!("{}/jwks", base),
+            "userinfo_endpoint": format!("{}/userinfo", base),
+        }))
+    }
+
+    async fn autorizar(
+        State(simulado): State<Arc<Simulado>>,
+        Query(params): Query<HashMap<String, String>>,
+    ) -> Redirect {
+        assert_eq!(params["client_id"], CLIENTE);
+        assert_eq!(params["code_challenge_method"], "S256");
+        let codigo = format!("codigo-{}", simulado.codigos.lock().unwrap().len());
+        simulado.codigos.lock().unwrap().insert(
+            codigo.clone(),
+            (params["code_challenge"].clone(), params["nonce"].clone()),
+        );
+        Redirect::to(&format!(
+            "{}?code={}&state={}",
+            params["redirect_uri"], codigo, params["state"]
+        ))
+    }
+
+    async fn token(
+        State(simulado): State<Arc<Simulado>>,
+        Form(params): Form<HashMap<String, String>>,
+    ) -> Result<Json<Value>, (axum::http::StatusCode, Json<Value>)> {
+        let invalido = || {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "invalid_grant" })),
+            )
+        };
+        // Cada código se canjea una sola vez
+        let (reto_guardado, nonce) = simulado
+            .codigos
+            .lock()
+            .unwrap()
+            .remove(&params["code"])
+            .ok_or_else(invalido)?;
+        if reto(&params["code_verifier"]) != reto_guardado || params["redirect_uri"] != REDIRECCION
+        {
+            return Err(invalido());
+        }
+        let mut id_token = claims(&simulado.emisor, CLIENTE, simulado.email_en_tokens, 60);
+        id_token["nonce"] = json!(nonce);
+        let access_token = claims(
+            &simulado.emisor,
+            "api-reservas",
+            simulado.email_en_tokens,
+            60,
+        );
+        Ok(Json(json!({
+            "token_type": "Bearer",
+            "id_token": firmar(id_token),
+            "access_token": firmar(access_token),
+        })))
+    }
+
+    async fn userinfo(cabeceras: HeaderMap) -> Json<Value> {
+        assert!(cabeceras.contains_key("authorization"));
+        Json(json!({
+            "sub": "usuario-42",
+            "email": "ana@empresa.com",
+            "email_verified": true,
+        }))
+    }
+
+    /// Arranca el proveedor simulado y devuelve su URL (el emisor)
+    async fn arrancar(email_en_tokens: bool) -> String {
+        let escucha = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let emisor = format!("http://{}", escucha.local_addr().unwrap());
+        let simulado = Arc::new(Simulado {
+            emisor: emisor.clone(),
+            email_en_tokens,
+            codigos: Mutex::new(HashMap::new()),
+        });
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(descubrimiento))
+            .route(
+                "/jwks",
+                get(|| async { ([("content-type", "application/json")], JWKS) }),
+            )
+            .route("/authorize", get(autorizar))
+            .route("/token", post(token))
+            .route("/userinfo", get(userinfo))
+            .with_state(simulado);
+        tokio::spawn(async move { axum::serve(escucha, app).await.unwrap() });
+        emisor
+    }
+
+    async fn proveedor(emisor: &str) -> ProveedorOidc {
+        let config = ConfigOidc::new(emisor, CLIENTE, REDIRECCION)
+            .con_secreto("secreto")
+            .con_audiencia("api-reservas")
+            .con_claim_roles("realm_access.roles");
+        ProveedorOidc::descubrir(config).await.unwrap()
+    }
+
+    /// Sigue la URL de autorización como lo haría el navegador y devuelve el
+    /// código de la redirección de vuelta
+    async fn codigo_de(url: &str, estado: &str) -> String {
+        let http = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap();
+        let respuesta = http.get(url).send().await.unwrap();
+        let destino = Url::parse(respuesta.headers()["location"].to_str().unwrap()).unwrap();
+        assert!(destino.as_str().starts_with(REDIRECCION));
+        let params: HashMap<_, _> = destino.query_pairs().into_owned().collect();
+        assert_eq!(params["state"], estado);
+        params["code"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_flujo_authorization_code_con_pkce() {
+        let emisor = arrancar(true).await;
+        let proveedor = proveedor(&emisor).await;
+
+        let url = proveedor.url_autorizacion("estado-1", "nonce-1", &reto("verificador-1"));
+        let codigo = codigo_de(&url, "estado-1").await;
+        let identidad = proveedor
+            .canjear_codigo(&codigo, "verificador-1", "nonce-1")
+            .await
+            .unwrap();
+        assert_eq!(identidad.sujeto, "usuario-42");
+        assert_eq!(identidad.email.as_deref(), Some("ana@empresa.com"));
+        assert_eq!(identidad.nombre.as_deref(), Some("Ana García"));
+        assert_eq!(identidad.roles, vec![Rol::Responsable]);
+
+        // El código ya se ha usado
+        assert!(proveedor
+            .canjear_codigo(&codigo, "verificador-1", "nonce-1")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rechaza_verificador_o_nonce_incorrectos() {
+        let emisor = arrancar(true).await;
+        let proveedor = proveedor(&emisor).await;
+
+        let url = proveedor.url_autorizacion("e", "nonce-1", &reto("verificador-1"));
+        let codigo = codigo_de(&url, "e").await;
+        let error = proveedor
+            .canjear_codigo(&codigo, "otro-verificador", "nonce-1")
+            .await
+            .unwrap_err();
+        assert!(error.contains("invalid_grant"));
+
+        let url = proveedor.url_autorizacion("e", "nonce-1", &reto("verificador-1"));
+        let codigo = codigo_de(&url, "e").await;
+        let error = proveedor
+            .canjear_codigo(&codigo, "verificador-1", "otro-nonce")
+            .await
+            .unwrap_err();
+        assert!(error.contains("nonce"));
+    }
+
+    #[tokio::test]
+    async fn test_valida_access_tokens() {
+        let emisor = arrancar(true).await;
+        let proveedor = proveedor(&emisor).await;
+
+        let token = firmar(claims(&emisor, "api-reservas", true, 60));
+        let identidad = proveedor.validar_token(&token).await.unwrap();
+        assert_eq!(identidad.email.as_deref(), Some("ana@empresa.com"));
+
+        // Otra audiencia, otro emisor o caducado
+        for claims in [
+            claims(&emisor, "otra-api", true, 60),
+            claims("http://otro-emisor", "api-reservas", true, 60),
+            claims(&emisor, "api-reservas", true, -120),
+        ] {
+            assert!(proveedor.validar_token(&firmar(claims)).await.is_err());
+        }
+
+        // Firmado con HS256 (p. ej. con la clave pública como secreto)
+        let hs256 = encode(
+            &Header::new(Algorithm::HS256),
+            &claims(&emisor, "api-reservas", true, 60),
+            &EncodingKey::from_secret(JWKS.as_bytes()),
+        )
+        .unwrap();
+        assert!(proveedor.validar_token(&hs256).await.is_err());
+
+        // Email sin verificar
+        let mut sin_verificar = claims(&emisor, "api-reservas", true, 60);
+        sin_verificar["email_verified"] = json!(false);
+        assert!(proveedor
+            .validar_token(&firmar(sin_verificar))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_token_sin_email_verified() {
+        let emisor = arrancar(true).await;
+        let proveedor = proveedor(&emisor).await;
+
+        let identidad = proveedor
+            .validar_token(&firmar(claims(&emisor, "api-reservas", true, 60)))
+            .await
+            .unwrap();
+        assert!(identidad.email_verificado);
+
+        // Sin la claim el token vale, pero el email no cuenta como verificado
+        let mut sin_claim = claims(&emisor, "api-reservas", true, 60);
+        sin_claim.as_object_mut().unwrap().remove("email_verified");
+        let identidad = proveedor.validar_token(&firmar(sin_claim)).await.unwrap();
+        assert_eq!(identidad.email.as_deref(), Some("ana@empresa.com"));
+        assert!(!identidad.email_verificado);
+    }
+
+    #[tokio::test]
+    async fn test_email_desde_userinfo() {
+        let emisor = arrancar(false).await;
+        let proveedor = proveedor(&emisor).await;
+
+        let token = firmar(claims(&emisor, "api-reservas", false, 60));
+        let identidad = proveedor.validar_token(&token).await.unwrap();
+        assert_eq!(identidad.email.as_deref(), Some("ana@empresa.com"));
+        assert_eq!(identidad.nombre.as_deref(), Some("Ana García"));
+    }
+
+    #[tokio::test]
+    async fn test_descubrimiento_con_otro_emisor_falla() {
+        let emisor = arrancar(true).await;
+        let config = ConfigOidc::new(format!("{}/otro", emisor), CLIENTE, REDIRECCION);
+        assert!(ProveedorOidc::descubrir(config).await.is_err());
+    }
+
+    #[test]
+    fn test_roles_de_claims() {
+        let claims = json!({
+            "roles": "admin-salas desconocido",
+            "realm_access": { "roles": ["empleado", "admin"] },
+        });
+        let claims = claims.as_object().unwrap();
+        assert_eq!(roles_de(claims, "roles"), vec![Rol::AdminSalas]);
+        assert_eq!(
+            roles_de(claims, "realm_access.roles"),
+            vec![Rol::Empleado, Rol::Admin]
+        );
+        assert!(roles_de(claims, "groups").is_empty());
+    }
+}
@@ -3,6 +3,7 @@
 
 use async_trait::async_trait;
 use reservas_domain::{Reserva, Slot};
+use reservas_ports::consulta::{ConsultaReservas, Pagina};
 use reservas_ports::out::reserva_repository::ReservaRepository;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -28,6 +29,12 @@ impl InMemoryReservaRepository {
     }
 }
 
+/// El HashMap no garantiza orden: devolvemos siempre orden cronológico
+fn ordenadas(mut reservas: Vec<Reserva>) -> Vec<Reserva> {
+    reservas.sort_by(|a, b| (a.slot.inicio, &a.id).cmp(&(b.slot.inicio, &b.id)));
+    reservas
+}
+
 #[async_trait]
 impl ReservaRepository for InMemoryReservaRepository {
     async fn guardar(&self, reserva: &Reserva) -> Result<(), String> {
@@ -36,6 +43,15 @@ impl ReservaRepository for InMemoryReservaRepository {
         Ok(())
     }
 
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        let mut storage = self.storage.write().await;
+
+        storage
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| "Reserva no encontrada".to_string())
+    }
+
     async fn obtener(&self, id: &str) -> Result<Option<Reserva>, String> {
         let storage = self.storage.read().await;
         Ok(storage.get(id).cloned())
@@ -43,25 +59,37 @@ impl ReservaRepository for InMemoryReservaRepository {
 
     async fn listar(&self) -> Result<Vec<Reserva>, String> {
         let storage = self.storage.read().await;
-        Ok(storage.values().cloned().collect())
+        Ok(ordenadas(storage.values().cloned().collect()))
+    }
+
+    async fn buscar(&self, consulta: &ConsultaReservas) -> Result<Pagina<Reserva>, String> {
+        let storage = self.storage.read().await;
+
+        let candidatos: Vec<&Reserva> = storage.values().filter(|r| consulta.cumple(r)).collect();
+
+        consulta.paginar(candidatos)
     }
 
     async fn listar_por_empleado(&self, empleado_id: &str) -> Result<Vec<Reserva>, String> {
         let storage = self.storage.read().await;
-        Ok(storage
-            .values()
-            .filter(|r| r.empleado_id == empleado_id && r.esta_activa())
-            .cloned()
-            .collect())
+        Ok(ordenadas(
+            storage
+                .values()
+                .filter(|r| r.empleado_id == empleado_id && r.esta_activa())
+                .cloned()
+                .collect(),
+        ))
     }
 
     async fn listar_por_slot(&self, slot: &Slot) -> Result<Vec<Reserva>, String> {
         let storage = self.storage.read().await;
-        Ok(storage
-            .values()
-            .filter(|r| r.slot == *slot && r.esta_activa())
-            .cloned()
-            .collect())
+        Ok(ordenadas(
+            storage
+                .values()
+                .filter(|r| r.slot == *slot && r.esta_activa())
+                .cloned()
+                .collect(),
+        ))
     }
 
     async fn actualizar(&self, reserva: &Reserva) -> Result<(), String> {
@@ -97,6 +125,12 @@ mod tests {
     use super::*;
     use chrono::{Datelike, Utc};
     use reservas_domain::{EstadoReserva, Slot};
+    use reservas_ports::testing;
+
+    #[tokio::test]
+    async fn test_contrato_reserva_repository() {
+        testing::reserva_repository::verificar_contrato(InMemoryReservaRepository::new).await;
+    }
 
     #[tokio::test]
     async fn test_guardar_y_obtener() {
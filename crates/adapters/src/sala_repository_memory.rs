@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use reservas_domain::Sala;
+use reservas_ports::consulta::{ConsultaSalas, Pagina};
 use reservas_ports::out::sala_repository::SalaRepository;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -38,7 +39,17 @@ impl SalaRepository for InMemorySalaRepository {
 
     async fn listar(&self) -> Result<Vec<Sala>, String> {
         let salas = self.salas.read().await;
-        Ok(salas.values().cloned().collect())
+        let mut listado: Vec<Sala> = salas.values().cloned().collect();
+        listado.sort_by(|a, b| (&a.nombre, &a.id).cmp(&(&b.nombre, &b.id)));
+        Ok(listado)
+    }
+
+    async fn buscar(&self, consulta: &ConsultaSalas) -> Result<Pagina<Sala>, String> {
+        let salas = self.salas.read().await;
+
+        let candidatas: Vec<&Sala> = salas.values().filter(|s| consulta.cumple(s)).collect();
+
+        consulta.paginar(candidatas)
     }
 
     async fn actualizar(&self, sala: &Sala) -> Result<(), String> {
@@ -50,4 +61,23 @@ impl SalaRepository for InMemorySalaRepository {
             Err("Sala no encontrada".to_string())
         }
     }
+
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        let mut salas = self.salas.write().await;
+        salas
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| "Sala no encontrada".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reservas_ports::testing;
+
+    #[tokio::test]
+    async fn test_contrato_sala_repository() {
+        testing::sala_repository::verificar_contrato(InMemorySalaRepository::new).await;
+    }
 }
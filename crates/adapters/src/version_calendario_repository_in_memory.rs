@@ -0,0 +1,37 @@
+// Adaptador de salida: versiones de las URLs de los calendarios en memoria
+
+use async_trait::async_trait;
+use reservas_ports::out::version_calendario_repository::VersionCalendarioRepository;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+#[derive(Default)]
+pub struct InMemoryVersionCalendarioRepository {
+    storage: RwLock<HashMap<String, u64>>,
+}
+
+impl InMemoryVersionCalendarioRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl VersionCalendarioRepository for InMemoryVersionCalendarioRepository {
+    async fn version(&self, calendario: &str) -> Result<u64, String> {
+        Ok(self
+            .storage
+            .read()
+            .await
+            .get(calendario)
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn incrementar(&self, calendario: &str) -> Result<u64, String> {
+        let mut storage = self.storage.write().await;
+        let version = storage.entry(calendario.to_string()).or_default();
+        *version += 1;
+        Ok(*version)
+    }
+}
@@ -0,0 +1,104 @@
+// Adaptador de salida: envío de webhooks por HTTP
+
+use async_trait::async_trait;
+use reservas_ports::out::webhooks::ClienteWebhook;
+use std::time::Duration;
+
+/// Tiempo máximo de espera por defecto a que responda el receptor
+pub const TIEMPO_MAXIMO_ENTREGA: Duration = Duration::from_secs(10);
+
+pub struct ClienteWebhookHttp {
+    http: reqwest::Client,
+}
+
+impl Default for ClienteWebhookHttp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClienteWebhookHttp {
+    pub fn new() -> Self {
+        Self::con_tiempo_maximo(TIEMPO_MAXIMO_ENTREGA)
+    }
+
+    /// Los receptores que tardan más se cuentan como fallo de red
+    pub fn con_tiempo_maximo(tiempo_maximo: Duration) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(tiempo_maximo)
+            // Una redirección podría llevar la entrega firmada a otro sitio
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("configuración válida del cliente HTTP");
+        Self { http }
+    }
+}
+
+#[async_trait]
+impl ClienteWebhook for ClienteWebhookHttp {
+    async fn enviar(
+        &self,
+        url: &str,
+        cabeceras: &[(String, String)],
+        cuerpo: &str,
+    ) -> Result<u16, String> {
+        let mut peticion = self
+            .http
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(cuerpo.to_string());
+        for (nombre, valor) in cabeceras {
+            peticion = peticion.header(nombre, valor);
+        }
+        let respuesta = peticion.send().await.map_err(|e| e.to_string())?;
+        Ok(respuesta.status().as_u16())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+
+    async fn receptor(cabeceras: HeaderMap, cuerpo: String) -> StatusCode {
+        let correcta = cabeceras.get("content-type").map(|v| v.as_bytes())
+            == Some(b"application/json")
+            && cabeceras.get("x-prueba").map(|v| v.as_bytes()) == Some(b"1")
+            && cuerpo == r#"{"hola":true}"#;
+        if correcta {
+            StatusCode::ACCEPTED
+        } else {
+            StatusCode::BAD_REQUEST
+        }
+    }
+
+    #[tokio::test]
+    async fn test_envia_cuerpo_y_cabeceras() {
+        let escucha = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", escucha.local_addr().unwrap());
+        let app = Router::new().route("/hook", post(receptor)).route(
+            "/lento",
+            post(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }),
+        );
+        tokio::spawn(async move { axum::serve(escucha, app).await.unwrap() });
+
+        let cliente = ClienteWebhookHttp::con_tiempo_maximo(Duration::from_millis(200));
+        let cabeceras = [("X-Prueba".to_string(), "1".to_string())];
+        assert_eq!(
+            cliente.enviar(&url, &cabeceras, r#"{"hola":true}"#).await,
+            Ok(202)
+        );
+        assert_eq!(cliente.enviar(&url, &[], "{}").await, Ok(400));
+        // Sin respuesta a tiempo o sin nadie escuchando no hay código
+        let lento = url.replace("/hook", "/lento");
+        assert!(cliente.enviar(&lento, &[], "{}").await.is_err());
+        assert!(cliente
+            .enviar("http://127.0.0.1:9/hook", &[], "{}")
+            .await
+            .is_err());
+    }
+}
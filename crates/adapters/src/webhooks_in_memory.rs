@@ -0,0 +1,159 @@
+// Adaptador de salida: webhooks y registro de entregas en memoria
+
+use async_trait::async_trait;
+use reservas_ports::out::webhooks::{Entrega, Webhook, WebhookRepository};
+use std::collections::VecDeque;
+use tokio::sync::RwLock;
+
+/// Entregas que se conservan por defecto; al llenarse se descartan las más antiguas
+pub const CAPACIDAD_ENTREGAS: usize = 1000;
+
+pub struct InMemoryWebhookRepository {
+    /// En orden de alta
+    webhooks: RwLock<Vec<Webhook>>,
+    entregas: RwLock<VecDeque<Entrega>>,
+    capacidad: usize,
+}
+
+impl Default for InMemoryWebhookRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryWebhookRepository {
+    pub fn new() -> Self {
+        Self::con_capacidad(CAPACIDAD_ENTREGAS)
+    }
+
+    pub fn con_capacidad(capacidad: usize) -> Self {
+        Self {
+            webhooks: RwLock::new(Vec::new()),
+            entregas: RwLock::new(VecDeque::new()),
+            capacidad,
+        }
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for InMemoryWebhookRepository {
+    async fn guardar(&self, webhook: &Webhook) -> Result<(), String> {
+        let mut webhooks = self.webhooks.write().await;
+        if webhooks.iter().any(|w| w.id == webhook.id) {
+            return Err(format!("El webhook {} ya existe", webhook.id));
+        }
+        webhooks.push(webhook.clone());
+        Ok(())
+    }
+
+    async fn obtener(&self, id: &str) -> Result<Option<Webhook>, String> {
+        let webhooks = self.webhooks.read().await;
+        Ok(webhooks.iter().find(|w| w.id == id).cloned())
+    }
+
+    async fn listar(&self) -> Result<Vec<Webhook>, String> {
+        Ok(self.webhooks.read().await.clone())
+    }
+
+    async fn eliminar(&self, id: &str) -> Result<bool, String> {
+        let mut webhooks = self.webhooks.write().await;
+        let antes = webhooks.len();
+        webhooks.retain(|w| w.id != id);
+        if webhooks.len() == antes {
+            return Ok(false);
+        }
+        self.entregas.write().await.retain(|e| e.webhook_id != id);
+        Ok(true)
+    }
+
+    async fn guardar_entrega(&self, entrega: &Entrega) -> Result<(), String> {
+        let mut entregas = self.entregas.write().await;
+        if let Some(actual) = entregas.iter_mut().find(|e| e.id == entrega.id) {
+            *actual = entrega.clone();
+            return Ok(());
+        }
+        if entregas.len() == self.capacidad {
+            entregas.pop_front();
+        }
+        entregas.push_back(entrega.clone());
+        Ok(())
+    }
+
+    async fn obtener_entrega(&self, id: &str) -> Result<Option<Entrega>, String> {
+        let entregas = self.entregas.read().await;
+        Ok(entregas.iter().find(|e| e.id == id).cloned())
+    }
+
+    async fn listar_entregas(&self, webhook_id: &str) -> Result<Vec<Entrega>, String> {
+        let entregas = self.entregas.read().await;
+        Ok(entregas
+            .iter()
+            .filter(|e| e.webhook_id == webhook_id)
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use reservas_ports::out::notificador::TipoCambio;
+    use reservas_ports::out::webhooks::EstadoEntrega;
+
+    fn webhook(id: &str) -> Webhook {
+        Webhook {
+            id: id.to_string(),
+            url: "http://localhost/hook".to_string(),
+            eventos: vec![],
+            secreto: "secreto".to_string(),
+            creado: Utc::now(),
+        }
+    }
+
+    fn entrega(id: &str, webhook_id: &str) -> Entrega {
+        Entrega {
+            id: id.to_string(),
+            webhook_id: webhook_id.to_string(),
+            evento: TipoCambio::Creada,
+            cuerpo: "{}".to_string(),
+            estado: EstadoEntrega::Pendiente,
+            intentos: vec![],
+            creada: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entregas_por_webhook_y_borrado_en_cascada() {
+        let repo = InMemoryWebhookRepository::con_capacidad(3);
+        repo.guardar(&webhook("w1")).await.unwrap();
+        repo.guardar(&webhook("w2")).await.unwrap();
+        assert!(repo.guardar(&webhook("w1")).await.is_err());
+
+        for (id, webhook_id) in [("e1", "w1"), ("e2", "w2"), ("e3", "w1"), ("e4", "w1")] {
+            repo.guardar_entrega(&entrega(id, webhook_id))
+                .await
+                .unwrap();
+        }
+        // Actualizar no ocupa sitio
+        let mut e4 = entrega("e4", "w1");
+        e4.estado = EstadoEntrega::Entregada;
+        repo.guardar_entrega(&e4).await.unwrap();
+
+        let ids = |entregas: Vec<Entrega>| -> Vec<String> {
+            entregas.into_iter().map(|e| e.id).collect()
+        };
+        // La más antigua (e1) se ha descartado
+        assert_eq!(ids(repo.listar_entregas("w1").await.unwrap()), ["e3", "e4"]);
+        assert_eq!(
+            repo.obtener_entrega("e4").await.unwrap().unwrap().estado,
+            EstadoEntrega::Entregada
+        );
+
+        assert!(repo.eliminar("w1").await.unwrap());
+        assert!(!repo.eliminar("w1").await.unwrap());
+        assert!(repo.listar_entregas("w1").await.unwrap().is_empty());
+        assert_eq!(ids(repo.listar_entregas("w2").await.unwrap()), ["e2"]);
+        assert_eq!(repo.listar().await.unwrap().len(), 1);
+    }
+}
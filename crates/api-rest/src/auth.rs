@@ -0,0 +1,535 @@
+// Autenticación de la API REST
+//
+// Se aceptan dos tipos de credencial, siempre configuradas en el servidor:
+// - Claves de API estáticas: cabecera `X-API-Key: <clave>` o `Authorization: Bearer <clave>`
+// - JWT firmados con HS256 (secreto compartido) o RS256 (clave pública PEM):
+//   `Authorization: Bearer <jwt>`
+// - Access tokens de un proveedor OpenID Connect, si está configurado. Se
+//   prueban los últimos porque validarlos puede requerir consultar al proveedor.
+//
+// El middleware `autenticar` valida la credencial e inserta el `Principal`
+// en la petición; los handlers lo reciben como un extractor más y lo pasan a
+// los casos de uso como `Actor`, que es quien decide los permisos.
+
+use crate::dtos::ProblemaResponse;
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use reservas_domain::{Actor, Rol};
+use reservas_ports::r#in::sso_service::SsoService;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Cabecera alternativa para enviar una clave de API
+pub const CABECERA_API_KEY: &str = "x-api-key";
+
+/// Cómo se autenticó el principal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetodoAuth {
+    ApiKey,
+    Jwt,
+    Oidc,
+    /// Autenticación desactivada
+    Anonimo,
+}
+
+/// Quién hace la petición
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    /// Nombre de la clave de API, `sub` del JWT o email (OIDC)
+    pub sujeto: String,
+    pub metodo: MetodoAuth,
+    /// Empleado al que representa (claim `empleado_id` del JWT)
+    pub empleado_id: Option<String>,
+    pub roles: Vec<Rol>,
+}
+
+impl Principal {
+    /// El principal tal como lo ven los casos de uso
+    pub fn actor(&self) -> Actor {
+        Actor::new(
+            self.sujeto.clone(),
+            self.empleado_id.clone(),
+            self.roles.clone(),
+        )
+    }
+}
+
+/// Los handlers piden el principal como parámetro. Solo está disponible en
+/// las rutas protegidas por `autenticar`.
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Principal {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Principal>().cloned().ok_or_else(|| {
+            ProblemaResponse::new(StatusCode::UNAUTHORIZED, "Petición sin autenticar")
+                .into_response()
+        })
+    }
+}
+
+/// Claims que se leen del JWT. `exp` es obligatorio.
+#[derive(Debug, Deserialize)]
+struct Claims {
+    sub: String,
+    #[serde(default)]
+    empleado_id: Option<String>,
+    /// Los roles desconocidos se ignoran
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+/// Clave de API configurada
+#[derive(Clone)]
+struct ClaveApi {
+    nombre: String,
+    roles: Vec<Rol>,
+}
+
+/// Credenciales aceptadas por la API
+#[derive(Clone, Default)]
+pub struct ConfigAuth {
+    /// Clave → nombre con el que aparece en los logs y sus roles
+    claves_api: HashMap<String, ClaveApi>,
+    hs256: Option<DecodingKey>,
+    rs256: Option<DecodingKey>,
+    emisor: Option<String>,
+    audiencia: Option<String>,
+    /// Proveedor OIDC cuyos access tokens se aceptan
+    oidc: Option<Arc<dyn SsoService>>,
+    /// Sin autenticación (solo para desarrollo)
+    desactivada: bool,
+}
+
+impl ConfigAuth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// La API queda abierta: todas las peticiones entran como `anonimo`
+    pub fn desactivada() -> Self {
+        Self {
+            desactivada: true,
+            ..Self::default()
+        }
+    }
+
+    /// Clave de API con rol de administrador
+    pub fn con_clave_api(self, nombre: impl Into<String>, clave: impl Into<String>) -> Self {
+        self.con_clave_api_y_roles(nombre, clave, vec![Rol::Admin])
+    }
+
+    pub fn con_clave_api_y_roles(
+        mut self,
+        nombre: impl Into<String>,
+        clave: impl Into<String>,
+        roles: Vec<Rol>,
+    ) -> Self {
+        let clave_api = ClaveApi {
+            nombre: nombre.into(),
+            roles,
+        };
+        self.claves_api.insert(clave.into(), clave_api);
+        self
+    }
+
+    /// Claves en formato `nombre:clave[:rol+rol],...`. Sin roles la clave es de
+    /// administrador.
+    pub fn con_claves_api(mut self, lista: &str) -> Result<Self, String> {
+        for entrada in lista.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let partes: Vec<&str> = entrada.splitn(3, ':').collect();
+            match partes.as_slice() {
+                [nombre, clave, resto @ ..] if !nombre.is_empty() && !clave.is_empty() => {
+                    let roles = match resto.first() {
+                        Some(roles) => roles
+                            .split('+')
+                            .map(|r| r.trim().parse::<Rol>())
+                            .collect::<Result<Vec<_>, _>>()
+                            .map_err(|e| format!("Clave de API '{}': {}", nombre, e))?,
+                        None => vec![Rol::Admin],
+                    };
+                    self = self.con_clave_api_y_roles(*nombre, *clave, roles);
+                }
+                _ => {
+                    return Err(format!(
+                        "Clave de API inválida '{}': use nombre:clave[:rol+rol]",
+                        entrada
+                    ))
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn con_jwt_hs256(mut self, secreto: &[u8]) -> Self {
+        self.hs256 = Some(DecodingKey::from_secret(secreto));
+        self
+    }
+
+    /// Clave pública RSA en PEM para verificar los JWT RS256
+    pub fn con_jwt_rs256(mut self, pem: &[u8]) -> Result<Self, String> {
+        let clave = DecodingKey::from_rsa_pem(pem)
+            .map_err(|e| format!("Clave pública RS256 inválida: {}", e))?;
+        self.rs256 = Some(clave);
+        Ok(self)
+    }
+
+    /// Exige este `iss` en los JWT
+    pub fn con_emisor(mut self, emisor: impl Into<String>) -> Self {
+        self.emisor = Some(emisor.into());
+        self
+    }
+
+    /// Exige este `aud` en los JWT
+    pub fn con_audiencia(mut self, audiencia: impl Into<String>) -> Self {
+        self.audiencia = Some(audiencia.into());
+        self
+    }
+
+    /// Acepta access tokens del proveedor OIDC; el empleado se busca (o se
+    /// crea) por el email del token
+    pub fn con_oidc(mut self, sso: Arc<dyn SsoService>) -> Self {
+        self.oidc = Some(sso);
+        self
+    }
+
+    pub fn esta_desactivada(&self) -> bool {
+        self.desactivada
+    }
+
+    /// Sin ninguna credencial configurada se rechaza toda petición
+    pub fn tiene_credenciales(&self) -> bool {
+        !self.claves_api.is_empty()
+            || self.hs256.is_some()
+            || self.rs256.is_some()
+            || self.oidc.is_some()
+    }
+
+    /// Valida las credenciales de la petición
+    pub fn autenticar(&self, cabeceras: &HeaderMap) -> Result<Principal, String> {
+        if self.desactivada {
+            return Ok(Principal {
+                sujeto: "anonimo".to_string(),
+                metodo: MetodoAuth::Anonimo,
+                empleado_id: None,
+                roles: vec![Rol::Admin],
+            });
+        }
+
+        if let Some(clave) = cabeceras.get(CABECERA_API_KEY) {
+            let clave = clave
+                .to_str()
+                .map_err(|_| "Cabecera X-API-Key inválida".to_string())?;
+            return self
+                .por_clave_api(clave)
+                .ok_or_else(|| "Clave de API no reconocida".to_string());
+        }
+
+        let token = token_bearer(cabeceras)?;
+        match self.por_clave_api(token) {
+            Some(principal) => Ok(principal),
+            None => self.por_jwt(token),
+        }
+    }
+
+    /// Como `autenticar`, probando al final con el proveedor OIDC
+    pub async fn autenticar_con_oidc(&self, cabeceras: &HeaderMap) -> Result<Principal, String> {
+        let error = match self.autenticar(cabeceras) {
+            Ok(principal) => return Ok(principal),
+            Err(error) => error,
+        };
+        let (Some(sso), Ok(token)) = (&self.oidc, token_bearer(cabeceras)) else {
+            return Err(error);
+        };
+        if cabeceras.contains_key(CABECERA_API_KEY) {
+            return Err(error);
+        }
+        let actor = sso.autenticar_token(token).await?;
+        Ok(Principal {
+            sujeto: actor.sujeto,
+            metodo: MetodoAuth::Oidc,
+            empleado_id: actor.empleado_id,
+            roles: actor.roles,
+        })
+    }
+
+    fn por_clave_api(&self, clave: &str) -> Option<Principal> {
+        // Comparación en tiempo constante contra todas las claves
+        let mut encontrada = None;
+        for (candidata, clave_api) in &self.claves_api {
+            if iguales_tiempo_constante(candidata.as_bytes(), clave.as_bytes()) {
+                encontrada = Some(clave_api);
+            }
+        }
+        encontrada.map(|clave_api| Principal {
+            sujeto: clave_api.nombre.clone(),
+            metodo: MetodoAuth::ApiKey,
+            empleado_id: None,
+            roles: clave_api.roles.clone(),
+        })
+    }
+
+    fn por_jwt(&self, token: &str) -> Result<Principal, String> {
+        let cabecera = decode_header(token).map_err(|_| "Token no reconocido".to_string())?;
+        // El algoritmo de la cabecera solo elige entre las claves configuradas
+        let clave = match cabecera.alg {
+            Algorithm::HS256 => self.hs256.as_ref(),
+            Algorithm::RS256 => self.rs256.as_ref(),
+            _ => None,
+        }
+        .ok_or_else(|| format!("Algoritmo JWT no aceptado: {:?}", cabecera.alg))?;
+
+        let mut validacion = Validation::new(cabecera.alg);
+        if let Some(emisor) = &self.emisor {
+            validacion.set_issuer(&[emisor]);
+        }
+        match &self.audiencia {
+            Some(audiencia) => validacion.set_audience(&[audiencia]),
+            None => validacion.validate_aud = false,
+        }
+
+        let datos = decode::<Claims>(token, clave, &validacion)
+            .map_err(|e| format!("JWT inválido: {}", e))?;
+        let roles = datos
+            .claims
+            .roles
+            .iter()
+            .filter_map(|r| r.parse::<Rol>().ok())
+            .collect();
+        Ok(Principal {
+            sujeto: datos.claims.sub,
+            metodo: MetodoAuth::Jwt,
+            empleado_id: datos.claims.empleado_id,
+            roles,
+        })
+    }
+}
+
+fn token_bearer(cabeceras: &HeaderMap) -> Result<&str, String> {
+    let autorizacion = cabeceras
+        .get(header::AUTHORIZATION)
+        .ok_or_else(|| "Falta la cabecera Authorization o X-API-Key".to_string())?
+        .to_str()
+        .map_err(|_| "Cabecera Authorization inválida".to_string())?;
+    autorizacion
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .ok_or_else(|| "Se esperaba 'Authorization: Bearer <token>'".to_string())
+}
+
+fn iguales_tiempo_constante(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Middleware de las rutas protegidas: 401 si la credencial no es válida
+pub(crate) async fn autenticar(
+    State(config): State<Arc<ConfigAuth>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match config.autenticar_con_oidc(request.headers()).await {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        }
+        Err(detalle) => {
+            tracing::debug!("Petición rechazada: {}", detalle);
+            let mut response =
+                ProblemaResponse::new(StatusCode::UNAUTHORIZED, detalle).into_response();
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde::Serialize;
+
+    const RSA_PRIVADA: &str = include_str!("../testdata/jwt_rs256.pem");
+    const RSA_PUBLICA: &str = include_str!("../testdata/jwt_rs256.pub.pem");
+
+    #[derive(Serialize)]
+    struct ClaimsPrueba<'a> {
+        sub: &'a str,
+        exp: i64,
+        iss: &'a str,
+        empleado_id: &'a str,
+        roles: Vec<&'a str>,
+    }
+
+    fn claims(exp_en_segundos: i64) -> ClaimsPrueba<'static> {
+        ClaimsPrueba {
+            sub: "ana",
+            exp: chrono::Utc::now().timestamp() + exp_en_segundos,
+            iss: "reservas",
+            empleado_id: "emp-ana",
+            roles: vec!["responsable", "desconocido"],
+        }
+    }
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut cabeceras = HeaderMap::new();
+        cabeceras.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+        );
+        cabeceras
+    }
+
+    fn hs256(secreto: &[u8], exp_en_segundos: i64) -> String {
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims(exp_en_segundos),
+            &EncodingKey::from_secret(secreto),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_clave_api() {
+        let config = ConfigAuth::new()
+            .con_claves_api("integracion:s3cr3t, backup:otra")
+            .unwrap();
+
+        let mut cabeceras = HeaderMap::new();
+        cabeceras.insert(CABECERA_API_KEY, HeaderValue::from_static("s3cr3t"));
+        let principal = config.autenticar(&cabeceras).unwrap();
+        assert_eq!(principal.sujeto, "integracion");
+        assert_eq!(principal.metodo, MetodoAuth::ApiKey);
+        assert_eq!(principal.roles, vec![Rol::Admin]);
+
+        // También como Bearer
+        assert_eq!(config.autenticar(&bearer("otra")).unwrap().sujeto, "backup");
+
+        cabeceras.insert(CABECERA_API_KEY, HeaderValue::from_static("mala"));
+        assert!(config.autenticar(&cabeceras).is_err());
+        assert!(config.autenticar(&HeaderMap::new()).is_err());
+        assert!(ConfigAuth::new().con_claves_api("sin-clave").is_err());
+    }
+
+    #[test]
+    fn test_clave_api_con_roles() {
+        let config = ConfigAuth::new()
+            .con_claves_api("salas:k1:admin-salas+responsable")
+            .unwrap();
+        let principal = config.autenticar(&bearer("k1")).unwrap();
+        assert_eq!(principal.roles, vec![Rol::AdminSalas, Rol::Responsable]);
+        assert!(principal.actor().tiene_rol(Rol::AdminSalas));
+
+        assert!(ConfigAuth::new().con_claves_api("x:k:jefe").is_err());
+    }
+
+    #[test]
+    fn test_jwt_hs256() {
+        let config = ConfigAuth::new()
+            .con_jwt_hs256(b"secreto")
+            .con_emisor("reservas");
+
+        let principal = config.autenticar(&bearer(&hs256(b"secreto", 60))).unwrap();
+        assert_eq!(principal.sujeto, "ana");
+        assert_eq!(principal.metodo, MetodoAuth::Jwt);
+        assert_eq!(principal.empleado_id.as_deref(), Some("emp-ana"));
+        assert_eq!(principal.roles, vec![Rol::Responsable]);
+
+        // Firma con otro secreto, caducado o emisor distinto
+        assert!(config.autenticar(&bearer(&hs256(b"otro", 60))).is_err());
+        assert!(config
+            .autenticar(&bearer(&hs256(b"secreto", -120)))
+            .is_err());
+        let config = ConfigAuth::new()
+            .con_jwt_hs256(b"secreto")
+            .con_emisor("otro");
+        assert!(config.autenticar(&bearer(&hs256(b"secreto", 60))).is_err());
+    }
+
+    #[test]
+    fn test_jwt_rs256() {
+        let config = ConfigAuth::new()
+            .con_jwt_rs256(RSA_PUBLICA.as_bytes())
+            .unwrap();
+        let token = encode(
+            &Header::new(Algorithm::RS256),
+            &claims(60),
+            &EncodingKey::from_rsa_pem(RSA_PRIVADA.as_bytes()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(config.autenticar(&bearer(&token)).unwrap().sujeto, "ana");
+
+        // Sin secreto HS256 configurado no se aceptan tokens HS256
+        assert!(config.autenticar(&bearer(&hs256(b"secreto", 60))).is_err());
+    }
+
+    /// Proveedor OIDC que solo reconoce `token-oidc`
+    struct SsoFalso;
+
+    #[async_trait]
+    impl SsoService for SsoFalso {
+        async fn iniciar(&self) -> Result<reservas_ports::r#in::sso_service::InicioSso, String> {
+            Err("no soportado".into())
+        }
+
+        async fn completar(&self, _estado: &str, _codigo: &str) -> Result<Actor, String> {
+            Err("no soportado".into())
+        }
+
+        async fn autenticar_token(&self, token: &str) -> Result<Actor, String> {
+            match token {
+                "token-oidc" => Ok(Actor::new(
+                    "ana@empresa.com",
+                    Some("emp-ana".to_string()),
+                    vec![Rol::Empleado],
+                )),
+                _ => Err("Token no reconocido".to_string()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_access_token_oidc() {
+        let config = ConfigAuth::new()
+            .con_clave_api("ci", "clave")
+            .con_oidc(Arc::new(SsoFalso));
+        assert!(config.tiene_credenciales());
+
+        let principal = config
+            .autenticar_con_oidc(&bearer("token-oidc"))
+            .await
+            .unwrap();
+        assert_eq!(principal.metodo, MetodoAuth::Oidc);
+        assert_eq!(principal.sujeto, "ana@empresa.com");
+        assert_eq!(principal.empleado_id.as_deref(), Some("emp-ana"));
+
+        // Las claves de API se siguen resolviendo sin consultar al proveedor
+        let principal = config.autenticar_con_oidc(&bearer("clave")).await.unwrap();
+        assert_eq!(principal.metodo, MetodoAuth::ApiKey);
+        assert!(config.autenticar_con_oidc(&bearer("otro")).await.is_err());
+        assert!(config.autenticar_con_oidc(&HeaderMap::new()).await.is_err());
+    }
+
+    #[test]
+    fn test_sin_credenciales_rechaza_y_desactivada_admite() {
+        let config = ConfigAuth::new();
+        assert!(!config.tiene_credenciales());
+        assert!(config.autenticar(&bearer("lo-que-sea")).is_err());
+
+        let principal = ConfigAuth::desactivada()
+            .autenticar(&HeaderMap::new())
+            .unwrap();
+        assert_eq!(principal.sujeto, "anonimo");
+    }
+}
@@ -15,6 +15,27 @@ pub struct CrearEmpleadoRequest {
     pub email: String,
 }
 
+/// Sustituye todos los datos editables del empleado (PUT)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ActualizarEmpleadoRequest {
+    #[schema(example = "Juan López")]
+    pub nombre: String,
+    #[schema(example = "juan@empresa.com")]
+    pub email: String,
+    /// Responsable que confirma sus reservas (sin él, nadie salvo un admin)
+    #[serde(default)]
+    pub responsable_id: Option<String>,
+}
+
+/// Cambia solo los campos presentes (PATCH)
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ModificarEmpleadoRequest {
+    pub nombre: Option<String>,
+    pub email: Option<String>,
+    /// ID del nuevo responsable; `""` lo quita
+    pub responsable_id: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct EmpleadoResponse {
     #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
@@ -24,6 +45,7 @@ pub struct EmpleadoResponse {
     #[schema(example = "yo@devel0pez.com")]
     pub email: String,
     pub activo: bool,
+    pub responsable_id: Option<String>,
 }
 
 // ============= DTOs para Reservas =============
@@ -37,6 +59,30 @@ pub struct CrearReservaRequest {
     pub inicio_slot: DateTime<Utc>,
     #[schema(example = "Reunión con cliente importante")]
     pub descripcion: String,
+    /// Sala a reservar junto con el slot (opcional)
+    #[serde(default)]
+    pub sala_id: Option<String>,
+}
+
+/// Sustituye los datos editables de la reserva (PUT)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ActualizarReservaRequest {
+    #[schema(example = "Reunión con cliente importante")]
+    pub descripcion: String,
+}
+
+/// Cambia solo los campos presentes (PATCH)
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ModificarReservaRequest {
+    pub descripcion: Option<String>,
+}
+
+/// Nuevo slot de una reserva existente
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReprogramarReservaRequest {
+    /// Fecha y hora de inicio del nuevo slot en formato ISO 8601
+    #[schema(example = "2025-11-26T15:00:00Z")]
+    pub inicio_slot: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -48,6 +94,7 @@ pub struct ReservaResponse {
     pub descripcion: String,
     #[schema(example = "pendiente")]
     pub estado: String,
+    pub sala_id: Option<String>,
 }
 
 // ============= DTOs para Disponibilidad =============
@@ -70,6 +117,16 @@ pub struct DisponibilidadEmpleadoResponse {
     pub descripcion_reserva: Option<String>,
 }
 
+/// Si una sala está libre en un slot y, si no, qué reserva la ocupa
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DisponibilidadSalaResponse {
+    pub sala_id: String,
+    pub slot_inicio: DateTime<Utc>,
+    pub slot_fin: DateTime<Utc>,
+    pub disponible: bool,
+    pub reserva_id: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct TablaDisponibilidadResponse {
     #[schema(example = "2025-11-25")]
@@ -85,12 +142,81 @@ pub struct SlotInfo {
     pub hora: u32,
 }
 
+/// Disponibilidad de varios días en forma de matriz: una fila por empleado o
+/// sala y, por cada día, una cadena con un carácter por hora de `horas`:
+/// `.` libre, `P` reserva pendiente y `C` reserva confirmada
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MatrizDisponibilidadResponse {
+    #[schema(example = "2025-11-24")]
+    pub desde: String,
+    #[schema(example = "2025-11-30")]
+    pub hasta: String,
+    /// Días del periodo (YYYY-MM-DD), en el orden de las cadenas de cada fila
+    pub dias: Vec<String>,
+    /// Hora de inicio (UTC) de cada slot del día
+    #[schema(example = json!([9, 10, 11, 12, 13, 14, 15, 16, 17]))]
+    pub horas: Vec<u32>,
+    pub filas: Vec<FilaMatrizResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FilaMatrizResponse {
+    /// empleado o sala
+    #[schema(example = "empleado")]
+    pub tipo: String,
+    pub id: String,
+    pub nombre: String,
+    /// Slots libres en todo el periodo
+    pub libres: usize,
+    /// Una cadena por día
+    #[schema(example = json!([".PC......", "........."]))]
+    pub ocupacion: Vec<String>,
+}
+
+/// Slots cuya disponibilidad ha cambiado por un cambio en una reserva
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ActualizacionDisponibilidadResponse {
+    /// creada | confirmada | cancelada | modificada | reprogramada | reasignada | eliminada
+    #[schema(example = "cancelada")]
+    pub cambio: String,
+    pub reserva_id: String,
+    pub sala_id: Option<String>,
+    /// Estado actual de los slots afectados para sus titulares; vacío si la
+    /// suscripción filtra por sala
+    pub slots: Vec<DisponibilidadEmpleadoResponse>,
+    /// Con filtro de sala, estado de la sala en los slots afectados
+    pub salas: Vec<DisponibilidadSalaResponse>,
+}
+
+/// Mensaje de `/disponibilidad/ws`. Con `resincronizar` se han perdido
+/// cambios y hay que volver a pedir la tabla completa.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "evento", rename_all = "lowercase")]
+pub enum EventoDisponibilidadResponse {
+    Disponibilidad(ActualizacionDisponibilidadResponse),
+    Resincronizar,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CrearSalaRequest {
     pub nombre: String,
     pub capacidad: u32,
 }
 
+/// Sustituye los datos editables de la sala (PUT)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ActualizarSalaRequest {
+    pub nombre: String,
+    pub capacidad: u32,
+}
+
+/// Cambia solo los campos presentes (PATCH)
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ModificarSalaRequest {
+    pub nombre: Option<String>,
+    pub capacidad: Option<u32>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SalaResponse {
     pub id: String,
@@ -99,14 +225,343 @@ pub struct SalaResponse {
     pub activa: bool,
 }
 
-// ============= DTOs genéricos =============
+// ============= DTOs de paginación =============
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PaginaReservasResponse {
+    pub elementos: Vec<ReservaResponse>,
+    /// Cursor para pedir la siguiente página (`null` si no hay más)
+    pub siguiente_cursor: Option<String>,
+    /// Enlace a la siguiente página con los mismos filtros
+    #[schema(example = "/api/reservas?estado=pendiente&cursor=123e4567")]
+    pub siguiente: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PaginaEmpleadosResponse {
+    pub elementos: Vec<EmpleadoResponse>,
+    pub siguiente_cursor: Option<String>,
+    pub siguiente: Option<String>,
+}
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
-pub struct ErrorResponse {
-    pub error: String,
+pub struct PaginaSalasResponse {
+    pub elementos: Vec<SalaResponse>,
+    pub siguiente_cursor: Option<String>,
+    pub siguiente: Option<String>,
+}
+
+// ============= DTOs genéricos =============
+
+/// Error de la API según RFC 7807 (`application/problem+json`)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProblemaResponse {
+    /// URN que identifica el tipo de problema
+    #[serde(rename = "type")]
+    #[schema(example = "urn:reservas:problema:validacion")]
+    pub tipo: String,
+    /// Resumen del tipo de problema, igual para todas sus apariciones
+    #[serde(rename = "title")]
+    #[schema(example = "Datos inválidos")]
+    pub titulo: String,
+    /// Código de estado HTTP
+    #[serde(rename = "status")]
+    #[schema(example = 400)]
+    pub estado: u16,
+    /// Explicación de esta aparición concreta
+    #[serde(rename = "detail")]
+    #[schema(example = "Datos inválidos: email: El email juan no es válido")]
+    pub detalle: String,
+    /// Ruta de la petición que falló
+    #[serde(rename = "instance", default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "/api/empleados")]
+    pub instancia: Option<String>,
+    /// Errores por campo (solo en los problemas de validación)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub campos: Vec<ErrorCampoResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErrorCampoResponse {
+    #[schema(example = "email")]
+    pub campo: String,
+    #[schema(example = "El email juan no es válido")]
+    pub mensaje: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct MensajeResponse {
     pub mensaje: String,
 }
+
+/// Problema encontrado por el verificador de integridad
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IncidenciaResponse {
+    /// empleado_inexistente, sala_inexistente, solapamiento, empleado_inactivo,
+    /// sala_inactiva o fuera_de_horario
+    pub tipo: String,
+    pub reserva_id: String,
+    pub detalle: String,
+    pub reparada: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InformeIntegridadResponse {
+    pub reservas_revisadas: usize,
+    pub consistente: bool,
+    pub reparadas: usize,
+    pub incidencias: Vec<IncidenciaResponse>,
+}
+
+/// Operación rechazada por falta de permisos
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IntentoDenegadoResponse {
+    pub fecha: DateTime<Utc>,
+    /// Principal que lo intentó
+    pub sujeto: String,
+    #[schema(example = "cancelar reserva")]
+    pub accion: String,
+    #[schema(example = "reserva:123e4567-e89b-12d3-a456-426614174000")]
+    pub recurso: String,
+}
+
+/// Peticiones rechazadas por una regla de límite
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReglaLimiteResponse {
+    #[schema(example = "GET /disponibilidad")]
+    pub regla: String,
+    pub rechazadas: u64,
+}
+
+/// Contadores del límite de peticiones desde que arrancó el servidor
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MetricasLimitesResponse {
+    pub admitidas: u64,
+    pub rechazadas: u64,
+    pub reglas: Vec<ReglaLimiteResponse>,
+}
+
+/// Entidades restauradas desde un respaldo
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ResumenRestauracionResponse {
+    pub empleados: usize,
+    pub salas: usize,
+    pub reservas: usize,
+    /// `true` si solo se validó, sin escribir
+    pub simulado: bool,
+}
+
+// ============= DTOs para Calendarios =============
+
+/// URL privada de un calendario iCalendar
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CalendarioResponse {
+    /// URL para suscribirse desde el cliente de calendario
+    #[schema(example = "http://localhost:3000/api/empleados/emp-001/calendario.ics?token=3f2a...")]
+    pub url: String,
+    pub token: String,
+}
+
+// ============= DTOs para Importación =============
+
+/// Un evento entero o un asistente en uno de sus slots
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ElementoImportacionResponse {
+    /// UID del VEVENT
+    pub uid: String,
+    pub email: Option<String>,
+    pub empleado_id: Option<String>,
+    pub slot_inicio: Option<DateTime<Utc>>,
+    /// importada, conflicto u omitida
+    #[schema(example = "importada")]
+    pub resultado: String,
+    /// Motivo del conflicto o de la omisión
+    pub detalle: Option<String>,
+    pub reserva_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InformeImportacionResponse {
+    /// VEVENTs leídos del fichero
+    pub eventos: usize,
+    pub importadas: usize,
+    pub conflictos: usize,
+    pub omitidas: usize,
+    /// `true` si solo se revisó, sin crear reservas
+    pub simulado: bool,
+    pub elementos: Vec<ElementoImportacionResponse>,
+}
+
+// ============= DTOs para Cargas masivas =============
+
+/// Fichero de una carga masiva
+#[derive(Debug, ToSchema)]
+#[allow(dead_code)]
+pub struct CargaMasivaForm {
+    /// Hoja CSV o XLSX; la primera fila son los nombres de columna
+    #[schema(value_type = String, format = Binary)]
+    pub fichero: Vec<u8>,
+    /// Columna de un campo, como `campo=Columna` (repetible). Sin él, cada
+    /// campo se busca en la columna con su nombre.
+    #[schema(example = json!(["nombre=Nombre completo", "email=Correo"]))]
+    pub columna: Vec<String>,
+}
+
+/// Una fila de la hoja
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FilaCargaResponse {
+    /// Número de fila en la hoja (la cabecera es la 1)
+    pub fila: usize,
+    /// Email del empleado o nombre de la sala
+    pub clave: String,
+    /// creada, actualizada, sin_cambios o error
+    #[schema(example = "creada")]
+    pub resultado: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errores: Vec<ErrorCampoResponse>,
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InformeCargaResponse {
+    pub creadas: usize,
+    pub actualizadas: usize,
+    pub sin_cambios: usize,
+    pub errores: usize,
+    /// `true` si solo se revisó, sin escribir nada
+    pub simulado: bool,
+    pub filas: Vec<FilaCargaResponse>,
+}
+
+// ============= DTOs para Informes =============
+
+/// Ocupación de un periodo. Las ocupaciones y tasas son fracciones entre 0 y
+/// 1; una reserva no presentada es una que terminó sin confirmarse.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InformeOcupacionResponse {
+    #[schema(example = "2025-11-01")]
+    pub desde: String,
+    #[schema(example = "2025-11-30")]
+    pub hasta: String,
+    /// Reservas del periodo, también las canceladas
+    pub total: usize,
+    pub pendientes: usize,
+    pub confirmadas: usize,
+    pub canceladas: usize,
+    pub no_presentadas: usize,
+    /// Canceladas sobre el total
+    #[schema(example = 0.12)]
+    pub tasa_cancelacion: f64,
+    /// No presentadas sobre las reservas activas ya terminadas
+    #[schema(example = 0.05)]
+    pub tasa_no_presentacion: f64,
+    /// De más a menos ocupado
+    pub por_empleado: Vec<UsoRecursoResponse>,
+    /// De más a menos ocupada
+    pub por_sala: Vec<UsoRecursoResponse>,
+    /// Por hora de inicio, sumando todos los días
+    pub por_hora: Vec<UsoHoraResponse>,
+    /// En orden cronológico
+    pub por_dia: Vec<UsoDiaResponse>,
+    /// Los días con más reservas, de más a menos
+    pub dias_mas_ocupados: Vec<UsoDiaResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UsoRecursoResponse {
+    pub id: String,
+    pub nombre: String,
+    /// Reservas activas
+    pub reservas: usize,
+    pub canceladas: usize,
+    /// Slots laborales del periodo ocupados
+    #[schema(example = 0.35)]
+    pub ocupacion: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UsoHoraResponse {
+    #[schema(example = 10)]
+    pub hora: u32,
+    pub reservas: usize,
+    /// Sobre los empleados activos en todos los días del periodo
+    pub ocupacion: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UsoDiaResponse {
+    #[schema(example = "2025-11-25")]
+    pub fecha: String,
+    pub reservas: usize,
+    /// Sobre los empleados activos en todas las horas del día
+    pub ocupacion: f64,
+}
+
+// ============= DTOs para Webhooks =============
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CrearWebhookRequest {
+    /// URL http(s) que recibirá los eventos por POST
+    #[schema(example = "https://rrhh.empresa.com/hooks/reservas")]
+    pub url: String,
+    /// Eventos a enviar; sin ellos, todos
+    #[schema(example = json!(["reserva.creada", "reserva.cancelada"]))]
+    pub eventos: Option<Vec<String>>,
+    /// Clave de la firma (mínimo 16 caracteres); si no se da, se genera
+    pub secreto: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookResponse {
+    pub id: String,
+    pub url: String,
+    /// Vacío = todos los eventos
+    pub eventos: Vec<String>,
+    /// Solo en la respuesta de alta
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secreto: Option<String>,
+    pub creado: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IntentoEntregaResponse {
+    pub fecha: DateTime<Utc>,
+    /// Código HTTP de la respuesta del receptor, si la hubo
+    pub codigo: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// Envío de un evento a un webhook
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EntregaResponse {
+    pub id: String,
+    pub webhook_id: String,
+    #[schema(example = "reserva.creada")]
+    pub evento: String,
+    /// pendiente | entregada | fallida
+    pub estado: String,
+    /// Cuerpo JSON enviado
+    pub cuerpo: String,
+    pub intentos: Vec<IntentoEntregaResponse>,
+    pub creada: DateTime<Utc>,
+}
+
+// ============= DTOs de salud =============
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ComponenteResponse {
+    #[schema(example = "reservas")]
+    pub nombre: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SaludResponse {
+    /// ok | preparado | no_preparado
+    pub estado: String,
+    /// Solo en `/ready`
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub componentes: Vec<ComponenteResponse>,
+}
@@ -0,0 +1,219 @@
+use super::respuestas::ErrorHttp;
+use crate::auth::Principal;
+use crate::dtos::{
+    InformeIntegridadResponse, IntentoDenegadoResponse, MetricasLimitesResponse, ProblemaResponse,
+    ReglaLimiteResponse, ResumenRestauracionResponse,
+};
+use crate::limites::MetricasLimites;
+use crate::respaldo;
+use axum::{
+    extract::Query,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use reservas_domain::{Accion, Actor};
+use reservas_ports::r#in::auditoria_service::AuditoriaService;
+use reservas_ports::r#in::integridad_service::IntegridadService;
+use reservas_ports::r#in::respaldo_service::RespaldoService;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Tipo MIME de JSON Lines
+const CONTENT_TYPE_JSONL: &str = "application/x-ndjson";
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct RestaurarQuery {
+    /// Solo valida el respaldo, sin escribir nada
+    #[serde(default)]
+    pub simular: bool,
+}
+
+/// Descargar un respaldo completo (JSON Lines versionado)
+#[utoipa::path(
+    get,
+    path = "/admin/respaldo",
+    responses(
+        (status = 200, description = "Respaldo de empleados, salas y reservas", body = String, content_type = "application/x-ndjson"),
+        (status = 500, description = "Error leyendo los repositorios", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Administración"
+)]
+pub async fn exportar_respaldo(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn RespaldoService>>,
+) -> Response {
+    match service.exportar(&principal.actor()).await {
+        Ok(datos) => {
+            let nombre = format!(
+                "attachment; filename=\"reservas-{}.jsonl\"",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S")
+            );
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, CONTENT_TYPE_JSONL.to_string()),
+                    (header::CONTENT_DISPOSITION, nombre),
+                ],
+                respaldo::serializar(&datos),
+            )
+                .into_response()
+        }
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Restaurar un respaldo. Crea lo que falta y sobrescribe lo existente.
+#[utoipa::path(
+    post,
+    path = "/admin/respaldo",
+    params(RestaurarQuery),
+    request_body(content = String, content_type = "application/x-ndjson"),
+    responses(
+        (status = 200, description = "Respaldo restaurado (o validado si se simula)", body = ResumenRestauracionResponse),
+        (status = 400, description = "Fichero ilegible o sin integridad referencial", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 500, description = "Error escribiendo en los repositorios (la restauración se deshace)", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Administración"
+)]
+pub async fn restaurar_respaldo(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn RespaldoService>>,
+    Query(params): Query<RestaurarQuery>,
+    contenido: String,
+) -> Response {
+    tracing::info!(
+        "{} restaura un respaldo (simular={})",
+        principal.sujeto,
+        params.simular
+    );
+    let datos = match respaldo::deserializar(&contenido) {
+        Ok(datos) => datos,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    match service
+        .restaurar(&principal.actor(), datos, params.simular)
+        .await
+    {
+        Ok(resumen) => (
+            StatusCode::OK,
+            Json(ResumenRestauracionResponse {
+                empleados: resumen.empleados,
+                salas: resumen.salas,
+                reservas: resumen.reservas,
+                simulado: params.simular,
+            }),
+        )
+            .into_response(),
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Verificar la integridad de los datos sin modificar nada
+#[utoipa::path(
+    get,
+    path = "/admin/integridad",
+    responses(
+        (status = 200, description = "Informe de integridad", body = InformeIntegridadResponse),
+        (status = 500, description = "Error leyendo los repositorios", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Administración"
+)]
+pub async fn verificar_integridad(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn IntegridadService>>,
+) -> Response {
+    informe_integridad(service.as_ref(), &principal.actor(), false).await
+}
+
+/// Verificar la integridad y cancelar las reservas activas afectadas
+#[utoipa::path(
+    post,
+    path = "/admin/integridad/reparar",
+    responses(
+        (status = 200, description = "Informe con las incidencias reparadas", body = InformeIntegridadResponse),
+        (status = 500, description = "Error accediendo a los repositorios", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Administración"
+)]
+pub async fn reparar_integridad(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn IntegridadService>>,
+) -> Response {
+    tracing::info!("{} repara la integridad de los datos", principal.sujeto);
+    informe_integridad(service.as_ref(), &principal.actor(), true).await
+}
+
+async fn informe_integridad(
+    service: &dyn IntegridadService,
+    actor: &Actor,
+    reparar: bool,
+) -> Response {
+    match service.verificar(actor, reparar).await {
+        Ok(informe) => (
+            StatusCode::OK,
+            Json(InformeIntegridadResponse::from(informe)),
+        )
+            .into_response(),
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Intentos de operación denegados por falta de permisos, del más antiguo al
+/// más reciente
+#[utoipa::path(
+    get,
+    path = "/admin/auditoria",
+    responses(
+        (status = 200, description = "Intentos denegados", body = [IntentoDenegadoResponse]),
+        (status = 500, description = "Error leyendo el registro", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Administración"
+)]
+pub async fn listar_auditoria(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn AuditoriaService>>,
+) -> Response {
+    match service.listar_denegados(&principal.actor()).await {
+        Ok(intentos) => {
+            let response: Vec<IntentoDenegadoResponse> =
+                intentos.into_iter().map(|i| i.into()).collect();
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Peticiones admitidas y rechazadas por el límite de peticiones
+#[utoipa::path(
+    get,
+    path = "/admin/limites",
+    responses(
+        (status = 200, description = "Contadores del límite de peticiones", body = MetricasLimitesResponse)
+    ),
+    tag = "Administración"
+)]
+pub async fn metricas_limites(
+    principal: Principal,
+    Extension(metricas): Extension<Arc<MetricasLimites>>,
+) -> Response {
+    // Son métricas del propio adaptador HTTP: no hay caso de uso que decida
+    if !principal.actor().puede(Accion::Administrar, None) {
+        return ProblemaResponse::new(
+            StatusCode::FORBIDDEN,
+            format!("{} no puede consultar las métricas", principal.sujeto),
+        )
+        .into_response();
+    }
+    let response = MetricasLimitesResponse {
+        admitidas: metricas.admitidas(),
+        rechazadas: metricas.rechazadas(),
+        reglas: metricas
+            .rechazadas_por_regla()
+            .into_iter()
+            .map(|(regla, rechazadas)| ReglaLimiteResponse { regla, rechazadas })
+            .collect(),
+    };
+    (StatusCode::OK, Json(response)).into_response()
+}
@@ -0,0 +1,244 @@
+// Handlers de los calendarios iCalendar por empleado y por sala
+
+use super::respuestas::ErrorHttp;
+use crate::auth::Principal;
+use crate::dtos::{CalendarioResponse, ProblemaResponse};
+use axum::{
+    extract::{OriginalUri, Path, Query},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use ical::CONTENT_TYPE_ICS;
+use reservas_ports::r#in::calendario_service::{Calendario, CalendarioService};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct FeedQuery {
+    /// Token de la URL privada del calendario
+    pub token: String,
+}
+
+fn no_encontrado(calendario: &Calendario) -> Response {
+    let detalle = match calendario {
+        Calendario::Empleado(id) => format!("Calendario del empleado {} no encontrado", id),
+        Calendario::Sala(id) => format!("Calendario de la sala {} no encontrado", id),
+    };
+    ProblemaResponse::new(StatusCode::NOT_FOUND, detalle).into_response()
+}
+
+/// URL absoluta si se conoce el host; los clientes de calendario no admiten
+/// rutas relativas
+fn url_feed(cabeceras: &HeaderMap, ruta: &str, token: &str) -> String {
+    let ruta = format!("{}.ics?token={}", ruta, token);
+    let valor = |nombre: &str| cabeceras.get(nombre).and_then(|v| v.to_str().ok());
+    match valor(header::HOST.as_str()) {
+        Some(host) => {
+            let esquema = valor("x-forwarded-proto").unwrap_or("http");
+            format!("{}://{}{}", esquema, host, ruta)
+        }
+        None => ruta,
+    }
+}
+
+/// URL privada del calendario; con `revocar`, una nueva que invalida la anterior
+async fn url_privada(
+    principal: Principal,
+    service: Arc<dyn CalendarioService>,
+    calendario: Calendario,
+    cabeceras: HeaderMap,
+    ruta: &str,
+    revocar: bool,
+) -> Response {
+    let actor = principal.actor();
+    let token = if revocar {
+        service.revocar(&actor, &calendario).await
+    } else {
+        service.token(&actor, &calendario).await
+    };
+    match token {
+        Ok(Some(token)) => {
+            let response = CalendarioResponse {
+                url: url_feed(&cabeceras, ruta, &token),
+                token,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Ok(None) => no_encontrado(&calendario),
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn feed(
+    service: Arc<dyn CalendarioService>,
+    calendario: Calendario,
+    token: &str,
+) -> Response {
+    match service.feed(&calendario, token).await {
+        Ok(Some(feed)) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, CONTENT_TYPE_ICS),
+                (header::CACHE_CONTROL, "private, max-age=300"),
+            ],
+            ical::serializar(&feed, chrono::Utc::now()),
+        )
+            .into_response(),
+        // Un token incorrecto no revela si el calendario existe
+        Ok(None) => no_encontrado(&calendario),
+        Err(e) => ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+/// URL privada para suscribirse al calendario de un empleado. Cada empleado
+/// obtiene solo la suya.
+#[utoipa::path(
+    get,
+    path = "/empleados/{id}/calendario",
+    params(
+        ("id" = String, Path, description = "ID del empleado")
+    ),
+    responses(
+        (status = 200, description = "URL privada del calendario", body = CalendarioResponse),
+        (status = 403, description = "Es el calendario de otro empleado", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Empleado no encontrado", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Calendarios"
+)]
+pub async fn calendario_empleado(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn CalendarioService>>,
+    OriginalUri(uri): OriginalUri,
+    cabeceras: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    let calendario = Calendario::Empleado(id);
+    url_privada(principal, service, calendario, cabeceras, uri.path(), false).await
+}
+
+/// Revocar la URL privada del calendario de un empleado, por ejemplo si se ha
+/// filtrado. Devuelve la nueva; las de los demás calendarios siguen valiendo.
+#[utoipa::path(
+    post,
+    path = "/empleados/{id}/calendario/revocar",
+    params(
+        ("id" = String, Path, description = "ID del empleado")
+    ),
+    responses(
+        (status = 200, description = "Nueva URL privada del calendario", body = CalendarioResponse),
+        (status = 403, description = "Es el calendario de otro empleado", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Empleado no encontrado", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Calendarios"
+)]
+pub async fn revocar_calendario_empleado(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn CalendarioService>>,
+    OriginalUri(uri): OriginalUri,
+    cabeceras: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    let calendario = Calendario::Empleado(id);
+    let ruta = uri.path().trim_end_matches("/revocar");
+    url_privada(principal, service, calendario, cabeceras, ruta, true).await
+}
+
+/// URL privada para suscribirse al calendario de una sala
+#[utoipa::path(
+    get,
+    path = "/salas/{id}/calendario",
+    params(
+        ("id" = String, Path, description = "ID de la sala")
+    ),
+    responses(
+        (status = 200, description = "URL privada del calendario", body = CalendarioResponse),
+        (status = 404, description = "Sala no encontrada", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Calendarios"
+)]
+pub async fn calendario_sala(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn CalendarioService>>,
+    OriginalUri(uri): OriginalUri,
+    cabeceras: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    let calendario = Calendario::Sala(id);
+    url_privada(principal, service, calendario, cabeceras, uri.path(), false).await
+}
+
+/// Revocar la URL privada del calendario de una sala. Afecta a todos sus
+/// suscriptores, así que requiere poder gestionar salas.
+#[utoipa::path(
+    post,
+    path = "/salas/{id}/calendario/revocar",
+    params(
+        ("id" = String, Path, description = "ID de la sala")
+    ),
+    responses(
+        (status = 200, description = "Nueva URL privada del calendario", body = CalendarioResponse),
+        (status = 403, description = "Sin permiso para gestionar salas", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Sala no encontrada", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Calendarios"
+)]
+pub async fn revocar_calendario_sala(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn CalendarioService>>,
+    OriginalUri(uri): OriginalUri,
+    cabeceras: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    let calendario = Calendario::Sala(id);
+    let ruta = uri.path().trim_end_matches("/revocar");
+    url_privada(principal, service, calendario, cabeceras, ruta, true).await
+}
+
+/// Calendario iCalendar de un empleado. Se autentica con el token de su URL
+/// privada, sin credenciales.
+#[utoipa::path(
+    get,
+    path = "/empleados/{id}/calendario.ics",
+    params(
+        ("id" = String, Path, description = "ID del empleado"),
+        FeedQuery
+    ),
+    responses(
+        (status = 200, description = "Reservas del empleado como VEVENTs", body = String, content_type = "text/calendar"),
+        (status = 404, description = "Empleado no encontrado o token incorrecto", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    security(()),
+    tag = "Calendarios"
+)]
+pub async fn feed_empleado(
+    Extension(service): Extension<Arc<dyn CalendarioService>>,
+    Path(id): Path<String>,
+    Query(params): Query<FeedQuery>,
+) -> Response {
+    feed(service, Calendario::Empleado(id), &params.token).await
+}
+
+/// Calendario iCalendar de una sala. Se autentica con el token de su URL
+/// privada, sin credenciales.
+#[utoipa::path(
+    get,
+    path = "/salas/{id}/calendario.ics",
+    params(
+        ("id" = String, Path, description = "ID de la sala"),
+        FeedQuery
+    ),
+    responses(
+        (status = 200, description = "Reservas de la sala como VEVENTs", body = String, content_type = "text/calendar"),
+        (status = 404, description = "Sala no encontrada o token incorrecto", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    security(()),
+    tag = "Calendarios"
+)]
+pub async fn feed_sala(
+    Extension(service): Extension<Arc<dyn CalendarioService>>,
+    Path(id): Path<String>,
+    Query(params): Query<FeedQuery>,
+) -> Response {
+    feed(service, Calendario::Sala(id), &params.token).await
+}
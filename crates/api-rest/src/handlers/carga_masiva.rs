@@ -0,0 +1,266 @@
+// Handlers de las cargas masivas y exportaciones en CSV o XLSX
+
+use super::respuestas::ErrorHttp;
+use crate::auth::Principal;
+use crate::dtos::{CargaMasivaForm, InformeCargaResponse, ProblemaResponse};
+use axum::{
+    extract::{Multipart, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use hojas_calculo::Formato;
+use reservas_ports::r#in::carga_masiva_service::{CargaMasivaService, Entidad, Mapeo};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ImportarHojaQuery {
+    /// Solo informa de lo que se haría, sin escribir nada
+    #[serde(default)]
+    pub simular: bool,
+    /// csv o xlsx; por defecto, según la extensión del fichero
+    pub formato: Option<String>,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ExportarQuery {
+    /// csv (por defecto) o xlsx
+    pub formato: Option<String>,
+}
+
+/// Fichero y mapeo de columnas de un formulario multipart
+struct Carga {
+    datos: Vec<u8>,
+    nombre: Option<String>,
+    mapeo: Mapeo,
+}
+
+async fn leer_carga(mut multipart: Multipart) -> Result<Carga, String> {
+    let mut fichero = None;
+    let mut mapeo = Mapeo::new();
+    while let Some(campo) = multipart
+        .next_field()
+        .await
+        .map_err(|e| format!("Formulario no válido: {}", e))?
+    {
+        match campo.name() {
+            Some("fichero") => {
+                let nombre = campo.file_name().map(str::to_string);
+                let datos = campo
+                    .bytes()
+                    .await
+                    .map_err(|e| format!("Formulario no válido: {}", e))?;
+                fichero = Some((datos.to_vec(), nombre));
+            }
+            Some("columna") => {
+                let valor = campo
+                    .text()
+                    .await
+                    .map_err(|e| format!("Formulario no válido: {}", e))?;
+                let (nombre, columna) = valor
+                    .split_once('=')
+                    .ok_or_else(|| format!("Columna no válida: {} (use campo=Columna)", valor))?;
+                mapeo.insert(nombre.trim().to_lowercase(), columna.trim().to_string());
+            }
+            _ => {}
+        }
+    }
+    let (datos, nombre) = fichero.ok_or_else(|| "Falta el fichero".to_string())?;
+    Ok(Carga {
+        datos,
+        nombre,
+        mapeo,
+    })
+}
+
+fn formato_de(valor: Option<&str>, fichero: Option<&str>) -> Result<Formato, String> {
+    match valor.filter(|v| !v.trim().is_empty()) {
+        Some(valor) => valor.parse(),
+        None => Ok(fichero
+            .and_then(Formato::desde_nombre)
+            .unwrap_or(Formato::Csv)),
+    }
+}
+
+async fn importar(
+    principal: Principal,
+    service: Arc<dyn CargaMasivaService>,
+    entidad: Entidad,
+    params: ImportarHojaQuery,
+    multipart: Multipart,
+) -> Response {
+    let (tabla, mapeo) = match leer_carga(multipart).await.and_then(|carga| {
+        let formato = formato_de(params.formato.as_deref(), carga.nombre.as_deref())?;
+        hojas_calculo::leer(&carga.datos, formato).map(|tabla| (tabla, carga.mapeo))
+    }) {
+        Ok(carga) => carga,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    tracing::info!(
+        "{} carga {} filas de {:?} (simular={})",
+        principal.sujeto,
+        tabla.filas.len(),
+        entidad,
+        params.simular
+    );
+
+    match service
+        .importar(&principal.actor(), entidad, tabla, &mapeo, params.simular)
+        .await
+    {
+        Ok(informe) => (
+            StatusCode::OK,
+            Json(InformeCargaResponse::desde(informe, params.simular)),
+        )
+            .into_response(),
+        Err(e) => e.en_respuesta(StatusCode::BAD_REQUEST),
+    }
+}
+
+async fn exportar(
+    service: Arc<dyn CargaMasivaService>,
+    entidad: Entidad,
+    nombre: &str,
+    params: ExportarQuery,
+) -> Response {
+    let formato = match formato_de(params.formato.as_deref(), None) {
+        Ok(formato) => formato,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let contenido = match service.exportar(entidad).await {
+        Ok(tabla) => hojas_calculo::escribir(&tabla, formato),
+        Err(e) => Err(e),
+    };
+    match contenido {
+        Ok(contenido) => {
+            let fichero = format!(
+                "attachment; filename=\"{}-{}.{}\"",
+                nombre,
+                chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+                formato.extension()
+            );
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, formato.content_type().to_string()),
+                    (header::CONTENT_DISPOSITION, fichero),
+                ],
+                contenido,
+            )
+                .into_response()
+        }
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Crear o actualizar empleados desde una hoja CSV o XLSX. Se identifican por
+/// email; el responsable se indica por su email. Las filas con errores no se
+/// aplican y se detallan en el informe.
+#[utoipa::path(
+    post,
+    path = "/empleados/importar",
+    params(ImportarHojaQuery),
+    request_body(content = CargaMasivaForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Informe fila a fila de la carga (o de la simulación)", body = InformeCargaResponse),
+        (status = 400, description = "Fichero ilegible o columnas que no cuadran", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 403, description = "Sin permiso para gestionar empleados", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Empleados"
+)]
+pub async fn importar_empleados(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn CargaMasivaService>>,
+    Query(params): Query<ImportarHojaQuery>,
+    multipart: Multipart,
+) -> Response {
+    importar(principal, service, Entidad::Empleados, params, multipart).await
+}
+
+/// Crear o actualizar salas desde una hoja CSV o XLSX. Se identifican por
+/// nombre. Las filas con errores no se aplican y se detallan en el informe.
+#[utoipa::path(
+    post,
+    path = "/salas/importar",
+    params(ImportarHojaQuery),
+    request_body(content = CargaMasivaForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Informe fila a fila de la carga (o de la simulación)", body = InformeCargaResponse),
+        (status = 400, description = "Fichero ilegible o columnas que no cuadran", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 403, description = "Sin permiso para gestionar salas", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Salas"
+)]
+pub async fn importar_salas(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn CargaMasivaService>>,
+    Query(params): Query<ImportarHojaQuery>,
+    multipart: Multipart,
+) -> Response {
+    importar(principal, service, Entidad::Salas, params, multipart).await
+}
+
+/// Exportar todos los empleados. El fichero se puede volver a importar.
+#[utoipa::path(
+    get,
+    path = "/empleados/exportar",
+    params(ExportarQuery),
+    responses(
+        (status = 200, description = "Hoja con los empleados", content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        )),
+        (status = 400, description = "Formato no admitido", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Empleados"
+)]
+pub async fn exportar_empleados(
+    Extension(service): Extension<Arc<dyn CargaMasivaService>>,
+    Query(params): Query<ExportarQuery>,
+) -> Response {
+    exportar(service, Entidad::Empleados, "empleados", params).await
+}
+
+/// Exportar todas las salas. El fichero se puede volver a importar.
+#[utoipa::path(
+    get,
+    path = "/salas/exportar",
+    params(ExportarQuery),
+    responses(
+        (status = 200, description = "Hoja con las salas", content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        )),
+        (status = 400, description = "Formato no admitido", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Salas"
+)]
+pub async fn exportar_salas(
+    Extension(service): Extension<Arc<dyn CargaMasivaService>>,
+    Query(params): Query<ExportarQuery>,
+) -> Response {
+    exportar(service, Entidad::Salas, "salas", params).await
+}
+
+/// Exportar todas las reservas, con el empleado y la sala de cada una. Las
+/// horas van en UTC.
+#[utoipa::path(
+    get,
+    path = "/reservas/exportar",
+    params(ExportarQuery),
+    responses(
+        (status = 200, description = "Hoja con las reservas", content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        )),
+        (status = 400, description = "Formato no admitido", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Reservas"
+)]
+pub async fn exportar_reservas(
+    Extension(service): Extension<Arc<dyn CargaMasivaService>>,
+    Query(params): Query<ExportarQuery>,
+) -> Response {
+    exportar(service, Entidad::Reservas, "reservas", params).await
+}
@@ -1,20 +1,36 @@
 // Handlers para endpoints de Disponibilidad
 
 use crate::dtos::{
-    DisponibilidadEmpleadoResponse, ErrorResponse, SlotInfo, TablaDisponibilidadResponse,
+    ActualizacionDisponibilidadResponse, DisponibilidadEmpleadoResponse,
+    EventoDisponibilidadResponse, MatrizDisponibilidadResponse, ProblemaResponse, SlotInfo,
+    TablaDisponibilidadResponse,
 };
 use axum::{
-    extract::Query,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query,
+    },
     http::StatusCode,
-    response::{IntoResponse, Response},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     Extension, Json,
 };
 use chrono::{Datelike, NaiveDate};
-use reservas_domain::{DisponibilidadService, Slot};
+use futures_util::{stream, SinkExt, StreamExt};
+use reservas_domain::{DisponibilidadService, Periodo, Slot};
+use reservas_ports::r#in::disponibilidad_en_vivo_service::{
+    DisponibilidadEnVivoService, EventoDisponibilidad, FiltroDisponibilidad, Suscripcion,
+};
 use reservas_ports::r#in::empleado_service::EmpleadoService;
+use reservas_ports::r#in::matriz_disponibilidad_service::{
+    ErrorMatriz, FiltroMatriz, MatrizDisponibilidadService,
+};
 use reservas_ports::r#in::reserva_service::ReservaService;
 use serde::Deserialize;
 
+use std::convert::Infallible;
 use std::sync::Arc;
 
 #[derive(Debug, Deserialize, utoipa::IntoParams)]
@@ -33,8 +49,8 @@ pub struct DisponibilidadQuery {
     ),
     responses(
         (status = 200, description = "Tabla de disponibilidad", body = TablaDisponibilidadResponse),
-        (status = 400, description = "Formato de fecha inválido", body = ErrorResponse),
-        (status = 500, description = "Error interno", body = ErrorResponse)
+        (status = 400, description = "Formato de fecha inválido", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 500, description = "Error interno", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Disponibilidad"
 )]
@@ -47,13 +63,11 @@ pub async fn obtener_disponibilidad(
     let fecha = match NaiveDate::parse_from_str(&params.fecha, "%Y-%m-%d") {
         Ok(f) => f,
         Err(_) => {
-            return (
+            return ProblemaResponse::new(
                 StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Formato de fecha inválido. Use YYYY-MM-DD".to_string(),
-                }),
+                "Formato de fecha inválido. Use YYYY-MM-DD",
             )
-                .into_response()
+            .into_response()
         }
     };
 
@@ -61,22 +75,14 @@ pub async fn obtener_disponibilidad(
     let empleados = match empleado_service.listar_empleados().await {
         Ok(e) => e,
         Err(err) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse { error: err }),
-            )
-                .into_response()
+            return ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, err).into_response()
         }
     };
 
     let reservas = match reserva_service.listar_reservas().await {
         Ok(r) => r,
         Err(err) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse { error: err }),
-            )
-                .into_response()
+            return ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, err).into_response()
         }
     };
 
@@ -91,19 +97,8 @@ pub async fn obtener_disponibilidad(
     // Convertir a DTOs
     let slots_info: Vec<SlotInfo> = tabla.slots.iter().map(|s| s.into()).collect();
 
-    let disponibilidad: Vec<DisponibilidadEmpleadoResponse> = tabla
-        .disponibilidad
-        .into_iter()
-        .map(|d| DisponibilidadEmpleadoResponse {
-            empleado_id: d.empleado_id,
-            empleado_nombre: d.empleado_nombre,
-            slot_inicio: d.slot.inicio,
-            slot_fin: d.slot.fin(),
-            disponible: d.disponible,
-            reserva_id: d.reserva_id,
-            descripcion_reserva: d.descripcion_reserva,
-        })
-        .collect();
+    let disponibilidad: Vec<DisponibilidadEmpleadoResponse> =
+        tabla.disponibilidad.into_iter().map(Into::into).collect();
 
     let response = TablaDisponibilidadResponse {
         fecha: params.fecha,
@@ -113,3 +108,256 @@ pub async fn obtener_disponibilidad(
 
     (StatusCode::OK, Json(response)).into_response()
 }
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct MatrizQuery {
+    /// Primer día (YYYY-MM-DD)
+    #[param(example = "2025-11-24")]
+    pub desde: Option<String>,
+    /// Último día incluido (YYYY-MM-DD); por defecto, `desde`
+    #[param(example = "2025-11-28")]
+    pub hasta: Option<String>,
+    /// Semana de lunes a domingo que contiene este día (YYYY-MM-DD)
+    #[param(example = "2025-11-26")]
+    pub semana: Option<String>,
+    /// Mes natural (YYYY-MM)
+    #[param(example = "2025-11")]
+    pub mes: Option<String>,
+    /// IDs de empleados separados por comas
+    pub empleados: Option<String>,
+    /// Equipo de un responsable: él y quienes le reportan
+    pub responsable_id: Option<String>,
+    /// IDs de salas separados por comas
+    pub salas: Option<String>,
+}
+
+fn no_vacio(valor: &Option<String>) -> Option<&str> {
+    valor.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn lista(valor: &Option<String>) -> Vec<&str> {
+    no_vacio(valor)
+        .map(|v| {
+            v.split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn leer_fecha(valor: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(valor, "%Y-%m-%d")
+        .map_err(|_| format!("Fecha inválida: {}. Use YYYY-MM-DD", valor))
+}
+
+/// Exactamente uno de `desde` (con o sin `hasta`), `semana` o `mes`.
+/// `rango` construye el periodo de `desde` a `hasta` con sus límites.
+pub(crate) fn leer_periodo(
+    desde: &Option<String>,
+    hasta: &Option<String>,
+    semana: &Option<String>,
+    mes: &Option<String>,
+    rango: fn(NaiveDate, NaiveDate) -> Result<Periodo, String>,
+) -> Result<Periodo, String> {
+    let hasta = no_vacio(hasta);
+    match (no_vacio(desde), no_vacio(semana), no_vacio(mes)) {
+        (Some(desde), None, None) => rango(leer_fecha(desde)?, leer_fecha(hasta.unwrap_or(desde))?),
+        (None, Some(semana), None) if hasta.is_none() => Ok(Periodo::semana(leer_fecha(semana)?)),
+        (None, None, Some(mes)) if hasta.is_none() => {
+            let primero = NaiveDate::parse_from_str(&format!("{}-01", mes), "%Y-%m-%d")
+                .map_err(|_| format!("Mes inválido: {}. Use YYYY-MM", mes))?;
+            Ok(Periodo::mes(primero))
+        }
+        (None, None, None) => Err("Indique desde (y hasta), semana o mes".to_string()),
+        _ => Err("Indique solo uno de desde/hasta, semana o mes".to_string()),
+    }
+}
+
+impl MatrizQuery {
+    fn periodo(&self) -> Result<Periodo, String> {
+        leer_periodo(
+            &self.desde,
+            &self.hasta,
+            &self.semana,
+            &self.mes,
+            Periodo::new,
+        )
+    }
+}
+
+/// Obtener la disponibilidad de un periodo como matriz de días y horas.
+/// Sin filtros incluye a todos los empleados activos; con filtros, solo a los
+/// empleados indicados, al equipo del responsable y a las salas indicadas.
+#[utoipa::path(
+    get,
+    path = "/disponibilidad/matriz",
+    params(MatrizQuery),
+    responses(
+        (status = 200, description = "Matriz de disponibilidad del periodo", body = MatrizDisponibilidadResponse),
+        (status = 400, description = "Periodo inválido o de más de 62 días", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Empleado, responsable o sala inexistente", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Disponibilidad"
+)]
+pub async fn matriz_disponibilidad(
+    Extension(service): Extension<Arc<dyn MatrizDisponibilidadService>>,
+    Query(params): Query<MatrizQuery>,
+) -> Response {
+    let periodo = match params.periodo() {
+        Ok(periodo) => periodo,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let filtro = FiltroMatriz {
+        empleados: lista(&params.empleados)
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        responsable_id: no_vacio(&params.responsable_id).map(String::from),
+        salas: lista(&params.salas).into_iter().map(String::from).collect(),
+    };
+    match service.matriz_disponibilidad(periodo, filtro).await {
+        Ok(matriz) => (
+            StatusCode::OK,
+            Json(MatrizDisponibilidadResponse::from(matriz)),
+        )
+            .into_response(),
+        Err(ErrorMatriz::NoEncontrado(e)) => {
+            ProblemaResponse::new(StatusCode::NOT_FOUND, e).into_response()
+        }
+        Err(ErrorMatriz::Otro(e)) => {
+            ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct SuscripcionQuery {
+    /// Solo slots de este día (YYYY-MM-DD)
+    #[param(example = "2025-11-25")]
+    pub fecha: Option<String>,
+    /// Solo reservas de este empleado
+    pub empleado_id: Option<String>,
+    /// Solo reservas de esta sala
+    pub sala_id: Option<String>,
+}
+
+impl SuscripcionQuery {
+    fn filtro(self) -> Result<FiltroDisponibilidad, String> {
+        let fecha = match self.fecha.filter(|f| !f.is_empty()) {
+            Some(fecha) => Some(
+                NaiveDate::parse_from_str(&fecha, "%Y-%m-%d")
+                    .map_err(|_| format!("Fecha inválida: {}. Use YYYY-MM-DD", fecha))?,
+            ),
+            None => None,
+        };
+        Ok(FiltroDisponibilidad {
+            fecha,
+            empleado_id: self.empleado_id.filter(|id| !id.is_empty()),
+            sala_id: self.sala_id.filter(|id| !id.is_empty()),
+        })
+    }
+}
+
+/// Evento SSE: `disponibilidad` con el cambio o `resincronizar`. Un evento
+/// sin datos no llega a los clientes, de ahí el `{}`.
+fn evento_sse(evento: EventoDisponibilidad) -> Event {
+    match evento {
+        EventoDisponibilidad::Actualizacion(actualizacion) => Event::default()
+            .event("disponibilidad")
+            .json_data(ActualizacionDisponibilidadResponse::from(actualizacion))
+            .expect("la actualización siempre se puede serializar"),
+        EventoDisponibilidad::Resincronizar => Event::default().event("resincronizar").data("{}"),
+    }
+}
+
+/// Recibir los cambios de disponibilidad en tiempo real (Server-Sent Events)
+///
+/// Cada vez que se crea, confirma, cancela, modifica, reprograma o borra una
+/// reserva que pasa el filtro se envía un evento `disponibilidad` con el
+/// estado actual de los slots afectados. El evento `resincronizar` indica
+/// que se han perdido cambios y hay que volver a pedir `/disponibilidad`.
+#[utoipa::path(
+    get,
+    path = "/disponibilidad/eventos",
+    params(
+        SuscripcionQuery
+    ),
+    responses(
+        (status = 200, description = "Flujo de eventos `disponibilidad` y `resincronizar`", body = ActualizacionDisponibilidadResponse, content_type = "text/event-stream"),
+        (status = 400, description = "Formato de fecha inválido", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Disponibilidad"
+)]
+pub async fn eventos_disponibilidad(
+    Extension(en_vivo): Extension<Arc<dyn DisponibilidadEnVivoService>>,
+    Query(params): Query<SuscripcionQuery>,
+) -> Response {
+    let filtro = match params.filtro() {
+        Ok(filtro) => filtro,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let eventos = stream::unfold(en_vivo.suscribir(filtro), |mut suscripcion| async move {
+        let evento = suscripcion.siguiente().await?;
+        Some((Ok::<_, Infallible>(evento_sse(evento)), suscripcion))
+    });
+    Sse::new(eventos)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// Recibir los cambios de disponibilidad en tiempo real (WebSocket)
+///
+/// Los mismos cambios que `/disponibilidad/eventos`, como mensajes de texto
+/// JSON con el campo `evento` (`disponibilidad` o `resincronizar`).
+#[utoipa::path(
+    get,
+    path = "/disponibilidad/ws",
+    params(
+        SuscripcionQuery
+    ),
+    responses(
+        (status = 101, description = "Conexión WebSocket establecida; cada mensaje es un evento", body = EventoDisponibilidadResponse),
+        (status = 400, description = "Formato de fecha inválido o petición sin `Upgrade: websocket`", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Disponibilidad"
+)]
+pub async fn disponibilidad_ws(
+    Extension(en_vivo): Extension<Arc<dyn DisponibilidadEnVivoService>>,
+    Query(params): Query<SuscripcionQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let filtro = match params.filtro() {
+        Ok(filtro) => filtro,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    // Suscrito ya antes del upgrade para no perder cambios
+    let suscripcion = en_vivo.suscribir(filtro);
+    ws.on_upgrade(move |socket| enviar_eventos(socket, suscripcion))
+}
+
+async fn enviar_eventos(socket: WebSocket, mut suscripcion: Box<dyn Suscripcion>) {
+    let (mut emisor, mut receptor) = socket.split();
+    let mut envio = tokio::spawn(async move {
+        while let Some(evento) = suscripcion.siguiente().await {
+            let mensaje = serde_json::to_string(&EventoDisponibilidadResponse::from(evento))
+                .expect("el evento siempre se puede serializar");
+            if emisor.send(Message::Text(mensaje)).await.is_err() {
+                break;
+            }
+        }
+    });
+    // El cliente no envía nada; solo interesa saber cuándo cierra
+    let cierre = async {
+        while let Some(Ok(mensaje)) = receptor.next().await {
+            if let Message::Close(_) = mensaje {
+                break;
+            }
+        }
+    };
+    tokio::select! {
+        _ = &mut envio => {}
+        _ = cierre => {}
+    }
+    envio.abort();
+}
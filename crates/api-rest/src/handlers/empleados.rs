@@ -1,14 +1,22 @@
 // Handlers para endpoints de Empleados
 // Estos son ADAPTADORES DE ENTRADA que traducen HTTP -> Casos de Uso
 
-use crate::dtos::{CrearEmpleadoRequest, EmpleadoResponse, ErrorResponse};
+use super::paginacion::enlace_siguiente;
+use super::respuestas::{respuesta_actualizacion, respuesta_borrado, ErrorHttp};
+use crate::auth::Principal;
+use crate::dtos::{
+    ActualizarEmpleadoRequest, CrearEmpleadoRequest, EmpleadoResponse, ModificarEmpleadoRequest,
+    PaginaEmpleadosResponse, ProblemaResponse,
+};
 use axum::{
-    extract::Path,
+    extract::{OriginalUri, Path, Query},
     http::StatusCode,
     response::{IntoResponse, Response},
     Extension, Json,
 };
-use reservas_ports::r#in::empleado_service::EmpleadoService;
+use reservas_ports::consulta::ConsultaEmpleados;
+use reservas_ports::r#in::empleado_service::{CambiosEmpleado, EmpleadoService};
+use serde::Deserialize;
 use std::sync::Arc;
 
 /// Crear un nuevo empleado
@@ -18,44 +26,76 @@ use std::sync::Arc;
     request_body = CrearEmpleadoRequest,
     responses(
         (status = 201, description = "Empleado creado exitosamente", body = EmpleadoResponse),
-        (status = 400, description = "Error de validación", body = ErrorResponse)
+        (status = 400, description = "Datos inválidos o email ya registrado", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Empleados"
 )]
 pub async fn crear_empleado(
+    principal: Principal,
     Extension(service): Extension<Arc<dyn EmpleadoService>>,
     Json(request): Json<CrearEmpleadoRequest>,
 ) -> Response {
-    match service.crear_empleado(request.nombre, request.email).await {
+    let actor = principal.actor();
+    match service
+        .crear_empleado(&actor, request.nombre, request.email)
+        .await
+    {
         Ok(empleado) => {
             let response: EmpleadoResponse = empleado.into();
             (StatusCode::CREATED, Json(response)).into_response()
         }
-        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })).into_response(),
+        Err(e) => e.en_respuesta(StatusCode::BAD_REQUEST),
     }
 }
 
-/// Listar todos los empleados
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ListarEmpleadosQuery {
+    /// Texto a buscar en nombre o email
+    pub q: Option<String>,
+    pub activo: Option<bool>,
+    /// Cursor devuelto en `siguiente_cursor` por la página anterior
+    pub cursor: Option<String>,
+    /// Tamaño de página (por defecto 50, máximo 200)
+    pub limite: Option<usize>,
+}
+
+/// Listar empleados ordenados por nombre, con filtros y paginación
 #[utoipa::path(
     get,
     path = "/empleados",
+    params(ListarEmpleadosQuery),
     responses(
-        (status = 200, description = "Lista de empleados", body = Vec<EmpleadoResponse>),
-        (status = 500, description = "Error interno", body = ErrorResponse)
+        (status = 200, description = "Página de empleados", body = PaginaEmpleadosResponse),
+        (status = 400, description = "Cursor inválido", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 500, description = "Error interno", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Empleados"
 )]
-pub async fn listar_empleados(Extension(service): Extension<Arc<dyn EmpleadoService>>) -> Response {
-    match service.listar_empleados().await {
-        Ok(empleados) => {
-            let response: Vec<EmpleadoResponse> = empleados.into_iter().map(|e| e.into()).collect();
+pub async fn listar_empleados(
+    Extension(service): Extension<Arc<dyn EmpleadoService>>,
+    OriginalUri(uri): OriginalUri,
+    Query(params): Query<ListarEmpleadosQuery>,
+) -> Response {
+    let consulta = ConsultaEmpleados {
+        texto: params.q,
+        activo: params.activo,
+        cursor: params.cursor,
+        limite: params.limite,
+    };
+    if let Err(e) = consulta.validar_cursor() {
+        return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response();
+    }
+
+    match service.buscar_empleados(consulta).await {
+        Ok(pagina) => {
+            let response = PaginaEmpleadosResponse {
+                siguiente: enlace_siguiente(&uri, pagina.siguiente_cursor.as_deref()),
+                siguiente_cursor: pagina.siguiente_cursor,
+                elementos: pagina.elementos.into_iter().map(|e| e.into()).collect(),
+            };
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
-        )
-            .into_response(),
+        Err(e) => ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
     }
 }
 
@@ -68,8 +108,8 @@ pub async fn listar_empleados(Extension(service): Extension<Arc<dyn EmpleadoServ
     ),
     responses(
         (status = 200, description = "Empleado encontrado", body = EmpleadoResponse),
-        (status = 404, description = "Empleado no encontrado", body = ErrorResponse),
-        (status = 500, description = "Error interno", body = ErrorResponse)
+        (status = 404, description = "Empleado no encontrado", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 500, description = "Error interno", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Empleados"
 )]
@@ -82,18 +122,12 @@ pub async fn obtener_empleado(
             let response: EmpleadoResponse = empleado.into();
             (StatusCode::OK, Json(response)).into_response()
         }
-        Ok(None) => (
+        Ok(None) => ProblemaResponse::new(
             StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Empleado {} no encontrado", id),
-            }),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
+            format!("Empleado {} no encontrado", id),
         )
-            .into_response(),
+        .into_response(),
+        Err(e) => ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
     }
 }
 
@@ -106,20 +140,22 @@ pub async fn obtener_empleado(
     ),
     responses(
         (status = 200, description = "Empleado desactivado", body = EmpleadoResponse),
-        (status = 400, description = "Error de validación", body = ErrorResponse)
+        (status = 400, description = "Error de validación", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Empleados"
 )]
 pub async fn desactivar_empleado(
+    principal: Principal,
     Extension(service): Extension<Arc<dyn EmpleadoService>>,
     Path(id): Path<String>,
 ) -> Response {
-    match service.desactivar_empleado(&id).await {
+    tracing::info!("{} desactiva el empleado {}", principal.sujeto, id);
+    match service.desactivar_empleado(&principal.actor(), &id).await {
         Ok(empleado) => {
             let response: EmpleadoResponse = empleado.into();
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })).into_response(),
+        Err(e) => e.en_respuesta(StatusCode::BAD_REQUEST),
     }
 }
 
@@ -132,19 +168,117 @@ pub async fn desactivar_empleado(
     ),
     responses(
         (status = 200, description = "Empleado activado", body = EmpleadoResponse),
-        (status = 400, description = "Error de validación", body = ErrorResponse)
+        (status = 400, description = "Error de validación", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Empleados"
 )]
 pub async fn activar_empleado(
+    principal: Principal,
     Extension(service): Extension<Arc<dyn EmpleadoService>>,
     Path(id): Path<String>,
 ) -> Response {
-    match service.activar_empleado(&id).await {
+    match service.activar_empleado(&principal.actor(), &id).await {
         Ok(empleado) => {
             let response: EmpleadoResponse = empleado.into();
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })).into_response(),
+        Err(e) => e.en_respuesta(StatusCode::BAD_REQUEST),
     }
 }
+
+/// Sustituir los datos de un empleado
+#[utoipa::path(
+    put,
+    path = "/empleados/{id}",
+    params(
+        ("id" = String, Path, description = "ID del empleado")
+    ),
+    request_body = ActualizarEmpleadoRequest,
+    responses(
+        (status = 200, description = "Empleado actualizado", body = EmpleadoResponse),
+        (status = 400, description = "Datos inválidos o email ya registrado", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Empleado no encontrado", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Empleados"
+)]
+pub async fn reemplazar_empleado(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn EmpleadoService>>,
+    Path(id): Path<String>,
+    Json(request): Json<ActualizarEmpleadoRequest>,
+) -> Response {
+    let cambios = CambiosEmpleado {
+        nombre: Some(request.nombre),
+        email: Some(request.email),
+        responsable_id: Some(request.responsable_id),
+    };
+    let resultado = service
+        .actualizar_empleado(&principal.actor(), &id, cambios)
+        .await;
+    respuesta_actualizacion::<_, EmpleadoResponse>(
+        resultado,
+        format!("Empleado {} no encontrado", id),
+    )
+}
+
+/// Modificar algunos datos de un empleado
+#[utoipa::path(
+    patch,
+    path = "/empleados/{id}",
+    params(
+        ("id" = String, Path, description = "ID del empleado")
+    ),
+    request_body = ModificarEmpleadoRequest,
+    responses(
+        (status = 200, description = "Empleado actualizado", body = EmpleadoResponse),
+        (status = 400, description = "Datos inválidos o email ya registrado", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Empleado no encontrado", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Empleados"
+)]
+pub async fn modificar_empleado(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn EmpleadoService>>,
+    Path(id): Path<String>,
+    Json(request): Json<ModificarEmpleadoRequest>,
+) -> Response {
+    let cambios = CambiosEmpleado {
+        nombre: request.nombre,
+        email: request.email,
+        // Una cadena vacía quita el responsable
+        responsable_id: request
+            .responsable_id
+            .map(|r| Some(r).filter(|r| !r.is_empty())),
+    };
+    let resultado = service
+        .actualizar_empleado(&principal.actor(), &id, cambios)
+        .await;
+    respuesta_actualizacion::<_, EmpleadoResponse>(
+        resultado,
+        format!("Empleado {} no encontrado", id),
+    )
+}
+
+/// Eliminar un empleado sin reservas
+#[utoipa::path(
+    delete,
+    path = "/empleados/{id}",
+    params(
+        ("id" = String, Path, description = "ID del empleado")
+    ),
+    responses(
+        (status = 204, description = "Empleado eliminado"),
+        (status = 404, description = "Empleado no encontrado", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 409, description = "El empleado tiene reservas", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Empleados"
+)]
+pub async fn eliminar_empleado(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn EmpleadoService>>,
+    Path(id): Path<String>,
+) -> Response {
+    tracing::info!("{} elimina el empleado {}", principal.sujeto, id);
+    let resultado = service.eliminar_empleado(&principal.actor(), &id).await;
+    respuesta_borrado(resultado, format!("Empleado {} no encontrado", id))
+}
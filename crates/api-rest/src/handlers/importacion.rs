@@ -0,0 +1,87 @@
+// Handler de la importación de reservas desde ficheros iCalendar
+
+use super::respuestas::ErrorHttp;
+use crate::auth::Principal;
+use crate::dtos::{InformeImportacionResponse, ProblemaResponse};
+use axum::{
+    extract::Query,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::FixedOffset;
+use reservas_ports::r#in::importacion_service::ImportacionService;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ImportarQuery {
+    /// Solo informa de lo que se importaría, sin crear reservas
+    #[serde(default)]
+    pub simular: bool,
+    /// Desfase respecto a UTC de las horas sin zona del fichero
+    #[param(example = "+01:00")]
+    pub desfase: Option<String>,
+}
+
+/// En una query sin codificar, el `+` llega como espacio
+fn leer_desfase(valor: Option<&str>) -> Result<FixedOffset, String> {
+    let Some(valor) = valor.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(FixedOffset::east_opt(0).expect("UTC es un desfase válido"));
+    };
+    let con_signo = if valor.starts_with(['+', '-']) {
+        valor.to_string()
+    } else {
+        format!("+{}", valor)
+    };
+    con_signo
+        .parse()
+        .map_err(|_| format!("Desfase no válido: {} (use +HH:MM o -HH:MM)", valor))
+}
+
+/// Importar reservas desde un fichero iCalendar. Cada asistente que es empleado
+/// recibe una reserva por cada slot de una hora que ocupa el evento.
+#[utoipa::path(
+    post,
+    path = "/reservas/importar",
+    params(ImportarQuery),
+    request_body(content = String, content_type = "text/calendar"),
+    responses(
+        (status = 200, description = "Informe de la importación (o de la simulación)", body = InformeImportacionResponse),
+        (status = 400, description = "Fichero ilegible o desfase no válido", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Reservas"
+)]
+pub async fn importar_reservas(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn ImportacionService>>,
+    Query(params): Query<ImportarQuery>,
+    contenido: String,
+) -> Response {
+    let desfase = match leer_desfase(params.desfase.as_deref()) {
+        Ok(desfase) => desfase,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let eventos = match ical::deserializar(&contenido, desfase) {
+        Ok(eventos) => eventos,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    tracing::info!(
+        "{} importa {} eventos (simular={})",
+        principal.sujeto,
+        eventos.len(),
+        params.simular
+    );
+
+    match service
+        .importar(&principal.actor(), eventos, params.simular)
+        .await
+    {
+        Ok(informe) => (
+            StatusCode::OK,
+            Json(InformeImportacionResponse::desde(informe, params.simular)),
+        )
+            .into_response(),
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -0,0 +1,116 @@
+// Handlers de los informes de ocupación, en JSON o como hoja CSV o XLSX
+
+use super::disponibilidad::leer_periodo;
+use crate::dtos::{InformeOcupacionResponse, ProblemaResponse};
+use axum::{
+    extract::Query,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use hojas_calculo::Formato;
+use reservas_domain::Periodo;
+use reservas_ports::r#in::informe_service::{Desglose, InformeService};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct InformeQuery {
+    /// Primer día (YYYY-MM-DD)
+    #[param(example = "2025-11-01")]
+    pub desde: Option<String>,
+    /// Último día incluido (YYYY-MM-DD); por defecto, `desde`
+    #[param(example = "2025-11-30")]
+    pub hasta: Option<String>,
+    /// Semana de lunes a domingo que contiene este día (YYYY-MM-DD)
+    pub semana: Option<String>,
+    /// Mes natural (YYYY-MM)
+    pub mes: Option<String>,
+    /// json (por defecto), csv o xlsx
+    pub formato: Option<String>,
+    /// Parte del informe en CSV o XLSX: resumen (por defecto), empleados,
+    /// salas, horas o dias
+    pub desglose: Option<String>,
+}
+
+/// Informe de ocupación de un periodo de hasta 366 días: uso por empleado,
+/// por sala, por hora del día y por día, días más ocupados y tasas de
+/// cancelación y de no presentación. En CSV o XLSX se descarga una parte.
+#[utoipa::path(
+    get,
+    path = "/informes/ocupacion",
+    params(InformeQuery),
+    responses(
+        (status = 200, description = "Informe del periodo", content(
+            (InformeOcupacionResponse = "application/json"),
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        )),
+        (status = 400, description = "Periodo, formato o desglose inválido", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Informes"
+)]
+pub async fn informe_ocupacion(
+    Extension(service): Extension<Arc<dyn InformeService>>,
+    Query(params): Query<InformeQuery>,
+) -> Response {
+    let peticion = leer_periodo(
+        &params.desde,
+        &params.hasta,
+        &params.semana,
+        &params.mes,
+        Periodo::para_informe,
+    )
+    .and_then(|periodo| {
+        let formato = match params.formato.as_deref().map(str::trim) {
+            None | Some("") | Some("json") => None,
+            Some(formato) => Some(formato.parse::<Formato>()?),
+        };
+        let desglose = match params.desglose.as_deref().map(str::trim) {
+            None | Some("") => Desglose::Resumen,
+            Some(desglose) => desglose.parse()?,
+        };
+        Ok((periodo, formato, desglose))
+    });
+    let (periodo, formato, desglose) = match peticion {
+        Ok(peticion) => peticion,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    let Some(formato) = formato else {
+        return match service.informe_ocupacion(periodo).await {
+            Ok(informe) => (
+                StatusCode::OK,
+                Json(InformeOcupacionResponse::from(informe)),
+            )
+                .into_response(),
+            Err(e) => ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+        };
+    };
+    let contenido = match service.exportar_informe(periodo, desglose).await {
+        Ok(tabla) => hojas_calculo::escribir(&tabla, formato),
+        Err(e) => Err(e),
+    };
+    match contenido {
+        Ok(contenido) => {
+            let fichero = format!(
+                "attachment; filename=\"ocupacion-{:?}-{}-{}.{}\"",
+                desglose,
+                periodo.desde.format("%Y%m%d"),
+                periodo.hasta.format("%Y%m%d"),
+                formato.extension()
+            )
+            .to_lowercase();
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, formato.content_type().to_string()),
+                    (header::CONTENT_DISPOSITION, fichero),
+                ],
+                contenido,
+            )
+                .into_response()
+        }
+        Err(e) => ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
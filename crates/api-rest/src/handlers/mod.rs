@@ -1,11 +1,25 @@
 // Módulo de handlers - Adaptadores de entrada HTTP
 
+pub mod admin;
+pub mod calendario;
+pub mod carga_masiva;
 pub mod disponibilidad;
 pub mod empleados;
+pub mod importacion;
+pub mod informes;
+mod paginacion;
 pub mod reservas;
+mod respuestas;
 pub mod salas;
+pub mod webhooks;
 
+pub use admin::*;
+pub use calendario::*;
+pub use carga_masiva::*;
 pub use disponibilidad::*;
 pub use empleados::*;
+pub use importacion::*;
+pub use informes::*;
 pub use reservas::*;
 pub use salas::*;
+pub use webhooks::*;
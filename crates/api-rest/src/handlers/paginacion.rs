@@ -0,0 +1,18 @@
+// Utilidades compartidas por los listados paginados
+
+use axum::http::Uri;
+
+/// Construye el enlace a la siguiente página: la misma URL con el cursor sustituido
+pub(crate) fn enlace_siguiente(uri: &Uri, cursor: Option<&str>) -> Option<String> {
+    let cursor = cursor?;
+
+    let mut params: Vec<(String, String)> = uri
+        .query()
+        .and_then(|q| serde_urlencoded::from_str(q).ok())
+        .unwrap_or_default();
+    params.retain(|(clave, _)| clave != "cursor");
+    params.push(("cursor".to_string(), cursor.to_string()));
+
+    let query = serde_urlencoded::to_string(&params).ok()?;
+    Some(format!("{}?{}", uri.path(), query))
+}
@@ -1,14 +1,24 @@
 // Handlers para endpoints de Reservas
 
-use crate::dtos::{CrearReservaRequest, ErrorResponse, ReservaResponse};
+use super::paginacion::enlace_siguiente;
+use super::respuestas::{respuesta_actualizacion, respuesta_borrado, ErrorHttp};
+use crate::auth::Principal;
+use crate::dtos::{
+    ActualizarReservaRequest, CrearReservaRequest, ModificarReservaRequest, PaginaReservasResponse,
+    ProblemaResponse, ReprogramarReservaRequest, ReservaResponse,
+};
+use crate::mappers::estado_desde_str;
 use axum::{
-    extract::Path,
+    extract::{OriginalUri, Path, Query},
     http::StatusCode,
     response::{IntoResponse, Response},
     Extension, Json,
 };
+use chrono::{Days, NaiveDate};
 use reservas_domain::Slot;
-use reservas_ports::r#in::reserva_service::ReservaService;
+use reservas_ports::consulta::{ConsultaReservas, Direccion, OrdenReservas};
+use reservas_ports::r#in::reserva_service::{CambiosReserva, ReservaService};
+use serde::Deserialize;
 
 use std::sync::Arc;
 
@@ -19,11 +29,12 @@ use std::sync::Arc;
     request_body = CrearReservaRequest,
     responses(
         (status = 201, description = "Reserva creada exitosamente", body = ReservaResponse),
-        (status = 400, description = "Error de validación o slot ocupado", body = ErrorResponse)
+        (status = 400, description = "Error de validación o slot ocupado", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Reservas"
 )]
 pub async fn crear_reserva(
+    principal: Principal,
     Extension(service): Extension<Arc<dyn ReservaService>>,
     Json(request): Json<CrearReservaRequest>,
 ) -> Response {
@@ -31,38 +42,133 @@ pub async fn crear_reserva(
     let slot = Slot::new(request.inicio_slot);
 
     match service
-        .crear_reserva(request.empleado_id, slot, request.descripcion)
+        .crear_reserva(
+            &principal.actor(),
+            request.empleado_id,
+            slot,
+            request.descripcion,
+            request.sala_id,
+        )
         .await
     {
         Ok(reserva) => {
             let response: ReservaResponse = reserva.into();
             (StatusCode::CREATED, Json(response)).into_response()
         }
-        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })).into_response(),
+        Err(e) => e.en_respuesta(StatusCode::BAD_REQUEST),
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ListarReservasQuery {
+    /// Primer día incluido (YYYY-MM-DD)
+    #[param(example = "2025-11-25")]
+    pub desde: Option<String>,
+    /// Último día incluido (YYYY-MM-DD)
+    #[param(example = "2025-11-30")]
+    pub hasta: Option<String>,
+    /// pendiente | confirmada | cancelada
+    pub estado: Option<String>,
+    pub empleado_id: Option<String>,
+    pub sala_id: Option<String>,
+    /// Texto a buscar en la descripción
+    pub q: Option<String>,
+    /// inicio (por defecto) | empleado | estado
+    pub orden: Option<String>,
+    /// asc (por defecto) | desc
+    pub direccion: Option<String>,
+    /// Cursor devuelto en `siguiente_cursor` por la página anterior
+    pub cursor: Option<String>,
+    /// Tamaño de página (por defecto 50, máximo 200)
+    pub limite: Option<usize>,
+}
+
+impl ListarReservasQuery {
+    fn a_consulta(self) -> Result<ConsultaReservas, String> {
+        let fecha = |valor: &str| {
+            NaiveDate::parse_from_str(valor, "%Y-%m-%d")
+                .map_err(|_| format!("Fecha inválida: {}. Use YYYY-MM-DD", valor))
+        };
+
+        let desde = match &self.desde {
+            Some(d) => Some(fecha(d)?.and_hms_opt(0, 0, 0).unwrap().and_utc()),
+            None => None,
+        };
+        // `hasta` es inclusivo: el límite real es el inicio del día siguiente
+        let hasta = match &self.hasta {
+            Some(h) => Some(
+                (fecha(h)? + Days::new(1))
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+                    .and_utc(),
+            ),
+            None => None,
+        };
+        let estado = match &self.estado {
+            Some(e) => Some(estado_desde_str(e).ok_or_else(|| format!("Estado inválido: {}", e))?),
+            None => None,
+        };
+        let orden = match self.orden.as_deref() {
+            None | Some("inicio") => OrdenReservas::Inicio,
+            Some("empleado") => OrdenReservas::Empleado,
+            Some("estado") => OrdenReservas::Estado,
+            Some(otro) => return Err(format!("Orden inválido: {}", otro)),
+        };
+        let direccion = match self.direccion.as_deref() {
+            None | Some("asc") => Direccion::Ascendente,
+            Some("desc") => Direccion::Descendente,
+            Some(otra) => return Err(format!("Dirección inválida: {}", otra)),
+        };
+
+        let consulta = ConsultaReservas {
+            desde,
+            hasta,
+            estado,
+            empleado_id: self.empleado_id,
+            sala_id: self.sala_id,
+            texto: self.q,
+            orden,
+            direccion,
+            cursor: self.cursor,
+            limite: self.limite,
+        };
+        consulta.validar_cursor()?;
+        Ok(consulta)
     }
 }
 
-/// Listar todas las reservas
+/// Listar reservas con filtros, orden y paginación por cursor
 #[utoipa::path(
     get,
     path = "/reservas",
+    params(ListarReservasQuery),
     responses(
-        (status = 200, description = "Lista de reservas", body = Vec<ReservaResponse>),
-        (status = 500, description = "Error interno", body = ErrorResponse)
+        (status = 200, description = "Página de reservas", body = PaginaReservasResponse),
+        (status = 400, description = "Parámetros de consulta inválidos", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 500, description = "Error interno", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Reservas"
 )]
-pub async fn listar_reservas(Extension(service): Extension<Arc<dyn ReservaService>>) -> Response {
-    match service.listar_reservas().await {
-        Ok(reservas) => {
-            let response: Vec<ReservaResponse> = reservas.into_iter().map(|r| r.into()).collect();
+pub async fn listar_reservas(
+    Extension(service): Extension<Arc<dyn ReservaService>>,
+    OriginalUri(uri): OriginalUri,
+    Query(params): Query<ListarReservasQuery>,
+) -> Response {
+    let consulta = match params.a_consulta() {
+        Ok(c) => c,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    match service.buscar_reservas(consulta).await {
+        Ok(pagina) => {
+            let response = PaginaReservasResponse {
+                siguiente: enlace_siguiente(&uri, pagina.siguiente_cursor.as_deref()),
+                siguiente_cursor: pagina.siguiente_cursor,
+                elementos: pagina.elementos.into_iter().map(|r| r.into()).collect(),
+            };
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
-        )
-            .into_response(),
+        Err(e) => ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
     }
 }
 
@@ -75,8 +181,8 @@ pub async fn listar_reservas(Extension(service): Extension<Arc<dyn ReservaServic
     ),
     responses(
         (status = 200, description = "Reserva encontrada", body = ReservaResponse),
-        (status = 404, description = "Reserva no encontrada", body = ErrorResponse),
-        (status = 500, description = "Error interno", body = ErrorResponse)
+        (status = 404, description = "Reserva no encontrada", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 500, description = "Error interno", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Reservas"
 )]
@@ -89,18 +195,12 @@ pub async fn obtener_reserva(
             let response: ReservaResponse = reserva.into();
             (StatusCode::OK, Json(response)).into_response()
         }
-        Ok(None) => (
+        Ok(None) => ProblemaResponse::new(
             StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Reserva {} no encontrada", id),
-            }),
+            format!("Reserva {} no encontrada", id),
         )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
-        )
-            .into_response(),
+        .into_response(),
+        Err(e) => ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
     }
 }
 
@@ -113,7 +213,7 @@ pub async fn obtener_reserva(
     ),
     responses(
         (status = 200, description = "Lista de reservas del empleado", body = Vec<ReservaResponse>),
-        (status = 500, description = "Error interno", body = ErrorResponse)
+        (status = 500, description = "Error interno", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Reservas"
 )]
@@ -126,11 +226,7 @@ pub async fn listar_reservas_empleado(
             let response: Vec<ReservaResponse> = reservas.into_iter().map(|r| r.into()).collect();
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
-        )
-            .into_response(),
+        Err(e) => ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
     }
 }
 
@@ -143,20 +239,21 @@ pub async fn listar_reservas_empleado(
     ),
     responses(
         (status = 200, description = "Reserva confirmada", body = ReservaResponse),
-        (status = 400, description = "Error de validación", body = ErrorResponse)
+        (status = 400, description = "Error de validación", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Reservas"
 )]
 pub async fn confirmar_reserva(
+    principal: Principal,
     Extension(service): Extension<Arc<dyn ReservaService>>,
     Path(id): Path<String>,
 ) -> Response {
-    match service.confirmar_reserva(&id).await {
+    match service.confirmar_reserva(&principal.actor(), &id).await {
         Ok(reserva) => {
             let response: ReservaResponse = reserva.into();
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })).into_response(),
+        Err(e) => e.en_respuesta(StatusCode::BAD_REQUEST),
     }
 }
 
@@ -169,19 +266,142 @@ pub async fn confirmar_reserva(
     ),
     responses(
         (status = 200, description = "Reserva cancelada", body = ReservaResponse),
-        (status = 400, description = "Error de validación", body = ErrorResponse)
+        (status = 400, description = "Error de validación", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Reservas"
 )]
 pub async fn cancelar_reserva(
+    principal: Principal,
     Extension(service): Extension<Arc<dyn ReservaService>>,
     Path(id): Path<String>,
 ) -> Response {
-    match service.cancelar_reserva(&id).await {
+    match service.cancelar_reserva(&principal.actor(), &id).await {
         Ok(reserva) => {
             let response: ReservaResponse = reserva.into();
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })).into_response(),
+        Err(e) => e.en_respuesta(StatusCode::BAD_REQUEST),
     }
 }
+
+/// Sustituir los datos editables de una reserva
+#[utoipa::path(
+    put,
+    path = "/reservas/{id}",
+    params(
+        ("id" = String, Path, description = "ID de la reserva")
+    ),
+    request_body = ActualizarReservaRequest,
+    responses(
+        (status = 200, description = "Reserva actualizada", body = ReservaResponse),
+        (status = 400, description = "Error de validación", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Reserva no encontrada", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Reservas"
+)]
+pub async fn reemplazar_reserva(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn ReservaService>>,
+    Path(id): Path<String>,
+    Json(request): Json<ActualizarReservaRequest>,
+) -> Response {
+    let cambios = CambiosReserva {
+        descripcion: Some(request.descripcion),
+        ..Default::default()
+    };
+    let resultado = service
+        .actualizar_reserva(&principal.actor(), &id, cambios)
+        .await;
+    respuesta_actualizacion::<_, ReservaResponse>(
+        resultado,
+        format!("Reserva {} no encontrada", id),
+    )
+}
+
+/// Modificar algunos datos de una reserva
+#[utoipa::path(
+    patch,
+    path = "/reservas/{id}",
+    params(
+        ("id" = String, Path, description = "ID de la reserva")
+    ),
+    request_body = ModificarReservaRequest,
+    responses(
+        (status = 200, description = "Reserva actualizada", body = ReservaResponse),
+        (status = 400, description = "Error de validación", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Reserva no encontrada", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Reservas"
+)]
+pub async fn modificar_reserva(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn ReservaService>>,
+    Path(id): Path<String>,
+    Json(request): Json<ModificarReservaRequest>,
+) -> Response {
+    let cambios = CambiosReserva {
+        descripcion: request.descripcion,
+        ..Default::default()
+    };
+    let resultado = service
+        .actualizar_reserva(&principal.actor(), &id, cambios)
+        .await;
+    respuesta_actualizacion::<_, ReservaResponse>(
+        resultado,
+        format!("Reserva {} no encontrada", id),
+    )
+}
+
+/// Mover una reserva a otro slot conservando su ID
+#[utoipa::path(
+    post,
+    path = "/reservas/{id}/reprogramar",
+    params(
+        ("id" = String, Path, description = "ID de la reserva")
+    ),
+    request_body = ReprogramarReservaRequest,
+    responses(
+        (status = 200, description = "Reserva reprogramada", body = ReservaResponse),
+        (status = 400, description = "Slot inválido, ocupado o reserva cancelada", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Reserva no encontrada", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Reservas"
+)]
+pub async fn reprogramar_reserva(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn ReservaService>>,
+    Path(id): Path<String>,
+    Json(request): Json<ReprogramarReservaRequest>,
+) -> Response {
+    let slot = Slot::new(request.inicio_slot);
+    let resultado = service
+        .reprogramar_reserva(&principal.actor(), &id, slot)
+        .await;
+    respuesta_actualizacion::<_, ReservaResponse>(
+        resultado,
+        format!("Reserva {} no encontrada", id),
+    )
+}
+
+/// Eliminar una reserva (para anularla conservando el histórico, cancelarla)
+#[utoipa::path(
+    delete,
+    path = "/reservas/{id}",
+    params(
+        ("id" = String, Path, description = "ID de la reserva")
+    ),
+    responses(
+        (status = 204, description = "Reserva eliminada"),
+        (status = 404, description = "Reserva no encontrada", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Reservas"
+)]
+pub async fn eliminar_reserva(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn ReservaService>>,
+    Path(id): Path<String>,
+) -> Response {
+    tracing::info!("{} elimina la reserva {}", principal.sujeto, id);
+    let resultado = service.eliminar_reserva(&principal.actor(), &id).await;
+    respuesta_borrado(resultado, format!("Reserva {} no encontrada", id))
+}
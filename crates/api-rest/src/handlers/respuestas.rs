@@ -0,0 +1,94 @@
+// Respuestas compartidas por las operaciones de edición y borrado
+
+use crate::dtos::ProblemaResponse;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::r#in::empleado_service::ErrorEmpleado;
+use reservas_ports::r#in::respaldo_service::ErrorRespaldo;
+use serde::Serialize;
+
+/// Errores de los casos de uso que saben presentarse como respuesta HTTP
+pub(crate) trait ErrorHttp {
+    fn en_respuesta(self, status: StatusCode) -> Response;
+}
+
+impl ErrorHttp for String {
+    fn en_respuesta(self, status: StatusCode) -> Response {
+        ProblemaResponse::new(status, self).into_response()
+    }
+}
+
+/// Los permisos denegados son siempre 403
+impl ErrorHttp for ErrorServicio {
+    fn en_respuesta(self, status: StatusCode) -> Response {
+        match self {
+            ErrorServicio::Denegado(mensaje) => mensaje.en_respuesta(StatusCode::FORBIDDEN),
+            ErrorServicio::Otro(mensaje) => mensaje.en_respuesta(status),
+        }
+    }
+}
+
+/// Los errores de validación llevan el detalle campo a campo (siempre 400)
+impl ErrorHttp for ErrorEmpleado {
+    fn en_respuesta(self, status: StatusCode) -> Response {
+        let detalle = self.to_string();
+        match self {
+            ErrorEmpleado::Validacion(campos) => ProblemaResponse::validacion(
+                detalle,
+                campos.into_iter().map(|c| c.into()).collect(),
+            )
+            .into_response(),
+            ErrorEmpleado::Denegado(mensaje) => mensaje.en_respuesta(StatusCode::FORBIDDEN),
+            ErrorEmpleado::Otro(mensaje) => mensaje.en_respuesta(status),
+        }
+    }
+}
+
+/// Un respaldo inconsistente es 400 y un permiso denegado 403
+impl ErrorHttp for ErrorRespaldo {
+    fn en_respuesta(self, status: StatusCode) -> Response {
+        let detalle = self.to_string();
+        match self {
+            ErrorRespaldo::Inconsistente(_) => detalle.en_respuesta(StatusCode::BAD_REQUEST),
+            ErrorRespaldo::Denegado(mensaje) => mensaje.en_respuesta(StatusCode::FORBIDDEN),
+            ErrorRespaldo::Otro(mensaje) => mensaje.en_respuesta(status),
+        }
+    }
+}
+
+/// 200 con la entidad editada, 404 si no existe, 403 sin permiso y 400 si no
+/// pasa la validación
+pub(crate) fn respuesta_actualizacion<T, R>(
+    resultado: Result<Option<T>, impl ErrorHttp>,
+    no_encontrado: String,
+) -> Response
+where
+    R: From<T> + Serialize,
+{
+    match resultado {
+        Ok(Some(entidad)) => (StatusCode::OK, Json(R::from(entidad))).into_response(),
+        Ok(None) => respuesta_no_encontrado(no_encontrado),
+        Err(e) => e.en_respuesta(StatusCode::BAD_REQUEST),
+    }
+}
+
+/// 204 si se borró, 404 si no existía, 403 sin permiso y 409 si otras
+/// entidades dependen de ella
+pub(crate) fn respuesta_borrado(
+    resultado: Result<bool, impl ErrorHttp>,
+    no_encontrado: String,
+) -> Response {
+    match resultado {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => respuesta_no_encontrado(no_encontrado),
+        Err(e) => e.en_respuesta(StatusCode::CONFLICT),
+    }
+}
+
+fn respuesta_no_encontrado(error: String) -> Response {
+    error.en_respuesta(StatusCode::NOT_FOUND)
+}
@@ -1,32 +1,73 @@
-use crate::dtos::{CrearSalaRequest, ErrorResponse, SalaResponse};
+use super::disponibilidad::{leer_fecha, leer_periodo};
+use super::paginacion::enlace_siguiente;
+use super::respuestas::{respuesta_actualizacion, respuesta_borrado, ErrorHttp};
+use crate::auth::Principal;
+use crate::dtos::{
+    ActualizarSalaRequest, CrearSalaRequest, MatrizDisponibilidadResponse, ModificarSalaRequest,
+    PaginaSalasResponse, ProblemaResponse, SalaResponse,
+};
 use axum::{
+    extract::{OriginalUri, Path, Query},
     http::StatusCode,
     response::{IntoResponse, Response},
     Extension, Json,
 };
-use reservas_ports::r#in::sala_service::SalaService;
+use reservas_domain::Periodo;
+use reservas_ports::consulta::ConsultaSalas;
+use reservas_ports::r#in::sala_service::{BusquedaSalas, CambiosSala, SalaService};
+use serde::Deserialize;
 use std::sync::Arc;
 
-/// Listar todas las salas
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ListarSalasQuery {
+    /// Texto a buscar en el nombre
+    pub q: Option<String>,
+    pub activa: Option<bool>,
+    pub capacidad_minima: Option<u32>,
+    /// Cursor devuelto en `siguiente_cursor` por la página anterior
+    pub cursor: Option<String>,
+    /// Tamaño de página (por defecto 50, máximo 200)
+    pub limite: Option<usize>,
+}
+
+/// Listar salas ordenadas por nombre, con filtros y paginación
 #[utoipa::path(
     get,
     path = "/salas",
+    params(ListarSalasQuery),
     responses(
-        (status = 200, description = "Lista de salas", body = [SalaResponse])
+        (status = 200, description = "Página de salas", body = PaginaSalasResponse),
+        (status = 400, description = "Cursor inválido", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 500, description = "Error interno", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Salas"
 )]
-pub async fn listar_salas(Extension(service): Extension<Arc<dyn SalaService>>) -> Response {
-    match service.listar_salas().await {
-        Ok(salas) => {
-            let response: Vec<SalaResponse> = salas.into_iter().map(|e| e.into()).collect();
+pub async fn listar_salas(
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    OriginalUri(uri): OriginalUri,
+    Query(params): Query<ListarSalasQuery>,
+) -> Response {
+    let consulta = ConsultaSalas {
+        texto: params.q,
+        activa: params.activa,
+        capacidad_minima: params.capacidad_minima,
+        cursor: params.cursor,
+        limite: params.limite,
+    };
+    if let Err(e) = consulta.validar_cursor() {
+        return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response();
+    }
+
+    match service.buscar_salas(consulta).await {
+        Ok(pagina) => {
+            let response = PaginaSalasResponse {
+                siguiente: enlace_siguiente(&uri, pagina.siguiente_cursor.as_deref()),
+                siguiente_cursor: pagina.siguiente_cursor,
+                elementos: pagina.elementos.into_iter().map(|s| s.into()).collect(),
+            };
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
-        )
-            .into_response(),
+        Err(e) => ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
     }
 }
 
@@ -36,25 +77,215 @@ pub async fn listar_salas(Extension(service): Extension<Arc<dyn SalaService>>) -
     path = "/salas",
     request_body = CrearSalaRequest,
     responses(
-        (status = 201, description = "Sala creada", body = SalaResponse)
+        (status = 201, description = "Sala creada", body = SalaResponse),
+        (status = 400, description = "Error de validación", body = ProblemaResponse, content_type = "application/problem+json")
     ),
     tag = "Salas"
 )]
 pub async fn crear_sala(
+    principal: Principal,
     Extension(service): Extension<Arc<dyn SalaService>>,
     Json(request): Json<CrearSalaRequest>,
-) -> Result<(StatusCode, Json<SalaResponse>), StatusCode> {
-    let sala = service
-        .crear_sala(request.nombre, request.capacidad)
+) -> Response {
+    let actor = principal.actor();
+    match service
+        .crear_sala(&actor, request.nombre, request.capacidad)
         .await
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    {
+        Ok(sala) => {
+            let response = SalaResponse {
+                id: sala.id,
+                nombre: sala.nombre,
+                capacidad: sala.capacidad,
+                activa: sala.activa,
+            };
+            (StatusCode::CREATED, Json(response)).into_response()
+        }
+        Err(e) => e.en_respuesta(StatusCode::BAD_REQUEST),
+    }
+}
 
-    let response = SalaResponse {
-        id: sala.id,
-        nombre: sala.nombre,
-        capacidad: sala.capacidad,
-        activa: sala.activa,
+/// Sustituir los datos de una sala
+#[utoipa::path(
+    put,
+    path = "/salas/{id}",
+    params(
+        ("id" = String, Path, description = "ID de la sala")
+    ),
+    request_body = ActualizarSalaRequest,
+    responses(
+        (status = 200, description = "Sala actualizada", body = SalaResponse),
+        (status = 400, description = "Error de validación", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Sala no encontrada", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Salas"
+)]
+pub async fn reemplazar_sala(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    Path(id): Path<String>,
+    Json(request): Json<ActualizarSalaRequest>,
+) -> Response {
+    let cambios = CambiosSala {
+        nombre: Some(request.nombre),
+        capacidad: Some(request.capacidad),
     };
+    let resultado = service
+        .actualizar_sala(&principal.actor(), &id, cambios)
+        .await;
+    respuesta_actualizacion::<_, SalaResponse>(resultado, format!("Sala {} no encontrada", id))
+}
 
-    Ok((StatusCode::CREATED, Json(response)))
+/// Modificar algunos datos de una sala
+#[utoipa::path(
+    patch,
+    path = "/salas/{id}",
+    params(
+        ("id" = String, Path, description = "ID de la sala")
+    ),
+    request_body = ModificarSalaRequest,
+    responses(
+        (status = 200, description = "Sala actualizada", body = SalaResponse),
+        (status = 400, description = "Error de validación", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 404, description = "Sala no encontrada", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Salas"
+)]
+pub async fn modificar_sala(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    Path(id): Path<String>,
+    Json(request): Json<ModificarSalaRequest>,
+) -> Response {
+    let cambios = CambiosSala {
+        nombre: request.nombre,
+        capacidad: request.capacidad,
+    };
+    let resultado = service
+        .actualizar_sala(&principal.actor(), &id, cambios)
+        .await;
+    respuesta_actualizacion::<_, SalaResponse>(resultado, format!("Sala {} no encontrada", id))
+}
+
+/// Eliminar una sala sin reservas
+#[utoipa::path(
+    delete,
+    path = "/salas/{id}",
+    params(
+        ("id" = String, Path, description = "ID de la sala")
+    ),
+    responses(
+        (status = 204, description = "Sala eliminada"),
+        (status = 404, description = "Sala no encontrada", body = ProblemaResponse, content_type = "application/problem+json"),
+        (status = 409, description = "La sala tiene reservas", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Salas"
+)]
+pub async fn eliminar_sala(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    Path(id): Path<String>,
+) -> Response {
+    tracing::info!("{} elimina la sala {}", principal.sujeto, id);
+    let resultado = service.eliminar_sala(&principal.actor(), &id).await;
+    respuesta_borrado(resultado, format!("Sala {} no encontrada", id))
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct DisponibilidadSalasQuery {
+    /// Primer día (YYYY-MM-DD)
+    #[param(example = "2025-11-24")]
+    pub desde: Option<String>,
+    /// Último día incluido (YYYY-MM-DD); por defecto, `desde`
+    pub hasta: Option<String>,
+    /// Semana de lunes a domingo que contiene este día (YYYY-MM-DD)
+    pub semana: Option<String>,
+    /// Mes natural (YYYY-MM)
+    pub mes: Option<String>,
+}
+
+/// Ocupación de todas las salas activas por días y horas, ordenadas por
+/// nombre. Indique `desde` (y `hasta`), `semana` o `mes`.
+#[utoipa::path(
+    get,
+    path = "/salas/disponibilidad",
+    params(DisponibilidadSalasQuery),
+    responses(
+        (status = 200, description = "Matriz de ocupación de las salas", body = MatrizDisponibilidadResponse),
+        (status = 400, description = "Periodo inválido o de más de 62 días", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Salas"
+)]
+pub async fn disponibilidad_salas(
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    Query(params): Query<DisponibilidadSalasQuery>,
+) -> Response {
+    let periodo = match leer_periodo(
+        &params.desde,
+        &params.hasta,
+        &params.semana,
+        &params.mes,
+        Periodo::new,
+    ) {
+        Ok(periodo) => periodo,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    match service.disponibilidad_salas(periodo).await {
+        Ok(matriz) => (
+            StatusCode::OK,
+            Json(MatrizDisponibilidadResponse::from(matriz)),
+        )
+            .into_response(),
+        Err(e) => ProblemaResponse::new(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct BuscarSalasLibresQuery {
+    /// Día (YYYY-MM-DD)
+    #[param(example = "2025-11-25")]
+    pub fecha: String,
+    /// Hora de inicio (9 a 17)
+    #[param(example = 10)]
+    pub hora_inicio: u32,
+    /// Hora de fin, no incluida (10 a 18)
+    #[param(example = 12)]
+    pub hora_fin: u32,
+    /// Personas que tienen que caber (por defecto 1)
+    pub capacidad_minima: Option<u32>,
+}
+
+/// Buscar salas activas libres durante toda una franja del día y con sitio
+/// suficiente, de la más pequeña a la más grande
+#[utoipa::path(
+    get,
+    path = "/salas/buscar",
+    params(BuscarSalasLibresQuery),
+    responses(
+        (status = 200, description = "Salas libres", body = Vec<SalaResponse>),
+        (status = 400, description = "Fecha o franja inválida", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Salas"
+)]
+pub async fn buscar_salas_libres(
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    Query(params): Query<BuscarSalasLibresQuery>,
+) -> Response {
+    let fecha = match leer_fecha(&params.fecha) {
+        Ok(fecha) => fecha,
+        Err(e) => return ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let busqueda = BusquedaSalas {
+        fecha,
+        hora_inicio: params.hora_inicio,
+        hora_fin: params.hora_fin,
+        capacidad_minima: params.capacidad_minima.unwrap_or(1),
+    };
+    match service.buscar_salas_libres(busqueda).await {
+        Ok(salas) => {
+            let salas: Vec<SalaResponse> = salas.into_iter().map(Into::into).collect();
+            (StatusCode::OK, Json(salas)).into_response()
+        }
+        Err(e) => ProblemaResponse::new(StatusCode::BAD_REQUEST, e).into_response(),
+    }
 }
@@ -0,0 +1,206 @@
+// Handlers para la gestión de webhooks salientes
+
+use super::respuestas::ErrorHttp;
+use crate::auth::Principal;
+use crate::dtos::{CrearWebhookRequest, EntregaResponse, ProblemaResponse, WebhookResponse};
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use reservas_ports::out::notificador::TipoCambio;
+use reservas_ports::r#in::webhook_service::{NuevoWebhook, WebhookService};
+use std::sync::Arc;
+
+fn no_encontrado(mensaje: String) -> Response {
+    ProblemaResponse::new(StatusCode::NOT_FOUND, mensaje).into_response()
+}
+
+/// Dar de alta un webhook. El secreto de la firma solo se devuelve aquí.
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    request_body = CrearWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook creado, con su secreto", body = WebhookResponse),
+        (status = 400, description = "URL, evento o secreto inválido", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Webhooks"
+)]
+pub async fn crear_webhook(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn WebhookService>>,
+    Json(request): Json<CrearWebhookRequest>,
+) -> Response {
+    let mut eventos = Vec::new();
+    for evento in request.eventos.unwrap_or_default() {
+        match TipoCambio::desde_evento(&evento) {
+            Some(tipo) => eventos.push(tipo),
+            None => {
+                return ProblemaResponse::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("Evento desconocido: {}", evento),
+                )
+                .into_response()
+            }
+        }
+    }
+    let nuevo = NuevoWebhook {
+        url: request.url,
+        eventos,
+        secreto: request.secreto,
+    };
+
+    match service.crear(&principal.actor(), nuevo).await {
+        Ok(webhook) => {
+            tracing::info!("{} da de alta el webhook {}", principal.sujeto, webhook.id);
+            let secreto = webhook.secreto.clone();
+            let response = WebhookResponse {
+                secreto: Some(secreto),
+                ..webhook.into()
+            };
+            (StatusCode::CREATED, Json(response)).into_response()
+        }
+        Err(e) => e.en_respuesta(StatusCode::BAD_REQUEST),
+    }
+}
+
+/// Listar los webhooks, en orden de alta
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    responses(
+        (status = 200, description = "Webhooks registrados", body = [WebhookResponse]),
+        (status = 500, description = "Error leyendo los webhooks", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Webhooks"
+)]
+pub async fn listar_webhooks(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn WebhookService>>,
+) -> Response {
+    match service.listar(&principal.actor()).await {
+        Ok(webhooks) => {
+            let response: Vec<WebhookResponse> = webhooks.into_iter().map(|w| w.into()).collect();
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Obtener un webhook
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}",
+    params(
+        ("id" = String, Path, description = "ID del webhook")
+    ),
+    responses(
+        (status = 200, description = "Webhook encontrado", body = WebhookResponse),
+        (status = 404, description = "Webhook no encontrado", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Webhooks"
+)]
+pub async fn obtener_webhook(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn WebhookService>>,
+    Path(id): Path<String>,
+) -> Response {
+    match service.obtener(&principal.actor(), &id).await {
+        Ok(Some(webhook)) => (StatusCode::OK, Json(WebhookResponse::from(webhook))).into_response(),
+        Ok(None) => no_encontrado(format!("Webhook {} no encontrado", id)),
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Borrar un webhook y su registro de entregas
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    params(
+        ("id" = String, Path, description = "ID del webhook")
+    ),
+    responses(
+        (status = 204, description = "Webhook borrado"),
+        (status = 404, description = "Webhook no encontrado", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Webhooks"
+)]
+pub async fn eliminar_webhook(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn WebhookService>>,
+    Path(id): Path<String>,
+) -> Response {
+    match service.eliminar(&principal.actor(), &id).await {
+        Ok(true) => {
+            tracing::info!("{} borra el webhook {}", principal.sujeto, id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => no_encontrado(format!("Webhook {} no encontrado", id)),
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Registro de entregas de un webhook, de la más antigua a la más reciente
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}/entregas",
+    params(
+        ("id" = String, Path, description = "ID del webhook")
+    ),
+    responses(
+        (status = 200, description = "Entregas con todos sus intentos", body = [EntregaResponse]),
+        (status = 404, description = "Webhook no encontrado", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Webhooks"
+)]
+pub async fn listar_entregas_webhook(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn WebhookService>>,
+    Path(id): Path<String>,
+) -> Response {
+    match service.listar_entregas(&principal.actor(), &id).await {
+        Ok(Some(entregas)) => {
+            let response: Vec<EntregaResponse> = entregas.into_iter().map(|e| e.into()).collect();
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Ok(None) => no_encontrado(format!("Webhook {} no encontrado", id)),
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Reenviar una entrega ahora (un solo intento, con firma nueva)
+#[utoipa::path(
+    post,
+    path = "/webhooks/{id}/entregas/{entrega_id}/reenviar",
+    params(
+        ("id" = String, Path, description = "ID del webhook"),
+        ("entrega_id" = String, Path, description = "ID de la entrega")
+    ),
+    responses(
+        (status = 200, description = "Entrega con el resultado del nuevo intento", body = EntregaResponse),
+        (status = 404, description = "Webhook o entrega no encontrados", body = ProblemaResponse, content_type = "application/problem+json")
+    ),
+    tag = "Webhooks"
+)]
+pub async fn reenviar_entrega_webhook(
+    principal: Principal,
+    Extension(service): Extension<Arc<dyn WebhookService>>,
+    Path((id, entrega_id)): Path<(String, String)>,
+) -> Response {
+    tracing::info!(
+        "{} reenvía la entrega {} del webhook {}",
+        principal.sujeto,
+        entrega_id,
+        id
+    );
+    match service.reenviar(&principal.actor(), &id, &entrega_id).await {
+        Ok(Some(entrega)) => (StatusCode::OK, Json(EntregaResponse::from(entrega))).into_response(),
+        Ok(None) => no_encontrado(format!(
+            "Entrega {} del webhook {} no encontrada",
+            entrega_id, id
+        )),
+        Err(e) => e.en_respuesta(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -9,11 +9,19 @@
 // IMPORTANTE: Este adaptador NO conoce los detalles de implementación
 // Solo conoce los PUERTOS (traits) definidos en reservas-ports
 
+pub mod auth;
 pub mod dtos;
 pub mod handlers;
+pub mod limites;
 pub mod mappers;
+pub mod observabilidad;
 pub mod openapi;
+pub mod problema;
+pub mod respaldo;
 pub mod routes;
 
+pub use auth::{ConfigAuth, MetodoAuth, Principal};
+pub use limites::{ConfigLimites, Limite, MetricasLimites};
+pub use observabilidad::{crear_router_salud, medir_peticiones};
 pub use openapi::ApiDoc;
 pub use routes::crear_router;
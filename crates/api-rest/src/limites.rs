@@ -0,0 +1,676 @@
+// Límite de peticiones por cliente (token bucket)
+//
+// Cada regla tiene un cubo de fichas por cliente: se llena a ritmo constante
+// hasta su capacidad y cada petición gasta una. Una petición gasta de todas
+// las reglas que le aplican y solo pasa si todas tienen fichas, así que un
+// límite general se combina con otros más estrictos para rutas concretas.
+//
+// El cliente es el principal autenticado (clave de API, usuario del JWT u
+// OIDC); si la API no exige autenticación, la IP de origen. Las reglas por
+// IP se comprueban antes de autenticar, así que también cuentan las
+// peticiones con credenciales falsas que acaban en 401. Las respuestas
+// llevan las cabeceras `RateLimit-*` de la regla más cercana a agotarse y,
+// al rechazar, `Retry-After` con los segundos hasta que haya ficha.
+
+use crate::auth::{MetodoAuth, Principal};
+use crate::dtos::ProblemaResponse;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+const RATELIMIT_POLICY: HeaderName = HeaderName::from_static("ratelimit-policy");
+
+/// Cada cuánto se descartan los cubos llenos (clientes inactivos)
+const INTERVALO_LIMPIEZA: Duration = Duration::from_secs(60);
+
+/// `capacidad` peticiones por `periodo`, admitiendo ráfagas de hasta `capacidad`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limite {
+    pub capacidad: u32,
+    pub periodo: Duration,
+}
+
+impl Limite {
+    pub fn por_segundo(capacidad: u32) -> Self {
+        Self {
+            capacidad,
+            periodo: Duration::from_secs(1),
+        }
+    }
+
+    pub fn por_minuto(capacidad: u32) -> Self {
+        Self {
+            capacidad,
+            periodo: Duration::from_secs(60),
+        }
+    }
+
+    pub fn por_hora(capacidad: u32) -> Self {
+        Self {
+            capacidad,
+            periodo: Duration::from_secs(60 * 60),
+        }
+    }
+
+    /// Fichas que se recuperan por segundo
+    fn ritmo(&self) -> f64 {
+        self.capacidad as f64 / self.periodo.as_secs_f64()
+    }
+}
+
+/// Límite aplicado a las peticiones con ese método (o cualquiera) cuya ruta
+/// empieza por `prefijo` (`*` para todas)
+#[derive(Debug, Clone)]
+struct Regla {
+    metodo: Option<Method>,
+    prefijo: String,
+    limite: Limite,
+    /// Por IP y antes de autenticar, en lugar de por principal
+    por_ip: bool,
+}
+
+impl Regla {
+    fn aplica(&self, metodo: &Method, ruta: &str) -> bool {
+        if self.metodo.as_ref().is_some_and(|m| m != metodo) {
+            return false;
+        }
+        self.prefijo == "*"
+            || ruta == self.prefijo
+            || ruta
+                .strip_prefix(self.prefijo.as_str())
+                .is_some_and(|resto| resto.starts_with('/'))
+    }
+
+    fn nombre(&self) -> String {
+        let alcance = match &self.metodo {
+            Some(metodo) => format!("{} {}", metodo, self.prefijo),
+            None => self.prefijo.clone(),
+        };
+        if self.por_ip {
+            format!("ip {}", alcance)
+        } else {
+            alcance
+        }
+    }
+}
+
+/// Peticiones admitidas y rechazadas. Se comparten con `Arc` para
+/// consultarlas desde fuera del router.
+#[derive(Debug, Default)]
+pub struct MetricasLimites {
+    admitidas: AtomicU64,
+    rechazadas: AtomicU64,
+    /// Rechazos por regla, en el orden de configuración
+    rechazadas_por_regla: Mutex<Vec<(String, u64)>>,
+}
+
+impl MetricasLimites {
+    pub fn admitidas(&self) -> u64 {
+        self.admitidas.load(Ordering::Relaxed)
+    }
+
+    pub fn rechazadas(&self) -> u64 {
+        self.rechazadas.load(Ordering::Relaxed)
+    }
+
+    /// `(regla, rechazos)` de cada regla configurada
+    pub fn rechazadas_por_regla(&self) -> Vec<(String, u64)> {
+        self.rechazadas_por_regla
+            .lock()
+            .expect("métricas envenenadas")
+            .clone()
+    }
+
+    fn registrar_regla(&self, nombre: String) {
+        self.rechazadas_por_regla
+            .lock()
+            .expect("métricas envenenadas")
+            .push((nombre, 0));
+    }
+
+    fn rechazo(&self, reglas: &[usize]) {
+        self.rechazadas.fetch_add(1, Ordering::Relaxed);
+        let mut por_regla = self
+            .rechazadas_por_regla
+            .lock()
+            .expect("métricas envenenadas");
+        for &indice in reglas {
+            por_regla[indice].1 += 1;
+        }
+    }
+}
+
+/// Reglas de límite de la API. Sin reglas no se limita nada.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLimites {
+    reglas: Vec<Regla>,
+    metricas: Arc<MetricasLimites>,
+}
+
+impl ConfigLimites {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Límite por cliente para las peticiones con `metodo` (`None` =
+    /// cualquiera) bajo `prefijo` (`*` = todas las rutas)
+    pub fn con_regla(
+        self,
+        metodo: Option<Method>,
+        prefijo: impl Into<String>,
+        limite: Limite,
+    ) -> Self {
+        self.anyadir(metodo, prefijo.into(), limite, false)
+    }
+
+    /// Como `con_regla`, pero por IP de origen y antes de autenticar
+    pub fn con_regla_por_ip(
+        self,
+        metodo: Option<Method>,
+        prefijo: impl Into<String>,
+        limite: Limite,
+    ) -> Self {
+        self.anyadir(metodo, prefijo.into(), limite, true)
+    }
+
+    fn anyadir(
+        mut self,
+        metodo: Option<Method>,
+        prefijo: String,
+        limite: Limite,
+        por_ip: bool,
+    ) -> Self {
+        let regla = Regla {
+            metodo,
+            prefijo,
+            limite,
+            por_ip,
+        };
+        self.metricas.registrar_regla(regla.nombre());
+        self.reglas.push(regla);
+        self
+    }
+
+    /// Reglas por cliente en formato `[MÉTODO ]ruta=N/unidad,...` con unidad
+    /// `s`, `min` o `h`. Por ejemplo: `*=300/min, POST *=60/min, GET /disponibilidad=20/min`
+    pub fn con_reglas(mut self, lista: &str) -> Result<Self, String> {
+        for (metodo, prefijo, limite) in analizar(lista)? {
+            self = self.con_regla(metodo, prefijo, limite);
+        }
+        Ok(self)
+    }
+
+    /// Reglas por IP, en el mismo formato que `con_reglas`
+    pub fn con_reglas_por_ip(mut self, lista: &str) -> Result<Self, String> {
+        for (metodo, prefijo, limite) in analizar(lista)? {
+            self = self.con_regla_por_ip(metodo, prefijo, limite);
+        }
+        Ok(self)
+    }
+
+    pub fn esta_activa(&self) -> bool {
+        !self.reglas.is_empty()
+    }
+
+    pub fn metricas(&self) -> Arc<MetricasLimites> {
+        Arc::clone(&self.metricas)
+    }
+}
+
+fn analizar(lista: &str) -> Result<Vec<(Option<Method>, String, Limite)>, String> {
+    let mut reglas = Vec::new();
+    for entrada in lista.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let error = || {
+            format!(
+                "Límite inválido '{}': use [MÉTODO ]ruta=N/unidad (s, min o h)",
+                entrada
+            )
+        };
+        let (alcance, limite) = entrada.split_once('=').ok_or_else(error)?;
+        let (metodo, prefijo) = match alcance.trim().split_once(' ') {
+            Some((metodo, prefijo)) => {
+                let metodo =
+                    Method::from_bytes(metodo.to_uppercase().as_bytes()).map_err(|_| error())?;
+                (Some(metodo), prefijo.trim())
+            }
+            None => (None, alcance.trim()),
+        };
+        if prefijo != "*" && !prefijo.starts_with('/') {
+            return Err(error());
+        }
+        let (capacidad, unidad) = limite.trim().split_once('/').ok_or_else(error)?;
+        let capacidad: u32 = capacidad
+            .trim()
+            .parse()
+            .ok()
+            .filter(|c| *c > 0)
+            .ok_or_else(error)?;
+        let limite = match unidad.trim() {
+            "s" => Limite::por_segundo(capacidad),
+            "min" => Limite::por_minuto(capacidad),
+            "h" => Limite::por_hora(capacidad),
+            _ => return Err(error()),
+        };
+        reglas.push((metodo, prefijo.to_string(), limite));
+    }
+    Ok(reglas)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Cubo {
+    fichas: f64,
+    actualizado: Instant,
+}
+
+/// Estado de la regla más restrictiva tras una petición
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cuota {
+    limite: Limite,
+    restantes: u32,
+    /// Segundos hasta que el cubo vuelva a estar lleno
+    reinicio: u64,
+}
+
+#[derive(Debug, PartialEq)]
+enum Decision {
+    /// Sin reglas que apliquen
+    Libre,
+    Admitida(Cuota),
+    /// `espera`: segundos hasta que haya ficha en todas las reglas
+    Rechazada {
+        cuota: Cuota,
+        espera: u64,
+    },
+}
+
+/// Cubos de todos los clientes
+pub(crate) struct Limitador {
+    config: ConfigLimites,
+    cubos: Mutex<HashMap<(usize, String), Cubo>>,
+    ultima_limpieza: Mutex<Instant>,
+}
+
+impl Limitador {
+    pub(crate) fn new(config: ConfigLimites) -> Self {
+        Self {
+            config,
+            cubos: Mutex::new(HashMap::new()),
+            ultima_limpieza: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Con `por_ip` solo cuentan las reglas por IP; si no, las demás
+    fn decidir(
+        &self,
+        cliente: &str,
+        por_ip: bool,
+        metodo: &Method,
+        ruta: &str,
+        ahora: Instant,
+    ) -> Decision {
+        let aplicables: Vec<usize> = (0..self.config.reglas.len())
+            .filter(|&i| {
+                let regla = &self.config.reglas[i];
+                regla.por_ip == por_ip && regla.aplica(metodo, ruta)
+            })
+            .collect();
+        if aplicables.is_empty() {
+            return Decision::Libre;
+        }
+
+        let mut cubos = self.cubos.lock().expect("cubos envenenados");
+        self.limpiar(&mut cubos, ahora);
+
+        // Se rellenan todos los cubos y solo se gasta si todos tienen ficha
+        let mut estado = Vec::with_capacity(aplicables.len());
+        for &indice in &aplicables {
+            let limite = self.config.reglas[indice].limite;
+            let cubo = cubos.entry((indice, cliente.to_string())).or_insert(Cubo {
+                fichas: limite.capacidad as f64,
+                actualizado: ahora,
+            });
+            let transcurrido = ahora.saturating_duration_since(cubo.actualizado);
+            cubo.fichas = (cubo.fichas + transcurrido.as_secs_f64() * limite.ritmo())
+                .min(limite.capacidad as f64);
+            cubo.actualizado = ahora;
+            estado.push((indice, limite, cubo.fichas));
+        }
+
+        let agotadas: Vec<usize> = estado
+            .iter()
+            .filter(|(_, _, fichas)| *fichas < 1.0)
+            .map(|(indice, _, _)| *indice)
+            .collect();
+        if !agotadas.is_empty() {
+            self.config.metricas.rechazo(&agotadas);
+            let (espera, limite, fichas) = estado
+                .iter()
+                .filter(|(_, _, fichas)| *fichas < 1.0)
+                .map(|(_, limite, fichas)| ((1.0 - fichas) / limite.ritmo(), *limite, *fichas))
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .expect("hay al menos una regla agotada");
+            return Decision::Rechazada {
+                cuota: cuota(limite, fichas),
+                espera: espera.ceil().max(1.0) as u64,
+            };
+        }
+
+        self.config
+            .metricas
+            .admitidas
+            .fetch_add(1, Ordering::Relaxed);
+        let mut mas_restrictiva: Option<Cuota> = None;
+        for (indice, limite, fichas) in estado {
+            let fichas = fichas - 1.0;
+            if let Some(cubo) = cubos.get_mut(&(indice, cliente.to_string())) {
+                cubo.fichas = fichas;
+            }
+            let actual = cuota(limite, fichas);
+            if mas_restrictiva.is_none_or(|c| actual.restantes < c.restantes) {
+                mas_restrictiva = Some(actual);
+            }
+        }
+        Decision::Admitida(mas_restrictiva.expect("hay al menos una regla"))
+    }
+
+    /// Quita los cubos que ya se habrían llenado: equivalen a uno nuevo
+    fn limpiar(&self, cubos: &mut HashMap<(usize, String), Cubo>, ahora: Instant) {
+        let mut ultima = self.ultima_limpieza.lock().expect("limpieza envenenada");
+        if ahora.saturating_duration_since(*ultima) < INTERVALO_LIMPIEZA {
+            return;
+        }
+        *ultima = ahora;
+        let reglas = &self.config.reglas;
+        cubos.retain(|(indice, _), cubo| {
+            let limite = reglas[*indice].limite;
+            let transcurrido = ahora.saturating_duration_since(cubo.actualizado);
+            cubo.fichas + transcurrido.as_secs_f64() * limite.ritmo() < limite.capacidad as f64
+        });
+    }
+}
+
+fn cuota(limite: Limite, fichas: f64) -> Cuota {
+    let faltan = (limite.capacidad as f64 - fichas).max(0.0);
+    Cuota {
+        limite,
+        restantes: fichas.max(0.0).floor() as u32,
+        reinicio: (faltan / limite.ritmo()).ceil() as u64,
+    }
+}
+
+/// Clave del cliente: el principal o, sin autenticación, la IP
+fn cliente(request: &Request) -> String {
+    match request.extensions().get::<Principal>() {
+        Some(principal) if principal.metodo != MetodoAuth::Anonimo => {
+            format!("{:?}:{}", principal.metodo, principal.sujeto)
+        }
+        _ => ip(request),
+    }
+}
+
+fn ip(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(origen)| format!("ip:{}", origen.ip()))
+        .unwrap_or_else(|| "ip:desconocida".to_string())
+}
+
+/// Las reglas por IP y las por cliente responden en la misma petición:
+/// manda la cuota con menos peticiones restantes
+fn cabeceras_cuota(cabeceras: &mut HeaderMap, cuota: &Cuota) {
+    let restantes_previas = cabeceras
+        .get(&RATELIMIT_REMAINING)
+        .and_then(|valor| valor.to_str().ok())
+        .and_then(|valor| valor.parse::<u32>().ok());
+    if restantes_previas.is_some_and(|previas| previas <= cuota.restantes) {
+        return;
+    }
+    let valores = [
+        (RATELIMIT_LIMIT, cuota.limite.capacidad.to_string()),
+        (RATELIMIT_REMAINING, cuota.restantes.to_string()),
+        (RATELIMIT_RESET, cuota.reinicio.to_string()),
+        (
+            RATELIMIT_POLICY,
+            format!(
+                "{};w={}",
+                cuota.limite.capacidad,
+                cuota.limite.periodo.as_secs()
+            ),
+        ),
+    ];
+    for (nombre, valor) in valores {
+        if let Ok(valor) = HeaderValue::from_str(&valor) {
+            cabeceras.insert(nombre, valor);
+        }
+    }
+}
+
+/// Middleware de las reglas por cliente, detrás de `auth::autenticar`
+pub(crate) async fn limitar(
+    State(limitador): State<Arc<Limitador>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let cliente = cliente(&request);
+    aplicar(&limitador, &cliente, false, request, next).await
+}
+
+/// Middleware de las reglas por IP, delante de `auth::autenticar`
+pub(crate) async fn limitar_por_ip(
+    State(limitador): State<Arc<Limitador>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let cliente = ip(&request);
+    aplicar(&limitador, &cliente, true, request, next).await
+}
+
+async fn aplicar(
+    limitador: &Limitador,
+    cliente: &str,
+    por_ip: bool,
+    request: Request,
+    next: Next,
+) -> Response {
+    let decision = limitador.decidir(
+        cliente,
+        por_ip,
+        request.method(),
+        request.uri().path(),
+        Instant::now(),
+    );
+    match decision {
+        Decision::Libre => next.run(request).await,
+        Decision::Admitida(cuota) => {
+            let mut response = next.run(request).await;
+            cabeceras_cuota(response.headers_mut(), &cuota);
+            response
+        }
+        Decision::Rechazada { cuota, espera } => {
+            tracing::debug!("Límite de peticiones superado por {}", cliente);
+            let mut response = ProblemaResponse::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                format!(
+                    "Límite de {} peticiones cada {} s superado; reintente en {} s",
+                    cuota.limite.capacidad,
+                    cuota.limite.periodo.as_secs(),
+                    espera
+                ),
+            )
+            .into_response();
+            cabeceras_cuota(response.headers_mut(), &cuota);
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, HeaderValue::from(espera));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limitador(reglas: &str) -> Limitador {
+        Limitador::new(ConfigLimites::new().con_reglas(reglas).unwrap())
+    }
+
+    #[test]
+    fn test_formato_de_reglas() {
+        let config = ConfigLimites::new()
+            .con_reglas("*=300/min, post *=60/min, GET /disponibilidad=2/s")
+            .unwrap();
+        assert_eq!(config.reglas.len(), 3);
+        assert_eq!(config.reglas[1].metodo, Some(Method::POST));
+        assert_eq!(config.reglas[2].limite, Limite::por_segundo(2));
+        assert_eq!(
+            config.metricas().rechazadas_por_regla()[1],
+            ("POST *".to_string(), 0)
+        );
+
+        for invalida in [
+            "*=10",
+            "*=0/min",
+            "*=10/dia",
+            "disponibilidad=1/s",
+            "X Y Z=1/s",
+        ] {
+            assert!(
+                ConfigLimites::new().con_reglas(invalida).is_err(),
+                "{}",
+                invalida
+            );
+        }
+        assert!(!ConfigLimites::new().esta_activa());
+    }
+
+    #[test]
+    fn test_reglas_por_ruta_y_metodo() {
+        let regla = Regla {
+            metodo: Some(Method::GET),
+            prefijo: "/reservas".to_string(),
+            limite: Limite::por_minuto(1),
+            por_ip: false,
+        };
+        assert!(regla.aplica(&Method::GET, "/reservas"));
+        assert!(regla.aplica(&Method::GET, "/reservas/r-1"));
+        assert!(!regla.aplica(&Method::GET, "/reservas-viejas"));
+        assert!(!regla.aplica(&Method::POST, "/reservas"));
+    }
+
+    #[test]
+    fn test_agota_y_recupera_fichas() {
+        let limitador = limitador("GET /disponibilidad=2/s");
+        let t0 = Instant::now();
+        let get = Method::GET;
+
+        let Decision::Admitida(cuota) = limitador.decidir("a", false, &get, "/disponibilidad", t0)
+        else {
+            panic!("debía admitirse");
+        };
+        assert_eq!(cuota.restantes, 1);
+        assert!(matches!(
+            limitador.decidir("a", false, &get, "/disponibilidad", t0),
+            Decision::Admitida(_)
+        ));
+        let Decision::Rechazada { cuota, espera } =
+            limitador.decidir("a", false, &get, "/disponibilidad", t0)
+        else {
+            panic!("debía rechazarse");
+        };
+        assert_eq!((cuota.restantes, espera), (0, 1));
+
+        // Cada cliente tiene su cubo y otras rutas no se limitan
+        assert!(matches!(
+            limitador.decidir("b", false, &get, "/disponibilidad", t0),
+            Decision::Admitida(_)
+        ));
+        assert_eq!(
+            limitador.decidir("a", false, &get, "/salas", t0),
+            Decision::Libre
+        );
+
+        // A 2 fichas por segundo, en medio segundo hay una nueva
+        let t1 = t0 + Duration::from_millis(500);
+        assert!(matches!(
+            limitador.decidir("a", false, &get, "/disponibilidad", t1),
+            Decision::Admitida(_)
+        ));
+
+        let metricas = limitador.config.metricas();
+        assert_eq!((metricas.admitidas(), metricas.rechazadas()), (4, 1));
+    }
+
+    #[test]
+    fn test_regla_estricta_no_gasta_la_general() {
+        let limitador = limitador("*=10/min, POST *=1/min");
+        let t0 = Instant::now();
+
+        assert!(matches!(
+            limitador.decidir("a", false, &Method::POST, "/reservas", t0),
+            Decision::Admitida(_)
+        ));
+        let Decision::Rechazada { espera, .. } =
+            limitador.decidir("a", false, &Method::POST, "/reservas", t0)
+        else {
+            panic!("debía rechazarse");
+        };
+        assert_eq!(espera, 60);
+
+        // El rechazo no ha gastado ficha de la regla general
+        let Decision::Admitida(cuota) =
+            limitador.decidir("a", false, &Method::GET, "/reservas", t0)
+        else {
+            panic!("debía admitirse");
+        };
+        assert_eq!(cuota.restantes, 8);
+        assert_eq!(
+            limitador.config.metricas().rechazadas_por_regla(),
+            vec![("*".to_string(), 0), ("POST *".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_reglas_por_ip_aparte() {
+        let limitador = Limitador::new(
+            ConfigLimites::new()
+                .con_reglas("*=10/min")
+                .unwrap()
+                .con_reglas_por_ip("*=1/min")
+                .unwrap(),
+        );
+        let t0 = Instant::now();
+        let get = Method::GET;
+
+        assert!(matches!(
+            limitador.decidir("ip:10.0.0.1", true, &get, "/reservas", t0),
+            Decision::Admitida(_)
+        ));
+        assert!(matches!(
+            limitador.decidir("ip:10.0.0.1", true, &get, "/reservas", t0),
+            Decision::Rechazada { .. }
+        ));
+        // Las reglas por cliente llevan su propia cuenta
+        let Decision::Admitida(cuota) = limitador.decidir("ApiKey:a", false, &get, "/reservas", t0)
+        else {
+            panic!("debía admitirse");
+        };
+        assert_eq!(cuota.limite, Limite::por_minuto(10));
+        assert_eq!(
+            limitador.config.metricas().rechazadas_por_regla(),
+            vec![("*".to_string(), 0), ("ip *".to_string(), 1)]
+        );
+    }
+}
@@ -3,7 +3,22 @@
 
 use crate::dtos::*;
 use chrono::Timelike;
-use reservas_domain::{Empleado, EstadoReserva, Reserva, Sala, Slot};
+use reservas_domain::informe::DIAS_MAS_OCUPADOS;
+use reservas_domain::{
+    DisponibilidadSalaSlot, DisponibilidadSlot, Empleado, ErrorCampo, EstadoReserva, FilaMatriz,
+    InformeOcupacion, MatrizDisponibilidad, Reserva, Sala, Slot, TipoFila, UsoDia, UsoHora,
+    UsoRecurso,
+};
+use reservas_ports::out::auditoria::IntentoDenegado;
+use reservas_ports::out::webhooks::{Entrega, EstadoEntrega, IntentoEntrega, Webhook};
+use reservas_ports::r#in::carga_masiva_service::{FilaCarga, InformeCarga, ResultadoFila};
+use reservas_ports::r#in::disponibilidad_en_vivo_service::{
+    ActualizacionDisponibilidad, EventoDisponibilidad,
+};
+use reservas_ports::r#in::importacion_service::{
+    ElementoImportacion, InformeImportacion, ResultadoImportacion,
+};
+use reservas_ports::r#in::integridad_service::{Incidencia, InformeIntegridad, TipoIncidencia};
 
 // ============= Mappers de Empleado =============
 
@@ -12,8 +27,18 @@ impl From<Empleado> for EmpleadoResponse {
         EmpleadoResponse {
             id: empleado.id,
             nombre: empleado.nombre,
-            email: empleado.email,
+            email: empleado.email.to_string(),
             activo: empleado.activo,
+            responsable_id: empleado.responsable_id,
+        }
+    }
+}
+
+impl From<ErrorCampo> for ErrorCampoResponse {
+    fn from(error: ErrorCampo) -> Self {
+        ErrorCampoResponse {
+            campo: error.campo,
+            mensaje: error.mensaje,
         }
     }
 }
@@ -22,30 +47,44 @@ impl From<Empleado> for EmpleadoResponse {
 
 impl From<Reserva> for ReservaResponse {
     fn from(reserva: Reserva) -> Self {
-        let estado_str = match reserva.estado {
-            EstadoReserva::Pendiente => "pendiente",
-            EstadoReserva::Confirmada => "confirmada",
-            EstadoReserva::Cancelada => "cancelada",
-        };
-
         ReservaResponse {
             id: reserva.id,
             empleado_id: reserva.empleado_id,
             slot_inicio: reserva.slot.inicio,
             slot_fin: reserva.slot.fin(),
             descripcion: reserva.descripcion,
-            estado: estado_str.to_string(),
+            estado: estado_a_str(&reserva.estado).to_string(),
+            sala_id: reserva.sala_id,
         }
     }
 }
 
+/// Nombre del estado tal y como aparece en la API
+pub fn estado_a_str(estado: &EstadoReserva) -> &'static str {
+    match estado {
+        EstadoReserva::Pendiente => "pendiente",
+        EstadoReserva::Confirmada => "confirmada",
+        EstadoReserva::Cancelada => "cancelada",
+    }
+}
+
+/// Convierte el estado recibido en la API ("pendiente", ...) al del dominio
+pub fn estado_desde_str(estado: &str) -> Option<EstadoReserva> {
+    match estado {
+        "pendiente" => Some(EstadoReserva::Pendiente),
+        "confirmada" => Some(EstadoReserva::Confirmada),
+        "cancelada" => Some(EstadoReserva::Cancelada),
+        _ => None,
+    }
+}
+
 impl From<Sala> for SalaResponse {
     fn from(sala: Sala) -> Self {
         SalaResponse {
             id: sala.id,
             nombre: sala.nombre,
             capacidad: sala.capacidad,
-            activa: true,
+            activa: sala.activa,
         }
     }
 }
@@ -60,3 +99,317 @@ impl From<&Slot> for SlotInfo {
         }
     }
 }
+
+// ============= Mappers de Disponibilidad =============
+
+impl From<DisponibilidadSlot> for DisponibilidadEmpleadoResponse {
+    fn from(d: DisponibilidadSlot) -> Self {
+        DisponibilidadEmpleadoResponse {
+            empleado_id: d.empleado_id,
+            empleado_nombre: d.empleado_nombre,
+            slot_inicio: d.slot.inicio,
+            slot_fin: d.slot.fin(),
+            disponible: d.disponible,
+            reserva_id: d.reserva_id,
+            descripcion_reserva: d.descripcion_reserva,
+        }
+    }
+}
+
+impl From<DisponibilidadSalaSlot> for DisponibilidadSalaResponse {
+    fn from(d: DisponibilidadSalaSlot) -> Self {
+        DisponibilidadSalaResponse {
+            sala_id: d.sala_id,
+            slot_inicio: d.slot.inicio,
+            slot_fin: d.slot.fin(),
+            disponible: d.disponible,
+            reserva_id: d.reserva_id,
+        }
+    }
+}
+
+impl From<ActualizacionDisponibilidad> for ActualizacionDisponibilidadResponse {
+    fn from(a: ActualizacionDisponibilidad) -> Self {
+        ActualizacionDisponibilidadResponse {
+            cambio: a.cambio.as_str().to_string(),
+            reserva_id: a.reserva_id,
+            sala_id: a.sala_id,
+            slots: a.slots.into_iter().map(Into::into).collect(),
+            salas: a.salas.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<EventoDisponibilidad> for EventoDisponibilidadResponse {
+    fn from(evento: EventoDisponibilidad) -> Self {
+        match evento {
+            EventoDisponibilidad::Actualizacion(a) => Self::Disponibilidad(a.into()),
+            EventoDisponibilidad::Resincronizar => Self::Resincronizar,
+        }
+    }
+}
+
+// ============= Mappers de Integridad =============
+
+impl From<Incidencia> for IncidenciaResponse {
+    fn from(incidencia: Incidencia) -> Self {
+        let tipo = match incidencia.tipo {
+            TipoIncidencia::EmpleadoInexistente => "empleado_inexistente",
+            TipoIncidencia::SalaInexistente => "sala_inexistente",
+            TipoIncidencia::Solapamiento => "solapamiento",
+            TipoIncidencia::EmpleadoInactivo => "empleado_inactivo",
+            TipoIncidencia::SalaInactiva => "sala_inactiva",
+            TipoIncidencia::FueraDeHorario => "fuera_de_horario",
+        };
+
+        IncidenciaResponse {
+            tipo: tipo.to_string(),
+            reserva_id: incidencia.reserva_id,
+            detalle: incidencia.detalle,
+            reparada: incidencia.reparada,
+        }
+    }
+}
+
+impl From<InformeIntegridad> for InformeIntegridadResponse {
+    fn from(informe: InformeIntegridad) -> Self {
+        InformeIntegridadResponse {
+            reservas_revisadas: informe.reservas_revisadas,
+            consistente: informe.es_consistente(),
+            reparadas: informe.reparadas(),
+            incidencias: informe.incidencias.into_iter().map(|i| i.into()).collect(),
+        }
+    }
+}
+
+// ============= Mappers de Auditoría =============
+
+impl From<IntentoDenegado> for IntentoDenegadoResponse {
+    fn from(intento: IntentoDenegado) -> Self {
+        IntentoDenegadoResponse {
+            fecha: intento.fecha,
+            sujeto: intento.sujeto,
+            accion: intento.accion.to_string(),
+            recurso: intento.recurso,
+        }
+    }
+}
+
+// ============= Mappers de Importación =============
+
+impl From<ElementoImportacion> for ElementoImportacionResponse {
+    fn from(elemento: ElementoImportacion) -> Self {
+        let resultado = match elemento.resultado {
+            ResultadoImportacion::Importada => "importada",
+            ResultadoImportacion::Conflicto => "conflicto",
+            ResultadoImportacion::Omitida => "omitida",
+        };
+        ElementoImportacionResponse {
+            uid: elemento.uid,
+            email: elemento.email,
+            empleado_id: elemento.empleado_id,
+            slot_inicio: elemento.slot.map(|s| s.inicio),
+            resultado: resultado.to_string(),
+            detalle: elemento.detalle,
+            reserva_id: elemento.reserva_id,
+        }
+    }
+}
+
+impl InformeImportacionResponse {
+    pub fn desde(informe: InformeImportacion, simulado: bool) -> Self {
+        InformeImportacionResponse {
+            eventos: informe.eventos,
+            importadas: informe.contar(ResultadoImportacion::Importada),
+            conflictos: informe.contar(ResultadoImportacion::Conflicto),
+            omitidas: informe.contar(ResultadoImportacion::Omitida),
+            simulado,
+            elementos: informe.elementos.into_iter().map(|e| e.into()).collect(),
+        }
+    }
+}
+
+// ============= Mappers de la matriz de disponibilidad =============
+
+impl From<FilaMatriz> for FilaMatrizResponse {
+    fn from(fila: FilaMatriz) -> Self {
+        let libres = fila.libres();
+        let ocupacion = fila
+            .celdas
+            .iter()
+            .map(|dia| {
+                dia.iter()
+                    .map(|celda| match celda {
+                        Some(EstadoReserva::Pendiente) => 'P',
+                        Some(EstadoReserva::Confirmada) => 'C',
+                        None | Some(EstadoReserva::Cancelada) => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        FilaMatrizResponse {
+            tipo: match fila.tipo {
+                TipoFila::Empleado => "empleado",
+                TipoFila::Sala => "sala",
+            }
+            .to_string(),
+            id: fila.id,
+            nombre: fila.nombre,
+            libres,
+            ocupacion,
+        }
+    }
+}
+
+impl From<MatrizDisponibilidad> for MatrizDisponibilidadResponse {
+    fn from(matriz: MatrizDisponibilidad) -> Self {
+        MatrizDisponibilidadResponse {
+            desde: matriz.periodo.desde.format("%Y-%m-%d").to_string(),
+            hasta: matriz.periodo.hasta.format("%Y-%m-%d").to_string(),
+            dias: matriz
+                .dias
+                .iter()
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .collect(),
+            horas: matriz.horas,
+            filas: matriz.filas.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+// ============= Mappers de Cargas masivas =============
+
+impl From<FilaCarga> for FilaCargaResponse {
+    fn from(fila: FilaCarga) -> Self {
+        let resultado = match fila.resultado {
+            ResultadoFila::Creada => "creada",
+            ResultadoFila::Actualizada => "actualizada",
+            ResultadoFila::SinCambios => "sin_cambios",
+            ResultadoFila::Error => "error",
+        };
+        FilaCargaResponse {
+            fila: fila.fila,
+            clave: fila.clave,
+            resultado: resultado.to_string(),
+            errores: fila.errores.into_iter().map(|e| e.into()).collect(),
+            id: fila.id,
+        }
+    }
+}
+
+impl InformeCargaResponse {
+    pub fn desde(informe: InformeCarga, simulado: bool) -> Self {
+        InformeCargaResponse {
+            creadas: informe.contar(ResultadoFila::Creada),
+            actualizadas: informe.contar(ResultadoFila::Actualizada),
+            sin_cambios: informe.contar(ResultadoFila::SinCambios),
+            errores: informe.contar(ResultadoFila::Error),
+            simulado,
+            filas: informe.filas.into_iter().map(|f| f.into()).collect(),
+        }
+    }
+}
+
+// ============= Mappers de Informes =============
+
+impl From<UsoRecurso> for UsoRecursoResponse {
+    fn from(uso: UsoRecurso) -> Self {
+        UsoRecursoResponse {
+            id: uso.id,
+            nombre: uso.nombre,
+            reservas: uso.reservas,
+            canceladas: uso.canceladas,
+            ocupacion: uso.ocupacion,
+        }
+    }
+}
+
+impl From<UsoHora> for UsoHoraResponse {
+    fn from(uso: UsoHora) -> Self {
+        UsoHoraResponse {
+            hora: uso.hora,
+            reservas: uso.reservas,
+            ocupacion: uso.ocupacion,
+        }
+    }
+}
+
+impl From<&UsoDia> for UsoDiaResponse {
+    fn from(uso: &UsoDia) -> Self {
+        UsoDiaResponse {
+            fecha: uso.fecha.format("%Y-%m-%d").to_string(),
+            reservas: uso.reservas,
+            ocupacion: uso.ocupacion,
+        }
+    }
+}
+
+impl From<InformeOcupacion> for InformeOcupacionResponse {
+    fn from(informe: InformeOcupacion) -> Self {
+        let dias_mas_ocupados = informe
+            .dias_mas_ocupados(DIAS_MAS_OCUPADOS)
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        InformeOcupacionResponse {
+            desde: informe.periodo.desde.format("%Y-%m-%d").to_string(),
+            hasta: informe.periodo.hasta.format("%Y-%m-%d").to_string(),
+            total: informe.total,
+            pendientes: informe.pendientes,
+            confirmadas: informe.confirmadas,
+            canceladas: informe.canceladas,
+            no_presentadas: informe.no_presentadas,
+            tasa_cancelacion: informe.tasa_cancelacion,
+            tasa_no_presentacion: informe.tasa_no_presentacion,
+            por_empleado: informe.por_empleado.into_iter().map(Into::into).collect(),
+            por_sala: informe.por_sala.into_iter().map(Into::into).collect(),
+            por_hora: informe.por_hora.into_iter().map(Into::into).collect(),
+            por_dia: informe.por_dia.iter().map(Into::into).collect(),
+            dias_mas_ocupados,
+        }
+    }
+}
+
+// ============= Mappers de Webhooks =============
+
+/// Sin el secreto, que solo se muestra al darlo de alta
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        WebhookResponse {
+            id: webhook.id,
+            url: webhook.url,
+            eventos: webhook.eventos.into_iter().map(|e| e.evento()).collect(),
+            secreto: None,
+            creado: webhook.creado,
+        }
+    }
+}
+
+impl From<IntentoEntrega> for IntentoEntregaResponse {
+    fn from(intento: IntentoEntrega) -> Self {
+        IntentoEntregaResponse {
+            fecha: intento.fecha,
+            codigo: intento.codigo,
+            error: intento.error,
+        }
+    }
+}
+
+impl From<Entrega> for EntregaResponse {
+    fn from(entrega: Entrega) -> Self {
+        let estado = match entrega.estado {
+            EstadoEntrega::Pendiente => "pendiente",
+            EstadoEntrega::Entregada => "entregada",
+            EstadoEntrega::Fallida => "fallida",
+        };
+        EntregaResponse {
+            id: entrega.id,
+            webhook_id: entrega.webhook_id,
+            evento: entrega.evento.evento(),
+            estado: estado.to_string(),
+            cuerpo: entrega.cuerpo,
+            intentos: entrega.intentos.into_iter().map(Into::into).collect(),
+            creada: entrega.creada,
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// Salud y métricas del servidor
+//
+// - `/health`: el proceso está vivo y atiende peticiones (liveness)
+// - `/ready`: los repositorios responden; si no, 503 para que el
+//   balanceador deje de mandar tráfico (readiness)
+// - `/metrics`: métricas en formato de texto de Prometheus
+//
+// Van fuera de `/api` y sin autenticación, como esperan los orquestadores.
+// `medir_peticiones` es un middleware para el router completo (web, API y
+// CalDAV) que anota cada petición por la plantilla de su ruta.
+
+use crate::dtos::{ComponenteResponse, SaludResponse};
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use reservas_ports::out::metricas::Metricas;
+use reservas_ports::r#in::salud_service::SaludService;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Tipo MIME del formato de exposición de texto de Prometheus
+pub const CONTENT_TYPE_METRICAS: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Etiqueta de las peticiones que no corresponden a ninguna ruta, para que
+/// las URLs inventadas no creen series nuevas
+const RUTA_DESCONOCIDA: &str = "desconocida";
+
+pub fn crear_router_salud(salud: Arc<dyn SaludService>, metricas: Arc<dyn Metricas>) -> Router {
+    Router::new()
+        .route("/health", get(vivo))
+        .route("/ready", get(preparado))
+        .route("/metrics", get(exponer_metricas))
+        .layer(Extension(salud))
+        .layer(Extension(metricas))
+}
+
+async fn vivo() -> Json<SaludResponse> {
+    Json(SaludResponse {
+        estado: "ok".to_string(),
+        componentes: Vec::new(),
+    })
+}
+
+async fn preparado(Extension(salud): Extension<Arc<dyn SaludService>>) -> Response {
+    let estado = salud.preparacion().await;
+    let (codigo, texto) = if estado.preparado() {
+        (StatusCode::OK, "preparado")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "no_preparado")
+    };
+    let cuerpo = SaludResponse {
+        estado: texto.to_string(),
+        componentes: estado
+            .componentes
+            .into_iter()
+            .map(|c| ComponenteResponse {
+                nombre: c.nombre,
+                ok: c.error.is_none(),
+                error: c.error,
+            })
+            .collect(),
+    };
+    (codigo, Json(cuerpo)).into_response()
+}
+
+async fn exponer_metricas(Extension(metricas): Extension<Arc<dyn Metricas>>) -> Response {
+    (
+        [(header::CONTENT_TYPE, CONTENT_TYPE_METRICAS)],
+        metricas.exponer(),
+    )
+        .into_response()
+}
+
+/// Middleware: cuenta las peticiones y su duración por método, plantilla de
+/// ruta y código de respuesta. Con `Router::layer` se ejecuta después de
+/// elegir la ruta, así que `MatchedPath` ya está disponible.
+pub async fn medir_peticiones(
+    State(metricas): State<Arc<dyn Metricas>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let metodo = request.method().to_string();
+    let ruta = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|ruta| ruta.as_str().to_string())
+        .unwrap_or_else(|| RUTA_DESCONOCIDA.to_string());
+    let inicio = Instant::now();
+    let respuesta = next.run(request).await;
+    metricas.peticion_http(
+        &metodo,
+        &ruta,
+        respuesta.status().as_u16(),
+        inicio.elapsed(),
+    );
+    respuesta
+}
@@ -1,14 +1,41 @@
 // Configuración de OpenAPI/Swagger para la documentación de la API
 
 use crate::dtos::*;
-use utoipa::OpenApi;
+use crate::problema::CONTENT_TYPE_PROBLEMA;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{ContentBuilder, Ref, ResponseBuilder};
+use utoipa::{Modify, OpenApi};
 
 #[derive(OpenApi)]
 #[openapi(
     info(
         title = "Sistema de Reservas - API REST",
         version = "1.0.0",
-        description = "API REST para el sistema de reservas de empleados con arquitectura hexagonal (Puertos y Adaptadores)",
+        description = "API REST para el sistema de reservas de empleados con arquitectura hexagonal (Puertos y Adaptadores).
+
+Los errores se devuelven como `application/problem+json` (RFC 7807) con los campos `type`, `title`, `status`, `detail` e `instance`. Tipos de problema:
+
+- `urn:reservas:problema:validacion`: datos inválidos; el error de cada campo va en `campos`
+- `urn:reservas:problema:peticion-invalida`: petición mal formada o rechazada
+- `urn:reservas:problema:no-autenticado`: falta la credencial o no es válida
+- `urn:reservas:problema:prohibido`: el principal no tiene permiso para la operación
+- `urn:reservas:problema:no-encontrado`: el recurso o la ruta no existe
+- `urn:reservas:problema:metodo-no-permitido`: método HTTP no soportado por la ruta
+- `urn:reservas:problema:conflicto`: la operación choca con el estado actual (p. ej. borrar con reservas)
+- `urn:reservas:problema:demasiadas-peticiones`: se ha superado el límite de peticiones; reintente tras `Retry-After` segundos
+- `urn:reservas:problema:error-interno`: fallo del servidor
+
+Todas las rutas salvo esta documentación y los calendarios `.ics` requieren autenticación: `Authorization: Bearer <token>` con un JWT (HS256 o RS256), un access token del proveedor OpenID Connect configurado o una clave de API, o bien la cabecera `X-API-Key: <clave>`.
+
+Los permisos dependen de los roles del principal (`empleado`, `responsable`, `admin-salas`, `admin`): cada empleado crea, modifica y cancela sus propias reservas, un responsable confirma las de las personas a su cargo, `admin-salas` gestiona las salas y `admin` puede hacerlo todo. Los intentos denegados se registran y se consultan en `/admin/auditoria`.
+
+El servidor puede limitar las peticiones de cada cliente (clave de API o usuario). Las respuestas limitadas llevan `RateLimit-Limit`, `RateLimit-Remaining`, `RateLimit-Reset` y `RateLimit-Policy`; al superar el límite se responde 429 con `Retry-After`.
+
+Cada empleado y cada sala tiene un calendario iCalendar (`text/calendar`) para suscribirse desde cualquier cliente de calendario. `GET /empleados/{id}/calendario` y `GET /salas/{id}/calendario` devuelven su URL privada `.../calendario.ics?token=<token>`, que funciona sin credenciales; `POST .../calendario/revocar` la invalida y devuelve otra. Cada reserva es un VEVENT con UID `<id de la reserva>@reservas` y STATUS `TENTATIVE` (pendiente), `CONFIRMED` o `CANCELLED`.
+
+`POST /reservas/importar` importa reservas desde un fichero iCalendar de otro sistema: cada asistente (`ATTENDEE:mailto:`) que es empleado recibe una reserva por cada slot de una hora que ocupa el evento. Con `simular=true` solo se devuelve el informe de lo que se importaría, los conflictos y lo que se omite (eventos cancelados, periódicos o de día completo, asistentes desconocidos, slots pasados o fuera de horario).
+
+Los administradores pueden suscribir webhooks a los eventos de las reservas (`reserva.creada`, `reserva.confirmada`, `reserva.cancelada`, `reserva.modificada`, `reserva.reprogramada`, `reserva.reasignada`, `reserva.eliminada`). Cada entrega es un POST JSON con las cabeceras `X-Reservas-Evento`, `X-Reservas-Entrega` y `X-Reservas-Firma: t=<marca unix>,v1=<hex>`, donde `v1` es el HMAC-SHA256 de `<marca>.<cuerpo>` con el secreto del webhook. Si el receptor no responde 2xx se reintenta con espera exponencial; las entregas quedan en `/webhooks/{id}/entregas` y se pueden reenviar a mano.",
         contact(
             name = "Sistema de Reservas",
             email = "contacto@reservas.com"
@@ -17,42 +44,204 @@ use utoipa::OpenApi;
     servers(
         (url = "/api", description = "API REST Server")
     ),
+    modifiers(&Seguridad),
+    security(
+        ("bearer" = []),
+        ("api_key" = [])
+    ),
     paths(
         crate::handlers::empleados::crear_empleado,
         crate::handlers::empleados::listar_empleados,
         crate::handlers::empleados::obtener_empleado,
         crate::handlers::empleados::desactivar_empleado,
         crate::handlers::empleados::activar_empleado,
+        crate::handlers::empleados::reemplazar_empleado,
+        crate::handlers::empleados::modificar_empleado,
+        crate::handlers::empleados::eliminar_empleado,
         crate::handlers::reservas::crear_reserva,
         crate::handlers::reservas::listar_reservas,
         crate::handlers::reservas::obtener_reserva,
         crate::handlers::reservas::listar_reservas_empleado,
         crate::handlers::reservas::confirmar_reserva,
         crate::handlers::reservas::cancelar_reserva,
+        crate::handlers::reservas::reemplazar_reserva,
+        crate::handlers::reservas::modificar_reserva,
+        crate::handlers::reservas::reprogramar_reserva,
+        crate::handlers::reservas::eliminar_reserva,
+        crate::handlers::importacion::importar_reservas,
+        crate::handlers::carga_masiva::importar_empleados,
+        crate::handlers::carga_masiva::exportar_empleados,
+        crate::handlers::carga_masiva::importar_salas,
+        crate::handlers::carga_masiva::exportar_salas,
+        crate::handlers::carga_masiva::exportar_reservas,
         crate::handlers::disponibilidad::obtener_disponibilidad,
+        crate::handlers::disponibilidad::matriz_disponibilidad,
+        crate::handlers::disponibilidad::eventos_disponibilidad,
+        crate::handlers::disponibilidad::disponibilidad_ws,
+        crate::handlers::informes::informe_ocupacion,
         crate::handlers::salas::listar_salas,
         crate::handlers::salas::crear_sala,
+        crate::handlers::salas::reemplazar_sala,
+        crate::handlers::salas::modificar_sala,
+        crate::handlers::salas::eliminar_sala,
+        crate::handlers::salas::disponibilidad_salas,
+        crate::handlers::salas::buscar_salas_libres,
+        crate::handlers::admin::exportar_respaldo,
+        crate::handlers::admin::restaurar_respaldo,
+        crate::handlers::admin::verificar_integridad,
+        crate::handlers::admin::reparar_integridad,
+        crate::handlers::admin::listar_auditoria,
+        crate::handlers::admin::metricas_limites,
+        crate::handlers::calendario::calendario_empleado,
+        crate::handlers::calendario::revocar_calendario_empleado,
+        crate::handlers::calendario::calendario_sala,
+        crate::handlers::calendario::revocar_calendario_sala,
+        crate::handlers::calendario::feed_empleado,
+        crate::handlers::calendario::feed_sala,
+        crate::handlers::webhooks::crear_webhook,
+        crate::handlers::webhooks::listar_webhooks,
+        crate::handlers::webhooks::obtener_webhook,
+        crate::handlers::webhooks::eliminar_webhook,
+        crate::handlers::webhooks::listar_entregas_webhook,
+        crate::handlers::webhooks::reenviar_entrega_webhook,
     ),
     components(
         schemas(
             CrearEmpleadoRequest,
+            ActualizarEmpleadoRequest,
+            ModificarEmpleadoRequest,
             EmpleadoResponse,
             CrearReservaRequest,
+            ActualizarReservaRequest,
+            ModificarReservaRequest,
+            ReprogramarReservaRequest,
             ReservaResponse,
             DisponibilidadEmpleadoResponse,
+            DisponibilidadSalaResponse,
             TablaDisponibilidadResponse,
+            MatrizDisponibilidadResponse,
+            FilaMatrizResponse,
+            InformeOcupacionResponse,
+            UsoRecursoResponse,
+            UsoHoraResponse,
+            UsoDiaResponse,
             SlotInfo,
-            ErrorResponse,
+            ActualizacionDisponibilidadResponse,
+            EventoDisponibilidadResponse,
+            ProblemaResponse,
+            ErrorCampoResponse,
             MensajeResponse,
             CrearSalaRequest,
-            SalaResponse
+            ActualizarSalaRequest,
+            ModificarSalaRequest,
+            SalaResponse,
+            PaginaReservasResponse,
+            PaginaEmpleadosResponse,
+            PaginaSalasResponse,
+            ResumenRestauracionResponse,
+            IncidenciaResponse,
+            InformeIntegridadResponse,
+            IntentoDenegadoResponse,
+            MetricasLimitesResponse,
+            ReglaLimiteResponse,
+            CalendarioResponse,
+            ElementoImportacionResponse,
+            InformeImportacionResponse,
+            CargaMasivaForm,
+            FilaCargaResponse,
+            InformeCargaResponse,
+            CrearWebhookRequest,
+            WebhookResponse,
+            EntregaResponse,
+            IntentoEntregaResponse
         )
     ),
     tags(
         (name = "Empleados", description = "Gestión de empleados"),
         (name = "Reservas", description = "Gestión de reservas de tiempo"),
-        (name = "Disponibilidad", description = "Consulta de disponibilidad de empleados"),
+        (name = "Disponibilidad", description = "Consulta de disponibilidad de empleados, también en tiempo real"),
         (name = "Salas", description = "Gestión de salas"),
+        (name = "Informes", description = "Informes de uso de empleados y salas"),
+        (name = "Administración", description = "Copias de seguridad, verificación de integridad, auditoría y límites de peticiones"),
+        (name = "Calendarios", description = "Calendarios iCalendar suscribibles por empleado y por sala"),
+        (name = "Webhooks", description = "Avisos a sistemas externos de los cambios en las reservas"),
     )
 )]
 pub struct ApiDoc;
+
+/// Esquemas de autenticación, las respuestas 401 (salvo en los calendarios
+/// `.ics`, que van con token) y 429 comunes a todas las rutas y la 403 de las que comprueban permisos (las que modifican y las de
+/// `/admin` y `/webhooks`)
+struct Seguridad;
+
+impl Modify for Seguridad {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .description(Some("JWT HS256/RS256, access token OIDC o clave de API"))
+                        .build(),
+                ),
+            );
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+            );
+        }
+
+        let no_autenticado = ResponseBuilder::new()
+            .description("Falta la credencial o no es válida")
+            .content(
+                CONTENT_TYPE_PROBLEMA,
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name("ProblemaResponse")))
+                    .build(),
+            )
+            .build();
+        let prohibido = ResponseBuilder::new()
+            .description("Sin permiso para la operación")
+            .content(
+                CONTENT_TYPE_PROBLEMA,
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name("ProblemaResponse")))
+                    .build(),
+            )
+            .build();
+        let limite_superado = ResponseBuilder::new()
+            .description("Límite de peticiones superado; reintentar tras `Retry-After` segundos")
+            .content(
+                CONTENT_TYPE_PROBLEMA,
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name("ProblemaResponse")))
+                    .build(),
+            )
+            .build();
+        for (ruta, item) in openapi.paths.paths.iter_mut() {
+            let es_admin = ruta.starts_with("/admin") || ruta.starts_with("/webhooks");
+            let operaciones = [
+                (&mut item.get, es_admin),
+                (&mut item.post, true),
+                (&mut item.put, true),
+                (&mut item.patch, true),
+                (&mut item.delete, true),
+            ];
+            for (operacion, con_permisos) in operaciones {
+                let Some(operacion) = operacion else {
+                    continue;
+                };
+                let respuestas = &mut operacion.responses.responses;
+                if !ruta.ends_with(".ics") {
+                    respuestas.insert("401".to_string(), no_autenticado.clone().into());
+                }
+                respuestas.insert("429".to_string(), limite_superado.clone().into());
+                if con_permisos {
+                    respuestas.insert("403".to_string(), prohibido.clone().into());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+// Errores de la API en formato RFC 7807 (application/problem+json)
+//
+// Todos los errores salen con el mismo cuerpo `ProblemaResponse`:
+// - los handlers lo construyen con `ProblemaResponse::new` o `::validacion`
+// - el middleware `normalizar_errores` rellena `instance` con la ruta pedida
+//   y convierte al mismo formato los errores que genera Axum por su cuenta
+//   (JSON mal formado, ruta o método inexistente...)
+
+use crate::dtos::{ErrorCampoResponse, ProblemaResponse};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{OriginalUri, Request},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// Tipo MIME de los errores (RFC 7807)
+pub const CONTENT_TYPE_PROBLEMA: &str = "application/problem+json";
+
+/// Tamaño máximo del cuerpo de un error de Axum que se copia en `detail`
+const LIMITE_DETALLE: usize = 16 * 1024;
+
+/// Tipos de problema que devuelve la API. El `type` es un URN estable que
+/// los clientes pueden comparar sin depender del texto de `title`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoProblema {
+    /// Datos inválidos; el detalle por campo va en `campos`
+    Validacion,
+    PeticionInvalida,
+    NoAutenticado,
+    /// Autenticado, pero sin permiso para la operación
+    Prohibido,
+    NoEncontrado,
+    MetodoNoPermitido,
+    Conflicto,
+    /// Límite de peticiones superado
+    DemasiadasPeticiones,
+    ErrorInterno,
+}
+
+impl TipoProblema {
+    pub fn uri(self) -> &'static str {
+        match self {
+            TipoProblema::Validacion => "urn:reservas:problema:validacion",
+            TipoProblema::PeticionInvalida => "urn:reservas:problema:peticion-invalida",
+            TipoProblema::NoAutenticado => "urn:reservas:problema:no-autenticado",
+            TipoProblema::Prohibido => "urn:reservas:problema:prohibido",
+            TipoProblema::NoEncontrado => "urn:reservas:problema:no-encontrado",
+            TipoProblema::MetodoNoPermitido => "urn:reservas:problema:metodo-no-permitido",
+            TipoProblema::Conflicto => "urn:reservas:problema:conflicto",
+            TipoProblema::DemasiadasPeticiones => "urn:reservas:problema:demasiadas-peticiones",
+            TipoProblema::ErrorInterno => "urn:reservas:problema:error-interno",
+        }
+    }
+
+    pub fn titulo(self) -> &'static str {
+        match self {
+            TipoProblema::Validacion => "Datos inválidos",
+            TipoProblema::PeticionInvalida => "Petición inválida",
+            TipoProblema::NoAutenticado => "Autenticación requerida",
+            TipoProblema::Prohibido => "Operación no permitida",
+            TipoProblema::NoEncontrado => "Recurso no encontrado",
+            TipoProblema::MetodoNoPermitido => "Método no permitido",
+            TipoProblema::Conflicto => "Conflicto con el estado actual",
+            TipoProblema::DemasiadasPeticiones => "Demasiadas peticiones",
+            TipoProblema::ErrorInterno => "Error interno",
+        }
+    }
+
+    fn desde_status(status: StatusCode) -> Option<Self> {
+        match status {
+            StatusCode::BAD_REQUEST
+            | StatusCode::UNPROCESSABLE_ENTITY
+            | StatusCode::UNSUPPORTED_MEDIA_TYPE
+            | StatusCode::PAYLOAD_TOO_LARGE => Some(TipoProblema::PeticionInvalida),
+            StatusCode::UNAUTHORIZED => Some(TipoProblema::NoAutenticado),
+            StatusCode::FORBIDDEN => Some(TipoProblema::Prohibido),
+            StatusCode::NOT_FOUND => Some(TipoProblema::NoEncontrado),
+            StatusCode::METHOD_NOT_ALLOWED => Some(TipoProblema::MetodoNoPermitido),
+            StatusCode::CONFLICT => Some(TipoProblema::Conflicto),
+            StatusCode::TOO_MANY_REQUESTS => Some(TipoProblema::DemasiadasPeticiones),
+            s if s.is_server_error() => Some(TipoProblema::ErrorInterno),
+            _ => None,
+        }
+    }
+}
+
+impl ProblemaResponse {
+    /// Problema genérico para el código de estado dado
+    pub fn new(status: StatusCode, detalle: impl Into<String>) -> Self {
+        let (tipo, titulo) = match TipoProblema::desde_status(status) {
+            Some(tipo) => (tipo.uri().to_string(), tipo.titulo().to_string()),
+            // RFC 7807: sin tipo propio, "about:blank" y el texto del estado HTTP
+            None => (
+                "about:blank".to_string(),
+                status.canonical_reason().unwrap_or("Error").to_string(),
+            ),
+        };
+        Self {
+            tipo,
+            titulo,
+            estado: status.as_u16(),
+            detalle: detalle.into(),
+            instancia: None,
+            campos: Vec::new(),
+        }
+    }
+
+    /// 400 con el error de cada campo rechazado
+    pub fn validacion(detalle: impl Into<String>, campos: Vec<ErrorCampoResponse>) -> Self {
+        Self {
+            tipo: TipoProblema::Validacion.uri().to_string(),
+            titulo: TipoProblema::Validacion.titulo().to_string(),
+            campos,
+            ..Self::new(StatusCode::BAD_REQUEST, detalle)
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.estado).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn en_respuesta(&self) -> Response {
+        let mut response = (self.status(), Json(self)).into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(CONTENT_TYPE_PROBLEMA),
+        );
+        response
+    }
+}
+
+impl IntoResponse for ProblemaResponse {
+    fn into_response(self) -> Response {
+        let mut response = self.en_respuesta();
+        // El middleware lo recoge para completar `instance`
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// Rutas de la API que no existen
+pub(crate) async fn ruta_no_encontrada(OriginalUri(uri): OriginalUri) -> ProblemaResponse {
+    ProblemaResponse::new(
+        StatusCode::NOT_FOUND,
+        format!("No existe la ruta {}", uri.path()),
+    )
+}
+
+/// Middleware: toda respuesta de error sale como problem+json con `instance`
+pub(crate) async fn normalizar_errores(
+    OriginalUri(uri): OriginalUri,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return response;
+    }
+
+    let mut problema = match response.extensions_mut().remove::<ProblemaResponse>() {
+        Some(problema) => problema,
+        // Error generado fuera de los handlers (rechazo de un extractor,
+        // método no permitido...): su cuerpo en texto pasa a `detail`
+        None => {
+            let cuerpo = std::mem::replace(response.body_mut(), Body::empty());
+            let texto = to_bytes(cuerpo, LIMITE_DETALLE)
+                .await
+                .map(|b| String::from_utf8_lossy(&b).trim().to_string())
+                .unwrap_or_default();
+            let detalle = if texto.is_empty() {
+                status.canonical_reason().unwrap_or("Error").to_string()
+            } else {
+                texto
+            };
+            ProblemaResponse::new(status, detalle)
+        }
+    };
+    problema.instancia = Some(uri.path().to_string());
+
+    // Se conservan las cabeceras propias de la respuesta (p. ej. `Allow` en un 405)
+    let mut nueva = problema.en_respuesta();
+    for (nombre, valor) in response.headers() {
+        if nombre != header::CONTENT_TYPE && nombre != header::CONTENT_LENGTH {
+            nueva.headers_mut().insert(nombre, valor.clone());
+        }
+    }
+    nueva
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tipo_segun_estado() {
+        let problema = ProblemaResponse::new(StatusCode::CONFLICT, "La sala tiene reservas");
+        assert_eq!(problema.tipo, "urn:reservas:problema:conflicto");
+        assert_eq!(problema.estado, 409);
+
+        let problema = ProblemaResponse::new(StatusCode::IM_A_TEAPOT, "Soy una tetera");
+        assert_eq!(problema.tipo, "about:blank");
+        assert_eq!(problema.titulo, "I'm a teapot");
+    }
+
+    #[test]
+    fn test_serializa_nombres_rfc_7807() {
+        let mut problema = ProblemaResponse::validacion(
+            "Datos inválidos",
+            vec![ErrorCampoResponse {
+                campo: "email".to_string(),
+                mensaje: "El email juan no es válido".to_string(),
+            }],
+        );
+        problema.instancia = Some("/api/empleados".to_string());
+
+        let json = serde_json::to_value(&problema).unwrap();
+        assert_eq!(json["type"], "urn:reservas:problema:validacion");
+        assert_eq!(json["title"], "Datos inválidos");
+        assert_eq!(json["status"], 400);
+        assert_eq!(json["detail"], "Datos inválidos");
+        assert_eq!(json["instance"], "/api/empleados");
+        assert_eq!(json["campos"][0]["campo"], "email");
+
+        // Sin campos ni instancia no aparecen
+        let json = serde_json::to_value(ProblemaResponse::new(StatusCode::NOT_FOUND, "x")).unwrap();
+        assert!(json.get("campos").is_none());
+        assert!(json.get("instance").is_none());
+    }
+}
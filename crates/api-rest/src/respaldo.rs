@@ -0,0 +1,256 @@
+// Formato de fichero de los respaldos: JSON Lines versionado
+//
+// La primera línea es una cabecera con el formato y la versión; cada línea
+// siguiente es una entidad con un campo `tipo` (empleado, sala o reserva).
+// Al ser una entidad por línea se puede generar, comprimir y revisar con
+// herramientas de texto sin cargar todo el fichero.
+//
+// Si el formato cambia de forma incompatible hay que subir VERSION_RESPALDO
+// y seguir aceptando las versiones anteriores en `deserializar`.
+
+use crate::mappers::{estado_a_str, estado_desde_str};
+use chrono::{DateTime, Utc};
+use reservas_domain::{Empleado, Reserva, Sala, Slot};
+use reservas_ports::r#in::respaldo_service::Respaldo;
+use serde::{Deserialize, Serialize};
+
+/// Identificador del formato en la cabecera
+pub const FORMATO_RESPALDO: &str = "reservas-respaldo";
+/// Versión del formato que se genera
+pub const VERSION_RESPALDO: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+enum Linea {
+    Cabecera {
+        formato: String,
+        version: u32,
+        creado: DateTime<Utc>,
+    },
+    Empleado {
+        id: String,
+        nombre: String,
+        email: String,
+        activo: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        responsable_id: Option<String>,
+    },
+    Sala {
+        id: String,
+        nombre: String,
+        capacidad: u32,
+        activa: bool,
+    },
+    Reserva {
+        id: String,
+        empleado_id: String,
+        inicio: DateTime<Utc>,
+        descripcion: String,
+        estado: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sala_id: Option<String>,
+    },
+}
+
+/// Genera el fichero de respaldo (una línea JSON por entidad)
+pub fn serializar(respaldo: &Respaldo) -> String {
+    let cabecera = Linea::Cabecera {
+        formato: FORMATO_RESPALDO.to_string(),
+        version: VERSION_RESPALDO,
+        creado: Utc::now(),
+    };
+
+    let empleados = respaldo.empleados.iter().map(|e| Linea::Empleado {
+        id: e.id.clone(),
+        nombre: e.nombre.clone(),
+        email: e.email.to_string(),
+        activo: e.activo,
+        responsable_id: e.responsable_id.clone(),
+    });
+    let salas = respaldo.salas.iter().map(|s| Linea::Sala {
+        id: s.id.clone(),
+        nombre: s.nombre.clone(),
+        capacidad: s.capacidad,
+        activa: s.activa,
+    });
+    let reservas = respaldo.reservas.iter().map(|r| Linea::Reserva {
+        id: r.id.clone(),
+        empleado_id: r.empleado_id.clone(),
+        inicio: r.slot.inicio,
+        descripcion: r.descripcion.clone(),
+        estado: estado_a_str(&r.estado).to_string(),
+        sala_id: r.sala_id.clone(),
+    });
+
+    std::iter::once(cabecera)
+        .chain(empleados)
+        .chain(salas)
+        .chain(reservas)
+        .map(|linea| serde_json::to_string(&linea).expect("una línea siempre se serializa"))
+        .map(|json| json + "\n")
+        .collect()
+}
+
+/// Lee un fichero de respaldo. Los errores indican el número de línea.
+///
+/// Las reservas se reconstruyen sin las validaciones de `Reserva::new`:
+/// un respaldo contiene reservas pasadas que ya no serían válidas hoy.
+pub fn deserializar(contenido: &str) -> Result<Respaldo, String> {
+    let mut lineas = contenido
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty());
+
+    match lineas.next() {
+        Some((_, primera)) => match serde_json::from_str(primera) {
+            Ok(Linea::Cabecera {
+                formato, version, ..
+            }) if formato == FORMATO_RESPALDO => {
+                if version > VERSION_RESPALDO {
+                    return Err(format!(
+                        "Versión de respaldo {} no soportada (máxima {})",
+                        version, VERSION_RESPALDO
+                    ));
+                }
+            }
+            _ => return Err("Línea 1: falta la cabecera del respaldo".to_string()),
+        },
+        None => return Err("El respaldo está vacío".to_string()),
+    }
+
+    let mut respaldo = Respaldo::default();
+    for (numero, linea) in lineas {
+        let error = |e: String| format!("Línea {}: {}", numero, e);
+        match serde_json::from_str(linea).map_err(|e| error(e.to_string()))? {
+            Linea::Cabecera { .. } => return Err(error("cabecera repetida".to_string())),
+            Linea::Empleado {
+                id,
+                nombre,
+                email,
+                activo,
+                responsable_id,
+            } => {
+                let mut empleado = Empleado::new(id, nombre, email).map_err(|errores| {
+                    let detalle: Vec<String> = errores.iter().map(|e| e.to_string()).collect();
+                    error(detalle.join("; "))
+                })?;
+                empleado.activo = activo;
+                // El servicio comprueba que el responsable esté en el respaldo
+                empleado.responsable_id = responsable_id;
+                respaldo.empleados.push(empleado);
+            }
+            Linea::Sala {
+                id,
+                nombre,
+                capacidad,
+                activa,
+            } => {
+                let mut sala = Sala::new(id, nombre, capacidad).map_err(error)?;
+                sala.activa = activa;
+                respaldo.salas.push(sala);
+            }
+            Linea::Reserva {
+                id,
+                empleado_id,
+                inicio,
+                descripcion,
+                estado,
+                sala_id,
+            } => {
+                let estado = estado_desde_str(&estado)
+                    .ok_or_else(|| error(format!("estado desconocido '{}'", estado)))?;
+                respaldo.reservas.push(Reserva {
+                    id,
+                    empleado_id,
+                    slot: Slot::new(inicio),
+                    descripcion,
+                    estado,
+                    sala_id,
+                });
+            }
+        }
+    }
+
+    Ok(respaldo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use reservas_domain::EstadoReserva;
+
+    fn respaldo() -> Respaldo {
+        let mut sala = Sala::new("s1".to_string(), "Sala Azul".to_string(), 8).unwrap();
+        sala.desactivar();
+        let mut responsable = Empleado::new(
+            "emp-002".to_string(),
+            "Marta".to_string(),
+            "marta@empresa.com".to_string(),
+        )
+        .unwrap();
+        responsable.activo = false;
+        let mut empleado = Empleado::new(
+            "emp-001".to_string(),
+            "Juan".to_string(),
+            "juan@empresa.com".to_string(),
+        )
+        .unwrap();
+        empleado
+            .asignar_responsable(Some("emp-002".to_string()))
+            .unwrap();
+        Respaldo {
+            empleados: vec![empleado, responsable],
+            salas: vec![sala],
+            reservas: vec![Reserva {
+                id: "r1".to_string(),
+                empleado_id: "emp-001".to_string(),
+                slot: Slot::new(Utc::now() - Duration::days(30)),
+                descripcion: "Reunión pasada".to_string(),
+                estado: EstadoReserva::Cancelada,
+                sala_id: Some("s1".to_string()),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_ida_y_vuelta() {
+        let original = respaldo();
+        let texto = serializar(&original);
+
+        assert_eq!(texto.lines().count(), 5);
+        assert_eq!(deserializar(&texto).unwrap(), original);
+    }
+
+    #[test]
+    fn test_rechaza_version_futura() {
+        let texto = format!(
+            "{{\"tipo\":\"cabecera\",\"formato\":\"{}\",\"version\":99,\"creado\":\"2024-01-01T00:00:00Z\"}}\n",
+            FORMATO_RESPALDO
+        );
+        assert!(deserializar(&texto).unwrap_err().contains("99"));
+    }
+
+    #[test]
+    fn test_error_indica_la_linea() {
+        let mut texto = serializar(&respaldo());
+        texto.push_str("{\"tipo\":\"reserva\",\"id\":\"r2\"}\n");
+
+        assert!(deserializar(&texto).unwrap_err().starts_with("Línea 6"));
+    }
+
+    #[test]
+    fn test_rechaza_email_invalido() {
+        let texto = serializar(&respaldo()).replace("juan@empresa.com", "juan");
+
+        let error = deserializar(&texto).unwrap_err();
+        assert!(error.starts_with("Línea 2: email:"), "{}", error);
+    }
+
+    #[test]
+    fn test_sin_cabecera_falla() {
+        assert!(deserializar("{\"tipo\":\"sala\"}").is_err());
+        assert!(deserializar("").is_err());
+    }
+}
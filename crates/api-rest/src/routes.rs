@@ -1,27 +1,63 @@
 // Configuración de rutas de la API REST
 // Aquí definimos todos los endpoints HTTP
 
-use crate::{handlers, openapi::ApiDoc};
+use crate::limites::{self, ConfigLimites, Limitador};
+use crate::{auth, auth::ConfigAuth, handlers, openapi::ApiDoc, problema};
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{get, post, put},
     Extension, Router,
 };
 
+use reservas_ports::r#in::auditoria_service::AuditoriaService;
+use reservas_ports::r#in::calendario_service::CalendarioService;
+use reservas_ports::r#in::carga_masiva_service::CargaMasivaService;
+use reservas_ports::r#in::disponibilidad_en_vivo_service::DisponibilidadEnVivoService;
 use reservas_ports::r#in::empleado_service::EmpleadoService;
+use reservas_ports::r#in::importacion_service::ImportacionService;
+use reservas_ports::r#in::informe_service::InformeService;
+use reservas_ports::r#in::integridad_service::IntegridadService;
+use reservas_ports::r#in::matriz_disponibilidad_service::MatrizDisponibilidadService;
 use reservas_ports::r#in::reserva_service::ReservaService;
+use reservas_ports::r#in::respaldo_service::RespaldoService;
 use reservas_ports::r#in::sala_service::SalaService;
+use reservas_ports::r#in::webhook_service::WebhookService;
 use std::sync::Arc;
 use tower_http::trace::TraceLayer;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
-/// Crea el router principal con todas las rutas de la API
+/// Tamaño máximo de un respaldo a restaurar (el límite por defecto es 2 MB)
+const LIMITE_RESPALDO: usize = 256 * 1024 * 1024;
+
+/// Tamaño máximo de un fichero a importar (iCalendar o hoja de cálculo)
+const LIMITE_IMPORTACION: usize = 16 * 1024 * 1024;
+
+/// Crea el router principal con todas las rutas de la API.
+/// La documentación es pública; el resto de rutas exige las credenciales de `auth`
+/// y está sujeto a los límites de peticiones de `limites`.
+#[allow(clippy::too_many_arguments)]
 pub fn crear_router(
     empleado_service: Arc<dyn EmpleadoService>,
     reserva_service: Arc<dyn ReservaService>,
     sala_service: Arc<dyn SalaService>,
+    respaldo_service: Arc<dyn RespaldoService>,
+    integridad_service: Arc<dyn IntegridadService>,
+    auditoria_service: Arc<dyn AuditoriaService>,
+    en_vivo: Arc<dyn DisponibilidadEnVivoService>,
+    webhook_service: Arc<dyn WebhookService>,
+    calendario_service: Arc<dyn CalendarioService>,
+    importacion_service: Arc<dyn ImportacionService>,
+    carga_masiva_service: Arc<dyn CargaMasivaService>,
+    informe_service: Arc<dyn InformeService>,
+    matriz_service: Arc<dyn MatrizDisponibilidadService>,
+    auth: ConfigAuth,
+    limites: ConfigLimites,
 ) -> Router {
     let openapi = ApiDoc::openapi();
+    let metricas_limites = limites.metricas();
+    let limitador = Arc::new(Limitador::new(limites));
 
     Router::new()
         // Swagger UI - el path debe ser absoluto incluyendo /api
@@ -31,10 +67,51 @@ pub fn crear_router(
             "/api-docs/openapi.json",
             get(|| async move { axum::Json(openapi) }),
         )
+        .merge(rutas_protegidas(auth, Arc::clone(&limitador)))
+        .merge(rutas_calendario(limitador))
+        .fallback(problema::ruta_no_encontrada)
+        // Errores en formato problem+json, incluidos los que genera Axum
+        .layer(middleware::from_fn(problema::normalizar_errores))
+        // Inyectar servicios como extensions (Dependency Injection)
+        .layer(Extension(empleado_service))
+        .layer(Extension(reserva_service))
+        .layer(Extension(sala_service))
+        .layer(Extension(respaldo_service))
+        .layer(Extension(integridad_service))
+        .layer(Extension(auditoria_service))
+        .layer(Extension(en_vivo))
+        .layer(Extension(webhook_service))
+        .layer(Extension(calendario_service))
+        .layer(Extension(importacion_service))
+        .layer(Extension(carga_masiva_service))
+        .layer(Extension(informe_service))
+        .layer(Extension(matriz_service))
+        .layer(Extension(metricas_limites))
+        // Logging de peticiones HTTP
+        .layer(TraceLayer::new_for_http())
+}
+
+/// Rutas que exigen autenticación. `route_layer` solo se aplica a las rutas
+/// existentes, así que una ruta desconocida sigue dando 404 y no 401.
+/// Los límites por IP van delante de la autenticación, para frenar también a
+/// quien prueba credenciales; los demás, detrás, para conocer al cliente.
+fn rutas_protegidas(auth: ConfigAuth, limitador: Arc<Limitador>) -> Router {
+    Router::new()
         // Rutas de empleados
         .route("/empleados", post(handlers::crear_empleado))
         .route("/empleados", get(handlers::listar_empleados))
-        .route("/empleados/:id", get(handlers::obtener_empleado))
+        .route(
+            "/empleados/importar",
+            post(handlers::importar_empleados).layer(DefaultBodyLimit::max(LIMITE_IMPORTACION)),
+        )
+        .route("/empleados/exportar", get(handlers::exportar_empleados))
+        .route(
+            "/empleados/:id",
+            get(handlers::obtener_empleado)
+                .put(handlers::reemplazar_empleado)
+                .patch(handlers::modificar_empleado)
+                .delete(handlers::eliminar_empleado),
+        )
         .route(
             "/empleados/:id/desactivar",
             post(handlers::desactivar_empleado),
@@ -43,22 +120,131 @@ pub fn crear_router(
         // Rutas de reservas
         .route("/reservas", post(handlers::crear_reserva))
         .route("/reservas", get(handlers::listar_reservas))
-        .route("/reservas/:id", get(handlers::obtener_reserva))
+        .route(
+            "/reservas/importar",
+            post(handlers::importar_reservas).layer(DefaultBodyLimit::max(LIMITE_IMPORTACION)),
+        )
+        .route("/reservas/exportar", get(handlers::exportar_reservas))
+        .route(
+            "/reservas/:id",
+            get(handlers::obtener_reserva)
+                .put(handlers::reemplazar_reserva)
+                .patch(handlers::modificar_reserva)
+                .delete(handlers::eliminar_reserva),
+        )
         .route("/reservas/:id/confirmar", post(handlers::confirmar_reserva))
         .route("/reservas/:id/cancelar", post(handlers::cancelar_reserva))
+        .route(
+            "/reservas/:id/reprogramar",
+            post(handlers::reprogramar_reserva),
+        )
         // Reservas por empleado
         .route(
             "/empleados/:id/reservas",
             get(handlers::listar_reservas_empleado),
         )
+        // URLs privadas de los calendarios
+        .route(
+            "/empleados/:id/calendario",
+            get(handlers::calendario_empleado),
+        )
+        .route(
+            "/empleados/:id/calendario/revocar",
+            post(handlers::revocar_calendario_empleado),
+        )
+        .route("/salas/:id/calendario", get(handlers::calendario_sala))
+        .route(
+            "/salas/:id/calendario/revocar",
+            post(handlers::revocar_calendario_sala),
+        )
         // Disponibilidad
         .route("/disponibilidad", get(handlers::obtener_disponibilidad))
+        .route(
+            "/disponibilidad/matriz",
+            get(handlers::matriz_disponibilidad),
+        )
+        .route("/informes/ocupacion", get(handlers::informe_ocupacion))
+        .route(
+            "/disponibilidad/eventos",
+            get(handlers::eventos_disponibilidad),
+        )
+        .route("/disponibilidad/ws", get(handlers::disponibilidad_ws))
         .route("/salas", get(handlers::listar_salas))
         .route("/salas", post(handlers::crear_sala))
-        // Inyectar servicios como extensions (Dependency Injection)
-        .layer(Extension(empleado_service))
-        .layer(Extension(reserva_service))
-        .layer(Extension(sala_service))
-        // Logging de peticiones HTTP
-        .layer(TraceLayer::new_for_http())
+        .route(
+            "/salas/importar",
+            post(handlers::importar_salas).layer(DefaultBodyLimit::max(LIMITE_IMPORTACION)),
+        )
+        .route("/salas/exportar", get(handlers::exportar_salas))
+        .route("/salas/disponibilidad", get(handlers::disponibilidad_salas))
+        .route("/salas/buscar", get(handlers::buscar_salas_libres))
+        .route(
+            "/salas/:id",
+            put(handlers::reemplazar_sala)
+                .patch(handlers::modificar_sala)
+                .delete(handlers::eliminar_sala),
+        )
+        // Administración
+        .route(
+            "/admin/respaldo",
+            get(handlers::exportar_respaldo)
+                .post(handlers::restaurar_respaldo)
+                .layer(DefaultBodyLimit::max(LIMITE_RESPALDO)),
+        )
+        .route("/admin/integridad", get(handlers::verificar_integridad))
+        .route(
+            "/admin/integridad/reparar",
+            post(handlers::reparar_integridad),
+        )
+        .route("/admin/auditoria", get(handlers::listar_auditoria))
+        .route("/admin/limites", get(handlers::metricas_limites))
+        // Webhooks salientes
+        .route(
+            "/webhooks",
+            get(handlers::listar_webhooks).post(handlers::crear_webhook),
+        )
+        .route(
+            "/webhooks/:id",
+            get(handlers::obtener_webhook).delete(handlers::eliminar_webhook),
+        )
+        .route(
+            "/webhooks/:id/entregas",
+            get(handlers::listar_entregas_webhook),
+        )
+        .route(
+            "/webhooks/:id/entregas/:entrega_id/reenviar",
+            post(handlers::reenviar_entrega_webhook),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&limitador),
+            limites::limitar,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::new(auth),
+            auth::autenticar,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            limitador,
+            limites::limitar_por_ip,
+        ))
+}
+
+/// Calendarios iCalendar: los clientes de calendario no envían credenciales,
+/// así que se protegen con el token de su URL privada. El límite de
+/// peticiones se aplica por IP.
+fn rutas_calendario(limitador: Arc<Limitador>) -> Router {
+    Router::new()
+        .route(
+            "/empleados/:id/calendario.ics",
+            get(handlers::feed_empleado),
+        )
+        .route("/salas/:id/calendario.ics", get(handlers::feed_sala))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&limitador),
+            limites::limitar,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            limitador,
+            limites::limitar_por_ip,
+        ))
 }
@@ -0,0 +1,33 @@
+use crate::autorizacion::Autorizador;
+use async_trait::async_trait;
+use reservas_domain::{Accion, Actor};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::out::auditoria::{IntentoDenegado, RegistroAuditoria};
+use reservas_ports::r#in::auditoria_service::AuditoriaService;
+use std::sync::Arc;
+
+/// Consulta del registro de accesos denegados. Los intentos de consultarlo
+/// sin permiso también quedan registrados.
+pub struct AuditoriaServiceImpl {
+    registro: Arc<dyn RegistroAuditoria>,
+    autorizador: Autorizador,
+}
+
+impl AuditoriaServiceImpl {
+    pub fn new(registro: Arc<dyn RegistroAuditoria>) -> Self {
+        Self {
+            autorizador: Autorizador::new(Arc::clone(&registro)),
+            registro,
+        }
+    }
+}
+
+#[async_trait]
+impl AuditoriaService for AuditoriaServiceImpl {
+    async fn listar_denegados(&self, actor: &Actor) -> Result<Vec<IntentoDenegado>, ErrorServicio> {
+        self.autorizador
+            .exigir(actor, Accion::Administrar, None, "auditoria".to_string())
+            .await?;
+        Ok(self.registro.listar().await?)
+    }
+}
@@ -0,0 +1,54 @@
+// Comprobación de permisos de los casos de uso
+//
+// Las reglas viven en el dominio (`Actor::puede`); aquí se aplican y se deja
+// constancia de cada intento denegado en el registro de auditoría.
+
+use chrono::Utc;
+use reservas_domain::{Accion, Actor, Empleado};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::out::auditoria::{IntentoDenegado, RegistroAuditoria};
+use std::sync::Arc;
+
+#[derive(Clone, Default)]
+pub struct Autorizador {
+    auditoria: Option<Arc<dyn RegistroAuditoria>>,
+}
+
+impl Autorizador {
+    pub fn new(auditoria: Arc<dyn RegistroAuditoria>) -> Self {
+        Self {
+            auditoria: Some(auditoria),
+        }
+    }
+
+    /// `Denegado` (y auditado) si el actor no puede realizar la acción sobre
+    /// `recurso`. `titular` es el dueño de la reserva afectada, si aplica.
+    pub async fn exigir(
+        &self,
+        actor: &Actor,
+        accion: Accion,
+        titular: Option<&Empleado>,
+        recurso: String,
+    ) -> Result<(), ErrorServicio> {
+        if actor.puede(accion, titular) {
+            return Ok(());
+        }
+
+        let mensaje = format!(
+            "{} no tiene permiso para {} ({})",
+            actor.sujeto, accion, recurso
+        );
+        if let Some(auditoria) = &self.auditoria {
+            // Si el registro falla la operación se rechaza igualmente
+            let _ = auditoria
+                .registrar(IntentoDenegado {
+                    fecha: Utc::now(),
+                    sujeto: actor.sujeto.clone(),
+                    accion,
+                    recurso,
+                })
+                .await;
+        }
+        Err(ErrorServicio::Denegado(mensaje))
+    }
+}
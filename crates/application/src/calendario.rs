@@ -0,0 +1,405 @@
+// Caso de uso de los calendarios suscribibles (feeds iCalendar)
+//
+// Reúne las reservas de un empleado o de una sala con los nombres que
+// muestran los clientes de calendario. El token de la URL privada es un
+// HMAC-SHA256 del calendario y de su versión con el secreto del servidor:
+// no hay que guardarlo, revocar un calendario cambia su versión y solo
+// invalida su URL, y cambiar el secreto invalida todas a la vez.
+
+use crate::autorizacion::Autorizador;
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use reservas_domain::{Accion, Actor};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::out::auditoria::RegistroAuditoria;
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::reserva_repository::ReservaRepository;
+use reservas_ports::out::sala_repository::SalaRepository;
+use reservas_ports::out::version_calendario_repository::VersionCalendarioRepository;
+use reservas_ports::r#in::calendario_service::{
+    Calendario, CalendarioService, EventoCalendario, FeedCalendario,
+};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub struct CalendarioServiceImpl<E, S, R, V>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+    V: VersionCalendarioRepository,
+{
+    empleados: E,
+    salas: S,
+    reservas: R,
+    versiones: V,
+    secreto: String,
+    autorizador: Autorizador,
+}
+
+impl<E, S, R, V> CalendarioServiceImpl<E, S, R, V>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+    V: VersionCalendarioRepository,
+{
+    /// `secreto` firma los tokens de las URLs privadas
+    pub fn new(
+        empleados: E,
+        salas: S,
+        reservas: R,
+        versiones: V,
+        secreto: impl Into<String>,
+    ) -> Self {
+        Self {
+            empleados,
+            salas,
+            reservas,
+            versiones,
+            secreto: secreto.into(),
+            autorizador: Autorizador::default(),
+        }
+    }
+
+    /// Registra en `auditoria` las operaciones denegadas
+    pub fn con_auditoria(mut self, auditoria: Arc<dyn RegistroAuditoria>) -> Self {
+        self.autorizador = Autorizador::new(auditoria);
+        self
+    }
+
+    async fn calcular_token(&self, calendario: &Calendario) -> Result<String, String> {
+        let recurso = recurso(calendario);
+        let version = self.versiones.version(&recurso).await?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secreto.as_bytes())
+            .expect("HMAC admite cualquier clave");
+        mac.update(format!("{}:v{}", recurso, version).as_bytes());
+        Ok(mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+
+    /// Comprueba que el calendario existe y que el actor puede pedir su URL.
+    /// Revocar la de una sala afecta a todos sus suscriptores, así que exige
+    /// gestionar salas. `false` si no existe.
+    async fn autorizar(
+        &self,
+        actor: &Actor,
+        calendario: &Calendario,
+        revocar: bool,
+    ) -> Result<bool, ErrorServicio> {
+        match calendario {
+            Calendario::Empleado(id) => {
+                let Some(empleado) = self.empleados.obtener(id).await? else {
+                    return Ok(false);
+                };
+                self.autorizador
+                    .exigir(
+                        actor,
+                        Accion::SuscribirCalendario,
+                        Some(&empleado),
+                        recurso(calendario),
+                    )
+                    .await?;
+            }
+            Calendario::Sala(id) => {
+                if self.salas.obtener(id).await?.is_none() {
+                    return Ok(false);
+                }
+                let accion = if revocar {
+                    Accion::GestionarSalas
+                } else {
+                    Accion::SuscribirCalendario
+                };
+                self.autorizador
+                    .exigir(actor, accion, None, recurso(calendario))
+                    .await?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn recurso(calendario: &Calendario) -> String {
+    match calendario {
+        Calendario::Empleado(id) => format!("empleado:{}", id),
+        Calendario::Sala(id) => format!("sala:{}", id),
+    }
+}
+
+/// Sin cortocircuito, para no dar pistas sobre el token por el tiempo de respuesta
+fn mismo_token(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |dif, (x, y)| dif | (x ^ y))
+            == 0
+}
+
+#[async_trait]
+impl<E, S, R, V> CalendarioService for CalendarioServiceImpl<E, S, R, V>
+where
+    E: EmpleadoRepository + Send + Sync,
+    S: SalaRepository + Send + Sync,
+    R: ReservaRepository + Send + Sync,
+    V: VersionCalendarioRepository + Send + Sync,
+{
+    async fn token(
+        &self,
+        actor: &Actor,
+        calendario: &Calendario,
+    ) -> Result<Option<String>, ErrorServicio> {
+        if !self.autorizar(actor, calendario, false).await? {
+            return Ok(None);
+        }
+        Ok(Some(self.calcular_token(calendario).await?))
+    }
+
+    async fn revocar(
+        &self,
+        actor: &Actor,
+        calendario: &Calendario,
+    ) -> Result<Option<String>, ErrorServicio> {
+        if !self.autorizar(actor, calendario, true).await? {
+            return Ok(None);
+        }
+        self.versiones.incrementar(&recurso(calendario)).await?;
+        Ok(Some(self.calcular_token(calendario).await?))
+    }
+
+    async fn feed(
+        &self,
+        calendario: &Calendario,
+        token: &str,
+    ) -> Result<Option<FeedCalendario>, String> {
+        if !mismo_token(token, &self.calcular_token(calendario).await?) {
+            return Ok(None);
+        }
+
+        // `listar_por_empleado` deja fuera las canceladas, que aquí hacen falta
+        let mut reservas = self.reservas.listar().await?;
+        let nombre = match calendario {
+            Calendario::Empleado(id) => {
+                let Some(empleado) = self.empleados.obtener(id).await? else {
+                    return Ok(None);
+                };
+                reservas.retain(|r| r.empleado_id == *id);
+                empleado.nombre
+            }
+            Calendario::Sala(id) => {
+                let Some(sala) = self.salas.obtener(id).await? else {
+                    return Ok(None);
+                };
+                reservas.retain(|r| r.sala_id.as_ref() == Some(id));
+                sala.nombre
+            }
+        };
+        reservas.sort_by_key(|r| r.slot.inicio);
+
+        let empleados: HashMap<_, _> = self
+            .empleados
+            .listar()
+            .await?
+            .into_iter()
+            .map(|e| (e.id, e.nombre))
+            .collect();
+        let salas: HashMap<_, _> = self
+            .salas
+            .listar()
+            .await?
+            .into_iter()
+            .map(|s| (s.id, s.nombre))
+            .collect();
+        let eventos = reservas
+            .into_iter()
+            .map(|reserva| EventoCalendario {
+                empleado: empleados.get(&reserva.empleado_id).cloned(),
+                sala: reserva
+                    .sala_id
+                    .as_ref()
+                    .and_then(|id| salas.get(id))
+                    .cloned(),
+                reserva,
+            })
+            .collect();
+        Ok(Some(FeedCalendario { nombre, eventos }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Duration, Utc};
+    use reservas_adapters::{
+        InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+        InMemoryVersionCalendarioRepository,
+    };
+    use reservas_domain::{Empleado, EstadoReserva, Reserva, Rol, Sala, Slot};
+
+    type Servicio = CalendarioServiceImpl<
+        Arc<InMemoryEmpleadoRepository>,
+        Arc<InMemorySalaRepository>,
+        Arc<InMemoryReservaRepository>,
+        InMemoryVersionCalendarioRepository,
+    >;
+
+    fn reserva(id: &str, empleado_id: &str, hora: u32, sala_id: Option<&str>) -> Reserva {
+        let manyana = Utc::now() + Duration::days(1);
+        Reserva {
+            id: id.to_string(),
+            empleado_id: empleado_id.to_string(),
+            slot: Slot::from_date_and_hour(manyana.year(), manyana.month(), manyana.day(), hora)
+                .unwrap(),
+            descripcion: "Reunión".to_string(),
+            estado: EstadoReserva::Pendiente,
+            sala_id: sala_id.map(str::to_string),
+        }
+    }
+
+    async fn escenario() -> Servicio {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let salas = Arc::new(InMemorySalaRepository::new());
+        let reservas = Arc::new(InMemoryReservaRepository::new());
+        for (id, nombre) in [("emp-001", "Juan"), ("emp-002", "Ana")] {
+            let email = format!("{}@empresa.com", id);
+            empleados
+                .guardar(&Empleado::new(id.to_string(), nombre.to_string(), email).unwrap())
+                .await
+                .unwrap();
+        }
+        salas
+            .guardar(&Sala::new("s1".to_string(), "Sala Azul".to_string(), 8).unwrap())
+            .await
+            .unwrap();
+        let mut cancelada = reserva("r3", "emp-001", 9, None);
+        cancelada.cancelar();
+        for reserva in [
+            reserva("r1", "emp-001", 12, Some("s1")),
+            reserva("r2", "emp-002", 10, Some("s1")),
+            cancelada,
+        ] {
+            reservas.guardar(&reserva).await.unwrap();
+        }
+        CalendarioServiceImpl::new(
+            empleados,
+            salas,
+            reservas,
+            InMemoryVersionCalendarioRepository::new(),
+            "secreto-del-servidor",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_feed_de_empleado_con_su_token() {
+        let servicio = escenario().await;
+        let juan = Actor::new("juan", Some("emp-001".to_string()), vec![Rol::Empleado]);
+        let calendario = Calendario::Empleado("emp-001".to_string());
+
+        let token = servicio.token(&juan, &calendario).await.unwrap().unwrap();
+        let feed = servicio.feed(&calendario, &token).await.unwrap().unwrap();
+        assert_eq!(feed.nombre, "Juan");
+        // Por orden de inicio, canceladas incluidas
+        let ids: Vec<_> = feed.eventos.iter().map(|e| e.reserva.id.as_str()).collect();
+        assert_eq!(ids, ["r3", "r1"]);
+        assert_eq!(feed.eventos[1].sala.as_deref(), Some("Sala Azul"));
+        assert_eq!(feed.eventos[1].empleado.as_deref(), Some("Juan"));
+
+        // El token es estable y no sirve para otro calendario
+        assert_eq!(
+            servicio
+                .token(&Actor::sistema(), &calendario)
+                .await
+                .unwrap(),
+            Some(token.clone())
+        );
+        let otro = Calendario::Empleado("emp-002".to_string());
+        assert_eq!(servicio.feed(&otro, &token).await.unwrap(), None);
+        assert_eq!(servicio.feed(&calendario, "x").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_permisos_y_calendarios_de_sala() {
+        let servicio = escenario().await;
+        let juan = Actor::new("juan", Some("emp-001".to_string()), vec![Rol::Empleado]);
+
+        assert!(matches!(
+            servicio
+                .token(&juan, &Calendario::Empleado("emp-002".to_string()))
+                .await,
+            Err(ErrorServicio::Denegado(_))
+        ));
+        assert_eq!(
+            servicio
+                .token(&juan, &Calendario::Empleado("emp-999".to_string()))
+                .await
+                .unwrap(),
+            None
+        );
+
+        let sala = Calendario::Sala("s1".to_string());
+        let token = servicio.token(&juan, &sala).await.unwrap().unwrap();
+        let feed = servicio.feed(&sala, &token).await.unwrap().unwrap();
+        assert_eq!(feed.nombre, "Sala Azul");
+        let empleados: Vec<_> = feed
+            .eventos
+            .iter()
+            .map(|e| e.empleado.as_deref().unwrap())
+            .collect();
+        assert_eq!(empleados, ["Ana", "Juan"]);
+        assert_eq!(
+            servicio
+                .token(&juan, &Calendario::Sala("s9".to_string()))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revocar_invalida_solo_esa_url() {
+        let servicio = escenario().await;
+        let juan = Actor::new("juan", Some("emp-001".to_string()), vec![Rol::Empleado]);
+        let suyo = Calendario::Empleado("emp-001".to_string());
+        let de_ana = Calendario::Empleado("emp-002".to_string());
+        let sala = Calendario::Sala("s1".to_string());
+        let admin = Actor::sistema();
+        let filtrado = servicio.token(&juan, &suyo).await.unwrap().unwrap();
+        let token_ana = servicio.token(&admin, &de_ana).await.unwrap().unwrap();
+        let token_sala = servicio.token(&juan, &sala).await.unwrap().unwrap();
+
+        let nuevo = servicio.revocar(&juan, &suyo).await.unwrap().unwrap();
+        assert_ne!(nuevo, filtrado);
+        assert_eq!(servicio.feed(&suyo, &filtrado).await.unwrap(), None);
+        assert!(servicio.feed(&suyo, &nuevo).await.unwrap().is_some());
+        assert_eq!(
+            servicio.token(&juan, &suyo).await.unwrap(),
+            Some(nuevo.clone())
+        );
+        // Los demás calendarios no cambian
+        assert!(servicio.feed(&de_ana, &token_ana).await.unwrap().is_some());
+        assert!(servicio.feed(&sala, &token_sala).await.unwrap().is_some());
+
+        // La de otro empleado o la de una sala no las revoca cualquiera
+        assert!(matches!(
+            servicio.revocar(&juan, &de_ana).await,
+            Err(ErrorServicio::Denegado(_))
+        ));
+        assert!(matches!(
+            servicio.revocar(&juan, &sala).await,
+            Err(ErrorServicio::Denegado(_))
+        ));
+        assert!(servicio.revocar(&admin, &sala).await.unwrap().is_some());
+        assert_eq!(servicio.feed(&sala, &token_sala).await.unwrap(), None);
+        assert_eq!(
+            servicio
+                .revocar(&admin, &Calendario::Sala("s9".to_string()))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
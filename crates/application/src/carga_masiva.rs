@@ -0,0 +1,908 @@
+// Caso de uso de altas y cambios masivos desde hojas de cálculo
+//
+// Cada fila se convierte en una llamada a `EmpleadoService` o `SalaService`,
+// así que valen las mismas reglas, permisos y auditoría que de una en una.
+// Antes de aplicar nada se revisa la hoja entera (emails repetidos,
+// responsables que no existen...), de modo que simular y aplicar dan el
+// mismo informe salvo por los fallos al escribir.
+//
+// Los empleados se identifican por email y las salas por nombre, sin
+// distinguir mayúsculas: si ya existen se actualizan. Una columna opcional
+// que no está en la hoja, o una celda vacía, deja ese dato como está; salvo
+// `responsable`, donde la celda vacía quita el responsable.
+
+use crate::autorizacion::Autorizador;
+use async_trait::async_trait;
+use reservas_domain::{Accion, Actor, Empleado, ErrorCampo, EstadoReserva, Sala};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::out::auditoria::RegistroAuditoria;
+use reservas_ports::r#in::carga_masiva_service::{
+    CargaMasivaService, Entidad, FilaCarga, InformeCarga, Mapeo, ResultadoFila, Tabla,
+};
+use reservas_ports::r#in::empleado_service::{CambiosEmpleado, EmpleadoService, ErrorEmpleado};
+use reservas_ports::r#in::reserva_service::ReservaService;
+use reservas_ports::r#in::sala_service::{CambiosSala, SalaService};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Formato de las fechas exportadas (UTC), que las hojas de cálculo
+/// reconocen como fecha y hora
+const FORMATO_FECHA: &str = "%Y-%m-%d %H:%M";
+
+pub struct CargaMasivaServiceImpl {
+    empleados: Arc<dyn EmpleadoService>,
+    salas: Arc<dyn SalaService>,
+    reservas: Arc<dyn ReservaService>,
+    autorizador: Autorizador,
+}
+
+impl CargaMasivaServiceImpl {
+    pub fn new(
+        empleados: Arc<dyn EmpleadoService>,
+        salas: Arc<dyn SalaService>,
+        reservas: Arc<dyn ReservaService>,
+    ) -> Self {
+        Self {
+            empleados,
+            salas,
+            reservas,
+            autorizador: Autorizador::default(),
+        }
+    }
+
+    /// Registra en `auditoria` las operaciones denegadas
+    pub fn con_auditoria(mut self, auditoria: Arc<dyn RegistroAuditoria>) -> Self {
+        self.autorizador = Autorizador::new(auditoria);
+        self
+    }
+}
+
+fn normalizar(texto: &str) -> String {
+    texto.trim().to_lowercase()
+}
+
+/// Índice de la columna de cada campo, según el mapeo o por su nombre
+fn columnas(
+    entidad: Entidad,
+    tabla: &Tabla,
+    mapeo: &Mapeo,
+) -> Result<HashMap<&'static str, usize>, String> {
+    if entidad.campos().is_empty() {
+        return Err("Las reservas se importan desde ficheros iCalendar".to_string());
+    }
+    let buscar = |nombre: &str| {
+        let nombre = normalizar(nombre);
+        tabla.columnas.iter().position(|c| normalizar(c) == nombre)
+    };
+
+    for campo in mapeo.keys() {
+        if !entidad.campos().contains(&normalizar(campo).as_str()) {
+            return Err(format!(
+                "Campo desconocido: {} (admite: {})",
+                campo,
+                entidad.campos().join(", ")
+            ));
+        }
+    }
+    let mut indices = HashMap::new();
+    for campo in entidad.campos() {
+        let mapeada = mapeo.iter().find(|(c, _)| normalizar(c) == *campo);
+        match mapeada {
+            Some((_, columna)) => {
+                let indice = buscar(columna).ok_or_else(|| {
+                    format!("No hay ninguna columna '{}' para {}", columna, campo)
+                })?;
+                indices.insert(*campo, indice);
+            }
+            None => {
+                if let Some(indice) = buscar(campo) {
+                    indices.insert(*campo, indice);
+                }
+            }
+        }
+    }
+    for campo in entidad.obligatorios() {
+        if !indices.contains_key(campo) {
+            return Err(format!(
+                "Falta la columna de {} (indique cuál es en el mapeo)",
+                campo
+            ));
+        }
+    }
+    Ok(indices)
+}
+
+/// Fila de la hoja con acceso por campo
+struct Celdas<'a> {
+    fila: &'a [String],
+    indices: &'a HashMap<&'static str, usize>,
+}
+
+impl Celdas<'_> {
+    /// `None` si la columna no está en la hoja
+    fn columna(&self, campo: &str) -> Option<&str> {
+        let indice = self.indices.get(campo)?;
+        Some(self.fila.get(*indice).map_or("", |v| v.trim()))
+    }
+
+    /// Valor no vacío del campo
+    fn valor(&self, campo: &str) -> Option<&str> {
+        self.columna(campo).filter(|v| !v.is_empty())
+    }
+
+    fn texto(&self, campo: &str) -> String {
+        self.valor(campo).unwrap_or_default().to_string()
+    }
+
+    fn booleano(&self, campo: &str, errores: &mut Vec<ErrorCampo>) -> Option<bool> {
+        let valor = self.valor(campo)?;
+        match normalizar(valor).as_str() {
+            "sí" | "si" | "s" | "x" | "1" | "true" | "verdadero" | "yes" => Some(true),
+            "no" | "n" | "0" | "false" | "falso" => Some(false),
+            _ => {
+                errores.push(ErrorCampo::new(
+                    campo,
+                    format!("Valor no válido: {} (use sí o no)", valor),
+                ));
+                None
+            }
+        }
+    }
+}
+
+/// Las filas sin ningún valor (p. ej. al final de una hoja) no cuentan
+fn vacia(fila: &[String]) -> bool {
+    fila.iter().all(|v| v.trim().is_empty())
+}
+
+fn fila_error(fila: usize, clave: String, errores: Vec<ErrorCampo>) -> FilaCarga {
+    FilaCarga {
+        fila,
+        clave,
+        resultado: ResultadoFila::Error,
+        errores,
+        id: None,
+    }
+}
+
+fn errores_de(error: ErrorEmpleado) -> Vec<ErrorCampo> {
+    match error {
+        ErrorEmpleado::Validacion(errores) => errores,
+        ErrorEmpleado::Denegado(mensaje) | ErrorEmpleado::Otro(mensaje) => {
+            vec![ErrorCampo::new("empleado", mensaje)]
+        }
+    }
+}
+
+/// Cambios pedidos para un empleado de la hoja
+struct PlanEmpleado {
+    fila: usize,
+    nombre: String,
+    email: String,
+    /// `None`: no se toca; `Some(None)`: se quita
+    responsable: Option<Option<String>>,
+    activo: Option<bool>,
+    existente: Option<Empleado>,
+}
+
+impl PlanEmpleado {
+    /// `responsable_id` resuelto con los empleados ya existentes; `None` si
+    /// el responsable es nuevo
+    fn cambia(&self, responsable_id: Option<Option<&str>>) -> bool {
+        let Some(actual) = &self.existente else {
+            return true;
+        };
+        actual.nombre != self.nombre
+            || self.activo.is_some_and(|activo| activo != actual.activo)
+            || match (&self.responsable, responsable_id) {
+                (None, _) => false,
+                (Some(_), None) => true,
+                (Some(_), Some(id)) => actual.responsable_id.as_deref() != id,
+            }
+    }
+}
+
+/// Cambios pedidos para una sala de la hoja
+struct PlanSala {
+    fila: usize,
+    nombre: String,
+    capacidad: u32,
+    activa: Option<bool>,
+    existente: Option<Sala>,
+}
+
+impl PlanSala {
+    fn cambia(&self) -> bool {
+        let Some(actual) = &self.existente else {
+            return true;
+        };
+        actual.nombre != self.nombre
+            || actual.capacidad != self.capacidad
+            || self.activa.is_some_and(|activa| activa != actual.activa)
+    }
+}
+
+impl CargaMasivaServiceImpl {
+    async fn importar_empleados(
+        &self,
+        actor: &Actor,
+        tabla: &Tabla,
+        indices: &HashMap<&'static str, usize>,
+        simular: bool,
+    ) -> Result<InformeCarga, ErrorServicio> {
+        let existentes: HashMap<String, Empleado> = self
+            .empleados
+            .listar_empleados()
+            .await?
+            .into_iter()
+            .map(|e| (e.email.as_str().to_string(), e))
+            .collect();
+
+        // 1. Validar cada fila por separado
+        let mut informe = InformeCarga::default();
+        let mut planes: Vec<PlanEmpleado> = Vec::new();
+        let mut vistos: HashMap<String, usize> = HashMap::new();
+        for (i, fila) in tabla.filas.iter().enumerate() {
+            if vacia(fila) {
+                continue;
+            }
+            let numero = i + 2;
+            let celdas = Celdas { fila, indices };
+            let mut errores = Vec::new();
+            let activo = celdas.booleano("activo", &mut errores);
+            let responsable = celdas
+                .columna("responsable")
+                .map(|v| (!v.is_empty()).then(|| normalizar(v)));
+            let nombre = celdas.texto("nombre");
+            let email = match Empleado::new(String::new(), nombre.clone(), celdas.texto("email")) {
+                Ok(empleado) => empleado.email.as_str().to_string(),
+                Err(mut invalidos) => {
+                    invalidos.append(&mut errores);
+                    errores = invalidos;
+                    normalizar(&celdas.texto("email"))
+                }
+            };
+            if let Some(anterior) = vistos.get(&email) {
+                errores.push(ErrorCampo::new(
+                    "email",
+                    format!("Repetido: ya está en la fila {}", anterior),
+                ));
+            } else if !email.is_empty() {
+                vistos.insert(email.clone(), numero);
+            }
+
+            if errores.is_empty() {
+                planes.push(PlanEmpleado {
+                    fila: numero,
+                    existente: existentes.get(&email).cloned(),
+                    nombre,
+                    email,
+                    responsable,
+                    activo,
+                });
+            } else {
+                informe.filas.push(fila_error(numero, email, errores));
+            }
+        }
+
+        // 2. Los responsables tienen que existir o estar en la hoja
+        let en_hoja: HashMap<String, usize> =
+            planes.iter().map(|p| (p.email.clone(), p.fila)).collect();
+        let mut validos = Vec::new();
+        for plan in planes {
+            let error = match &plan.responsable {
+                Some(Some(responsable)) if *responsable == plan.email => {
+                    Some("Un empleado no puede ser su propio responsable")
+                }
+                Some(Some(responsable))
+                    if !existentes.contains_key(responsable)
+                        && !en_hoja.contains_key(responsable) =>
+                {
+                    Some("No es el email de ningún empleado")
+                }
+                _ => None,
+            };
+            match error {
+                Some(mensaje) => informe.filas.push(fila_error(
+                    plan.fila,
+                    plan.email,
+                    vec![ErrorCampo::new("responsable", mensaje)],
+                )),
+                None => validos.push(plan),
+            }
+        }
+
+        if !validos.is_empty() {
+            self.autorizador
+                .exigir(
+                    actor,
+                    Accion::GestionarEmpleados,
+                    None,
+                    "empleados".to_string(),
+                )
+                .await?;
+        }
+        let id_existente = |email: &str| existentes.get(email).map(|e| e.id.as_str());
+        if simular {
+            for plan in validos {
+                let responsable_id = match &plan.responsable {
+                    Some(Some(email)) => id_existente(email).map(Some),
+                    _ => Some(None),
+                };
+                informe.filas.push(FilaCarga {
+                    fila: plan.fila,
+                    clave: plan.email.clone(),
+                    resultado: match &plan.existente {
+                        None => ResultadoFila::Creada,
+                        Some(_) if plan.cambia(responsable_id) => ResultadoFila::Actualizada,
+                        Some(_) => ResultadoFila::SinCambios,
+                    },
+                    errores: Vec::new(),
+                    id: plan.existente.as_ref().map(|e| e.id.clone()),
+                });
+            }
+            informe.filas.sort_by_key(|f| f.fila);
+            return Ok(informe);
+        }
+
+        // 3. Crear y actualizar; los responsables, al final, cuando ya
+        //    existen todos
+        let mut ids: HashMap<String, String> = existentes
+            .iter()
+            .map(|(email, e)| (email.clone(), e.id.clone()))
+            .collect();
+        let mut aplicados = Vec::new();
+        for plan in validos {
+            let resultado = self.aplicar_empleado(actor, &plan).await;
+            match resultado {
+                Ok((id, resultado)) => {
+                    ids.insert(plan.email.clone(), id.clone());
+                    aplicados.push((plan, id, resultado));
+                }
+                Err(errores) => informe
+                    .filas
+                    .push(fila_error(plan.fila, plan.email, errores)),
+            }
+        }
+        for (plan, id, mut resultado) in aplicados {
+            let mut errores = Vec::new();
+            if let Some(responsable) = &plan.responsable {
+                let responsable_id = match responsable {
+                    Some(email) => ids.get(email).cloned().map(Some),
+                    None => Some(None),
+                };
+                let actual = plan
+                    .existente
+                    .as_ref()
+                    .and_then(|e| e.responsable_id.clone());
+                match responsable_id {
+                    None => errores.push(ErrorCampo::new(
+                        "responsable",
+                        "El responsable no se ha podido crear",
+                    )),
+                    Some(responsable_id) if responsable_id != actual => {
+                        let cambios = CambiosEmpleado {
+                            responsable_id: Some(responsable_id),
+                            ..Default::default()
+                        };
+                        match self
+                            .empleados
+                            .actualizar_empleado(actor, &id, cambios)
+                            .await
+                        {
+                            Ok(_) if resultado == ResultadoFila::SinCambios => {
+                                resultado = ResultadoFila::Actualizada
+                            }
+                            Ok(_) => {}
+                            Err(e) => errores = errores_de(e),
+                        }
+                    }
+                    Some(_) => {}
+                }
+            }
+            informe.filas.push(FilaCarga {
+                fila: plan.fila,
+                clave: plan.email,
+                // Si falla el responsable, lo demás ya se ha aplicado
+                resultado,
+                errores,
+                id: Some(id),
+            });
+        }
+        informe.filas.sort_by_key(|f| f.fila);
+        Ok(informe)
+    }
+
+    /// Alta o cambio del empleado, salvo el responsable
+    async fn aplicar_empleado(
+        &self,
+        actor: &Actor,
+        plan: &PlanEmpleado,
+    ) -> Result<(String, ResultadoFila), Vec<ErrorCampo>> {
+        let (empleado, mut resultado) = match &plan.existente {
+            None => (
+                self.empleados
+                    .crear_empleado(actor, plan.nombre.clone(), plan.email.clone())
+                    .await
+                    .map_err(errores_de)?,
+                ResultadoFila::Creada,
+            ),
+            Some(actual) if actual.nombre != plan.nombre => {
+                let cambios = CambiosEmpleado {
+                    nombre: Some(plan.nombre.clone()),
+                    ..Default::default()
+                };
+                let actualizado = self
+                    .empleados
+                    .actualizar_empleado(actor, &actual.id, cambios)
+                    .await
+                    .map_err(errores_de)?;
+                (
+                    actualizado.unwrap_or_else(|| actual.clone()),
+                    ResultadoFila::Actualizada,
+                )
+            }
+            Some(actual) => (actual.clone(), ResultadoFila::SinCambios),
+        };
+
+        match plan.activo {
+            Some(activo) if activo != empleado.activo => {
+                let cambio = if activo {
+                    self.empleados.activar_empleado(actor, &empleado.id).await
+                } else {
+                    self.empleados
+                        .desactivar_empleado(actor, &empleado.id)
+                        .await
+                };
+                cambio.map_err(|e| errores_de(e.into()))?;
+                if resultado == ResultadoFila::SinCambios {
+                    resultado = ResultadoFila::Actualizada;
+                }
+            }
+            _ => {}
+        }
+        Ok((empleado.id, resultado))
+    }
+
+    async fn importar_salas(
+        &self,
+        actor: &Actor,
+        tabla: &Tabla,
+        indices: &HashMap<&'static str, usize>,
+        simular: bool,
+    ) -> Result<InformeCarga, ErrorServicio> {
+        let existentes: HashMap<String, Sala> = self
+            .salas
+            .listar_salas()
+            .await?
+            .into_iter()
+            .map(|s| (normalizar(&s.nombre), s))
+            .collect();
+
+        let mut informe = InformeCarga::default();
+        let mut planes = Vec::new();
+        let mut vistas: HashMap<String, usize> = HashMap::new();
+        for (i, fila) in tabla.filas.iter().enumerate() {
+            if vacia(fila) {
+                continue;
+            }
+            let numero = i + 2;
+            let celdas = Celdas { fila, indices };
+            let mut errores = Vec::new();
+            let nombre = celdas.texto("nombre");
+            let activa = celdas.booleano("activa", &mut errores);
+            let capacidad = match celdas.valor("capacidad").map(str::parse::<u32>) {
+                Some(Ok(capacidad)) => capacidad,
+                Some(Err(_)) => {
+                    errores.push(ErrorCampo::new(
+                        "capacidad",
+                        "Debe ser un número entero positivo",
+                    ));
+                    0
+                }
+                None => {
+                    errores.push(ErrorCampo::new("capacidad", "Falta la capacidad"));
+                    0
+                }
+            };
+            if errores.is_empty() {
+                if let Err(e) = Sala::new(String::new(), nombre.clone(), capacidad) {
+                    errores.push(ErrorCampo::new("sala", e));
+                }
+            }
+            let clave = normalizar(&nombre);
+            if let Some(anterior) = vistas.get(&clave) {
+                errores.push(ErrorCampo::new(
+                    "nombre",
+                    format!("Repetido: ya está en la fila {}", anterior),
+                ));
+            } else if !clave.is_empty() {
+                vistas.insert(clave.clone(), numero);
+            }
+
+            if errores.is_empty() {
+                planes.push(PlanSala {
+                    fila: numero,
+                    existente: existentes.get(&clave).cloned(),
+                    nombre,
+                    capacidad,
+                    activa,
+                });
+            } else {
+                informe.filas.push(fila_error(numero, nombre, errores));
+            }
+        }
+
+        if !planes.is_empty() {
+            self.autorizador
+                .exigir(actor, Accion::GestionarSalas, None, "salas".to_string())
+                .await?;
+        }
+        for plan in planes {
+            let resultado = match &plan.existente {
+                None => ResultadoFila::Creada,
+                Some(_) if plan.cambia() => ResultadoFila::Actualizada,
+                Some(_) => ResultadoFila::SinCambios,
+            };
+            let aplicado = if simular {
+                Ok(plan.existente.as_ref().map(|s| s.id.clone()))
+            } else {
+                self.aplicar_sala(actor, &plan).await.map(Some)
+            };
+            informe.filas.push(match aplicado {
+                Ok(id) => FilaCarga {
+                    fila: plan.fila,
+                    clave: plan.nombre,
+                    resultado,
+                    errores: Vec::new(),
+                    id,
+                },
+                Err(e) => fila_error(
+                    plan.fila,
+                    plan.nombre,
+                    vec![ErrorCampo::new("sala", e.to_string())],
+                ),
+            });
+        }
+        informe.filas.sort_by_key(|f| f.fila);
+        Ok(informe)
+    }
+
+    async fn aplicar_sala(&self, actor: &Actor, plan: &PlanSala) -> Result<String, ErrorServicio> {
+        let sala = match &plan.existente {
+            None => {
+                self.salas
+                    .crear_sala(actor, plan.nombre.clone(), plan.capacidad)
+                    .await?
+            }
+            Some(actual) if actual.nombre != plan.nombre || actual.capacidad != plan.capacidad => {
+                let cambios = CambiosSala {
+                    nombre: Some(plan.nombre.clone()),
+                    capacidad: Some(plan.capacidad),
+                };
+                self.salas
+                    .actualizar_sala(actor, &actual.id, cambios)
+                    .await?
+                    .unwrap_or_else(|| actual.clone())
+            }
+            Some(actual) => actual.clone(),
+        };
+        match plan.activa {
+            Some(true) if !sala.activa => self.salas.activar_sala(actor, &sala.id).await?,
+            Some(false) if sala.activa => self.salas.desactivar_sala(actor, &sala.id).await?,
+            _ => {}
+        }
+        Ok(sala.id)
+    }
+}
+
+fn si_no(valor: bool) -> String {
+    if valor { "sí" } else { "no" }.to_string()
+}
+
+#[async_trait]
+impl CargaMasivaService for CargaMasivaServiceImpl {
+    async fn importar(
+        &self,
+        actor: &Actor,
+        entidad: Entidad,
+        tabla: Tabla,
+        mapeo: &Mapeo,
+        simular: bool,
+    ) -> Result<InformeCarga, ErrorServicio> {
+        let indices = columnas(entidad, &tabla, mapeo)?;
+        match entidad {
+            Entidad::Empleados => {
+                self.importar_empleados(actor, &tabla, &indices, simular)
+                    .await
+            }
+            Entidad::Salas => self.importar_salas(actor, &tabla, &indices, simular).await,
+            Entidad::Reservas => unreachable!("`columnas` rechaza las reservas"),
+        }
+    }
+
+    async fn exportar(&self, entidad: Entidad) -> Result<Tabla, String> {
+        match entidad {
+            Entidad::Empleados => {
+                let mut empleados = self.empleados.listar_empleados().await?;
+                empleados.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+                let emails: HashMap<&str, &str> = empleados
+                    .iter()
+                    .map(|e| (e.id.as_str(), e.email.as_str()))
+                    .collect();
+                let mut tabla = Tabla::new(&["id", "nombre", "email", "responsable", "activo"]);
+                for e in &empleados {
+                    let responsable = e
+                        .responsable_id
+                        .as_deref()
+                        .and_then(|id| emails.get(id))
+                        .unwrap_or(&"");
+                    tabla.filas.push(vec![
+                        e.id.clone(),
+                        e.nombre.clone(),
+                        e.email.to_string(),
+                        responsable.to_string(),
+                        si_no(e.activo),
+                    ]);
+                }
+                Ok(tabla)
+            }
+            Entidad::Salas => {
+                let mut salas = self.salas.listar_salas().await?;
+                salas.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+                let mut tabla = Tabla::new(&["id", "nombre", "capacidad", "activa"]);
+                for s in salas {
+                    tabla.filas.push(vec![
+                        s.id,
+                        s.nombre,
+                        s.capacidad.to_string(),
+                        si_no(s.activa),
+                    ]);
+                }
+                Ok(tabla)
+            }
+            Entidad::Reservas => {
+                let empleados: HashMap<String, Empleado> = self
+                    .empleados
+                    .listar_empleados()
+                    .await?
+                    .into_iter()
+                    .map(|e| (e.id.clone(), e))
+                    .collect();
+                let salas: HashMap<String, String> = self
+                    .salas
+                    .listar_salas()
+                    .await?
+                    .into_iter()
+                    .map(|s| (s.id, s.nombre))
+                    .collect();
+                let mut reservas = self.reservas.listar_reservas().await?;
+                reservas.sort_by(|a, b| a.slot.inicio.cmp(&b.slot.inicio).then(a.id.cmp(&b.id)));
+
+                let mut tabla = Tabla::new(&[
+                    "id",
+                    "inicio",
+                    "fin",
+                    "empleado_id",
+                    "empleado",
+                    "email",
+                    "sala_id",
+                    "sala",
+                    "descripcion",
+                    "estado",
+                ]);
+                for r in reservas {
+                    let empleado = empleados.get(&r.empleado_id);
+                    let sala = r.sala_id.as_ref().and_then(|id| salas.get(id));
+                    tabla.filas.push(vec![
+                        r.id.clone(),
+                        r.slot.inicio.format(FORMATO_FECHA).to_string(),
+                        r.slot.fin().format(FORMATO_FECHA).to_string(),
+                        r.empleado_id.clone(),
+                        empleado.map(|e| e.nombre.clone()).unwrap_or_default(),
+                        empleado.map(|e| e.email.to_string()).unwrap_or_default(),
+                        r.sala_id.clone().unwrap_or_default(),
+                        sala.cloned().unwrap_or_default(),
+                        r.descripcion.clone(),
+                        match r.estado {
+                            EstadoReserva::Pendiente => "pendiente",
+                            EstadoReserva::Confirmada => "confirmada",
+                            EstadoReserva::Cancelada => "cancelada",
+                        }
+                        .to_string(),
+                    ]);
+                }
+                Ok(tabla)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmpleadoServiceImpl, ReservaServiceImpl, SalaServiceImpl};
+    use reservas_adapters::{
+        InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+    };
+    use reservas_domain::Rol;
+
+    fn admin() -> Actor {
+        Actor::new("admin", None, vec![Rol::Admin])
+    }
+
+    fn servicio() -> CargaMasivaServiceImpl {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let reservas = Arc::new(InMemoryReservaRepository::new());
+        let salas = Arc::new(InMemorySalaRepository::new());
+        let servicio_reservas: Arc<dyn ReservaService> = Arc::new(ReservaServiceImpl::new(
+            Arc::clone(&reservas),
+            Arc::clone(&empleados),
+            Arc::clone(&salas),
+        ));
+        CargaMasivaServiceImpl::new(
+            Arc::new(EmpleadoServiceImpl::new(
+                empleados,
+                Arc::clone(&servicio_reservas),
+            )),
+            Arc::new(SalaServiceImpl::new(salas, reservas)),
+            servicio_reservas,
+        )
+    }
+
+    fn tabla(columnas: &[&str], filas: &[&[&str]]) -> Tabla {
+        let mut tabla = Tabla::new(columnas);
+        tabla.filas = filas
+            .iter()
+            .map(|f| f.iter().map(|v| v.to_string()).collect())
+            .collect();
+        tabla
+    }
+
+    fn resultados(informe: &InformeCarga) -> Vec<(usize, ResultadoFila)> {
+        informe
+            .filas
+            .iter()
+            .map(|f| (f.fila, f.resultado))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_importar_empleados_simula_valida_y_aplica() {
+        let servicio = servicio();
+        servicio
+            .empleados
+            .crear_empleado(&admin(), "Ana".to_string(), "ana@empresa.com".to_string())
+            .await
+            .unwrap();
+
+        let hoja = tabla(
+            &["Nombre completo", "Correo", "Jefe", "Notas"],
+            &[
+                &["Juan López", "Juan@Empresa.com", "ana@empresa.com", "x"],
+                &["Ana", "ana@empresa.com", "", ""],
+                &["", "", "", ""],
+                &["Sin email", "", "", ""],
+                &["Otro Juan", "juan@empresa.com", "", ""],
+                &["Eva", "eva@empresa.com", "nadie@empresa.com", ""],
+            ],
+        );
+        let mapeo: Mapeo = [
+            ("nombre", "nombre completo"),
+            ("email", "Correo"),
+            ("responsable", "Jefe"),
+        ]
+        .into_iter()
+        .map(|(c, v)| (c.to_string(), v.to_string()))
+        .collect();
+
+        let simulado = servicio
+            .importar(&admin(), Entidad::Empleados, hoja.clone(), &mapeo, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            resultados(&simulado),
+            [
+                (2, ResultadoFila::Creada),
+                (3, ResultadoFila::SinCambios),
+                (5, ResultadoFila::Error),
+                (6, ResultadoFila::Error),
+                (7, ResultadoFila::Error),
+            ]
+        );
+        assert_eq!(simulado.filas[2].errores[0].campo, "email");
+        assert!(simulado.filas[3].errores[0].mensaje.contains("fila 2"));
+        assert_eq!(simulado.filas[4].errores[0].campo, "responsable");
+        assert_eq!(
+            servicio.empleados.listar_empleados().await.unwrap().len(),
+            1
+        );
+
+        let aplicado = servicio
+            .importar(&admin(), Entidad::Empleados, hoja, &mapeo, false)
+            .await
+            .unwrap();
+        assert_eq!(resultados(&aplicado), resultados(&simulado));
+        let empleados = servicio.empleados.listar_empleados().await.unwrap();
+        let ana = empleados.iter().find(|e| e.nombre == "Ana").unwrap();
+        let juan = empleados.iter().find(|e| e.nombre == "Juan López").unwrap();
+        assert_eq!(juan.email.as_str(), "juan@empresa.com");
+        assert_eq!(juan.responsable_id.as_deref(), Some(ana.id.as_str()));
+        assert_eq!(aplicado.filas[0].id.as_deref(), Some(juan.id.as_str()));
+
+        // Lo exportado se puede volver a importar sin cambios
+        let exportado = servicio.exportar(Entidad::Empleados).await.unwrap();
+        let otra_vez = servicio
+            .importar(&admin(), Entidad::Empleados, exportado, &Mapeo::new(), true)
+            .await
+            .unwrap();
+        assert_eq!(otra_vez.contar(ResultadoFila::SinCambios), 2);
+    }
+
+    #[tokio::test]
+    async fn test_importar_salas_y_errores_de_la_hoja() {
+        let servicio = servicio();
+        let hoja = tabla(
+            &["nombre", "capacidad", "activa"],
+            &[
+                &["Sala Norte", "8", ""],
+                &["Sala Sur", "0", "sí"],
+                &["sala norte", "10", ""],
+                &["Sala Este", "muchas", "quizá"],
+            ],
+        );
+        let informe = servicio
+            .importar(&admin(), Entidad::Salas, hoja, &Mapeo::new(), false)
+            .await
+            .unwrap();
+        assert_eq!(
+            resultados(&informe),
+            [
+                (2, ResultadoFila::Creada),
+                (3, ResultadoFila::Error),
+                (4, ResultadoFila::Error),
+                (5, ResultadoFila::Error),
+            ]
+        );
+        assert_eq!(informe.filas[3].errores.len(), 2);
+
+        let hoja = tabla(&["Nombre", "Capacidad"], &[&["SALA NORTE", "12"]]);
+        let informe = servicio
+            .importar(&admin(), Entidad::Salas, hoja, &Mapeo::new(), false)
+            .await
+            .unwrap();
+        assert_eq!(resultados(&informe), [(2, ResultadoFila::Actualizada)]);
+        let salas = servicio.salas.listar_salas().await.unwrap();
+        assert_eq!(salas.len(), 1);
+        assert_eq!(
+            (salas[0].nombre.as_str(), salas[0].capacidad),
+            ("SALA NORTE", 12)
+        );
+
+        // El mapeo tiene que cuadrar con la hoja y los permisos se exigen
+        let hoja = tabla(&["nombre"], &[&["Sala Oeste"]]);
+        let error = servicio
+            .importar(&admin(), Entidad::Salas, hoja.clone(), &Mapeo::new(), true)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("capacidad"));
+        let mapeo: Mapeo = [("aforo".to_string(), "nombre".to_string())].into();
+        assert!(servicio
+            .importar(&admin(), Entidad::Salas, hoja, &mapeo, true)
+            .await
+            .is_err());
+
+        let hoja = tabla(&["nombre", "capacidad"], &[&["Sala Oeste", "4"]]);
+        let error = servicio
+            .importar(
+                &Actor::new("juan", Some("emp-001".to_string()), vec![Rol::Empleado]),
+                Entidad::Salas,
+                hoja,
+                &Mapeo::new(),
+                true,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ErrorServicio::Denegado(_)));
+    }
+}
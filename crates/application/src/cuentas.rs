@@ -0,0 +1,255 @@
+// Cuentas locales de la interfaz web
+//
+// Las contraseñas se guardan con argon2id (formato PHC, con sal aleatoria).
+// El hash es costoso a propósito, así que se calcula fuera del runtime async.
+
+use crate::autorizacion::Autorizador;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use async_trait::async_trait;
+use reservas_domain::cuenta::LONGITUD_MINIMA_PASSWORD;
+use reservas_domain::{Accion, Actor, Cuenta};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::out::auditoria::RegistroAuditoria;
+use reservas_ports::out::cuenta_repository::CuentaRepository;
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::r#in::cuenta_service::{CuentaService, NuevaCuenta};
+use std::sync::{Arc, OnceLock};
+
+pub struct CuentaServiceImpl<C: CuentaRepository, E: EmpleadoRepository> {
+    repository: C,
+    empleados: E,
+    autorizador: Autorizador,
+}
+
+impl<C: CuentaRepository, E: EmpleadoRepository> CuentaServiceImpl<C, E> {
+    pub fn new(repository: C, empleados: E) -> Self {
+        Self {
+            repository,
+            empleados,
+            autorizador: Autorizador::default(),
+        }
+    }
+
+    /// Registra en `auditoria` las operaciones denegadas
+    pub fn con_auditoria(mut self, auditoria: Arc<dyn RegistroAuditoria>) -> Self {
+        self.autorizador = Autorizador::new(auditoria);
+        self
+    }
+}
+
+fn validar_password(password: &str) -> Result<(), ErrorServicio> {
+    if password.chars().count() < LONGITUD_MINIMA_PASSWORD {
+        return Err(ErrorServicio::Otro(format!(
+            "La contraseña debe tener al menos {} caracteres",
+            LONGITUD_MINIMA_PASSWORD
+        )));
+    }
+    Ok(())
+}
+
+async fn calcular_hash(password: &str) -> Result<String, String> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || {
+        let sal = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &sal)
+            .map(|hash| hash.to_string())
+            .map_err(|e| format!("No se pudo calcular el hash: {}", e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+async fn verificar_hash(password: &str, hash: &str) -> bool {
+    let password = password.to_string();
+    let hash = hash.to_string();
+    tokio::task::spawn_blocking(move || {
+        PasswordHash::new(&hash)
+            .map(|hash| {
+                Argon2::default()
+                    .verify_password(password.as_bytes(), &hash)
+                    .is_ok()
+            })
+            .unwrap_or(false)
+    })
+    .await
+    .unwrap_or(false)
+}
+
+/// Hash con el que se compara cuando el usuario no existe, para que la
+/// respuesta tarde lo mismo y no delate qué usuarios hay
+fn hash_señuelo() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| {
+        let sal = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(b"sin-cuenta", &sal)
+            .map(|hash| hash.to_string())
+            .unwrap_or_default()
+    })
+}
+
+#[async_trait]
+impl<C, E> CuentaService for CuentaServiceImpl<C, E>
+where
+    C: CuentaRepository,
+    E: EmpleadoRepository + Send + Sync,
+{
+    async fn registrar(&self, actor: &Actor, nueva: NuevaCuenta) -> Result<Cuenta, ErrorServicio> {
+        self.autorizador
+            .exigir(
+                actor,
+                Accion::Administrar,
+                None,
+                format!("cuenta:{}", nueva.usuario),
+            )
+            .await?;
+        validar_password(&nueva.password)?;
+        if let Some(empleado_id) = &nueva.empleado_id {
+            if !self.empleados.existe(empleado_id).await? {
+                return Err(format!("El empleado {} no existe", empleado_id).into());
+            }
+        }
+
+        let hash = calcular_hash(&nueva.password).await?;
+        let cuenta = Cuenta::new(nueva.usuario, hash, nueva.empleado_id, nueva.roles)
+            .map_err(|e| e.to_string())?;
+        self.repository.guardar(&cuenta).await?;
+        Ok(cuenta)
+    }
+
+    async fn autenticar(&self, usuario: &str, password: &str) -> Result<Option<Cuenta>, String> {
+        let usuario = usuario.trim().to_lowercase();
+        match self.repository.obtener(&usuario).await? {
+            Some(cuenta) => {
+                let valida = verificar_hash(password, &cuenta.hash_password).await;
+                Ok(Some(cuenta).filter(|c| valida && c.activa))
+            }
+            None => {
+                verificar_hash(password, hash_señuelo()).await;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn cambiar_password(
+        &self,
+        actor: &Actor,
+        usuario: &str,
+        password: &str,
+    ) -> Result<(), ErrorServicio> {
+        // Cada cuenta puede cambiar su propia contraseña
+        if actor.sujeto != usuario {
+            self.autorizador
+                .exigir(
+                    actor,
+                    Accion::Administrar,
+                    None,
+                    format!("cuenta:{}", usuario),
+                )
+                .await?;
+        }
+        validar_password(password)?;
+
+        let mut cuenta = self
+            .repository
+            .obtener(usuario)
+            .await?
+            .ok_or_else(|| format!("La cuenta {} no existe", usuario))?;
+        cuenta.hash_password = calcular_hash(password).await?;
+        Ok(self.repository.actualizar(&cuenta).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reservas_adapters::{InMemoryCuentaRepository, InMemoryEmpleadoRepository};
+    use reservas_domain::{Empleado, Rol};
+
+    async fn servicio() -> CuentaServiceImpl<InMemoryCuentaRepository, InMemoryEmpleadoRepository> {
+        let empleados = InMemoryEmpleadoRepository::new();
+        let ana = Empleado::new(
+            "emp-1".to_string(),
+            "Ana".to_string(),
+            "ana@empresa.com".to_string(),
+        )
+        .unwrap();
+        empleados.guardar(&ana).await.unwrap();
+        CuentaServiceImpl::new(InMemoryCuentaRepository::new(), empleados)
+    }
+
+    fn nueva(usuario: &str, password: &str) -> NuevaCuenta {
+        NuevaCuenta {
+            usuario: usuario.to_string(),
+            password: password.to_string(),
+            empleado_id: Some("emp-1".to_string()),
+            roles: vec![Rol::Empleado],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registrar_y_autenticar() {
+        let service = servicio().await;
+        let cuenta = service
+            .registrar(&Actor::sistema(), nueva("Ana", "contraseña-larga"))
+            .await
+            .unwrap();
+        assert_eq!(cuenta.usuario, "ana");
+        assert!(cuenta.hash_password.starts_with("$argon2id$"));
+
+        let autenticada = service.autenticar("ana", "contraseña-larga").await.unwrap();
+        assert_eq!(autenticada.unwrap().empleado_id.as_deref(), Some("emp-1"));
+        assert!(service.autenticar("ana", "otra").await.unwrap().is_none());
+        assert!(service.autenticar("nadie", "x").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_registro_validado_y_solo_admin() {
+        let service = servicio().await;
+        let admin = Actor::sistema();
+
+        assert!(service
+            .registrar(&admin, nueva("ana", "corta"))
+            .await
+            .is_err());
+        let mut sin_empleado = nueva("luis", "contraseña-larga");
+        sin_empleado.empleado_id = Some("emp-9".to_string());
+        assert!(service.registrar(&admin, sin_empleado).await.is_err());
+
+        let empleado = Actor::new("ana", Some("emp-1".to_string()), vec![Rol::Empleado]);
+        let error = service
+            .registrar(&empleado, nueva("eva", "contraseña-larga"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ErrorServicio::Denegado(_)));
+    }
+
+    #[tokio::test]
+    async fn test_cambiar_password_propia() {
+        let service = servicio().await;
+        service
+            .registrar(&Actor::sistema(), nueva("ana", "contraseña-larga"))
+            .await
+            .unwrap();
+
+        let ana = Actor::new("ana", Some("emp-1".to_string()), vec![Rol::Empleado]);
+        service
+            .cambiar_password(&ana, "ana", "nueva-contraseña")
+            .await
+            .unwrap();
+        assert!(service
+            .autenticar("ana", "nueva-contraseña")
+            .await
+            .unwrap()
+            .is_some());
+
+        let otro = Actor::new("luis", None, vec![Rol::Empleado]);
+        assert!(service
+            .cambiar_password(&otro, "ana", "de-otro-usuario")
+            .await
+            .is_err());
+    }
+}
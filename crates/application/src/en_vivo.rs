@@ -0,0 +1,484 @@
+// Disponibilidad en tiempo real
+//
+// El servicio de reservas notifica cada cambio ya guardado; aquí se reparte
+// a los suscriptores por un canal broadcast y cada suscripción traduce los
+// cambios que le interesan a la disponibilidad actual de los slots afectados.
+
+use async_trait::async_trait;
+use reservas_domain::{DisponibilidadSalaSlot, DisponibilidadService, DisponibilidadSlot, Slot};
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::notificador::{CambioReserva, NotificadorCambios};
+use reservas_ports::out::reserva_repository::ReservaRepository;
+use reservas_ports::r#in::disponibilidad_en_vivo_service::{
+    ActualizacionDisponibilidad, DisponibilidadEnVivoService, EventoDisponibilidad,
+    FiltroDisponibilidad, Suscripcion,
+};
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Cambios que puede acumular un suscriptor antes de tener que resincronizar
+pub const CAPACIDAD_CAMBIOS: usize = 256;
+
+/// Repositorios con los que se calcula la disponibilidad de cada cambio
+struct Fuentes<R, E> {
+    reservas: R,
+    empleados: E,
+}
+
+pub struct DisponibilidadEnVivoServiceImpl<R, E> {
+    fuentes: Arc<Fuentes<R, E>>,
+    canal: broadcast::Sender<CambioReserva>,
+}
+
+impl<R, E> DisponibilidadEnVivoServiceImpl<R, E>
+where
+    R: ReservaRepository,
+    E: EmpleadoRepository,
+{
+    pub fn new(reservas: R, empleados: E) -> Self {
+        Self::con_capacidad(reservas, empleados, CAPACIDAD_CAMBIOS)
+    }
+
+    pub fn con_capacidad(reservas: R, empleados: E, capacidad: usize) -> Self {
+        let (canal, _) = broadcast::channel(capacidad);
+        Self {
+            fuentes: Arc::new(Fuentes {
+                reservas,
+                empleados,
+            }),
+            canal,
+        }
+    }
+}
+
+impl<R, E> NotificadorCambios for DisponibilidadEnVivoServiceImpl<R, E>
+where
+    R: ReservaRepository + Send + Sync,
+    E: EmpleadoRepository + Send + Sync,
+{
+    fn notificar(&self, cambio: CambioReserva) {
+        // Sin suscriptores el envío falla, y no importa
+        let _ = self.canal.send(cambio);
+    }
+}
+
+impl<R, E> DisponibilidadEnVivoService for DisponibilidadEnVivoServiceImpl<R, E>
+where
+    R: ReservaRepository + Send + Sync + 'static,
+    E: EmpleadoRepository + Send + Sync + 'static,
+{
+    fn suscribir(&self, filtro: FiltroDisponibilidad) -> Box<dyn Suscripcion> {
+        Box::new(SuscripcionCanal {
+            fuentes: Arc::clone(&self.fuentes),
+            cambios: self.canal.subscribe(),
+            filtro,
+        })
+    }
+}
+
+struct SuscripcionCanal<R, E> {
+    fuentes: Arc<Fuentes<R, E>>,
+    cambios: broadcast::Receiver<CambioReserva>,
+    filtro: FiltroDisponibilidad,
+}
+
+/// Slots afectados por el cambio que caen en la fecha del filtro
+fn slots_afectados(cambio: &CambioReserva, filtro: &FiltroDisponibilidad) -> Vec<Slot> {
+    let mut slots = vec![cambio.reserva.slot.clone()];
+    if let Some(anterior) = &cambio.slot_anterior {
+        if *anterior != cambio.reserva.slot {
+            slots.push(anterior.clone());
+        }
+    }
+    slots.retain(|slot| {
+        filtro
+            .fecha
+            .is_none_or(|fecha| slot.inicio.date_naive() == fecha)
+    });
+    slots
+}
+
+/// Empleados cuya disponibilidad cambia: el titular y, si se ha
+/// reasignado, el anterior, que queda libre
+fn titulares<'a>(cambio: &'a CambioReserva, filtro: &FiltroDisponibilidad) -> Vec<&'a str> {
+    std::iter::once(cambio.reserva.empleado_id.as_str())
+        .chain(cambio.empleado_anterior.as_deref())
+        .filter(|id| {
+            filtro
+                .empleado_id
+                .as_deref()
+                .is_none_or(|filtro| filtro == *id)
+        })
+        .collect()
+}
+
+fn interesa(cambio: &CambioReserva, filtro: &FiltroDisponibilidad) -> bool {
+    !titulares(cambio, filtro).is_empty()
+        && filtro
+            .sala_id
+            .as_ref()
+            .is_none_or(|id| cambio.reserva.sala_id.as_ref() == Some(id))
+}
+
+impl<R, E> SuscripcionCanal<R, E>
+where
+    R: ReservaRepository,
+    E: EmpleadoRepository,
+{
+    /// Disponibilidad actual de los titulares en los slots afectados
+    async fn disponibilidad(
+        &self,
+        titulares: &[&str],
+        slots: &[Slot],
+    ) -> Result<Vec<DisponibilidadSlot>, String> {
+        let mut empleados = Vec::new();
+        let mut reservas = Vec::new();
+        for id in titulares {
+            if let Some(empleado) = self.fuentes.empleados.obtener(id).await? {
+                empleados.push(empleado);
+                reservas.extend(self.fuentes.reservas.listar_por_empleado(id).await?);
+            }
+        }
+        let tabla =
+            DisponibilidadService::generar_tabla_disponibilidad(&empleados, slots, &reservas);
+        Ok(tabla.disponibilidad)
+    }
+
+    /// Disponibilidad actual de la sala en los slots afectados, sea quien
+    /// sea el titular de la reserva que la ocupa
+    async fn disponibilidad_sala(
+        &self,
+        sala_id: &str,
+        slots: &[Slot],
+    ) -> Result<Vec<DisponibilidadSalaSlot>, String> {
+        let mut filas = Vec::new();
+        for slot in slots {
+            let reservas = self.fuentes.reservas.listar_por_slot(slot).await?;
+            let ocupante = reservas
+                .iter()
+                .find(|r| r.sala_id.as_deref() == Some(sala_id) && r.esta_activa());
+            filas.push(DisponibilidadSalaSlot {
+                sala_id: sala_id.to_string(),
+                slot: slot.clone(),
+                disponible: DisponibilidadService::sala_disponible_en_slot(
+                    sala_id, slot, &reservas,
+                ),
+                reserva_id: ocupante.map(|r| r.id.clone()),
+            });
+        }
+        Ok(filas)
+    }
+}
+
+#[async_trait]
+impl<R, E> Suscripcion for SuscripcionCanal<R, E>
+where
+    R: ReservaRepository + Send + Sync,
+    E: EmpleadoRepository + Send + Sync,
+{
+    async fn siguiente(&mut self) -> Option<EventoDisponibilidad> {
+        loop {
+            let cambio = match self.cambios.recv().await {
+                Ok(cambio) => cambio,
+                Err(RecvError::Lagged(_)) => return Some(EventoDisponibilidad::Resincronizar),
+                Err(RecvError::Closed) => return None,
+            };
+            if !interesa(&cambio, &self.filtro) {
+                continue;
+            }
+            let slots = slots_afectados(&cambio, &self.filtro);
+            if slots.is_empty() {
+                continue;
+            }
+            // Con filtro de sala interesa si la sala está libre, no el titular
+            let (slots, salas) = match &self.filtro.sala_id {
+                Some(sala_id) => match self.disponibilidad_sala(sala_id, &slots).await {
+                    Ok(salas) => (Vec::new(), salas),
+                    Err(_) => return Some(EventoDisponibilidad::Resincronizar),
+                },
+                None => {
+                    let titulares = titulares(&cambio, &self.filtro);
+                    match self.disponibilidad(&titulares, &slots).await {
+                        // Empleado desactivado o borrado: no aparece en la tabla
+                        Ok(slots) if slots.is_empty() => continue,
+                        Ok(slots) => (slots, Vec::new()),
+                        // Sin poder calcularla, mejor que el cliente pida la tabla entera
+                        Err(_) => return Some(EventoDisponibilidad::Resincronizar),
+                    }
+                }
+            };
+            return Some(EventoDisponibilidad::Actualizacion(
+                ActualizacionDisponibilidad {
+                    cambio: cambio.tipo,
+                    reserva_id: cambio.reserva.id,
+                    sala_id: cambio.reserva.sala_id,
+                    slots,
+                    salas,
+                },
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReservaServiceImpl;
+    use chrono::{Datelike, Duration, Utc};
+    use reservas_adapters::{
+        InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+    };
+    use reservas_domain::{Actor, Empleado, Sala};
+    use reservas_ports::out::notificador::TipoCambio;
+    use reservas_ports::out::sala_repository::SalaRepository;
+    use reservas_ports::r#in::reserva_service::ReservaService;
+
+    type EnVivo = DisponibilidadEnVivoServiceImpl<
+        Arc<InMemoryReservaRepository>,
+        Arc<InMemoryEmpleadoRepository>,
+    >;
+    type Reservas = ReservaServiceImpl<
+        Arc<InMemoryReservaRepository>,
+        Arc<InMemoryEmpleadoRepository>,
+        Arc<InMemorySalaRepository>,
+    >;
+
+    fn slot(dias: i64, hora: u32) -> Slot {
+        let dia = Utc::now() + Duration::days(dias);
+        Slot::from_date_and_hour(dia.year(), dia.month(), dia.day(), hora).unwrap()
+    }
+
+    async fn servicios(capacidad: usize) -> (Reservas, Arc<EnVivo>) {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let salas = Arc::new(InMemorySalaRepository::new());
+        let reservas = Arc::new(InMemoryReservaRepository::new());
+        for id in ["emp-001", "emp-002"] {
+            let empleado = Empleado::new(
+                id.to_string(),
+                id.to_string(),
+                format!("{}@empresa.com", id),
+            )
+            .unwrap();
+            empleados.guardar(&empleado).await.unwrap();
+        }
+        salas
+            .guardar(&Sala::new("s1".to_string(), "Sala Azul".to_string(), 8).unwrap())
+            .await
+            .unwrap();
+
+        let en_vivo = Arc::new(DisponibilidadEnVivoServiceImpl::con_capacidad(
+            Arc::clone(&reservas),
+            Arc::clone(&empleados),
+            capacidad,
+        ));
+        let reservas = ReservaServiceImpl::new(reservas, empleados, salas)
+            .con_notificador(Arc::clone(&en_vivo) as Arc<dyn NotificadorCambios>);
+        (reservas, en_vivo)
+    }
+
+    fn actualizacion(evento: Option<EventoDisponibilidad>) -> ActualizacionDisponibilidad {
+        match evento {
+            Some(EventoDisponibilidad::Actualizacion(actualizacion)) => actualizacion,
+            otro => panic!("se esperaba una actualización: {:?}", otro),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_crear_confirmar_y_cancelar_llegan_al_suscriptor() {
+        let (reservas, en_vivo) = servicios(CAPACIDAD_CAMBIOS).await;
+        let mut suscripcion = en_vivo.suscribir(FiltroDisponibilidad::default());
+        let actor = Actor::sistema();
+
+        let reserva = reservas
+            .crear_reserva(
+                &actor,
+                "emp-001".to_string(),
+                slot(1, 10),
+                "Revisión".to_string(),
+                Some("s1".to_string()),
+            )
+            .await
+            .unwrap();
+        let creada = actualizacion(suscripcion.siguiente().await);
+        assert_eq!(creada.cambio, TipoCambio::Creada);
+        assert_eq!(creada.reserva_id, reserva.id);
+        assert_eq!(creada.sala_id.as_deref(), Some("s1"));
+        assert_eq!(creada.slots.len(), 1);
+        assert!(!creada.slots[0].disponible);
+        assert_eq!(
+            creada.slots[0].reserva_id.as_deref(),
+            Some(reserva.id.as_str())
+        );
+
+        reservas
+            .confirmar_reserva(&actor, &reserva.id)
+            .await
+            .unwrap();
+        let confirmada = actualizacion(suscripcion.siguiente().await);
+        assert_eq!(confirmada.cambio, TipoCambio::Confirmada);
+        assert!(!confirmada.slots[0].disponible);
+
+        reservas
+            .cancelar_reserva(&actor, &reserva.id)
+            .await
+            .unwrap();
+        let cancelada = actualizacion(suscripcion.siguiente().await);
+        assert_eq!(cancelada.cambio, TipoCambio::Cancelada);
+        assert!(cancelada.slots[0].disponible);
+        assert_eq!(cancelada.slots[0].reserva_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_filtros_y_reprogramacion() {
+        let (reservas, en_vivo) = servicios(CAPACIDAD_CAMBIOS).await;
+        let actor = Actor::sistema();
+        let manyana = slot(1, 9).inicio.date_naive();
+        let mut del_dia = en_vivo.suscribir(FiltroDisponibilidad {
+            fecha: Some(manyana),
+            ..Default::default()
+        });
+        let mut de_maria = en_vivo.suscribir(FiltroDisponibilidad {
+            empleado_id: Some("emp-002".to_string()),
+            ..Default::default()
+        });
+        let mut de_la_sala = en_vivo.suscribir(FiltroDisponibilidad {
+            sala_id: Some("s1".to_string()),
+            ..Default::default()
+        });
+
+        let juan = reservas
+            .crear_reserva(
+                &actor,
+                "emp-001".to_string(),
+                slot(1, 10),
+                "Juan".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        // Pasa a pasado mañana: el suscriptor del día solo ve liberarse el slot
+        reservas
+            .reprogramar_reserva(&actor, &juan.id, slot(2, 10))
+            .await
+            .unwrap();
+        let maria = reservas
+            .crear_reserva(
+                &actor,
+                "emp-002".to_string(),
+                slot(2, 11),
+                "María".to_string(),
+                Some("s1".to_string()),
+            )
+            .await
+            .unwrap();
+
+        let creada = actualizacion(del_dia.siguiente().await);
+        assert_eq!(creada.reserva_id, juan.id);
+        let reprogramada = actualizacion(del_dia.siguiente().await);
+        assert_eq!(reprogramada.cambio, TipoCambio::Reprogramada);
+        assert_eq!(reprogramada.slots.len(), 1);
+        assert_eq!(reprogramada.slots[0].slot, slot(1, 10));
+        assert!(reprogramada.slots[0].disponible);
+
+        // Los otros dos solo ven la reserva de María
+        assert_eq!(
+            actualizacion(de_maria.siguiente().await).reserva_id,
+            maria.id
+        );
+        let ocupada = actualizacion(de_la_sala.siguiente().await);
+        assert_eq!(ocupada.reserva_id, maria.id);
+        assert!(ocupada.slots.is_empty());
+        assert_eq!(ocupada.salas.len(), 1);
+        assert_eq!(ocupada.salas[0].sala_id, "s1");
+        assert!(!ocupada.salas[0].disponible);
+        assert_eq!(
+            ocupada.salas[0].reserva_id.as_deref(),
+            Some(maria.id.as_str())
+        );
+
+        // Al cancelarla, la sala queda libre
+        reservas.cancelar_reserva(&actor, &maria.id).await.unwrap();
+        let libre = actualizacion(de_la_sala.siguiente().await);
+        assert_eq!(libre.cambio, TipoCambio::Cancelada);
+        assert!(libre.salas[0].disponible);
+        assert_eq!(libre.salas[0].reserva_id, None);
+
+        // Y el del día no recibe nada más
+        en_vivo.notificar(CambioReserva {
+            tipo: TipoCambio::Eliminada,
+            reserva: maria,
+            slot_anterior: None,
+            empleado_anterior: None,
+        });
+        let pendiente =
+            tokio::time::timeout(std::time::Duration::from_millis(50), del_dia.siguiente()).await;
+        assert!(pendiente.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reasignar_libera_al_titular_anterior() {
+        let (reservas, en_vivo) = servicios(CAPACIDAD_CAMBIOS).await;
+        let actor = Actor::sistema();
+        let reserva = reservas
+            .crear_reserva(
+                &actor,
+                "emp-001".to_string(),
+                slot(1, 10),
+                "Revisión".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        let mut de_juan = en_vivo.suscribir(FiltroDisponibilidad {
+            empleado_id: Some("emp-001".to_string()),
+            ..Default::default()
+        });
+        let mut todos = en_vivo.suscribir(FiltroDisponibilidad::default());
+
+        reservas
+            .reasignar_reservas_futuras(&actor, "emp-001", "emp-002")
+            .await
+            .unwrap();
+
+        let reasignada = actualizacion(de_juan.siguiente().await);
+        assert_eq!(reasignada.cambio, TipoCambio::Reasignada);
+        assert_eq!(reasignada.reserva_id, reserva.id);
+        assert_eq!(reasignada.slots.len(), 1);
+        assert_eq!(reasignada.slots[0].empleado_id, "emp-001");
+        assert!(reasignada.slots[0].disponible);
+
+        let ocupacion: Vec<(String, bool)> = actualizacion(todos.siguiente().await)
+            .slots
+            .into_iter()
+            .map(|s| (s.empleado_id, s.disponible))
+            .collect();
+        assert!(ocupacion.contains(&("emp-001".to_string(), true)));
+        assert!(ocupacion.contains(&("emp-002".to_string(), false)));
+    }
+
+    #[tokio::test]
+    async fn test_suscriptor_lento_debe_resincronizar() {
+        let (reservas, en_vivo) = servicios(1).await;
+        let mut suscripcion = en_vivo.suscribir(FiltroDisponibilidad::default());
+        let actor = Actor::sistema();
+        for hora in [10, 11] {
+            reservas
+                .crear_reserva(
+                    &actor,
+                    "emp-001".to_string(),
+                    slot(1, hora),
+                    "Reunión".to_string(),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            suscripcion.siguiente().await,
+            Some(EventoDisponibilidad::Resincronizar)
+        );
+        let ultima = actualizacion(suscripcion.siguiente().await);
+        assert_eq!(ultima.slots[0].slot, slot(1, 11));
+    }
+}
@@ -0,0 +1,418 @@
+// Caso de uso de importación de reservas desde calendarios externos
+//
+// Cada evento se reparte en slots de una hora y cada asistente que es
+// empleado recibe una reserva por slot. Las reservas se crean a través de
+// `ReservaService`, así que valen las mismas reglas, permisos, conflictos y
+// notificaciones que al reservar a mano. Antes de crear nada se revisa todo
+// el lote, de modo que simular y confirmar dan el mismo informe.
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use reservas_domain::{Accion, Actor, Email, Empleado, Slot};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::r#in::importacion_service::{
+    ElementoImportacion, EventoExterno, ImportacionService, InformeImportacion,
+    ResultadoImportacion,
+};
+use reservas_ports::r#in::reserva_service::ReservaService;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Descripción de las reservas de eventos sin título
+const DESCRIPCION_POR_DEFECTO: &str = "Evento importado";
+
+/// Los eventos más largos no son reuniones sino bloqueos de agenda
+const DURACION_MAXIMA_HORAS: i64 = 24;
+
+pub struct ImportacionServiceImpl<E>
+where
+    E: EmpleadoRepository,
+{
+    empleados: E,
+    reservas: Arc<dyn ReservaService>,
+}
+
+impl<E> ImportacionServiceImpl<E>
+where
+    E: EmpleadoRepository,
+{
+    pub fn new(empleados: E, reservas: Arc<dyn ReservaService>) -> Self {
+        Self {
+            empleados,
+            reservas,
+        }
+    }
+
+    /// Empleado de un asistente, o el motivo por el que no cuenta
+    async fn resolver(
+        &self,
+        actor: &Actor,
+        email: &str,
+    ) -> Result<Result<Empleado, String>, String> {
+        let Ok(email) = Email::new(email) else {
+            return Ok(Err("Email no válido".to_string()));
+        };
+        let Some(empleado) = self.empleados.obtener_por_email(&email).await? else {
+            return Ok(Err("No es un empleado".to_string()));
+        };
+        if !empleado.activo {
+            return Ok(Err(format!("El empleado {} está desactivado", empleado.id)));
+        }
+        // Sin auditar: al confirmar, `crear_reserva` vuelve a comprobarlo
+        if !actor.puede(Accion::CrearReserva, Some(&empleado)) {
+            return Ok(Err(format!(
+                "Sin permiso para reservar a nombre de {}",
+                empleado.id
+            )));
+        }
+        Ok(Ok(empleado))
+    }
+
+    /// Slots activos del empleado, consultados una vez por importación
+    async fn ocupados(
+        &self,
+        cache: &mut HashMap<String, HashSet<Slot>>,
+        empleado_id: &str,
+    ) -> Result<HashSet<Slot>, String> {
+        if let Some(slots) = cache.get(empleado_id) {
+            return Ok(slots.clone());
+        }
+        let slots: HashSet<Slot> = self
+            .reservas
+            .listar_reservas_empleado(empleado_id)
+            .await?
+            .into_iter()
+            .filter(|r| r.esta_activa())
+            .map(|r| r.slot)
+            .collect();
+        cache.insert(empleado_id.to_string(), slots.clone());
+        Ok(slots)
+    }
+}
+
+/// Motivo para no importar el evento entero
+fn motivo_omision(evento: &EventoExterno) -> Option<&'static str> {
+    if evento.cancelado {
+        Some("Evento cancelado")
+    } else if evento.periodico {
+        Some("Los eventos periódicos no se importan")
+    } else if evento.dia_completo {
+        Some("Los eventos de día completo no se importan")
+    } else if evento.fin <= evento.inicio {
+        Some("El evento no tiene duración")
+    } else if evento.fin - evento.inicio > Duration::hours(DURACION_MAXIMA_HORAS) {
+        Some("El evento dura más de 24 horas")
+    } else if evento.asistentes.is_empty() {
+        Some("El evento no tiene asistentes")
+    } else {
+        None
+    }
+}
+
+/// Slots de una hora que tocan el evento: el inicio se redondea hacia abajo
+/// y el fin hacia arriba
+fn slots(evento: &EventoExterno) -> Vec<Slot> {
+    let mut slots = Vec::new();
+    let mut slot = Slot::new(evento.inicio);
+    while slot.inicio < evento.fin {
+        let siguiente = slot.siguiente();
+        slots.push(slot);
+        slot = siguiente;
+    }
+    slots
+}
+
+fn elemento(
+    evento: &EventoExterno,
+    resultado: ResultadoImportacion,
+    detalle: impl Into<String>,
+) -> ElementoImportacion {
+    ElementoImportacion {
+        uid: evento.uid.clone(),
+        email: None,
+        empleado_id: None,
+        slot: None,
+        resultado,
+        detalle: Some(detalle.into()),
+        reserva_id: None,
+    }
+}
+
+#[async_trait]
+impl<E> ImportacionService for ImportacionServiceImpl<E>
+where
+    E: EmpleadoRepository + Send + Sync,
+{
+    async fn importar(
+        &self,
+        actor: &Actor,
+        eventos: Vec<EventoExterno>,
+        simular: bool,
+    ) -> Result<InformeImportacion, ErrorServicio> {
+        let mut informe = InformeImportacion {
+            eventos: eventos.len(),
+            elementos: Vec::new(),
+        };
+        let ahora = Utc::now();
+        let mut ocupados = HashMap::new();
+        // Slots ya asignados en este lote, por si dos eventos se solapan
+        let mut asignados: HashSet<(String, Slot)> = HashSet::new();
+        let mut pendientes = Vec::new();
+
+        for evento in &eventos {
+            if let Some(motivo) = motivo_omision(evento) {
+                informe
+                    .elementos
+                    .push(elemento(evento, ResultadoImportacion::Omitida, motivo));
+                continue;
+            }
+            let descripcion = match evento.resumen.trim() {
+                "" => DESCRIPCION_POR_DEFECTO.to_string(),
+                resumen => resumen.to_string(),
+            };
+
+            let mut vistos = HashSet::new();
+            for email in &evento.asistentes {
+                if !vistos.insert(email.to_lowercase()) {
+                    continue;
+                }
+                let empleado = match self.resolver(actor, email).await? {
+                    Ok(empleado) => empleado,
+                    Err(motivo) => {
+                        let mut omitido = elemento(evento, ResultadoImportacion::Omitida, motivo);
+                        omitido.email = Some(email.clone());
+                        informe.elementos.push(omitido);
+                        continue;
+                    }
+                };
+                let ya_ocupados = self.ocupados(&mut ocupados, &empleado.id).await?;
+
+                for slot in slots(evento) {
+                    let (resultado, detalle) = if slot.inicio < ahora {
+                        (ResultadoImportacion::Omitida, Some("Slot en el pasado"))
+                    } else if !slot.es_horario_laboral() {
+                        (
+                            ResultadoImportacion::Omitida,
+                            Some("Slot fuera del horario laboral"),
+                        )
+                    } else if ya_ocupados.contains(&slot) {
+                        (
+                            ResultadoImportacion::Conflicto,
+                            Some("El empleado ya tiene una reserva en ese slot"),
+                        )
+                    } else if !asignados.insert((empleado.id.clone(), slot.clone())) {
+                        (
+                            ResultadoImportacion::Conflicto,
+                            Some("Otro evento importado ocupa ese slot"),
+                        )
+                    } else {
+                        pendientes.push((informe.elementos.len(), descripcion.clone()));
+                        (ResultadoImportacion::Importada, None)
+                    };
+                    informe.elementos.push(ElementoImportacion {
+                        uid: evento.uid.clone(),
+                        email: Some(email.clone()),
+                        empleado_id: Some(empleado.id.clone()),
+                        slot: Some(slot),
+                        resultado,
+                        detalle: detalle.map(str::to_string),
+                        reserva_id: None,
+                    });
+                }
+            }
+        }
+
+        if simular {
+            return Ok(informe);
+        }
+        for (indice, descripcion) in pendientes {
+            let elemento = &mut informe.elementos[indice];
+            let (Some(empleado_id), Some(slot)) = (&elemento.empleado_id, &elemento.slot) else {
+                continue;
+            };
+            match self
+                .reservas
+                .crear_reserva(actor, empleado_id.clone(), slot.clone(), descripcion, None)
+                .await
+            {
+                Ok(reserva) => elemento.reserva_id = Some(reserva.id),
+                // Alguien reservó entre la revisión y la creación
+                Err(e) => {
+                    elemento.resultado = ResultadoImportacion::Conflicto;
+                    elemento.detalle = Some(e.to_string());
+                }
+            }
+        }
+        Ok(informe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReservaServiceImpl;
+    use chrono::{DateTime, Datelike, TimeZone};
+    use reservas_adapters::{
+        InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+    };
+    use reservas_domain::Rol;
+
+    fn manyana(hora: u32, minuto: u32) -> DateTime<Utc> {
+        let dia = Utc::now() + Duration::days(1);
+        Utc.with_ymd_and_hms(dia.year(), dia.month(), dia.day(), hora, minuto, 0)
+            .unwrap()
+    }
+
+    fn evento(
+        uid: &str,
+        inicio: DateTime<Utc>,
+        fin: DateTime<Utc>,
+        asistentes: &[&str],
+    ) -> EventoExterno {
+        EventoExterno {
+            uid: uid.to_string(),
+            resumen: "Planificación".to_string(),
+            inicio,
+            fin,
+            asistentes: asistentes.iter().map(|a| a.to_string()).collect(),
+            cancelado: false,
+            dia_completo: false,
+            periodico: false,
+        }
+    }
+
+    async fn escenario() -> (
+        ImportacionServiceImpl<Arc<InMemoryEmpleadoRepository>>,
+        Arc<dyn ReservaService>,
+    ) {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        for (id, nombre) in [("emp-001", "Juan"), ("emp-002", "Ana")] {
+            let email = format!("{}@empresa.com", nombre.to_lowercase());
+            empleados
+                .guardar(&Empleado::new(id.to_string(), nombre.to_string(), email).unwrap())
+                .await
+                .unwrap();
+        }
+        let reservas: Arc<dyn ReservaService> = Arc::new(ReservaServiceImpl::new(
+            Arc::new(InMemoryReservaRepository::new()),
+            Arc::clone(&empleados),
+            Arc::new(InMemorySalaRepository::new()),
+        ));
+        reservas
+            .crear_reserva(
+                &Actor::sistema(),
+                "emp-002".to_string(),
+                Slot::new(manyana(11, 0)),
+                "Ocupada".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        (
+            ImportacionServiceImpl::new(empleados, Arc::clone(&reservas)),
+            reservas,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_simular_informa_sin_crear_y_confirmar_crea() {
+        let (servicio, reservas) = escenario().await;
+        let eventos = vec![
+            // 10:30-12:00 ocupa los slots de las 10 y las 11
+            evento(
+                "a",
+                manyana(10, 30),
+                manyana(12, 0),
+                &["Juan@Empresa.com", "ana@empresa.com", "externo@otra.com"],
+            ),
+            // Se solapa con el anterior para Juan
+            evento("b", manyana(11, 0), manyana(11, 15), &["juan@empresa.com"]),
+            EventoExterno {
+                cancelado: true,
+                ..evento("c", manyana(15, 0), manyana(16, 0), &["juan@empresa.com"])
+            },
+        ];
+
+        let informe = servicio
+            .importar(&Actor::sistema(), eventos.clone(), true)
+            .await
+            .unwrap();
+        assert_eq!(informe.eventos, 3);
+        // Juan 10 y 11, Ana 10
+        assert_eq!(informe.contar(ResultadoImportacion::Importada), 3);
+        // Ana 11 (ya reservada) y Juan 11 del evento "b"
+        assert_eq!(informe.contar(ResultadoImportacion::Conflicto), 2);
+        // Externo y el evento cancelado
+        assert_eq!(informe.contar(ResultadoImportacion::Omitida), 2);
+        assert!(informe.elementos.iter().all(|e| e.reserva_id.is_none()));
+        assert!(reservas
+            .listar_reservas_empleado("emp-001")
+            .await
+            .unwrap()
+            .is_empty());
+
+        let confirmado = servicio
+            .importar(&Actor::sistema(), eventos, false)
+            .await
+            .unwrap();
+        assert_eq!(confirmado.contar(ResultadoImportacion::Importada), 3);
+        let creadas = reservas.listar_reservas_empleado("emp-001").await.unwrap();
+        assert_eq!(creadas.len(), 2);
+        assert!(creadas.iter().all(|r| r.descripcion == "Planificación"));
+        assert!(confirmado
+            .elementos
+            .iter()
+            .filter(|e| e.resultado == ResultadoImportacion::Importada)
+            .all(|e| e.reserva_id.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_omite_lo_que_no_se_puede_reservar() {
+        let (servicio, _) = escenario().await;
+        let juan = Actor::new("juan", Some("emp-001".to_string()), vec![Rol::Empleado]);
+        let eventos = vec![
+            // 17:00-19:00: solo el slot de las 17 es laboral
+            evento(
+                "tarde",
+                manyana(17, 0),
+                manyana(19, 0),
+                &["juan@empresa.com", "ana@empresa.com"],
+            ),
+            EventoExterno {
+                dia_completo: true,
+                ..evento(
+                    "dia",
+                    manyana(0, 0),
+                    manyana(0, 0) + Duration::days(1),
+                    &["juan@empresa.com"],
+                )
+            },
+        ];
+
+        let informe = servicio.importar(&juan, eventos, false).await.unwrap();
+        let detalles: Vec<_> = informe
+            .elementos
+            .iter()
+            .map(|e| (e.resultado, e.detalle.as_deref()))
+            .collect();
+        assert_eq!(
+            detalles,
+            [
+                (ResultadoImportacion::Importada, None),
+                (
+                    ResultadoImportacion::Omitida,
+                    Some("Slot fuera del horario laboral")
+                ),
+                (
+                    ResultadoImportacion::Omitida,
+                    Some("Sin permiso para reservar a nombre de emp-002")
+                ),
+                (
+                    ResultadoImportacion::Omitida,
+                    Some("Los eventos de día completo no se importan")
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,225 @@
+// Caso de uso de informes de ocupación
+//
+// Lee empleados, salas y reservas a través de los puertos de salida y deja
+// el cálculo al dominio (`InformeOcupacion`). Las exportaciones son tablas
+// de texto, como las de las cargas masivas, para que la API y la web las
+// escriban en CSV o XLSX.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use reservas_domain::{InformeOcupacion, Periodo, UsoRecurso};
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::reserva_repository::ReservaRepository;
+use reservas_ports::out::sala_repository::SalaRepository;
+use reservas_ports::r#in::carga_masiva_service::Tabla;
+use reservas_ports::r#in::informe_service::{Desglose, InformeService};
+
+pub struct InformeServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+{
+    empleados: E,
+    salas: S,
+    reservas: R,
+}
+
+impl<E, S, R> InformeServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+{
+    pub fn new(empleados: E, salas: S, reservas: R) -> Self {
+        Self {
+            empleados,
+            salas,
+            reservas,
+        }
+    }
+}
+
+/// Fracción con cuatro decimales y punto, que cualquier hoja lee como número
+fn decimal(valor: f64) -> String {
+    format!("{:.4}", valor)
+}
+
+fn tabla_usos(usos: &[UsoRecurso]) -> Tabla {
+    let mut tabla = Tabla::new(&["id", "nombre", "reservas", "canceladas", "ocupacion"]);
+    for uso in usos {
+        tabla.filas.push(vec![
+            uso.id.clone(),
+            uso.nombre.clone(),
+            uso.reservas.to_string(),
+            uso.canceladas.to_string(),
+            decimal(uso.ocupacion),
+        ]);
+    }
+    tabla
+}
+
+#[async_trait]
+impl<E, S, R> InformeService for InformeServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository + Send + Sync,
+    S: SalaRepository + Send + Sync,
+    R: ReservaRepository + Send + Sync,
+{
+    async fn informe_ocupacion(&self, periodo: Periodo) -> Result<InformeOcupacion, String> {
+        let empleados = self.empleados.listar().await?;
+        let salas = self.salas.listar().await?;
+        let reservas = self.reservas.listar().await?;
+        Ok(InformeOcupacion::generar(
+            periodo,
+            &empleados,
+            &salas,
+            &reservas,
+            Utc::now(),
+        ))
+    }
+
+    async fn exportar_informe(
+        &self,
+        periodo: Periodo,
+        desglose: Desglose,
+    ) -> Result<Tabla, String> {
+        let informe = self.informe_ocupacion(periodo).await?;
+        Ok(match desglose {
+            Desglose::Resumen => {
+                let mut tabla = Tabla::new(&["metrica", "valor"]);
+                for (metrica, valor) in [
+                    ("desde", informe.periodo.desde.to_string()),
+                    ("hasta", informe.periodo.hasta.to_string()),
+                    ("reservas", informe.total.to_string()),
+                    ("pendientes", informe.pendientes.to_string()),
+                    ("confirmadas", informe.confirmadas.to_string()),
+                    ("canceladas", informe.canceladas.to_string()),
+                    ("no_presentadas", informe.no_presentadas.to_string()),
+                    ("tasa_cancelacion", decimal(informe.tasa_cancelacion)),
+                    (
+                        "tasa_no_presentacion",
+                        decimal(informe.tasa_no_presentacion),
+                    ),
+                ] {
+                    tabla.filas.push(vec![metrica.to_string(), valor]);
+                }
+                tabla
+            }
+            Desglose::Empleados => tabla_usos(&informe.por_empleado),
+            Desglose::Salas => tabla_usos(&informe.por_sala),
+            Desglose::Horas => {
+                let mut tabla = Tabla::new(&["hora", "reservas", "ocupacion"]);
+                for uso in &informe.por_hora {
+                    tabla.filas.push(vec![
+                        format!("{:02}:00", uso.hora),
+                        uso.reservas.to_string(),
+                        decimal(uso.ocupacion),
+                    ]);
+                }
+                tabla
+            }
+            Desglose::Dias => {
+                let mut tabla = Tabla::new(&["fecha", "reservas", "ocupacion"]);
+                for uso in &informe.por_dia {
+                    tabla.filas.push(vec![
+                        uso.fecha.to_string(),
+                        uso.reservas.to_string(),
+                        decimal(uso.ocupacion),
+                    ]);
+                }
+                tabla
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Duration};
+    use reservas_adapters::{
+        InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+    };
+    use reservas_domain::{Empleado, Reserva, Sala, Slot};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_informe_y_exportaciones() {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let salas = Arc::new(InMemorySalaRepository::new());
+        let reservas = Arc::new(InMemoryReservaRepository::new());
+        empleados
+            .guardar(
+                &Empleado::new(
+                    "emp-001".to_string(),
+                    "Juan".to_string(),
+                    "juan@empresa.com".to_string(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        salas
+            .guardar(&Sala::new("s1".to_string(), "Sala Azul".to_string(), 8).unwrap())
+            .await
+            .unwrap();
+        let manyana = Utc::now() + Duration::days(1);
+        let slot =
+            Slot::from_date_and_hour(manyana.year(), manyana.month(), manyana.day(), 10).unwrap();
+        reservas
+            .guardar(
+                &Reserva::new(
+                    "r1".to_string(),
+                    "emp-001".to_string(),
+                    slot,
+                    "Reunión".to_string(),
+                )
+                .unwrap()
+                .en_sala("s1".to_string()),
+            )
+            .await
+            .unwrap();
+
+        let servicio = InformeServiceImpl::new(empleados, salas, reservas);
+        let periodo = Periodo::semana(manyana.date_naive());
+        let informe = servicio.informe_ocupacion(periodo).await.unwrap();
+        assert_eq!(informe.total, 1);
+        assert_eq!(informe.por_sala[0].reservas, 1);
+
+        let tabla = servicio
+            .exportar_informe(periodo, Desglose::Empleados)
+            .await
+            .unwrap();
+        assert_eq!(tabla.columnas[4], "ocupacion");
+        assert_eq!(
+            tabla.filas,
+            [["emp-001", "Juan", "1", "0", "0.0159"].map(String::from)]
+        );
+
+        let tabla = servicio
+            .exportar_informe(periodo, Desglose::Horas)
+            .await
+            .unwrap();
+        assert_eq!(tabla.filas.len(), 9);
+        assert_eq!(tabla.filas[1], ["10:00", "1", "0.1429"]);
+
+        let tabla = servicio
+            .exportar_informe(periodo, Desglose::Resumen)
+            .await
+            .unwrap();
+        assert_eq!(tabla.filas[2], ["reservas", "1"]);
+        assert_eq!(
+            servicio
+                .exportar_informe(periodo, Desglose::Dias)
+                .await
+                .unwrap()
+                .filas
+                .len(),
+            7
+        );
+
+        assert!("semanas".parse::<Desglose>().is_err());
+        assert_eq!("Días".parse::<Desglose>(), Ok(Desglose::Dias));
+    }
+}
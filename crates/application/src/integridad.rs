@@ -0,0 +1,422 @@
+// Caso de uso de verificación de integridad (fsck)
+//
+// Recorre empleados, salas y reservas a través de los puertos de salida y
+// detecta datos que las reglas actuales ya no dejarían entrar: referencias
+// rotas, solapamientos, reservas futuras de empleados o salas inactivos y
+// reservas fuera de horario. La reparación es conservadora: nunca borra, solo cancela
+// la reserva activa que causa el problema, a través del servicio de reservas
+// para que la cancelación espere a las escrituras en curso y se notifique.
+
+use crate::autorizacion::Autorizador;
+use async_trait::async_trait;
+use chrono::Utc;
+use reservas_domain::{Accion, Actor, EstadoReserva, Reserva, Slot};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::out::auditoria::RegistroAuditoria;
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::reserva_repository::ReservaRepository;
+use reservas_ports::out::sala_repository::SalaRepository;
+use reservas_ports::r#in::integridad_service::{
+    Incidencia, InformeIntegridad, IntegridadService, TipoIncidencia,
+};
+use reservas_ports::r#in::reserva_service::ReservaService;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+pub struct IntegridadServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+{
+    empleados: E,
+    salas: S,
+    reservas: R,
+    servicio_reservas: Arc<dyn ReservaService>,
+    autorizador: Autorizador,
+}
+
+impl<E, S, R> IntegridadServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+{
+    pub fn new(
+        empleados: E,
+        salas: S,
+        reservas: R,
+        servicio_reservas: Arc<dyn ReservaService>,
+    ) -> Self {
+        Self {
+            empleados,
+            salas,
+            reservas,
+            servicio_reservas,
+            autorizador: Autorizador::default(),
+        }
+    }
+
+    /// Registra en `auditoria` las operaciones denegadas
+    pub fn con_auditoria(mut self, auditoria: Arc<dyn RegistroAuditoria>) -> Self {
+        self.autorizador = Autorizador::new(auditoria);
+        self
+    }
+
+    async fn revisar(&self, reparar: bool) -> Result<InformeIntegridad, String> {
+        let empleados: HashMap<_, _> = self
+            .empleados
+            .listar()
+            .await?
+            .into_iter()
+            .map(|e| (e.id.clone(), e.activo))
+            .collect();
+        let salas: HashMap<_, _> = self
+            .salas
+            .listar()
+            .await?
+            .into_iter()
+            .map(|s| (s.id.clone(), s.activa))
+            .collect();
+        let reservas = self.reservas.listar().await?;
+        let ahora = Utc::now();
+
+        let mut revision = Revision::default();
+
+        for reserva in &reservas {
+            let activa = reserva.esta_activa();
+            // Desactivar solo afecta a las reservas futuras; las pasadas son
+            // historia y se quedan como estaban
+            let pendiente = activa && reserva.slot.inicio >= ahora;
+
+            match empleados.get(&reserva.empleado_id) {
+                None => revision.anotar(
+                    reserva,
+                    TipoIncidencia::EmpleadoInexistente,
+                    format!("El empleado {} no existe", reserva.empleado_id),
+                ),
+                Some(false) if pendiente => revision.anotar(
+                    reserva,
+                    TipoIncidencia::EmpleadoInactivo,
+                    format!("El empleado {} está desactivado", reserva.empleado_id),
+                ),
+                _ => {}
+            }
+
+            if let Some(sala_id) = &reserva.sala_id {
+                match salas.get(sala_id) {
+                    None => revision.anotar(
+                        reserva,
+                        TipoIncidencia::SalaInexistente,
+                        format!("La sala {} no existe", sala_id),
+                    ),
+                    Some(false) if pendiente => revision.anotar(
+                        reserva,
+                        TipoIncidencia::SalaInactiva,
+                        format!("La sala {} está desactivada", sala_id),
+                    ),
+                    _ => {}
+                }
+            }
+
+            if activa && !reserva.slot.es_horario_laboral() {
+                revision.anotar(
+                    reserva,
+                    TipoIncidencia::FueraDeHorario,
+                    format!(
+                        "El slot {} está fuera del horario laboral",
+                        reserva.slot.formato_legible()
+                    ),
+                );
+            }
+        }
+
+        // Solapamientos entre las activas que no se van a cancelar ya por
+        // otro motivo: en cada grupo se conserva una (la confirmada si la
+        // hay, y si no la de ID menor) y las demás son incidencias.
+        let mut por_empleado: HashMap<(&str, &Slot), Vec<&Reserva>> = HashMap::new();
+        let mut por_sala: HashMap<(&str, &Slot), Vec<&Reserva>> = HashMap::new();
+        for reserva in reservas
+            .iter()
+            .filter(|r| r.esta_activa() && !revision.a_cancelar.contains(&r.id))
+        {
+            por_empleado
+                .entry((&reserva.empleado_id, &reserva.slot))
+                .or_default()
+                .push(reserva);
+            if let Some(sala_id) = &reserva.sala_id {
+                por_sala
+                    .entry((sala_id, &reserva.slot))
+                    .or_default()
+                    .push(reserva);
+            }
+        }
+        let mut grupos: Vec<_> = por_empleado
+            .into_values()
+            .map(|g| ("del empleado", g))
+            .chain(por_sala.into_values().map(|g| ("de la sala", g)))
+            .filter(|(_, g)| g.len() > 1)
+            .collect();
+        // Orden estable del informe, independiente del HashMap
+        grupos.iter_mut().for_each(|(_, g)| {
+            g.sort_by_key(|r| (r.estado != EstadoReserva::Confirmada, r.id.clone()))
+        });
+        grupos.sort_by(|(_, a), (_, b)| {
+            (&a[0].slot.inicio, &a[0].id).cmp(&(&b[0].slot.inicio, &b[0].id))
+        });
+
+        for (recurso, grupo) in grupos {
+            let conservada = grupo[0];
+            for reserva in &grupo[1..] {
+                if revision.a_cancelar.contains(&reserva.id) {
+                    continue;
+                }
+                revision.anotar(
+                    reserva,
+                    TipoIncidencia::Solapamiento,
+                    format!(
+                        "Coincide con la reserva {} {} en el slot {}",
+                        conservada.id,
+                        recurso,
+                        reserva.slot.formato_legible()
+                    ),
+                );
+            }
+        }
+
+        if reparar {
+            for id in &revision.orden_cancelacion {
+                self.servicio_reservas
+                    .cancelar_reserva(&Actor::sistema(), id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                revision
+                    .incidencias
+                    .iter_mut()
+                    .filter(|i| &i.reserva_id == id)
+                    .for_each(|i| i.reparada = true);
+            }
+        }
+
+        Ok(InformeIntegridad {
+            reservas_revisadas: reservas.len(),
+            incidencias: revision.incidencias,
+        })
+    }
+}
+
+#[async_trait]
+impl<E, S, R> IntegridadService for IntegridadServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository + Send + Sync,
+    S: SalaRepository + Send + Sync,
+    R: ReservaRepository + Send + Sync,
+{
+    async fn verificar(
+        &self,
+        actor: &Actor,
+        reparar: bool,
+    ) -> Result<InformeIntegridad, ErrorServicio> {
+        self.autorizador
+            .exigir(actor, Accion::Administrar, None, "integridad".to_string())
+            .await?;
+        Ok(self.revisar(reparar).await?)
+    }
+}
+
+/// Incidencias acumuladas durante una pasada
+#[derive(Default)]
+struct Revision {
+    incidencias: Vec<Incidencia>,
+    /// Reservas activas afectadas (las que `reparar` cancelaría)
+    a_cancelar: HashSet<String>,
+    orden_cancelacion: Vec<String>,
+}
+
+impl Revision {
+    fn anotar(&mut self, reserva: &Reserva, tipo: TipoIncidencia, detalle: String) {
+        if reserva.esta_activa() && self.a_cancelar.insert(reserva.id.clone()) {
+            self.orden_cancelacion.push(reserva.id.clone());
+        }
+        self.incidencias.push(Incidencia {
+            tipo,
+            reserva_id: reserva.id.clone(),
+            detalle,
+            reparada: false,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReservaServiceImpl;
+    use chrono::{Datelike, Duration};
+    use reservas_adapters::{
+        InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+    };
+    use reservas_domain::{Empleado, Sala};
+    use std::sync::Arc;
+
+    fn slot(hora: u32) -> Slot {
+        let manyana = Utc::now() + Duration::days(1);
+        Slot::from_date_and_hour(manyana.year(), manyana.month(), manyana.day(), hora).unwrap()
+    }
+
+    fn reserva(id: &str, empleado_id: &str, hora: u32, sala_id: Option<&str>) -> Reserva {
+        Reserva {
+            id: id.to_string(),
+            empleado_id: empleado_id.to_string(),
+            slot: slot(hora),
+            descripcion: "Reunión".to_string(),
+            estado: EstadoReserva::Pendiente,
+            sala_id: sala_id.map(str::to_string),
+        }
+    }
+
+    async fn escenario() -> (
+        IntegridadServiceImpl<
+            Arc<InMemoryEmpleadoRepository>,
+            Arc<InMemorySalaRepository>,
+            Arc<InMemoryReservaRepository>,
+        >,
+        Arc<InMemoryReservaRepository>,
+    ) {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let salas = Arc::new(InMemorySalaRepository::new());
+        let reservas = Arc::new(InMemoryReservaRepository::new());
+
+        empleados
+            .guardar(
+                &Empleado::new(
+                    "emp-001".to_string(),
+                    "Juan".to_string(),
+                    "juan@empresa.com".to_string(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let mut inactivo = Empleado::new(
+            "emp-002".to_string(),
+            "Ana".to_string(),
+            "ana@empresa.com".to_string(),
+        )
+        .unwrap();
+        inactivo.desactivar();
+        empleados.guardar(&inactivo).await.unwrap();
+        salas
+            .guardar(&Sala::new("s1".to_string(), "Sala Azul".to_string(), 8).unwrap())
+            .await
+            .unwrap();
+
+        let servicio_reservas = Arc::new(ReservaServiceImpl::new(
+            Arc::clone(&reservas),
+            Arc::clone(&empleados),
+            Arc::clone(&salas),
+        ));
+        let servicio =
+            IntegridadServiceImpl::new(empleados, salas, Arc::clone(&reservas), servicio_reservas);
+        (servicio, reservas)
+    }
+
+    #[tokio::test]
+    async fn test_datos_consistentes() {
+        let (servicio, reservas) = escenario().await;
+        reservas
+            .guardar(&reserva("r1", "emp-001", 10, Some("s1")))
+            .await
+            .unwrap();
+
+        let informe = servicio.verificar(&Actor::sistema(), false).await.unwrap();
+        assert!(informe.es_consistente());
+        assert_eq!(informe.reservas_revisadas, 1);
+    }
+
+    #[tokio::test]
+    async fn test_detecta_todas_las_incidencias() {
+        let (servicio, reservas) = escenario().await;
+        for r in [
+            reserva("r1", "emp-999", 10, None),
+            reserva("r2", "emp-001", 11, Some("s9")),
+            reserva("r3", "emp-002", 12, None),
+            reserva("r4", "emp-001", 20, None),
+            reserva("r5", "emp-001", 14, None),
+            reserva("r6", "emp-001", 14, None),
+        ] {
+            reservas.guardar(&r).await.unwrap();
+        }
+
+        let informe = servicio.verificar(&Actor::sistema(), false).await.unwrap();
+        let tipos: Vec<_> = informe
+            .incidencias
+            .iter()
+            .map(|i| (i.reserva_id.as_str(), i.tipo))
+            .collect();
+
+        assert_eq!(informe.incidencias.len(), 5);
+        assert!(tipos.contains(&("r1", TipoIncidencia::EmpleadoInexistente)));
+        assert!(tipos.contains(&("r2", TipoIncidencia::SalaInexistente)));
+        assert!(tipos.contains(&("r3", TipoIncidencia::EmpleadoInactivo)));
+        assert!(tipos.contains(&("r4", TipoIncidencia::FueraDeHorario)));
+        assert!(tipos.contains(&("r6", TipoIncidencia::Solapamiento)));
+        // Sin reparar no se toca nada
+        assert!(reservas.obtener("r6").await.unwrap().unwrap().esta_activa());
+    }
+
+    #[tokio::test]
+    async fn test_solapamiento_conserva_la_confirmada() {
+        let (servicio, reservas) = escenario().await;
+        reservas
+            .guardar(&reserva("r1", "emp-001", 10, Some("s1")))
+            .await
+            .unwrap();
+        let mut confirmada = reserva("r2", "emp-001", 10, Some("s1"));
+        confirmada.confirmar();
+        reservas.guardar(&confirmada).await.unwrap();
+
+        let informe = servicio.verificar(&Actor::sistema(), true).await.unwrap();
+
+        // Se solapan por empleado y por sala, pero es una única reserva a cancelar
+        assert_eq!(informe.incidencias.len(), 1);
+        assert_eq!(informe.incidencias[0].reserva_id, "r1");
+        assert_eq!(informe.reparadas(), 1);
+        assert!(!reservas.obtener("r1").await.unwrap().unwrap().esta_activa());
+        assert!(reservas.obtener("r2").await.unwrap().unwrap().esta_activa());
+    }
+
+    #[tokio::test]
+    async fn test_reparar_cancela_activas_y_no_toca_canceladas() {
+        let (servicio, reservas) = escenario().await;
+        reservas
+            .guardar(&reserva("r1", "emp-999", 10, None))
+            .await
+            .unwrap();
+        let mut cancelada = reserva("r2", "emp-999", 11, None);
+        cancelada.cancelar();
+        reservas.guardar(&cancelada).await.unwrap();
+
+        let informe = servicio.verificar(&Actor::sistema(), true).await.unwrap();
+        assert_eq!(informe.incidencias.len(), 2);
+        assert_eq!(informe.reparadas(), 1);
+        assert!(!reservas.obtener("r1").await.unwrap().unwrap().esta_activa());
+
+        // La segunda pasada sigue informando la referencia rota, sin nada que reparar
+        let informe = servicio.verificar(&Actor::sistema(), true).await.unwrap();
+        assert_eq!(informe.incidencias.len(), 2);
+        assert_eq!(informe.reparadas(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_no_toca_reservas_pasadas_de_inactivos() {
+        let (servicio, reservas) = escenario().await;
+        let ayer = Utc::now() - Duration::days(1);
+        let mut pasada = reserva("r1", "emp-002", 10, None);
+        pasada.slot = Slot::from_date_and_hour(ayer.year(), ayer.month(), ayer.day(), 10).unwrap();
+        reservas.guardar(&pasada).await.unwrap();
+
+        let informe = servicio.verificar(&Actor::sistema(), true).await.unwrap();
+        assert!(informe.es_consistente());
+        assert!(reservas.obtener("r1").await.unwrap().unwrap().esta_activa());
+    }
+}
@@ -9,58 +9,380 @@
 // - Gestionar transacciones
 // - Llamar al dominio
 
+mod auditoria;
+mod autorizacion;
+mod calendario;
+mod carga_masiva;
+mod cuentas;
+mod en_vivo;
+mod importacion;
+mod informes;
+mod integridad;
+mod matriz;
+mod respaldo;
+mod salud;
+mod sso;
+mod webhooks;
+
+pub use auditoria::AuditoriaServiceImpl;
+pub use autorizacion::Autorizador;
+pub use calendario::CalendarioServiceImpl;
+pub use carga_masiva::CargaMasivaServiceImpl;
+pub use cuentas::CuentaServiceImpl;
+pub use en_vivo::DisponibilidadEnVivoServiceImpl;
+pub use importacion::ImportacionServiceImpl;
+pub use informes::InformeServiceImpl;
+pub use integridad::IntegridadServiceImpl;
+pub use matriz::MatrizDisponibilidadServiceImpl;
+pub use respaldo::{validar as validar_respaldo, RespaldoServiceImpl};
+pub use salud::SaludServiceImpl;
+pub use sso::SsoServiceImpl;
+pub use webhooks::{
+    firmar as firmar_webhook, WebhookServiceImpl, CABECERA_ENTREGA, CABECERA_EVENTO, CABECERA_FIRMA,
+};
+
 use async_trait::async_trait;
-use reservas_domain::{Empleado, Reserva, Sala, Slot};
-use reservas_ports::r#in::reserva_service::ReservaService;
+use chrono::Utc;
+use reservas_domain::{
+    Accion, Actor, DisponibilidadService, Empleado, ErrorCampo, EstadoReserva,
+    MatrizDisponibilidad, Periodo, Reserva, Sala, Slot,
+};
+use reservas_ports::consulta::{ConsultaEmpleados, ConsultaReservas, ConsultaSalas, Pagina};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::out::auditoria::RegistroAuditoria;
+use reservas_ports::out::metricas::{Metricas, RecursoEnConflicto};
+use reservas_ports::out::notificador::{CambioReserva, NotificadorCambios, TipoCambio};
+use reservas_ports::r#in::reserva_service::{CambiosReserva, ReservaService};
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use reservas_ports::out::empleado_repository::EmpleadoRepository;
 use reservas_ports::out::reserva_repository::ReservaRepository;
 use reservas_ports::out::sala_repository::SalaRepository;
-use reservas_ports::r#in::empleado_service::EmpleadoService;
-use reservas_ports::r#in::sala_service::SalaService;
+use reservas_ports::r#in::empleado_service::{CambiosEmpleado, EmpleadoService, ErrorEmpleado};
+use reservas_ports::r#in::sala_service::{BusquedaSalas, CambiosSala, SalaService};
 
-/// Servicio de aplicación que implementa los casos de uso de reservas
-pub struct ReservaServiceImpl<R: ReservaRepository> {
+/// Servicio de aplicación que implementa los casos de uso de reservas.
+/// Depende también de los repositorios de empleados y salas para validar
+/// las referencias de cada reserva nueva.
+pub struct ReservaServiceImpl<R, E, S>
+where
+    R: ReservaRepository,
+    E: EmpleadoRepository,
+    S: SalaRepository,
+{
     repository: R,
+    empleados: E,
+    salas: S,
+    /// Serializa las escrituras: nadie ocupa el slot entre la comprobación
+    /// de conflictos y la escritura, y nadie pisa una reserva entre que otro
+    /// la lee y la vuelve a guardar entera
+    escrituras: Mutex<()>,
+    autorizador: Autorizador,
+    notificadores: Vec<Arc<dyn NotificadorCambios>>,
+    metricas: Option<Arc<dyn Metricas>>,
 }
 
-impl<R: ReservaRepository> ReservaServiceImpl<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R, E, S> ReservaServiceImpl<R, E, S>
+where
+    R: ReservaRepository,
+    E: EmpleadoRepository,
+    S: SalaRepository,
+{
+    pub fn new(repository: R, empleados: E, salas: S) -> Self {
+        Self {
+            repository,
+            empleados,
+            salas,
+            escrituras: Mutex::new(()),
+            autorizador: Autorizador::default(),
+            notificadores: Vec::new(),
+            metricas: None,
+        }
     }
-}
 
-#[async_trait]
-impl<R: ReservaRepository + Send + Sync> ReservaService for ReservaServiceImpl<R> {
-    async fn crear_reserva(
+    /// Registra en `auditoria` las operaciones denegadas
+    pub fn con_auditoria(mut self, auditoria: Arc<dyn RegistroAuditoria>) -> Self {
+        self.autorizador = Autorizador::new(auditoria);
+        self
+    }
+
+    /// Avisa a `notificador` de cada reserva creada, modificada o borrada.
+    /// Se puede llamar varias veces para avisar a varios.
+    pub fn con_notificador(mut self, notificador: Arc<dyn NotificadorCambios>) -> Self {
+        self.notificadores.push(notificador);
+        self
+    }
+
+    /// Cuenta en `metricas` los cambios en las reservas y los conflictos
+    /// rechazados
+    pub fn con_metricas(mut self, metricas: Arc<dyn Metricas>) -> Self {
+        self.metricas = Some(metricas);
+        self
+    }
+
+    fn notificar(&self, tipo: TipoCambio, reserva: &Reserva, slot_anterior: Option<Slot>) {
+        self.notificar_cambio(CambioReserva {
+            tipo,
+            reserva: reserva.clone(),
+            slot_anterior,
+            empleado_anterior: None,
+        });
+    }
+
+    fn notificar_cambio(&self, cambio: CambioReserva) {
+        if let Some(metricas) = &self.metricas {
+            metricas.cambio_reserva(cambio.tipo);
+        }
+        for notificador in &self.notificadores {
+            notificador.notificar(cambio.clone());
+        }
+    }
+
+    /// Comprueba que el actor puede realizar `accion` sobre la reserva
+    async fn autorizar(
         &self,
-        empleado_id: String,
-        slot: Slot,
-        descripcion: String,
-    ) -> Result<Reserva, String> {
-        // Generamos un ID único
-        let id = Uuid::new_v4().to_string();
+        actor: &Actor,
+        accion: Accion,
+        reserva: &Reserva,
+    ) -> Result<(), ErrorServicio> {
+        let titular = self.empleados.obtener(&reserva.empleado_id).await?;
+        self.autorizador
+            .exigir(
+                actor,
+                accion,
+                titular.as_ref(),
+                format!("reserva:{}", reserva.id),
+            )
+            .await
+    }
 
-        // Verificamos que el empleado no tenga ya una reserva en este slot
-        if self
+    /// Pasa la reserva, sin guardarla, a `nuevo_slot` si sigue activa y el
+    /// empleado y la sala están libres. Devuelve el slot anterior.
+    /// Una reserva confirmada solo sigue confirmada si el actor podría
+    /// confirmarla; si no, vuelve a pendiente de su responsable.
+    async fn mover(
+        &self,
+        actor: &Actor,
+        reserva: &mut Reserva,
+        nuevo_slot: Slot,
+    ) -> Result<Slot, String> {
+        if !reserva.esta_activa() {
+            return Err(format!(
+                "La reserva {} está cancelada; crea una nueva en su lugar",
+                reserva.id
+            ));
+        }
+        // Como al crearla: no se ocupa un slot nuevo con un empleado o una
+        // sala desactivados
+        let titular = self.empleados.obtener(&reserva.empleado_id).await?;
+        if !titular.as_ref().is_some_and(|e| e.activo) {
+            return Err(format!(
+                "El empleado {} no existe o está desactivado",
+                reserva.empleado_id
+            ));
+        }
+        if let Some(sala_id) = &reserva.sala_id {
+            if !self.salas.obtener(sala_id).await?.is_some_and(|s| s.activa) {
+                return Err(format!("La sala {} no existe o está desactivada", sala_id));
+            }
+        }
+        let confirmada = reserva.estado == EstadoReserva::Confirmada;
+        let slot_anterior = reserva.slot.clone();
+        reserva
+            .reprogramar(nuevo_slot)
+            .map_err(|e| format!("Error de validación: {:?}", e))?;
+        if confirmada && actor.puede(Accion::ConfirmarReserva, titular.as_ref()) {
+            reserva.confirmar();
+        }
+        self.comprobar_conflictos(
+            &reserva.empleado_id,
+            reserva.sala_id.as_deref(),
+            &reserva.slot,
+            Some(&reserva.id),
+        )
+        .await?;
+        Ok(slot_anterior)
+    }
+
+    /// Error si `slot` ya está ocupado por otra reserva activa del empleado o
+    /// de la sala. `excluir` es la propia reserva cuando se reprograma.
+    async fn comprobar_conflictos(
+        &self,
+        empleado_id: &str,
+        sala_id: Option<&str>,
+        slot: &Slot,
+        excluir: Option<&str>,
+    ) -> Result<(), String> {
+        let ocupantes: Vec<Reserva> = self
             .repository
-            .existe_para_empleado_en_slot(&empleado_id, &slot)
+            .listar_por_slot(slot)
             .await?
-        {
+            .into_iter()
+            .filter(|r| Some(r.id.as_str()) != excluir)
+            .collect();
+
+        if ocupantes.iter().any(|r| r.empleado_id == empleado_id) {
+            self.contar_conflicto(RecursoEnConflicto::Empleado);
             return Err(format!(
                 "El empleado {} ya tiene una reserva en el slot {}",
                 empleado_id,
                 slot.formato_legible()
             ));
         }
+        if let Some(sala_id) = sala_id {
+            if ocupantes
+                .iter()
+                .any(|r| r.sala_id.as_deref() == Some(sala_id))
+            {
+                self.contar_conflicto(RecursoEnConflicto::Sala);
+                return Err(format!(
+                    "La sala {} ya está reservada en el slot {}",
+                    sala_id,
+                    slot.formato_legible()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn contar_conflicto(&self, recurso: RecursoEnConflicto) {
+        if let Some(metricas) = &self.metricas {
+            metricas.conflicto_rechazado(recurso);
+        }
+    }
+
+    async fn futuras_activas(&self, empleado_id: &str) -> Result<Vec<Reserva>, String> {
+        let ahora = Utc::now();
+        Ok(self
+            .repository
+            .listar_por_empleado(empleado_id)
+            .await?
+            .into_iter()
+            .filter(|r| r.esta_activa() && r.slot.inicio >= ahora)
+            .collect())
+    }
+
+    /// Crea o sobrescribe cada reserva y devuelve la versión anterior de
+    /// cada una (`None` si no existía). Si una escritura falla, deshace las
+    /// anteriores.
+    async fn escribir_restauradas(
+        &self,
+        reservas: &[Reserva],
+    ) -> Result<Vec<Option<Reserva>>, String> {
+        let mut anteriores = Vec::new();
+        for reserva in reservas {
+            let resultado = match self.repository.obtener(&reserva.id).await {
+                Ok(Some(anterior)) => self
+                    .repository
+                    .actualizar(reserva)
+                    .await
+                    .map(|()| Some(anterior)),
+                Ok(None) => self.repository.guardar(reserva).await.map(|()| None),
+                Err(e) => Err(e),
+            };
+            match resultado {
+                Ok(anterior) => anteriores.push(anterior),
+                Err(e) => {
+                    for (escrita, anterior) in reservas.iter().zip(&anteriores).rev() {
+                        let deshecha = match anterior {
+                            Some(anterior) => self.repository.actualizar(anterior).await,
+                            None => self.repository.eliminar(&escrita.id).await,
+                        };
+                        if let Err(otro) = deshecha {
+                            return Err(format!("{}; al deshacer {}: {}", e, escrita.id, otro));
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(anteriores)
+    }
+
+    /// Guarda cada reserva nueva; si una falla, devuelve las ya guardadas a
+    /// su versión original para no dejar el lote a medias
+    async fn actualizar_lote(&self, cambios: &[(Reserva, Reserva)]) -> Result<(), String> {
+        for (i, (_, nueva)) in cambios.iter().enumerate() {
+            if let Err(e) = self.repository.actualizar(nueva).await {
+                for (original, _) in &cambios[..i] {
+                    if let Err(otro) = self.repository.actualizar(original).await {
+                        return Err(format!("{}; al deshacer {}: {}", e, original.id, otro));
+                    }
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<R, E, S> ReservaService for ReservaServiceImpl<R, E, S>
+where
+    R: ReservaRepository + Send + Sync,
+    E: EmpleadoRepository + Send + Sync,
+    S: SalaRepository + Send + Sync,
+{
+    async fn crear_reserva(
+        &self,
+        actor: &Actor,
+        empleado_id: String,
+        slot: Slot,
+        descripcion: String,
+        sala_id: Option<String>,
+    ) -> Result<Reserva, ErrorServicio> {
+        // Antes de validar nada, para no revelar qué empleados existen
+        let empleado = self.empleados.obtener(&empleado_id).await?;
+        self.autorizador
+            .exigir(
+                actor,
+                Accion::CrearReserva,
+                empleado.as_ref(),
+                format!("empleado:{}", empleado_id),
+            )
+            .await?;
+
+        // El empleado debe existir y estar activo
+        let empleado = empleado.ok_or_else(|| format!("El empleado {} no existe", empleado_id))?;
+        if !empleado.activo {
+            return Err(format!("El empleado {} está desactivado", empleado_id).into());
+        }
+
+        // Y la sala, si se pide, también
+        if let Some(sala_id) = &sala_id {
+            let sala = self
+                .salas
+                .obtener(sala_id)
+                .await?
+                .ok_or_else(|| format!("La sala {} no existe", sala_id))?;
+            if !sala.activa {
+                return Err(format!("La sala {} está desactivada", sala_id).into());
+            }
+        }
+
+        // Generamos un ID único
+        let id = Uuid::new_v4().to_string();
+
+        // Verificamos que ni el empleado ni la sala (si se pide) tengan ya
+        // una reserva en este slot
+        let _escritura = self.escrituras.lock().await;
+        self.comprobar_conflictos(&empleado_id, sala_id.as_deref(), &slot, None)
+            .await?;
 
         // Creamos la entidad usando la lógica del dominio
-        let reserva = Reserva::new(id, empleado_id, slot, descripcion)
+        let mut reserva = Reserva::new(id, empleado_id, slot, descripcion)
             .map_err(|e| format!("Error de validación: {:?}", e))?;
+        if let Some(sala_id) = sala_id {
+            reserva = reserva.en_sala(sala_id);
+        }
 
         // Persistimos usando el puerto de salida
         self.repository.guardar(&reserva).await?;
+        self.notificar(TipoCambio::Creada, &reserva, None);
 
         Ok(reserva)
     }
@@ -73,60 +395,473 @@ impl<R: ReservaRepository + Send + Sync> ReservaService for ReservaServiceImpl<R
         self.repository.listar().await
     }
 
+    async fn buscar_reservas(&self, consulta: ConsultaReservas) -> Result<Pagina<Reserva>, String> {
+        self.repository.buscar(&consulta).await
+    }
+
     async fn listar_reservas_empleado(&self, empleado_id: &str) -> Result<Vec<Reserva>, String> {
         self.repository.listar_por_empleado(empleado_id).await
     }
 
-    async fn confirmar_reserva(&self, id: &str) -> Result<Reserva, String> {
+    async fn confirmar_reserva(&self, actor: &Actor, id: &str) -> Result<Reserva, ErrorServicio> {
+        let _escritura = self.escrituras.lock().await;
+
         let mut reserva = self
             .repository
             .obtener(id)
             .await?
-            .ok_or_else(|| "Reserva no encontrada".to_string())?;
+            .ok_or("Reserva no encontrada")?;
+        self.autorizar(actor, Accion::ConfirmarReserva, &reserva)
+            .await?;
+        // Reactivarla ocuparía de nuevo un slot que quizá ya es de otro
+        if !reserva.esta_activa() {
+            return Err(format!(
+                "La reserva {} está cancelada; crea una nueva en su lugar",
+                id
+            )
+            .into());
+        }
 
         // Lógica de dominio
         reserva.confirmar();
 
         // Persistimos el cambio
         self.repository.actualizar(&reserva).await?;
+        self.notificar(TipoCambio::Confirmada, &reserva, None);
 
         Ok(reserva)
     }
 
-    async fn cancelar_reserva(&self, id: &str) -> Result<Reserva, String> {
+    async fn cancelar_reserva(&self, actor: &Actor, id: &str) -> Result<Reserva, ErrorServicio> {
+        let _escritura = self.escrituras.lock().await;
+
         let mut reserva = self
             .repository
             .obtener(id)
             .await?
-            .ok_or_else(|| "Reserva no encontrada".to_string())?;
+            .ok_or("Reserva no encontrada")?;
+        self.autorizar(actor, Accion::CancelarReserva, &reserva)
+            .await?;
 
         // Lógica de dominio
         reserva.cancelar();
 
         // Persistimos el cambio
         self.repository.actualizar(&reserva).await?;
+        self.notificar(TipoCambio::Cancelada, &reserva, None);
 
         Ok(reserva)
     }
+
+    async fn actualizar_reserva(
+        &self,
+        actor: &Actor,
+        id: &str,
+        cambios: CambiosReserva,
+    ) -> Result<Option<Reserva>, ErrorServicio> {
+        let _escritura = self.escrituras.lock().await;
+
+        let Some(mut reserva) = self.repository.obtener(id).await? else {
+            return Ok(None);
+        };
+        self.autorizar(actor, Accion::ModificarReserva, &reserva)
+            .await?;
+
+        if let Some(descripcion) = cambios.descripcion {
+            reserva
+                .cambiar_descripcion(descripcion)
+                .map_err(|e| format!("Error de validación: {:?}", e))?;
+        }
+        let slot_anterior = match cambios.slot {
+            Some(slot) if slot != reserva.slot => {
+                Some(self.mover(actor, &mut reserva, slot).await?)
+            }
+            _ => None,
+        };
+
+        self.repository.actualizar(&reserva).await?;
+        match slot_anterior {
+            Some(anterior) => self.notificar(TipoCambio::Reprogramada, &reserva, Some(anterior)),
+            None => self.notificar(TipoCambio::Modificada, &reserva, None),
+        }
+
+        Ok(Some(reserva))
+    }
+
+    async fn reprogramar_reserva(
+        &self,
+        actor: &Actor,
+        id: &str,
+        nuevo_slot: Slot,
+    ) -> Result<Option<Reserva>, ErrorServicio> {
+        let _escritura = self.escrituras.lock().await;
+
+        let Some(mut reserva) = self.repository.obtener(id).await? else {
+            return Ok(None);
+        };
+        self.autorizar(actor, Accion::ModificarReserva, &reserva)
+            .await?;
+        let slot_anterior = self.mover(actor, &mut reserva, nuevo_slot).await?;
+
+        // Una sola escritura: la reserva nunca deja de existir ni libera el
+        // slot antiguo antes de ocupar el nuevo
+        self.repository.actualizar(&reserva).await?;
+        self.notificar(TipoCambio::Reprogramada, &reserva, Some(slot_anterior));
+
+        Ok(Some(reserva))
+    }
+
+    async fn eliminar_reserva(&self, actor: &Actor, id: &str) -> Result<bool, ErrorServicio> {
+        self.autorizador
+            .exigir(
+                actor,
+                Accion::EliminarReserva,
+                None,
+                format!("reserva:{}", id),
+            )
+            .await?;
+        let _escritura = self.escrituras.lock().await;
+        let Some(reserva) = self.repository.obtener(id).await? else {
+            return Ok(false);
+        };
+        self.repository.eliminar(id).await?;
+        self.notificar(TipoCambio::Eliminada, &reserva, None);
+        Ok(true)
+    }
+
+    async fn cancelar_reservas_futuras(
+        &self,
+        actor: &Actor,
+        empleado_id: &str,
+    ) -> Result<Vec<Reserva>, ErrorServicio> {
+        self.autorizador
+            .exigir(
+                actor,
+                Accion::GestionarEmpleados,
+                None,
+                format!("empleado:{}", empleado_id),
+            )
+            .await?;
+        let _escritura = self.escrituras.lock().await;
+
+        let cambios: Vec<(Reserva, Reserva)> = self
+            .futuras_activas(empleado_id)
+            .await?
+            .into_iter()
+            .map(|original| {
+                let mut cancelada = original.clone();
+                cancelada.cancelar();
+                (original, cancelada)
+            })
+            .collect();
+        self.actualizar_lote(&cambios).await?;
+
+        let canceladas: Vec<Reserva> = cambios.into_iter().map(|(_, nueva)| nueva).collect();
+        for reserva in &canceladas {
+            self.notificar(TipoCambio::Cancelada, reserva, None);
+        }
+        Ok(canceladas)
+    }
+
+    async fn reasignar_reservas_futuras(
+        &self,
+        actor: &Actor,
+        empleado_id: &str,
+        destino: &str,
+    ) -> Result<Vec<Reserva>, ErrorServicio> {
+        self.autorizador
+            .exigir(
+                actor,
+                Accion::GestionarEmpleados,
+                None,
+                format!("empleado:{}", empleado_id),
+            )
+            .await?;
+        if destino == empleado_id {
+            return Err("No se pueden reasignar las reservas al mismo empleado".into());
+        }
+        let _escritura = self.escrituras.lock().await;
+
+        let futuras = self.futuras_activas(empleado_id).await?;
+        if futuras.is_empty() {
+            return Ok(Vec::new());
+        }
+        let activo = self
+            .empleados
+            .obtener(destino)
+            .await?
+            .is_some_and(|e| e.activo);
+        if !activo {
+            return Err(format!(
+                "El empleado {} para reasignar no existe o está desactivado",
+                destino
+            )
+            .into());
+        }
+        // Todas las comprobaciones antes de la primera escritura. La sala no
+        // cambia y ya es de la propia reserva: solo cuenta el empleado.
+        for reserva in &futuras {
+            self.comprobar_conflictos(destino, None, &reserva.slot, Some(&reserva.id))
+                .await?;
+        }
+
+        let cambios: Vec<(Reserva, Reserva)> = futuras
+            .into_iter()
+            .map(|original| {
+                let mut reasignada = original.clone();
+                reasignada.reasignar(destino.to_string());
+                (original, reasignada)
+            })
+            .collect();
+        self.actualizar_lote(&cambios).await?;
+
+        let reasignadas: Vec<Reserva> = cambios.into_iter().map(|(_, nueva)| nueva).collect();
+        for reserva in &reasignadas {
+            self.notificar_cambio(CambioReserva {
+                tipo: TipoCambio::Reasignada,
+                reserva: reserva.clone(),
+                slot_anterior: None,
+                empleado_anterior: Some(empleado_id.to_string()),
+            });
+        }
+        Ok(reasignadas)
+    }
+
+    async fn restaurar_reservas(
+        &self,
+        actor: &Actor,
+        reservas: Vec<Reserva>,
+    ) -> Result<(), ErrorServicio> {
+        self.autorizador
+            .exigir(actor, Accion::Administrar, None, "reservas".to_string())
+            .await?;
+        let _escritura = self.escrituras.lock().await;
+
+        let anteriores = self.escribir_restauradas(&reservas).await?;
+        for (anterior, reserva) in anteriores.into_iter().zip(&reservas) {
+            if let Some(cambio) = cambio_restaurado(anterior, reserva) {
+                self.notificar_cambio(cambio);
+            }
+        }
+        Ok(())
+    }
 }
 
-/// Servicio de aplicación para gestión de empleados
+/// Cómo avisar de una reserva restaurada según lo que cambió; `None` si
+/// ya estaba así
+fn cambio_restaurado(anterior: Option<Reserva>, reserva: &Reserva) -> Option<CambioReserva> {
+    let cambio = |tipo| CambioReserva {
+        tipo,
+        reserva: reserva.clone(),
+        slot_anterior: None,
+        empleado_anterior: None,
+    };
+    let Some(anterior) = anterior else {
+        return Some(cambio(TipoCambio::Creada));
+    };
+    if anterior == *reserva {
+        return None;
+    }
+    Some(
+        if reserva.estado != anterior.estado && !reserva.esta_activa() {
+            cambio(TipoCambio::Cancelada)
+        } else if reserva.empleado_id != anterior.empleado_id {
+            CambioReserva {
+                empleado_anterior: Some(anterior.empleado_id),
+                ..cambio(TipoCambio::Reasignada)
+            }
+        } else if reserva.slot != anterior.slot {
+            CambioReserva {
+                slot_anterior: Some(anterior.slot),
+                ..cambio(TipoCambio::Reprogramada)
+            }
+        } else if reserva.estado != anterior.estado && reserva.estado == EstadoReserva::Confirmada {
+            cambio(TipoCambio::Confirmada)
+        } else {
+            cambio(TipoCambio::Modificada)
+        },
+    )
+}
+
+/// Qué hacer con las reservas futuras de un empleado que se desactiva
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum PoliticaDesactivacion {
+    /// Cancelar sus reservas futuras activas
+    #[default]
+    Cancelar,
+    /// Pasar sus reservas futuras activas al empleado indicado
+    Reasignar(String),
+    /// Rechazar la desactivación mientras tenga reservas futuras activas
+    Bloquear,
+}
+
+impl FromStr for PoliticaDesactivacion {
+    type Err = String;
+
+    /// Acepta `cancelar`, `bloquear` o `reasignar:<empleado_id>`
+    fn from_str(valor: &str) -> Result<Self, Self::Err> {
+        match valor.split_once(':') {
+            None if valor == "cancelar" => Ok(Self::Cancelar),
+            None if valor == "bloquear" => Ok(Self::Bloquear),
+            Some(("reasignar", destino)) if !destino.trim().is_empty() => {
+                Ok(Self::Reasignar(destino.trim().to_string()))
+            }
+            _ => Err(format!(
+                "Política de desactivación inválida '{}' (cancelar, bloquear o reasignar:<empleado_id>)",
+                valor
+            )),
+        }
+    }
+}
+
+/// Servicio de aplicación para gestión de empleados. Los cambios en las
+/// reservas del empleado pasan por el servicio de reservas, que los
+/// serializa con el resto de escrituras y avisa a los notificadores.
 pub struct EmpleadoServiceImpl<R: EmpleadoRepository> {
     repository: R,
+    reservas: Arc<dyn ReservaService>,
+    politica: PoliticaDesactivacion,
+    autorizador: Autorizador,
 }
 
 impl<R: EmpleadoRepository> EmpleadoServiceImpl<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+    /// Crea el servicio con la política por defecto (cancelar)
+    pub fn new(repository: R, reservas: Arc<dyn ReservaService>) -> Self {
+        Self {
+            repository,
+            reservas,
+            politica: PoliticaDesactivacion::default(),
+            autorizador: Autorizador::default(),
+        }
+    }
+
+    pub fn con_politica(mut self, politica: PoliticaDesactivacion) -> Self {
+        self.politica = politica;
+        self
+    }
+
+    /// Registra en `auditoria` las operaciones denegadas
+    pub fn con_auditoria(mut self, auditoria: Arc<dyn RegistroAuditoria>) -> Self {
+        self.autorizador = Autorizador::new(auditoria);
+        self
+    }
+
+    async fn autorizar(&self, actor: &Actor, recurso: String) -> Result<(), ErrorServicio> {
+        self.autorizador
+            .exigir(actor, Accion::GestionarEmpleados, None, recurso)
+            .await
+    }
+
+    /// El responsable tiene que ser otro empleado existente y no puede
+    /// depender, directa o indirectamente, del propio empleado
+    async fn comprobar_responsable(&self, empleado: &Empleado) -> Result<(), ErrorEmpleado> {
+        let Some(responsable_id) = &empleado.responsable_id else {
+            return Ok(());
+        };
+        if !self.repository.existe(responsable_id).await? {
+            return Err(ErrorEmpleado::Validacion(vec![ErrorCampo::new(
+                "responsable_id",
+                format!("El empleado {} no existe", responsable_id),
+            )]));
+        }
+
+        // Se sube por la cadena de responsables; `vistos` corta los ciclos
+        // que ya hubiera para no dar vueltas sin fin
+        let mut vistos = HashSet::new();
+        let mut actual = Some(responsable_id.clone());
+        while let Some(id) = actual {
+            if id == empleado.id {
+                return Err(ErrorEmpleado::Validacion(vec![ErrorCampo::new(
+                    "responsable_id",
+                    format!(
+                        "El empleado {} depende de {}: no puede ser su responsable",
+                        responsable_id, empleado.id
+                    ),
+                )]));
+            }
+            if !vistos.insert(id.clone()) {
+                break;
+            }
+            actual = self
+                .repository
+                .obtener(&id)
+                .await?
+                .and_then(|e| e.responsable_id);
+        }
+        Ok(())
+    }
+
+    /// El repositorio también lo garantiza, pero así el error llega asociado
+    /// al campo `email`
+    async fn comprobar_email_libre(&self, empleado: &Empleado) -> Result<(), ErrorEmpleado> {
+        match self.repository.obtener_por_email(&empleado.email).await? {
+            Some(otro) if otro.id != empleado.id => {
+                Err(ErrorEmpleado::Validacion(vec![ErrorCampo::new(
+                    "email",
+                    format!("El email {} ya está registrado", empleado.email),
+                )]))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Aplica la política a las reservas futuras activas del empleado.
+    /// El servicio de reservas valida todo antes de escribir para no dejar
+    /// cambios a medias.
+    async fn aplicar_politica(
+        &self,
+        actor: &Actor,
+        empleado_id: &str,
+    ) -> Result<(), ErrorServicio> {
+        match &self.politica {
+            PoliticaDesactivacion::Bloquear => {
+                let ahora = Utc::now();
+                let futuras = self
+                    .reservas
+                    .listar_reservas_empleado(empleado_id)
+                    .await?
+                    .into_iter()
+                    .filter(|r| r.esta_activa() && r.slot.inicio >= ahora)
+                    .count();
+                if futuras > 0 {
+                    return Err(format!(
+                        "El empleado {} tiene {} reservas futuras activas",
+                        empleado_id, futuras
+                    )
+                    .into());
+                }
+            }
+            PoliticaDesactivacion::Cancelar => {
+                self.reservas
+                    .cancelar_reservas_futuras(actor, empleado_id)
+                    .await?;
+            }
+            PoliticaDesactivacion::Reasignar(destino) => {
+                self.reservas
+                    .reasignar_reservas_futuras(actor, empleado_id, destino)
+                    .await?;
+            }
+        }
+        Ok(())
     }
 }
 
 #[async_trait]
-impl<R: EmpleadoRepository + Send + Sync> EmpleadoService for EmpleadoServiceImpl<R> {
-    async fn crear_empleado(&self, nombre: String, email: String) -> Result<Empleado, String> {
+impl<R> EmpleadoService for EmpleadoServiceImpl<R>
+where
+    R: EmpleadoRepository + Send + Sync,
+{
+    async fn crear_empleado(
+        &self,
+        actor: &Actor,
+        nombre: String,
+        email: String,
+    ) -> Result<Empleado, ErrorEmpleado> {
+        self.autorizar(actor, "empleados".to_string()).await?;
         let id = Uuid::new_v4().to_string();
-        let empleado = Empleado::new(id, nombre, email);
+        let empleado = Empleado::new(id, nombre, email)?;
 
+        self.comprobar_email_libre(&empleado).await?;
         self.repository.guardar(&empleado).await?;
 
         Ok(empleado)
@@ -140,13 +875,26 @@ impl<R: EmpleadoRepository + Send + Sync> EmpleadoService for EmpleadoServiceImp
         self.repository.listar().await
     }
 
-    async fn desactivar_empleado(&self, id: &str) -> Result<Empleado, String> {
+    async fn buscar_empleados(
+        &self,
+        consulta: ConsultaEmpleados,
+    ) -> Result<Pagina<Empleado>, String> {
+        self.repository.buscar(&consulta).await
+    }
+
+    async fn desactivar_empleado(
+        &self,
+        actor: &Actor,
+        id: &str,
+    ) -> Result<Empleado, ErrorServicio> {
+        self.autorizar(actor, format!("empleado:{}", id)).await?;
         let mut empleado = self
             .repository
             .obtener(id)
             .await?
-            .ok_or_else(|| "Empleado no encontrado".to_string())?;
+            .ok_or("Empleado no encontrado")?;
 
+        self.aplicar_politica(actor, id).await?;
         empleado.desactivar();
 
         self.repository.actualizar(&empleado).await?;
@@ -154,12 +902,13 @@ impl<R: EmpleadoRepository + Send + Sync> EmpleadoService for EmpleadoServiceImp
         Ok(empleado)
     }
 
-    async fn activar_empleado(&self, id: &str) -> Result<Empleado, String> {
+    async fn activar_empleado(&self, actor: &Actor, id: &str) -> Result<Empleado, ErrorServicio> {
+        self.autorizar(actor, format!("empleado:{}", id)).await?;
         let mut empleado = self
             .repository
             .obtener(id)
             .await?
-            .ok_or_else(|| "Empleado no encontrado".to_string())?;
+            .ok_or("Empleado no encontrado")?;
 
         empleado.activar();
 
@@ -167,21 +916,126 @@ impl<R: EmpleadoRepository + Send + Sync> EmpleadoService for EmpleadoServiceImp
 
         Ok(empleado)
     }
+
+    async fn actualizar_empleado(
+        &self,
+        actor: &Actor,
+        id: &str,
+        cambios: CambiosEmpleado,
+    ) -> Result<Option<Empleado>, ErrorEmpleado> {
+        self.autorizar(actor, format!("empleado:{}", id)).await?;
+        let Some(mut empleado) = self.repository.obtener(id).await? else {
+            return Ok(None);
+        };
+
+        let nombre = cambios.nombre.unwrap_or_else(|| empleado.nombre.clone());
+        let email = cambios
+            .email
+            .unwrap_or_else(|| empleado.email.as_str().to_string());
+        let mut errores = empleado.modificar(nombre, email).err().unwrap_or_default();
+        if let Some(responsable_id) = cambios.responsable_id {
+            if let Err(error) = empleado.asignar_responsable(responsable_id) {
+                errores.push(error);
+            }
+        }
+        if !errores.is_empty() {
+            return Err(errores.into());
+        }
+
+        self.comprobar_responsable(&empleado).await?;
+        self.comprobar_email_libre(&empleado).await?;
+        self.repository.actualizar(&empleado).await?;
+
+        Ok(Some(empleado))
+    }
+
+    async fn eliminar_empleado(&self, actor: &Actor, id: &str) -> Result<bool, ErrorServicio> {
+        self.autorizar(actor, format!("empleado:{}", id)).await?;
+        if !self.repository.existe(id).await? {
+            return Ok(false);
+        }
+
+        // Borrarlo dejaría reservas huérfanas (también las históricas)
+        let consulta = ConsultaReservas {
+            empleado_id: Some(id.to_string()),
+            limite: Some(1),
+            ..Default::default()
+        };
+        if !self
+            .reservas
+            .buscar_reservas(consulta)
+            .await?
+            .elementos
+            .is_empty()
+        {
+            return Err(format!(
+                "El empleado {} tiene reservas; desactívalo en lugar de eliminarlo",
+                id
+            )
+            .into());
+        }
+        // Y subordinados con un responsable inexistente
+        let subordinados = self
+            .repository
+            .listar()
+            .await?
+            .into_iter()
+            .filter(|e| e.responsable_id.as_deref() == Some(id))
+            .count();
+        if subordinados > 0 {
+            return Err(format!(
+                "El empleado {} es responsable de {} empleados; asígnales otro responsable antes de eliminarlo",
+                id, subordinados
+            )
+            .into());
+        }
+
+        self.repository.eliminar(id).await?;
+        Ok(true)
+    }
 }
 
-pub struct SalaServiceImpl<R: SalaRepository> {
+pub struct SalaServiceImpl<R: SalaRepository, RR: ReservaRepository> {
     repository: R,
+    reservas: RR,
+    autorizador: Autorizador,
 }
 
-impl<R: SalaRepository> SalaServiceImpl<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: SalaRepository, RR: ReservaRepository> SalaServiceImpl<R, RR> {
+    pub fn new(repository: R, reservas: RR) -> Self {
+        Self {
+            repository,
+            reservas,
+            autorizador: Autorizador::default(),
+        }
+    }
+
+    /// Registra en `auditoria` las operaciones denegadas
+    pub fn con_auditoria(mut self, auditoria: Arc<dyn RegistroAuditoria>) -> Self {
+        self.autorizador = Autorizador::new(auditoria);
+        self
+    }
+
+    async fn autorizar(&self, actor: &Actor, recurso: String) -> Result<(), ErrorServicio> {
+        self.autorizador
+            .exigir(actor, Accion::GestionarSalas, None, recurso)
+            .await
     }
 }
 
 #[async_trait]
-impl<R: SalaRepository + Send + Sync> SalaService for SalaServiceImpl<R> {
-    async fn crear_sala(&self, nombre: String, capacidad: u32) -> Result<Sala, String> {
+impl<R, RR> SalaService for SalaServiceImpl<R, RR>
+where
+    R: SalaRepository + Send + Sync,
+    RR: ReservaRepository + Send + Sync,
+{
+    async fn crear_sala(
+        &self,
+        actor: &Actor,
+        nombre: String,
+        capacidad: u32,
+    ) -> Result<Sala, ErrorServicio> {
+        self.autorizar(actor, "salas".to_string()).await?;
         let id = Uuid::new_v4().to_string();
         let sala = Sala::new(id, nombre, capacidad)?;
         self.repository.guardar(&sala).await?;
@@ -192,27 +1046,969 @@ impl<R: SalaRepository + Send + Sync> SalaService for SalaServiceImpl<R> {
         self.repository.listar().await
     }
 
+    async fn buscar_salas(&self, consulta: ConsultaSalas) -> Result<Pagina<Sala>, String> {
+        self.repository.buscar(&consulta).await
+    }
+
     async fn obtener_sala(&self, id: &str) -> Result<Option<Sala>, String> {
         self.repository.obtener(id).await
     }
 
-    async fn activar_sala(&self, id: &str) -> Result<(), String> {
+    async fn activar_sala(&self, actor: &Actor, id: &str) -> Result<(), ErrorServicio> {
+        self.autorizar(actor, format!("sala:{}", id)).await?;
         let mut sala = self
             .repository
             .obtener(id)
             .await?
             .ok_or("Sala no encontrada")?;
         sala.activar();
-        self.repository.actualizar(&sala).await
+        Ok(self.repository.actualizar(&sala).await?)
     }
 
-    async fn desactivar_sala(&self, id: &str) -> Result<(), String> {
+    async fn desactivar_sala(&self, actor: &Actor, id: &str) -> Result<(), ErrorServicio> {
+        self.autorizar(actor, format!("sala:{}", id)).await?;
         let mut sala = self
             .repository
             .obtener(id)
             .await?
             .ok_or("Sala no encontrada")?;
         sala.desactivar();
-        self.repository.actualizar(&sala).await
+        Ok(self.repository.actualizar(&sala).await?)
+    }
+
+    async fn actualizar_sala(
+        &self,
+        actor: &Actor,
+        id: &str,
+        cambios: CambiosSala,
+    ) -> Result<Option<Sala>, ErrorServicio> {
+        self.autorizar(actor, format!("sala:{}", id)).await?;
+        let Some(mut sala) = self.repository.obtener(id).await? else {
+            return Ok(None);
+        };
+
+        let nombre = cambios.nombre.unwrap_or_else(|| sala.nombre.clone());
+        let capacidad = cambios.capacidad.unwrap_or(sala.capacidad);
+        sala.modificar(nombre, capacidad)?;
+
+        self.repository.actualizar(&sala).await?;
+        Ok(Some(sala))
+    }
+
+    async fn eliminar_sala(&self, actor: &Actor, id: &str) -> Result<bool, ErrorServicio> {
+        self.autorizar(actor, format!("sala:{}", id)).await?;
+        if self.repository.obtener(id).await?.is_none() {
+            return Ok(false);
+        }
+
+        let consulta = ConsultaReservas {
+            sala_id: Some(id.to_string()),
+            limite: Some(1),
+            ..Default::default()
+        };
+        if !self.reservas.buscar(&consulta).await?.elementos.is_empty() {
+            return Err(format!(
+                "La sala {} tiene reservas; desactívala en lugar de eliminarla",
+                id
+            )
+            .into());
+        }
+
+        self.repository.eliminar(id).await?;
+        Ok(true)
+    }
+
+    async fn disponibilidad_salas(&self, periodo: Periodo) -> Result<MatrizDisponibilidad, String> {
+        let mut salas = self.repository.listar().await?;
+        salas.retain(|s| s.activa);
+        salas.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+        let reservas = self.reservas.listar().await?;
+        Ok(MatrizDisponibilidad::generar(
+            periodo,
+            &[],
+            &salas,
+            &reservas,
+        ))
+    }
+
+    async fn buscar_salas_libres(&self, busqueda: BusquedaSalas) -> Result<Vec<Sala>, String> {
+        let slots = Slot::franja(busqueda.fecha, busqueda.hora_inicio, busqueda.hora_fin)?;
+        let salas = self.repository.listar().await?;
+        // Solo cuentan las reservas de la franja
+        let mut reservas = Vec::new();
+        for slot in &slots {
+            reservas.extend(self.reservas.listar_por_slot(slot).await?);
+        }
+        Ok(DisponibilidadService::salas_libres(
+            &salas,
+            &slots,
+            busqueda.capacidad_minima,
+            &reservas,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Duration};
+    use reservas_adapters::{
+        InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+        MetricasPrometheus,
+    };
+    use reservas_domain::Rol;
+    use std::sync::Arc;
+
+    type Reservas = ReservaServiceImpl<
+        Arc<InMemoryReservaRepository>,
+        Arc<InMemoryEmpleadoRepository>,
+        Arc<InMemorySalaRepository>,
+    >;
+    type Empleados = EmpleadoServiceImpl<Arc<InMemoryEmpleadoRepository>>;
+    type Salas = SalaServiceImpl<Arc<InMemorySalaRepository>, Arc<InMemoryReservaRepository>>;
+
+    fn slot(hora: u32) -> Slot {
+        let manyana = Utc::now() + Duration::days(1);
+        Slot::from_date_and_hour(manyana.year(), manyana.month(), manyana.day(), hora).unwrap()
+    }
+
+    async fn servicios(politica: PoliticaDesactivacion) -> (Arc<Reservas>, Empleados, Salas) {
+        servicios_con(politica, |reservas| reservas).await
+    }
+
+    /// `configurar` añade al servicio de reservas métricas, auditoría...
+    async fn servicios_con(
+        politica: PoliticaDesactivacion,
+        configurar: impl FnOnce(Reservas) -> Reservas,
+    ) -> (Arc<Reservas>, Empleados, Salas) {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let salas = Arc::new(InMemorySalaRepository::new());
+        let reservas = Arc::new(InMemoryReservaRepository::new());
+
+        for (id, nombre) in [("emp-001", "Juan"), ("emp-002", "María")] {
+            empleados
+                .guardar(
+                    &Empleado::new(
+                        id.to_string(),
+                        nombre.to_string(),
+                        format!("{}@empresa.com", id),
+                    )
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+        let mut inactiva = Sala::new("s2".to_string(), "Sala Roja".to_string(), 4).unwrap();
+        inactiva.desactivar();
+        salas.guardar(&inactiva).await.unwrap();
+
+        salas
+            .guardar(&Sala::new("s1".to_string(), "Sala Azul".to_string(), 8).unwrap())
+            .await
+            .unwrap();
+
+        let servicio = Arc::new(configurar(ReservaServiceImpl::new(
+            Arc::clone(&reservas),
+            Arc::clone(&empleados),
+            Arc::clone(&salas),
+        )));
+        (
+            Arc::clone(&servicio),
+            EmpleadoServiceImpl::new(empleados, servicio).con_politica(politica),
+            SalaServiceImpl::new(salas, reservas),
+        )
+    }
+
+    async fn reservar(
+        servicio: &Reservas,
+        empleado_id: &str,
+        hora: u32,
+    ) -> Result<Reserva, ErrorServicio> {
+        servicio
+            .crear_reserva(
+                &Actor::sistema(),
+                empleado_id.to_string(),
+                slot(hora),
+                "Reunión".to_string(),
+                None,
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_crear_reserva_rechaza_referencias_invalidas() {
+        let (reservas, empleados, _) = servicios(PoliticaDesactivacion::Cancelar).await;
+
+        let error = reservar(&reservas, "emp-999", 10).await.unwrap_err();
+        assert!(error.to_string().contains("no existe"));
+
+        let error = reservas
+            .crear_reserva(
+                &Actor::sistema(),
+                "emp-001".to_string(),
+                slot(10),
+                "Reunión".to_string(),
+                Some("s9".to_string()),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("s9"));
+
+        let error = reservas
+            .crear_reserva(
+                &Actor::sistema(),
+                "emp-001".to_string(),
+                slot(10),
+                "Reunión".to_string(),
+                Some("s2".to_string()),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("desactivada"));
+
+        empleados
+            .desactivar_empleado(&Actor::sistema(), "emp-001")
+            .await
+            .unwrap();
+        let error = reservar(&reservas, "emp-001", 10).await.unwrap_err();
+        assert!(error.to_string().contains("desactivado"));
+    }
+
+    #[tokio::test]
+    async fn test_desactivar_cancela_reservas_futuras() {
+        let (reservas, empleados, _) = servicios(PoliticaDesactivacion::Cancelar).await;
+        let reserva = reservar(&reservas, "emp-001", 10).await.unwrap();
+
+        empleados
+            .desactivar_empleado(&Actor::sistema(), "emp-001")
+            .await
+            .unwrap();
+
+        let reserva = reservas
+            .obtener_reserva(&reserva.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!reserva.esta_activa());
+    }
+
+    #[tokio::test]
+    async fn test_desactivar_reasigna_reservas_futuras() {
+        let politica = PoliticaDesactivacion::Reasignar("emp-002".to_string());
+        let (reservas, empleados, _) = servicios(politica).await;
+        let reserva = reservar(&reservas, "emp-001", 10).await.unwrap();
+
+        empleados
+            .desactivar_empleado(&Actor::sistema(), "emp-001")
+            .await
+            .unwrap();
+
+        let reserva = reservas
+            .obtener_reserva(&reserva.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reserva.empleado_id, "emp-002");
+        assert!(reserva.esta_activa());
+    }
+
+    #[tokio::test]
+    async fn test_desactivar_avisa_de_los_cambios() {
+        let metricas = Arc::new(MetricasPrometheus::new());
+        let politica = PoliticaDesactivacion::Reasignar("emp-002".to_string());
+        let (reservas, empleados, _) = servicios_con(politica, |reservas| {
+            reservas.con_metricas(Arc::clone(&metricas) as Arc<dyn Metricas>)
+        })
+        .await;
+        reservar(&reservas, "emp-001", 10).await.unwrap();
+        reservar(&reservas, "emp-001", 11).await.unwrap();
+
+        empleados
+            .desactivar_empleado(&Actor::sistema(), "emp-001")
+            .await
+            .unwrap();
+
+        assert!(metricas
+            .exponer()
+            .contains("reservas_cambios_total{cambio=\"reasignada\"} 2"));
+    }
+
+    #[tokio::test]
+    async fn test_reasignar_con_conflicto_no_cambia_nada() {
+        let politica = PoliticaDesactivacion::Reasignar("emp-002".to_string());
+        let (reservas, empleados, _) = servicios(politica).await;
+        let reserva = reservar(&reservas, "emp-001", 10).await.unwrap();
+        reservar(&reservas, "emp-002", 10).await.unwrap();
+
+        assert!(empleados
+            .desactivar_empleado(&Actor::sistema(), "emp-001")
+            .await
+            .is_err());
+
+        let empleado = empleados
+            .obtener_empleado("emp-001")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(empleado.activo);
+        let reserva = reservas
+            .obtener_reserva(&reserva.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reserva.empleado_id, "emp-001");
+    }
+
+    #[tokio::test]
+    async fn test_bloquear_impide_desactivar_con_reservas() {
+        let (reservas, empleados, _) = servicios(PoliticaDesactivacion::Bloquear).await;
+        reservar(&reservas, "emp-001", 10).await.unwrap();
+
+        assert!(empleados
+            .desactivar_empleado(&Actor::sistema(), "emp-001")
+            .await
+            .is_err());
+        // Sin reservas futuras se puede desactivar
+        empleados
+            .desactivar_empleado(&Actor::sistema(), "emp-002")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_email_valido_y_unico() {
+        let (_, empleados, _) = servicios(PoliticaDesactivacion::Cancelar).await;
+
+        let error = empleados
+            .crear_empleado(
+                &Actor::sistema(),
+                " ".to_string(),
+                "no-es-email".to_string(),
+            )
+            .await
+            .unwrap_err();
+        let ErrorEmpleado::Validacion(campos) = error else {
+            panic!("se esperaba un error de validación");
+        };
+        let campos: Vec<&str> = campos.iter().map(|e| e.campo.as_str()).collect();
+        assert_eq!(campos, ["nombre", "email"]);
+
+        // La comparación no distingue mayúsculas porque el email se normaliza
+        let error = empleados
+            .crear_empleado(
+                &Actor::sistema(),
+                "Otro Juan".to_string(),
+                "EMP-001@Empresa.com".to_string(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(&error, ErrorEmpleado::Validacion(c) if c[0].campo == "email"));
+
+        let error = empleados
+            .actualizar_empleado(
+                &Actor::sistema(),
+                "emp-002",
+                CambiosEmpleado {
+                    email: Some("emp-001@empresa.com".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(&error, ErrorEmpleado::Validacion(c) if c[0].campo == "email"));
+
+        // Volver a guardar su propio email no es un duplicado
+        let empleado = empleados
+            .actualizar_empleado(
+                &Actor::sistema(),
+                "emp-001",
+                CambiosEmpleado {
+                    nombre: Some("Juan López".to_string()),
+                    email: Some("Emp-001@empresa.com".to_string()),
+                    responsable_id: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(empleado.email.as_str(), "emp-001@empresa.com");
+    }
+
+    #[tokio::test]
+    async fn test_actualizar_revalida_y_persiste() {
+        let (reservas, empleados, salas) = servicios(PoliticaDesactivacion::Cancelar).await;
+        let reserva = reservar(&reservas, "emp-001", 10).await.unwrap();
+
+        let empleado = empleados
+            .actualizar_empleado(
+                &Actor::sistema(),
+                "emp-001",
+                CambiosEmpleado {
+                    email: Some("jlopez@empresa.com".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(empleado.nombre, "Juan");
+        assert_eq!(empleado.email.as_str(), "jlopez@empresa.com");
+
+        let invalida = CambiosSala {
+            capacidad: Some(0),
+            ..Default::default()
+        };
+        assert!(salas
+            .actualizar_sala(&Actor::sistema(), "s1", invalida)
+            .await
+            .is_err());
+        assert_eq!(
+            salas.obtener_sala("s1").await.unwrap().unwrap().capacidad,
+            8
+        );
+
+        let cambios = CambiosReserva {
+            descripcion: Some("Reunión con cliente".to_string()),
+            ..Default::default()
+        };
+        let editada = reservas
+            .actualizar_reserva(&Actor::sistema(), &reserva.id, cambios)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(editada.descripcion, "Reunión con cliente");
+
+        let inexistente = reservas
+            .actualizar_reserva(&Actor::sistema(), "no-existe", CambiosReserva::default())
+            .await
+            .unwrap();
+        assert!(inexistente.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_eliminar_protege_referencias() {
+        let (reservas, empleados, salas) = servicios(PoliticaDesactivacion::Cancelar).await;
+        let reserva = reservas
+            .crear_reserva(
+                &Actor::sistema(),
+                "emp-001".to_string(),
+                slot(10),
+                "Reunión".to_string(),
+                Some("s1".to_string()),
+            )
+            .await
+            .unwrap();
+
+        // Con reservas (aunque estén canceladas) no se pueden eliminar
+        reservas
+            .cancelar_reserva(&Actor::sistema(), &reserva.id)
+            .await
+            .unwrap();
+        assert!(empleados
+            .eliminar_empleado(&Actor::sistema(), "emp-001")
+            .await
+            .is_err());
+        assert!(salas.eliminar_sala(&Actor::sistema(), "s1").await.is_err());
+
+        assert!(reservas
+            .eliminar_reserva(&Actor::sistema(), &reserva.id)
+            .await
+            .unwrap());
+        assert!(!reservas
+            .eliminar_reserva(&Actor::sistema(), &reserva.id)
+            .await
+            .unwrap());
+
+        assert!(empleados
+            .eliminar_empleado(&Actor::sistema(), "emp-001")
+            .await
+            .unwrap());
+        assert!(salas.eliminar_sala(&Actor::sistema(), "s1").await.unwrap());
+        assert!(!salas.eliminar_sala(&Actor::sistema(), "s1").await.unwrap());
+        assert!(empleados
+            .obtener_empleado("emp-001")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reprogramar_conserva_la_reserva() {
+        let (reservas, _, _) = servicios(PoliticaDesactivacion::Cancelar).await;
+        let mut reserva = reservas
+            .crear_reserva(
+                &Actor::sistema(),
+                "emp-001".to_string(),
+                slot(10),
+                "Reunión".to_string(),
+                Some("s1".to_string()),
+            )
+            .await
+            .unwrap();
+        reserva = reservas
+            .confirmar_reserva(&Actor::sistema(), &reserva.id)
+            .await
+            .unwrap();
+
+        let movida = reservas
+            .reprogramar_reserva(&Actor::sistema(), &reserva.id, slot(12))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(movida.id, reserva.id);
+        assert_eq!(movida.slot, slot(12));
+        assert_eq!(movida.estado, reserva.estado);
+        assert_eq!(movida.sala_id.as_deref(), Some("s1"));
+
+        // El slot antiguo queda libre y reprogramar al mismo slot no choca consigo misma
+        assert!(reservar(&reservas, "emp-001", 10).await.is_ok());
+        assert!(reservas
+            .reprogramar_reserva(&Actor::sistema(), &reserva.id, slot(12))
+            .await
+            .is_ok());
+        assert!(reservas
+            .reprogramar_reserva(&Actor::sistema(), "no-existe", slot(12))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reprogramar_exige_empleado_y_sala_activos() {
+        let (reservas, _, salas) = servicios(PoliticaDesactivacion::Cancelar).await;
+        let reserva = reservas
+            .crear_reserva(
+                &Actor::sistema(),
+                "emp-001".to_string(),
+                slot(10),
+                "Reunión".to_string(),
+                Some("s1".to_string()),
+            )
+            .await
+            .unwrap();
+
+        salas
+            .desactivar_sala(&Actor::sistema(), "s1")
+            .await
+            .unwrap();
+        let error = reservas
+            .reprogramar_reserva(&Actor::sistema(), &reserva.id, slot(12))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("desactivada"));
+        salas.activar_sala(&Actor::sistema(), "s1").await.unwrap();
+
+        // Desactivado sin pasar por el servicio, que cancelaría sus reservas
+        let mut juan = reservas
+            .empleados
+            .obtener("emp-001")
+            .await
+            .unwrap()
+            .unwrap();
+        juan.desactivar();
+        reservas.empleados.actualizar(&juan).await.unwrap();
+        let error = reservas
+            .reprogramar_reserva(&Actor::sistema(), &reserva.id, slot(12))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("desactivado"));
+        let cambios = CambiosReserva {
+            slot: Some(slot(12)),
+            ..Default::default()
+        };
+        assert!(reservas
+            .actualizar_reserva(&Actor::sistema(), &reserva.id, cambios)
+            .await
+            .is_err());
+        assert_eq!(
+            reservas
+                .obtener_reserva(&reserva.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .slot,
+            slot(10)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirmar_no_reactiva_canceladas() {
+        let (reservas, _, _) = servicios(PoliticaDesactivacion::Cancelar).await;
+        let reserva = reservar(&reservas, "emp-001", 10).await.unwrap();
+        reservas
+            .cancelar_reserva(&Actor::sistema(), &reserva.id)
+            .await
+            .unwrap();
+        // Otro ocupa el slot que ha quedado libre
+        reservar(&reservas, "emp-001", 10).await.unwrap();
+
+        let error = reservas
+            .confirmar_reserva(&Actor::sistema(), &reserva.id)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("cancelada"));
+        let reserva = reservas
+            .obtener_reserva(&reserva.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!reserva.esta_activa());
+    }
+
+    #[tokio::test]
+    async fn test_reprogramar_rechaza_conflictos() {
+        let (reservas, _, _) = servicios(PoliticaDesactivacion::Cancelar).await;
+        let reserva = reservas
+            .crear_reserva(
+                &Actor::sistema(),
+                "emp-001".to_string(),
+                slot(10),
+                "Reunión".to_string(),
+                Some("s1".to_string()),
+            )
+            .await
+            .unwrap();
+        reservar(&reservas, "emp-001", 11).await.unwrap();
+        reservas
+            .crear_reserva(
+                &Actor::sistema(),
+                "emp-002".to_string(),
+                slot(12),
+                "Otra".to_string(),
+                Some("s1".to_string()),
+            )
+            .await
+            .unwrap();
+
+        let error = reservas
+            .reprogramar_reserva(&Actor::sistema(), &reserva.id, slot(11))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("ya tiene una reserva"));
+        let error = reservas
+            .reprogramar_reserva(&Actor::sistema(), &reserva.id, slot(12))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("ya está reservada"));
+        let error = reservas
+            .reprogramar_reserva(&Actor::sistema(), &reserva.id, slot(20))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("SlotFueraDeHorarioLaboral"));
+
+        // Ningún intento fallido movió la reserva
+        let actual = reservas
+            .obtener_reserva(&reserva.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(actual.slot, slot(10));
+
+        reservas
+            .cancelar_reserva(&Actor::sistema(), &reserva.id)
+            .await
+            .unwrap();
+        assert!(reservas
+            .reprogramar_reserva(&Actor::sistema(), &reserva.id, slot(14))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_metricas_de_negocio() {
+        let metricas = Arc::new(MetricasPrometheus::new());
+        let (reservas, _, _) = servicios_con(PoliticaDesactivacion::Cancelar, |reservas| {
+            reservas.con_metricas(Arc::clone(&metricas) as Arc<dyn Metricas>)
+        })
+        .await;
+
+        let reserva = reservar(&reservas, "emp-001", 10).await.unwrap();
+        reservas
+            .confirmar_reserva(&Actor::sistema(), &reserva.id)
+            .await
+            .unwrap();
+        assert!(reservar(&reservas, "emp-001", 10).await.is_err());
+        reservas
+            .cancelar_reserva(&Actor::sistema(), &reserva.id)
+            .await
+            .unwrap();
+
+        let texto = metricas.exponer();
+        for linea in [
+            "reservas_cambios_total{cambio=\"creada\"} 1",
+            "reservas_cambios_total{cambio=\"confirmada\"} 1",
+            "reservas_cambios_total{cambio=\"cancelada\"} 1",
+            "reservas_conflictos_total{recurso=\"empleado\"} 1",
+            "reservas_conflictos_total{recurso=\"sala\"} 0",
+        ] {
+            assert!(texto.contains(linea), "falta {}", linea);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_permisos_y_auditoria() {
+        let auditoria = Arc::new(reservas_adapters::InMemoryRegistroAuditoria::new());
+        let (reservas, empleados, salas) =
+            servicios_con(PoliticaDesactivacion::Cancelar, |reservas| {
+                reservas.con_auditoria(auditoria.clone())
+            })
+            .await;
+        let salas = salas.con_auditoria(auditoria.clone());
+
+        // María es la responsable de Juan
+        let cambios = CambiosEmpleado {
+            responsable_id: Some(Some("emp-002".to_string())),
+            ..Default::default()
+        };
+        empleados
+            .actualizar_empleado(&Actor::sistema(), "emp-001", cambios)
+            .await
+            .unwrap();
+        let juan = Actor::new("juan", Some("emp-001".to_string()), vec![Rol::Empleado]);
+        let maria = Actor::new("maria", Some("emp-002".to_string()), vec![Rol::Responsable]);
+
+        // Cada uno reserva solo para sí mismo
+        let reserva = reservas
+            .crear_reserva(
+                &juan,
+                "emp-001".to_string(),
+                slot(10),
+                "Reunión".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        let error = reservas
+            .crear_reserva(
+                &juan,
+                "emp-002".to_string(),
+                slot(10),
+                "Reunión".to_string(),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ErrorServicio::Denegado(_)));
+        assert!(matches!(
+            reservas.cancelar_reserva(&maria, &reserva.id).await,
+            Err(ErrorServicio::Denegado(_))
+        ));
+
+        // Solo el responsable confirma
+        assert!(matches!(
+            reservas.confirmar_reserva(&juan, &reserva.id).await,
+            Err(ErrorServicio::Denegado(_))
+        ));
+        reservas
+            .confirmar_reserva(&maria, &reserva.id)
+            .await
+            .unwrap();
+
+        // Las salas son cosa de admin-salas
+        assert!(salas
+            .crear_sala(&juan, "Sala".to_string(), 4)
+            .await
+            .is_err());
+        let facilities = Actor::new("facilities", None, vec![Rol::AdminSalas]);
+        salas
+            .crear_sala(&facilities, "Sala".to_string(), 4)
+            .await
+            .unwrap();
+
+        let denegados: Vec<(String, Accion)> = auditoria
+            .listar()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| (i.sujeto, i.accion))
+            .collect();
+        assert_eq!(
+            denegados,
+            [
+                ("juan".to_string(), Accion::CrearReserva),
+                ("maria".to_string(), Accion::CancelarReserva),
+                ("juan".to_string(), Accion::ConfirmarReserva),
+                ("juan".to_string(), Accion::GestionarSalas),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reprogramar_sin_permiso_de_confirmar_vuelve_a_pendiente() {
+        let (reservas, empleados, _) = servicios(PoliticaDesactivacion::Cancelar).await;
+        let cambios = CambiosEmpleado {
+            responsable_id: Some(Some("emp-002".to_string())),
+            ..Default::default()
+        };
+        empleados
+            .actualizar_empleado(&Actor::sistema(), "emp-001", cambios)
+            .await
+            .unwrap();
+        let juan = Actor::new("juan", Some("emp-001".to_string()), vec![Rol::Empleado]);
+        let maria = Actor::new("maria", Some("emp-002".to_string()), vec![Rol::Responsable]);
+        let reserva = reservar(&reservas, "emp-001", 10).await.unwrap();
+        reservas
+            .confirmar_reserva(&maria, &reserva.id)
+            .await
+            .unwrap();
+
+        // Juan mueve su reserva confirmada: la responsable debe confirmarla otra vez
+        let movida = reservas
+            .reprogramar_reserva(&juan, &reserva.id, slot(12))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(movida.estado, EstadoReserva::Pendiente);
+        let cambios = CambiosReserva {
+            descripcion: Some("Otra".to_string()),
+            ..Default::default()
+        };
+        let editada = reservas
+            .actualizar_reserva(&juan, &reserva.id, cambios)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(editada.estado, EstadoReserva::Pendiente);
+
+        // Si la mueve quien puede confirmarla, sigue confirmada
+        reservas
+            .confirmar_reserva(&maria, &reserva.id)
+            .await
+            .unwrap();
+        let movida = reservas
+            .reprogramar_reserva(&Actor::sistema(), &reserva.id, slot(14))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(movida.estado, EstadoReserva::Confirmada);
+    }
+
+    #[tokio::test]
+    async fn test_responsable_valido() {
+        let (_, empleados, _) = servicios(PoliticaDesactivacion::Cancelar).await;
+
+        for responsable in ["emp-999", "emp-001"] {
+            let cambios = CambiosEmpleado {
+                responsable_id: Some(Some(responsable.to_string())),
+                ..Default::default()
+            };
+            let error = empleados
+                .actualizar_empleado(&Actor::sistema(), "emp-001", cambios)
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(&error, ErrorEmpleado::Validacion(c) if c[0].campo == "responsable_id")
+            );
+        }
+
+        // Juan depende de María, así que María no puede depender de Juan
+        let responsable = |id: &str| CambiosEmpleado {
+            responsable_id: Some(Some(id.to_string())),
+            ..Default::default()
+        };
+        empleados
+            .actualizar_empleado(&Actor::sistema(), "emp-001", responsable("emp-002"))
+            .await
+            .unwrap();
+        let error = empleados
+            .actualizar_empleado(&Actor::sistema(), "emp-002", responsable("emp-001"))
+            .await
+            .unwrap_err();
+        assert!(matches!(&error, ErrorEmpleado::Validacion(c) if c[0].campo == "responsable_id"));
+
+        // Ni se puede eliminar a María mientras sea responsable de Juan
+        let error = empleados
+            .eliminar_empleado(&Actor::sistema(), "emp-002")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("responsable"));
+        let sin_responsable = CambiosEmpleado {
+            responsable_id: Some(None),
+            ..Default::default()
+        };
+        empleados
+            .actualizar_empleado(&Actor::sistema(), "emp-001", sin_responsable)
+            .await
+            .unwrap();
+        assert!(empleados
+            .eliminar_empleado(&Actor::sistema(), "emp-002")
+            .await
+            .unwrap());
+
+        let juan = Actor::new("juan", Some("emp-001".to_string()), vec![Rol::Empleado]);
+        let error = empleados
+            .actualizar_empleado(&juan, "emp-001", CambiosEmpleado::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ErrorEmpleado::Denegado(_)));
+    }
+
+    #[test]
+    fn test_politica_desde_texto() {
+        assert_eq!("cancelar".parse(), Ok(PoliticaDesactivacion::Cancelar));
+        assert_eq!("bloquear".parse(), Ok(PoliticaDesactivacion::Bloquear));
+        assert_eq!(
+            "reasignar:emp-002".parse(),
+            Ok(PoliticaDesactivacion::Reasignar("emp-002".to_string()))
+        );
+        assert!("reasignar:".parse::<PoliticaDesactivacion>().is_err());
+        assert!("borrar".parse::<PoliticaDesactivacion>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disponibilidad_y_busqueda_de_salas() {
+        let (reservas, _, salas) = servicios(PoliticaDesactivacion::Cancelar).await;
+        salas
+            .crear_sala(&Actor::sistema(), "Sala Verde".to_string(), 12)
+            .await
+            .unwrap();
+        reservas
+            .crear_reserva(
+                &Actor::sistema(),
+                "emp-001".to_string(),
+                slot(11),
+                "Reunión".to_string(),
+                Some("s1".to_string()),
+            )
+            .await
+            .unwrap();
+        let fecha = slot(9).inicio.date_naive();
+
+        // Solo las salas activas, por nombre
+        let matriz = salas
+            .disponibilidad_salas(Periodo::dia(fecha))
+            .await
+            .unwrap();
+        let nombres: Vec<&str> = matriz.filas.iter().map(|f| f.nombre.as_str()).collect();
+        assert_eq!(nombres, ["Sala Azul", "Sala Verde"]);
+        assert_eq!(matriz.filas[0].libres(), 8);
+
+        let busqueda = |hora_inicio, hora_fin, capacidad_minima| BusquedaSalas {
+            fecha,
+            hora_inicio,
+            hora_fin,
+            capacidad_minima,
+        };
+        let libres = salas.buscar_salas_libres(busqueda(9, 11, 4)).await.unwrap();
+        let nombres: Vec<&str> = libres.iter().map(|s| s.nombre.as_str()).collect();
+        assert_eq!(nombres, ["Sala Azul", "Sala Verde"]);
+
+        // La azul está reservada a las 11 y no cabe un grupo de 10
+        let libres = salas
+            .buscar_salas_libres(busqueda(10, 12, 4))
+            .await
+            .unwrap();
+        assert_eq!(libres.len(), 1);
+        assert_eq!(libres[0].nombre, "Sala Verde");
+        assert!(salas
+            .buscar_salas_libres(busqueda(9, 11, 13))
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(salas
+            .buscar_salas_libres(busqueda(12, 10, 1))
+            .await
+            .is_err());
     }
 }
@@ -0,0 +1,292 @@
+// Caso de uso de la matriz de disponibilidad
+//
+// Elige las filas (empleados, equipo de un responsable, salas) y deja el
+// cálculo al dominio (`MatrizDisponibilidad`). Solo se leen las reservas del
+// periodo, página a página, para no cargar el histórico entero.
+
+use async_trait::async_trait;
+use chrono::Days;
+use reservas_domain::{MatrizDisponibilidad, Periodo, Reserva};
+use reservas_ports::consulta::{ConsultaReservas, LIMITE_MAXIMO};
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::reserva_repository::ReservaRepository;
+use reservas_ports::out::sala_repository::SalaRepository;
+use reservas_ports::r#in::matriz_disponibilidad_service::{
+    ErrorMatriz, FiltroMatriz, MatrizDisponibilidadService,
+};
+
+pub struct MatrizDisponibilidadServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+{
+    empleados: E,
+    salas: S,
+    reservas: R,
+}
+
+impl<E, S, R> MatrizDisponibilidadServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+{
+    pub fn new(empleados: E, salas: S, reservas: R) -> Self {
+        Self {
+            empleados,
+            salas,
+            reservas,
+        }
+    }
+
+    /// Reservas con slot dentro del periodo
+    async fn reservas_del_periodo(&self, periodo: &Periodo) -> Result<Vec<Reserva>, String> {
+        let mut consulta = ConsultaReservas {
+            desde: Some(
+                periodo
+                    .desde
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap_or_default()
+                    .and_utc(),
+            ),
+            hasta: periodo
+                .hasta
+                .checked_add_days(Days::new(1))
+                .and_then(|dia| dia.and_hms_opt(0, 0, 0))
+                .map(|inicio| inicio.and_utc()),
+            limite: Some(LIMITE_MAXIMO),
+            ..Default::default()
+        };
+        let mut reservas = Vec::new();
+        loop {
+            let pagina = self.reservas.buscar(&consulta).await?;
+            reservas.extend(pagina.elementos);
+            match pagina.siguiente_cursor {
+                Some(cursor) => consulta.cursor = Some(cursor),
+                None => return Ok(reservas),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<E, S, R> MatrizDisponibilidadService for MatrizDisponibilidadServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository + Send + Sync,
+    S: SalaRepository + Send + Sync,
+    R: ReservaRepository + Send + Sync,
+{
+    async fn matriz_disponibilidad(
+        &self,
+        periodo: Periodo,
+        filtro: FiltroMatriz,
+    ) -> Result<MatrizDisponibilidad, ErrorMatriz> {
+        let mut empleados = self.empleados.listar().await?;
+        let mut salas = self.salas.listar().await?;
+
+        let responsable = filtro.responsable_id.as_deref();
+        if let Some(id) = filtro
+            .empleados
+            .iter()
+            .map(String::as_str)
+            .chain(responsable)
+            .find(|id| !empleados.iter().any(|e| e.id == *id))
+        {
+            return Err(ErrorMatriz::NoEncontrado(format!(
+                "Empleado no encontrado: {}",
+                id
+            )));
+        }
+        if let Some(id) = filtro
+            .salas
+            .iter()
+            .find(|id| !salas.iter().any(|s| s.id == **id))
+        {
+            return Err(ErrorMatriz::NoEncontrado(format!(
+                "Sala no encontrada: {}",
+                id
+            )));
+        }
+
+        if filtro == FiltroMatriz::default() {
+            salas.clear();
+        } else {
+            empleados.retain(|e| {
+                filtro.empleados.contains(&e.id)
+                    || responsable
+                        .is_some_and(|r| e.id == r || e.responsable_id.as_deref() == Some(r))
+            });
+            salas.retain(|s| filtro.salas.contains(&s.id));
+        }
+        empleados.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+        salas.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+
+        let reservas = self.reservas_del_periodo(&periodo).await?;
+        Ok(MatrizDisponibilidad::generar(
+            periodo, &empleados, &salas, &reservas,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use reservas_adapters::{
+        InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+    };
+    use reservas_domain::{Empleado, Sala, Slot, TipoFila};
+    use std::sync::Arc;
+
+    type Servicio = MatrizDisponibilidadServiceImpl<
+        Arc<InMemoryEmpleadoRepository>,
+        Arc<InMemorySalaRepository>,
+        Arc<InMemoryReservaRepository>,
+    >;
+
+    fn empleado(id: &str, nombre: &str, responsable: Option<&str>) -> Empleado {
+        let mut empleado = Empleado::new(
+            id.to_string(),
+            nombre.to_string(),
+            format!("{}@empresa.com", id),
+        )
+        .unwrap();
+        empleado.responsable_id = responsable.map(String::from);
+        empleado
+    }
+
+    fn reserva(id: &str, empleado_id: &str, dia: u32) -> Reserva {
+        Reserva::new(
+            id.to_string(),
+            empleado_id.to_string(),
+            Slot::from_date_and_hour(2030, 3, dia, 10).unwrap(),
+            "Reunión".to_string(),
+        )
+        .unwrap()
+        .en_sala("s1".to_string())
+    }
+
+    async fn servicio() -> Servicio {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let salas = Arc::new(InMemorySalaRepository::new());
+        let reservas = Arc::new(InMemoryReservaRepository::new());
+        for e in [
+            empleado("jefa", "Marta", None),
+            empleado("e1", "Ana", Some("jefa")),
+            empleado("e2", "Luis", None),
+        ] {
+            empleados.guardar(&e).await.unwrap();
+        }
+        salas
+            .guardar(&Sala::new("s1".to_string(), "Sala Azul".to_string(), 8).unwrap())
+            .await
+            .unwrap();
+        reservas.guardar(&reserva("r1", "e1", 4)).await.unwrap();
+        // Fuera del periodo: no debe marcar ninguna celda
+        reservas.guardar(&reserva("r2", "e2", 11)).await.unwrap();
+        MatrizDisponibilidadServiceImpl::new(empleados, salas, reservas)
+    }
+
+    fn periodo() -> Periodo {
+        Periodo::new(
+            NaiveDate::from_ymd_opt(2030, 3, 4).unwrap(),
+            NaiveDate::from_ymd_opt(2030, 3, 8).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn filas(matriz: &MatrizDisponibilidad) -> Vec<(TipoFila, &str)> {
+        matriz
+            .filas
+            .iter()
+            .map(|f| (f.tipo, f.id.as_str()))
+            .collect()
+    }
+
+    fn ocupadas(matriz: &MatrizDisponibilidad) -> usize {
+        matriz
+            .filas
+            .iter()
+            .flat_map(|f| f.celdas.iter().flatten())
+            .filter(|c| c.is_some())
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_sin_filtro_solo_empleados() {
+        let servicio = servicio().await;
+        let matriz = servicio
+            .matriz_disponibilidad(periodo(), FiltroMatriz::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            filas(&matriz),
+            [
+                (TipoFila::Empleado, "e1"),
+                (TipoFila::Empleado, "e2"),
+                (TipoFila::Empleado, "jefa"),
+            ]
+        );
+        assert_eq!(ocupadas(&matriz), 1);
+    }
+
+    #[tokio::test]
+    async fn test_filtros_de_equipo_y_salas() {
+        let servicio = servicio().await;
+        let filtro = FiltroMatriz {
+            responsable_id: Some("jefa".to_string()),
+            salas: vec!["s1".to_string()],
+            ..Default::default()
+        };
+        let matriz = servicio
+            .matriz_disponibilidad(periodo(), filtro)
+            .await
+            .unwrap();
+        assert_eq!(
+            filas(&matriz),
+            [
+                (TipoFila::Empleado, "e1"),
+                (TipoFila::Empleado, "jefa"),
+                (TipoFila::Sala, "s1"),
+            ]
+        );
+        assert_eq!(ocupadas(&matriz), 2);
+
+        let filtro = FiltroMatriz {
+            empleados: vec!["e2".to_string()],
+            ..Default::default()
+        };
+        let matriz = servicio
+            .matriz_disponibilidad(periodo(), filtro)
+            .await
+            .unwrap();
+        assert_eq!(filas(&matriz), [(TipoFila::Empleado, "e2")]);
+        assert_eq!(ocupadas(&matriz), 0);
+    }
+
+    #[tokio::test]
+    async fn test_filtro_con_id_inexistente() {
+        let servicio = servicio().await;
+        let filtro = FiltroMatriz {
+            salas: vec!["s9".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            servicio.matriz_disponibilidad(periodo(), filtro).await,
+            Err(ErrorMatriz::NoEncontrado(
+                "Sala no encontrada: s9".to_string()
+            ))
+        );
+        let filtro = FiltroMatriz {
+            responsable_id: Some("nadie".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            servicio.matriz_disponibilidad(periodo(), filtro).await,
+            Err(ErrorMatriz::NoEncontrado(
+                "Empleado no encontrado: nadie".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,585 @@
+// Caso de uso de copia de seguridad y restauración
+//
+// Trabaja con los puertos de salida, así sirve para cualquier implementación
+// de los repositorios. Las reservas se restauran a través del servicio de
+// reservas, para no pisar escrituras en curso y que se avise de los cambios.
+// El formato de fichero (versión, serialización) es cosa de los adaptadores
+// de entrada.
+
+use crate::autorizacion::Autorizador;
+use async_trait::async_trait;
+use reservas_domain::{Accion, Actor, Email};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::out::auditoria::RegistroAuditoria;
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::reserva_repository::ReservaRepository;
+use reservas_ports::out::sala_repository::SalaRepository;
+use reservas_ports::r#in::reserva_service::ReservaService;
+use reservas_ports::r#in::respaldo_service::{
+    ErrorRespaldo, Respaldo, RespaldoService, ResumenRestauracion,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+pub struct RespaldoServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+{
+    empleados: E,
+    salas: S,
+    reservas: R,
+    servicio_reservas: Arc<dyn ReservaService>,
+    autorizador: Autorizador,
+}
+
+impl<E, S, R> RespaldoServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+{
+    pub fn new(
+        empleados: E,
+        salas: S,
+        reservas: R,
+        servicio_reservas: Arc<dyn ReservaService>,
+    ) -> Self {
+        Self {
+            empleados,
+            salas,
+            reservas,
+            servicio_reservas,
+            autorizador: Autorizador::default(),
+        }
+    }
+
+    /// Registra en `auditoria` las operaciones denegadas
+    pub fn con_auditoria(mut self, auditoria: Arc<dyn RegistroAuditoria>) -> Self {
+        self.autorizador = Autorizador::new(auditoria);
+        self
+    }
+
+    async fn autorizar(&self, actor: &Actor) -> Result<(), ErrorServicio> {
+        self.autorizador
+            .exigir(actor, Accion::Administrar, None, "respaldo".to_string())
+            .await
+    }
+
+    async fn leer_todo(&self) -> Result<Respaldo, String> {
+        Ok(Respaldo {
+            empleados: self.empleados.listar().await?,
+            salas: self.salas.listar().await?,
+            reservas: self.reservas.listar().await?,
+        })
+    }
+}
+
+impl<E, S, R> RespaldoServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository + Send + Sync,
+    S: SalaRepository + Send + Sync,
+    R: ReservaRepository + Send + Sync,
+{
+    /// Escribe `cambios` sobre `actual`: actualiza lo que ya existe y crea
+    /// lo demás. Primero las entidades referenciadas, luego las reservas.
+    async fn aplicar(&self, actual: &Respaldo, cambios: &Respaldo) -> Result<(), String> {
+        // Quien cambia de email se borra antes de escribir a nadie: si dos
+        // empleados se intercambian el email, el primero que se actualizara
+        // chocaría con el índice único aunque el resultado final sea válido
+        let emails: HashMap<&str, &Email> = actual
+            .empleados
+            .iter()
+            .map(|e| (e.id.as_str(), &e.email))
+            .collect();
+        let mut existentes = ids(&actual.empleados, |e| &e.id);
+        for empleado in &cambios.empleados {
+            if emails
+                .get(empleado.id.as_str())
+                .is_some_and(|email| **email != empleado.email)
+            {
+                self.empleados.eliminar(&empleado.id).await?;
+                existentes.remove(empleado.id.as_str());
+            }
+        }
+        for empleado in &cambios.empleados {
+            if existentes.contains(empleado.id.as_str()) {
+                self.empleados.actualizar(empleado).await?;
+            } else {
+                self.empleados.guardar(empleado).await?;
+            }
+        }
+        let existentes = ids(&actual.salas, |s| &s.id);
+        for sala in &cambios.salas {
+            if existentes.contains(sala.id.as_str()) {
+                self.salas.actualizar(sala).await?;
+            } else {
+                self.salas.guardar(sala).await?;
+            }
+        }
+        // Todas o ninguna: si fallan, no queda ninguna reserva que deshacer
+        if !cambios.reservas.is_empty() {
+            self.servicio_reservas
+                .restaurar_reservas(&Actor::sistema(), cambios.reservas.clone())
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Vuelve al estado `antes` tras una restauración a medias de
+    /// `respaldo`: borra lo que creó y reescribe lo que sobrescribió. Las
+    /// reservas son lo último que se escribe y su servicio ya las deja como
+    /// estaban, así que solo quedan empleados y salas.
+    async fn deshacer(&self, antes: &Respaldo, respaldo: &Respaldo) -> Result<(), String> {
+        let ahora = self.leer_todo().await?;
+
+        let previas = ids(&antes.salas, |s| &s.id);
+        let presentes = ids(&ahora.salas, |s| &s.id);
+        for sala in &respaldo.salas {
+            if !previas.contains(sala.id.as_str()) && presentes.contains(sala.id.as_str()) {
+                self.salas.eliminar(&sala.id).await?;
+            }
+        }
+        let previos = ids(&antes.empleados, |e| &e.id);
+        let presentes = ids(&ahora.empleados, |e| &e.id);
+        for empleado in &respaldo.empleados {
+            if !previos.contains(empleado.id.as_str()) && presentes.contains(empleado.id.as_str()) {
+                self.empleados.eliminar(&empleado.id).await?;
+            }
+        }
+
+        let tocados = Respaldo {
+            empleados: solo_en(&antes.empleados, &respaldo.empleados, |e| &e.id),
+            salas: solo_en(&antes.salas, &respaldo.salas, |s| &s.id),
+            reservas: Vec::new(),
+        };
+        let ahora = self.leer_todo().await?;
+        self.aplicar(&ahora, &tocados).await
+    }
+}
+
+#[async_trait]
+impl<E, S, R> RespaldoService for RespaldoServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository + Send + Sync,
+    S: SalaRepository + Send + Sync,
+    R: ReservaRepository + Send + Sync,
+{
+    async fn exportar(&self, actor: &Actor) -> Result<Respaldo, ErrorServicio> {
+        self.autorizar(actor).await?;
+        Ok(self.leer_todo().await?)
+    }
+
+    async fn restaurar(
+        &self,
+        actor: &Actor,
+        respaldo: Respaldo,
+        simular: bool,
+    ) -> Result<ResumenRestauracion, ErrorRespaldo> {
+        self.autorizar(actor).await?;
+        // Se valida el resultado, no solo el respaldo: puede apoyarse en lo
+        // que ya existe y no menciona
+        let antes = self.leer_todo().await?;
+        let errores = validar(&fusionar(&antes, &respaldo));
+        if !errores.is_empty() {
+            return Err(ErrorRespaldo::Inconsistente(errores));
+        }
+
+        let resumen = ResumenRestauracion {
+            empleados: respaldo.empleados.len(),
+            salas: respaldo.salas.len(),
+            reservas: respaldo.reservas.len(),
+        };
+        if simular {
+            return Ok(resumen);
+        }
+
+        // O se restaura todo o nada: un fallo a medias deja los datos como estaban
+        if let Err(e) = self.aplicar(&antes, &respaldo).await {
+            let mensaje = match self.deshacer(&antes, &respaldo).await {
+                Ok(()) => format!("Restauración deshecha: {}", e),
+                Err(otro) => format!("Restauración a medias: {}; al deshacer: {}", e, otro),
+            };
+            return Err(mensaje.into());
+        }
+
+        Ok(resumen)
+    }
+}
+
+/// Estado que deja restaurar `respaldo` sobre `actual`: lo que el respaldo
+/// no menciona se queda como está
+fn fusionar(actual: &Respaldo, respaldo: &Respaldo) -> Respaldo {
+    Respaldo {
+        empleados: fusionar_por_id(&actual.empleados, &respaldo.empleados, |e| &e.id),
+        salas: fusionar_por_id(&actual.salas, &respaldo.salas, |s| &s.id),
+        reservas: fusionar_por_id(&actual.reservas, &respaldo.reservas, |r| &r.id),
+    }
+}
+
+fn fusionar_por_id<T: Clone>(actuales: &[T], restaurados: &[T], id: fn(&T) -> &String) -> Vec<T> {
+    let sustituidos = ids(restaurados, id);
+    actuales
+        .iter()
+        .filter(|a| !sustituidos.contains(id(a).as_str()))
+        .chain(restaurados)
+        .cloned()
+        .collect()
+}
+
+/// Los elementos de `elementos` cuyo ID aparece en `filtro`
+fn solo_en<T: Clone>(elementos: &[T], filtro: &[T], id: fn(&T) -> &String) -> Vec<T> {
+    let buscados = ids(filtro, id);
+    elementos
+        .iter()
+        .filter(|e| buscados.contains(id(e).as_str()))
+        .cloned()
+        .collect()
+}
+
+fn ids<T>(elementos: &[T], id: fn(&T) -> &String) -> HashSet<&str> {
+    elementos.iter().map(|e| id(e).as_str()).collect()
+}
+
+/// Comprueba que un conjunto de datos es coherente por sí mismo: IDs y
+/// emails únicos, y toda referencia (responsable, empleado y sala de cada
+/// reserva) apunta a algo incluido en él.
+/// Devuelve todos los problemas encontrados, no solo el primero.
+pub fn validar(respaldo: &Respaldo) -> Vec<String> {
+    let mut errores = Vec::new();
+
+    let empleados = ids_unicos(
+        "empleado",
+        respaldo.empleados.iter().map(|e| e.id.as_str()),
+        &mut errores,
+    );
+    let salas = ids_unicos(
+        "sala",
+        respaldo.salas.iter().map(|s| s.id.as_str()),
+        &mut errores,
+    );
+    ids_unicos(
+        "reserva",
+        respaldo.reservas.iter().map(|r| r.id.as_str()),
+        &mut errores,
+    );
+    let mut emails = HashSet::new();
+    for empleado in &respaldo.empleados {
+        if !emails.insert(&empleado.email) {
+            errores.push(format!("Email de empleado duplicado: {}", empleado.email));
+        }
+        if let Some(responsable_id) = &empleado.responsable_id {
+            if !empleados.contains(responsable_id.as_str()) {
+                errores.push(format!(
+                    "El empleado {} tiene como responsable al empleado inexistente {}",
+                    empleado.id, responsable_id
+                ));
+            }
+        }
+    }
+
+    for reserva in &respaldo.reservas {
+        if !empleados.contains(reserva.empleado_id.as_str()) {
+            errores.push(format!(
+                "La reserva {} referencia al empleado inexistente {}",
+                reserva.id, reserva.empleado_id
+            ));
+        }
+        if let Some(sala_id) = &reserva.sala_id {
+            if !salas.contains(sala_id.as_str()) {
+                errores.push(format!(
+                    "La reserva {} referencia a la sala inexistente {}",
+                    reserva.id, sala_id
+                ));
+            }
+        }
+    }
+
+    errores
+}
+
+fn ids_unicos<'a>(
+    tipo: &str,
+    ids: impl Iterator<Item = &'a str>,
+    errores: &mut Vec<String>,
+) -> HashSet<&'a str> {
+    let mut vistos = HashSet::new();
+    for id in ids {
+        if !vistos.insert(id) {
+            errores.push(format!("ID de {} duplicado: {}", tipo, id));
+        }
+    }
+    vistos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReservaServiceImpl;
+    use chrono::{Duration, Utc};
+    use reservas_adapters::{
+        InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+        MetricasPrometheus,
+    };
+    use reservas_domain::{Empleado, EstadoReserva, Reserva, Sala, Slot};
+    use reservas_ports::consulta::{ConsultaSalas, Pagina};
+    use reservas_ports::out::metricas::Metricas;
+
+    /// Repositorio de salas que no admite salas nuevas
+    struct SalasSinAltas(InMemorySalaRepository);
+
+    #[async_trait]
+    impl SalaRepository for SalasSinAltas {
+        async fn guardar(&self, _: &Sala) -> Result<(), String> {
+            Err("Sin espacio".to_string())
+        }
+        async fn obtener(&self, id: &str) -> Result<Option<Sala>, String> {
+            self.0.obtener(id).await
+        }
+        async fn listar(&self) -> Result<Vec<Sala>, String> {
+            self.0.listar().await
+        }
+        async fn buscar(&self, consulta: &ConsultaSalas) -> Result<Pagina<Sala>, String> {
+            self.0.buscar(consulta).await
+        }
+        async fn actualizar(&self, sala: &Sala) -> Result<(), String> {
+            self.0.actualizar(sala).await
+        }
+        async fn eliminar(&self, id: &str) -> Result<(), String> {
+            self.0.eliminar(id).await
+        }
+    }
+
+    type Servicio<S> = RespaldoServiceImpl<
+        Arc<InMemoryEmpleadoRepository>,
+        Arc<S>,
+        Arc<InMemoryReservaRepository>,
+    >;
+
+    fn servicio<S: SalaRepository + Send + Sync + 'static>(
+        empleados: InMemoryEmpleadoRepository,
+        salas: S,
+    ) -> Servicio<S> {
+        servicio_con(empleados, salas, Arc::new(MetricasPrometheus::new()))
+    }
+
+    /// Con las métricas del servicio de reservas, para ver de qué avisa
+    fn servicio_con<S: SalaRepository + Send + Sync + 'static>(
+        empleados: InMemoryEmpleadoRepository,
+        salas: S,
+        metricas: Arc<MetricasPrometheus>,
+    ) -> Servicio<S> {
+        let empleados = Arc::new(empleados);
+        let salas = Arc::new(salas);
+        let reservas = Arc::new(InMemoryReservaRepository::new());
+        let servicio_reservas = ReservaServiceImpl::new(
+            Arc::clone(&reservas),
+            Arc::clone(&empleados),
+            Arc::clone(&salas),
+        )
+        .con_metricas(metricas);
+        RespaldoServiceImpl::new(empleados, salas, reservas, Arc::new(servicio_reservas))
+    }
+
+    fn empleado(id: &str, nombre: &str, email: &str) -> Empleado {
+        Empleado::new(id.to_string(), nombre.to_string(), email.to_string()).unwrap()
+    }
+
+    fn reserva(id: &str, empleado_id: &str, sala_id: Option<&str>) -> Reserva {
+        Reserva {
+            id: id.to_string(),
+            empleado_id: empleado_id.to_string(),
+            slot: Slot::new(Utc::now() - Duration::days(30)),
+            descripcion: "Histórica".to_string(),
+            estado: EstadoReserva::Confirmada,
+            sala_id: sala_id.map(str::to_string),
+        }
+    }
+
+    fn respaldo() -> Respaldo {
+        Respaldo {
+            empleados: vec![Empleado::new(
+                "emp-001".to_string(),
+                "Juan".to_string(),
+                "juan@empresa.com".to_string(),
+            )
+            .unwrap()],
+            salas: vec![Sala::new("s1".to_string(), "Sala Azul".to_string(), 8).unwrap()],
+            reservas: vec![reserva("r1", "emp-001", Some("s1"))],
+        }
+    }
+
+    #[test]
+    fn test_respaldo_coherente_es_valido() {
+        assert!(validar(&respaldo()).is_empty());
+    }
+
+    #[test]
+    fn test_detecta_referencias_rotas() {
+        let mut r = respaldo();
+        r.reservas.push(reserva("r2", "emp-999", Some("s9")));
+
+        let errores = validar(&r);
+        assert_eq!(errores.len(), 2);
+        assert!(errores[0].contains("emp-999"));
+        assert!(errores[1].contains("s9"));
+    }
+
+    #[test]
+    fn test_detecta_ids_duplicados() {
+        let mut r = respaldo();
+        r.reservas.push(reserva("r1", "emp-001", None));
+
+        assert_eq!(validar(&r), vec!["ID de reserva duplicado: r1"]);
+    }
+
+    #[test]
+    fn test_detecta_emails_duplicados() {
+        let mut r = respaldo();
+        r.empleados.push(
+            Empleado::new(
+                "emp-002".to_string(),
+                "Juan bis".to_string(),
+                "JUAN@empresa.com".to_string(),
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            validar(&r),
+            vec!["Email de empleado duplicado: juan@empresa.com"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restaurar_valida_contra_lo_existente() {
+        let empleados = InMemoryEmpleadoRepository::new();
+        empleados
+            .guardar(&empleado("emp-002", "Ana", "ana@empresa.com"))
+            .await
+            .unwrap();
+        let servicio = servicio(empleados, InMemorySalaRepository::new());
+
+        // La reserva es de un empleado que ya existe aunque no esté en el respaldo
+        let mut r = respaldo();
+        r.reservas.push(reserva("r2", "emp-002", None));
+        servicio
+            .restaurar(&Actor::sistema(), r, false)
+            .await
+            .unwrap();
+
+        let mut r = respaldo();
+        r.reservas.push(reserva("r3", "emp-999", None));
+        let error = servicio
+            .restaurar(&Actor::sistema(), r, false)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("emp-999"));
+    }
+
+    #[tokio::test]
+    async fn test_restaurar_deshace_si_falla_a_medias() {
+        let empleados = InMemoryEmpleadoRepository::new();
+        let original = empleado("emp-001", "Juan Antiguo", "juan@empresa.com");
+        empleados.guardar(&original).await.unwrap();
+        let servicio = servicio(empleados, SalasSinAltas(InMemorySalaRepository::new()));
+
+        // Los empleados se escriben y luego falla el alta de la sala
+        let mut r = respaldo();
+        r.empleados
+            .push(empleado("emp-002", "Ana", "ana@empresa.com"));
+        let error = servicio
+            .restaurar(&Actor::sistema(), r, false)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Restauración deshecha"));
+
+        let respaldo = servicio.exportar(&Actor::sistema()).await.unwrap();
+        assert_eq!(respaldo.empleados, vec![original]);
+        assert!(respaldo.reservas.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_restaurar_intercambio_de_emails() {
+        let empleados = InMemoryEmpleadoRepository::new();
+        empleados
+            .guardar(&empleado("emp-001", "Juan", "juan@empresa.com"))
+            .await
+            .unwrap();
+        empleados
+            .guardar(&empleado("emp-002", "Ana", "ana@empresa.com"))
+            .await
+            .unwrap();
+        empleados
+            .guardar(&empleado("emp-003", "Luis", "luis@empresa.com"))
+            .await
+            .unwrap();
+        let servicio = servicio(empleados, InMemorySalaRepository::new());
+
+        let intercambio = Respaldo {
+            empleados: vec![
+                empleado("emp-001", "Juan", "ana@empresa.com"),
+                empleado("emp-002", "Ana", "juan@empresa.com"),
+            ],
+            ..Default::default()
+        };
+        servicio
+            .restaurar(&Actor::sistema(), intercambio.clone(), false)
+            .await
+            .unwrap();
+        let restaurados = servicio.exportar(&Actor::sistema()).await.unwrap();
+        assert_eq!(restaurados.empleados.len(), 3);
+        assert!(intercambio
+            .empleados
+            .iter()
+            .all(|e| restaurados.empleados.contains(e)));
+
+        // Choca con un empleado que el respaldo no toca: no se escribe nada
+        let choque = Respaldo {
+            empleados: vec![empleado("emp-001", "Juan", "luis@empresa.com")],
+            ..Default::default()
+        };
+        let error = servicio
+            .restaurar(&Actor::sistema(), choque, false)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("luis@empresa.com"));
+        assert_eq!(
+            servicio.exportar(&Actor::sistema()).await.unwrap(),
+            restaurados
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restaurar_reservas_avisa_de_los_cambios() {
+        let metricas = Arc::new(MetricasPrometheus::new());
+        let servicio = servicio_con(
+            InMemoryEmpleadoRepository::new(),
+            InMemorySalaRepository::new(),
+            Arc::clone(&metricas),
+        );
+        servicio
+            .restaurar(&Actor::sistema(), respaldo(), false)
+            .await
+            .unwrap();
+
+        // La misma reserva otra vez no cambia nada; cancelada sí
+        let mut r = respaldo();
+        r.reservas[0].cancelar();
+        servicio
+            .restaurar(&Actor::sistema(), respaldo(), false)
+            .await
+            .unwrap();
+        servicio
+            .restaurar(&Actor::sistema(), r, false)
+            .await
+            .unwrap();
+
+        let texto = metricas.exponer();
+        assert!(texto.contains("reservas_cambios_total{cambio=\"creada\"} 1"));
+        assert!(texto.contains("reservas_cambios_total{cambio=\"cancelada\"} 1"));
+        assert!(texto.contains("reservas_cambios_total{cambio=\"modificada\"} 0"));
+    }
+}
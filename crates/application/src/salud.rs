@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use reservas_ports::consulta::{ConsultaEmpleados, ConsultaReservas, ConsultaSalas};
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::reserva_repository::ReservaRepository;
+use reservas_ports::out::sala_repository::SalaRepository;
+use reservas_ports::r#in::salud_service::{EstadoComponente, EstadoPreparacion, SaludService};
+use std::future::Future;
+use std::time::Duration;
+
+/// Un repositorio que tarda más que esto en contestar se da por caído
+const TIEMPO_MAXIMO: Duration = Duration::from_secs(2);
+
+/// Comprobaciones de salud: un repositorio responde si devuelve la primera
+/// página de una búsqueda. Vale para cualquier adaptador, sin pedirles una
+/// operación específica.
+pub struct SaludServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+{
+    empleados: E,
+    salas: S,
+    reservas: R,
+}
+
+impl<E, S, R> SaludServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository,
+    S: SalaRepository,
+    R: ReservaRepository,
+{
+    pub fn new(empleados: E, salas: S, reservas: R) -> Self {
+        Self {
+            empleados,
+            salas,
+            reservas,
+        }
+    }
+}
+
+async fn comprobar<T>(
+    nombre: &str,
+    consulta: impl Future<Output = Result<T, String>>,
+) -> EstadoComponente {
+    let error = match tokio::time::timeout(TIEMPO_MAXIMO, consulta).await {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(e),
+        Err(_) => Some(format!("Sin respuesta en {:?}", TIEMPO_MAXIMO)),
+    };
+    EstadoComponente {
+        nombre: nombre.to_string(),
+        error,
+    }
+}
+
+#[async_trait]
+impl<E, S, R> SaludService for SaludServiceImpl<E, S, R>
+where
+    E: EmpleadoRepository + Send + Sync,
+    S: SalaRepository + Send + Sync,
+    R: ReservaRepository + Send + Sync,
+{
+    async fn preparacion(&self) -> EstadoPreparacion {
+        let empleados = ConsultaEmpleados {
+            limite: Some(1),
+            ..Default::default()
+        };
+        let salas = ConsultaSalas {
+            limite: Some(1),
+            ..Default::default()
+        };
+        let reservas = ConsultaReservas {
+            limite: Some(1),
+            ..Default::default()
+        };
+        let (empleados, salas, reservas) = tokio::join!(
+            comprobar("empleados", self.empleados.buscar(&empleados)),
+            comprobar("salas", self.salas.buscar(&salas)),
+            comprobar("reservas", self.reservas.buscar(&reservas)),
+        );
+        EstadoPreparacion {
+            componentes: vec![empleados, salas, reservas],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use reservas_adapters::{
+        InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+    };
+    use reservas_domain::Sala;
+    use reservas_ports::consulta::Pagina;
+
+    /// Repositorio de salas sin conexión
+    struct SalasCaidas;
+
+    #[async_trait]
+    impl SalaRepository for SalasCaidas {
+        async fn guardar(&self, _: &Sala) -> Result<(), String> {
+            Err("Sin conexión".to_string())
+        }
+        async fn obtener(&self, _: &str) -> Result<Option<Sala>, String> {
+            Err("Sin conexión".to_string())
+        }
+        async fn listar(&self) -> Result<Vec<Sala>, String> {
+            Err("Sin conexión".to_string())
+        }
+        async fn buscar(&self, _: &ConsultaSalas) -> Result<Pagina<Sala>, String> {
+            Err("Sin conexión".to_string())
+        }
+        async fn actualizar(&self, _: &Sala) -> Result<(), String> {
+            Err("Sin conexión".to_string())
+        }
+        async fn eliminar(&self, _: &str) -> Result<(), String> {
+            Err("Sin conexión".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preparacion() {
+        let salud = SaludServiceImpl::new(
+            InMemoryEmpleadoRepository::new(),
+            InMemorySalaRepository::new(),
+            InMemoryReservaRepository::new(),
+        );
+        let estado = salud.preparacion().await;
+        assert!(estado.preparado());
+        let nombres: Vec<&str> = estado
+            .componentes
+            .iter()
+            .map(|c| c.nombre.as_str())
+            .collect();
+        assert_eq!(nombres, ["empleados", "salas", "reservas"]);
+
+        let salud = SaludServiceImpl::new(
+            InMemoryEmpleadoRepository::new(),
+            SalasCaidas,
+            InMemoryReservaRepository::new(),
+        );
+        let estado = salud.preparacion().await;
+        assert!(!estado.preparado());
+        assert_eq!(estado.componentes[1].error.as_deref(), Some("Sin conexión"));
+        assert_eq!(estado.componentes[2].error, None);
+    }
+}
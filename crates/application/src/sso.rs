@@ -0,0 +1,360 @@
+// Inicio de sesión único (OIDC)
+//
+// El flujo es authorization code con PKCE: `iniciar` genera el estado, el
+// nonce y el verificador, y los guarda hasta que el proveedor devuelve al
+// navegador con el código. Las identidades se asocian a empleados por email y,
+// si el empleado no existe, se da de alta en ese momento (aprovisionamiento
+// just-in-time). Solo se entra como un empleado existente si el proveedor
+// garantiza que el email está verificado; si no, cualquiera podría registrarse
+// en el proveedor con el email de otro.
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use reservas_domain::{Actor, Email, Empleado, Rol};
+use reservas_ports::out::empleado_repository::EmpleadoRepository;
+use reservas_ports::out::proveedor_identidad::{IdentidadExterna, ProveedorIdentidad};
+use reservas_ports::r#in::sso_service::{InicioSso, SsoService};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Tiempo máximo entre `iniciar` y `completar`
+const VIGENCIA_INICIO: Duration = Duration::from_secs(10 * 60);
+
+/// Lo que hace falta recordar de un inicio de sesión hasta que vuelve el código
+struct Pendiente {
+    verificador: String,
+    nonce: String,
+    expira: Instant,
+}
+
+pub struct SsoServiceImpl<E: EmpleadoRepository> {
+    proveedor: Arc<dyn ProveedorIdentidad>,
+    empleados: E,
+    /// Roles de quien llega sin ninguno reconocido
+    roles_por_defecto: Vec<Rol>,
+    pendientes: Mutex<HashMap<String, Pendiente>>,
+}
+
+impl<E: EmpleadoRepository> SsoServiceImpl<E> {
+    pub fn new(proveedor: Arc<dyn ProveedorIdentidad>, empleados: E) -> Self {
+        Self {
+            proveedor,
+            empleados,
+            roles_por_defecto: vec![Rol::Empleado],
+            pendientes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn con_roles_por_defecto(mut self, roles: Vec<Rol>) -> Self {
+        self.roles_por_defecto = roles;
+        self
+    }
+}
+
+/// Token aleatorio de 244 bits en hexadecimal (válido como verificador PKCE)
+fn token_aleatorio() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Un empleado que ya existe solo se asocia con un email verificado
+fn existente(empleado: Empleado, identidad: &IdentidadExterna) -> Result<Empleado, String> {
+    if !identidad.email_verificado {
+        return Err(format!(
+            "El proveedor de identidad no garantiza que {} esté verificado",
+            empleado.email
+        ));
+    }
+    Ok(empleado)
+}
+
+/// `code_challenge` del método S256 (RFC 7636)
+fn reto_pkce(verificador: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verificador.as_bytes()))
+}
+
+impl<E: EmpleadoRepository + Send + Sync> SsoServiceImpl<E> {
+    /// Empleado con el email de la identidad; se crea si no existe
+    async fn empleado_de(&self, identidad: &IdentidadExterna) -> Result<Empleado, String> {
+        let email = identidad
+            .email
+            .as_deref()
+            .ok_or_else(|| "El proveedor de identidad no envía el email".to_string())?;
+        let email = Email::new(email)?;
+        if let Some(empleado) = self.empleados.obtener_por_email(&email).await? {
+            return existente(empleado, identidad);
+        }
+
+        let nombre = identidad
+            .nombre
+            .clone()
+            .filter(|nombre| !nombre.trim().is_empty())
+            .unwrap_or_else(|| {
+                email
+                    .as_str()
+                    .split('@')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            });
+        let empleado = Empleado::new(
+            Uuid::new_v4().to_string(),
+            nombre,
+            email.as_str().to_string(),
+        )
+        .map_err(|errores| {
+            let detalle: Vec<String> = errores.iter().map(|e| e.to_string()).collect();
+            detalle.join("; ")
+        })?;
+        match self.empleados.guardar(&empleado).await {
+            Ok(()) => Ok(empleado),
+            // Otro inicio de sesión simultáneo lo acaba de crear
+            Err(error) => match self.empleados.obtener_por_email(&email).await? {
+                Some(empleado) => existente(empleado, identidad),
+                None => Err(error),
+            },
+        }
+    }
+
+    async fn actor_de(&self, identidad: IdentidadExterna) -> Result<Actor, String> {
+        let empleado = self.empleado_de(&identidad).await?;
+        if !empleado.activo {
+            return Err(format!("El empleado {} está desactivado", empleado.email));
+        }
+        let roles = if identidad.roles.is_empty() {
+            self.roles_por_defecto.clone()
+        } else {
+            identidad.roles
+        };
+        Ok(Actor::new(
+            empleado.email.as_str(),
+            Some(empleado.id),
+            roles,
+        ))
+    }
+}
+
+#[async_trait]
+impl<E> SsoService for SsoServiceImpl<E>
+where
+    E: EmpleadoRepository + Send + Sync,
+{
+    async fn iniciar(&self) -> Result<InicioSso, String> {
+        let estado = token_aleatorio();
+        let pendiente = Pendiente {
+            verificador: token_aleatorio(),
+            nonce: token_aleatorio(),
+            expira: Instant::now() + VIGENCIA_INICIO,
+        };
+        let url = self.proveedor.url_autorizacion(
+            &estado,
+            &pendiente.nonce,
+            &reto_pkce(&pendiente.verificador),
+        );
+
+        let mut pendientes = self.pendientes.lock().expect("pendientes envenenados");
+        let ahora = Instant::now();
+        pendientes.retain(|_, p| p.expira > ahora);
+        pendientes.insert(estado.clone(), pendiente);
+        Ok(InicioSso { url, estado })
+    }
+
+    async fn completar(&self, estado: &str, codigo: &str) -> Result<Actor, String> {
+        let pendiente = self
+            .pendientes
+            .lock()
+            .expect("pendientes envenenados")
+            .remove(estado)
+            .filter(|p| p.expira > Instant::now())
+            .ok_or_else(|| "Inicio de sesión desconocido o caducado".to_string())?;
+        let identidad = self
+            .proveedor
+            .canjear_codigo(codigo, &pendiente.verificador, &pendiente.nonce)
+            .await?;
+        self.actor_de(identidad).await
+    }
+
+    async fn autenticar_token(&self, token: &str) -> Result<Actor, String> {
+        let identidad = self.proveedor.validar_token(token).await?;
+        self.actor_de(identidad).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reservas_adapters::InMemoryEmpleadoRepository;
+
+    /// Proveedor que acepta el código `bueno` si el verificador corresponde
+    /// al reto de la URL y devuelve siempre la misma identidad
+    struct ProveedorFalso {
+        identidad: IdentidadExterna,
+        reto: Mutex<Option<String>>,
+    }
+
+    #[async_trait]
+    impl ProveedorIdentidad for ProveedorFalso {
+        fn url_autorizacion(&self, estado: &str, _nonce: &str, reto_pkce: &str) -> String {
+            *self.reto.lock().unwrap() = Some(reto_pkce.to_string());
+            format!("https://idp.test/authorize?state={}", estado)
+        }
+
+        async fn canjear_codigo(
+            &self,
+            codigo: &str,
+            verificador_pkce: &str,
+            _nonce: &str,
+        ) -> Result<IdentidadExterna, String> {
+            let reto = self.reto.lock().unwrap().clone();
+            if codigo != "bueno" || reto != Some(reto_pkce(verificador_pkce)) {
+                return Err("invalid_grant".to_string());
+            }
+            Ok(self.identidad.clone())
+        }
+
+        async fn validar_token(&self, token: &str) -> Result<IdentidadExterna, String> {
+            match token {
+                "token-valido" => Ok(self.identidad.clone()),
+                _ => Err("Token no válido".to_string()),
+            }
+        }
+    }
+
+    fn servicio(
+        email: Option<&str>,
+        roles: Vec<Rol>,
+        empleados: Arc<InMemoryEmpleadoRepository>,
+    ) -> SsoServiceImpl<Arc<InMemoryEmpleadoRepository>> {
+        let proveedor = ProveedorFalso {
+            identidad: IdentidadExterna {
+                sujeto: "abc-123".to_string(),
+                email: email.map(str::to_string),
+                email_verificado: true,
+                nombre: Some("Ana García".to_string()),
+                roles,
+            },
+            reto: Mutex::new(None),
+        };
+        SsoServiceImpl::new(Arc::new(proveedor), empleados)
+    }
+
+    #[test]
+    fn test_reto_pkce_s256() {
+        // base64url sin relleno del SHA-256 del verificador
+        assert_eq!(
+            reto_pkce("verificador-de-prueba"),
+            "ZLy4L_A-tM2NHBmAkyi5agdE5oM2WHDN9HoG4thqYZE"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_primer_inicio_crea_el_empleado() {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let service = servicio(Some("Ana@Empresa.com"), vec![], Arc::clone(&empleados));
+
+        let inicio = service.iniciar().await.unwrap();
+        assert!(inicio.url.contains(&inicio.estado));
+        let actor = service.completar(&inicio.estado, "bueno").await.unwrap();
+        assert_eq!(actor.sujeto, "ana@empresa.com");
+        assert_eq!(actor.roles, vec![Rol::Empleado]);
+
+        let creados = empleados.listar().await.unwrap();
+        assert_eq!(creados.len(), 1);
+        assert_eq!(creados[0].nombre, "Ana García");
+        assert_eq!(actor.empleado_id.as_deref(), Some(creados[0].id.as_str()));
+
+        // El estado no se puede reutilizar
+        assert!(service.completar(&inicio.estado, "bueno").await.is_err());
+
+        // Con el token se llega al mismo empleado, sin duplicarlo
+        let actor_api = service.autenticar_token("token-valido").await.unwrap();
+        assert_eq!(actor_api.empleado_id, actor.empleado_id);
+        assert_eq!(empleados.listar().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_empleado_existente_y_roles_del_proveedor() {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let mut ana = Empleado::new(
+            "emp-1".to_string(),
+            "Ana".to_string(),
+            "ana@empresa.com".to_string(),
+        )
+        .unwrap();
+        empleados.guardar(&ana).await.unwrap();
+        let service = servicio(
+            Some("ana@empresa.com"),
+            vec![Rol::Responsable],
+            Arc::clone(&empleados),
+        );
+
+        let actor = service.autenticar_token("token-valido").await.unwrap();
+        assert_eq!(actor.empleado_id.as_deref(), Some("emp-1"));
+        assert_eq!(actor.roles, vec![Rol::Responsable]);
+        assert!(service.autenticar_token("otro").await.is_err());
+
+        // Un empleado desactivado no puede entrar
+        ana.desactivar();
+        empleados.actualizar(&ana).await.unwrap();
+        assert!(service.autenticar_token("token-valido").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_email_sin_verificar_no_entra_como_empleado_existente() {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let ana = Empleado::new(
+            "emp-1".to_string(),
+            "Ana".to_string(),
+            "ana@empresa.com".to_string(),
+        )
+        .unwrap();
+        empleados.guardar(&ana).await.unwrap();
+        let sin_verificar = |email: &str| {
+            let proveedor = ProveedorFalso {
+                identidad: IdentidadExterna {
+                    sujeto: "abc-123".to_string(),
+                    email: Some(email.to_string()),
+                    email_verificado: false,
+                    nombre: None,
+                    roles: vec![],
+                },
+                reto: Mutex::new(None),
+            };
+            SsoServiceImpl::new(Arc::new(proveedor), Arc::clone(&empleados))
+        };
+
+        let error = sin_verificar("ana@empresa.com")
+            .autenticar_token("token-valido")
+            .await
+            .unwrap_err();
+        assert!(error.contains("verificado"));
+
+        // Un email nuevo sí se puede dar de alta
+        let actor = sin_verificar("luis@empresa.com")
+            .autenticar_token("token-valido")
+            .await
+            .unwrap();
+        assert_ne!(actor.empleado_id.as_deref(), Some("emp-1"));
+        assert_eq!(empleados.listar().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rechaza_codigo_estado_o_email_invalidos() {
+        let empleados = Arc::new(InMemoryEmpleadoRepository::new());
+        let service = servicio(Some("ana@empresa.com"), vec![], Arc::clone(&empleados));
+        let inicio = service.iniciar().await.unwrap();
+        assert!(service.completar("desconocido", "bueno").await.is_err());
+        assert!(service.completar(&inicio.estado, "malo").await.is_err());
+
+        let sin_email = servicio(None, vec![], Arc::clone(&empleados));
+        let error = sin_email
+            .autenticar_token("token-valido")
+            .await
+            .unwrap_err();
+        assert!(error.contains("email"));
+        assert!(empleados.listar().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,720 @@
+// Webhooks salientes
+//
+// Cada cambio en una reserva se envía por POST a los webhooks suscritos a ese
+// evento. La entrega va firmada con HMAC-SHA256, se reintenta con espera
+// exponencial si el receptor no responde 2xx y queda en el registro de
+// entregas, desde donde se puede reenviar a mano.
+
+use crate::autorizacion::Autorizador;
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reservas_domain::{Accion, Actor, EstadoReserva, Reserva};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::out::auditoria::RegistroAuditoria;
+use reservas_ports::out::notificador::{CambioReserva, NotificadorCambios};
+use reservas_ports::out::webhooks::{
+    ClienteWebhook, Entrega, EstadoEntrega, IntentoEntrega, Webhook, WebhookRepository,
+};
+use reservas_ports::r#in::webhook_service::{NuevoWebhook, WebhookService};
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::OwnedMutexGuard;
+use uuid::Uuid;
+
+/// Intentos por defecto de cada entrega, contando el primero
+pub const INTENTOS_ENTREGA: u32 = 5;
+/// Espera antes del primer reintento; se duplica en cada uno (10s, 20s, 40s...)
+pub const ESPERA_REINTENTO: Duration = Duration::from_secs(10);
+
+/// Cabeceras de cada entrega
+pub const CABECERA_EVENTO: &str = "X-Reservas-Evento";
+pub const CABECERA_ENTREGA: &str = "X-Reservas-Entrega";
+pub const CABECERA_FIRMA: &str = "X-Reservas-Firma";
+
+/// Valor de `X-Reservas-Firma`: `t=<marca unix>,v1=<hex>`, con el HMAC-SHA256
+/// de `"<marca>.<cuerpo>"`. La marca permite al receptor rechazar entregas
+/// antiguas reenviadas por un tercero.
+pub fn firmar(secreto: &str, marca: i64, cuerpo: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secreto.as_bytes()).expect("HMAC admite cualquier clave");
+    mac.update(format!("{}.{}", marca, cuerpo).as_bytes());
+    let firma: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("t={},v1={}", marca, firma)
+}
+
+fn estado_a_str(estado: &EstadoReserva) -> &'static str {
+    match estado {
+        EstadoReserva::Pendiente => "pendiente",
+        EstadoReserva::Confirmada => "confirmada",
+        EstadoReserva::Cancelada => "cancelada",
+    }
+}
+
+fn datos_reserva(reserva: &Reserva) -> serde_json::Value {
+    json!({
+        "id": reserva.id,
+        "empleado_id": reserva.empleado_id,
+        "slot_inicio": reserva.slot.inicio,
+        "slot_fin": reserva.slot.fin(),
+        "descripcion": reserva.descripcion,
+        "estado": estado_a_str(&reserva.estado),
+        "sala_id": reserva.sala_id,
+    })
+}
+
+/// Un cerrojo por entrega, para que el reenvío manual y los reintentos
+/// automáticos no envíen ni guarden la misma entrega a la vez
+#[derive(Default)]
+struct Turnos(Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>);
+
+impl Turnos {
+    async fn tomar(&self, entrega_id: &str) -> OwnedMutexGuard<()> {
+        let cerrojo = Arc::clone(
+            self.0
+                .lock()
+                .unwrap()
+                .entry(entrega_id.to_string())
+                .or_default(),
+        );
+        cerrojo.lock_owned().await
+    }
+
+    /// Devuelve el turno y olvida el cerrojo si nadie más lo espera
+    fn soltar(&self, entrega_id: &str, turno: OwnedMutexGuard<()>) {
+        let mut cerrojos = self.0.lock().unwrap();
+        drop(turno);
+        if cerrojos
+            .get(entrega_id)
+            .is_some_and(|cerrojo| Arc::strong_count(cerrojo) == 1)
+        {
+            cerrojos.remove(entrega_id);
+        }
+    }
+}
+
+/// Lo que necesitan las tareas de entrega, que sobreviven a la petición
+struct Repartidor {
+    repositorio: Arc<dyn WebhookRepository>,
+    cliente: Arc<dyn ClienteWebhook>,
+    intentos: u32,
+    espera: Duration,
+    turnos: Turnos,
+}
+
+impl Repartidor {
+    /// Un intento de envío, con la firma calculada en el momento
+    async fn intentar(&self, webhook: &Webhook, entrega: &mut Entrega) {
+        let cabeceras = [
+            (CABECERA_EVENTO.to_string(), entrega.evento.evento()),
+            (CABECERA_ENTREGA.to_string(), entrega.id.clone()),
+            (
+                CABECERA_FIRMA.to_string(),
+                firmar(&webhook.secreto, Utc::now().timestamp(), &entrega.cuerpo),
+            ),
+        ];
+        let resultado = self
+            .cliente
+            .enviar(&webhook.url, &cabeceras, &entrega.cuerpo)
+            .await;
+        let intento = match resultado {
+            Ok(codigo) if (200..300).contains(&codigo) => IntentoEntrega {
+                fecha: Utc::now(),
+                codigo: Some(codigo),
+                error: None,
+            },
+            Ok(codigo) => IntentoEntrega {
+                fecha: Utc::now(),
+                codigo: Some(codigo),
+                error: Some(format!("El receptor respondió {}", codigo)),
+            },
+            Err(error) => IntentoEntrega {
+                fecha: Utc::now(),
+                codigo: None,
+                error: Some(error),
+            },
+        };
+        entrega.estado = if intento.error.is_none() {
+            EstadoEntrega::Entregada
+        } else {
+            EstadoEntrega::Fallida
+        };
+        entrega.intentos.push(intento);
+    }
+
+    /// Envía con reintentos hasta que el receptor acepta o se agotan
+    async fn entregar(&self, webhook: Webhook, mut entrega: Entrega) {
+        for numero in 1..=self.intentos {
+            if numero > 1 {
+                tokio::time::sleep(self.espera * 2u32.pow(numero - 2)).await;
+                // Si lo han borrado mientras tanto, no se resucita su registro
+                if !matches!(self.repositorio.obtener(&webhook.id).await, Ok(Some(_))) {
+                    return;
+                }
+            }
+            let turno = self.turnos.tomar(&entrega.id).await;
+            let terminada = self.reintentar(&webhook, &mut entrega, numero).await;
+            self.turnos.soltar(&entrega.id, turno);
+            if terminada {
+                return;
+            }
+        }
+    }
+
+    /// El intento `numero` de la entrega; hay que tener su turno. Devuelve
+    /// si ya no hace falta seguir intentando.
+    async fn reintentar(&self, webhook: &Webhook, entrega: &mut Entrega, numero: u32) -> bool {
+        // Un reenvío manual puede haberla entregado o haber añadido
+        // intentos: se sigue desde lo guardado, no desde la copia propia
+        match self.repositorio.obtener_entrega(&entrega.id).await {
+            Ok(Some(guardada)) => *entrega = guardada,
+            Ok(None) => return true,
+            Err(_) => {}
+        }
+        if entrega.estado == EstadoEntrega::Entregada {
+            return true;
+        }
+        self.intentar(webhook, entrega).await;
+        let entregada = entrega.estado == EstadoEntrega::Entregada;
+        if !entregada && numero < self.intentos {
+            entrega.estado = EstadoEntrega::Pendiente;
+        }
+        // Sin registro no se pierde la entrega: se sigue intentando
+        let _ = self.repositorio.guardar_entrega(entrega).await;
+        entregada
+    }
+
+    async fn repartir(self: Arc<Self>, cambio: CambioReserva) {
+        let Ok(webhooks) = self.repositorio.listar().await else {
+            return;
+        };
+        for webhook in webhooks.into_iter().filter(|w| w.acepta(cambio.tipo)) {
+            let id = Uuid::new_v4().to_string();
+            let cuerpo = json!({
+                "id": id,
+                "evento": cambio.tipo.evento(),
+                "fecha": Utc::now(),
+                "reserva": datos_reserva(&cambio.reserva),
+                "slot_anterior": cambio.slot_anterior.as_ref().map(|slot| slot.inicio),
+                "empleado_anterior": cambio.empleado_anterior,
+            });
+            let entrega = Entrega {
+                id,
+                webhook_id: webhook.id.clone(),
+                evento: cambio.tipo,
+                cuerpo: cuerpo.to_string(),
+                estado: EstadoEntrega::Pendiente,
+                intentos: Vec::new(),
+                creada: Utc::now(),
+            };
+            if self.repositorio.guardar_entrega(&entrega).await.is_err() {
+                continue;
+            }
+            // Cada webhook por su lado: uno lento no retrasa a los demás
+            let repartidor = Arc::clone(&self);
+            tokio::spawn(async move { repartidor.entregar(webhook, entrega).await });
+        }
+    }
+}
+
+pub struct WebhookServiceImpl {
+    repartidor: Arc<Repartidor>,
+    autorizador: Autorizador,
+}
+
+impl WebhookServiceImpl {
+    pub fn new(repositorio: Arc<dyn WebhookRepository>, cliente: Arc<dyn ClienteWebhook>) -> Self {
+        Self {
+            repartidor: Arc::new(Repartidor {
+                repositorio,
+                cliente,
+                intentos: INTENTOS_ENTREGA,
+                espera: ESPERA_REINTENTO,
+                turnos: Turnos::default(),
+            }),
+            autorizador: Autorizador::default(),
+        }
+    }
+
+    /// Intentos de cada entrega (al menos uno) y espera antes del primer reintento
+    pub fn con_reintentos(mut self, intentos: u32, espera: Duration) -> Self {
+        let repartidor = Arc::get_mut(&mut self.repartidor).expect("aún sin entregas en curso");
+        repartidor.intentos = intentos.max(1);
+        repartidor.espera = espera;
+        self
+    }
+
+    /// Registra en `auditoria` las operaciones denegadas
+    pub fn con_auditoria(mut self, auditoria: Arc<dyn RegistroAuditoria>) -> Self {
+        self.autorizador = Autorizador::new(auditoria);
+        self
+    }
+
+    async fn exigir_admin(&self, actor: &Actor, recurso: String) -> Result<(), ErrorServicio> {
+        self.autorizador
+            .exigir(actor, Accion::Administrar, None, recurso)
+            .await
+    }
+
+    /// Un intento sobre la entrega tal como está guardada; hay que tener su turno
+    async fn reenviar_en_turno(
+        &self,
+        webhook: &Webhook,
+        entrega_id: &str,
+    ) -> Result<Option<Entrega>, ErrorServicio> {
+        let repositorio = &self.repartidor.repositorio;
+        let Some(mut entrega) = repositorio
+            .obtener_entrega(entrega_id)
+            .await?
+            .filter(|e| e.webhook_id == webhook.id)
+        else {
+            return Ok(None);
+        };
+
+        let pendiente = entrega.estado == EstadoEntrega::Pendiente;
+        self.repartidor.intentar(webhook, &mut entrega).await;
+        // Si fallaba con reintentos por delante, los reintentos siguen
+        if pendiente && entrega.estado == EstadoEntrega::Fallida {
+            entrega.estado = EstadoEntrega::Pendiente;
+        }
+        repositorio.guardar_entrega(&entrega).await?;
+        Ok(Some(entrega))
+    }
+}
+
+/// Secreto aleatorio de 244 bits en hexadecimal
+fn secreto_aleatorio() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn validar_url(url: &str) -> Result<(), String> {
+    let resto = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| format!("La URL {} debe empezar por http:// o https://", url))?;
+    if resto.is_empty() || resto.starts_with('/') || url.chars().any(char::is_whitespace) {
+        return Err(format!("La URL {} no es válida", url));
+    }
+    Ok(())
+}
+
+impl NotificadorCambios for WebhookServiceImpl {
+    /// Las entregas se hacen en segundo plano; el cambio ya está guardado
+    fn notificar(&self, cambio: CambioReserva) {
+        tokio::spawn(Arc::clone(&self.repartidor).repartir(cambio));
+    }
+}
+
+#[async_trait]
+impl WebhookService for WebhookServiceImpl {
+    async fn crear(&self, actor: &Actor, nuevo: NuevoWebhook) -> Result<Webhook, ErrorServicio> {
+        self.exigir_admin(actor, "webhooks".to_string()).await?;
+        let url = nuevo.url.trim().to_string();
+        validar_url(&url)?;
+        let secreto = match nuevo.secreto {
+            Some(secreto) if secreto.len() < 16 => {
+                return Err("El secreto debe tener al menos 16 caracteres".into())
+            }
+            Some(secreto) => secreto,
+            None => secreto_aleatorio(),
+        };
+        let mut eventos = Vec::new();
+        for tipo in nuevo.eventos {
+            if !eventos.contains(&tipo) {
+                eventos.push(tipo);
+            }
+        }
+
+        let webhook = Webhook {
+            id: Uuid::new_v4().to_string(),
+            url,
+            eventos,
+            secreto,
+            creado: Utc::now(),
+        };
+        self.repartidor.repositorio.guardar(&webhook).await?;
+        Ok(webhook)
+    }
+
+    async fn listar(&self, actor: &Actor) -> Result<Vec<Webhook>, ErrorServicio> {
+        self.exigir_admin(actor, "webhooks".to_string()).await?;
+        Ok(self.repartidor.repositorio.listar().await?)
+    }
+
+    async fn obtener(&self, actor: &Actor, id: &str) -> Result<Option<Webhook>, ErrorServicio> {
+        self.exigir_admin(actor, format!("webhook:{}", id)).await?;
+        Ok(self.repartidor.repositorio.obtener(id).await?)
+    }
+
+    async fn eliminar(&self, actor: &Actor, id: &str) -> Result<bool, ErrorServicio> {
+        self.exigir_admin(actor, format!("webhook:{}", id)).await?;
+        Ok(self.repartidor.repositorio.eliminar(id).await?)
+    }
+
+    async fn listar_entregas(
+        &self,
+        actor: &Actor,
+        webhook_id: &str,
+    ) -> Result<Option<Vec<Entrega>>, ErrorServicio> {
+        self.exigir_admin(actor, format!("webhook:{}", webhook_id))
+            .await?;
+        let repositorio = &self.repartidor.repositorio;
+        if repositorio.obtener(webhook_id).await?.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(repositorio.listar_entregas(webhook_id).await?))
+    }
+
+    async fn reenviar(
+        &self,
+        actor: &Actor,
+        webhook_id: &str,
+        entrega_id: &str,
+    ) -> Result<Option<Entrega>, ErrorServicio> {
+        self.exigir_admin(actor, format!("webhook:{}", webhook_id))
+            .await?;
+        let repositorio = &self.repartidor.repositorio;
+        let Some(webhook) = repositorio.obtener(webhook_id).await? else {
+            return Ok(None);
+        };
+        let turnos = &self.repartidor.turnos;
+        let turno = turnos.tomar(entrega_id).await;
+        let resultado = self.reenviar_en_turno(&webhook, entrega_id).await;
+        turnos.soltar(entrega_id, turno);
+        resultado
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use chrono::{Datelike, Duration as Dias};
+    use reservas_adapters::{ClienteWebhookHttp, InMemoryWebhookRepository};
+    use reservas_domain::{Rol, Slot};
+    use reservas_ports::out::notificador::TipoCambio;
+    use std::sync::Mutex;
+
+    /// Petición recibida: cabeceras de interés y cuerpo
+    #[derive(Debug, Clone)]
+    struct Recibida {
+        evento: String,
+        entrega: String,
+        firma: String,
+        cuerpo: String,
+    }
+
+    /// Receptor HTTP local que responde con los códigos de `respuestas` en
+    /// orden (el último se repite) y guarda lo recibido
+    struct Receptor {
+        url: String,
+        recibidas: Arc<Mutex<Vec<Recibida>>>,
+        respuestas: Arc<Mutex<Vec<u16>>>,
+    }
+
+    async fn arrancar_receptor(respuestas: Vec<u16>) -> Receptor {
+        let escucha = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", escucha.local_addr().unwrap());
+        let recibidas = Arc::new(Mutex::new(Vec::new()));
+        let respuestas = Arc::new(Mutex::new(respuestas));
+        let (r, c) = (Arc::clone(&recibidas), Arc::clone(&respuestas));
+        let app = Router::new().route(
+            "/hook",
+            post(move |cabeceras: HeaderMap, cuerpo: String| async move {
+                let cabecera = |nombre: &str| {
+                    cabeceras
+                        .get(nombre)
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                r.lock().unwrap().push(Recibida {
+                    evento: cabecera(CABECERA_EVENTO),
+                    entrega: cabecera(CABECERA_ENTREGA),
+                    firma: cabecera(CABECERA_FIRMA),
+                    cuerpo,
+                });
+                let mut codigos = c.lock().unwrap();
+                let codigo = if codigos.len() > 1 {
+                    codigos.remove(0)
+                } else {
+                    codigos[0]
+                };
+                StatusCode::from_u16(codigo).unwrap()
+            }),
+        );
+        tokio::spawn(async move { axum::serve(escucha, app).await.unwrap() });
+        Receptor {
+            url,
+            recibidas,
+            respuestas,
+        }
+    }
+
+    fn servicio(intentos: u32) -> (WebhookServiceImpl, Arc<InMemoryWebhookRepository>) {
+        let repositorio = Arc::new(InMemoryWebhookRepository::new());
+        let servicio = WebhookServiceImpl::new(
+            Arc::clone(&repositorio) as Arc<dyn WebhookRepository>,
+            Arc::new(ClienteWebhookHttp::new()),
+        )
+        .con_reintentos(intentos, Duration::from_millis(10));
+        (servicio, repositorio)
+    }
+
+    fn cambio(tipo: TipoCambio) -> CambioReserva {
+        let manyana = Utc::now() + Dias::days(1);
+        let slot =
+            Slot::from_date_and_hour(manyana.year(), manyana.month(), manyana.day(), 10).unwrap();
+        CambioReserva {
+            tipo,
+            reserva: Reserva::new(
+                "res-1".to_string(),
+                "emp-001".to_string(),
+                slot,
+                "Revisión".to_string(),
+            )
+            .unwrap(),
+            slot_anterior: None,
+            empleado_anterior: None,
+        }
+    }
+
+    /// Espera a que la única entrega del webhook deje de estar pendiente
+    async fn entrega_terminada(
+        repositorio: &InMemoryWebhookRepository,
+        webhook_id: &str,
+    ) -> Entrega {
+        for _ in 0..200 {
+            let entregas = repositorio.listar_entregas(webhook_id).await.unwrap();
+            if let [entrega] = entregas.as_slice() {
+                if entrega.estado != EstadoEntrega::Pendiente {
+                    return entrega.clone();
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("la entrega no ha terminado");
+    }
+
+    #[test]
+    fn test_firma_hmac_sha256() {
+        assert_eq!(
+            firmar("clave", 1_700_000_000, r#"{"a":1}"#),
+            "t=1700000000,v1=cb2b2ad44c902f4477a88a59965059d44d06ff1664d70feded67d5bd3b193059"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_entrega_firmada_con_reintentos() {
+        let receptor = arrancar_receptor(vec![500, 503, 204]).await;
+        let (servicio, repositorio) = servicio(3);
+        let webhook = servicio
+            .crear(
+                &Actor::sistema(),
+                NuevoWebhook {
+                    url: receptor.url.clone(),
+                    eventos: vec![TipoCambio::Creada, TipoCambio::Cancelada],
+                    secreto: Some("un-secreto-bastante-largo".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+
+        // Confirmada no interesa a este webhook
+        servicio.notificar(cambio(TipoCambio::Confirmada));
+        servicio.notificar(cambio(TipoCambio::Creada));
+
+        let entrega = entrega_terminada(&repositorio, &webhook.id).await;
+        assert_eq!(entrega.estado, EstadoEntrega::Entregada);
+        assert_eq!(entrega.evento, TipoCambio::Creada);
+        let codigos: Vec<Option<u16>> = entrega.intentos.iter().map(|i| i.codigo).collect();
+        assert_eq!(codigos, [Some(500), Some(503), Some(204)]);
+
+        let recibidas = receptor.recibidas.lock().unwrap().clone();
+        assert_eq!(recibidas.len(), 3);
+        for recibida in &recibidas {
+            assert_eq!(recibida.evento, "reserva.creada");
+            assert_eq!(recibida.entrega, entrega.id);
+            assert_eq!(recibida.cuerpo, entrega.cuerpo);
+            // El receptor comprueba la firma con el secreto compartido
+            let marca: i64 = recibida.firma[2..recibida.firma.find(',').unwrap()]
+                .parse()
+                .unwrap();
+            assert_eq!(
+                recibida.firma,
+                firmar("un-secreto-bastante-largo", marca, &recibida.cuerpo)
+            );
+        }
+        let cuerpo: serde_json::Value = serde_json::from_str(&entrega.cuerpo).unwrap();
+        assert_eq!(cuerpo["evento"], "reserva.creada");
+        assert_eq!(cuerpo["id"], entrega.id.as_str());
+        assert_eq!(cuerpo["reserva"]["id"], "res-1");
+        assert_eq!(cuerpo["reserva"]["estado"], "pendiente");
+    }
+
+    #[tokio::test]
+    async fn test_entrega_fallida_y_reenvio_manual() {
+        let receptor = arrancar_receptor(vec![500]).await;
+        let (servicio, repositorio) = servicio(2);
+        let admin = Actor::sistema();
+        let webhook = servicio
+            .crear(
+                &admin,
+                NuevoWebhook {
+                    url: receptor.url.clone(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(webhook.secreto.len(), 64);
+
+        servicio.notificar(cambio(TipoCambio::Eliminada));
+        let entrega = entrega_terminada(&repositorio, &webhook.id).await;
+        assert_eq!(entrega.estado, EstadoEntrega::Fallida);
+        assert_eq!(entrega.intentos.len(), 2);
+
+        // El receptor se recupera y se reenvía a mano
+        *receptor.respuestas.lock().unwrap() = vec![200];
+        let reenviada = servicio
+            .reenviar(&admin, &webhook.id, &entrega.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reenviada.estado, EstadoEntrega::Entregada);
+        assert_eq!(reenviada.intentos.len(), 3);
+        assert_eq!(
+            servicio
+                .listar_entregas(&admin, &webhook.id)
+                .await
+                .unwrap()
+                .unwrap(),
+            vec![reenviada]
+        );
+        let recibidas = receptor.recibidas.lock().unwrap().clone();
+        assert_eq!(recibidas.len(), 3);
+        assert_eq!(recibidas[2].cuerpo, recibidas[0].cuerpo);
+
+        // La entrega solo se encuentra desde su webhook
+        assert_eq!(
+            servicio
+                .reenviar(&admin, "otro", &entrega.id)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            servicio.listar_entregas(&admin, "otro").await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reenvio_manual_detiene_los_reintentos() {
+        let receptor = arrancar_receptor(vec![500, 200]).await;
+        let (servicio, repositorio) = servicio(3);
+        let servicio = servicio.con_reintentos(3, Duration::from_millis(300));
+        let admin = Actor::sistema();
+        let webhook = servicio
+            .crear(
+                &admin,
+                NuevoWebhook {
+                    url: receptor.url.clone(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        servicio.notificar(cambio(TipoCambio::Creada));
+        let mut entrega = None;
+        for _ in 0..100 {
+            if let Some(e) = repositorio
+                .listar_entregas(&webhook.id)
+                .await
+                .unwrap()
+                .pop()
+            {
+                if !e.intentos.is_empty() {
+                    entrega = Some(e);
+                    break;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let entrega = entrega.expect("sin primer intento");
+        assert_eq!(entrega.estado, EstadoEntrega::Pendiente);
+
+        // Se reenvía antes del primer reintento automático
+        let reenviada = servicio
+            .reenviar(&admin, &webhook.id, &entrega.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reenviada.estado, EstadoEntrega::Entregada);
+        assert_eq!(reenviada.intentos.len(), 2);
+
+        // El reintento ve que ya está entregada: ni otro envío ni pisa el registro
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(receptor.recibidas.lock().unwrap().len(), 2);
+        assert_eq!(
+            repositorio.obtener_entrega(&entrega.id).await.unwrap(),
+            Some(reenviada)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_solo_administradores_y_datos_validos() {
+        let (servicio, _) = servicio(1);
+        let empleado = Actor::new("ana", Some("emp-001".to_string()), vec![Rol::Empleado]);
+        let nuevo = NuevoWebhook {
+            url: "https://rrhh.empresa.com/hooks/reservas".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            servicio.crear(&empleado, nuevo.clone()).await,
+            Err(ErrorServicio::Denegado(_))
+        ));
+        assert!(matches!(
+            servicio.listar(&empleado).await,
+            Err(ErrorServicio::Denegado(_))
+        ));
+
+        let admin = Actor::sistema();
+        for url in [
+            "ftp://rrhh.empresa.com",
+            "https://",
+            "http:///hook",
+            "https://a b",
+        ] {
+            let invalido = NuevoWebhook {
+                url: url.to_string(),
+                ..Default::default()
+            };
+            assert!(servicio.crear(&admin, invalido).await.is_err(), "{}", url);
+        }
+        let secreto_corto = NuevoWebhook {
+            secreto: Some("corto".to_string()),
+            ..nuevo.clone()
+        };
+        assert!(servicio.crear(&admin, secreto_corto).await.is_err());
+
+        let webhook = servicio.crear(&admin, nuevo).await.unwrap();
+        assert_eq!(
+            servicio.listar(&admin).await.unwrap(),
+            vec![webhook.clone()]
+        );
+        assert!(servicio.eliminar(&admin, &webhook.id).await.unwrap());
+        assert!(!servicio.eliminar(&admin, &webhook.id).await.unwrap());
+    }
+}
@@ -0,0 +1,85 @@
+// Autenticación HTTP Basic con las cuentas locales
+//
+// Los clientes de calendario nativos solo saben enviar usuario y contraseña,
+// así que se comprueban contra las mismas cuentas que la interfaz web.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reservas_ports::r#in::cuenta_service::CuentaService;
+use std::sync::Arc;
+
+/// Realm que muestran los clientes al pedir la contraseña
+const REALM: &str = "Basic realm=\"Reservas\", charset=\"UTF-8\"";
+
+/// Usuario y contraseña de la cabecera `Authorization: Basic`
+fn credenciales(cabeceras: &HeaderMap) -> Option<(String, String)> {
+    let valor = cabeceras.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (esquema, codificado) = valor.split_once(' ')?;
+    if !esquema.eq_ignore_ascii_case("basic") {
+        return None;
+    }
+    let decodificado = String::from_utf8(STANDARD.decode(codificado.trim()).ok()?).ok()?;
+    let (usuario, password) = decodificado.split_once(':')?;
+    Some((usuario.to_string(), password.to_string()))
+}
+
+fn no_autenticado() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, HeaderValue::from_static(REALM))],
+        "Se requieren credenciales",
+    )
+        .into_response()
+}
+
+/// Middleware: 401 con el reto Basic si no hay credenciales válidas.
+/// Deja el `Actor` de la cuenta en las extensiones de la petición.
+pub(crate) async fn autenticar(
+    State(cuentas): State<Arc<dyn CuentaService>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some((usuario, password)) = credenciales(request.headers()) else {
+        return no_autenticado();
+    };
+    match cuentas.autenticar(&usuario, &password).await {
+        Ok(Some(cuenta)) => {
+            request.extensions_mut().insert(cuenta.actor());
+            next.run(request).await
+        }
+        Ok(None) => {
+            tracing::debug!("CalDAV: credenciales rechazadas para {}", usuario);
+            no_autenticado()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lee_credenciales_basic() {
+        let mut cabeceras = HeaderMap::new();
+        assert_eq!(credenciales(&cabeceras), None);
+
+        // "juan:se:creta"
+        cabeceras.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Basic anVhbjpzZTpjcmV0YQ=="),
+        );
+        assert_eq!(
+            credenciales(&cabeceras),
+            Some(("juan".to_string(), "se:creta".to_string()))
+        );
+
+        cabeceras.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer x"));
+        assert_eq!(credenciales(&cabeceras), None);
+    }
+}
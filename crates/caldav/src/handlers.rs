@@ -0,0 +1,718 @@
+// Métodos WebDAV/CalDAV sobre los recursos
+//
+// Todo pasa por los puertos de entrada: leer un calendario exige el mismo
+// permiso que suscribirse a su feed, y crear, mover o cancelar reservas lo
+// decide `ReservaService`, con sus reglas de slots, conflictos y permisos.
+// Borrar un evento cancela la reserva, para conservar el historial.
+
+use crate::propiedades::{self, Contexto, Datos, Nodo, Peticion, CONTENT_TYPE_XML};
+use crate::recursos::{self, Nombres, Recurso};
+use crate::xml::{self, CALDAV, DAV};
+use crate::ConfigCalDav;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+    Extension,
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use reservas_domain::{Accion, Actor, Empleado, Reserva, Rol, Slot};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::r#in::calendario_service::{EventoCalendario, FeedCalendario};
+use reservas_ports::r#in::empleado_service::EmpleadoService;
+use reservas_ports::r#in::importacion_service::EventoExterno;
+use reservas_ports::r#in::reserva_service::{CambiosReserva, ReservaService};
+use reservas_ports::r#in::sala_service::SalaService;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Métodos que admite cualquier recurso
+const METODOS: &str = "OPTIONS, GET, HEAD, PUT, DELETE, PROPFIND, REPORT";
+
+/// Descripción de las reservas creadas desde un evento sin título
+const DESCRIPCION_POR_DEFECTO: &str = "Evento de calendario";
+
+pub(crate) struct Servicios {
+    pub config: ConfigCalDav,
+    pub empleados: Arc<dyn EmpleadoService>,
+    pub reservas: Arc<dyn ReservaService>,
+    pub salas: Arc<dyn SalaService>,
+    pub nombres: RwLock<Nombres>,
+}
+
+/// Cómo termina una petición que no sale bien
+enum Fallo {
+    NoEncontrado,
+    NoPermitido,
+    Prohibido(String),
+    Conflicto(String),
+    /// Precondición WebDAV/CalDAV con su elemento (RFC 4791, sección 1.3)
+    Precondicion(&'static str, &'static str, String),
+    PrecondicionFallida,
+    PeticionInvalida(String),
+    Interno(String),
+}
+
+impl From<String> for Fallo {
+    fn from(error: String) -> Self {
+        Fallo::Interno(error)
+    }
+}
+
+impl From<ErrorServicio> for Fallo {
+    fn from(error: ErrorServicio) -> Self {
+        match error {
+            ErrorServicio::Denegado(detalle) => Fallo::Prohibido(detalle),
+            ErrorServicio::Otro(detalle) => Fallo::Conflicto(detalle),
+        }
+    }
+}
+
+impl IntoResponse for Fallo {
+    fn into_response(self) -> Response {
+        match self {
+            Fallo::NoEncontrado => (StatusCode::NOT_FOUND, "Recurso no encontrado").into_response(),
+            Fallo::NoPermitido => (
+                StatusCode::METHOD_NOT_ALLOWED,
+                [(header::ALLOW, METODOS)],
+                "Método no permitido en este recurso",
+            )
+                .into_response(),
+            Fallo::Prohibido(detalle) => (StatusCode::FORBIDDEN, detalle).into_response(),
+            Fallo::Conflicto(detalle) => (StatusCode::CONFLICT, detalle).into_response(),
+            Fallo::Precondicion(espacio, precondicion, detalle) => (
+                StatusCode::FORBIDDEN,
+                [(header::CONTENT_TYPE, CONTENT_TYPE_XML)],
+                propiedades::error(espacio, precondicion, &detalle),
+            )
+                .into_response(),
+            Fallo::PrecondicionFallida => StatusCode::PRECONDITION_FAILED.into_response(),
+            Fallo::PeticionInvalida(detalle) => (StatusCode::BAD_REQUEST, detalle).into_response(),
+            Fallo::Interno(detalle) => {
+                tracing::error!("CalDAV: {}", detalle);
+                (StatusCode::INTERNAL_SERVER_ERROR, detalle).into_response()
+            }
+        }
+    }
+}
+
+/// `/.well-known/caldav` (RFC 6764): los clientes empiezan aquí el descubrimiento
+pub(crate) async fn descubrir(State(servicios): State<Arc<Servicios>>) -> Response {
+    Redirect::permanent(&Recurso::Raiz.href(&servicios.config.prefijo)).into_response()
+}
+
+/// Punto de entrada de todas las rutas: axum no enruta métodos WebDAV
+pub(crate) async fn despachar(
+    State(servicios): State<Arc<Servicios>>,
+    Extension(actor): Extension<Actor>,
+    metodo: Method,
+    uri: Uri,
+    cabeceras: HeaderMap,
+    cuerpo: String,
+) -> Response {
+    let Some(recurso) = Recurso::desde_ruta(&servicios.config.prefijo, uri.path()) else {
+        return Fallo::NoEncontrado.into_response();
+    };
+    let operacion = Operacion {
+        servicios: &servicios,
+        actor: &actor,
+        recurso,
+        cabeceras: &cabeceras,
+    };
+    let resultado = match metodo.as_str() {
+        "OPTIONS" => Ok(opciones()),
+        "PROPFIND" => operacion.propfind(&cuerpo).await,
+        "REPORT" => operacion.report(&cuerpo).await,
+        "GET" => operacion.get(false).await,
+        "HEAD" => operacion.get(true).await,
+        "PUT" => operacion.put(&cuerpo).await,
+        "DELETE" => operacion.delete().await,
+        _ => Err(Fallo::NoPermitido),
+    };
+    resultado.unwrap_or_else(IntoResponse::into_response)
+}
+
+fn opciones() -> Response {
+    (
+        StatusCode::OK,
+        [
+            (header::ALLOW, METODOS),
+            (
+                header::HeaderName::from_static("dav"),
+                "1, 3, calendar-access",
+            ),
+        ],
+    )
+        .into_response()
+}
+
+fn multiestado(respuestas: &[String]) -> Response {
+    (
+        StatusCode::MULTI_STATUS,
+        [(header::CONTENT_TYPE, CONTENT_TYPE_XML)],
+        propiedades::multiestado(respuestas),
+    )
+        .into_response()
+}
+
+/// Fecha UTC de un atributo `start`/`end` de time-range
+fn fecha_rango(valor: &str) -> Result<DateTime<Utc>, Fallo> {
+    NaiveDateTime::parse_from_str(valor, "%Y%m%dT%H%M%SZ")
+        .map(|fecha| fecha.and_utc())
+        .map_err(|_| Fallo::PeticionInvalida(format!("time-range no válido: {}", valor)))
+}
+
+/// Intervalo `[desde, hasta)` en UTC
+type Rango = (DateTime<Utc>, DateTime<Utc>);
+
+/// Intervalo de un `<c:time-range>`; sin extremo, abierto por ese lado
+fn rango(elemento: &xml::Elemento) -> Result<Option<Rango>, Fallo> {
+    let Some(rango) = elemento.buscar(CALDAV, "time-range") else {
+        return Ok(None);
+    };
+    let desde = match rango.atributo("start") {
+        Some(valor) => fecha_rango(valor)?,
+        None => DateTime::<Utc>::MIN_UTC,
+    };
+    let hasta = match rango.atributo("end") {
+        Some(valor) => fecha_rango(valor)?,
+        None => DateTime::<Utc>::MAX_UTC,
+    };
+    Ok(Some((desde, hasta)))
+}
+
+/// Slot de un evento que se guarda como reserva: tiene que caber en uno
+fn slot_de(evento: &EventoExterno) -> Result<Slot, Fallo> {
+    let invalido = |detalle: &str| {
+        Fallo::Precondicion(
+            CALDAV,
+            "valid-calendar-object-resource",
+            detalle.to_string(),
+        )
+    };
+    if evento.periodico {
+        return Err(invalido("Los eventos periódicos no se admiten"));
+    }
+    if evento.dia_completo {
+        return Err(invalido("Los eventos de día completo no se admiten"));
+    }
+    let slot = Slot::new(evento.inicio);
+    if evento.fin <= evento.inicio || evento.fin > slot.fin() {
+        return Err(invalido(
+            "El evento debe caber en un slot de una hora (de hh:00 a hh+1:00)",
+        ));
+    }
+    Ok(slot)
+}
+
+/// Comprueba If-Match / If-None-Match contra el ETag actual
+fn precondiciones(cabeceras: &HeaderMap, actual: Option<&Reserva>) -> Result<(), Fallo> {
+    let valor = |nombre| {
+        cabeceras
+            .get(nombre)
+            .and_then(|v: &HeaderValue| v.to_str().ok())
+    };
+    let coincide = |lista: &str| {
+        actual.is_some_and(|reserva| {
+            let etag = recursos::etag(reserva);
+            lista.split(',').any(|e| {
+                let e = e.trim();
+                e == "*" || e.trim_start_matches("W/") == etag
+            })
+        })
+    };
+    if let Some(lista) = valor(header::IF_MATCH) {
+        if !coincide(lista) {
+            return Err(Fallo::PrecondicionFallida);
+        }
+    }
+    if let Some(lista) = valor(header::IF_NONE_MATCH) {
+        if coincide(lista) {
+            return Err(Fallo::PrecondicionFallida);
+        }
+    }
+    Ok(())
+}
+
+/// Una petición ya autenticada sobre un recurso
+struct Operacion<'a> {
+    servicios: &'a Servicios,
+    actor: &'a Actor,
+    recurso: Recurso,
+    cabeceras: &'a HeaderMap,
+}
+
+impl Operacion<'_> {
+    fn base(&self) -> &str {
+        &self.servicios.config.prefijo
+    }
+
+    fn contexto(&self) -> Contexto<'_> {
+        Contexto {
+            base: self.base(),
+            actor: self.actor,
+            generado: Utc::now(),
+        }
+    }
+
+    async fn empleado(&self, id: &str) -> Result<Empleado, Fallo> {
+        self.servicios
+            .empleados
+            .obtener_empleado(id)
+            .await?
+            .ok_or(Fallo::NoEncontrado)
+    }
+
+    /// El calendario de un empleado se ve con el mismo permiso que su feed
+    async fn empleado_visible(&self, id: &str) -> Result<Empleado, Fallo> {
+        let empleado = self.empleado(id).await?;
+        if !self
+            .actor
+            .puede(Accion::SuscribirCalendario, Some(&empleado))
+        {
+            return Err(Fallo::Prohibido(format!(
+                "No puede ver el calendario del empleado {}",
+                id
+            )));
+        }
+        Ok(empleado)
+    }
+
+    /// Reservas activas del empleado: las canceladas desaparecen del calendario
+    async fn reservas(&self, empleado_id: &str) -> Result<Vec<Reserva>, Fallo> {
+        let mut reservas = self
+            .servicios
+            .reservas
+            .listar_reservas_empleado(empleado_id)
+            .await?;
+        reservas.retain(Reserva::esta_activa);
+        reservas.sort_by_key(|r| r.slot.inicio);
+        Ok(reservas)
+    }
+
+    async fn nombres_salas(&self) -> Result<HashMap<String, String>, Fallo> {
+        Ok(self
+            .servicios
+            .salas
+            .listar_salas()
+            .await?
+            .into_iter()
+            .map(|s| (s.id, s.nombre))
+            .collect())
+    }
+
+    fn evento(
+        reserva: Reserva,
+        empleado: &Empleado,
+        salas: &HashMap<String, String>,
+    ) -> EventoCalendario {
+        EventoCalendario {
+            empleado: Some(empleado.nombre.clone()),
+            sala: reserva
+                .sala_id
+                .as_ref()
+                .and_then(|id| salas.get(id))
+                .cloned(),
+            reserva,
+        }
+    }
+
+    fn nodo_evento(
+        &self,
+        reserva: Reserva,
+        empleado: &Empleado,
+        salas: &HashMap<String, String>,
+    ) -> Nodo {
+        let nombre = self.servicios.nombres.read().unwrap().nombre(&reserva.id);
+        Nodo {
+            recurso: Recurso::Evento(empleado.id.clone(), nombre),
+            datos: Datos::Evento(Self::evento(reserva, empleado, salas), empleado.clone()),
+        }
+    }
+
+    /// Reserva activa del recurso de evento, si existe
+    async fn reserva(&self, empleado_id: &str, nombre: &str) -> Result<Option<Reserva>, Fallo> {
+        let id = self
+            .servicios
+            .nombres
+            .read()
+            .unwrap()
+            .reserva(empleado_id, nombre);
+        Ok(self
+            .reservas(empleado_id)
+            .await?
+            .into_iter()
+            .find(|r| r.id == id))
+    }
+
+    /// Cancela la reserva, que deja de estar en el calendario
+    async fn cancelar(&self, reserva: &Reserva) -> Result<(), Fallo> {
+        self.servicios
+            .reservas
+            .cancelar_reserva(self.actor, &reserva.id)
+            .await?;
+        self.servicios.nombres.write().unwrap().olvidar(&reserva.id);
+        Ok(())
+    }
+
+    /// Calendarios que aparecen en el calendar-home-set: el propio y, para
+    /// los administradores, los de todos los empleados activos
+    async fn calendarios(&self) -> Result<Vec<Nodo>, Fallo> {
+        let empleados = if self.actor.tiene_rol(Rol::Admin) {
+            self.servicios
+                .empleados
+                .listar_empleados()
+                .await?
+                .into_iter()
+                .filter(|e| e.activo)
+                .collect()
+        } else if let Some(id) = &self.actor.empleado_id {
+            self.servicios
+                .empleados
+                .obtener_empleado(id)
+                .await?
+                .into_iter()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let mut nodos = Vec::new();
+        for empleado in empleados {
+            let reservas = self.reservas(&empleado.id).await?;
+            nodos.push(Nodo {
+                recurso: Recurso::Calendario(empleado.id.clone()),
+                datos: Datos::Calendario { empleado, reservas },
+            });
+        }
+        Ok(nodos)
+    }
+
+    /// El recurso y, con `hijos`, sus miembros
+    async fn nodos(&self, hijos: bool) -> Result<Vec<Nodo>, Fallo> {
+        let recurso = self.recurso.clone();
+        let mut nodos = Vec::new();
+        match &recurso {
+            Recurso::Raiz => nodos.push(Nodo {
+                recurso,
+                datos: Datos::Raiz,
+            }),
+            Recurso::Principal(usuario) => {
+                if *usuario != self.actor.sujeto {
+                    return Err(Fallo::NoEncontrado);
+                }
+                let empleado = match &self.actor.empleado_id {
+                    Some(id) => self.servicios.empleados.obtener_empleado(id).await?,
+                    None => None,
+                };
+                nodos.push(Nodo {
+                    recurso,
+                    datos: Datos::Principal(empleado),
+                });
+            }
+            Recurso::Inicio => {
+                nodos.push(Nodo {
+                    recurso,
+                    datos: Datos::Inicio,
+                });
+                if hijos {
+                    nodos.extend(self.calendarios().await?);
+                }
+            }
+            Recurso::Calendario(id) => {
+                let empleado = self.empleado_visible(id).await?;
+                let reservas = self.reservas(id).await?;
+                if hijos {
+                    let salas = self.nombres_salas().await?;
+                    for reserva in reservas.clone() {
+                        nodos.push(self.nodo_evento(reserva, &empleado, &salas));
+                    }
+                }
+                nodos.insert(
+                    0,
+                    Nodo {
+                        recurso,
+                        datos: Datos::Calendario { empleado, reservas },
+                    },
+                );
+            }
+            Recurso::Evento(id, nombre) => {
+                let empleado = self.empleado_visible(id).await?;
+                let reserva = self.reserva(id, nombre).await?.ok_or(Fallo::NoEncontrado)?;
+                let salas = self.nombres_salas().await?;
+                nodos.push(self.nodo_evento(reserva, &empleado, &salas));
+            }
+        }
+        Ok(nodos)
+    }
+
+    async fn propfind(&self, cuerpo: &str) -> Result<Response, Fallo> {
+        let documento = if cuerpo.trim().is_empty() {
+            None
+        } else {
+            let raiz = xml::leer(cuerpo).map_err(Fallo::PeticionInvalida)?;
+            if !raiz.es(DAV, "propfind") {
+                return Err(Fallo::PeticionInvalida(
+                    "Se esperaba <propfind>".to_string(),
+                ));
+            }
+            Some(raiz)
+        };
+        let peticion = Peticion::desde(documento.as_ref());
+        // Depth: infinity se trata como 1; los calendarios no tienen más niveles
+        let profundidad = self
+            .cabeceras
+            .get("depth")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("infinity");
+        let nodos = self.nodos(profundidad.trim() != "0").await?;
+
+        let contexto = self.contexto();
+        let respuestas: Vec<String> = nodos
+            .iter()
+            .map(|nodo| contexto.respuesta(nodo, &peticion))
+            .collect();
+        Ok(multiestado(&respuestas))
+    }
+
+    async fn report(&self, cuerpo: &str) -> Result<Response, Fallo> {
+        let raiz = xml::leer(cuerpo).map_err(Fallo::PeticionInvalida)?;
+        if raiz.es(CALDAV, "free-busy-query") {
+            return self.libre_ocupado(&raiz).await;
+        }
+        let peticion = Peticion::desde(Some(&raiz));
+        let nodos = if raiz.es(CALDAV, "calendar-query") {
+            self.consulta(&raiz).await?
+        } else if raiz.es(CALDAV, "calendar-multiget") {
+            self.multiget(&raiz).await?
+        } else {
+            return Err(Fallo::Precondicion(
+                DAV,
+                "supported-report",
+                format!("REPORT no soportado: {}", raiz.nombre.local),
+            ));
+        };
+
+        let contexto = self.contexto();
+        let respuestas: Vec<String> = nodos
+            .iter()
+            .map(|nodo| match nodo {
+                Ok(nodo) => contexto.respuesta(nodo, &peticion),
+                Err(url) => propiedades::respuesta_no_encontrado(url),
+            })
+            .collect();
+        Ok(multiestado(&respuestas))
+    }
+
+    /// calendar-query: los eventos del calendario, filtrados por time-range
+    async fn consulta(&self, raiz: &xml::Elemento) -> Result<Vec<Result<Nodo, String>>, Fallo> {
+        let rango = rango(raiz)?;
+        let nodos = match &self.recurso {
+            Recurso::Calendario(_) => self.nodos(true).await?.into_iter().skip(1).collect(),
+            Recurso::Evento(..) => self.nodos(false).await?,
+            _ => return Err(Fallo::NoPermitido),
+        };
+        Ok(nodos
+            .into_iter()
+            .filter(|nodo| match (&nodo.datos, rango) {
+                (Datos::Evento(evento, _), Some((desde, hasta))) => {
+                    evento.reserva.slot.inicio < hasta && evento.reserva.slot.fin() > desde
+                }
+                _ => true,
+            })
+            .map(Ok)
+            .collect())
+    }
+
+    /// calendar-multiget: los eventos de los href pedidos
+    async fn multiget(&self, raiz: &xml::Elemento) -> Result<Vec<Result<Nodo, String>>, Fallo> {
+        let mut salas = None;
+        let mut resultado = Vec::new();
+        for href in raiz.hijos.iter().filter(|h| h.es(DAV, "href")) {
+            let url = href.texto.trim().to_string();
+            let Some(Recurso::Evento(id, nombre)) = Recurso::desde_ruta(self.base(), &url) else {
+                resultado.push(Err(url));
+                continue;
+            };
+            let empleado = match self.empleado_visible(&id).await {
+                Ok(empleado) => empleado,
+                Err(Fallo::Interno(e)) => return Err(Fallo::Interno(e)),
+                Err(_) => {
+                    resultado.push(Err(url));
+                    continue;
+                }
+            };
+            let Some(reserva) = self.reserva(&id, &nombre).await? else {
+                resultado.push(Err(url));
+                continue;
+            };
+            if salas.is_none() {
+                salas = Some(self.nombres_salas().await?);
+            }
+            let salas = salas.as_ref().expect("recién cargadas");
+            resultado.push(Ok(self.nodo_evento(reserva, &empleado, salas)));
+        }
+        Ok(resultado)
+    }
+
+    /// free-busy-query: cualquiera puede ver cuándo está ocupado un empleado,
+    /// sin los detalles de sus reservas
+    async fn libre_ocupado(&self, raiz: &xml::Elemento) -> Result<Response, Fallo> {
+        let Recurso::Calendario(id) = &self.recurso else {
+            return Err(Fallo::NoPermitido);
+        };
+        let (desde, hasta) = rango(raiz)?.ok_or_else(|| {
+            Fallo::PeticionInvalida("free-busy-query exige time-range".to_string())
+        })?;
+        self.empleado(id).await?;
+        let reservas = self.reservas(id).await?;
+        Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, ical::CONTENT_TYPE_ICS)],
+            ical::serializar_libre_ocupado(&reservas, desde, hasta, Utc::now()),
+        )
+            .into_response())
+    }
+
+    async fn get(&self, solo_cabeceras: bool) -> Result<Response, Fallo> {
+        let generado = Utc::now();
+        let (cuerpo, etag) = match &self.recurso {
+            Recurso::Evento(id, nombre) => {
+                let empleado = self.empleado_visible(id).await?;
+                let reserva = self.reserva(id, nombre).await?.ok_or(Fallo::NoEncontrado)?;
+                let etag = recursos::etag(&reserva);
+                let salas = self.nombres_salas().await?;
+                let evento = Self::evento(reserva, &empleado, &salas);
+                (ical::serializar_evento(&evento, generado), etag)
+            }
+            Recurso::Calendario(id) => {
+                let empleado = self.empleado_visible(id).await?;
+                let reservas = self.reservas(id).await?;
+                let etag = recursos::ctag(&reservas);
+                let salas = self.nombres_salas().await?;
+                let feed = FeedCalendario {
+                    eventos: reservas
+                        .into_iter()
+                        .map(|r| Self::evento(r, &empleado, &salas))
+                        .collect(),
+                    nombre: empleado.nombre,
+                };
+                (ical::serializar(&feed, generado), etag)
+            }
+            _ => return Err(Fallo::NoPermitido),
+        };
+        let longitud = cuerpo.len().to_string();
+        let cuerpo = if solo_cabeceras {
+            String::new()
+        } else {
+            cuerpo
+        };
+        Ok((
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, ical::CONTENT_TYPE_ICS.to_string()),
+                (header::ETAG, etag),
+                (header::CONTENT_LENGTH, longitud),
+            ],
+            cuerpo,
+        )
+            .into_response())
+    }
+
+    async fn put(&self, cuerpo: &str) -> Result<Response, Fallo> {
+        let Recurso::Evento(id, nombre) = &self.recurso else {
+            return Err(Fallo::NoPermitido);
+        };
+        let empleado = self.empleado_visible(id).await?;
+
+        let mut eventos = ical::deserializar(cuerpo, self.servicios.config.desfase)
+            .map_err(|e| Fallo::Precondicion(CALDAV, "valid-calendar-data", e))?;
+        if eventos.len() != 1 {
+            return Err(Fallo::Precondicion(
+                CALDAV,
+                "valid-calendar-object-resource",
+                "El recurso debe tener exactamente un VEVENT".to_string(),
+            ));
+        }
+        let evento = eventos.remove(0);
+        let mut existente = self.reserva(id, nombre).await?;
+        // Un nombre olvidado (reinicio, restauración) con el UID de una de
+        // nuestras reservas es esa reserva, no una nueva. Si ya no está, el
+        // cliente tiene una copia vieja: 412 antes que crear un duplicado.
+        let recuperada = match ical::reserva_de_uid(&evento.uid) {
+            Some(reserva_id) if existente.is_none() => {
+                existente = self
+                    .reservas(id)
+                    .await?
+                    .into_iter()
+                    .find(|r| r.id == reserva_id);
+                existente.as_ref().ok_or(Fallo::PrecondicionFallida)?;
+                true
+            }
+            _ => false,
+        };
+        precondiciones(self.cabeceras, existente.as_ref())?;
+        let reservas = &self.servicios.reservas;
+
+        let Some(reserva) = existente else {
+            if evento.cancelado {
+                return Err(Fallo::Conflicto(
+                    "No se crean reservas de eventos cancelados".to_string(),
+                ));
+            }
+            let slot = slot_de(&evento)?;
+            let descripcion = match evento.resumen.trim() {
+                "" => DESCRIPCION_POR_DEFECTO.to_string(),
+                resumen => resumen.to_string(),
+            };
+            let creada = reservas
+                .crear_reserva(self.actor, empleado.id.clone(), slot, descripcion, None)
+                .await?;
+            // El recurso queda en la URL del cliente. Sin ETag: el guardado
+            // no es idéntico al enviado (UID).
+            self.servicios
+                .nombres
+                .write()
+                .unwrap()
+                .asignar(&empleado.id, nombre, &creada.id);
+            return Ok(StatusCode::CREATED.into_response());
+        };
+
+        if recuperada {
+            self.servicios
+                .nombres
+                .write()
+                .unwrap()
+                .asignar(&empleado.id, nombre, &reserva.id);
+        }
+        if evento.cancelado {
+            self.cancelar(&reserva).await?;
+            return Ok(StatusCode::NO_CONTENT.into_response());
+        }
+        // Hora y título en una sola escritura: o cambian los dos o ninguno
+        let slot = slot_de(&evento)?;
+        let descripcion = evento.resumen.trim();
+        let cambios = CambiosReserva {
+            descripcion: (!descripcion.is_empty() && descripcion != reserva.descripcion)
+                .then(|| descripcion.to_string()),
+            slot: (slot != reserva.slot).then_some(slot),
+        };
+        if cambios.descripcion.is_some() || cambios.slot.is_some() {
+            reservas
+                .actualizar_reserva(self.actor, &reserva.id, cambios)
+                .await?
+                .ok_or(Fallo::NoEncontrado)?;
+        }
+        Ok(StatusCode::NO_CONTENT.into_response())
+    }
+
+    async fn delete(&self) -> Result<Response, Fallo> {
+        let Recurso::Evento(id, nombre) = &self.recurso else {
+            return Err(Fallo::NoPermitido);
+        };
+        self.empleado_visible(id).await?;
+        let reserva = self.reserva(id, nombre).await?.ok_or(Fallo::NoEncontrado)?;
+        precondiciones(self.cabeceras, Some(&reserva))?;
+        self.cancelar(&reserva).await?;
+        Ok(StatusCode::NO_CONTENT.into_response())
+    }
+}
@@ -0,0 +1,15 @@
+// CalDAV - Adaptador de entrada para clientes de calendario nativos
+//
+// Publica el calendario de reservas de cada empleado por CalDAV (RFC 4791),
+// de modo que Apple Calendar, Thunderbird o DAVx⁵ pueden verlo, crear
+// reservas, moverlas y cancelarlas. Como los demás adaptadores de entrada,
+// solo conoce los PUERTOS definidos en reservas-ports.
+
+mod auth;
+mod handlers;
+mod propiedades;
+mod recursos;
+pub mod routes;
+pub mod xml;
+
+pub use routes::{crear_router_caldav, ConfigCalDav};
@@ -0,0 +1,268 @@
+// Propiedades WebDAV/CalDAV de cada recurso y respuestas multistatus
+//
+// Solo se publican las propiedades que usan los clientes para descubrir y
+// sincronizar calendarios. Las que se piden y no existen se devuelven con
+// 404 en su propio propstat, como manda RFC 4918.
+
+use crate::recursos::{self, Recurso};
+use crate::xml::{self, Nombre, CALDAV, CALENDARSERVER, DAV};
+use reservas_domain::{Accion, Actor, Empleado, Reserva};
+use reservas_ports::r#in::calendario_service::EventoCalendario;
+
+/// Tipo MIME de las respuestas XML
+pub const CONTENT_TYPE_XML: &str = "application/xml; charset=utf-8";
+
+/// Lo que hace falta saber de un recurso para responder sus propiedades
+pub enum Datos {
+    Raiz,
+    Principal(Option<Empleado>),
+    Inicio,
+    Calendario {
+        empleado: Empleado,
+        reservas: Vec<Reserva>,
+    },
+    Evento(EventoCalendario, Empleado),
+}
+
+pub struct Nodo {
+    pub recurso: Recurso,
+    pub datos: Datos,
+}
+
+/// Propiedades de `allprop`. calendar-data no es una propiedad WebDAV: solo
+/// se devuelve si se pide en un REPORT.
+const TODAS: [(&str, &str); 13] = [
+    (DAV, "resourcetype"),
+    (DAV, "displayname"),
+    (DAV, "current-user-principal"),
+    (DAV, "principal-URL"),
+    (DAV, "getetag"),
+    (DAV, "getcontenttype"),
+    (DAV, "supported-report-set"),
+    (DAV, "current-user-privilege-set"),
+    (CALDAV, "calendar-home-set"),
+    (CALDAV, "calendar-user-address-set"),
+    (CALDAV, "supported-calendar-component-set"),
+    (CALDAV, "calendar-description"),
+    (CALENDARSERVER, "getctag"),
+];
+
+/// Propiedades pedidas: una lista concreta o todas las conocidas
+pub enum Peticion {
+    Todas,
+    Lista(Vec<Nombre>),
+}
+
+impl Peticion {
+    /// A partir del elemento `propfind` o del REPORT; sin `prop`, todas
+    pub fn desde(elemento: Option<&xml::Elemento>) -> Self {
+        match elemento.and_then(|e| e.hijo(DAV, "prop")) {
+            Some(prop) => Peticion::Lista(prop.hijos.iter().map(|h| h.nombre.clone()).collect()),
+            None => Peticion::Todas,
+        }
+    }
+}
+
+fn prefijo(espacio: &str) -> Option<&'static str> {
+    match espacio {
+        DAV => Some("d"),
+        CALDAV => Some("c"),
+        CALENDARSERVER => Some("cs"),
+        _ => None,
+    }
+}
+
+fn etiqueta(nombre: &Nombre, contenido: &str) -> String {
+    let (apertura, cierre) = match prefijo(&nombre.espacio) {
+        Some(p) => (
+            format!("{}:{}", p, nombre.local),
+            format!("{}:{}", p, nombre.local),
+        ),
+        None => (
+            format!(
+                "x:{} xmlns:x=\"{}\"",
+                nombre.local,
+                xml::escapar(&nombre.espacio)
+            ),
+            format!("x:{}", nombre.local),
+        ),
+    };
+    if contenido.is_empty() {
+        format!("<{}/>", apertura)
+    } else {
+        format!("<{}>{}</{}>", apertura, contenido, cierre)
+    }
+}
+
+fn href(url: &str) -> String {
+    format!("<d:href>{}</d:href>", xml::escapar(url))
+}
+
+fn privilegios(escritura: bool) -> String {
+    let mut privilegios = String::from("<d:privilege><d:read/></d:privilege>");
+    if escritura {
+        privilegios.push_str(
+            "<d:privilege><d:write/></d:privilege><d:privilege><d:write-content/></d:privilege>\
+             <d:privilege><d:bind/></d:privilege><d:privilege><d:unbind/></d:privilege>",
+        );
+    }
+    privilegios
+}
+
+/// Contexto común a todos los nodos de una respuesta
+pub struct Contexto<'a> {
+    pub base: &'a str,
+    pub actor: &'a Actor,
+    pub generado: chrono::DateTime<chrono::Utc>,
+}
+
+impl Contexto<'_> {
+    fn principal(&self) -> String {
+        Recurso::Principal(self.actor.sujeto.clone()).href(self.base)
+    }
+
+    /// Valor de la propiedad en el nodo, o `None` si no la tiene
+    fn valor(&self, nodo: &Nodo, nombre: &Nombre) -> Option<String> {
+        let espacio = nombre.espacio.as_str();
+        let valor = match (espacio, nombre.local.as_str(), &nodo.datos) {
+            (DAV, "resourcetype", Datos::Principal(_)) => {
+                "<d:collection/><d:principal/>".to_string()
+            }
+            (DAV, "resourcetype", Datos::Calendario { .. }) => {
+                "<d:collection/><c:calendar/>".to_string()
+            }
+            (DAV, "resourcetype", Datos::Evento(..)) => String::new(),
+            (DAV, "resourcetype", _) => "<d:collection/>".to_string(),
+
+            (DAV, "displayname", Datos::Raiz) => "Reservas".to_string(),
+            (DAV, "displayname", Datos::Principal(empleado)) => xml::escapar(
+                empleado
+                    .as_ref()
+                    .map_or(self.actor.sujeto.as_str(), |e| e.nombre.as_str()),
+            ),
+            (DAV, "displayname", Datos::Inicio) => "Calendarios".to_string(),
+            (DAV, "displayname", Datos::Calendario { empleado, .. }) => {
+                xml::escapar(&format!("Reservas - {}", empleado.nombre))
+            }
+            (DAV, "displayname", Datos::Evento(evento, _)) => {
+                xml::escapar(&evento.reserva.descripcion)
+            }
+
+            (DAV, "current-user-principal", _) => href(&self.principal()),
+            (DAV, "principal-URL", Datos::Principal(_)) => href(&self.principal()),
+
+            (DAV, "getetag", Datos::Evento(evento, _)) => {
+                xml::escapar(&recursos::etag(&evento.reserva))
+            }
+            (DAV, "getetag", Datos::Calendario { reservas, .. })
+            | (CALENDARSERVER, "getctag", Datos::Calendario { reservas, .. }) => {
+                xml::escapar(&recursos::ctag(reservas))
+            }
+            (DAV, "getcontenttype", Datos::Evento(..)) => {
+                "text/calendar; charset=utf-8; component=VEVENT".to_string()
+            }
+            (DAV, "getcontenttype", Datos::Calendario { .. }) => {
+                "text/calendar; charset=utf-8".to_string()
+            }
+
+            (DAV, "supported-report-set", Datos::Calendario { .. }) => {
+                ["calendar-query", "calendar-multiget", "free-busy-query"]
+                    .iter()
+                    .map(|r| {
+                        format!(
+                            "<d:supported-report><d:report><c:{}/></d:report></d:supported-report>",
+                            r
+                        )
+                    })
+                    .collect()
+            }
+            (DAV, "current-user-privilege-set", Datos::Calendario { empleado, .. })
+            | (DAV, "current-user-privilege-set", Datos::Evento(_, empleado)) => {
+                privilegios(self.actor.puede(Accion::CrearReserva, Some(empleado)))
+            }
+
+            (CALDAV, "calendar-home-set", Datos::Raiz | Datos::Principal(_)) => {
+                href(&Recurso::Inicio.href(self.base))
+            }
+            (CALDAV, "calendar-user-address-set", Datos::Principal(Some(empleado))) => {
+                href(&format!("mailto:{}", empleado.email.as_str()))
+            }
+            (CALDAV, "supported-calendar-component-set", Datos::Calendario { .. }) => {
+                "<c:comp name=\"VEVENT\"/>".to_string()
+            }
+            (CALDAV, "calendar-description", Datos::Calendario { empleado, .. }) => {
+                xml::escapar(&format!("Reservas de {}", empleado.nombre))
+            }
+            (CALDAV, "calendar-data", Datos::Evento(evento, _)) => {
+                xml::escapar(&ical::serializar_evento(evento, self.generado))
+            }
+            _ => return None,
+        };
+        Some(valor)
+    }
+
+    /// `<d:response>` del nodo con las propiedades encontradas y las que no
+    pub fn respuesta(&self, nodo: &Nodo, peticion: &Peticion) -> String {
+        let nombres: Vec<Nombre> = match peticion {
+            Peticion::Todas => TODAS.iter().map(|(e, l)| Nombre::new(e, l)).collect(),
+            Peticion::Lista(nombres) => nombres.clone(),
+        };
+        let mut encontradas = String::new();
+        let mut ausentes = String::new();
+        for nombre in &nombres {
+            match self.valor(nodo, nombre) {
+                Some(valor) => encontradas.push_str(&etiqueta(nombre, &valor)),
+                // En allprop solo se listan las que existen
+                None if matches!(peticion, Peticion::Lista(_)) => {
+                    ausentes.push_str(&etiqueta(nombre, ""))
+                }
+                None => {}
+            }
+        }
+
+        let mut respuesta = format!("<d:response>{}", href(&nodo.recurso.href(self.base)));
+        for (propiedades, estado) in [(encontradas, "200 OK"), (ausentes, "404 Not Found")] {
+            if !propiedades.is_empty() {
+                respuesta.push_str(&format!(
+                    "<d:propstat><d:prop>{}</d:prop><d:status>HTTP/1.1 {}</d:status></d:propstat>",
+                    propiedades, estado
+                ));
+            }
+        }
+        respuesta.push_str("</d:response>");
+        respuesta
+    }
+}
+
+/// Respuesta a un href que no existe dentro de un multiget
+pub fn respuesta_no_encontrado(url: &str) -> String {
+    format!(
+        "<d:response>{}<d:status>HTTP/1.1 404 Not Found</d:status></d:response>",
+        href(url)
+    )
+}
+
+/// Documento `<d:multistatus>` con las respuestas ya formadas
+pub fn multiestado(respuestas: &[String]) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <d:multistatus xmlns:d=\"{}\" xmlns:c=\"{}\" xmlns:cs=\"{}\">{}</d:multistatus>",
+        DAV,
+        CALDAV,
+        CALENDARSERVER,
+        respuestas.concat()
+    )
+}
+
+/// Cuerpo `<d:error>` de una precondición incumplida (RFC 4791, sección 1.3)
+pub fn error(espacio: &str, precondicion: &str, detalle: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <d:error xmlns:d=\"{}\" xmlns:c=\"{}\" xmlns:cs=\"{}\">{}<d:responsedescription>{}</d:responsedescription></d:error>",
+        DAV,
+        CALDAV,
+        CALENDARSERVER,
+        etiqueta(&Nombre::new(espacio, precondicion), ""),
+        xml::escapar(detalle)
+    )
+}
@@ -0,0 +1,246 @@
+// Recursos WebDAV del servidor y sus URLs
+//
+//   {base}/                                   raíz (descubrimiento)
+//   {base}/principals/{usuario}/              principal de la cuenta
+//   {base}/calendarios/                       calendar-home-set
+//   {base}/calendarios/{empleado_id}/         calendario de un empleado
+//   {base}/calendarios/{empleado_id}/{id}.ics una reserva
+//
+// El nombre de un recurso es el ID de la reserva, salvo en los eventos que
+// crea un cliente: esos conservan el nombre que eligió, porque la mayoría de
+// clientes ignoran `Location` y siguen usando su URL.
+
+use reservas_domain::{EstadoReserva, Reserva};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Extensión de los recursos de evento
+pub const EXTENSION: &str = ".ics";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recurso {
+    Raiz,
+    Principal(String),
+    Inicio,
+    Calendario(String),
+    /// Empleado y nombre del recurso sin la extensión
+    Evento(String, String),
+}
+
+impl Recurso {
+    /// Interpreta la ruta de una petición o de un href; `None` si no es
+    /// nuestra o no corresponde a ningún recurso
+    pub fn desde_ruta(base: &str, ruta: &str) -> Option<Self> {
+        let ruta = ruta.split(['?', '#']).next().unwrap_or(ruta);
+        let resto = ruta.strip_prefix(base)?;
+        let segmentos: Vec<String> = resto
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(decodificar)
+            .collect::<Option<_>>()?;
+        let segmentos: Vec<&str> = segmentos.iter().map(String::as_str).collect();
+        match segmentos.as_slice() {
+            [] => Some(Recurso::Raiz),
+            ["principals", usuario] => Some(Recurso::Principal(usuario.to_string())),
+            ["calendarios"] => Some(Recurso::Inicio),
+            ["calendarios", empleado] => Some(Recurso::Calendario(empleado.to_string())),
+            ["calendarios", empleado, nombre] => nombre
+                .strip_suffix(EXTENSION)
+                .filter(|n| !n.is_empty())
+                .map(|n| Recurso::Evento(empleado.to_string(), n.to_string())),
+            _ => None,
+        }
+    }
+
+    /// URL canónica; las colecciones terminan en `/`
+    pub fn href(&self, base: &str) -> String {
+        match self {
+            Recurso::Raiz => format!("{}/", base),
+            Recurso::Principal(usuario) => format!("{}/principals/{}/", base, codificar(usuario)),
+            Recurso::Inicio => format!("{}/calendarios/", base),
+            Recurso::Calendario(empleado) => {
+                format!("{}/calendarios/{}/", base, codificar(empleado))
+            }
+            Recurso::Evento(empleado, nombre) => format!(
+                "{}/calendarios/{}/{}{}",
+                base,
+                codificar(empleado),
+                codificar(nombre),
+                EXTENSION
+            ),
+        }
+    }
+}
+
+/// Nombres de recurso que han elegido los clientes para las reservas que
+/// crearon. No sobrevive a un reinicio ni a una restauración: entonces el
+/// UID del evento (el de la reserva) dice a qué reserva se refiere.
+#[derive(Debug, Default)]
+pub struct Nombres {
+    /// (empleado, nombre) → reserva
+    reservas: HashMap<(String, String), String>,
+    /// reserva → nombre
+    nombres: HashMap<String, String>,
+}
+
+impl Nombres {
+    /// Reserva del recurso `nombre` del calendario del empleado
+    pub fn reserva(&self, empleado_id: &str, nombre: &str) -> String {
+        self.reservas
+            .get(&(empleado_id.to_string(), nombre.to_string()))
+            .cloned()
+            .unwrap_or_else(|| nombre.to_string())
+    }
+
+    /// Nombre del recurso de la reserva
+    pub fn nombre(&self, reserva_id: &str) -> String {
+        self.nombres
+            .get(reserva_id)
+            .cloned()
+            .unwrap_or_else(|| reserva_id.to_string())
+    }
+
+    pub fn asignar(&mut self, empleado_id: &str, nombre: &str, reserva_id: &str) {
+        let clave = (empleado_id.to_string(), nombre.to_string());
+        if let Some(anterior) = self.reservas.insert(clave, reserva_id.to_string()) {
+            self.nombres.remove(&anterior);
+        }
+        self.nombres
+            .insert(reserva_id.to_string(), nombre.to_string());
+    }
+
+    /// La reserva ya no está en el calendario (cancelada)
+    pub fn olvidar(&mut self, reserva_id: &str) {
+        if self.nombres.remove(reserva_id).is_some() {
+            self.reservas.retain(|_, id| id != reserva_id);
+        }
+    }
+}
+
+/// Codifica un segmento de ruta (RFC 3986, caracteres no reservados tal cual)
+fn codificar(segmento: &str) -> String {
+    let mut codificado = String::with_capacity(segmento.len());
+    for byte in segmento.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' => {
+                codificado.push(byte as char)
+            }
+            _ => codificado.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    codificado
+}
+
+fn decodificar(segmento: &str) -> Option<String> {
+    let bytes = segmento.as_bytes();
+    let mut decodificado = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = segmento.get(i + 1..i + 3)?;
+            decodificado.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decodificado.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decodificado).ok()
+}
+
+/// ETag de una reserva: cambia con cualquier dato que se publica.
+/// `DefaultHasher::new()` no usa claves aleatorias, así que es estable entre
+/// reinicios del servidor.
+pub fn etag(reserva: &Reserva) -> String {
+    let mut hasher = DefaultHasher::new();
+    reserva.id.hash(&mut hasher);
+    reserva.slot.inicio.timestamp().hash(&mut hasher);
+    reserva.descripcion.hash(&mut hasher);
+    reserva.sala_id.hash(&mut hasher);
+    let estado: u8 = match reserva.estado {
+        EstadoReserva::Pendiente => 0,
+        EstadoReserva::Confirmada => 1,
+        EstadoReserva::Cancelada => 2,
+    };
+    estado.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+/// CTag de un calendario: cambia cuando cambia cualquiera de sus eventos
+pub fn ctag(reservas: &[Reserva]) -> String {
+    let mut etags: Vec<String> = reservas.iter().map(etag).collect();
+    etags.sort();
+    let mut hasher = DefaultHasher::new();
+    etags.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reservas_domain::Slot;
+
+    #[test]
+    fn test_rutas_de_ida_y_vuelta() {
+        let base = "/caldav";
+        for recurso in [
+            Recurso::Raiz,
+            Recurso::Principal("juan.lopez".to_string()),
+            Recurso::Inicio,
+            Recurso::Calendario("emp 001".to_string()),
+            Recurso::Evento("emp-001".to_string(), "r1".to_string()),
+        ] {
+            let href = recurso.href(base);
+            assert_eq!(Recurso::desde_ruta(base, &href), Some(recurso));
+        }
+        assert_eq!(
+            Recurso::desde_ruta(base, "/caldav/calendarios/emp%20001"),
+            Some(Recurso::Calendario("emp 001".to_string()))
+        );
+        assert_eq!(Recurso::desde_ruta(base, "/api/reservas"), None);
+        assert_eq!(
+            Recurso::desde_ruta(base, "/caldav/calendarios/e/r1.txt"),
+            None
+        );
+        assert_eq!(Recurso::desde_ruta(base, "/caldav/otros"), None);
+    }
+
+    #[test]
+    fn test_nombres_elegidos_por_el_cliente() {
+        let mut nombres = Nombres::default();
+        assert_eq!(nombres.reserva("emp-001", "r1"), "r1");
+        assert_eq!(nombres.nombre("r1"), "r1");
+
+        nombres.asignar("emp-001", "ABC-123", "r1");
+        assert_eq!(nombres.reserva("emp-001", "ABC-123"), "r1");
+        assert_eq!(nombres.reserva("emp-002", "ABC-123"), "ABC-123");
+        assert_eq!(nombres.nombre("r1"), "ABC-123");
+
+        // El mismo nombre para una reserva nueva sustituye a la anterior
+        nombres.asignar("emp-001", "ABC-123", "r2");
+        assert_eq!(nombres.nombre("r1"), "r1");
+        assert_eq!(nombres.nombre("r2"), "ABC-123");
+
+        nombres.olvidar("r2");
+        assert_eq!(nombres.reserva("emp-001", "ABC-123"), "ABC-123");
+        assert_eq!(nombres.nombre("r2"), "r2");
+    }
+
+    #[test]
+    fn test_etag_cambia_con_la_reserva() {
+        let mut reserva = Reserva {
+            id: "r1".to_string(),
+            empleado_id: "emp-001".to_string(),
+            slot: Slot::from_date_and_hour(2025, 11, 25, 10).unwrap(),
+            descripcion: "Demo".to_string(),
+            estado: EstadoReserva::Pendiente,
+            sala_id: None,
+        };
+        let antes = etag(&reserva);
+        assert_eq!(antes, etag(&reserva.clone()));
+        reserva.confirmar();
+        assert_ne!(antes, etag(&reserva));
+        assert!(antes.starts_with('"') && antes.ends_with('"'));
+    }
+}
@@ -0,0 +1,628 @@
+// Configuración de rutas del servidor CalDAV
+
+use crate::auth;
+use crate::handlers::{self, Servicios};
+use axum::{middleware, routing::any, Router};
+use chrono::FixedOffset;
+use reservas_ports::r#in::cuenta_service::CuentaService;
+use reservas_ports::r#in::empleado_service::EmpleadoService;
+use reservas_ports::r#in::reserva_service::ReservaService;
+use reservas_ports::r#in::sala_service::SalaService;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct ConfigCalDav {
+    /// Ruta bajo la que se sirve, sin `/` final
+    pub prefijo: String,
+    /// Desfase con el que se interpretan las horas con zona horaria
+    /// (TZID) que envían los clientes; no hay base de datos de zonas
+    pub desfase: FixedOffset,
+}
+
+impl Default for ConfigCalDav {
+    fn default() -> Self {
+        Self {
+            prefijo: "/caldav".to_string(),
+            desfase: FixedOffset::east_opt(0).expect("UTC es un desfase válido"),
+        }
+    }
+}
+
+/// Crea el router CalDAV bajo `config.prefijo`, más `/.well-known/caldav`.
+/// Todo exige HTTP Basic con una cuenta local de `cuenta_service`.
+pub fn crear_router_caldav(
+    config: ConfigCalDav,
+    empleado_service: Arc<dyn EmpleadoService>,
+    reserva_service: Arc<dyn ReservaService>,
+    sala_service: Arc<dyn SalaService>,
+    cuenta_service: Arc<dyn CuentaService>,
+) -> Router {
+    let prefijo = config.prefijo.trim_end_matches('/').to_string();
+    let servicios = Arc::new(Servicios {
+        config: ConfigCalDav {
+            prefijo: prefijo.clone(),
+            ..config
+        },
+        empleados: empleado_service,
+        reservas: reserva_service,
+        salas: sala_service,
+        nombres: Default::default(),
+    });
+
+    Router::new()
+        .route(&prefijo, any(handlers::despachar))
+        .route(&format!("{}/", prefijo), any(handlers::despachar))
+        .route(&format!("{}/*ruta", prefijo), any(handlers::despachar))
+        .route_layer(middleware::from_fn_with_state(
+            cuenta_service,
+            auth::autenticar,
+        ))
+        .route("/.well-known/caldav", any(handlers::descubrir))
+        .with_state(servicios)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use axum::response::Response;
+    use chrono::{Datelike, Duration, Utc};
+    use reservas_adapters::{
+        InMemoryCuentaRepository, InMemoryEmpleadoRepository, InMemoryReservaRepository,
+        InMemorySalaRepository,
+    };
+    use reservas_application::{
+        CuentaServiceImpl, EmpleadoServiceImpl, ReservaServiceImpl, SalaServiceImpl,
+    };
+    use reservas_domain::{Actor, Empleado, Rol, Slot};
+    use reservas_ports::out::empleado_repository::EmpleadoRepository;
+    use reservas_ports::r#in::cuenta_service::NuevaCuenta;
+    use tower::ServiceExt;
+
+    // "juan:password-juan" y "ana:password-ana"
+    const JUAN: &str = "Basic anVhbjpwYXNzd29yZC1qdWFu";
+    const ANA: &str = "Basic YW5hOnBhc3N3b3JkLWFuYQ==";
+
+    struct Escenario {
+        router: Router,
+        reservas: Arc<dyn ReservaService>,
+        empleados: Arc<dyn EmpleadoService>,
+        salas: Arc<dyn SalaService>,
+        cuentas: Arc<dyn CuentaService>,
+    }
+
+    async fn escenario() -> Escenario {
+        let empleados_repo = Arc::new(InMemoryEmpleadoRepository::new());
+        let reservas_repo = Arc::new(InMemoryReservaRepository::new());
+        let salas_repo = Arc::new(InMemorySalaRepository::new());
+        let reservas: Arc<dyn ReservaService> = Arc::new(ReservaServiceImpl::new(
+            Arc::clone(&reservas_repo),
+            Arc::clone(&empleados_repo),
+            Arc::clone(&salas_repo),
+        ));
+        let empleados: Arc<dyn EmpleadoService> = Arc::new(EmpleadoServiceImpl::new(
+            Arc::clone(&empleados_repo),
+            Arc::clone(&reservas),
+        ));
+        let salas: Arc<dyn SalaService> =
+            Arc::new(SalaServiceImpl::new(salas_repo, Arc::clone(&reservas_repo)));
+        let cuentas: Arc<dyn CuentaService> = Arc::new(CuentaServiceImpl::new(
+            InMemoryCuentaRepository::new(),
+            Arc::clone(&empleados_repo),
+        ));
+
+        for (id, nombre) in [("emp-001", "Juan"), ("emp-002", "Ana")] {
+            let email = format!("{}@empresa.com", nombre.to_lowercase());
+            empleados_repo
+                .guardar(&Empleado::new(id.to_string(), nombre.to_string(), email).unwrap())
+                .await
+                .unwrap();
+            cuentas
+                .registrar(
+                    &Actor::sistema(),
+                    NuevaCuenta {
+                        usuario: nombre.to_lowercase(),
+                        password: format!("password-{}", nombre.to_lowercase()),
+                        empleado_id: Some(id.to_string()),
+                        roles: vec![Rol::Empleado],
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let mut e = Escenario {
+            router: Router::new(),
+            reservas,
+            empleados,
+            salas,
+            cuentas,
+        };
+        e.reiniciar();
+        e
+    }
+
+    /// Fecha de mañana en formato iCalendar, a la hora indicada
+    fn manyana(hora: u32, minuto: u32) -> String {
+        let dia = Utc::now() + Duration::days(1);
+        format!(
+            "{:04}{:02}{:02}T{:02}{:02}00Z",
+            dia.year(),
+            dia.month(),
+            dia.day(),
+            hora,
+            minuto
+        )
+    }
+
+    fn vevent(uid: &str, inicio: &str, fin: &str, extra: &str) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Cliente//ES\r\nBEGIN:VEVENT\r\n\
+             UID:{}\r\nDTSTAMP:20250101T000000Z\r\nDTSTART:{}\r\nDTEND:{}\r\n\
+             SUMMARY:Revisión de diseño\r\n{}END:VEVENT\r\nEND:VCALENDAR\r\n",
+            uid, inicio, fin, extra
+        )
+    }
+
+    impl Escenario {
+        /// Router nuevo sobre los mismos servicios, como tras reiniciar el
+        /// servidor: los datos siguen, los nombres de los clientes no
+        fn reiniciar(&mut self) {
+            self.router = crear_router_caldav(
+                ConfigCalDav::default(),
+                Arc::clone(&self.empleados),
+                Arc::clone(&self.reservas),
+                Arc::clone(&self.salas),
+                Arc::clone(&self.cuentas),
+            );
+        }
+
+        async fn enviar(
+            &self,
+            metodo: &str,
+            ruta: &str,
+            credencial: Option<&str>,
+            cabeceras: &[(&str, &str)],
+            cuerpo: &str,
+        ) -> (Response, String) {
+            let mut peticion = Request::builder().method(metodo).uri(ruta);
+            if let Some(credencial) = credencial {
+                peticion = peticion.header(header::AUTHORIZATION, credencial);
+            }
+            for (nombre, valor) in cabeceras {
+                peticion = peticion.header(*nombre, *valor);
+            }
+            let respuesta = self
+                .router
+                .clone()
+                .oneshot(peticion.body(Body::from(cuerpo.to_string())).unwrap())
+                .await
+                .unwrap();
+            let (partes, cuerpo) = respuesta.into_parts();
+            let bytes = axum::body::to_bytes(cuerpo, usize::MAX).await.unwrap();
+            (
+                Response::from_parts(partes, Body::empty()),
+                String::from_utf8(bytes.to_vec()).unwrap(),
+            )
+        }
+    }
+
+    fn cabecera(respuesta: &Response, nombre: header::HeaderName) -> &str {
+        respuesta.headers()[nombre].to_str().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_descubrimiento_del_calendario() {
+        let e = escenario().await;
+
+        let (r, _) = e.enviar("PROPFIND", "/caldav/", None, &[], "").await;
+        assert_eq!(r.status(), StatusCode::UNAUTHORIZED);
+        assert!(cabecera(&r, header::WWW_AUTHENTICATE).starts_with("Basic"));
+
+        let (r, _) = e.enviar("GET", "/.well-known/caldav", None, &[], "").await;
+        assert_eq!(r.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(cabecera(&r, header::LOCATION), "/caldav/");
+
+        let (r, _) = e.enviar("OPTIONS", "/caldav/", Some(JUAN), &[], "").await;
+        assert!(cabecera(&r, header::HeaderName::from_static("dav")).contains("calendar-access"));
+
+        // current-user-principal -> calendar-home-set -> calendarios
+        let (r, cuerpo) = e
+            .enviar(
+                "PROPFIND",
+                "/caldav/",
+                Some(JUAN),
+                &[("Depth", "0")],
+                r#"<d:propfind xmlns:d="DAV:"><d:prop><d:current-user-principal/></d:prop></d:propfind>"#,
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::MULTI_STATUS);
+        assert!(cuerpo.contains(
+            "<d:current-user-principal><d:href>/caldav/principals/juan/</d:href></d:current-user-principal>"
+        ));
+
+        let (_, cuerpo) = e
+            .enviar(
+                "PROPFIND",
+                "/caldav/principals/juan/",
+                Some(JUAN),
+                &[("Depth", "0")],
+                r#"<propfind xmlns="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+                     <prop><C:calendar-home-set/><C:calendar-user-address-set/><C:schedule-inbox-URL/></prop>
+                   </propfind>"#,
+            )
+            .await;
+        assert!(cuerpo.contains("<c:calendar-home-set><d:href>/caldav/calendarios/</d:href>"));
+        assert!(cuerpo.contains("<d:href>mailto:juan@empresa.com</d:href>"));
+        // Lo que no existe va aparte, con 404
+        assert!(cuerpo.contains(
+            "<d:prop><c:schedule-inbox-URL/></d:prop><d:status>HTTP/1.1 404 Not Found</d:status>"
+        ));
+
+        // Otro principal no se ve
+        let (r, _) = e
+            .enviar("PROPFIND", "/caldav/principals/ana/", Some(JUAN), &[], "")
+            .await;
+        assert_eq!(r.status(), StatusCode::NOT_FOUND);
+
+        let (_, cuerpo) = e
+            .enviar(
+                "PROPFIND",
+                "/caldav/calendarios/",
+                Some(JUAN),
+                &[("Depth", "1")],
+                r#"<d:propfind xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+                     <d:prop><d:resourcetype/><d:displayname/><cs:getctag/></d:prop>
+                   </d:propfind>"#,
+            )
+            .await;
+        assert!(cuerpo.contains("<d:href>/caldav/calendarios/emp-001/</d:href>"));
+        assert!(cuerpo.contains("<d:resourcetype><d:collection/><c:calendar/></d:resourcetype>"));
+        assert!(cuerpo.contains("<d:displayname>Reservas - Juan</d:displayname>"));
+        assert!(!cuerpo.contains("emp-002"));
+    }
+
+    #[tokio::test]
+    async fn test_crear_modificar_y_cancelar_desde_el_cliente() {
+        let e = escenario().await;
+        let calendario = "/caldav/calendarios/emp-001/";
+        let ctag = |cuerpo: &str| {
+            let inicio = cuerpo.find("<cs:getctag>").unwrap();
+            cuerpo[inicio..cuerpo[inicio..].find("</cs:getctag>").unwrap() + inicio].to_string()
+        };
+        let propfind_ctag = r#"<d:propfind xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/"><d:prop><cs:getctag/></d:prop></d:propfind>"#;
+        let (_, cuerpo) = e
+            .enviar(
+                "PROPFIND",
+                calendario,
+                Some(JUAN),
+                &[("Depth", "0")],
+                propfind_ctag,
+            )
+            .await;
+        let ctag_vacio = ctag(&cuerpo);
+
+        // PUT de un evento nuevo: se crea la reserva en la URL del cliente
+        let ubicacion = "/caldav/calendarios/emp-001/ABC-123.ics";
+        let (r, _) = e
+            .enviar(
+                "PUT",
+                ubicacion,
+                Some(JUAN),
+                &[("If-None-Match", "*"), ("Content-Type", "text/calendar")],
+                &vevent("ABC-123", &manyana(10, 15), &manyana(11, 0), ""),
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::CREATED);
+        let reservas = e
+            .reservas
+            .listar_reservas_empleado("emp-001")
+            .await
+            .unwrap();
+        assert_eq!(reservas.len(), 1);
+        assert_eq!(reservas[0].descripcion, "Revisión de diseño");
+
+        // Y el calendario lo lista con ese nombre, no con el ID de la reserva
+        let (_, cuerpo) = e
+            .enviar("PROPFIND", calendario, Some(JUAN), &[("Depth", "1")], "")
+            .await;
+        assert!(cuerpo.contains(&format!("<d:href>{}</d:href>", ubicacion)));
+        assert!(!cuerpo.contains(&reservas[0].id));
+
+        let (_, cuerpo) = e
+            .enviar(
+                "PROPFIND",
+                calendario,
+                Some(JUAN),
+                &[("Depth", "0")],
+                propfind_ctag,
+            )
+            .await;
+        assert_ne!(ctag(&cuerpo), ctag_vacio);
+
+        // GET del recurso creado, con su ETag
+        let (r, ics) = e.enviar("GET", ubicacion, Some(JUAN), &[], "").await;
+        assert_eq!(r.status(), StatusCode::OK);
+        let etag = cabecera(&r, header::ETAG).to_string();
+        assert!(ics.contains(&format!("UID:{}@reservas", reservas[0].id)));
+        assert!(ics.contains("STATUS:TENTATIVE"));
+
+        // Mover a las 12 con el ETag correcto; con uno viejo, 412
+        let movido = vevent("x", &manyana(12, 0), &manyana(13, 0), "");
+        let (r, _) = e
+            .enviar(
+                "PUT",
+                ubicacion,
+                Some(JUAN),
+                &[("If-Match", "\"viejo\"")],
+                &movido,
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::PRECONDITION_FAILED);
+        let (r, _) = e
+            .enviar(
+                "PUT",
+                ubicacion,
+                Some(JUAN),
+                &[("If-Match", &etag)],
+                &movido,
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::NO_CONTENT);
+        let reserva = e
+            .reservas
+            .obtener_reserva(&reservas[0].id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(reserva.slot.inicio.format("%H%M").to_string() == "1200");
+        assert_eq!(
+            e.reservas
+                .listar_reservas_empleado("emp-001")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Hora y título cambian juntos: si la hora choca, tampoco cambia el título
+        e.reservas
+            .crear_reserva(
+                &Actor::sistema(),
+                "emp-001".to_string(),
+                Slot::new(reserva.slot.inicio + Duration::hours(2)),
+                "Ocupado".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        let choca = vevent("x", &manyana(14, 0), &manyana(15, 0), "")
+            .replace("Revisión de diseño", "Otro título");
+        let (r, _) = e.enviar("PUT", ubicacion, Some(JUAN), &[], &choca).await;
+        assert_eq!(r.status(), StatusCode::CONFLICT);
+        let reserva = e
+            .reservas
+            .obtener_reserva(&reservas[0].id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reserva.descripcion, "Revisión de diseño");
+        assert!(reserva.slot.inicio.format("%H%M").to_string() == "1200");
+
+        // Un evento que no cabe en un slot no se guarda
+        let (r, cuerpo) = e
+            .enviar(
+                "PUT",
+                "/caldav/calendarios/emp-001/largo.ics",
+                Some(JUAN),
+                &[],
+                &vevent("largo", &manyana(14, 0), &manyana(16, 0), ""),
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::FORBIDDEN);
+        assert!(cuerpo.contains("<c:valid-calendar-object-resource/>"));
+
+        // Ana no puede escribir en el calendario de Juan ni verlo
+        let (r, _) = e
+            .enviar(
+                "PUT",
+                "/caldav/calendarios/emp-001/otro.ics",
+                Some(ANA),
+                &[],
+                &vevent("otro", &manyana(15, 0), &manyana(16, 0), ""),
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::FORBIDDEN);
+
+        // DELETE cancela la reserva y la quita del calendario
+        let (r, _) = e.enviar("DELETE", ubicacion, Some(JUAN), &[], "").await;
+        assert_eq!(r.status(), StatusCode::NO_CONTENT);
+        let reserva = e
+            .reservas
+            .obtener_reserva(&reservas[0].id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!reserva.esta_activa());
+        let (r, _) = e.enviar("GET", ubicacion, Some(JUAN), &[], "").await;
+        assert_eq!(r.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_put_tras_reiniciar_usa_el_uid_de_la_reserva() {
+        let mut e = escenario().await;
+        let ubicacion = "/caldav/calendarios/emp-001/ABC-123.ics";
+        let (r, _) = e
+            .enviar(
+                "PUT",
+                ubicacion,
+                Some(JUAN),
+                &[("If-None-Match", "*")],
+                &vevent("ABC-123", &manyana(10, 0), &manyana(11, 0), ""),
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::CREATED);
+        let id = e
+            .reservas
+            .listar_reservas_empleado("emp-001")
+            .await
+            .unwrap()[0]
+            .id
+            .clone();
+
+        // Tras el reinicio el nombre del cliente ya no está, pero el evento
+        // que descargó lleva el UID de la reserva: se modifica, no se duplica
+        e.reiniciar();
+        let uid = format!("{}@reservas", id);
+        let (r, _) = e
+            .enviar(
+                "PUT",
+                ubicacion,
+                Some(JUAN),
+                &[],
+                &vevent(&uid, &manyana(12, 0), &manyana(13, 0), ""),
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::NO_CONTENT);
+        let reservas = e
+            .reservas
+            .listar_reservas_empleado("emp-001")
+            .await
+            .unwrap();
+        assert_eq!(reservas.len(), 1);
+        assert_eq!(reservas[0].id, id);
+        assert_eq!(reservas[0].slot.inicio.format("%H").to_string(), "12");
+
+        // Y el nombre vuelve a apuntar a la reserva
+        let (r, _) = e.enviar("GET", ubicacion, Some(JUAN), &[], "").await;
+        assert_eq!(r.status(), StatusCode::OK);
+
+        // Un UID nuestro que ya no corresponde a ninguna reserva: 412
+        let (r, _) = e
+            .enviar(
+                "PUT",
+                "/caldav/calendarios/emp-001/OTRO.ics",
+                Some(JUAN),
+                &[],
+                &vevent("no-existe@reservas", &manyana(14, 0), &manyana(15, 0), ""),
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(
+            e.reservas
+                .listar_reservas_empleado("emp-001")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reports_de_consulta_multiget_y_libre_ocupado() {
+        let e = escenario().await;
+        for (hora, uid) in [(9, "a"), (11, "b")] {
+            let (r, _) = e
+                .enviar(
+                    "PUT",
+                    &format!("/caldav/calendarios/emp-001/{}.ics", uid),
+                    Some(JUAN),
+                    &[],
+                    &vevent(uid, &manyana(hora, 0), &manyana(hora + 1, 0), ""),
+                )
+                .await;
+            assert_eq!(r.status(), StatusCode::CREATED);
+        }
+        let ids: Vec<String> = e
+            .reservas
+            .listar_reservas_empleado("emp-001")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+
+        // calendar-query con time-range: solo la de las 11
+        let consulta = format!(
+            r#"<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+                 <d:prop><d:getetag/><c:calendar-data/></d:prop>
+                 <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VEVENT">
+                   <c:time-range start="{}" end="{}"/>
+                 </c:comp-filter></c:comp-filter></c:filter>
+               </c:calendar-query>"#,
+            manyana(10, 30),
+            manyana(18, 0)
+        );
+        let (r, cuerpo) = e
+            .enviar(
+                "REPORT",
+                "/caldav/calendarios/emp-001/",
+                Some(JUAN),
+                &[("Depth", "1")],
+                &consulta,
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::MULTI_STATUS);
+        assert_eq!(cuerpo.matches("<d:response>").count(), 1);
+        assert!(cuerpo.contains("<c:calendar-data>BEGIN:VCALENDAR&#13;\n"));
+        assert!(cuerpo.contains(&format!("DTSTART:{}", manyana(11, 0))));
+
+        // calendar-multiget: una existente y otra que no
+        let multiget = format!(
+            r#"<c:calendar-multiget xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+                 <d:prop><d:getetag/></d:prop>
+                 <d:href>/caldav/calendarios/emp-001/{}.ics</d:href>
+                 <d:href>/caldav/calendarios/emp-001/no-existe.ics</d:href>
+               </c:calendar-multiget>"#,
+            ids[0]
+        );
+        let (_, cuerpo) = e
+            .enviar(
+                "REPORT",
+                "/caldav/calendarios/emp-001/",
+                Some(JUAN),
+                &[],
+                &multiget,
+            )
+            .await;
+        assert_eq!(cuerpo.matches("<d:getetag>").count(), 1);
+        assert!(cuerpo.contains(
+            "<d:href>/caldav/calendarios/emp-001/no-existe.ics</d:href><d:status>HTTP/1.1 404 Not Found</d:status>"
+        ));
+
+        // free-busy-query: Ana ve cuándo está ocupado Juan, sin detalles
+        let libre_ocupado = format!(
+            r#"<C:free-busy-query xmlns:C="urn:ietf:params:xml:ns:caldav">
+                 <C:time-range start="{}" end="{}"/>
+               </C:free-busy-query>"#,
+            manyana(0, 0),
+            manyana(23, 0)
+        );
+        let (r, ics) = e
+            .enviar(
+                "REPORT",
+                "/caldav/calendarios/emp-001/",
+                Some(ANA),
+                &[],
+                &libre_ocupado,
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::OK);
+        assert!(cabecera(&r, header::CONTENT_TYPE).starts_with("text/calendar"));
+        assert_eq!(ics.matches("FREEBUSY;FBTYPE=BUSY-TENTATIVE:").count(), 2);
+        assert!(!ics.contains("Revisión"));
+
+        // Pero no su calendario
+        let (r, _) = e
+            .enviar(
+                "REPORT",
+                "/caldav/calendarios/emp-001/",
+                Some(ANA),
+                &[],
+                &consulta,
+            )
+            .await;
+        assert_eq!(r.status(), StatusCode::FORBIDDEN);
+    }
+}
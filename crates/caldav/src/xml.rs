@@ -0,0 +1,371 @@
+// Lector y utilidades XML para los cuerpos WebDAV
+//
+// Los clientes solo envían documentos pequeños (propfind, REPORT), así que
+// basta un lector en memoria que resuelva los espacios de nombres y deje un
+// árbol de elementos. Se ignoran comentarios, instrucciones de proceso y
+// DOCTYPE; no se expanden entidades salvo las cinco predefinidas y las
+// numéricas.
+
+use std::collections::HashMap;
+
+/// Espacio de nombres de WebDAV
+pub const DAV: &str = "DAV:";
+/// Espacio de nombres de CalDAV (RFC 4791)
+pub const CALDAV: &str = "urn:ietf:params:xml:ns:caldav";
+/// Extensiones de Apple Calendar Server (getctag)
+pub const CALENDARSERVER: &str = "http://calendarserver.org/ns/";
+
+/// Nombre cualificado: espacio de nombres y nombre local
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Nombre {
+    pub espacio: String,
+    pub local: String,
+}
+
+impl Nombre {
+    pub fn new(espacio: &str, local: &str) -> Self {
+        Self {
+            espacio: espacio.to_string(),
+            local: local.to_string(),
+        }
+    }
+
+    pub fn es(&self, espacio: &str, local: &str) -> bool {
+        self.espacio == espacio && self.local == local
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Elemento {
+    pub nombre: Nombre,
+    /// Atributos sin espacio de nombres, por nombre local
+    pub atributos: HashMap<String, String>,
+    pub hijos: Vec<Elemento>,
+    pub texto: String,
+}
+
+impl Elemento {
+    pub fn es(&self, espacio: &str, local: &str) -> bool {
+        self.nombre.es(espacio, local)
+    }
+
+    pub fn hijo(&self, espacio: &str, local: &str) -> Option<&Elemento> {
+        self.hijos.iter().find(|h| h.es(espacio, local))
+    }
+
+    /// Primer descendiente (en profundidad) con ese nombre
+    pub fn buscar(&self, espacio: &str, local: &str) -> Option<&Elemento> {
+        self.hijos.iter().find_map(|h| {
+            if h.es(espacio, local) {
+                Some(h)
+            } else {
+                h.buscar(espacio, local)
+            }
+        })
+    }
+
+    pub fn atributo(&self, nombre: &str) -> Option<&str> {
+        self.atributos.get(nombre).map(String::as_str)
+    }
+}
+
+/// Elemento abierto y las declaraciones de espacio de nombres que introdujo
+struct Abierto {
+    prefijo: Option<String>,
+    local: String,
+    atributos: Vec<(String, String)>,
+    hijos: Vec<Elemento>,
+    texto: String,
+    espacios: HashMap<String, String>,
+}
+
+fn resolver(pila: &[Abierto], prefijo: Option<&str>) -> Result<String, String> {
+    let clave = prefijo.unwrap_or("");
+    for abierto in pila.iter().rev() {
+        if let Some(espacio) = abierto.espacios.get(clave) {
+            return Ok(espacio.clone());
+        }
+    }
+    match prefijo {
+        None => Ok(String::new()),
+        Some("xml") => Ok("http://www.w3.org/XML/1998/namespace".to_string()),
+        Some(p) => Err(format!("Prefijo sin declarar: {}", p)),
+    }
+}
+
+fn separar(nombre: &str) -> (Option<&str>, &str) {
+    match nombre.split_once(':') {
+        Some((prefijo, local)) => (Some(prefijo), local),
+        None => (None, nombre),
+    }
+}
+
+fn cerrar(pila: &mut Vec<Abierto>, abierto: Abierto) -> Result<Elemento, String> {
+    pila.push(abierto);
+    let espacio = {
+        let actual = pila.last().expect("recién añadido");
+        resolver(pila, actual.prefijo.as_deref())?
+    };
+    let abierto = pila.pop().expect("recién añadido");
+    let atributos = abierto
+        .atributos
+        .into_iter()
+        .filter(|(n, _)| !n.contains(':'))
+        .collect();
+    Ok(Elemento {
+        nombre: Nombre {
+            espacio,
+            local: abierto.local,
+        },
+        atributos,
+        hijos: abierto.hijos,
+        texto: abierto.texto,
+    })
+}
+
+/// Deshace las referencias a entidades de un texto o valor de atributo
+fn desescapar(texto: &str) -> Result<String, String> {
+    let mut resultado = String::with_capacity(texto.len());
+    let mut resto = texto;
+    while let Some(inicio) = resto.find('&') {
+        resultado.push_str(&resto[..inicio]);
+        let fin = resto[inicio..]
+            .find(';')
+            .ok_or_else(|| "Entidad sin terminar".to_string())?;
+        let entidad = &resto[inicio + 1..inicio + fin];
+        let caracter = match entidad {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let codigo = if let Some(hex) = entidad.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else {
+                    entidad.strip_prefix('#').and_then(|d| d.parse().ok())
+                };
+                codigo
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("Entidad desconocida: &{};", entidad))?
+            }
+        };
+        resultado.push(caracter);
+        resto = &resto[inicio + fin + 1..];
+    }
+    resultado.push_str(resto);
+    Ok(resultado)
+}
+
+fn leer_atributos(texto: &str) -> Result<Vec<(String, String)>, String> {
+    let mut atributos = Vec::new();
+    let mut resto = texto.trim();
+    while !resto.is_empty() {
+        let igual = resto
+            .find('=')
+            .ok_or_else(|| format!("Atributo sin valor: {}", resto))?;
+        let nombre = resto[..igual].trim().to_string();
+        let valor = resto[igual + 1..].trim_start();
+        let comilla = valor
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("Valor sin comillas en {}", nombre))?;
+        let fin = valor[1..]
+            .find(comilla)
+            .ok_or_else(|| format!("Valor sin cerrar en {}", nombre))?;
+        atributos.push((nombre, desescapar(&valor[1..1 + fin])?));
+        resto = valor[fin + 2..].trim_start();
+    }
+    Ok(atributos)
+}
+
+/// Lee un documento y devuelve su elemento raíz
+pub fn leer(documento: &str) -> Result<Elemento, String> {
+    let mut pila: Vec<Abierto> = Vec::new();
+    let mut raiz = None;
+    let mut resto = documento.trim_start_matches('\u{feff}');
+
+    while let Some(inicio) = resto.find('<') {
+        let texto = &resto[..inicio];
+        if let Some(actual) = pila.last_mut() {
+            actual.texto.push_str(&desescapar(texto)?);
+        } else if !texto.trim().is_empty() {
+            return Err("Texto fuera del elemento raíz".to_string());
+        }
+        resto = &resto[inicio..];
+
+        if let Some(cuerpo) = resto.strip_prefix("<!--") {
+            let fin = cuerpo.find("-->").ok_or("Comentario sin cerrar")?;
+            resto = &cuerpo[fin + 3..];
+            continue;
+        }
+        if let Some(cuerpo) = resto.strip_prefix("<![CDATA[") {
+            let fin = cuerpo.find("]]>").ok_or("CDATA sin cerrar")?;
+            if let Some(actual) = pila.last_mut() {
+                actual.texto.push_str(&cuerpo[..fin]);
+            }
+            resto = &cuerpo[fin + 3..];
+            continue;
+        }
+        if resto.starts_with("<?") || resto.starts_with("<!") {
+            let fin = resto.find('>').ok_or("Declaración sin cerrar")?;
+            resto = &resto[fin + 1..];
+            continue;
+        }
+
+        let fin = resto.find('>').ok_or("Etiqueta sin cerrar")?;
+        let etiqueta = &resto[1..fin];
+        resto = &resto[fin + 1..];
+
+        if let Some(nombre) = etiqueta.strip_prefix('/') {
+            let abierto = pila.pop().ok_or("Cierre sin apertura")?;
+            let esperado = match &abierto.prefijo {
+                Some(p) => format!("{}:{}", p, abierto.local),
+                None => abierto.local.clone(),
+            };
+            if nombre.trim() != esperado {
+                return Err(format!("Se esperaba </{}>", esperado));
+            }
+            let elemento = cerrar(&mut pila, abierto)?;
+            match pila.last_mut() {
+                Some(padre) => padre.hijos.push(elemento),
+                None => raiz = Some(elemento),
+            }
+            continue;
+        }
+
+        let (etiqueta, vacio) = match etiqueta.strip_suffix('/') {
+            Some(e) => (e, true),
+            None => (etiqueta, false),
+        };
+        let (nombre, atributos) = match etiqueta.find(char::is_whitespace) {
+            Some(i) => (&etiqueta[..i], &etiqueta[i..]),
+            None => (etiqueta, ""),
+        };
+        if nombre.is_empty() {
+            return Err("Etiqueta sin nombre".to_string());
+        }
+        if raiz.is_some() {
+            return Err("Más de un elemento raíz".to_string());
+        }
+        let atributos = leer_atributos(atributos)?;
+        let espacios = atributos
+            .iter()
+            .filter_map(|(n, v)| match n.as_str() {
+                "xmlns" => Some((String::new(), v.clone())),
+                n => n
+                    .strip_prefix("xmlns:")
+                    .map(|prefijo| (prefijo.to_string(), v.clone())),
+            })
+            .collect();
+        let (prefijo, local) = separar(nombre);
+        let abierto = Abierto {
+            prefijo: prefijo.map(str::to_string),
+            local: local.to_string(),
+            atributos,
+            hijos: Vec::new(),
+            texto: String::new(),
+            espacios,
+        };
+        if vacio {
+            let elemento = cerrar(&mut pila, abierto)?;
+            match pila.last_mut() {
+                Some(padre) => padre.hijos.push(elemento),
+                None => raiz = Some(elemento),
+            }
+        } else {
+            pila.push(abierto);
+        }
+    }
+
+    if !pila.is_empty() {
+        return Err("Documento incompleto".to_string());
+    }
+    raiz.ok_or_else(|| "Documento vacío".to_string())
+}
+
+/// Escapa texto para incluirlo en un documento
+pub fn escapar(texto: &str) -> String {
+    let mut escapado = String::with_capacity(texto.len());
+    for c in texto.chars() {
+        match c {
+            '<' => escapado.push_str("&lt;"),
+            '>' => escapado.push_str("&gt;"),
+            '&' => escapado.push_str("&amp;"),
+            '"' => escapado.push_str("&quot;"),
+            '\r' => escapado.push_str("&#13;"),
+            c => escapado.push(c),
+        }
+    }
+    escapado
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resuelve_espacios_de_nombres() {
+        let documento = r#"<?xml version="1.0" encoding="utf-8" ?>
+            <!-- propfind de un cliente -->
+            <D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+              <D:prop>
+                <D:displayname/>
+                <C:calendar-home-set />
+                <getctag xmlns="http://calendarserver.org/ns/"/>
+              </D:prop>
+            </D:propfind>"#;
+        let raiz = leer(documento).unwrap();
+        assert!(raiz.es(DAV, "propfind"));
+        let prop = raiz.hijo(DAV, "prop").unwrap();
+        let nombres: Vec<_> = prop.hijos.iter().map(|h| h.nombre.clone()).collect();
+        assert_eq!(
+            nombres,
+            [
+                Nombre::new(DAV, "displayname"),
+                Nombre::new(CALDAV, "calendar-home-set"),
+                Nombre::new(CALENDARSERVER, "getctag"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_texto_atributos_y_entidades() {
+        let documento = "<C:calendar-multiget xmlns:C='urn:ietf:params:xml:ns:caldav'>\
+            <href xmlns=\"DAV:\">/a&amp;b.ics</href>\
+            <C:time-range start=\"20251125T000000Z\" end='20251126T000000Z'/>\
+            <C:text-match><![CDATA[<x>]]>&#233;</C:text-match>\
+            </C:calendar-multiget>";
+        let raiz = leer(documento).unwrap();
+        assert_eq!(raiz.hijo(DAV, "href").unwrap().texto, "/a&b.ics");
+        let rango = raiz.buscar(CALDAV, "time-range").unwrap();
+        assert_eq!(rango.atributo("start"), Some("20251125T000000Z"));
+        assert_eq!(rango.atributo("end"), Some("20251126T000000Z"));
+        assert_eq!(raiz.hijo(CALDAV, "text-match").unwrap().texto, "<x>é");
+    }
+
+    #[test]
+    fn test_rechaza_documentos_mal_formados() {
+        for documento in [
+            "",
+            "texto",
+            "<a>",
+            "<a></b>",
+            "<x:a/>",
+            "<a/><b/>",
+            "<a b=c/>",
+            "<a>&desconocida;</a>",
+        ] {
+            assert!(leer(documento).is_err(), "{:?}", documento);
+        }
+    }
+
+    #[test]
+    fn test_escapar() {
+        assert_eq!(
+            escapar("a<b & \"c\"\r\n"),
+            "a&lt;b &amp; &quot;c&quot;&#13;\n"
+        );
+    }
+}
@@ -1,8 +1,13 @@
 // Cliente HTTP para interactuar con la API REST
 
 use crate::response::{
-    CrearEmpleadoRequest, CrearReservaRequest, CrearSalaRequest, EmpleadoResponse, ErrorResponse,
-    ReservaResponse, SalaResponse, TablaDisponibilidadResponse,
+    BuscarSalasLibresQuery, CrearEmpleadoRequest, CrearReservaRequest, CrearSalaRequest,
+    EmpleadoResponse, InformeCargaResponse, InformeImportacionResponse, InformeIntegridadResponse,
+    InformeOcupacionResponse, InformeQuery, IntentoDenegadoResponse, ListarEmpleadosQuery,
+    ListarReservasQuery, ListarSalasQuery, MatrizDisponibilidadQuery, MatrizDisponibilidadResponse,
+    MetricasLimitesResponse, ModificarEmpleadoRequest, ModificarReservaRequest,
+    ModificarSalaRequest, PaginaResponse, ProblemaResponse, ReprogramarReservaRequest,
+    ReservaResponse, ResumenRestauracionResponse, SalaResponse, TablaDisponibilidadResponse,
 };
 use chrono::{DateTime, Utc};
 
@@ -21,6 +26,23 @@ impl ApiClient {
         }
     }
 
+    /// Envía el token (clave de API o JWT) como `Authorization: Bearer` en
+    /// todas las peticiones
+    pub fn con_token(mut self, token: Option<String>) -> Result<Self, String> {
+        if let Some(token) = token {
+            let mut valor = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|_| "El token contiene caracteres no válidos".to_string())?;
+            valor.set_sensitive(true);
+            let mut cabeceras = reqwest::header::HeaderMap::new();
+            cabeceras.insert(reqwest::header::AUTHORIZATION, valor);
+            self.client = reqwest::blocking::Client::builder()
+                .default_headers(cabeceras)
+                .build()
+                .map_err(|e| format!("Error creando el cliente HTTP: {}", e))?;
+        }
+        Ok(self)
+    }
+
     // Empleados
 
     pub fn crear_empleado(
@@ -42,17 +64,18 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            let error: ErrorResponse = response
-                .json()
-                .map_err(|e| format!("Error parseando error: {}", e))?;
-            Err(error.error)
+            Err(leer_problema(response))
         }
     }
 
-    pub fn listar_empleados(&self) -> Result<Vec<EmpleadoResponse>, String> {
+    pub fn listar_empleados(
+        &self,
+        query: &ListarEmpleadosQuery,
+    ) -> Result<PaginaResponse<EmpleadoResponse>, String> {
         let response = self
             .client
             .get(format!("{}/empleados", self.base_url))
+            .query(query)
             .send()
             .map_err(|e| format!("Error de conexión: {}", e))?;
 
@@ -61,7 +84,7 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            Err("Error obteniendo empleados".to_string())
+            Err(leer_problema(response))
         }
     }
 
@@ -77,10 +100,7 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            let error: ErrorResponse = response
-                .json()
-                .map_err(|e| format!("Error parseando error: {}", e))?;
-            Err(error.error)
+            Err(leer_problema(response))
         }
     }
 
@@ -96,10 +116,7 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            let error: ErrorResponse = response
-                .json()
-                .map_err(|e| format!("Error parseando error: {}", e))?;
-            Err(error.error)
+            Err(leer_problema(response))
         }
     }
 
@@ -115,10 +132,42 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            let error: ErrorResponse = response
+            Err(leer_problema(response))
+        }
+    }
+
+    pub fn modificar_empleado(
+        &self,
+        id: &str,
+        cambios: ModificarEmpleadoRequest,
+    ) -> Result<EmpleadoResponse, String> {
+        let response = self
+            .client
+            .patch(format!("{}/empleados/{}", self.base_url, id))
+            .json(&cambios)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
                 .json()
-                .map_err(|e| format!("Error parseando error: {}", e))?;
-            Err(error.error)
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    pub fn eliminar_empleado(&self, id: &str) -> Result<(), String> {
+        let response = self
+            .client
+            .delete(format!("{}/empleados/{}", self.base_url, id))
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(leer_problema(response))
         }
     }
 
@@ -129,11 +178,13 @@ impl ApiClient {
         empleado_id: String,
         inicio_slot: DateTime<Utc>,
         descripcion: String,
+        sala_id: Option<String>,
     ) -> Result<ReservaResponse, String> {
         let request = CrearReservaRequest {
             empleado_id,
             inicio_slot,
             descripcion,
+            sala_id,
         };
 
         let response = self
@@ -148,17 +199,18 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            let error: ErrorResponse = response
-                .json()
-                .map_err(|e| format!("Error parseando error: {}", e))?;
-            Err(error.error)
+            Err(leer_problema(response))
         }
     }
 
-    pub fn listar_reservas(&self) -> Result<Vec<ReservaResponse>, String> {
+    pub fn listar_reservas(
+        &self,
+        query: &ListarReservasQuery,
+    ) -> Result<PaginaResponse<ReservaResponse>, String> {
         let response = self
             .client
             .get(format!("{}/reservas", self.base_url))
+            .query(query)
             .send()
             .map_err(|e| format!("Error de conexión: {}", e))?;
 
@@ -167,7 +219,7 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            Err("Error obteniendo reservas".to_string())
+            Err(leer_problema(response))
         }
     }
 
@@ -189,7 +241,7 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            Err("Error obteniendo reservas del empleado".to_string())
+            Err(leer_problema(response))
         }
     }
 
@@ -205,10 +257,7 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            let error: ErrorResponse = response
-                .json()
-                .map_err(|e| format!("Error parseando error: {}", e))?;
-            Err(error.error)
+            Err(leer_problema(response))
         }
     }
 
@@ -224,10 +273,7 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            let error: ErrorResponse = response
-                .json()
-                .map_err(|e| format!("Error parseando error: {}", e))?;
-            Err(error.error)
+            Err(leer_problema(response))
         }
     }
 
@@ -248,18 +294,266 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            let error: ErrorResponse = response
+            Err(leer_problema(response))
+        }
+    }
+
+    /// Disponibilidad de varios días en forma de matriz
+    pub fn matriz_disponibilidad(
+        &self,
+        query: &MatrizDisponibilidadQuery,
+    ) -> Result<MatrizDisponibilidadResponse, String> {
+        let response = self
+            .client
+            .get(format!("{}/disponibilidad/matriz", self.base_url))
+            .query(query)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    /// Ocupación de todas las salas activas en forma de matriz
+    pub fn disponibilidad_salas(
+        &self,
+        query: &MatrizDisponibilidadQuery,
+    ) -> Result<MatrizDisponibilidadResponse, String> {
+        let response = self
+            .client
+            .get(format!("{}/salas/disponibilidad", self.base_url))
+            .query(query)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    pub fn buscar_salas_libres(
+        &self,
+        query: &BuscarSalasLibresQuery,
+    ) -> Result<Vec<SalaResponse>, String> {
+        let response = self
+            .client
+            .get(format!("{}/salas/buscar", self.base_url))
+            .query(query)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    pub fn reprogramar_reserva(
+        &self,
+        id: &str,
+        inicio_slot: DateTime<Utc>,
+    ) -> Result<ReservaResponse, String> {
+        let request = ReprogramarReservaRequest { inicio_slot };
+
+        let response = self
+            .client
+            .post(format!("{}/reservas/{}/reprogramar", self.base_url, id))
+            .json(&request)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    pub fn modificar_reserva(
+        &self,
+        id: &str,
+        cambios: ModificarReservaRequest,
+    ) -> Result<ReservaResponse, String> {
+        let response = self
+            .client
+            .patch(format!("{}/reservas/{}", self.base_url, id))
+            .json(&cambios)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    pub fn eliminar_reserva(&self, id: &str) -> Result<(), String> {
+        let response = self
+            .client
+            .delete(format!("{}/reservas/{}", self.base_url, id))
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    /// Envía un fichero iCalendar; `desfase` se aplica a sus horas sin zona
+    pub fn importar_reservas(
+        &self,
+        contenido: String,
+        simular: bool,
+        desfase: &str,
+    ) -> Result<InformeImportacionResponse, String> {
+        let response = self
+            .client
+            .post(format!("{}/reservas/importar", self.base_url))
+            .query(&[
+                ("simular", simular.to_string().as_str()),
+                ("desfase", desfase),
+            ])
+            .header(reqwest::header::CONTENT_TYPE, "text/calendar")
+            .body(contenido)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    /// Carga masiva de `empleados` o `salas` desde una hoja de cálculo.
+    /// `columnas` son pares `campo=Columna`.
+    pub fn importar_hoja(
+        &self,
+        entidad: &str,
+        nombre_fichero: String,
+        contenido: Vec<u8>,
+        columnas: &[String],
+        simular: bool,
+        formato: Option<&str>,
+    ) -> Result<InformeCargaResponse, String> {
+        let mut formulario = reqwest::blocking::multipart::Form::new().part(
+            "fichero",
+            reqwest::blocking::multipart::Part::bytes(contenido).file_name(nombre_fichero),
+        );
+        for columna in columnas {
+            formulario = formulario.text("columna", columna.clone());
+        }
+        let mut query = vec![("simular", simular.to_string())];
+        if let Some(formato) = formato {
+            query.push(("formato", formato.to_string()));
+        }
+        let response = self
+            .client
+            .post(format!("{}/{}/importar", self.base_url, entidad))
+            .query(&query)
+            .multipart(formulario)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    /// Hoja con todos los `empleados`, `salas` o `reservas`
+    pub fn exportar_hoja(&self, entidad: &str, formato: &str) -> Result<Vec<u8>, String> {
+        let response = self
+            .client
+            .get(format!("{}/{}/exportar", self.base_url, entidad))
+            .query(&[("formato", formato)])
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .bytes()
+                .map(|b| b.to_vec())
+                .map_err(|e| format!("Error leyendo la hoja: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    // Informes
+
+    pub fn informe_ocupacion(
+        &self,
+        query: &InformeQuery,
+    ) -> Result<InformeOcupacionResponse, String> {
+        let response = self
+            .client
+            .get(format!("{}/informes/ocupacion", self.base_url))
+            .query(query)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
                 .json()
-                .map_err(|e| format!("Error parseando error: {}", e))?;
-            Err(error.error)
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    /// Una parte del informe como hoja; `query` lleva el formato y el desglose
+    pub fn exportar_informe(&self, query: &InformeQuery) -> Result<Vec<u8>, String> {
+        let response = self
+            .client
+            .get(format!("{}/informes/ocupacion", self.base_url))
+            .query(query)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .bytes()
+                .map(|b| b.to_vec())
+                .map_err(|e| format!("Error leyendo la hoja: {}", e))
+        } else {
+            Err(leer_problema(response))
         }
     }
 
     // Salas
-    pub fn listar_salas(&self) -> Result<Vec<SalaResponse>, String> {
+    pub fn listar_salas(
+        &self,
+        query: &ListarSalasQuery,
+    ) -> Result<PaginaResponse<SalaResponse>, String> {
         let response = self
             .client
             .get(format!("{}/salas", self.base_url))
+            .query(query)
             .send()
             .map_err(|e| format!("Error de conexión: {}", e))?;
 
@@ -268,7 +562,7 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            Err("Error obteniendo salas".to_string())
+            Err(leer_problema(response))
         }
     }
     // Salas
@@ -288,10 +582,149 @@ impl ApiClient {
                 .json()
                 .map_err(|e| format!("Error parseando respuesta: {}", e))
         } else {
-            let error: ErrorResponse = response
+            Err(leer_problema(response))
+        }
+    }
+
+    pub fn modificar_sala(
+        &self,
+        id: &str,
+        cambios: ModificarSalaRequest,
+    ) -> Result<SalaResponse, String> {
+        let response = self
+            .client
+            .patch(format!("{}/salas/{}", self.base_url, id))
+            .json(&cambios)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
                 .json()
-                .map_err(|e| format!("Error parseando error: {}", e))?;
-            Err(error.error)
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
         }
     }
+
+    pub fn eliminar_sala(&self, id: &str) -> Result<(), String> {
+        let response = self
+            .client
+            .delete(format!("{}/salas/{}", self.base_url, id))
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    // Administración
+
+    /// Descarga el respaldo tal cual (JSON Lines)
+    pub fn exportar_respaldo(&self) -> Result<String, String> {
+        let response = self
+            .client
+            .get(format!("{}/admin/respaldo", self.base_url))
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .text()
+                .map_err(|e| format!("Error leyendo respaldo: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    pub fn restaurar_respaldo(
+        &self,
+        contenido: String,
+        simular: bool,
+    ) -> Result<ResumenRestauracionResponse, String> {
+        let response = self
+            .client
+            .post(format!("{}/admin/respaldo", self.base_url))
+            .query(&[("simular", simular)])
+            .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+            .body(contenido)
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    /// Ejecuta el verificador de integridad (con `reparar` cancela lo afectado)
+    pub fn verificar_integridad(&self, reparar: bool) -> Result<InformeIntegridadResponse, String> {
+        let request = if reparar {
+            self.client
+                .post(format!("{}/admin/integridad/reparar", self.base_url))
+        } else {
+            self.client
+                .get(format!("{}/admin/integridad", self.base_url))
+        };
+        let response = request
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    pub fn listar_auditoria(&self) -> Result<Vec<IntentoDenegadoResponse>, String> {
+        let response = self
+            .client
+            .get(format!("{}/admin/auditoria", self.base_url))
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+
+    pub fn metricas_limites(&self) -> Result<MetricasLimitesResponse, String> {
+        let response = self
+            .client
+            .get(format!("{}/admin/limites", self.base_url))
+            .send()
+            .map_err(|e| format!("Error de conexión: {}", e))?;
+
+        if response.status().is_success() {
+            response
+                .json()
+                .map_err(|e| format!("Error parseando respuesta: {}", e))
+        } else {
+            Err(leer_problema(response))
+        }
+    }
+}
+
+/// Mensaje de error de una respuesta fallida. La API responde con
+/// `application/problem+json`; si el cuerpo no lo es (p. ej. un proxy
+/// intermedio), se usa el código de estado.
+fn leer_problema(response: reqwest::blocking::Response) -> String {
+    let status = response.status();
+    match response.json::<ProblemaResponse>() {
+        Ok(problema) => problema.mensaje(),
+        Err(_) => format!("Error HTTP {}", status),
+    }
 }
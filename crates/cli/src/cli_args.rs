@@ -1,19 +1,20 @@
 // Definición de argumentos y comandos del CLI usando clap
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "reservas")]
 #[command(about = "CLI para el Sistema de Reservas de Empleados", long_about = None)]
 #[command(version)]
 pub struct Cli {
-    #[arg(
-        short,
-        long,
-        default_value = "http://localhost:3000/api",
-        global = true
-    )]
-    pub url: String,
+    /// URL de la API [por defecto: http://localhost:3000/api]
+    #[arg(short, long, env = "RESERVAS_API_URL", global = true)]
+    pub url: Option<String>,
+
+    /// Clave de API o JWT para autenticarse
+    #[arg(long, env = "RESERVAS_TOKEN", hide_env_values = true, global = true)]
+    pub token: Option<String>,
 
     #[command(subcommand)]
     pub command: Commands,
@@ -29,14 +30,17 @@ pub enum Commands {
     #[command(subcommand)]
     Reserva(ReservaCommands),
 
-    /// Ver disponibilidad
-    Disponibilidad {
-        /// Fecha en formato YYYY-MM-DD
-        #[arg(short, long)]
-        fecha: String,
-    },
+    /// Ver disponibilidad de un día, una semana o un mes
+    Disponibilidad(DisponibilidadArgs),
     #[command(subcommand)]
     Sala(SalaCommands),
+
+    /// Informe de ocupación de un periodo (por defecto, el mes en curso)
+    Informe(InformeArgs),
+
+    /// Administración: copias de seguridad
+    #[command(subcommand)]
+    Admin(AdminCommands),
 }
 
 #[derive(Subcommand)]
@@ -52,8 +56,8 @@ pub enum EmpleadoCommands {
         email: String,
     },
 
-    /// Listar todos los empleados
-    Listar,
+    /// Listar empleados (ordenados por nombre, paginados)
+    Listar(FiltrosEmpleadosArgs),
 
     /// Obtener información de un empleado
     Obtener {
@@ -75,6 +79,38 @@ pub enum EmpleadoCommands {
         #[arg(short, long)]
         id: String,
     },
+
+    /// Editar un empleado (solo cambian los campos indicados)
+    Editar {
+        /// ID del empleado
+        #[arg(short, long)]
+        id: String,
+
+        /// Nuevo nombre
+        #[arg(short, long)]
+        nombre: Option<String>,
+
+        /// Nuevo email
+        #[arg(short, long)]
+        email: Option<String>,
+
+        /// ID del responsable que confirma sus reservas ("" para quitarlo)
+        #[arg(long)]
+        responsable: Option<String>,
+    },
+
+    /// Eliminar un empleado sin reservas
+    Eliminar {
+        /// ID del empleado
+        #[arg(short, long)]
+        id: String,
+    },
+
+    /// Crear o actualizar empleados (por email) desde una hoja CSV o XLSX
+    Importar(ImportarHojaArgs),
+
+    /// Descargar todos los empleados a una hoja CSV o XLSX
+    Exportar(ExportarHojaArgs),
 }
 
 #[derive(Subcommand)]
@@ -96,10 +132,14 @@ pub enum ReservaCommands {
         /// Descripción de la reserva
         #[arg(short, long)]
         descripcion: String,
+
+        /// ID de la sala a reservar (opcional)
+        #[arg(short, long)]
+        sala_id: Option<String>,
     },
 
-    /// Listar todas las reservas
-    Listar,
+    /// Listar reservas con filtros, orden y paginación
+    Listar(FiltrosReservasArgs),
 
     /// Listar reservas de un empleado
     ListarEmpleado {
@@ -121,19 +161,338 @@ pub enum ReservaCommands {
         #[arg(short, long)]
         id: String,
     },
+
+    /// Mover una reserva a otro slot conservando su ID
+    Reprogramar {
+        /// ID de la reserva
+        #[arg(short, long)]
+        id: String,
+
+        /// Nueva fecha en formato YYYY-MM-DD
+        #[arg(short, long)]
+        fecha: String,
+
+        /// Nueva hora (9-17)
+        #[arg(long)]
+        hora: u32,
+    },
+
+    /// Editar la descripción de una reserva
+    Editar {
+        /// ID de la reserva
+        #[arg(short, long)]
+        id: String,
+
+        /// Nueva descripción
+        #[arg(short, long)]
+        descripcion: Option<String>,
+    },
+
+    /// Eliminar una reserva (para conservar el historial, mejor cancelarla)
+    Eliminar {
+        /// ID de la reserva
+        #[arg(short, long)]
+        id: String,
+    },
+
+    /// Importar reservas desde un fichero iCalendar (.ics)
+    Importar {
+        /// Fichero iCalendar
+        #[arg(short, long)]
+        entrada: PathBuf,
+
+        /// Solo mostrar lo que se importaría, sin crear reservas
+        #[arg(long)]
+        simular: bool,
+
+        /// Desfase respecto a UTC de las horas sin zona del fichero
+        #[arg(long, default_value = "+00:00", allow_hyphen_values = true)]
+        desfase: String,
+    },
+
+    /// Descargar todas las reservas a una hoja CSV o XLSX
+    Exportar(ExportarHojaArgs),
 }
 
 #[derive(Subcommand)]
 pub enum SalaCommands {
-    /// Listar todas las salas
-    Listar,
+    /// Listar salas (ordenadas por nombre, paginadas)
+    Listar(FiltrosSalasArgs),
     //  /// Crear nueva sala
     Crear {
         nombre: String,
         capacidad: u32,
     },
+    /// Editar una sala (solo cambian los campos indicados)
+    Editar {
+        /// ID de la sala
+        #[arg(short, long)]
+        id: String,
+
+        /// Nuevo nombre
+        #[arg(short, long)]
+        nombre: Option<String>,
+
+        /// Nueva capacidad
+        #[arg(short, long)]
+        capacidad: Option<u32>,
+    },
+    /// Eliminar una sala sin reservas
+    Eliminar {
+        /// ID de la sala
+        #[arg(short, long)]
+        id: String,
+    },
+    /// Crear o actualizar salas (por nombre) desde una hoja CSV o XLSX
+    Importar(ImportarHojaArgs),
+    /// Descargar todas las salas a una hoja CSV o XLSX
+    Exportar(ExportarHojaArgs),
+    /// Ocupación de las salas por horas o, con --desde, salas libres en una franja
+    Disponibilidad(DisponibilidadSalasArgs),
     // /// Activar sala
     // Activar { id: String },
     // /// Desactivar sala
     // Desactivar { id: String },
 }
+
+#[derive(Subcommand)]
+pub enum AdminCommands {
+    /// Descargar un respaldo completo a un fichero JSON Lines
+    Backup {
+        /// Fichero de destino
+        #[arg(short, long, default_value = "reservas-respaldo.jsonl")]
+        salida: PathBuf,
+    },
+
+    /// Restaurar un respaldo (crea lo que falta y sobrescribe lo existente)
+    Restore {
+        /// Fichero de respaldo
+        #[arg(short, long)]
+        entrada: PathBuf,
+
+        /// Solo validar el respaldo, sin escribir nada
+        #[arg(long)]
+        simular: bool,
+    },
+
+    /// Verificar la integridad de los datos (referencias, solapamientos, horario)
+    Fsck {
+        /// Cancelar las reservas activas afectadas
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Listar las operaciones denegadas por falta de permisos
+    Auditoria,
+
+    /// Mostrar las peticiones admitidas y rechazadas por los límites
+    Limites,
+}
+
+#[derive(Args, Debug)]
+pub struct DisponibilidadArgs {
+    /// Fecha en formato YYYY-MM-DD
+    #[arg(short, long)]
+    pub fecha: String,
+
+    /// Toda la semana (de lunes a domingo) de la fecha
+    #[arg(long, conflicts_with = "mes")]
+    pub semana: bool,
+
+    /// Todo el mes de la fecha
+    #[arg(long)]
+    pub mes: bool,
+
+    /// Solo estos empleados (repetible)
+    #[arg(short, long = "empleado")]
+    pub empleados: Vec<String>,
+
+    /// Solo el equipo de este responsable (él y quienes le reportan)
+    #[arg(long)]
+    pub equipo: Option<String>,
+
+    /// Incluir estas salas (repetible)
+    #[arg(short, long = "sala")]
+    pub salas: Vec<String>,
+}
+
+impl DisponibilidadArgs {
+    /// Sin vista de semana o mes ni filtros se muestra la tabla clásica del día
+    pub fn es_matriz(&self) -> bool {
+        self.semana
+            || self.mes
+            || !self.empleados.is_empty()
+            || self.equipo.is_some()
+            || !self.salas.is_empty()
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct DisponibilidadSalasArgs {
+    /// Fecha en formato YYYY-MM-DD
+    #[arg(short, long)]
+    pub fecha: String,
+
+    /// Toda la semana (de lunes a domingo) de la fecha
+    #[arg(long, conflicts_with_all = ["mes", "desde"])]
+    pub semana: bool,
+
+    /// Todo el mes de la fecha
+    #[arg(long, conflicts_with = "desde")]
+    pub mes: bool,
+
+    /// Buscar salas libres desde esta hora (9 a 17)
+    #[arg(short, long)]
+    pub desde: Option<u32>,
+
+    /// Hora de fin de la búsqueda, no incluida; por defecto, una hora
+    #[arg(long, requires = "desde")]
+    pub hasta: Option<u32>,
+
+    /// Personas que tienen que caber
+    #[arg(short, long, requires = "desde", default_value_t = 1)]
+    pub capacidad: u32,
+}
+
+#[derive(Args, Debug)]
+pub struct InformeArgs {
+    /// Primer día (YYYY-MM-DD)
+    #[arg(short, long, conflicts_with_all = ["semana", "mes"])]
+    pub desde: Option<String>,
+
+    /// Último día incluido (YYYY-MM-DD); por defecto, el de --desde
+    #[arg(long, requires = "desde")]
+    pub hasta: Option<String>,
+
+    /// Semana de lunes a domingo que contiene este día (YYYY-MM-DD)
+    #[arg(long, conflicts_with = "mes")]
+    pub semana: Option<String>,
+
+    /// Mes natural (YYYY-MM)
+    #[arg(short, long)]
+    pub mes: Option<String>,
+
+    /// Guardar una parte del informe en este fichero CSV o XLSX en lugar
+    /// de mostrarlo
+    #[arg(short, long)]
+    pub salida: Option<PathBuf>,
+
+    /// Parte que se guarda: resumen, empleados, salas, horas o dias
+    #[arg(long, requires = "salida", default_value = "resumen")]
+    pub desglose: String,
+}
+
+/// Opciones de las cargas masivas desde hojas de cálculo
+#[derive(Args, Debug)]
+pub struct ImportarHojaArgs {
+    /// Fichero CSV o XLSX; la primera fila son los nombres de columna
+    #[arg(short, long)]
+    pub entrada: PathBuf,
+
+    /// Columna de la que se lee un campo, como campo=Columna (repetible).
+    /// Sin ella, cada campo se busca en la columna con su nombre.
+    #[arg(short, long = "columna")]
+    pub columnas: Vec<String>,
+
+    /// Solo mostrar lo que se haría, sin guardar cambios
+    #[arg(long)]
+    pub simular: bool,
+
+    /// csv o xlsx (por defecto, según la extensión del fichero)
+    #[arg(long)]
+    pub formato: Option<String>,
+}
+
+/// Opciones de las exportaciones a hojas de cálculo
+#[derive(Args, Debug)]
+pub struct ExportarHojaArgs {
+    /// Fichero de destino [por defecto: <entidad>.<formato>]
+    #[arg(short, long)]
+    pub salida: Option<PathBuf>,
+
+    /// csv o xlsx (por defecto, según la extensión de la salida, o csv)
+    #[arg(long)]
+    pub formato: Option<String>,
+}
+
+/// Opciones comunes de paginación
+#[derive(Args, Debug, Default)]
+pub struct PaginacionArgs {
+    /// Cursor devuelto por la página anterior
+    #[arg(long)]
+    pub cursor: Option<String>,
+
+    /// Tamaño de página (por defecto 50, máximo 200)
+    #[arg(long)]
+    pub limite: Option<usize>,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct FiltrosReservasArgs {
+    /// Primer día incluido (YYYY-MM-DD)
+    #[arg(long)]
+    pub desde: Option<String>,
+
+    /// Último día incluido (YYYY-MM-DD)
+    #[arg(long)]
+    pub hasta: Option<String>,
+
+    /// Estado: pendiente, confirmada o cancelada
+    #[arg(long)]
+    pub estado: Option<String>,
+
+    /// ID del empleado
+    #[arg(short, long)]
+    pub empleado_id: Option<String>,
+
+    /// ID de la sala
+    #[arg(short, long)]
+    pub sala_id: Option<String>,
+
+    /// Texto a buscar en la descripción
+    #[arg(short, long)]
+    pub buscar: Option<String>,
+
+    /// Campo de orden: inicio, empleado o estado
+    #[arg(long)]
+    pub orden: Option<String>,
+
+    /// Orden descendente
+    #[arg(long)]
+    pub desc: bool,
+
+    #[command(flatten)]
+    pub paginacion: PaginacionArgs,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct FiltrosEmpleadosArgs {
+    /// Texto a buscar en nombre o email
+    #[arg(short, long)]
+    pub buscar: Option<String>,
+
+    /// Filtrar por estado (true = activos, false = inactivos)
+    #[arg(long)]
+    pub activo: Option<bool>,
+
+    #[command(flatten)]
+    pub paginacion: PaginacionArgs,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct FiltrosSalasArgs {
+    /// Texto a buscar en el nombre
+    #[arg(short, long)]
+    pub buscar: Option<String>,
+
+    /// Filtrar por estado (true = activas, false = inactivas)
+    #[arg(long)]
+    pub activa: Option<bool>,
+
+    /// Capacidad mínima
+    #[arg(long)]
+    pub capacidad_minima: Option<u32>,
+
+    #[command(flatten)]
+    pub paginacion: PaginacionArgs,
+}
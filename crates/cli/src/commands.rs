@@ -1,9 +1,32 @@
 // Comandos del CLI
 
 use crate::api_client::ApiClient;
-use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use crate::cli_args::{
+    DisponibilidadArgs, DisponibilidadSalasArgs, ExportarHojaArgs, FiltrosEmpleadosArgs,
+    FiltrosReservasArgs, FiltrosSalasArgs, ImportarHojaArgs, InformeArgs,
+};
+use crate::response::{
+    BuscarSalasLibresQuery, FilaMatrizResponse, InformeQuery, ListarEmpleadosQuery,
+    ListarReservasQuery, ListarSalasQuery, MatrizDisponibilidadQuery, MatrizDisponibilidadResponse,
+    ModificarEmpleadoRequest, ModificarReservaRequest, ModificarSalaRequest, UsoRecursoResponse,
+};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Utc};
 use colored::Colorize;
+use std::fs;
+use std::path::PathBuf;
 use tabled::{Table, Tabled};
+
+/// Indica cómo pedir la siguiente página, si la hay
+fn mostrar_siguiente_pagina(siguiente_cursor: Option<String>) {
+    if let Some(cursor) = siguiente_cursor {
+        println!(
+            "{} {}",
+            "Hay más resultados. Siguiente página con:".yellow(),
+            format!("--cursor {}", cursor).bold()
+        );
+    }
+}
+
 // ============= Comandos de Empleados =============
 
 #[derive(Tabled)]
@@ -35,11 +58,19 @@ pub fn crear_empleado(client: &ApiClient, nombre: String, email: String) {
     }
 }
 
-pub fn listar_empleados(client: &ApiClient) {
+pub fn listar_empleados(client: &ApiClient, filtros: FiltrosEmpleadosArgs) {
     println!("{}", "Obteniendo lista de empleados...".cyan());
 
-    match client.listar_empleados() {
-        Ok(empleados) => {
+    let query = ListarEmpleadosQuery {
+        q: filtros.buscar,
+        activo: filtros.activo,
+        cursor: filtros.paginacion.cursor,
+        limite: filtros.paginacion.limite,
+    };
+
+    match client.listar_empleados(&query) {
+        Ok(pagina) => {
+            let empleados = pagina.elementos;
             if empleados.is_empty() {
                 println!("{}", "No hay empleados registrados".yellow());
                 return;
@@ -63,6 +94,7 @@ pub fn listar_empleados(client: &ApiClient) {
             let table = Table::new(rows).to_string();
             println!("\n{}", table);
             println!("\n{} empleado(s) encontrado(s)", count);
+            mostrar_siguiente_pagina(pagina.siguiente_cursor);
         }
         Err(e) => {
             println!("{} {}", "✗ Error:".red(), e);
@@ -80,6 +112,9 @@ pub fn obtener_empleado(client: &ApiClient, id: String) {
             println!("  Nombre: {}", empleado.nombre);
             println!("  Email: {}", empleado.email);
             println!("  Activo: {}", if empleado.activo { "Sí" } else { "No" });
+            if let Some(responsable) = empleado.responsable_id {
+                println!("  Responsable: {}", responsable);
+            }
         }
         Err(e) => {
             println!("{} {}", "✗ Error:".red(), e);
@@ -115,6 +150,45 @@ pub fn desactivar_empleado(client: &ApiClient, id: String) {
     }
 }
 
+pub fn editar_empleado(
+    client: &ApiClient,
+    id: String,
+    nombre: Option<String>,
+    email: Option<String>,
+    responsable_id: Option<String>,
+) {
+    println!("{}", "Editando empleado...".cyan());
+
+    let cambios = ModificarEmpleadoRequest {
+        nombre,
+        email,
+        responsable_id,
+    };
+    match client.modificar_empleado(&id, cambios) {
+        Ok(empleado) => {
+            println!("{}", "✓ Empleado actualizado exitosamente".green());
+            println!("  {}: {}", "Nombre".bold(), empleado.nombre);
+            println!("  {}: {}", "Email".bold(), empleado.email);
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+pub fn eliminar_empleado(client: &ApiClient, id: String) {
+    println!("{}", "Eliminando empleado...".cyan());
+
+    match client.eliminar_empleado(&id) {
+        Ok(()) => {
+            println!("{}", "✓ Empleado eliminado exitosamente".green());
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
 // ============= Comandos de Reservas =============
 
 #[derive(Tabled)]
@@ -129,39 +203,39 @@ struct ReservaRow {
     horario: String,
     #[tabled(rename = "Descripción")]
     descripcion: String,
+    #[tabled(rename = "Sala")]
+    sala: String,
     #[tabled(rename = "Estado")]
     estado: String,
 }
 
+/// Inicio del slot a partir de fecha (YYYY-MM-DD) y hora
+fn inicio_slot(fecha: &str, hora: u32) -> Result<DateTime<Utc>, String> {
+    let date = NaiveDate::parse_from_str(fecha, "%Y-%m-%d")
+        .map_err(|_| "Formato de fecha inválido. Use YYYY-MM-DD".to_string())?;
+    let time = NaiveTime::from_hms_opt(hora, 0, 0).ok_or_else(|| "Hora inválida".to_string())?;
+    Ok(Utc.from_utc_datetime(&date.and_time(time)))
+}
+
 pub fn crear_reserva(
     client: &ApiClient,
     empleado_id: String,
     fecha: String,
     hora: u32,
     descripcion: String,
+    sala_id: Option<String>,
 ) {
     println!("{}", "Creando reserva...".cyan());
 
-    // Parsear fecha
-    let date = match NaiveDate::parse_from_str(&fecha, "%Y-%m-%d") {
+    let datetime = match inicio_slot(&fecha, hora) {
         Ok(d) => d,
-        Err(_) => {
-            println!("{} Formato de fecha inválido. Use YYYY-MM-DD", "✗".red());
-            return;
-        }
-    };
-
-    let time = match NaiveTime::from_hms_opt(hora, 0, 0) {
-        Some(t) => t,
-        None => {
-            println!("{} Hora inválida", "✗".red());
+        Err(e) => {
+            println!("{} {}", "✗".red(), e);
             return;
         }
     };
 
-    let datetime = Utc.from_utc_datetime(&date.and_time(time));
-
-    match client.crear_reserva(empleado_id, datetime, descripcion) {
+    match client.crear_reserva(empleado_id, datetime, descripcion, sala_id) {
         Ok(reserva) => {
             println!("{}", "✓ Reserva creada exitosamente".green());
             println!("  ID: {}", reserva.id);
@@ -171,6 +245,9 @@ pub fn crear_reserva(
                 reserva.slot_fin.format("%H:%M")
             );
             println!("  Descripción: {}", reserva.descripcion);
+            if let Some(sala_id) = reserva.sala_id {
+                println!("  Sala: {}", sala_id);
+            }
             println!("  Estado: {}", reserva.estado);
         }
         Err(e) => {
@@ -179,11 +256,25 @@ pub fn crear_reserva(
     }
 }
 
-pub fn listar_reservas(client: &ApiClient) {
+pub fn listar_reservas(client: &ApiClient, filtros: FiltrosReservasArgs) {
     println!("{}", "Obteniendo lista de reservas...".cyan());
 
-    match client.listar_reservas() {
-        Ok(reservas) => {
+    let query = ListarReservasQuery {
+        desde: filtros.desde,
+        hasta: filtros.hasta,
+        estado: filtros.estado,
+        empleado_id: filtros.empleado_id,
+        sala_id: filtros.sala_id,
+        q: filtros.buscar,
+        orden: filtros.orden,
+        direccion: filtros.desc.then(|| "desc".to_string()),
+        cursor: filtros.paginacion.cursor,
+        limite: filtros.paginacion.limite,
+    };
+
+    match client.listar_reservas(&query) {
+        Ok(pagina) => {
+            let reservas = pagina.elementos;
             if reservas.is_empty() {
                 println!("{}", "No hay reservas registradas".yellow());
                 return;
@@ -201,6 +292,7 @@ pub fn listar_reservas(client: &ApiClient) {
                         r.slot_fin.format("%H:%M")
                     ),
                     descripcion: r.descripcion,
+                    sala: r.sala_id.unwrap_or_else(|| "-".to_string()),
                     estado: r.estado,
                 })
                 .collect();
@@ -209,6 +301,7 @@ pub fn listar_reservas(client: &ApiClient) {
             let table = Table::new(rows).to_string();
             println!("\n{}", table);
             println!("\n{} reserva(s) encontrada(s)", count);
+            mostrar_siguiente_pagina(pagina.siguiente_cursor);
         }
         Err(e) => {
             println!("{} {}", "✗ Error:".red(), e);
@@ -241,6 +334,7 @@ pub fn listar_reservas_empleado(client: &ApiClient, empleado_id: String) {
                         r.slot_fin.format("%H:%M")
                     ),
                     descripcion: r.descripcion,
+                    sala: r.sala_id.unwrap_or_else(|| "-".to_string()),
                     estado: r.estado,
                 })
                 .collect();
@@ -284,9 +378,144 @@ pub fn cancelar_reserva(client: &ApiClient, id: String) {
     }
 }
 
+pub fn reprogramar_reserva(client: &ApiClient, id: String, fecha: String, hora: u32) {
+    println!("{}", "Reprogramando reserva...".cyan());
+
+    let datetime = match inicio_slot(&fecha, hora) {
+        Ok(d) => d,
+        Err(e) => {
+            println!("{} {}", "✗".red(), e);
+            return;
+        }
+    };
+
+    match client.reprogramar_reserva(&id, datetime) {
+        Ok(reserva) => {
+            println!("{}", "✓ Reserva reprogramada exitosamente".green());
+            println!(
+                "  Slot: {} - {}",
+                reserva.slot_inicio.format("%Y-%m-%d %H:%M"),
+                reserva.slot_fin.format("%H:%M")
+            );
+            println!("  Estado: {}", reserva.estado);
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+pub fn editar_reserva(client: &ApiClient, id: String, descripcion: Option<String>) {
+    println!("{}", "Editando reserva...".cyan());
+
+    match client.modificar_reserva(&id, ModificarReservaRequest { descripcion }) {
+        Ok(reserva) => {
+            println!("{}", "✓ Reserva actualizada exitosamente".green());
+            println!("  {}: {}", "Descripción".bold(), reserva.descripcion);
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+pub fn eliminar_reserva(client: &ApiClient, id: String) {
+    println!("{}", "Eliminando reserva...".cyan());
+
+    match client.eliminar_reserva(&id) {
+        Ok(()) => {
+            println!("{}", "✓ Reserva eliminada exitosamente".green());
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct ElementoImportacionRow {
+    #[tabled(rename = "Evento")]
+    uid: String,
+    #[tabled(rename = "Asistente")]
+    email: String,
+    #[tabled(rename = "Slot")]
+    slot: String,
+    #[tabled(rename = "Resultado")]
+    resultado: String,
+    #[tabled(rename = "Detalle")]
+    detalle: String,
+}
+
+pub fn importar_reservas(client: &ApiClient, entrada: PathBuf, simular: bool, desfase: String) {
+    let contenido = match fs::read_to_string(&entrada) {
+        Ok(contenido) => contenido,
+        Err(e) => {
+            println!("{} {}", "✗ Error leyendo el fichero:".red(), e);
+            return;
+        }
+    };
+
+    if simular {
+        println!("{}", "Revisando calendario (simulación)...".cyan());
+    } else {
+        println!("{}", "Importando reservas...".cyan());
+    }
+
+    match client.importar_reservas(contenido, simular, &desfase) {
+        Ok(informe) => {
+            let rows: Vec<ElementoImportacionRow> = informe
+                .elementos
+                .into_iter()
+                .map(|e| ElementoImportacionRow {
+                    uid: e.uid,
+                    email: e.email.unwrap_or_default(),
+                    slot: e
+                        .slot_inicio
+                        .map(|s| s.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default(),
+                    resultado: match e.resultado.as_str() {
+                        "importada" => e.resultado.green(),
+                        "conflicto" => e.resultado.red(),
+                        _ => e.resultado.yellow(),
+                    }
+                    .to_string(),
+                    detalle: e.detalle.unwrap_or_default(),
+                })
+                .collect();
+            if !rows.is_empty() {
+                println!("{}", Table::new(rows));
+            }
+
+            println!("  Eventos: {}", informe.eventos);
+            println!("  Importadas: {}", informe.importadas);
+            println!("  Conflictos: {}", informe.conflictos);
+            println!("  Omitidas: {}", informe.omitidas);
+            if informe.simulado {
+                println!(
+                    "{}",
+                    "✓ Simulación terminada, no se ha creado ninguna reserva".green()
+                );
+            } else {
+                println!(
+                    "{}",
+                    format!("✓ {} reservas creadas", informe.importadas).green()
+                );
+            }
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
 // ============= Comandos de Disponibilidad =============
 
-pub fn ver_disponibilidad(client: &ApiClient, fecha: String) {
+pub fn ver_disponibilidad(client: &ApiClient, args: DisponibilidadArgs) {
+    if args.es_matriz() {
+        ver_matriz_disponibilidad(client, args);
+        return;
+    }
+    let fecha = args.fecha;
     println!(
         "{}",
         format!("Obteniendo disponibilidad para {}...", fecha).cyan()
@@ -352,6 +581,155 @@ pub fn ver_disponibilidad(client: &ApiClient, fecha: String) {
     }
 }
 
+const DIAS_SEMANA: [&str; 7] = ["Lu", "Ma", "Mi", "Ju", "Vi", "Sá", "Do"];
+
+/// Semana o mes (o un día con filtros) como matriz de empleados y salas
+fn ver_matriz_disponibilidad(client: &ApiClient, args: DisponibilidadArgs) {
+    let fecha = match NaiveDate::parse_from_str(&args.fecha, "%Y-%m-%d") {
+        Ok(fecha) => fecha,
+        Err(_) => {
+            println!("{} Fecha inválida. Use YYYY-MM-DD", "✗ Error:".red());
+            return;
+        }
+    };
+    let mut query = MatrizDisponibilidadQuery {
+        empleados: (!args.empleados.is_empty()).then(|| args.empleados.join(",")),
+        responsable_id: args.equipo,
+        salas: (!args.salas.is_empty()).then(|| args.salas.join(",")),
+        ..Default::default()
+    };
+    if args.semana {
+        query.semana = Some(args.fecha);
+    } else if args.mes {
+        query.mes = Some(fecha.format("%Y-%m").to_string());
+    } else {
+        query.desde = Some(args.fecha);
+    }
+
+    println!("{}", "Obteniendo disponibilidad...".cyan());
+
+    let matriz = match client.matriz_disponibilidad(&query) {
+        Ok(matriz) => matriz,
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+            return;
+        }
+    };
+    println!(
+        "\n{}",
+        format!("Disponibilidad - {} a {}", matriz.desde, matriz.hasta)
+            .green()
+            .bold()
+    );
+    if matriz.filas.is_empty() {
+        println!("{}", "No hay empleados ni salas que mostrar".yellow());
+        return;
+    }
+    imprimir_matriz(&matriz);
+}
+
+/// Bloques de horas por día hasta una semana; más días, las horas libres de
+/// cada uno
+fn imprimir_matriz(matriz: &MatrizDisponibilidadResponse) {
+    let dias: Vec<NaiveDate> = matriz
+        .dias
+        .iter()
+        .filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .collect();
+    let ancho_nombre = matriz
+        .filas
+        .iter()
+        .map(|f| nombre_fila(f).chars().count())
+        .max()
+        .unwrap_or(0);
+    let horas = matriz.horas.len();
+
+    if dias.len() <= 7 {
+        // Un bloque por día con un carácter por hora
+        print!("{:ancho_nombre$}", "");
+        for dia in &dias {
+            let cabecera = format!(
+                "{} {}",
+                DIAS_SEMANA[dia.weekday().num_days_from_monday() as usize],
+                dia.format("%d/%m")
+            );
+            print!(" {:<horas$}", cabecera);
+        }
+        println!();
+        for fila in &matriz.filas {
+            print!("{:<ancho_nombre$}", nombre_fila(fila));
+            for dia in &fila.ocupacion {
+                let celdas: String = dia
+                    .chars()
+                    .map(|c| match c {
+                        'P' => "P".yellow().to_string(),
+                        'C' => "C".red().to_string(),
+                        _ => "·".green().to_string(),
+                    })
+                    .collect();
+                print!(" {}", celdas);
+            }
+            println!();
+        }
+        println!(
+            "\n  {} libre  {} pendiente  {} confirmada  (horas {}:00 a {}:00 UTC)",
+            "·".green(),
+            "P".yellow(),
+            "C".red(),
+            matriz.horas.first().unwrap_or(&0),
+            matriz.horas.last().map(|h| h + 1).unwrap_or(0)
+        );
+    } else {
+        // Un carácter por día: las horas libres que quedan
+        for linea in [
+            dias.iter()
+                .map(|d| {
+                    let decena = d.day() / 10;
+                    if decena == 0 {
+                        " ".to_string()
+                    } else {
+                        decena.to_string()
+                    }
+                })
+                .collect::<String>(),
+            dias.iter().map(|d| (d.day() % 10).to_string()).collect(),
+        ] {
+            println!("{:ancho_nombre$} {}", "", linea);
+        }
+        for fila in &matriz.filas {
+            let celdas: String = fila
+                .ocupacion
+                .iter()
+                .map(|dia| {
+                    let libres = dia.chars().filter(|c| *c == '.').count();
+                    let texto = libres.to_string();
+                    if libres == 0 {
+                        texto.red().to_string()
+                    } else if libres < horas {
+                        texto.yellow().to_string()
+                    } else {
+                        texto.green().to_string()
+                    }
+                })
+                .collect();
+            println!("{:<ancho_nombre$} {}", nombre_fila(fila), celdas);
+        }
+        println!("\n  Horas libres de cada día (de {})", horas);
+    }
+
+    for fila in &matriz.filas {
+        println!("  {}: {} slots libres", nombre_fila(fila), fila.libres);
+    }
+}
+
+fn nombre_fila(fila: &FilaMatrizResponse) -> String {
+    if fila.tipo == "sala" {
+        format!("[{}]", fila.nombre)
+    } else {
+        fila.nombre.clone()
+    }
+}
+
 // ============= Comandos de Salas =============
 
 #[derive(Tabled)]
@@ -366,11 +744,20 @@ struct SalaRow {
     activa: String,
 }
 
-pub fn listar_salas(client: &ApiClient) {
+pub fn listar_salas(client: &ApiClient, filtros: FiltrosSalasArgs) {
     println!("{}", "Obteniendo lista de salas...".cyan());
 
-    match client.listar_salas() {
-        Ok(salas) => {
+    let query = ListarSalasQuery {
+        q: filtros.buscar,
+        activa: filtros.activa,
+        capacidad_minima: filtros.capacidad_minima,
+        cursor: filtros.paginacion.cursor,
+        limite: filtros.paginacion.limite,
+    };
+
+    match client.listar_salas(&query) {
+        Ok(pagina) => {
+            let salas = pagina.elementos;
             if salas.is_empty() {
                 println!("{}", "No hay salas registradas".yellow());
                 return;
@@ -394,6 +781,7 @@ pub fn listar_salas(client: &ApiClient) {
             let table = Table::new(rows).to_string();
             println!("\n{}", table);
             println!("\n{} sala(s) encontrada(s)", count);
+            mostrar_siguiente_pagina(pagina.siguiente_cursor);
         }
         Err(e) => {
             println!("{} {}", "✗ Error:".red(), e);
@@ -417,3 +805,575 @@ pub fn crear_sala(client: &ApiClient, nombre: String, capacidad: u32) {
         }
     }
 }
+
+pub fn editar_sala(client: &ApiClient, id: String, nombre: Option<String>, capacidad: Option<u32>) {
+    println!("{}", "Editando sala...".cyan());
+
+    match client.modificar_sala(&id, ModificarSalaRequest { nombre, capacidad }) {
+        Ok(sala) => {
+            println!("{}", "✓ Sala actualizada exitosamente".green());
+            println!("  {}: {}", "Nombre".bold(), sala.nombre);
+            println!("  {}: {}", "Capacidad".bold(), sala.capacidad);
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+pub fn eliminar_sala(client: &ApiClient, id: String) {
+    println!("{}", "Eliminando sala...".cyan());
+
+    match client.eliminar_sala(&id) {
+        Ok(()) => {
+            println!("{}", "✓ Sala eliminada exitosamente".green());
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+/// Ocupación de las salas (día, semana o mes) o, con `--desde`, las salas
+/// libres en esa franja con sitio para `--capacidad` personas
+pub fn disponibilidad_salas(client: &ApiClient, args: DisponibilidadSalasArgs) {
+    let fecha = match NaiveDate::parse_from_str(&args.fecha, "%Y-%m-%d") {
+        Ok(fecha) => fecha,
+        Err(_) => {
+            println!("{} Fecha inválida. Use YYYY-MM-DD", "✗ Error:".red());
+            return;
+        }
+    };
+
+    if let Some(desde) = args.desde {
+        let query = BuscarSalasLibresQuery {
+            fecha: args.fecha,
+            hora_inicio: desde,
+            hora_fin: args.hasta.unwrap_or(desde + 1),
+            capacidad_minima: args.capacidad,
+        };
+        println!("{}", "Buscando salas libres...".cyan());
+        match client.buscar_salas_libres(&query) {
+            Ok(salas) if salas.is_empty() => {
+                println!(
+                    "{}",
+                    "No hay ninguna sala libre en esa franja con sitio suficiente".yellow()
+                );
+            }
+            Ok(salas) => {
+                let rows: Vec<SalaRow> = salas
+                    .into_iter()
+                    .map(|s| SalaRow {
+                        id: s.id,
+                        nombre: s.nombre,
+                        capacidad: s.capacidad,
+                        activa: "Sí".to_string(),
+                    })
+                    .collect();
+                println!(
+                    "\n{}",
+                    format!(
+                        "Salas libres el {} de {}:00 a {}:00",
+                        query.fecha, query.hora_inicio, query.hora_fin
+                    )
+                    .green()
+                    .bold()
+                );
+                println!("{}", Table::new(rows));
+            }
+            Err(e) => {
+                println!("{} {}", "✗ Error:".red(), e);
+            }
+        }
+        return;
+    }
+
+    let mut query = MatrizDisponibilidadQuery::default();
+    if args.semana {
+        query.semana = Some(args.fecha);
+    } else if args.mes {
+        query.mes = Some(fecha.format("%Y-%m").to_string());
+    } else {
+        query.desde = Some(args.fecha);
+    }
+
+    println!("{}", "Obteniendo ocupación de las salas...".cyan());
+
+    match client.disponibilidad_salas(&query) {
+        Ok(matriz) if matriz.filas.is_empty() => {
+            println!("{}", "No hay salas activas".yellow());
+        }
+        Ok(matriz) => {
+            println!(
+                "\n{}",
+                format!("Salas - {} a {}", matriz.desde, matriz.hasta)
+                    .green()
+                    .bold()
+            );
+            imprimir_matriz(&matriz);
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+// ============= Comandos de hojas de cálculo =============
+
+#[derive(Tabled)]
+struct FilaCargaRow {
+    #[tabled(rename = "Fila")]
+    fila: usize,
+    #[tabled(rename = "Clave")]
+    clave: String,
+    #[tabled(rename = "Resultado")]
+    resultado: String,
+    #[tabled(rename = "Errores")]
+    errores: String,
+}
+
+/// Carga masiva de `empleados` o `salas`
+pub fn importar_hoja(client: &ApiClient, entidad: &str, args: ImportarHojaArgs) {
+    let contenido = match fs::read(&args.entrada) {
+        Ok(contenido) => contenido,
+        Err(e) => {
+            println!("{} {}", "✗ Error leyendo el fichero:".red(), e);
+            return;
+        }
+    };
+    let nombre_fichero = args
+        .entrada
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    if args.simular {
+        println!(
+            "{}",
+            format!("Revisando {} (simulación)...", entidad).cyan()
+        );
+    } else {
+        println!("{}", format!("Importando {}...", entidad).cyan());
+    }
+
+    match client.importar_hoja(
+        entidad,
+        nombre_fichero,
+        contenido,
+        &args.columnas,
+        args.simular,
+        args.formato.as_deref(),
+    ) {
+        Ok(informe) => {
+            let rows: Vec<FilaCargaRow> = informe
+                .filas
+                .into_iter()
+                .map(|f| FilaCargaRow {
+                    fila: f.fila,
+                    clave: f.clave,
+                    resultado: match f.resultado.as_str() {
+                        "creada" | "actualizada" => f.resultado.green(),
+                        "error" => f.resultado.red(),
+                        _ => f.resultado.yellow(),
+                    }
+                    .to_string(),
+                    errores: f
+                        .errores
+                        .iter()
+                        .map(|e| format!("{}: {}", e.campo, e.mensaje))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                })
+                .collect();
+            if !rows.is_empty() {
+                println!("{}", Table::new(rows));
+            }
+
+            println!("  Creadas: {}", informe.creadas);
+            println!("  Actualizadas: {}", informe.actualizadas);
+            println!("  Sin cambios: {}", informe.sin_cambios);
+            println!("  Con errores: {}", informe.errores);
+            if informe.simulado {
+                println!(
+                    "{}",
+                    "✓ Simulación terminada, no se ha guardado nada".green()
+                );
+            } else {
+                println!(
+                    "{}",
+                    format!(
+                        "✓ {} filas aplicadas",
+                        informe.creadas + informe.actualizadas
+                    )
+                    .green()
+                );
+            }
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+/// Descarga todos los `empleados`, `salas` o `reservas`
+pub fn exportar_hoja(client: &ApiClient, entidad: &str, args: ExportarHojaArgs) {
+    // Sin formato, el de la extensión de la salida; si tampoco, CSV
+    let formato = args
+        .formato
+        .or_else(|| {
+            args.salida
+                .as_ref()
+                .and_then(|s| s.extension())
+                .map(|e| e.to_string_lossy().to_lowercase())
+        })
+        .unwrap_or_else(|| "csv".to_string());
+    let salida = args
+        .salida
+        .unwrap_or_else(|| PathBuf::from(format!("{}.{}", entidad, formato)));
+
+    println!("{}", format!("Descargando {}...", entidad).cyan());
+
+    let contenido = match client.exportar_hoja(entidad, &formato) {
+        Ok(contenido) => contenido,
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+            return;
+        }
+    };
+
+    match fs::write(&salida, &contenido) {
+        Ok(()) => {
+            println!("{} {}", "✓ Hoja guardada en".green(), salida.display());
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error escribiendo el fichero:".red(), e);
+        }
+    }
+}
+
+// ============= Comandos de Informes =============
+
+#[derive(Tabled)]
+struct UsoRow {
+    #[tabled(rename = "Nombre")]
+    nombre: String,
+    #[tabled(rename = "Reservas")]
+    reservas: usize,
+    #[tabled(rename = "Canceladas")]
+    canceladas: usize,
+    #[tabled(rename = "Ocupación")]
+    ocupacion: String,
+}
+
+fn porcentaje(fraccion: f64) -> String {
+    format!("{:.1} %", fraccion * 100.0)
+}
+
+fn tabla_usos(titulo: &str, usos: Vec<UsoRecursoResponse>) {
+    println!("\n{}", titulo.bold());
+    if usos.is_empty() {
+        println!("{}", "  Sin datos en el periodo".yellow());
+        return;
+    }
+    let rows: Vec<UsoRow> = usos
+        .into_iter()
+        .map(|u| UsoRow {
+            nombre: u.nombre,
+            reservas: u.reservas,
+            canceladas: u.canceladas,
+            ocupacion: porcentaje(u.ocupacion),
+        })
+        .collect();
+    println!("{}", Table::new(rows));
+}
+
+/// Muestra el informe de ocupación o, con `--salida`, guarda una parte en
+/// CSV o XLSX (según la extensión)
+pub fn informe(client: &ApiClient, args: InformeArgs) {
+    // Sin periodo, el mes en curso
+    let sin_periodo = args.desde.is_none() && args.semana.is_none() && args.mes.is_none();
+    let mut query = InformeQuery {
+        desde: args.desde,
+        hasta: args.hasta,
+        semana: args.semana,
+        mes: args
+            .mes
+            .or_else(|| sin_periodo.then(|| Utc::now().format("%Y-%m").to_string())),
+        ..Default::default()
+    };
+
+    if let Some(salida) = args.salida {
+        query.formato = Some(
+            salida
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| "csv".to_string()),
+        );
+        query.desglose = Some(args.desglose);
+        println!("{}", "Descargando informe...".cyan());
+        match client
+            .exportar_informe(&query)
+            .and_then(|contenido| fs::write(&salida, contenido).map_err(|e| e.to_string()))
+        {
+            Ok(()) => println!("{} {}", "✓ Informe guardado en".green(), salida.display()),
+            Err(e) => println!("{} {}", "✗ Error:".red(), e),
+        }
+        return;
+    }
+
+    println!("{}", "Obteniendo informe...".cyan());
+
+    let informe = match client.informe_ocupacion(&query) {
+        Ok(informe) => informe,
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+            return;
+        }
+    };
+    println!(
+        "\n{}",
+        format!("Ocupación - {} a {}", informe.desde, informe.hasta)
+            .green()
+            .bold()
+    );
+    println!(
+        "  {} reservas: {} confirmadas, {} pendientes, {} canceladas",
+        informe.total, informe.confirmadas, informe.pendientes, informe.canceladas
+    );
+    println!(
+        "  Tasa de cancelación: {}",
+        porcentaje(informe.tasa_cancelacion)
+    );
+    println!(
+        "  Sin confirmar al terminar: {} ({})",
+        informe.no_presentadas,
+        porcentaje(informe.tasa_no_presentacion)
+    );
+
+    tabla_usos("Por empleado", informe.por_empleado);
+    tabla_usos("Por sala", informe.por_sala);
+
+    // Barras de 30 caracteres, relativas a la hora más ocupada
+    println!("\n{}", "Por hora del día".bold());
+    let maximo = informe
+        .por_hora
+        .iter()
+        .map(|h| h.reservas)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    for hora in &informe.por_hora {
+        let barra = "█".repeat(hora.reservas * 30 / maximo);
+        println!(
+            "  {:02}:00 {:<30} {} ({})",
+            hora.hora,
+            barra.cyan(),
+            hora.reservas,
+            porcentaje(hora.ocupacion)
+        );
+    }
+
+    if !informe.dias_mas_ocupados.is_empty() {
+        println!("\n{}", "Días más ocupados".bold());
+        for dia in &informe.dias_mas_ocupados {
+            println!(
+                "  {}: {} reservas ({})",
+                dia.fecha,
+                dia.reservas,
+                porcentaje(dia.ocupacion)
+            );
+        }
+    }
+}
+
+// ============= Comandos de Administración =============
+
+pub fn backup(client: &ApiClient, salida: PathBuf) {
+    println!("{}", "Descargando respaldo...".cyan());
+
+    let contenido = match client.exportar_respaldo() {
+        Ok(contenido) => contenido,
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+            return;
+        }
+    };
+
+    match fs::write(&salida, &contenido) {
+        Ok(()) => {
+            println!("{} {}", "✓ Respaldo guardado en".green(), salida.display());
+            // La primera línea es la cabecera
+            println!(
+                "  Entidades: {}",
+                contenido.lines().count().saturating_sub(1)
+            );
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error escribiendo el fichero:".red(), e);
+        }
+    }
+}
+
+pub fn restore(client: &ApiClient, entrada: PathBuf, simular: bool) {
+    let contenido = match fs::read_to_string(&entrada) {
+        Ok(contenido) => contenido,
+        Err(e) => {
+            println!("{} {}", "✗ Error leyendo el fichero:".red(), e);
+            return;
+        }
+    };
+
+    if simular {
+        println!("{}", "Validando respaldo (simulación)...".cyan());
+    } else {
+        println!("{}", "Restaurando respaldo...".cyan());
+    }
+
+    match client.restaurar_respaldo(contenido, simular) {
+        Ok(resumen) => {
+            if resumen.simulado {
+                println!("{}", "✓ Respaldo válido, no se ha escrito nada".green());
+            } else {
+                println!("{}", "✓ Respaldo restaurado".green());
+            }
+            println!("  Empleados: {}", resumen.empleados);
+            println!("  Salas: {}", resumen.salas);
+            println!("  Reservas: {}", resumen.reservas);
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct IncidenciaRow {
+    #[tabled(rename = "Reserva")]
+    reserva_id: String,
+    #[tabled(rename = "Tipo")]
+    tipo: String,
+    #[tabled(rename = "Detalle")]
+    detalle: String,
+    #[tabled(rename = "Reparada")]
+    reparada: String,
+}
+
+pub fn fsck(client: &ApiClient, fix: bool) {
+    println!("{}", "Verificando integridad...".cyan());
+
+    match client.verificar_integridad(fix) {
+        Ok(informe) => {
+            println!("  Reservas revisadas: {}", informe.reservas_revisadas);
+            if informe.consistente {
+                println!("{}", "✓ No se han encontrado incidencias".green());
+                return;
+            }
+
+            let rows: Vec<IncidenciaRow> = informe
+                .incidencias
+                .into_iter()
+                .map(|i| IncidenciaRow {
+                    reserva_id: i.reserva_id,
+                    tipo: i.tipo,
+                    detalle: i.detalle,
+                    reparada: if i.reparada {
+                        "✓".green()
+                    } else {
+                        "✗".red()
+                    }
+                    .to_string(),
+                })
+                .collect();
+            let total = rows.len();
+            println!("{}", Table::new(rows));
+
+            if fix {
+                println!(
+                    "{} {} de {} incidencias reparadas (reservas canceladas)",
+                    "⚠".yellow(),
+                    informe.reparadas,
+                    total
+                );
+            } else {
+                println!(
+                    "{} {} incidencias. Usa --fix para cancelar las reservas afectadas",
+                    "⚠".yellow(),
+                    total
+                );
+            }
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct IntentoDenegadoRow {
+    #[tabled(rename = "Fecha")]
+    fecha: String,
+    #[tabled(rename = "Sujeto")]
+    sujeto: String,
+    #[tabled(rename = "Acción")]
+    accion: String,
+    #[tabled(rename = "Recurso")]
+    recurso: String,
+}
+
+pub fn auditoria(client: &ApiClient) {
+    println!("{}", "Obteniendo intentos denegados...".cyan());
+
+    match client.listar_auditoria() {
+        Ok(intentos) if intentos.is_empty() => {
+            println!("{}", "✓ No hay intentos denegados".green());
+        }
+        Ok(intentos) => {
+            let rows: Vec<IntentoDenegadoRow> = intentos
+                .into_iter()
+                .map(|i| IntentoDenegadoRow {
+                    fecha: i.fecha.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    sujeto: i.sujeto,
+                    accion: i.accion,
+                    recurso: i.recurso,
+                })
+                .collect();
+            println!("{}", Table::new(rows));
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct ReglaLimiteRow {
+    #[tabled(rename = "Regla")]
+    regla: String,
+    #[tabled(rename = "Rechazadas")]
+    rechazadas: u64,
+}
+
+pub fn limites(client: &ApiClient) {
+    println!("{}", "Obteniendo métricas de límites...".cyan());
+
+    match client.metricas_limites() {
+        Ok(metricas) => {
+            println!("Admitidas:  {}", metricas.admitidas);
+            println!("Rechazadas: {}", metricas.rechazadas);
+            if !metricas.reglas.is_empty() {
+                let rows: Vec<ReglaLimiteRow> = metricas
+                    .reglas
+                    .into_iter()
+                    .map(|r| ReglaLimiteRow {
+                        regla: r.regla,
+                        rechazadas: r.rechazadas,
+                    })
+                    .collect();
+                println!("{}", Table::new(rows));
+            }
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Error:".red(), e);
+        }
+    }
+}
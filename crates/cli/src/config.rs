@@ -0,0 +1,43 @@
+// Configuración persistente del CLI
+//
+// Fichero JSON opcional con la URL de la API y el token:
+//
+//   { "url": "https://reservas.example.com/api", "token": "..." }
+//
+// Se busca en $RESERVAS_CLI_CONFIG o, si no está definida, en
+// ~/.config/reservas/cli.json. Los argumentos y variables de entorno
+// tienen prioridad sobre el fichero.
+
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ConfigCli {
+    pub url: Option<String>,
+    /// Clave de API o JWT que se envía como `Authorization: Bearer`
+    pub token: Option<String>,
+}
+
+impl ConfigCli {
+    /// Lee el fichero de configuración; si no existe, configuración vacía
+    pub fn cargar() -> Result<Self, String> {
+        match ruta() {
+            Some(ruta) if ruta.exists() => Self::leer(&ruta),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn leer(ruta: &PathBuf) -> Result<Self, String> {
+        let contenido = std::fs::read_to_string(ruta)
+            .map_err(|e| format!("No se pudo leer {}: {}", ruta.display(), e))?;
+        serde_json::from_str(&contenido)
+            .map_err(|e| format!("Configuración inválida en {}: {}", ruta.display(), e))
+    }
+}
+
+fn ruta() -> Option<PathBuf> {
+    if let Some(ruta) = std::env::var_os("RESERVAS_CLI_CONFIG") {
+        return Some(PathBuf::from(ruta));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config/reservas/cli.json"))
+}
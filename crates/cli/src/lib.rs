@@ -7,8 +7,10 @@
 pub mod api_client;
 pub mod cli_args;
 pub mod commands;
+pub mod config;
 mod response;
 
 // Re-exportar tipos principales para facilitar el uso
 pub use api_client::ApiClient;
-pub use cli_args::{Cli, Commands, EmpleadoCommands, ReservaCommands};
+pub use cli_args::{AdminCommands, Cli, Commands, EmpleadoCommands, ReservaCommands};
+pub use config::ConfigCli;
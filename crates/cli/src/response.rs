@@ -11,12 +11,26 @@ pub struct CrearEmpleadoRequest {
     pub email: String,
 }
 
+/// Solo se envían los campos que cambian (PATCH)
+#[derive(Debug, Serialize)]
+pub struct ModificarEmpleadoRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nombre: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// `""` quita el responsable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub responsable_id: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct EmpleadoResponse {
     pub id: String,
     pub nombre: String,
     pub email: String,
     pub activo: bool,
+    #[serde(default)]
+    pub responsable_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -24,6 +38,19 @@ pub struct CrearReservaRequest {
     pub empleado_id: String,
     pub inicio_slot: DateTime<Utc>,
     pub descripcion: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sala_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReprogramarReservaRequest {
+    pub inicio_slot: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModificarReservaRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub descripcion: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,6 +61,67 @@ pub struct ReservaResponse {
     pub slot_fin: DateTime<Utc>,
     pub descripcion: String,
     pub estado: String,
+    #[serde(default)]
+    pub sala_id: Option<String>,
+}
+
+/// Página de resultados de un listado
+#[derive(Debug, Deserialize)]
+pub struct PaginaResponse<T> {
+    pub elementos: Vec<T>,
+    pub siguiente_cursor: Option<String>,
+}
+
+// 👇 query -> Serialize (los `None` no se envían)
+
+#[derive(Debug, Default, Serialize)]
+pub struct ListarReservasQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desde: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hasta: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estado: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub empleado_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sala_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orden: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direccion: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limite: Option<usize>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ListarEmpleadosQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activo: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limite: Option<usize>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ListarSalasQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activa: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacidad_minima: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limite: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,12 +131,117 @@ pub struct TablaDisponibilidadResponse {
     pub disponibilidad: Vec<DisponibilidadEmpleadoResponse>,
 }
 
+#[derive(Debug, Serialize, Default)]
+pub struct MatrizDisponibilidadQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desde: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semana: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub empleados: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub responsable_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salas: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct InformeQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desde: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hasta: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semana: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formato: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desglose: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InformeOcupacionResponse {
+    pub desde: String,
+    pub hasta: String,
+    pub total: usize,
+    pub pendientes: usize,
+    pub confirmadas: usize,
+    pub canceladas: usize,
+    pub no_presentadas: usize,
+    pub tasa_cancelacion: f64,
+    pub tasa_no_presentacion: f64,
+    pub por_empleado: Vec<UsoRecursoResponse>,
+    pub por_sala: Vec<UsoRecursoResponse>,
+    pub por_hora: Vec<UsoHoraResponse>,
+    pub dias_mas_ocupados: Vec<UsoDiaResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsoRecursoResponse {
+    pub nombre: String,
+    pub reservas: usize,
+    pub canceladas: usize,
+    pub ocupacion: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsoHoraResponse {
+    pub hora: u32,
+    pub reservas: usize,
+    pub ocupacion: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsoDiaResponse {
+    pub fecha: String,
+    pub reservas: usize,
+    pub ocupacion: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BuscarSalasLibresQuery {
+    pub fecha: String,
+    pub hora_inicio: u32,
+    pub hora_fin: u32,
+    pub capacidad_minima: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MatrizDisponibilidadResponse {
+    pub desde: String,
+    pub hasta: String,
+    pub dias: Vec<String>,
+    pub horas: Vec<u32>,
+    pub filas: Vec<FilaMatrizResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FilaMatrizResponse {
+    pub tipo: String,
+    pub id: String,
+    pub nombre: String,
+    pub libres: usize,
+    pub ocupacion: Vec<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct CrearSalaRequest {
     pub nombre: String,
     pub capacidad: u32,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ModificarSalaRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nombre: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacidad: Option<u32>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SalaResponse {
     pub id: String,
@@ -76,6 +269,122 @@ pub struct DisponibilidadEmpleadoResponse {
 }
 
 #[derive(Debug, Deserialize)]
-pub struct ErrorResponse {
-    pub error: String,
+/// Error de la API en formato RFC 7807 (`application/problem+json`).
+/// Solo se leen los campos que se muestran al usuario; `type`, `status` e
+/// `instance` se ignoran.
+pub struct ProblemaResponse {
+    #[serde(rename = "title")]
+    pub titulo: String,
+    #[serde(rename = "detail")]
+    pub detalle: String,
+    /// Detalle por campo de los errores de validación
+    #[serde(default)]
+    pub campos: Vec<ErrorCampoResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ErrorCampoResponse {
+    pub campo: String,
+    pub mensaje: String,
+}
+
+impl ProblemaResponse {
+    /// Mensaje para mostrar, con un renglón por campo inválido
+    pub fn mensaje(self) -> String {
+        if self.campos.is_empty() {
+            return self.detalle;
+        }
+        let campos: Vec<String> = self
+            .campos
+            .iter()
+            .map(|c| format!("\n  - {}: {}", c.campo, c.mensaje))
+            .collect();
+        format!("{}:{}", self.titulo, campos.concat())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResumenRestauracionResponse {
+    pub empleados: usize,
+    pub salas: usize,
+    pub reservas: usize,
+    pub simulado: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ElementoImportacionResponse {
+    pub uid: String,
+    pub email: Option<String>,
+    pub empleado_id: Option<String>,
+    pub slot_inicio: Option<DateTime<Utc>>,
+    pub resultado: String,
+    pub detalle: Option<String>,
+    pub reserva_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InformeImportacionResponse {
+    pub eventos: usize,
+    pub importadas: usize,
+    pub conflictos: usize,
+    pub omitidas: usize,
+    pub simulado: bool,
+    pub elementos: Vec<ElementoImportacionResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FilaCargaResponse {
+    pub fila: usize,
+    pub clave: String,
+    pub resultado: String,
+    #[serde(default)]
+    pub errores: Vec<ErrorCampoResponse>,
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InformeCargaResponse {
+    pub creadas: usize,
+    pub actualizadas: usize,
+    pub sin_cambios: usize,
+    pub errores: usize,
+    pub simulado: bool,
+    pub filas: Vec<FilaCargaResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IncidenciaResponse {
+    pub tipo: String,
+    pub reserva_id: String,
+    pub detalle: String,
+    pub reparada: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InformeIntegridadResponse {
+    pub reservas_revisadas: usize,
+    pub consistente: bool,
+    pub reparadas: usize,
+    pub incidencias: Vec<IncidenciaResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IntentoDenegadoResponse {
+    pub fecha: DateTime<Utc>,
+    pub sujeto: String,
+    pub accion: String,
+    pub recurso: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReglaLimiteResponse {
+    pub regla: String,
+    pub rechazadas: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MetricasLimitesResponse {
+    pub admitidas: u64,
+    pub rechazadas: u64,
+    pub reglas: Vec<ReglaLimiteResponse>,
 }
@@ -13,17 +13,20 @@ fn main() {
             "emp-001".to_string(),
             "Juan López".to_string(),
             "juan@empresa.com".to_string(),
-        ),
+        )
+        .unwrap(),
         Empleado::new(
             "emp-002".to_string(),
             "María García".to_string(),
             "maria@empresa.com".to_string(),
-        ),
+        )
+        .unwrap(),
         Empleado::new(
             "emp-003".to_string(),
             "Pedro Martínez".to_string(),
             "pedro@empresa.com".to_string(),
-        ),
+        )
+        .unwrap(),
     ];
 
     println!("👥 Empleados:");
@@ -0,0 +1,209 @@
+use crate::empleado::Empleado;
+use std::fmt;
+use std::str::FromStr;
+
+/// Roles que puede tener quien actúa sobre el sistema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rol {
+    /// Gestiona sus propias reservas
+    Empleado,
+    /// Además confirma las reservas de sus subordinados
+    Responsable,
+    /// Gestiona las salas
+    AdminSalas,
+    /// Puede hacerlo todo
+    Admin,
+}
+
+impl FromStr for Rol {
+    type Err = String;
+
+    fn from_str(valor: &str) -> Result<Self, Self::Err> {
+        match valor {
+            "empleado" => Ok(Rol::Empleado),
+            "responsable" => Ok(Rol::Responsable),
+            "admin-salas" => Ok(Rol::AdminSalas),
+            "admin" => Ok(Rol::Admin),
+            _ => Err(format!(
+                "Rol desconocido '{}' (empleado, responsable, admin-salas o admin)",
+                valor
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Rol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Rol::Empleado => "empleado",
+            Rol::Responsable => "responsable",
+            Rol::AdminSalas => "admin-salas",
+            Rol::Admin => "admin",
+        })
+    }
+}
+
+/// Operaciones sujetas a permisos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accion {
+    CrearReserva,
+    ModificarReserva,
+    CancelarReserva,
+    ConfirmarReserva,
+    EliminarReserva,
+    GestionarEmpleados,
+    GestionarSalas,
+    /// Obtener la URL privada del calendario de un empleado o de una sala
+    SuscribirCalendario,
+    /// Respaldos, integridad y auditoría
+    Administrar,
+}
+
+impl fmt::Display for Accion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Accion::CrearReserva => "crear reserva",
+            Accion::ModificarReserva => "modificar reserva",
+            Accion::CancelarReserva => "cancelar reserva",
+            Accion::ConfirmarReserva => "confirmar reserva",
+            Accion::EliminarReserva => "eliminar reserva",
+            Accion::GestionarEmpleados => "gestionar empleados",
+            Accion::GestionarSalas => "gestionar salas",
+            Accion::SuscribirCalendario => "suscribirse al calendario",
+            Accion::Administrar => "administrar",
+        })
+    }
+}
+
+/// Quién realiza una operación: la identidad ya autenticada, traducida a
+/// términos del dominio
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Actor {
+    /// Identificador para auditoría (usuario, clave de API...)
+    pub sujeto: String,
+    /// Empleado que corresponde a esta identidad, si lo hay
+    pub empleado_id: Option<String>,
+    pub roles: Vec<Rol>,
+}
+
+impl Actor {
+    pub fn new(sujeto: impl Into<String>, empleado_id: Option<String>, roles: Vec<Rol>) -> Self {
+        Self {
+            sujeto: sujeto.into(),
+            empleado_id,
+            roles,
+        }
+    }
+
+    /// Procesos internos (arranque, tareas programadas): todos los permisos
+    pub fn sistema() -> Self {
+        Self::new("sistema", None, vec![Rol::Admin])
+    }
+
+    pub fn tiene_rol(&self, rol: Rol) -> bool {
+        self.roles.contains(&rol)
+    }
+
+    fn es(&self, empleado: &Empleado) -> bool {
+        self.empleado_id.as_deref() == Some(empleado.id.as_str())
+    }
+
+    /// Reglas de permisos. `titular` es el empleado dueño de la reserva en
+    /// las acciones sobre reservas.
+    ///
+    /// - `admin` puede hacerlo todo
+    /// - Cada empleado crea, modifica y cancela solo sus reservas
+    /// - Un `responsable` confirma las reservas de sus subordinados
+    /// - `admin-salas` gestiona las salas
+    /// - Cada empleado se suscribe a su calendario; a los de las salas, todos
+    /// - Eliminar reservas, gestionar empleados y administrar: solo `admin`
+    pub fn puede(&self, accion: Accion, titular: Option<&Empleado>) -> bool {
+        if self.tiene_rol(Rol::Admin) {
+            return true;
+        }
+        match accion {
+            Accion::CrearReserva | Accion::ModificarReserva | Accion::CancelarReserva => {
+                titular.is_some_and(|t| self.es(t))
+            }
+            Accion::ConfirmarReserva => {
+                self.tiene_rol(Rol::Responsable)
+                    && titular.is_some_and(|t| {
+                        t.responsable_id.is_some()
+                            && t.responsable_id.as_deref() == self.empleado_id.as_deref()
+                    })
+            }
+            Accion::GestionarSalas => self.tiene_rol(Rol::AdminSalas),
+            // Sin titular es el calendario de una sala, que puede ver cualquiera
+            Accion::SuscribirCalendario => titular.is_none_or(|t| self.es(t)),
+            Accion::EliminarReserva | Accion::GestionarEmpleados | Accion::Administrar => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empleado(id: &str, responsable: Option<&str>) -> Empleado {
+        let mut empleado = Empleado::new(
+            id.to_string(),
+            "Nombre".to_string(),
+            format!("{}@empresa.com", id),
+        )
+        .unwrap();
+        empleado.responsable_id = responsable.map(str::to_string);
+        empleado
+    }
+
+    #[test]
+    fn test_empleado_solo_sus_reservas() {
+        let actor = Actor::new("juan", Some("emp-1".to_string()), vec![Rol::Empleado]);
+        let propio = empleado("emp-1", Some("jefe"));
+        let otro = empleado("emp-2", Some("jefe"));
+
+        assert!(actor.puede(Accion::CrearReserva, Some(&propio)));
+        assert!(actor.puede(Accion::CancelarReserva, Some(&propio)));
+        assert!(!actor.puede(Accion::CrearReserva, Some(&otro)));
+        assert!(!actor.puede(Accion::ConfirmarReserva, Some(&propio)));
+        assert!(!actor.puede(Accion::EliminarReserva, Some(&propio)));
+        assert!(!actor.puede(Accion::GestionarSalas, None));
+        assert!(actor.puede(Accion::SuscribirCalendario, Some(&propio)));
+        assert!(!actor.puede(Accion::SuscribirCalendario, Some(&otro)));
+        assert!(actor.puede(Accion::SuscribirCalendario, None));
+    }
+
+    #[test]
+    fn test_responsable_confirma_a_sus_subordinados() {
+        let jefe = Actor::new("ana", Some("jefe".to_string()), vec![Rol::Responsable]);
+
+        assert!(jefe.puede(
+            Accion::ConfirmarReserva,
+            Some(&empleado("emp-1", Some("jefe")))
+        ));
+        assert!(!jefe.puede(
+            Accion::ConfirmarReserva,
+            Some(&empleado("emp-2", Some("otro")))
+        ));
+        assert!(!jefe.puede(Accion::ConfirmarReserva, Some(&empleado("emp-3", None))));
+        // Sin empleado asociado no es responsable de nadie
+        let sin_empleado = Actor::new("x", None, vec![Rol::Responsable]);
+        assert!(!sin_empleado.puede(Accion::ConfirmarReserva, Some(&empleado("emp-3", None))));
+    }
+
+    #[test]
+    fn test_roles_de_administracion() {
+        let salas = Actor::new("facilities", None, vec![Rol::AdminSalas]);
+        assert!(salas.puede(Accion::GestionarSalas, None));
+        assert!(!salas.puede(Accion::GestionarEmpleados, None));
+
+        assert!(Actor::sistema().puede(Accion::EliminarReserva, None));
+        assert!(Actor::sistema().puede(Accion::Administrar, None));
+    }
+
+    #[test]
+    fn test_rol_desde_texto() {
+        assert_eq!("admin-salas".parse(), Ok(Rol::AdminSalas));
+        assert_eq!(Rol::Responsable.to_string(), "responsable");
+        assert!("jefe".parse::<Rol>().is_err());
+    }
+}
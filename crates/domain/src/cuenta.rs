@@ -0,0 +1,93 @@
+use crate::autorizacion::{Actor, Rol};
+use crate::validacion::ErrorCampo;
+
+/// Longitud mínima de las contraseñas de las cuentas locales
+pub const LONGITUD_MINIMA_PASSWORD: usize = 8;
+
+/// Cuenta local con la que se entra en la interfaz web.
+/// La contraseña solo se guarda como hash; el formato lo decide la aplicación.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cuenta {
+    /// Nombre de usuario (minúsculas, dígitos, `.`, `_` y `-`)
+    pub usuario: String,
+    pub hash_password: String,
+    /// Empleado al que representa, si lo hay
+    pub empleado_id: Option<String>,
+    pub roles: Vec<Rol>,
+    pub activa: bool,
+}
+
+impl Cuenta {
+    pub fn new(
+        usuario: String,
+        hash_password: String,
+        empleado_id: Option<String>,
+        roles: Vec<Rol>,
+    ) -> Result<Self, ErrorCampo> {
+        let usuario = usuario.trim().to_lowercase();
+        if usuario.len() < 3 || usuario.len() > 50 {
+            return Err(ErrorCampo::new(
+                "usuario",
+                "El usuario debe tener entre 3 y 50 caracteres",
+            ));
+        }
+        let valido = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || "._-".contains(c);
+        if !usuario.chars().all(valido) {
+            return Err(ErrorCampo::new(
+                "usuario",
+                format!(
+                    "El usuario {} solo puede tener letras, dígitos, '.', '_' y '-'",
+                    usuario
+                ),
+            ));
+        }
+
+        Ok(Self {
+            usuario,
+            hash_password,
+            empleado_id,
+            roles,
+            activa: true,
+        })
+    }
+
+    /// Quién actúa cuando se usa esta cuenta
+    pub fn actor(&self) -> Actor {
+        Actor::new(
+            self.usuario.clone(),
+            self.empleado_id.clone(),
+            self.roles.clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normaliza_y_valida_usuario() {
+        let cuenta = Cuenta::new(" Ana.Ruiz ".to_string(), "h".to_string(), None, vec![]).unwrap();
+        assert_eq!(cuenta.usuario, "ana.ruiz");
+        assert!(cuenta.activa);
+
+        assert!(Cuenta::new("ab".to_string(), "h".to_string(), None, vec![]).is_err());
+        let error = Cuenta::new("ana ruiz".to_string(), "h".to_string(), None, vec![]).unwrap_err();
+        assert_eq!(error.campo, "usuario");
+    }
+
+    #[test]
+    fn test_actor_de_la_cuenta() {
+        let cuenta = Cuenta::new(
+            "ana".to_string(),
+            "h".to_string(),
+            Some("emp-1".to_string()),
+            vec![Rol::Responsable],
+        )
+        .unwrap();
+        let actor = cuenta.actor();
+        assert_eq!(actor.sujeto, "ana");
+        assert_eq!(actor.empleado_id.as_deref(), Some("emp-1"));
+        assert!(actor.tiene_rol(Rol::Responsable));
+    }
+}
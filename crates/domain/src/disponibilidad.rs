@@ -1,7 +1,16 @@
-use crate::{Empleado, Reserva, Slot};
-use chrono::Timelike;
+use crate::{Empleado, EstadoReserva, Reserva, Sala, Slot};
+use chrono::{Datelike, Days, Months, NaiveDate, Timelike};
 use std::collections::HashMap;
 
+/// Horas de inicio de los slots de un día laboral (9:00 - 18:00)
+pub const HORAS_LABORALES: std::ops::Range<u32> = 9..18;
+
+/// Días como máximo de una consulta de disponibilidad por periodo
+pub const MAX_DIAS_PERIODO: u64 = 62;
+
+/// Días como máximo de un informe de ocupación
+pub const MAX_DIAS_INFORME: u64 = 366;
+
 /// Servicio de Dominio: Gestiona la disponibilidad de empleados
 ///
 /// Este es un SERVICIO DE DOMINIO porque:
@@ -21,6 +30,15 @@ pub struct DisponibilidadSlot {
     pub descripcion_reserva: Option<String>,
 }
 
+/// Representa la disponibilidad de una sala en un slot
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisponibilidadSalaSlot {
+    pub sala_id: String,
+    pub slot: Slot,
+    pub disponible: bool,
+    pub reserva_id: Option<String>,
+}
+
 /// Tabla de disponibilidad para múltiples empleados
 #[derive(Debug)]
 pub struct TablaDisponibilidad {
@@ -43,6 +61,37 @@ impl DisponibilidadService {
             .any(|r| r.empleado_id == empleado_id && r.slot == *slot && r.esta_activa())
     }
 
+    /// Verifica si una sala está libre en un slot
+    ///
+    /// Regla de negocio: una sala solo puede tener UNA reserva activa por slot
+    pub fn sala_disponible_en_slot(sala_id: &str, slot: &Slot, reservas: &[Reserva]) -> bool {
+        !reservas
+            .iter()
+            .any(|r| r.sala_id.as_deref() == Some(sala_id) && r.slot == *slot && r.esta_activa())
+    }
+
+    /// Salas activas con al menos `capacidad_minima` plazas y libres en todos
+    /// los slots, de la más pequeña a la más grande (y por nombre)
+    pub fn salas_libres(
+        salas: &[Sala],
+        slots: &[Slot],
+        capacidad_minima: u32,
+        reservas: &[Reserva],
+    ) -> Vec<Sala> {
+        let mut libres: Vec<Sala> = salas
+            .iter()
+            .filter(|sala| sala.activa && sala.capacidad >= capacidad_minima)
+            .filter(|sala| {
+                slots
+                    .iter()
+                    .all(|slot| Self::sala_disponible_en_slot(&sala.id, slot, reservas))
+            })
+            .cloned()
+            .collect();
+        libres.sort_by(|a, b| a.capacidad.cmp(&b.capacidad).then(a.nombre.cmp(&b.nombre)));
+        libres
+    }
+
     /// Obtiene todas las reservas de un empleado en un rango de slots
     pub fn reservas_de_empleado<'a>(
         empleado_id: &str,
@@ -150,11 +199,186 @@ impl DisponibilidadService {
             .map(|e| (e.nombre.clone(), *conteo.get(&e.id).unwrap_or(&0)))
             .collect();
 
-        resultado.sort_by(|a, b| b.1.cmp(&a.1));
+        resultado.sort_by_key(|e| std::cmp::Reverse(e.1));
         resultado
     }
 }
 
+/// Value Object: rango de días, ambos incluidos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Periodo {
+    pub desde: NaiveDate,
+    pub hasta: NaiveDate,
+}
+
+impl Periodo {
+    /// Regla de negocio: como mucho `MAX_DIAS_PERIODO` días, para que la
+    /// matriz no crezca sin límite
+    pub fn new(desde: NaiveDate, hasta: NaiveDate) -> Result<Self, String> {
+        Self::con_maximo(desde, hasta, MAX_DIAS_PERIODO)
+    }
+
+    /// Periodo de un informe: no se pinta celda a celda, así que admite
+    /// hasta `MAX_DIAS_INFORME` días
+    pub fn para_informe(desde: NaiveDate, hasta: NaiveDate) -> Result<Self, String> {
+        Self::con_maximo(desde, hasta, MAX_DIAS_INFORME)
+    }
+
+    fn con_maximo(desde: NaiveDate, hasta: NaiveDate, maximo: u64) -> Result<Self, String> {
+        if hasta < desde {
+            return Err(format!(
+                "El periodo termina ({}) antes de empezar ({})",
+                hasta, desde
+            ));
+        }
+        let dias = (hasta - desde).num_days() as u64 + 1;
+        if dias > maximo {
+            return Err(format!(
+                "El periodo tiene {} días; como mucho se consultan {}",
+                dias, maximo
+            ));
+        }
+        Ok(Self { desde, hasta })
+    }
+
+    pub fn dia(fecha: NaiveDate) -> Self {
+        Self {
+            desde: fecha,
+            hasta: fecha,
+        }
+    }
+
+    /// Semana de lunes a domingo que contiene la fecha
+    pub fn semana(fecha: NaiveDate) -> Self {
+        let lunes = fecha - Days::new(fecha.weekday().num_days_from_monday() as u64);
+        Self {
+            desde: lunes,
+            hasta: lunes + Days::new(6),
+        }
+    }
+
+    /// Mes natural que contiene la fecha
+    pub fn mes(fecha: NaiveDate) -> Self {
+        let primero = fecha.with_day(1).unwrap_or(fecha);
+        let ultimo = primero
+            .checked_add_months(Months::new(1))
+            .and_then(|siguiente| siguiente.pred_opt())
+            .unwrap_or(primero);
+        Self {
+            desde: primero,
+            hasta: ultimo,
+        }
+    }
+
+    pub fn dias(&self) -> Vec<NaiveDate> {
+        self.desde
+            .iter_days()
+            .take_while(|dia| *dia <= self.hasta)
+            .collect()
+    }
+}
+
+/// Qué representa una fila de la matriz de disponibilidad
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoFila {
+    Empleado,
+    Sala,
+}
+
+/// Ocupación de un empleado o una sala en cada slot laboral del periodo
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilaMatriz {
+    pub tipo: TipoFila,
+    pub id: String,
+    pub nombre: String,
+    /// Por día y hora: el estado de la reserva activa que lo ocupa, o `None`
+    /// si está libre
+    pub celdas: Vec<Vec<Option<EstadoReserva>>>,
+}
+
+impl FilaMatriz {
+    pub fn libres(&self) -> usize {
+        self.celdas.iter().flatten().filter(|c| c.is_none()).count()
+    }
+}
+
+/// Disponibilidad de varios días, compacta: una fila por empleado o sala y
+/// una celda por slot laboral
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrizDisponibilidad {
+    pub periodo: Periodo,
+    pub dias: Vec<NaiveDate>,
+    pub horas: Vec<u32>,
+    pub filas: Vec<FilaMatriz>,
+}
+
+impl MatrizDisponibilidad {
+    /// Una fila por cada empleado activo y cada sala, en ese orden. Un
+    /// empleado está ocupado si tiene una reserva activa en el slot; una
+    /// sala, si alguna reserva activa la usa.
+    pub fn generar(
+        periodo: Periodo,
+        empleados: &[Empleado],
+        salas: &[Sala],
+        reservas: &[Reserva],
+    ) -> Self {
+        let dias = periodo.dias();
+        let horas: Vec<u32> = HORAS_LABORALES.collect();
+
+        // Reservas activas del periodo por (día, hora), para no recorrerlas
+        // todas en cada celda
+        let mut por_slot: HashMap<(NaiveDate, u32), Vec<&Reserva>> = HashMap::new();
+        for reserva in reservas.iter().filter(|r| r.esta_activa()) {
+            let dia = reserva.slot.inicio.date_naive();
+            if dia >= periodo.desde && dia <= periodo.hasta {
+                por_slot
+                    .entry((dia, reserva.slot.inicio.hour()))
+                    .or_default()
+                    .push(reserva);
+            }
+        }
+        let celdas = |ocupa: &dyn Fn(&Reserva) -> bool| -> Vec<Vec<Option<EstadoReserva>>> {
+            dias.iter()
+                .map(|dia| {
+                    horas
+                        .iter()
+                        .map(|hora| {
+                            por_slot
+                                .get(&(*dia, *hora))
+                                .and_then(|rs| rs.iter().find(|r| ocupa(r)))
+                                .map(|r| r.estado.clone())
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+
+        let mut filas: Vec<FilaMatriz> = empleados
+            .iter()
+            .filter(|e| e.activo)
+            .map(|e| FilaMatriz {
+                tipo: TipoFila::Empleado,
+                id: e.id.clone(),
+                nombre: e.nombre.clone(),
+                celdas: celdas(&|r| r.empleado_id == e.id),
+            })
+            .collect();
+        filas.extend(salas.iter().map(|s| FilaMatriz {
+            tipo: TipoFila::Sala,
+            id: s.id.clone(),
+            nombre: s.nombre.clone(),
+            celdas: celdas(&|r| r.sala_id.as_deref() == Some(s.id.as_str())),
+        }));
+
+        Self {
+            periodo,
+            dias,
+            horas,
+            filas,
+        }
+    }
+}
+
 impl TablaDisponibilidad {
     /// Renderiza la tabla en formato de texto
     pub fn formato_texto(&self) -> String {
@@ -218,6 +442,7 @@ mod tests {
             nombre.to_string(),
             format!("{}@empresa.com", id),
         )
+        .unwrap()
     }
 
     fn crear_slot_futuro(hour: u32) -> Slot {
@@ -286,6 +511,105 @@ mod tests {
         assert!(disp.disponible);
     }
 
+    #[test]
+    fn test_periodos() {
+        let fecha = NaiveDate::from_ymd_opt(2025, 11, 26).unwrap(); // miércoles
+        let semana = Periodo::semana(fecha);
+        assert_eq!(semana.desde, NaiveDate::from_ymd_opt(2025, 11, 24).unwrap());
+        assert_eq!(semana.hasta, NaiveDate::from_ymd_opt(2025, 11, 30).unwrap());
+        assert_eq!(semana.dias().len(), 7);
+
+        let febrero = Periodo::mes(NaiveDate::from_ymd_opt(2024, 2, 10).unwrap());
+        assert_eq!(febrero.desde, NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
+        assert_eq!(febrero.hasta, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        let diciembre = Periodo::mes(NaiveDate::from_ymd_opt(2025, 12, 31).unwrap());
+        assert_eq!(diciembre.dias().len(), 31);
+
+        assert!(Periodo::new(fecha, fecha.pred_opt().unwrap()).is_err());
+        assert!(Periodo::new(fecha, fecha + Days::new(MAX_DIAS_PERIODO - 1)).is_ok());
+        assert!(Periodo::new(fecha, fecha + Days::new(MAX_DIAS_PERIODO)).is_err());
+        assert!(Periodo::para_informe(fecha, fecha + Days::new(MAX_DIAS_PERIODO)).is_ok());
+        assert!(Periodo::para_informe(fecha, fecha + Days::new(MAX_DIAS_INFORME)).is_err());
+    }
+
+    #[test]
+    fn test_matriz_disponibilidad() {
+        let empleados = vec![
+            crear_empleado("emp-001", "Juan"),
+            crear_empleado("emp-002", "María"),
+        ];
+        let salas = vec![Sala::new("sala-1".to_string(), "Andrómeda".to_string(), 8).unwrap()];
+        let slot = crear_slot_futuro(10);
+        let mut confirmada = Reserva::new(
+            "r1".to_string(),
+            "emp-001".to_string(),
+            slot.clone(),
+            "Reunión".to_string(),
+        )
+        .unwrap()
+        .en_sala("sala-1".to_string());
+        confirmada.confirmar();
+        let mut cancelada = Reserva::new(
+            "r2".to_string(),
+            "emp-002".to_string(),
+            slot.clone(),
+            "Cancelada".to_string(),
+        )
+        .unwrap();
+        cancelada.cancelar();
+
+        let dia = slot.inicio.date_naive();
+        let periodo = Periodo::new(dia, dia + Days::new(1)).unwrap();
+        let matriz =
+            MatrizDisponibilidad::generar(periodo, &empleados, &salas, &[confirmada, cancelada]);
+
+        assert_eq!(matriz.dias.len(), 2);
+        assert_eq!(matriz.horas, (9..18).collect::<Vec<_>>());
+        assert_eq!(matriz.filas.len(), 3);
+        let [juan, maria, sala] = &matriz.filas[..] else {
+            panic!("Se esperaban tres filas");
+        };
+        // Las 10 son la segunda hora del primer día
+        assert_eq!(juan.celdas[0][1], Some(EstadoReserva::Confirmada));
+        assert_eq!(juan.libres(), 2 * 9 - 1);
+        assert_eq!(maria.libres(), 2 * 9);
+        assert_eq!(sala.tipo, TipoFila::Sala);
+        assert_eq!(sala.celdas[0][1], Some(EstadoReserva::Confirmada));
+        assert!(sala.celdas[1].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_salas_libres() {
+        let salas = vec![
+            Sala::new("grande".to_string(), "Orión".to_string(), 20).unwrap(),
+            Sala::new("mediana".to_string(), "Lira".to_string(), 8).unwrap(),
+            Sala::new("ocupada".to_string(), "Vega".to_string(), 6).unwrap(),
+            Sala::new("pequena".to_string(), "Cabina".to_string(), 2).unwrap(),
+        ];
+        let slots = vec![crear_slot_futuro(10), crear_slot_futuro(11)];
+        let reserva = Reserva::new(
+            "r1".to_string(),
+            "emp-001".to_string(),
+            slots[1].clone(),
+            "Reunión".to_string(),
+        )
+        .unwrap()
+        .en_sala("ocupada".to_string());
+
+        let reservas = vec![reserva];
+        let libres = DisponibilidadService::salas_libres(&salas, &slots, 4, &reservas);
+        let ids: Vec<&str> = libres.iter().map(|s| s.id.as_str()).collect();
+        // La más pequeña que cabe, primero; la ocupada en un slot no sale
+        assert_eq!(ids, ["mediana", "grande"]);
+
+        assert!(DisponibilidadService::sala_disponible_en_slot(
+            "ocupada", &slots[0], &reservas
+        ));
+        assert!(!DisponibilidadService::sala_disponible_en_slot(
+            "ocupada", &slots[1], &reservas
+        ));
+    }
+
     #[test]
     fn test_slots_con_todos_disponibles() {
         let empleados = vec![
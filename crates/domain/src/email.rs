@@ -0,0 +1,85 @@
+use std::fmt;
+
+/// Value Object: Email
+/// Dirección de correo con sintaxis validada y normalizada (sin espacios
+/// alrededor y en minúsculas), de modo que dos emails iguales comparan igual
+/// aunque se escribieran con distintas mayúsculas.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Email(String);
+
+impl Email {
+    pub fn new(valor: &str) -> Result<Self, String> {
+        let valor = valor.trim().to_lowercase();
+        if valor.is_empty() {
+            return Err("El email no puede estar vacío".to_string());
+        }
+
+        let invalido = || Err(format!("El email {} no es válido", valor));
+        let Some((usuario, dominio)) = valor.split_once('@') else {
+            return invalido();
+        };
+        if usuario.is_empty() || usuario.len() > 64 || valor.len() > 254 {
+            return invalido();
+        }
+        if valor.chars().any(|c| c.is_whitespace() || c.is_control()) || dominio.contains('@') {
+            return invalido();
+        }
+        // El dominio necesita al menos un punto y ninguna etiqueta vacía
+        let etiquetas: Vec<&str> = dominio.split('.').collect();
+        if etiquetas.len() < 2 || etiquetas.iter().any(|e| e.is_empty()) {
+            return invalido();
+        }
+        if etiquetas
+            .iter()
+            .any(|e| e.starts_with('-') || e.ends_with('-'))
+        {
+            return invalido();
+        }
+
+        Ok(Self(valor))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Email {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normaliza() {
+        let email = Email::new("  Juan.Lopez@Empresa.COM ").unwrap();
+        assert_eq!(email.as_str(), "juan.lopez@empresa.com");
+        assert_eq!(email, Email::new("juan.lopez@empresa.com").unwrap());
+    }
+
+    #[test]
+    fn test_rechaza_sintaxis_invalida() {
+        for valor in [
+            "",
+            "   ",
+            "juan",
+            "@empresa.com",
+            "juan@",
+            "juan@empresa",
+            "juan@@empresa.com",
+            "juan@empresa..com",
+            "juan lopez@empresa.com",
+            "juan@-empresa.com",
+        ] {
+            assert!(
+                Email::new(valor).is_err(),
+                "{:?} debería ser inválido",
+                valor
+            );
+        }
+    }
+}
@@ -1,21 +1,69 @@
+use crate::email::Email;
+use crate::validacion::ErrorCampo;
+
 /// Entidad de dominio: Empleado
 /// Representa un empleado que puede tener reservas asignadas
 #[derive(Debug, Clone, PartialEq)]
 pub struct Empleado {
     pub id: String,
     pub nombre: String,
-    pub email: String,
+    pub email: Email,
     pub activo: bool,
+    /// Empleado que confirma sus reservas
+    pub responsable_id: Option<String>,
 }
 
 impl Empleado {
-    pub fn new(id: String, nombre: String, email: String) -> Self {
-        Self {
+    /// Valida nombre y email; devuelve todos los errores a la vez, por campo
+    pub fn new(id: String, nombre: String, email: String) -> Result<Self, Vec<ErrorCampo>> {
+        let email = Self::validar(&nombre, &email)?;
+
+        Ok(Self {
             id,
             nombre,
             email,
             activo: true,
+            responsable_id: None,
+        })
+    }
+
+    /// Cambia los datos editables del empleado con las mismas reglas que al crearlo
+    pub fn modificar(&mut self, nombre: String, email: String) -> Result<(), Vec<ErrorCampo>> {
+        let email = Self::validar(&nombre, &email)?;
+
+        self.nombre = nombre;
+        self.email = email;
+        Ok(())
+    }
+
+    fn validar(nombre: &str, email: &str) -> Result<Email, Vec<ErrorCampo>> {
+        let mut errores = Vec::new();
+        if nombre.trim().is_empty() {
+            errores.push(ErrorCampo::new("nombre", "El nombre no puede estar vacío"));
         }
+        match Email::new(email) {
+            Ok(email) if errores.is_empty() => Ok(email),
+            Ok(_) => Err(errores),
+            Err(e) => {
+                errores.push(ErrorCampo::new("email", e));
+                Err(errores)
+            }
+        }
+    }
+
+    /// Asigna (o quita, con `None`) el responsable del empleado
+    pub fn asignar_responsable(
+        &mut self,
+        responsable_id: Option<String>,
+    ) -> Result<(), ErrorCampo> {
+        if responsable_id.as_deref() == Some(self.id.as_str()) {
+            return Err(ErrorCampo::new(
+                "responsable_id",
+                "Un empleado no puede ser su propio responsable",
+            ));
+        }
+        self.responsable_id = responsable_id;
+        Ok(())
     }
 
     pub fn desactivar(&mut self) {
@@ -36,20 +84,32 @@ mod tests {
         let empleado = Empleado::new(
             "1".to_string(),
             "Juan López".to_string(),
-            "juan@empresa.com".to_string(),
-        );
+            "Juan@Empresa.com".to_string(),
+        )
+        .unwrap();
 
         assert_eq!(empleado.nombre, "Juan López");
+        assert_eq!(empleado.email.as_str(), "juan@empresa.com");
         assert!(empleado.activo);
     }
 
+    #[test]
+    fn test_crear_empleado_invalido() {
+        let errores =
+            Empleado::new("1".to_string(), " ".to_string(), "juan".to_string()).unwrap_err();
+
+        let campos: Vec<&str> = errores.iter().map(|e| e.campo.as_str()).collect();
+        assert_eq!(campos, ["nombre", "email"]);
+    }
+
     #[test]
     fn test_desactivar_empleado() {
         let mut empleado = Empleado::new(
             "1".to_string(),
             "Juan".to_string(),
             "juan@empresa.com".to_string(),
-        );
+        )
+        .unwrap();
 
         empleado.desactivar();
         assert!(!empleado.activo);
@@ -57,4 +117,25 @@ mod tests {
         empleado.activar();
         assert!(empleado.activo);
     }
+
+    #[test]
+    fn test_modificar_empleado() {
+        let mut empleado = Empleado::new(
+            "1".to_string(),
+            "Juan".to_string(),
+            "juan@empresa.com".to_string(),
+        )
+        .unwrap();
+
+        assert!(empleado
+            .modificar("Juan".to_string(), " ".to_string())
+            .is_err());
+        assert_eq!(empleado.email.as_str(), "juan@empresa.com");
+
+        empleado
+            .modificar("Juan López".to_string(), "jlopez@empresa.com".to_string())
+            .unwrap();
+        assert_eq!(empleado.nombre, "Juan López");
+        assert_eq!(empleado.email.as_str(), "jlopez@empresa.com");
+    }
 }
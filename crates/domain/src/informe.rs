@@ -0,0 +1,303 @@
+// Informe de ocupación de un periodo: uso de empleados y salas, por hora del
+// día y por día, y tasas de cancelación y de no presentación.
+//
+// La ocupación es siempre la fracción de slots laborales ocupados por una
+// reserva activa (pendiente o confirmada). Una reserva "no presentada" es la
+// que terminó sin que nadie la confirmara.
+
+use crate::disponibilidad::HORAS_LABORALES;
+use crate::{DisponibilidadService, Empleado, EstadoReserva, Periodo, Reserva, Sala, Slot};
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use std::collections::HashMap;
+
+/// Días que se destacan como los más ocupados del periodo
+pub const DIAS_MAS_OCUPADOS: usize = 5;
+
+/// Uso de un empleado o una sala en el periodo
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsoRecurso {
+    pub id: String,
+    pub nombre: String,
+    /// Reservas activas
+    pub reservas: usize,
+    pub canceladas: usize,
+    /// Fracción de los slots laborales del periodo que tiene ocupados
+    pub ocupacion: f64,
+}
+
+/// Reservas activas que empiezan a una hora, sumando todos los días
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsoHora {
+    pub hora: u32,
+    pub reservas: usize,
+    /// Sobre los empleados activos en todos los días del periodo
+    pub ocupacion: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsoDia {
+    pub fecha: NaiveDate,
+    pub reservas: usize,
+    /// Sobre los empleados activos en todas las horas del día
+    pub ocupacion: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InformeOcupacion {
+    pub periodo: Periodo,
+    /// Reservas del periodo, también las canceladas
+    pub total: usize,
+    pub pendientes: usize,
+    pub confirmadas: usize,
+    pub canceladas: usize,
+    /// Terminadas sin confirmar
+    pub no_presentadas: usize,
+    /// Canceladas sobre el total
+    pub tasa_cancelacion: f64,
+    /// No presentadas sobre las reservas activas ya terminadas
+    pub tasa_no_presentacion: f64,
+    /// De más a menos ocupado
+    pub por_empleado: Vec<UsoRecurso>,
+    /// De más a menos ocupada
+    pub por_sala: Vec<UsoRecurso>,
+    pub por_hora: Vec<UsoHora>,
+    /// En orden cronológico
+    pub por_dia: Vec<UsoDia>,
+}
+
+/// Fracción sin dividir entre cero
+fn fraccion(parte: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        parte as f64 / total as f64
+    }
+}
+
+impl InformeOcupacion {
+    /// Se cuentan las reservas cuyo slot cae en el periodo. Salen los
+    /// empleados activos y las salas activas, más los inactivos que tengan
+    /// reservas en el periodo. `ahora` decide qué reservas ya terminaron.
+    pub fn generar(
+        periodo: Periodo,
+        empleados: &[Empleado],
+        salas: &[Sala],
+        reservas: &[Reserva],
+        ahora: DateTime<Utc>,
+    ) -> Self {
+        let dias = periodo.dias();
+        let reservas: Vec<&Reserva> = reservas
+            .iter()
+            .filter(|r| {
+                let dia = r.slot.inicio.date_naive();
+                periodo.desde <= dia && dia <= periodo.hasta
+            })
+            .collect();
+        let contar = |estado: EstadoReserva| reservas.iter().filter(|r| r.estado == estado).count();
+        let canceladas = contar(EstadoReserva::Cancelada);
+        let terminadas: Vec<&&Reserva> = reservas
+            .iter()
+            .filter(|r| r.esta_activa() && r.slot.fin() <= ahora)
+            .collect();
+        let no_presentadas = terminadas
+            .iter()
+            .filter(|r| r.estado == EstadoReserva::Pendiente)
+            .count();
+
+        let slots_periodo = dias.len() * HORAS_LABORALES.len();
+        let uso = |id: &str, nombre: &str, de: &dyn Fn(&Reserva) -> bool| {
+            let suyas = reservas.iter().filter(|r| de(r));
+            let canceladas = suyas.clone().filter(|r| !r.esta_activa()).count();
+            let activas = suyas.count() - canceladas;
+            UsoRecurso {
+                id: id.to_string(),
+                nombre: nombre.to_string(),
+                reservas: activas,
+                canceladas,
+                ocupacion: fraccion(activas, slots_periodo),
+            }
+        };
+        let mut por_empleado: Vec<UsoRecurso> = empleados
+            .iter()
+            .map(|e| uso(&e.id, &e.nombre, &|r| r.empleado_id == e.id))
+            .zip(empleados)
+            .filter(|(uso, e)| e.activo || uso.reservas + uso.canceladas > 0)
+            .map(|(uso, _)| uso)
+            .collect();
+        let mut por_sala: Vec<UsoRecurso> = salas
+            .iter()
+            .map(|s| uso(&s.id, &s.nombre, &|r| r.sala_id.as_deref() == Some(&s.id)))
+            .zip(salas)
+            .filter(|(uso, s)| s.activa || uso.reservas + uso.canceladas > 0)
+            .map(|(uso, _)| uso)
+            .collect();
+        for usos in [&mut por_empleado, &mut por_sala] {
+            usos.sort_by(|a, b| b.reservas.cmp(&a.reservas).then(a.nombre.cmp(&b.nombre)));
+        }
+
+        // Reservas activas por slot laboral del periodo
+        let slots: Vec<Slot> = dias
+            .iter()
+            .flat_map(|dia| Slot::franja(*dia, HORAS_LABORALES.start, HORAS_LABORALES.end))
+            .flatten()
+            .collect();
+        let reservas_periodo: Vec<Reserva> = reservas.iter().map(|r| (*r).clone()).collect();
+        let ocupacion = DisponibilidadService::resumen_ocupacion(&slots, &reservas_periodo);
+        let mut por_hora: HashMap<u32, usize> = HashMap::new();
+        let mut por_fecha: HashMap<NaiveDate, usize> = HashMap::new();
+        for (slot, cuantas) in &ocupacion {
+            *por_hora.entry(slot.inicio.hour()).or_default() += cuantas;
+            *por_fecha.entry(slot.inicio.date_naive()).or_default() += cuantas;
+        }
+        let activos = empleados.iter().filter(|e| e.activo).count();
+
+        Self {
+            periodo,
+            total: reservas.len(),
+            pendientes: contar(EstadoReserva::Pendiente),
+            confirmadas: contar(EstadoReserva::Confirmada),
+            canceladas,
+            no_presentadas,
+            tasa_cancelacion: fraccion(canceladas, reservas.len()),
+            tasa_no_presentacion: fraccion(no_presentadas, terminadas.len()),
+            por_empleado,
+            por_sala,
+            por_hora: HORAS_LABORALES
+                .map(|hora| {
+                    let reservas = por_hora.get(&hora).copied().unwrap_or(0);
+                    UsoHora {
+                        hora,
+                        reservas,
+                        ocupacion: fraccion(reservas, activos * dias.len()),
+                    }
+                })
+                .collect(),
+            por_dia: dias
+                .iter()
+                .map(|fecha| {
+                    let reservas = por_fecha.get(fecha).copied().unwrap_or(0);
+                    UsoDia {
+                        fecha: *fecha,
+                        reservas,
+                        ocupacion: fraccion(reservas, activos * HORAS_LABORALES.len()),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Los `n` días con más reservas activas (sin contar los vacíos); a
+    /// igualdad, el más antiguo primero
+    pub fn dias_mas_ocupados(&self, n: usize) -> Vec<&UsoDia> {
+        let mut dias: Vec<&UsoDia> = self.por_dia.iter().filter(|d| d.reservas > 0).collect();
+        dias.sort_by(|a, b| b.reservas.cmp(&a.reservas).then(a.fecha.cmp(&b.fecha)));
+        dias.truncate(n);
+        dias
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Duration};
+
+    fn slot(dias: i64, hora: u32) -> Slot {
+        let dia = Utc::now() + Duration::days(dias);
+        Slot::from_date_and_hour(dia.year(), dia.month(), dia.day(), hora).unwrap()
+    }
+
+    fn reserva(id: &str, empleado_id: &str, slot: Slot) -> Reserva {
+        Reserva::new(
+            id.to_string(),
+            empleado_id.to_string(),
+            slot,
+            "Reunión".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_informe_ocupacion() {
+        let mut baja = Empleado::new(
+            "emp-003".to_string(),
+            "Eva".to_string(),
+            "eva@empresa.com".to_string(),
+        )
+        .unwrap();
+        baja.desactivar();
+        let empleados = vec![
+            Empleado::new(
+                "emp-001".to_string(),
+                "Juan".to_string(),
+                "juan@empresa.com".to_string(),
+            )
+            .unwrap(),
+            Empleado::new(
+                "emp-002".to_string(),
+                "María".to_string(),
+                "maria@empresa.com".to_string(),
+            )
+            .unwrap(),
+            baja,
+        ];
+        let salas = vec![Sala::new("sala-1".to_string(), "Andrómeda".to_string(), 8).unwrap()];
+
+        let mut confirmada = reserva("r1", "emp-001", slot(1, 10)).en_sala("sala-1".to_string());
+        confirmada.confirmar();
+        let mut cancelada = reserva("r3", "emp-002", slot(1, 10));
+        cancelada.cancelar();
+        let reservas = vec![
+            confirmada,
+            reserva("r2", "emp-001", slot(1, 11)),
+            cancelada,
+            reserva("r4", "emp-002", slot(2, 10)),
+            // Fuera del periodo
+            reserva("r5", "emp-002", slot(5, 10)),
+        ];
+        let periodo = Periodo::new(
+            slot(1, 9).inicio.date_naive(),
+            slot(2, 9).inicio.date_naive(),
+        )
+        .unwrap();
+
+        // Dentro de tres días, todas las reservas del periodo han terminado
+        let ahora = slot(3, 9).inicio;
+        let informe = InformeOcupacion::generar(periodo, &empleados, &salas, &reservas, ahora);
+
+        assert_eq!(informe.total, 4);
+        assert_eq!(informe.confirmadas, 1);
+        assert_eq!(informe.pendientes, 2);
+        assert_eq!(informe.canceladas, 1);
+        assert_eq!(informe.tasa_cancelacion, 0.25);
+        // r2 y r4 terminaron sin confirmar, de 3 activas terminadas
+        assert_eq!(informe.no_presentadas, 2);
+        assert!((informe.tasa_no_presentacion - 2.0 / 3.0).abs() < 1e-9);
+
+        // La empleada dada de baja y sin reservas no sale
+        let empleados: Vec<(&str, usize, usize)> = informe
+            .por_empleado
+            .iter()
+            .map(|u| (u.id.as_str(), u.reservas, u.canceladas))
+            .collect();
+        assert_eq!(empleados, [("emp-001", 2, 0), ("emp-002", 1, 1)]);
+        assert_eq!(informe.por_empleado[0].ocupacion, 2.0 / 18.0);
+        assert_eq!(informe.por_sala[0].reservas, 1);
+
+        let diez = informe.por_hora.iter().find(|h| h.hora == 10).unwrap();
+        assert_eq!(diez.reservas, 2);
+        assert_eq!(diez.ocupacion, 0.5);
+        assert_eq!(informe.por_hora.len(), 9);
+
+        assert_eq!(informe.por_dia.len(), 2);
+        assert_eq!(informe.por_dia[0].reservas, 2);
+        let mas_ocupados = informe.dias_mas_ocupados(1);
+        assert_eq!(mas_ocupados.len(), 1);
+        assert_eq!(mas_ocupados[0].fecha, periodo.desde);
+
+        // Antes de que empiecen no hay no presentadas
+        let informe = InformeOcupacion::generar(periodo, &[], &[], &reservas, Utc::now());
+        assert_eq!(informe.no_presentadas, 0);
+        assert_eq!(informe.tasa_no_presentacion, 0.0);
+        assert_eq!(informe.por_hora[1].ocupacion, 0.0);
+    }
+}
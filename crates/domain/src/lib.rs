@@ -2,7 +2,7 @@
 //
 // Esta capa contiene:
 // - Entidades de negocio (Reserva, Empleado)
-// - Value Objects (Slot)
+// - Value Objects (Slot, Email)
 // - Servicios de dominio
 // - Reglas de negocio
 // - Validaciones
@@ -10,15 +10,28 @@
 // PRINCIPIO: El dominio NO conoce nada de infraestructura
 // No depende de bases de datos, APIs, frameworks, etc.
 
+pub mod autorizacion;
+pub mod cuenta;
 pub mod disponibilidad;
+pub mod email;
 pub mod empleado;
+pub mod informe;
 pub mod reserva;
 pub mod slot;
+pub mod validacion;
 
 pub mod sala;
 pub use sala::Sala;
 
-pub use disponibilidad::DisponibilidadService;
+pub use autorizacion::{Accion, Actor, Rol};
+pub use cuenta::Cuenta;
+pub use disponibilidad::{
+    DisponibilidadSalaSlot, DisponibilidadService, DisponibilidadSlot, FilaMatriz,
+    MatrizDisponibilidad, Periodo, TipoFila,
+};
+pub use email::Email;
 pub use empleado::Empleado;
+pub use informe::{InformeOcupacion, UsoDia, UsoHora, UsoRecurso};
 pub use reserva::{EstadoReserva, Reserva, ReservaError};
 pub use slot::Slot;
+pub use validacion::ErrorCampo;
@@ -10,6 +10,8 @@ pub struct Reserva {
     pub slot: Slot,
     pub descripcion: String,
     pub estado: EstadoReserva,
+    /// Sala reservada junto con el slot (opcional)
+    pub sala_id: Option<String>,
 }
 
 /// Estados posibles de una reserva
@@ -37,6 +39,24 @@ impl Reserva {
         slot: Slot,
         descripcion: String,
     ) -> Result<Self, ReservaError> {
+        Self::validar_slot(&slot)?;
+
+        // Regla de negocio: la descripción no puede estar vacía
+        if descripcion.trim().is_empty() {
+            return Err(ReservaError::DescripcionVacia);
+        }
+
+        Ok(Reserva {
+            id,
+            empleado_id,
+            slot,
+            descripcion,
+            estado: EstadoReserva::Pendiente,
+            sala_id: None,
+        })
+    }
+
+    fn validar_slot(slot: &Slot) -> Result<(), ReservaError> {
         // Regla de negocio: no se pueden hacer reservas en el pasado
         if slot.inicio < Utc::now() {
             return Err(ReservaError::SlotEnElPasado);
@@ -47,18 +67,39 @@ impl Reserva {
             return Err(ReservaError::SlotFueraDeHorarioLaboral);
         }
 
-        // Regla de negocio: la descripción no puede estar vacía
+        Ok(())
+    }
+
+    /// Mueve la reserva a otro slot con las mismas reglas que al crearla.
+    /// Conserva ID, descripción y sala; una reserva confirmada vuelve a
+    /// pendiente, porque la confirmación era para el slot anterior.
+    pub fn reprogramar(&mut self, slot: Slot) -> Result<(), ReservaError> {
+        Self::validar_slot(&slot)?;
+        if slot != self.slot && self.estado == EstadoReserva::Confirmada {
+            self.estado = EstadoReserva::Pendiente;
+        }
+        self.slot = slot;
+        Ok(())
+    }
+
+    /// Asocia la reserva a una sala
+    pub fn en_sala(mut self, sala_id: String) -> Self {
+        self.sala_id = Some(sala_id);
+        self
+    }
+
+    /// Cambia la descripción con la misma regla que al crearla
+    pub fn cambiar_descripcion(&mut self, descripcion: String) -> Result<(), ReservaError> {
         if descripcion.trim().is_empty() {
             return Err(ReservaError::DescripcionVacia);
         }
+        self.descripcion = descripcion;
+        Ok(())
+    }
 
-        Ok(Reserva {
-            id,
-            empleado_id,
-            slot,
-            descripcion,
-            estado: EstadoReserva::Pendiente,
-        })
+    /// Pasa la reserva a otro empleado (p. ej. al desactivar al titular)
+    pub fn reasignar(&mut self, empleado_id: String) {
+        self.empleado_id = empleado_id;
     }
 
     /// Lógica de negocio: confirmar reserva
@@ -99,6 +140,25 @@ mod tests {
         let r = reserva.unwrap();
         assert_eq!(r.estado, EstadoReserva::Pendiente);
         assert_eq!(r.empleado_id, "emp-001");
+        assert_eq!(r.sala_id, None);
+    }
+
+    #[test]
+    fn test_reserva_en_sala() {
+        let manyana = Utc::now() + chrono::Duration::days(1);
+        let slot =
+            Slot::from_date_and_hour(manyana.year(), manyana.month(), manyana.day(), 10).unwrap();
+
+        let reserva = Reserva::new(
+            "1".to_string(),
+            "emp-001".to_string(),
+            slot,
+            "Reunión de equipo".to_string(),
+        )
+        .unwrap()
+        .en_sala("sala-1".to_string());
+
+        assert_eq!(reserva.sala_id.as_deref(), Some("sala-1"));
     }
 
     #[test]
@@ -153,6 +213,60 @@ mod tests {
         assert_eq!(reserva, Err(ReservaError::DescripcionVacia));
     }
 
+    #[test]
+    fn test_cambiar_descripcion() {
+        let manyana = Utc::now() + chrono::Duration::days(1);
+        let slot =
+            Slot::from_date_and_hour(manyana.year(), manyana.month(), manyana.day(), 10).unwrap();
+        let mut reserva = Reserva::new(
+            "1".to_string(),
+            "emp-001".to_string(),
+            slot,
+            "Reunión".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            reserva.cambiar_descripcion(" ".to_string()),
+            Err(ReservaError::DescripcionVacia)
+        );
+        reserva
+            .cambiar_descripcion("Reunión con cliente".to_string())
+            .unwrap();
+        assert_eq!(reserva.descripcion, "Reunión con cliente");
+    }
+
+    #[test]
+    fn test_reprogramar() {
+        let manyana = Utc::now() + chrono::Duration::days(1);
+        let slot = |hora| {
+            Slot::from_date_and_hour(manyana.year(), manyana.month(), manyana.day(), hora).unwrap()
+        };
+
+        let mut reserva = Reserva::new(
+            "1".to_string(),
+            "emp-001".to_string(),
+            slot(10),
+            "Reunión".to_string(),
+        )
+        .unwrap();
+        reserva.confirmar();
+
+        assert_eq!(
+            reserva.reprogramar(slot(20)),
+            Err(ReservaError::SlotFueraDeHorarioLaboral)
+        );
+        assert_eq!(reserva.slot, slot(10));
+
+        reserva.reprogramar(slot(10)).unwrap();
+        assert_eq!(reserva.estado, EstadoReserva::Confirmada);
+
+        reserva.reprogramar(slot(15)).unwrap();
+        assert_eq!(reserva.slot, slot(15));
+        assert_eq!(reserva.id, "1");
+        assert_eq!(reserva.estado, EstadoReserva::Pendiente);
+    }
+
     #[test]
     fn test_confirmar_reserva() {
         let manyana = Utc::now() + chrono::Duration::days(1);
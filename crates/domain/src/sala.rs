@@ -1,6 +1,6 @@
 //use chrono::{DateTime, Utc};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Sala {
     pub id: String,
     pub nombre: String,
@@ -10,12 +10,7 @@ pub struct Sala {
 
 impl Sala {
     pub fn new(id: String, nombre: String, capacidad: u32) -> Result<Self, String> {
-        if nombre.trim().is_empty() {
-            return Err("El nombre no puede estar vacío".to_string());
-        }
-        if capacidad == 0 {
-            return Err("La capacidad debe ser mayor a 0".to_string());
-        }
+        Self::validar(&nombre, capacidad)?;
 
         Ok(Self {
             id,
@@ -25,6 +20,25 @@ impl Sala {
         })
     }
 
+    /// Cambia los datos editables de la sala con las mismas reglas que al crearla
+    pub fn modificar(&mut self, nombre: String, capacidad: u32) -> Result<(), String> {
+        Self::validar(&nombre, capacidad)?;
+
+        self.nombre = nombre;
+        self.capacidad = capacidad;
+        Ok(())
+    }
+
+    fn validar(nombre: &str, capacidad: u32) -> Result<(), String> {
+        if nombre.trim().is_empty() {
+            return Err("El nombre no puede estar vacío".to_string());
+        }
+        if capacidad == 0 {
+            return Err("La capacidad debe ser mayor a 0".to_string());
+        }
+        Ok(())
+    }
+
     pub fn desactivar(&mut self) {
         self.activa = false;
     }
@@ -64,6 +64,24 @@ impl Slot {
         slots
     }
 
+    /// Slots consecutivos de un día desde la hora `desde` hasta la hora
+    /// `hasta` (no incluida), dentro del horario laboral
+    pub fn franja(fecha: chrono::NaiveDate, desde: u32, hasta: u32) -> Result<Vec<Self>, String> {
+        if hasta <= desde {
+            return Err("La hora de fin debe ser posterior a la de inicio".to_string());
+        }
+        if desde < 9 || hasta > 18 {
+            return Err(
+                "La franja debe estar dentro del horario laboral (9:00 - 18:00)".to_string(),
+            );
+        }
+        Ok((desde..hasta)
+            .filter_map(|hora| {
+                Self::from_date_and_hour(fecha.year(), fecha.month(), fecha.day(), hora)
+            })
+            .collect())
+    }
+
     /// Formatea el slot para mostrar
     pub fn formato_legible(&self) -> String {
         format!(
@@ -111,6 +129,22 @@ mod tests {
         assert_eq!(slots.len(), 9); // 9:00 a 17:00 (9 slots)
     }
 
+    #[test]
+    fn test_franja() {
+        let fecha = chrono::NaiveDate::from_ymd_opt(2025, 11, 22).unwrap();
+        let franja = Slot::franja(fecha, 10, 13).unwrap();
+        assert_eq!(franja.len(), 3);
+        assert_eq!(
+            franja[0],
+            Slot::from_date_and_hour(2025, 11, 22, 10).unwrap()
+        );
+        assert_eq!(franja[2].fin().hour(), 13);
+
+        assert!(Slot::franja(fecha, 12, 12).is_err());
+        assert!(Slot::franja(fecha, 8, 10).is_err());
+        assert!(Slot::franja(fecha, 17, 19).is_err());
+    }
+
     #[test]
     fn test_formato_legible() {
         let slot = Slot::from_date_and_hour(2025, 11, 22, 14).unwrap();
@@ -0,0 +1,24 @@
+use std::fmt;
+
+/// Error de validación asociado a un campo concreto, para que los
+/// formularios y la API puedan indicar qué dato corregir
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorCampo {
+    pub campo: String,
+    pub mensaje: String,
+}
+
+impl ErrorCampo {
+    pub fn new(campo: &str, mensaje: impl Into<String>) -> Self {
+        Self {
+            campo: campo.to_string(),
+            mensaje: mensaje.into(),
+        }
+    }
+}
+
+impl fmt::Display for ErrorCampo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.campo, self.mensaje)
+    }
+}
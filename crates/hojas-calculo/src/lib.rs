@@ -0,0 +1,297 @@
+// 📊 HOJAS DE CÁLCULO - Adaptador de formatos de fichero CSV y XLSX
+//
+// Convierte entre ficheros y la `Tabla` de los puertos, que solo tiene
+// texto: qué significa cada columna lo decide el caso de uso. Lo usan la
+// API REST y la interfaz web para las cargas y exportaciones masivas.
+//
+// - CSV: UTF-8, con o sin BOM. El separador (`,`, `;` o tabulador) se deduce
+//   de la cabecera, porque Excel en español guarda con `;`. Se escribe con
+//   `,` y BOM para que Excel reconozca la codificación.
+// - XLSX: se lee la primera hoja. Los números enteros se leen sin decimales
+//   y las fechas como `AAAA-MM-DD HH:MM`.
+//
+// Los textos vienen de los usuarios: al escribir, los que Excel tomaría por
+// una fórmula (`=`, `+`, `-`, `@`...) llevan delante un `'`, que se quita al
+// leer para que exportar e importar deje los datos como estaban.
+
+use calamine::{open_workbook_from_rs, Data, DataType, Reader, Xlsx};
+use reservas_ports::r#in::carga_masiva_service::Tabla;
+use rust_xlsxwriter::{Format, Workbook};
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::str::FromStr;
+
+/// Marca de orden de bytes con la que Excel guarda los CSV en UTF-8
+const BOM: &str = "\u{feff}";
+
+/// Primeros caracteres con los que una hoja de cálculo evalúa la celda
+const INICIO_FORMULA: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Formato {
+    Csv,
+    Xlsx,
+}
+
+impl Formato {
+    /// Formato según la extensión del nombre de fichero
+    pub fn desde_nombre(fichero: &str) -> Option<Self> {
+        let (_, extension) = fichero.rsplit_once('.')?;
+        extension.parse().ok()
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Formato::Csv => "csv",
+            Formato::Xlsx => "xlsx",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Formato::Csv => "text/csv; charset=utf-8",
+            Formato::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+}
+
+impl FromStr for Formato {
+    type Err = String;
+
+    fn from_str(valor: &str) -> Result<Self, Self::Err> {
+        match valor.trim().to_lowercase().as_str() {
+            "csv" => Ok(Formato::Csv),
+            "xlsx" => Ok(Formato::Xlsx),
+            _ => Err(format!("Formato no admitido: {} (use csv o xlsx)", valor)),
+        }
+    }
+}
+
+/// Lee el fichero; la primera fila son los nombres de columna
+pub fn leer(datos: &[u8], formato: Formato) -> Result<Tabla, String> {
+    let mut filas = match formato {
+        Formato::Csv => leer_csv(datos)?,
+        Formato::Xlsx => leer_xlsx(datos)?,
+    }
+    .into_iter();
+    let columnas = filas
+        .next()
+        .ok_or_else(|| "La hoja está vacía".to_string())?;
+    Ok(Tabla {
+        columnas,
+        filas: filas
+            .map(|fila| fila.into_iter().map(restaurar).collect())
+            .collect(),
+    })
+}
+
+pub fn escribir(tabla: &Tabla, formato: Formato) -> Result<Vec<u8>, String> {
+    match formato {
+        Formato::Csv => escribir_csv(tabla),
+        Formato::Xlsx => escribir_xlsx(tabla),
+    }
+}
+
+/// Texto que no se ejecuta como fórmula al abrir el fichero. Los números
+/// negativos se quedan como están.
+fn neutralizar(valor: &str) -> Cow<'_, str> {
+    if valor.starts_with(INICIO_FORMULA) && valor.parse::<f64>().is_err() {
+        Cow::Owned(format!("'{}", valor))
+    } else {
+        Cow::Borrowed(valor)
+    }
+}
+
+/// Deshace `neutralizar`
+fn restaurar(valor: String) -> String {
+    match valor.strip_prefix('\'') {
+        Some(resto) if neutralizar(resto).len() != resto.len() => resto.to_string(),
+        _ => valor,
+    }
+}
+
+/// Separador más frecuente en la cabecera
+fn separador(cabecera: &str) -> u8 {
+    [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|s| cabecera.bytes().filter(|b| b == s).count())
+        .unwrap_or(b',')
+}
+
+fn leer_csv(datos: &[u8]) -> Result<Vec<Vec<String>>, String> {
+    let texto = std::str::from_utf8(datos)
+        .map_err(|_| "El CSV debe estar codificado en UTF-8".to_string())?;
+    let texto = texto.strip_prefix(BOM).unwrap_or(texto);
+    let cabecera = texto.lines().next().unwrap_or_default();
+
+    let mut lector = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(separador(cabecera))
+        .from_reader(texto.as_bytes());
+    lector
+        .records()
+        .map(|registro| {
+            registro
+                .map(|r| r.iter().map(str::to_string).collect())
+                .map_err(|e| format!("CSV no válido: {}", e))
+        })
+        .collect()
+}
+
+fn celda(dato: &Data) -> String {
+    match dato {
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < 1e15 => format!("{}", *f as i64),
+        Data::DateTime(_) | Data::DateTimeIso(_) => dato
+            .as_datetime()
+            .map(|fecha| fecha.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| dato.to_string()),
+        _ => dato.to_string(),
+    }
+}
+
+fn leer_xlsx(datos: &[u8]) -> Result<Vec<Vec<String>>, String> {
+    let mut libro: Xlsx<_> =
+        open_workbook_from_rs(Cursor::new(datos)).map_err(|e| format!("XLSX no válido: {}", e))?;
+    let hoja = libro
+        .worksheet_range_at(0)
+        .ok_or_else(|| "El XLSX no tiene hojas".to_string())?
+        .map_err(|e| format!("XLSX no válido: {}", e))?;
+    Ok(hoja
+        .rows()
+        .map(|fila| fila.iter().map(celda).collect())
+        .collect())
+}
+
+fn escribir_csv(tabla: &Tabla) -> Result<Vec<u8>, String> {
+    let mut escritor = csv::WriterBuilder::new()
+        .flexible(true)
+        .from_writer(BOM.as_bytes().to_vec());
+    let error = |e: csv::Error| format!("Error generando el CSV: {}", e);
+    escritor.write_record(&tabla.columnas).map_err(error)?;
+    for fila in &tabla.filas {
+        escritor
+            .write_record(fila.iter().map(|valor| neutralizar(valor).into_owned()))
+            .map_err(error)?;
+    }
+    escritor
+        .into_inner()
+        .map_err(|e| format!("Error generando el CSV: {}", e))
+}
+
+fn escribir_xlsx(tabla: &Tabla) -> Result<Vec<u8>, String> {
+    let error = |e: rust_xlsxwriter::XlsxError| format!("Error generando el XLSX: {}", e);
+    let mut libro = Workbook::new();
+    let hoja = libro.add_worksheet();
+    let negrita = Format::new().set_bold();
+    for (columna, nombre) in tabla.columnas.iter().enumerate() {
+        hoja.write_string_with_format(0, columna as u16, nombre, &negrita)
+            .map_err(error)?;
+    }
+    for (fila, valores) in tabla.filas.iter().enumerate() {
+        let fila = fila as u32 + 1;
+        for (columna, valor) in valores.iter().enumerate() {
+            let columna = columna as u16;
+            // Los enteros como número, para poder ordenar y sumar; los que
+            // empiezan por 0 (códigos) se quedan como texto
+            match valor.parse::<i64>() {
+                Ok(numero) if !valor.starts_with('0') || valor == "0" => {
+                    hoja.write_number(fila, columna, numero as f64)
+                }
+                _ => hoja.write_string(fila, columna, neutralizar(valor)),
+            }
+            .map_err(error)?;
+        }
+    }
+    hoja.set_freeze_panes(1, 0).map_err(error)?;
+    hoja.autofit();
+    libro.save_to_buffer().map_err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tabla() -> Tabla {
+        Tabla {
+            columnas: vec![
+                "nombre".to_string(),
+                "email".to_string(),
+                "capacidad".to_string(),
+            ],
+            filas: vec![
+                vec![
+                    "López, Juan".to_string(),
+                    "juan@empresa.com".to_string(),
+                    "8".to_string(),
+                ],
+                vec![
+                    "Ana \"la jefa\"".to_string(),
+                    "ana@empresa.com".to_string(),
+                    "012".to_string(),
+                ],
+                vec!["Eva".to_string()],
+            ],
+        }
+    }
+
+    #[test]
+    fn test_csv_de_ida_y_vuelta() {
+        let csv = escribir(&tabla(), Formato::Csv).unwrap();
+        assert!(csv.starts_with(BOM.as_bytes()));
+        // Las filas cortas se leen tal cual
+        assert_eq!(leer(&csv, Formato::Csv).unwrap(), tabla());
+    }
+
+    #[test]
+    fn test_csv_de_excel_con_punto_y_coma() {
+        let csv =
+            "Nombre;Correo\r\nJuan López;juan@empresa.com\r\n\"Ana; RRHH\";ana@empresa.com\r\n";
+        let leida = leer(csv.as_bytes(), Formato::Csv).unwrap();
+        assert_eq!(leida.columnas, ["Nombre", "Correo"]);
+        assert_eq!(leida.filas[1], ["Ana; RRHH", "ana@empresa.com"]);
+
+        assert!(leer(&[0xff, 0xfe, 0x41], Formato::Csv).is_err());
+        assert_eq!(leer(b"", Formato::Csv).unwrap_err(), "La hoja está vacía");
+    }
+
+    #[test]
+    fn test_xlsx_de_ida_y_vuelta() {
+        let xlsx = escribir(&tabla(), Formato::Xlsx).unwrap();
+        let leida = leer(&xlsx, Formato::Xlsx).unwrap();
+        assert_eq!(leida.columnas, tabla().columnas);
+        assert_eq!(leida.filas[0], ["López, Juan", "juan@empresa.com", "8"]);
+        assert_eq!(leida.filas[1][2], "012");
+        // El rango es rectangular: las celdas que faltan se leen vacías
+        assert_eq!(leida.filas[2], ["Eva", "", ""]);
+
+        assert!(leer(b"no es un zip", Formato::Xlsx).is_err());
+    }
+
+    #[test]
+    fn test_formulas_neutralizadas() {
+        let peligrosa = Tabla {
+            columnas: vec!["descripcion".to_string(), "saldo".to_string()],
+            filas: vec![
+                vec!["=HYPERLINK(\"http://x\")".to_string(), "-3".to_string()],
+                vec!["@SUM(A1)".to_string(), "+34 600".to_string()],
+            ],
+        };
+        let csv = escribir(&peligrosa, Formato::Csv).unwrap();
+        let texto = String::from_utf8(csv.clone()).unwrap();
+        assert!(texto.contains("\"'=HYPERLINK(\"\"http://x\"\")\",-3"));
+        assert!(texto.contains("'@SUM(A1),'+34 600"));
+        assert_eq!(leer(&csv, Formato::Csv).unwrap(), peligrosa);
+
+        let xlsx = escribir(&peligrosa, Formato::Xlsx).unwrap();
+        assert_eq!(leer(&xlsx, Formato::Xlsx).unwrap(), peligrosa);
+    }
+
+    #[test]
+    fn test_formato_desde_nombre() {
+        assert_eq!(Formato::desde_nombre("plantilla.XLSX"), Some(Formato::Xlsx));
+        assert_eq!(Formato::desde_nombre("empleados.csv"), Some(Formato::Csv));
+        assert_eq!(Formato::desde_nombre("empleados.ods"), None);
+        assert_eq!(Formato::desde_nombre("sin_extension"), None);
+    }
+}
@@ -0,0 +1,623 @@
+// 📅 ICAL - Formato iCalendar (RFC 5545)
+//
+// Lo comparten los adaptadores de entrada que hablan iCalendar: los
+// calendarios suscribibles y la importación de la API REST, y el servidor
+// CalDAV.
+//
+// Cada reserva es un VEVENT cuyo UID sale del ID de la reserva, así que los
+// clientes reconocen el mismo evento de una descarga a otra aunque cambie de
+// slot o de estado. Las canceladas se publican con STATUS:CANCELLED para que
+// los clientes las quiten de la agenda. Para las consultas de libre/ocupado
+// se publica un VFREEBUSY, sin títulos.
+//
+// Al importar se lee el subconjunto que necesita una reserva: UID, SUMMARY,
+// DTSTART, DTEND o DURATION, ATTENDEE, STATUS y RRULE. Las horas sin zona
+// (flotantes o con un TZID distinto de UTC) se interpretan con el desfase que
+// indique quien importa, porque no hay base de datos de zonas horarias.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use reservas_domain::{EstadoReserva, Reserva};
+use reservas_ports::r#in::calendario_service::{EventoCalendario, FeedCalendario};
+use reservas_ports::r#in::importacion_service::EventoExterno;
+
+/// Tipo MIME de iCalendar
+pub const CONTENT_TYPE_ICS: &str = "text/calendar; charset=utf-8";
+
+/// Identifica al generador en PRODID
+const PRODID: &str = "-//Sistema de Reservas//Reservas 1.0//ES";
+
+/// Dominio de los UID, para que no choquen con los de otros calendarios
+const DOMINIO_UID: &str = "reservas";
+
+/// Octetos máximos por línea antes de plegarla
+const LONGITUD_LINEA: usize = 75;
+
+pub fn estado_ics(estado: &EstadoReserva) -> &'static str {
+    match estado {
+        EstadoReserva::Pendiente => "TENTATIVE",
+        EstadoReserva::Confirmada => "CONFIRMED",
+        EstadoReserva::Cancelada => "CANCELLED",
+    }
+}
+
+pub fn uid(reserva_id: &str) -> String {
+    format!("{}@{}", reserva_id, DOMINIO_UID)
+}
+
+/// ID de la reserva de un UID generado por `uid`; `None` si es de otro calendario
+pub fn reserva_de_uid(uid: &str) -> Option<&str> {
+    uid.strip_suffix(DOMINIO_UID)?
+        .strip_suffix('@')
+        .filter(|id| !id.is_empty())
+}
+
+fn fecha_ics(fecha: DateTime<Utc>) -> String {
+    fecha.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapa un valor TEXT: barra invertida, punto y coma, coma y saltos de línea
+fn escapar(texto: &str) -> String {
+    let mut escapado = String::with_capacity(texto.len());
+    for c in texto.chars() {
+        match c {
+            '\\' => escapado.push_str("\\\\"),
+            ';' => escapado.push_str("\\;"),
+            ',' => escapado.push_str("\\,"),
+            '\n' => escapado.push_str("\\n"),
+            '\r' => {}
+            c => escapado.push(c),
+        }
+    }
+    escapado
+}
+
+/// Añade la línea terminada en CRLF, plegada a 75 octetos sin partir caracteres
+fn escribir_linea(salida: &mut String, linea: &str) {
+    let mut ocupados = 0;
+    for c in linea.chars() {
+        if ocupados + c.len_utf8() > LONGITUD_LINEA {
+            salida.push_str("\r\n ");
+            // El espacio del plegado cuenta en la línea siguiente
+            ocupados = 1;
+        }
+        salida.push(c);
+        ocupados += c.len_utf8();
+    }
+    salida.push_str("\r\n");
+}
+
+fn escribir_evento(salida: &mut String, evento: &EventoCalendario, generado: DateTime<Utc>) {
+    let reserva = &evento.reserva;
+    escribir_linea(salida, "BEGIN:VEVENT");
+    escribir_linea(salida, &format!("UID:{}", escapar(&uid(&reserva.id))));
+    escribir_linea(salida, &format!("DTSTAMP:{}", fecha_ics(generado)));
+    escribir_linea(
+        salida,
+        &format!("DTSTART:{}", fecha_ics(reserva.slot.inicio)),
+    );
+    escribir_linea(salida, &format!("DTEND:{}", fecha_ics(reserva.slot.fin())));
+    escribir_linea(
+        salida,
+        &format!("SUMMARY:{}", escapar(&reserva.descripcion)),
+    );
+    if let Some(empleado) = &evento.empleado {
+        escribir_linea(
+            salida,
+            &format!(
+                "DESCRIPTION:{}",
+                escapar(&format!("Reserva de {}", empleado))
+            ),
+        );
+    }
+    if let Some(sala) = &evento.sala {
+        escribir_linea(salida, &format!("LOCATION:{}", escapar(sala)));
+    }
+    escribir_linea(salida, &format!("STATUS:{}", estado_ics(&reserva.estado)));
+    escribir_linea(salida, "TRANSP:OPAQUE");
+    escribir_linea(salida, "END:VEVENT");
+}
+
+fn escribir_cabecera(salida: &mut String) {
+    escribir_linea(salida, "BEGIN:VCALENDAR");
+    escribir_linea(salida, "VERSION:2.0");
+    escribir_linea(salida, &format!("PRODID:{}", PRODID));
+    escribir_linea(salida, "CALSCALE:GREGORIAN");
+}
+
+/// VCALENDAR completo. `generado` es el DTSTAMP de todos los eventos.
+pub fn serializar(feed: &FeedCalendario, generado: DateTime<Utc>) -> String {
+    let mut salida = String::new();
+    escribir_cabecera(&mut salida);
+    escribir_linea(&mut salida, "METHOD:PUBLISH");
+    escribir_linea(
+        &mut salida,
+        &format!(
+            "X-WR-CALNAME:{}",
+            escapar(&format!("Reservas - {}", feed.nombre))
+        ),
+    );
+    for evento in &feed.eventos {
+        escribir_evento(&mut salida, evento, generado);
+    }
+    escribir_linea(&mut salida, "END:VCALENDAR");
+    salida
+}
+
+/// VCALENDAR con un solo evento, como lo guarda un recurso CalDAV (sin METHOD)
+pub fn serializar_evento(evento: &EventoCalendario, generado: DateTime<Utc>) -> String {
+    let mut salida = String::new();
+    escribir_cabecera(&mut salida);
+    escribir_evento(&mut salida, evento, generado);
+    escribir_linea(&mut salida, "END:VCALENDAR");
+    salida
+}
+
+/// Respuesta VFREEBUSY (RFC 4791, free-busy-query) del intervalo
+/// `desde`-`hasta`. Las reservas pendientes cuentan como ocupación provisional
+/// y las canceladas no cuentan.
+pub fn serializar_libre_ocupado(
+    reservas: &[Reserva],
+    desde: DateTime<Utc>,
+    hasta: DateTime<Utc>,
+    generado: DateTime<Utc>,
+) -> String {
+    let mut salida = String::new();
+    escribir_cabecera(&mut salida);
+    escribir_linea(&mut salida, "BEGIN:VFREEBUSY");
+    escribir_linea(&mut salida, &format!("DTSTAMP:{}", fecha_ics(generado)));
+    escribir_linea(&mut salida, &format!("DTSTART:{}", fecha_ics(desde)));
+    escribir_linea(&mut salida, &format!("DTEND:{}", fecha_ics(hasta)));
+    for reserva in reservas {
+        let tipo = match reserva.estado {
+            EstadoReserva::Pendiente => "BUSY-TENTATIVE",
+            EstadoReserva::Confirmada => "BUSY",
+            EstadoReserva::Cancelada => continue,
+        };
+        if reserva.slot.fin() <= desde || reserva.slot.inicio >= hasta {
+            continue;
+        }
+        escribir_linea(
+            &mut salida,
+            &format!(
+                "FREEBUSY;FBTYPE={}:{}/{}",
+                tipo,
+                fecha_ics(reserva.slot.inicio),
+                fecha_ics(reserva.slot.fin())
+            ),
+        );
+    }
+    escribir_linea(&mut salida, "END:VFREEBUSY");
+    escribir_linea(&mut salida, "END:VCALENDAR");
+    salida
+}
+
+/// Propiedad de una línea de contenido: `NOMBRE;PARAM=valor:valor`
+struct Propiedad {
+    nombre: String,
+    parametros: Vec<(String, String)>,
+    valor: String,
+}
+
+impl Propiedad {
+    fn parametro(&self, nombre: &str) -> Option<&str> {
+        self.parametros
+            .iter()
+            .find(|(n, _)| n == nombre)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Une las líneas plegadas (las que empiezan por espacio o tabulador)
+fn desplegar(contenido: &str) -> Vec<String> {
+    let mut lineas: Vec<String> = Vec::new();
+    for linea in contenido.split('\n') {
+        let linea = linea.strip_suffix('\r').unwrap_or(linea);
+        match (linea.strip_prefix([' ', '\t']), lineas.last_mut()) {
+            (Some(continuacion), Some(anterior)) => anterior.push_str(continuacion),
+            _ if linea.is_empty() => {}
+            _ => lineas.push(linea.to_string()),
+        }
+    }
+    lineas
+}
+
+/// Separa nombre, parámetros y valor respetando los valores entre comillas
+fn leer_propiedad(linea: &str) -> Option<Propiedad> {
+    let mut entre_comillas = false;
+    let mut cortes = Vec::new();
+    let mut dos_puntos = None;
+    for (i, c) in linea.char_indices() {
+        match c {
+            '"' => entre_comillas = !entre_comillas,
+            ';' if !entre_comillas => cortes.push(i),
+            ':' if !entre_comillas => {
+                dos_puntos = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let dos_puntos = dos_puntos?;
+    let mut partes = cortes
+        .iter()
+        .chain(std::iter::once(&dos_puntos))
+        .scan(0, |desde, &hasta| {
+            let parte = &linea[*desde..hasta];
+            *desde = hasta + 1;
+            Some(parte)
+        });
+    let nombre = partes.next()?.to_ascii_uppercase();
+    let parametros = partes
+        .filter_map(|p| p.split_once('='))
+        .map(|(n, v)| (n.to_ascii_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Some(Propiedad {
+        nombre,
+        parametros,
+        valor: linea[dos_puntos + 1..].to_string(),
+    })
+}
+
+/// Deshace `escapar`
+fn desescapar(texto: &str) -> String {
+    let mut resultado = String::with_capacity(texto.len());
+    let mut caracteres = texto.chars();
+    while let Some(c) = caracteres.next() {
+        if c != '\\' {
+            resultado.push(c);
+            continue;
+        }
+        match caracteres.next() {
+            Some('n') | Some('N') => resultado.push('\n'),
+            Some(otro) => resultado.push(otro),
+            None => resultado.push('\\'),
+        }
+    }
+    resultado
+}
+
+/// DATE-TIME o DATE. Devuelve el instante y si era solo fecha.
+fn leer_fecha(
+    propiedad: &Propiedad,
+    desfase: FixedOffset,
+) -> Result<(DateTime<Utc>, bool), String> {
+    let valor = propiedad.valor.trim();
+    let error = || format!("{} no válido: {}", propiedad.nombre, valor);
+    if propiedad.parametro("VALUE") == Some("DATE") || valor.len() == 8 {
+        let fecha = NaiveDate::parse_from_str(valor, "%Y%m%d").map_err(|_| error())?;
+        let local = fecha.and_hms_opt(0, 0, 0).ok_or_else(error)?;
+        return Ok((en_desfase(local, desfase).ok_or_else(error)?, true));
+    }
+    if let Some(utc) = valor.strip_suffix('Z') {
+        let fecha = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| error())?;
+        return Ok((fecha.and_utc(), false));
+    }
+    let fecha = NaiveDateTime::parse_from_str(valor, "%Y%m%dT%H%M%S").map_err(|_| error())?;
+    let es_utc = propiedad
+        .parametro("TZID")
+        .is_some_and(|tz| matches!(tz.to_ascii_uppercase().as_str(), "UTC" | "ETC/UTC" | "Z"));
+    if es_utc {
+        Ok((fecha.and_utc(), false))
+    } else {
+        Ok((en_desfase(fecha, desfase).ok_or_else(error)?, false))
+    }
+}
+
+fn en_desfase(local: NaiveDateTime, desfase: FixedOffset) -> Option<DateTime<Utc>> {
+    desfase
+        .from_local_datetime(&local)
+        .single()
+        .map(|fecha| fecha.with_timezone(&Utc))
+}
+
+/// DURATION como `P1D`, `PT1H30M` o `P1W`; el signo negativo no tiene sentido aquí
+fn leer_duracion(valor: &str) -> Option<Duration> {
+    let mut resto = valor.trim().strip_prefix('+').unwrap_or(valor.trim());
+    resto = resto.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut numero = String::new();
+    for c in resto.chars() {
+        match c {
+            'T' => {}
+            '0'..='9' => numero.push(c),
+            unidad => {
+                let cantidad: i64 = numero.parse().ok()?;
+                numero.clear();
+                total += match unidad {
+                    'W' => Duration::weeks(cantidad),
+                    'D' => Duration::days(cantidad),
+                    'H' => Duration::hours(cantidad),
+                    'M' => Duration::minutes(cantidad),
+                    'S' => Duration::seconds(cantidad),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    numero.is_empty().then_some(total)
+}
+
+/// Lo leído de un VEVENT antes de completar el fin
+#[derive(Default)]
+struct Borrador {
+    uid: Option<String>,
+    resumen: String,
+    inicio: Option<(DateTime<Utc>, bool)>,
+    fin: Option<DateTime<Utc>>,
+    duracion: Option<Duration>,
+    asistentes: Vec<String>,
+    cancelado: bool,
+    periodico: bool,
+}
+
+impl Borrador {
+    fn aplicar(&mut self, propiedad: Propiedad, desfase: FixedOffset) -> Result<(), String> {
+        match propiedad.nombre.as_str() {
+            "UID" => self.uid = Some(desescapar(&propiedad.valor)),
+            "SUMMARY" => self.resumen = desescapar(&propiedad.valor),
+            "DTSTART" => self.inicio = Some(leer_fecha(&propiedad, desfase)?),
+            "DTEND" => self.fin = Some(leer_fecha(&propiedad, desfase)?.0),
+            "DURATION" => {
+                self.duracion = Some(
+                    leer_duracion(&propiedad.valor)
+                        .ok_or_else(|| format!("DURATION no válida: {}", propiedad.valor))?,
+                )
+            }
+            "ATTENDEE" => {
+                let valor = propiedad.valor.trim();
+                let email = valor
+                    .get(..7)
+                    .filter(|p| p.eq_ignore_ascii_case("mailto:"))
+                    .map_or(valor, |_| &valor[7..]);
+                self.asistentes.push(email.to_string());
+            }
+            "STATUS" => self.cancelado = propiedad.valor.trim().eq_ignore_ascii_case("CANCELLED"),
+            "RRULE" | "RDATE" => self.periodico = true,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn terminar(self, posicion: usize) -> Result<EventoExterno, String> {
+        let (inicio, dia_completo) = self
+            .inicio
+            .ok_or_else(|| format!("El evento {} no tiene DTSTART", posicion))?;
+        // Sin DTEND ni DURATION dura un día si es solo fecha, y nada si no
+        let fin = self.fin.unwrap_or_else(|| match self.duracion {
+            Some(duracion) => inicio + duracion,
+            None if dia_completo => inicio + Duration::days(1),
+            None => inicio,
+        });
+        Ok(EventoExterno {
+            uid: self.uid.unwrap_or_else(|| format!("evento-{}", posicion)),
+            resumen: self.resumen,
+            inicio,
+            fin,
+            asistentes: self.asistentes,
+            cancelado: self.cancelado,
+            dia_completo,
+            periodico: self.periodico,
+        })
+    }
+}
+
+/// Eventos de un VCALENDAR. `desfase` se aplica a las horas sin zona.
+pub fn deserializar(contenido: &str, desfase: FixedOffset) -> Result<Vec<EventoExterno>, String> {
+    let mut eventos = Vec::new();
+    let mut en_calendario = false;
+    let mut borrador: Option<Borrador> = None;
+    // Componentes anidados en el VEVENT (VALARM) cuyas propiedades se ignoran
+    let mut anidados = 0usize;
+
+    for linea in desplegar(contenido.trim_start_matches('\u{feff}')) {
+        let propiedad =
+            leer_propiedad(&linea).ok_or_else(|| format!("Línea no válida: {}", linea))?;
+        let componente = propiedad.valor.trim().to_ascii_uppercase();
+        match (propiedad.nombre.as_str(), componente.as_str()) {
+            ("BEGIN", "VCALENDAR") => en_calendario = true,
+            ("BEGIN", "VEVENT") if borrador.is_none() => borrador = Some(Borrador::default()),
+            ("BEGIN", _) if borrador.is_some() => anidados += 1,
+            ("END", "VEVENT") if anidados == 0 => {
+                let terminado = borrador
+                    .take()
+                    .ok_or("END:VEVENT sin BEGIN:VEVENT")?
+                    .terminar(eventos.len() + 1)?;
+                eventos.push(terminado);
+            }
+            ("END", _) if anidados > 0 => anidados -= 1,
+            _ => {
+                if let (Some(borrador), 0) = (borrador.as_mut(), anidados) {
+                    borrador.aplicar(propiedad, desfase)?;
+                }
+            }
+        }
+    }
+    if !en_calendario {
+        return Err("El fichero no es un VCALENDAR".to_string());
+    }
+    if borrador.is_some() {
+        return Err("VEVENT sin terminar".to_string());
+    }
+    Ok(eventos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use reservas_domain::Slot;
+
+    fn evento(id: &str, estado: EstadoReserva, descripcion: &str) -> EventoCalendario {
+        EventoCalendario {
+            reserva: Reserva {
+                id: id.to_string(),
+                empleado_id: "emp-001".to_string(),
+                slot: Slot::from_date_and_hour(2025, 11, 25, 10).unwrap(),
+                descripcion: descripcion.to_string(),
+                estado,
+                sala_id: Some("s1".to_string()),
+            },
+            empleado: Some("Juan".to_string()),
+            sala: Some("Sala Azul".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_vevents_con_uid_estable_y_estado() {
+        let feed = FeedCalendario {
+            nombre: "Juan".to_string(),
+            eventos: vec![
+                evento("r1", EstadoReserva::Pendiente, "Revisión, sprint; 1"),
+                evento("r2", EstadoReserva::Confirmada, "Demo"),
+                evento("r3", EstadoReserva::Cancelada, "Baja"),
+            ],
+        };
+        let generado = Utc.with_ymd_and_hms(2025, 11, 20, 8, 0, 0).unwrap();
+        let ics = serializar(&feed, generado);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("X-WR-CALNAME:Reservas - Juan\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 3);
+        assert!(ics.contains(
+            "BEGIN:VEVENT\r\nUID:r1@reservas\r\nDTSTAMP:20251120T080000Z\r\n\
+             DTSTART:20251125T100000Z\r\nDTEND:20251125T110000Z\r\n\
+             SUMMARY:Revisión\\, sprint\\; 1\r\nDESCRIPTION:Reserva de Juan\r\n\
+             LOCATION:Sala Azul\r\nSTATUS:TENTATIVE\r\n"
+        ));
+        assert!(ics.contains("UID:r2@reservas\r\n"));
+        assert!(ics.contains("STATUS:CONFIRMED\r\n"));
+        assert!(ics.contains("STATUS:CANCELLED\r\n"));
+        // Mismo contenido, misma salida: los clientes no ven cambios falsos
+        assert_eq!(ics, serializar(&feed, generado));
+    }
+
+    #[test]
+    fn test_reserva_de_uid() {
+        assert_eq!(reserva_de_uid(&uid("r1")), Some("r1"));
+        assert_eq!(reserva_de_uid("ABC-123"), None);
+        assert_eq!(reserva_de_uid("r1@otro.com"), None);
+        assert_eq!(reserva_de_uid("@reservas"), None);
+    }
+
+    #[test]
+    fn test_lineas_largas_plegadas() {
+        let descripcion = "ñ".repeat(60);
+        let feed = FeedCalendario {
+            nombre: "Sala Azul".to_string(),
+            eventos: vec![evento("r1", EstadoReserva::Pendiente, &descripcion)],
+        };
+        let ics = serializar(&feed, Utc::now());
+
+        for linea in ics.split("\r\n") {
+            assert!(linea.len() <= LONGITUD_LINEA, "{}", linea);
+        }
+        // Al desplegar se recupera el valor original
+        let desplegado = ics.replace("\r\n ", "");
+        assert!(desplegado.contains(&format!("SUMMARY:{}\r\n", descripcion)));
+    }
+
+    #[test]
+    fn test_libre_ocupado_del_intervalo() {
+        let reserva = |id: &str, hora: u32, estado: EstadoReserva| Reserva {
+            estado,
+            slot: Slot::from_date_and_hour(2025, 11, 25, hora).unwrap(),
+            ..evento(id, EstadoReserva::Pendiente, "x").reserva
+        };
+        let reservas = [
+            reserva("r1", 9, EstadoReserva::Confirmada),
+            reserva("r2", 10, EstadoReserva::Pendiente),
+            reserva("r3", 11, EstadoReserva::Cancelada),
+            reserva("r4", 17, EstadoReserva::Confirmada),
+        ];
+        let desde = Utc.with_ymd_and_hms(2025, 11, 25, 9, 0, 0).unwrap();
+        let hasta = Utc.with_ymd_and_hms(2025, 11, 25, 12, 0, 0).unwrap();
+        let ics = serializar_libre_ocupado(&reservas, desde, hasta, desde);
+
+        assert!(ics.contains(
+            "BEGIN:VFREEBUSY\r\nDTSTAMP:20251125T090000Z\r\n\
+             DTSTART:20251125T090000Z\r\nDTEND:20251125T120000Z\r\n\
+             FREEBUSY;FBTYPE=BUSY:20251125T090000Z/20251125T100000Z\r\n\
+             FREEBUSY;FBTYPE=BUSY-TENTATIVE:20251125T100000Z/20251125T110000Z\r\n\
+             END:VFREEBUSY\r\n"
+        ));
+        assert!(!ics.contains("METHOD"));
+    }
+
+    #[test]
+    fn test_lee_eventos_con_asistentes_y_zonas() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:a@ext\r\nSUMMARY:Revisión\\, sprint\r\n\
+            DTSTART:20251125T100000Z\r\nDTEND:20251125T113000Z\r\n\
+            ATTENDEE;CN=\"López: Juan\";ROLE=REQ-PARTICIPANT:mailto:juan@empresa.com\r\n\
+            ATTENDEE:MAILTO:ana@empresa.com\r\n\
+            BEGIN:VALARM\r\nACTION:DISPLAY\r\nSUMMARY:Aviso\r\nEND:VALARM\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:b@ext\r\nDTSTART;TZID=Europe/Madrid:20251126T\r\n 090000\r\n\
+            DURATION:PT1H30M\r\nSTATUS:CANCELLED\r\nRRULE:FREQ=WEEKLY\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251127\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let desfase = FixedOffset::east_opt(3600).unwrap();
+        let eventos = deserializar(ics, desfase).unwrap();
+        assert_eq!(eventos.len(), 3);
+
+        let a = &eventos[0];
+        assert_eq!(a.uid, "a@ext");
+        assert_eq!(a.resumen, "Revisión, sprint");
+        assert_eq!(
+            a.inicio,
+            Utc.with_ymd_and_hms(2025, 11, 25, 10, 0, 0).unwrap()
+        );
+        assert_eq!(
+            a.fin,
+            Utc.with_ymd_and_hms(2025, 11, 25, 11, 30, 0).unwrap()
+        );
+        assert_eq!(a.asistentes, ["juan@empresa.com", "ana@empresa.com"]);
+        assert!(!a.cancelado && !a.periodico && !a.dia_completo);
+
+        // Hora local con el desfase indicado, plegada en dos líneas
+        let b = &eventos[1];
+        assert_eq!(
+            b.inicio,
+            Utc.with_ymd_and_hms(2025, 11, 26, 8, 0, 0).unwrap()
+        );
+        assert_eq!(b.fin, Utc.with_ymd_and_hms(2025, 11, 26, 9, 30, 0).unwrap());
+        assert!(b.cancelado && b.periodico);
+
+        let c = &eventos[2];
+        assert_eq!(c.uid, "evento-3");
+        assert!(c.dia_completo);
+        assert_eq!(c.fin - c.inicio, Duration::days(1));
+    }
+
+    #[test]
+    fn test_lee_lo_que_genera_el_feed() {
+        let feed = FeedCalendario {
+            nombre: "Juan".to_string(),
+            eventos: vec![evento("r1", EstadoReserva::Cancelada, "Demo; final")],
+        };
+        let eventos = deserializar(
+            &serializar(&feed, Utc::now()),
+            FixedOffset::east_opt(0).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(eventos.len(), 1);
+        assert_eq!(eventos[0].uid, "r1@reservas");
+        assert_eq!(eventos[0].resumen, "Demo; final");
+        assert_eq!(eventos[0].fin - eventos[0].inicio, Duration::hours(1));
+        assert!(eventos[0].cancelado);
+    }
+
+    #[test]
+    fn test_rechaza_ficheros_mal_formados() {
+        let utc = FixedOffset::east_opt(0).unwrap();
+        assert!(deserializar("hola", utc).is_err());
+        assert!(deserializar("BEGIN:VEVENT\r\nEND:VEVENT\r\n", utc).is_err());
+        assert!(deserializar("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nEND:VCALENDAR\r\n", utc).is_err());
+        assert!(deserializar(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART:mañana\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            utc
+        )
+        .is_err());
+    }
+}
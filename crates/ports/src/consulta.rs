@@ -0,0 +1,353 @@
+// Criterios de consulta para los listados: filtros, orden y paginación
+//
+// Son objetos de valor compartidos por los puertos de entrada y de salida,
+// así el caso de uso puede pasar la consulta tal cual al repositorio.
+//
+// La paginación es por CURSOR (keyset): el cursor lleva la clave de orden y
+// el ID del último elemento devuelto, y la siguiente página empieza justo
+// después de esa posición según el orden pedido. A diferencia de un offset,
+// no se salta ni repite elementos aunque se inserten registros entre petición
+// y petición. Como la posición va en el propio cursor, tampoco le afecta que
+// ese último elemento cambie de estado, se reprograme o se borre.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use reservas_domain::{Empleado, EstadoReserva, Reserva, Sala};
+use std::cmp::Ordering;
+
+/// Tamaño de página si la consulta no indica límite
+pub const LIMITE_POR_DEFECTO: usize = 50;
+/// Tamaño máximo de página que se acepta
+pub const LIMITE_MAXIMO: usize = 200;
+
+/// Una página de resultados y el cursor para pedir la siguiente
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pagina<T> {
+    pub elementos: Vec<T>,
+    /// `None` si no hay más resultados
+    pub siguiente_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direccion {
+    #[default]
+    Ascendente,
+    Descendente,
+}
+
+/// Campo por el que se ordenan las reservas (el ID desempata siempre)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrdenReservas {
+    #[default]
+    Inicio,
+    Empleado,
+    Estado,
+}
+
+/// Criterios para buscar reservas. Todos los filtros son opcionales.
+#[derive(Debug, Clone, Default)]
+pub struct ConsultaReservas {
+    /// Inicio del slot >= desde
+    pub desde: Option<DateTime<Utc>>,
+    /// Inicio del slot < hasta
+    pub hasta: Option<DateTime<Utc>>,
+    pub estado: Option<EstadoReserva>,
+    pub empleado_id: Option<String>,
+    pub sala_id: Option<String>,
+    /// Búsqueda sin distinguir mayúsculas en la descripción
+    pub texto: Option<String>,
+    pub orden: OrdenReservas,
+    pub direccion: Direccion,
+    pub cursor: Option<String>,
+    pub limite: Option<usize>,
+}
+
+impl ConsultaReservas {
+    /// Indica si la reserva cumple todos los filtros
+    pub fn cumple(&self, reserva: &Reserva) -> bool {
+        self.desde.is_none_or(|d| reserva.slot.inicio >= d)
+            && self.hasta.is_none_or(|h| reserva.slot.inicio < h)
+            && self.estado.as_ref().is_none_or(|e| reserva.estado == *e)
+            && self
+                .empleado_id
+                .as_ref()
+                .is_none_or(|id| reserva.empleado_id == *id)
+            && self
+                .sala_id
+                .as_ref()
+                .is_none_or(|id| reserva.sala_id.as_ref() == Some(id))
+            && contiene(&reserva.descripcion, self.texto.as_deref())
+    }
+
+    /// Clave de orden ascendente: el campo pedido (vacío si es el inicio),
+    /// el inicio del slot y el ID, que desempata
+    fn clave(&self, reserva: &Reserva) -> (String, DateTime<Utc>, String) {
+        let campo = match self.orden {
+            OrdenReservas::Inicio => String::new(),
+            OrdenReservas::Empleado => reserva.empleado_id.clone(),
+            OrdenReservas::Estado => rango_estado(&reserva.estado).to_string(),
+        };
+        (campo, reserva.slot.inicio, reserva.id.clone())
+    }
+
+    fn etiqueta_orden(&self) -> &'static str {
+        match self.orden {
+            OrdenReservas::Inicio => "inicio",
+            OrdenReservas::Empleado => "empleado",
+            OrdenReservas::Estado => "estado",
+        }
+    }
+
+    /// Orden total de las reservas según el campo y la dirección pedidos
+    pub fn comparar(&self, a: &Reserva, b: &Reserva) -> Ordering {
+        self.direccion.aplicar(self.clave(a).cmp(&self.clave(b)))
+    }
+
+    pub fn limite_efectivo(&self) -> usize {
+        limite_efectivo(self.limite)
+    }
+
+    /// Posición que trae el cursor. Falla si no lo ha generado `paginar`
+    /// o si es de otro orden.
+    fn posicion(&self) -> Result<Option<(String, DateTime<Utc>, String)>, String> {
+        let Some(cursor) = &self.cursor else {
+            return Ok(None);
+        };
+        let campos = decodificar_cursor(cursor, 4)?;
+        if campos[0] != self.etiqueta_orden() {
+            return Err(CURSOR_INVALIDO.to_string());
+        }
+        let inicio = DateTime::parse_from_rfc3339(&campos[2])
+            .map_err(|_| CURSOR_INVALIDO.to_string())?
+            .with_timezone(&Utc);
+        Ok(Some((campos[1].clone(), inicio, campos[3].clone())))
+    }
+
+    /// Error del cliente si el cursor no es válido, para comprobarlo antes
+    /// de llegar al repositorio
+    pub fn validar_cursor(&self) -> Result<(), String> {
+        self.posicion().map(|_| ())
+    }
+
+    /// Ordena las reservas ya filtradas y corta la página que sigue al
+    /// cursor. Falla si el cursor no es válido o es de otro orden.
+    pub fn paginar(&self, candidatos: Vec<&Reserva>) -> Result<Pagina<Reserva>, String> {
+        Ok(paginar(
+            candidatos,
+            self.posicion()?,
+            self.limite_efectivo(),
+            self.direccion,
+            |r| self.clave(r),
+            |(campo, inicio, id)| {
+                codificar_cursor(&[
+                    self.etiqueta_orden(),
+                    campo,
+                    &inicio.to_rfc3339_opts(SecondsFormat::Nanos, true),
+                    id,
+                ])
+            },
+        ))
+    }
+}
+
+/// Criterios para buscar empleados, ordenados por nombre
+#[derive(Debug, Clone, Default)]
+pub struct ConsultaEmpleados {
+    /// Búsqueda sin distinguir mayúsculas en nombre o email
+    pub texto: Option<String>,
+    pub activo: Option<bool>,
+    pub cursor: Option<String>,
+    pub limite: Option<usize>,
+}
+
+impl ConsultaEmpleados {
+    pub fn cumple(&self, empleado: &Empleado) -> bool {
+        self.activo.is_none_or(|a| empleado.activo == a)
+            && (contiene(&empleado.nombre, self.texto.as_deref())
+                || contiene(empleado.email.as_str(), self.texto.as_deref()))
+    }
+
+    pub fn comparar(&self, a: &Empleado, b: &Empleado) -> Ordering {
+        (&a.nombre, &a.id).cmp(&(&b.nombre, &b.id))
+    }
+
+    pub fn limite_efectivo(&self) -> usize {
+        limite_efectivo(self.limite)
+    }
+
+    pub fn validar_cursor(&self) -> Result<(), String> {
+        posicion_por_nombre(self.cursor.as_deref()).map(|_| ())
+    }
+
+    /// Ordena los empleados ya filtrados y corta la página que sigue al cursor
+    pub fn paginar(&self, candidatos: Vec<&Empleado>) -> Result<Pagina<Empleado>, String> {
+        paginar_por_nombre(
+            candidatos,
+            self.cursor.as_deref(),
+            self.limite_efectivo(),
+            |e| (e.nombre.clone(), e.id.clone()),
+        )
+    }
+}
+
+/// Criterios para buscar salas, ordenadas por nombre
+#[derive(Debug, Clone, Default)]
+pub struct ConsultaSalas {
+    /// Búsqueda sin distinguir mayúsculas en el nombre
+    pub texto: Option<String>,
+    pub activa: Option<bool>,
+    pub capacidad_minima: Option<u32>,
+    pub cursor: Option<String>,
+    pub limite: Option<usize>,
+}
+
+impl ConsultaSalas {
+    pub fn cumple(&self, sala: &Sala) -> bool {
+        self.activa.is_none_or(|a| sala.activa == a)
+            && self.capacidad_minima.is_none_or(|c| sala.capacidad >= c)
+            && contiene(&sala.nombre, self.texto.as_deref())
+    }
+
+    pub fn comparar(&self, a: &Sala, b: &Sala) -> Ordering {
+        (&a.nombre, &a.id).cmp(&(&b.nombre, &b.id))
+    }
+
+    pub fn limite_efectivo(&self) -> usize {
+        limite_efectivo(self.limite)
+    }
+
+    pub fn validar_cursor(&self) -> Result<(), String> {
+        posicion_por_nombre(self.cursor.as_deref()).map(|_| ())
+    }
+
+    /// Ordena las salas ya filtradas y corta la página que sigue al cursor
+    pub fn paginar(&self, candidatas: Vec<&Sala>) -> Result<Pagina<Sala>, String> {
+        paginar_por_nombre(
+            candidatas,
+            self.cursor.as_deref(),
+            self.limite_efectivo(),
+            |s| (s.nombre.clone(), s.id.clone()),
+        )
+    }
+}
+
+impl Direccion {
+    fn aplicar(self, orden: Ordering) -> Ordering {
+        match self {
+            Direccion::Ascendente => orden,
+            Direccion::Descendente => orden.reverse(),
+        }
+    }
+}
+
+const CURSOR_INVALIDO: &str = "Cursor inválido";
+
+/// El cursor es opaco para los clientes: los campos de la posición separados
+/// por un carácter de control y en hexadecimal
+fn codificar_cursor(campos: &[&str]) -> String {
+    campos
+        .join("\u{1f}")
+        .bytes()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn decodificar_cursor(cursor: &str, numero_campos: usize) -> Result<Vec<String>, String> {
+    let invalido = || CURSOR_INVALIDO.to_string();
+    if !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+        return Err(invalido());
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| invalido())?;
+    let texto = String::from_utf8(bytes).map_err(|_| invalido())?;
+    let campos: Vec<String> = texto.split('\u{1f}').map(str::to_string).collect();
+    if campos.len() != numero_campos {
+        return Err(invalido());
+    }
+    Ok(campos)
+}
+
+fn posicion_por_nombre(cursor: Option<&str>) -> Result<Option<(String, String)>, String> {
+    let Some(cursor) = cursor else {
+        return Ok(None);
+    };
+    let mut campos = decodificar_cursor(cursor, 2)?.into_iter();
+    Ok(campos.next().zip(campos.next()))
+}
+
+/// Paginación de los listados ordenados por nombre e ID
+fn paginar_por_nombre<T: Clone>(
+    candidatos: Vec<&T>,
+    cursor: Option<&str>,
+    limite: usize,
+    clave: impl Fn(&T) -> (String, String),
+) -> Result<Pagina<T>, String> {
+    Ok(paginar(
+        candidatos,
+        posicion_por_nombre(cursor)?,
+        limite,
+        Direccion::Ascendente,
+        clave,
+        |(nombre, id)| codificar_cursor(&[nombre, id]),
+    ))
+}
+
+/// Ordena los candidatos ya filtrados por su clave y corta la página de los
+/// estrictamente posteriores a `despues_de`, la clave que traía el cursor.
+/// No hace falta que ese elemento siga existiendo. Solo se clonan los
+/// elementos de la página.
+fn paginar<T: Clone, K: Ord>(
+    mut candidatos: Vec<&T>,
+    despues_de: Option<K>,
+    limite: usize,
+    direccion: Direccion,
+    clave: impl Fn(&T) -> K,
+    cursor: impl Fn(&K) -> String,
+) -> Pagina<T> {
+    candidatos.sort_by(|a, b| direccion.aplicar(clave(a).cmp(&clave(b))));
+
+    let inicio = match &despues_de {
+        Some(c) => {
+            candidatos.partition_point(|e| direccion.aplicar(clave(e).cmp(c)) != Ordering::Greater)
+        }
+        None => 0,
+    };
+
+    let restantes = &candidatos[inicio..];
+    let elementos: Vec<T> = restantes
+        .iter()
+        .take(limite)
+        .map(|e| (*e).clone())
+        .collect();
+    let siguiente_cursor = if restantes.len() > limite {
+        elementos.last().map(|e| cursor(&clave(e)))
+    } else {
+        None
+    };
+
+    Pagina {
+        elementos,
+        siguiente_cursor,
+    }
+}
+
+fn limite_efectivo(limite: Option<usize>) -> usize {
+    limite.unwrap_or(LIMITE_POR_DEFECTO).clamp(1, LIMITE_MAXIMO)
+}
+
+fn contiene(campo: &str, texto: Option<&str>) -> bool {
+    match texto.map(str::trim) {
+        None | Some("") => true,
+        Some(t) => campo.to_lowercase().contains(&t.to_lowercase()),
+    }
+}
+
+fn rango_estado(estado: &EstadoReserva) -> u8 {
+    match estado {
+        EstadoReserva::Pendiente => 0,
+        EstadoReserva::Confirmada => 1,
+        EstadoReserva::Cancelada => 2,
+    }
+}
@@ -0,0 +1,32 @@
+use std::fmt;
+
+/// Error de los casos de uso que comprueban permisos
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorServicio {
+    /// Quien actúa no tiene permiso para la operación (ya auditado)
+    Denegado(String),
+    /// Cualquier otro fallo: validación, conflicto, repositorio...
+    Otro(String),
+}
+
+impl fmt::Display for ErrorServicio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorServicio::Denegado(mensaje) | ErrorServicio::Otro(mensaje) => f.write_str(mensaje),
+        }
+    }
+}
+
+impl std::error::Error for ErrorServicio {}
+
+impl From<String> for ErrorServicio {
+    fn from(mensaje: String) -> Self {
+        ErrorServicio::Otro(mensaje)
+    }
+}
+
+impl From<&str> for ErrorServicio {
+    fn from(mensaje: &str) -> Self {
+        ErrorServicio::Otro(mensaje.to_string())
+    }
+}
@@ -0,0 +1,11 @@
+use crate::error::ErrorServicio;
+use crate::out::auditoria::IntentoDenegado;
+use async_trait::async_trait;
+use reservas_domain::Actor;
+
+/// Puerto de entrada: consulta de la auditoría de accesos denegados
+#[async_trait]
+pub trait AuditoriaService: Send + Sync {
+    /// Solo para administradores
+    async fn listar_denegados(&self, actor: &Actor) -> Result<Vec<IntentoDenegado>, ErrorServicio>;
+}
@@ -0,0 +1,59 @@
+use crate::error::ErrorServicio;
+use async_trait::async_trait;
+use reservas_domain::{Actor, Reserva};
+
+/// De quién es un calendario
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Calendario {
+    Empleado(String),
+    Sala(String),
+}
+
+/// Reserva con los nombres que necesita un cliente de calendario
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventoCalendario {
+    pub reserva: Reserva,
+    pub empleado: Option<String>,
+    pub sala: Option<String>,
+}
+
+/// Contenido de un calendario, listo para publicarlo
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedCalendario {
+    /// Nombre del empleado o de la sala
+    pub nombre: String,
+    /// Por orden de inicio. Incluye las canceladas, para que los clientes
+    /// suscritos las quiten.
+    pub eventos: Vec<EventoCalendario>,
+}
+
+/// Puerto de entrada: calendarios suscribibles por empleado y por sala.
+/// Los clientes de calendario no envían credenciales, así que cada
+/// calendario tiene una URL privada con un token propio.
+#[async_trait]
+pub trait CalendarioService: Send + Sync {
+    /// Token de la URL privada. Cada empleado obtiene el suyo; el de una
+    /// sala, cualquiera. `None` si el empleado o la sala no existen.
+    async fn token(
+        &self,
+        actor: &Actor,
+        calendario: &Calendario,
+    ) -> Result<Option<String>, ErrorServicio>;
+
+    /// Invalida la URL privada actual del calendario, por ejemplo si se ha
+    /// filtrado, y devuelve el token de la nueva. Las de los demás
+    /// calendarios siguen valiendo. Mismos permisos que `token`, salvo en
+    /// salas: hace falta poder gestionarlas.
+    async fn revocar(
+        &self,
+        actor: &Actor,
+        calendario: &Calendario,
+    ) -> Result<Option<String>, ErrorServicio>;
+
+    /// `None` si no existe o el token no es el suyo
+    async fn feed(
+        &self,
+        calendario: &Calendario,
+        token: &str,
+    ) -> Result<Option<FeedCalendario>, String>;
+}
@@ -0,0 +1,111 @@
+use crate::error::ErrorServicio;
+use async_trait::async_trait;
+use reservas_domain::{Actor, ErrorCampo};
+use std::collections::HashMap;
+
+/// Hoja de cálculo ya leída, sin formato: nombres de columna y filas de texto
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tabla {
+    pub columnas: Vec<String>,
+    pub filas: Vec<Vec<String>>,
+}
+
+impl Tabla {
+    pub fn new(columnas: &[&str]) -> Self {
+        Self {
+            columnas: columnas.iter().map(|c| c.to_string()).collect(),
+            filas: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entidad {
+    Empleados,
+    Salas,
+    Reservas,
+}
+
+impl Entidad {
+    /// Campos que se leen al importar; los obligatorios, primero.
+    /// Las reservas no se importan desde hojas de cálculo (ver
+    /// `ImportacionService`).
+    pub fn campos(&self) -> &'static [&'static str] {
+        match self {
+            Entidad::Empleados => &["nombre", "email", "responsable", "activo"],
+            Entidad::Salas => &["nombre", "capacidad", "activa"],
+            Entidad::Reservas => &[],
+        }
+    }
+
+    pub fn obligatorios(&self) -> &'static [&'static str] {
+        match self {
+            Entidad::Empleados => &["nombre", "email"],
+            Entidad::Salas => &["nombre", "capacidad"],
+            Entidad::Reservas => &[],
+        }
+    }
+}
+
+/// Columna del fichero de la que sale cada campo. Los campos que no se
+/// indican se buscan en la columna del mismo nombre.
+pub type Mapeo = HashMap<String, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultadoFila {
+    Creada,
+    Actualizada,
+    /// Ya existe con los mismos datos
+    SinCambios,
+    /// No se aplica: ver los errores
+    Error,
+}
+
+/// Qué pasa con una fila de la hoja
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilaCarga {
+    /// Número de fila en la hoja, contando la cabecera como la 1
+    pub fila: usize,
+    /// Email del empleado o nombre de la sala
+    pub clave: String,
+    pub resultado: ResultadoFila,
+    pub errores: Vec<ErrorCampo>,
+    /// Entidad creada o actualizada; nunca al simular si es nueva
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InformeCarga {
+    pub filas: Vec<FilaCarga>,
+}
+
+impl InformeCarga {
+    pub fn contar(&self, resultado: ResultadoFila) -> usize {
+        self.filas
+            .iter()
+            .filter(|f| f.resultado == resultado)
+            .count()
+    }
+}
+
+/// Puerto de entrada: altas y cambios masivos desde hojas de cálculo y
+/// exportación a tabla
+#[async_trait]
+pub trait CargaMasivaService: Send + Sync {
+    /// Crea o actualiza empleados (por email) o salas (por nombre) fila a
+    /// fila, con las mismas reglas y permisos que uno a uno. Las filas con
+    /// errores no se aplican, pero no detienen el resto. Falla entera si el
+    /// mapeo no cuadra con las columnas. Con `simular` solo se informa.
+    async fn importar(
+        &self,
+        actor: &Actor,
+        entidad: Entidad,
+        tabla: Tabla,
+        mapeo: &Mapeo,
+        simular: bool,
+    ) -> Result<InformeCarga, ErrorServicio>;
+
+    /// Todas las entidades del tipo; la de empleados y la de salas se
+    /// pueden volver a importar tal cual
+    async fn exportar(&self, entidad: Entidad) -> Result<Tabla, String>;
+}
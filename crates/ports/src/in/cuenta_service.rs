@@ -0,0 +1,31 @@
+use crate::error::ErrorServicio;
+use async_trait::async_trait;
+use reservas_domain::{Actor, Cuenta, Rol};
+
+/// Datos para dar de alta una cuenta local
+#[derive(Debug, Clone)]
+pub struct NuevaCuenta {
+    pub usuario: String,
+    pub password: String,
+    pub empleado_id: Option<String>,
+    pub roles: Vec<Rol>,
+}
+
+/// Puerto de entrada: cuentas locales con contraseña
+#[async_trait]
+pub trait CuentaService: Send + Sync {
+    /// Da de alta la cuenta (solo administradores). La contraseña se guarda
+    /// con hash.
+    async fn registrar(&self, actor: &Actor, nueva: NuevaCuenta) -> Result<Cuenta, ErrorServicio>;
+
+    /// Comprueba usuario y contraseña. `None` si no coinciden o la cuenta
+    /// está desactivada; el motivo no se distingue a propósito.
+    async fn autenticar(&self, usuario: &str, password: &str) -> Result<Option<Cuenta>, String>;
+
+    async fn cambiar_password(
+        &self,
+        actor: &Actor,
+        usuario: &str,
+        password: &str,
+    ) -> Result<(), ErrorServicio>;
+}
@@ -0,0 +1,47 @@
+use crate::out::notificador::TipoCambio;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use reservas_domain::{DisponibilidadSalaSlot, DisponibilidadSlot};
+
+/// Qué cambios interesan a un suscriptor (`None` = todos)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FiltroDisponibilidad {
+    /// Día de los slots afectados (UTC)
+    pub fecha: Option<NaiveDate>,
+    pub empleado_id: Option<String>,
+    pub sala_id: Option<String>,
+}
+
+/// Slots cuya disponibilidad ha cambiado por un cambio en una reserva
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActualizacionDisponibilidad {
+    pub cambio: TipoCambio,
+    pub reserva_id: String,
+    pub sala_id: Option<String>,
+    /// Estado actual de los slots afectados (el nuevo y, si se reprogramó,
+    /// el antiguo) que pasan el filtro. Vacío con filtro de sala.
+    pub slots: Vec<DisponibilidadSlot>,
+    /// Con filtro de sala, estado de la sala en esos mismos slots
+    pub salas: Vec<DisponibilidadSalaSlot>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventoDisponibilidad {
+    Actualizacion(ActualizacionDisponibilidad),
+    /// Se han perdido cambios (suscriptor demasiado lento): hay que volver a
+    /// pedir la tabla completa
+    Resincronizar,
+}
+
+/// Flujo de eventos de una suscripción
+#[async_trait]
+pub trait Suscripcion: Send {
+    /// Siguiente evento; `None` cuando ya no habrá más
+    async fn siguiente(&mut self) -> Option<EventoDisponibilidad>;
+}
+
+/// Puerto de entrada: cambios de disponibilidad en tiempo real, para
+/// pantallas que hoy consultan la tabla completa cada pocos segundos
+pub trait DisponibilidadEnVivoService: Send + Sync {
+    fn suscribir(&self, filtro: FiltroDisponibilidad) -> Box<dyn Suscripcion>;
+}
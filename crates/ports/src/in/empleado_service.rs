@@ -1,16 +1,100 @@
+use crate::consulta::{ConsultaEmpleados, Pagina};
+use crate::error::ErrorServicio;
 use async_trait::async_trait;
-use reservas_domain::Empleado;
+use reservas_domain::{Actor, Empleado, ErrorCampo};
+use std::fmt;
 
-/// Puerto de entrada para gestión de empleados
+/// Campos a modificar de un empleado (`None` = no cambia)
+#[derive(Debug, Clone, Default)]
+pub struct CambiosEmpleado {
+    pub nombre: Option<String>,
+    pub email: Option<String>,
+    /// `Some(None)` quita el responsable
+    pub responsable_id: Option<Option<String>>,
+}
+
+/// Error al crear o modificar un empleado
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorEmpleado {
+    /// Datos inválidos o email ya en uso, campo a campo
+    Validacion(Vec<ErrorCampo>),
+    /// Quien actúa no tiene permiso para la operación
+    Denegado(String),
+    /// Cualquier otro fallo (p. ej. del repositorio)
+    Otro(String),
+}
+
+impl fmt::Display for ErrorEmpleado {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorEmpleado::Validacion(errores) => {
+                let detalle: Vec<String> = errores.iter().map(|e| e.to_string()).collect();
+                write!(f, "Datos inválidos: {}", detalle.join("; "))
+            }
+            ErrorEmpleado::Denegado(mensaje) | ErrorEmpleado::Otro(mensaje) => f.write_str(mensaje),
+        }
+    }
+}
+
+impl std::error::Error for ErrorEmpleado {}
+
+impl From<String> for ErrorEmpleado {
+    fn from(mensaje: String) -> Self {
+        ErrorEmpleado::Otro(mensaje)
+    }
+}
+
+impl From<ErrorServicio> for ErrorEmpleado {
+    fn from(error: ErrorServicio) -> Self {
+        match error {
+            ErrorServicio::Denegado(mensaje) => ErrorEmpleado::Denegado(mensaje),
+            ErrorServicio::Otro(mensaje) => ErrorEmpleado::Otro(mensaje),
+        }
+    }
+}
+
+impl From<Vec<ErrorCampo>> for ErrorEmpleado {
+    fn from(errores: Vec<ErrorCampo>) -> Self {
+        ErrorEmpleado::Validacion(errores)
+    }
+}
+
+/// Puerto de entrada para gestión de empleados.
+/// Las operaciones que modifican empleados son solo para administradores.
 #[async_trait]
 pub trait EmpleadoService: Send + Sync {
-    async fn crear_empleado(&self, nombre: String, email: String) -> Result<Empleado, String>;
+    /// Crea el empleado validando nombre, email y que el email no esté en uso
+    async fn crear_empleado(
+        &self,
+        actor: &Actor,
+        nombre: String,
+        email: String,
+    ) -> Result<Empleado, ErrorEmpleado>;
 
     async fn obtener_empleado(&self, id: &str) -> Result<Option<Empleado>, String>;
 
     async fn listar_empleados(&self) -> Result<Vec<Empleado>, String>;
 
-    async fn desactivar_empleado(&self, id: &str) -> Result<Empleado, String>;
+    async fn buscar_empleados(
+        &self,
+        consulta: ConsultaEmpleados,
+    ) -> Result<Pagina<Empleado>, String>;
+
+    async fn desactivar_empleado(&self, actor: &Actor, id: &str)
+        -> Result<Empleado, ErrorServicio>;
+
+    async fn activar_empleado(&self, actor: &Actor, id: &str) -> Result<Empleado, ErrorServicio>;
+
+    /// Aplica los cambios validando de nuevo (el responsable debe existir).
+    /// `None` si el empleado no existe.
+    async fn actualizar_empleado(
+        &self,
+        actor: &Actor,
+        id: &str,
+        cambios: CambiosEmpleado,
+    ) -> Result<Option<Empleado>, ErrorEmpleado>;
 
-    async fn activar_empleado(&self, id: &str) -> Result<Empleado, String>;
+    /// Borra el empleado; `false` si no existía. Falla si tiene reservas
+    /// (en ese caso hay que desactivarlo).
+    async fn eliminar_empleado(&self, actor: &Actor, id: &str) -> Result<bool, ErrorServicio>;
 }
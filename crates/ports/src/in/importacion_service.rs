@@ -0,0 +1,74 @@
+use crate::error::ErrorServicio;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reservas_domain::{Actor, Slot};
+
+/// Evento leído de un calendario externo (un VEVENT)
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventoExterno {
+    pub uid: String,
+    pub resumen: String,
+    pub inicio: DateTime<Utc>,
+    pub fin: DateTime<Utc>,
+    /// Emails de los asistentes
+    pub asistentes: Vec<String>,
+    pub cancelado: bool,
+    /// Solo fecha, sin hora
+    pub dia_completo: bool,
+    /// Tiene regla de repetición
+    pub periodico: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultadoImportacion {
+    /// Reserva creada (o que se crearía al simular)
+    Importada,
+    /// El empleado ya tiene una reserva en ese slot
+    Conflicto,
+    /// No se importa: ver el detalle
+    Omitida,
+}
+
+/// Qué pasa con un evento entero o con un asistente en uno de sus slots
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementoImportacion {
+    pub uid: String,
+    pub email: Option<String>,
+    pub empleado_id: Option<String>,
+    pub slot: Option<Slot>,
+    pub resultado: ResultadoImportacion,
+    /// Motivo del conflicto o de la omisión
+    pub detalle: Option<String>,
+    /// Reserva creada; nunca al simular
+    pub reserva_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InformeImportacion {
+    pub eventos: usize,
+    pub elementos: Vec<ElementoImportacion>,
+}
+
+impl InformeImportacion {
+    pub fn contar(&self, resultado: ResultadoImportacion) -> usize {
+        self.elementos
+            .iter()
+            .filter(|e| e.resultado == resultado)
+            .count()
+    }
+}
+
+/// Puerto de entrada: importación de reservas desde calendarios externos
+#[async_trait]
+pub trait ImportacionService: Send + Sync {
+    /// Cada asistente que es empleado recibe una reserva por cada slot que
+    /// ocupa el evento, redondeado a horas enteras. Las reservas se crean a
+    /// través de `ReservaService`, con sus mismas reglas y permisos.
+    /// Con `simular` solo se informa de lo que se haría.
+    async fn importar(
+        &self,
+        actor: &Actor,
+        eventos: Vec<EventoExterno>,
+        simular: bool,
+    ) -> Result<InformeImportacion, ErrorServicio>;
+}
@@ -0,0 +1,44 @@
+use crate::r#in::carga_masiva_service::Tabla;
+use async_trait::async_trait;
+use reservas_domain::{InformeOcupacion, Periodo};
+use std::str::FromStr;
+
+/// Parte del informe que se exporta como tabla
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Desglose {
+    /// Totales y tasas, una métrica por fila
+    Resumen,
+    Empleados,
+    Salas,
+    Horas,
+    Dias,
+}
+
+impl FromStr for Desglose {
+    type Err = String;
+
+    fn from_str(valor: &str) -> Result<Self, Self::Err> {
+        match valor.trim().to_lowercase().as_str() {
+            "resumen" => Ok(Desglose::Resumen),
+            "empleados" => Ok(Desglose::Empleados),
+            "salas" => Ok(Desglose::Salas),
+            "horas" => Ok(Desglose::Horas),
+            "dias" | "días" => Ok(Desglose::Dias),
+            _ => Err(format!(
+                "Desglose no válido: {} (use resumen, empleados, salas, horas o dias)",
+                valor
+            )),
+        }
+    }
+}
+
+/// Puerto de entrada: informes de uso de empleados y salas
+#[async_trait]
+pub trait InformeService: Send + Sync {
+    async fn informe_ocupacion(&self, periodo: Periodo) -> Result<InformeOcupacion, String>;
+
+    /// Una parte del informe como tabla, para descargarla en CSV o XLSX. Las
+    /// ocupaciones y tasas van como fracción entre 0 y 1.
+    async fn exportar_informe(&self, periodo: Periodo, desglose: Desglose)
+        -> Result<Tabla, String>;
+}
@@ -0,0 +1,61 @@
+use crate::error::ErrorServicio;
+use async_trait::async_trait;
+use reservas_domain::Actor;
+
+/// Tipos de inconsistencia que detecta el verificador
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoIncidencia {
+    /// La reserva apunta a un empleado que no existe
+    EmpleadoInexistente,
+    /// La reserva apunta a una sala que no existe
+    SalaInexistente,
+    /// Dos reservas activas del mismo empleado o sala en el mismo slot
+    Solapamiento,
+    /// Reserva activa y futura de un empleado desactivado
+    EmpleadoInactivo,
+    /// Reserva activa y futura en una sala desactivada
+    SalaInactiva,
+    /// Reserva activa fuera del horario laboral
+    FueraDeHorario,
+}
+
+/// Un problema encontrado en una reserva concreta
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incidencia {
+    pub tipo: TipoIncidencia,
+    pub reserva_id: String,
+    pub detalle: String,
+    /// `true` si se ha corregido en esta pasada
+    pub reparada: bool,
+}
+
+/// Resultado de una pasada del verificador
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InformeIntegridad {
+    pub reservas_revisadas: usize,
+    pub incidencias: Vec<Incidencia>,
+}
+
+impl InformeIntegridad {
+    pub fn es_consistente(&self) -> bool {
+        self.incidencias.is_empty()
+    }
+
+    pub fn reparadas(&self) -> usize {
+        self.incidencias.iter().filter(|i| i.reparada).count()
+    }
+}
+
+/// Puerto de entrada: verificación de la consistencia de los datos (fsck).
+/// Solo para administradores.
+#[async_trait]
+pub trait IntegridadService: Send + Sync {
+    /// Recorre los repositorios y devuelve las incidencias encontradas.
+    /// Con `reparar` cancela las reservas activas afectadas; las que ya
+    /// están canceladas no se pueden corregir y solo se informan.
+    async fn verificar(
+        &self,
+        actor: &Actor,
+        reparar: bool,
+    ) -> Result<InformeIntegridad, ErrorServicio>;
+}
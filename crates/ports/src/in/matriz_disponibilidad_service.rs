@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use reservas_domain::{MatrizDisponibilidad, Periodo};
+use std::fmt;
+
+/// Qué filas lleva la matriz. Sin ningún filtro, todos los empleados activos
+/// y ninguna sala; con alguno, solo lo que piden los filtros.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FiltroMatriz {
+    pub empleados: Vec<String>,
+    /// Equipo de un responsable: él y quienes le reportan
+    pub responsable_id: Option<String>,
+    pub salas: Vec<String>,
+}
+
+/// Error al generar la matriz
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorMatriz {
+    /// Un empleado, responsable o sala del filtro no existe
+    NoEncontrado(String),
+    /// Cualquier otro fallo (p. ej. del repositorio)
+    Otro(String),
+}
+
+impl fmt::Display for ErrorMatriz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorMatriz::NoEncontrado(mensaje) | ErrorMatriz::Otro(mensaje) => f.write_str(mensaje),
+        }
+    }
+}
+
+impl std::error::Error for ErrorMatriz {}
+
+impl From<String> for ErrorMatriz {
+    fn from(mensaje: String) -> Self {
+        ErrorMatriz::Otro(mensaje)
+    }
+}
+
+/// Puerto de entrada: disponibilidad de varios días de empleados y salas
+#[async_trait]
+pub trait MatrizDisponibilidadService: Send + Sync {
+    /// Filas de empleados y luego de salas, cada grupo por nombre
+    async fn matriz_disponibilidad(
+        &self,
+        periodo: Periodo,
+        filtro: FiltroMatriz,
+    ) -> Result<MatrizDisponibilidad, ErrorMatriz>;
+}
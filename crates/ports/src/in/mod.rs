@@ -1,3 +1,16 @@
+pub mod auditoria_service;
+pub mod calendario_service;
+pub mod carga_masiva_service;
+pub mod cuenta_service;
+pub mod disponibilidad_en_vivo_service;
 pub mod empleado_service;
+pub mod importacion_service;
+pub mod informe_service;
+pub mod integridad_service;
+pub mod matriz_disponibilidad_service;
 pub mod reserva_service;
+pub mod respaldo_service;
 pub mod sala_service;
+pub mod salud_service;
+pub mod sso_service;
+pub mod webhook_service;
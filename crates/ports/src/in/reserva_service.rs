@@ -1,17 +1,34 @@
+use crate::consulta::{ConsultaReservas, Pagina};
+use crate::error::ErrorServicio;
 use async_trait::async_trait;
-use reservas_domain::{Reserva, Slot};
+use reservas_domain::{Actor, Reserva, Slot};
+
+/// Campos a modificar de una reserva (`None` = no cambia)
+#[derive(Debug, Clone, Default)]
+pub struct CambiosReserva {
+    pub descripcion: Option<String>,
+    /// Como `reprogramar_reserva`, pero en la misma escritura que el resto
+    /// de cambios
+    pub slot: Option<Slot>,
+}
 
 /// Puerto de entrada (INPUT PORT): Define cómo usar el sistema
-/// Este es el "caso de uso" de nuestro sistema
+/// Este es el "caso de uso" de nuestro sistema.
+///
+/// Las operaciones que modifican reservas reciben al `Actor` que las pide y
+/// fallan con `ErrorServicio::Denegado` si no tiene permiso.
 #[async_trait]
 pub trait ReservaService: Send + Sync {
-    /// Crea una nueva reserva para un empleado en un slot específico
+    /// Crea una nueva reserva para un empleado en un slot específico,
+    /// opcionalmente ocupando también una sala
     async fn crear_reserva(
         &self,
+        actor: &Actor,
         empleado_id: String,
         slot: Slot,
         descripcion: String,
-    ) -> Result<Reserva, String>;
+        sala_id: Option<String>,
+    ) -> Result<Reserva, ErrorServicio>;
 
     /// Obtiene una reserva por su ID
     async fn obtener_reserva(&self, id: &str) -> Result<Option<Reserva>, String>;
@@ -19,12 +36,67 @@ pub trait ReservaService: Send + Sync {
     /// Lista todas las reservas activas
     async fn listar_reservas(&self) -> Result<Vec<Reserva>, String>;
 
+    /// Busca reservas con filtros, orden y paginación por cursor
+    async fn buscar_reservas(&self, consulta: ConsultaReservas) -> Result<Pagina<Reserva>, String>;
+
     /// Lista las reservas de un empleado específico
     async fn listar_reservas_empleado(&self, empleado_id: &str) -> Result<Vec<Reserva>, String>;
 
-    /// Confirma una reserva
-    async fn confirmar_reserva(&self, id: &str) -> Result<Reserva, String>;
+    /// Confirma una reserva (su titular debe ser subordinado del actor).
+    /// Una reserva cancelada no se puede confirmar.
+    async fn confirmar_reserva(&self, actor: &Actor, id: &str) -> Result<Reserva, ErrorServicio>;
 
     /// Cancela una reserva
-    async fn cancelar_reserva(&self, id: &str) -> Result<Reserva, String>;
+    async fn cancelar_reserva(&self, actor: &Actor, id: &str) -> Result<Reserva, ErrorServicio>;
+
+    /// Aplica los cambios validando de nuevo, todos o ninguno. Un slot
+    /// distinto se valida como en `reprogramar_reserva` y se avisa como
+    /// reprogramación. `None` si la reserva no existe.
+    async fn actualizar_reserva(
+        &self,
+        actor: &Actor,
+        id: &str,
+        cambios: CambiosReserva,
+    ) -> Result<Option<Reserva>, ErrorServicio>;
+
+    /// Mueve la reserva a otro slot conservando su ID, validando el slot y los
+    /// conflictos del empleado y la sala. `None` si la reserva no existe.
+    async fn reprogramar_reserva(
+        &self,
+        actor: &Actor,
+        id: &str,
+        nuevo_slot: Slot,
+    ) -> Result<Option<Reserva>, ErrorServicio>;
+
+    /// Borra la reserva; `false` si no existía
+    async fn eliminar_reserva(&self, actor: &Actor, id: &str) -> Result<bool, ErrorServicio>;
+
+    /// Cancela todas las reservas futuras activas del empleado (al
+    /// desactivarlo). Exige permiso para gestionar empleados. Devuelve las
+    /// canceladas; si falla, no cancela ninguna.
+    async fn cancelar_reservas_futuras(
+        &self,
+        actor: &Actor,
+        empleado_id: &str,
+    ) -> Result<Vec<Reserva>, ErrorServicio>;
+
+    /// Pasa todas las reservas futuras activas del empleado a `destino`, que
+    /// debe estar activo y libre en esos slots. Exige permiso para gestionar
+    /// empleados. Devuelve las reasignadas; si falla, no cambia ninguna.
+    async fn reasignar_reservas_futuras(
+        &self,
+        actor: &Actor,
+        empleado_id: &str,
+        destino: &str,
+    ) -> Result<Vec<Reserva>, ErrorServicio>;
+
+    /// Escribe tal cual las reservas de un respaldo: crea las que no existen
+    /// y sobrescribe las demás, sin validar slots ni conflictos. Exige
+    /// permiso de administración. Avisa de cada reserva que cambia; si una
+    /// escritura falla, deja todas como estaban.
+    async fn restaurar_reservas(
+        &self,
+        actor: &Actor,
+        reservas: Vec<Reserva>,
+    ) -> Result<(), ErrorServicio>;
 }
@@ -0,0 +1,79 @@
+use crate::error::ErrorServicio;
+use async_trait::async_trait;
+use reservas_domain::{Actor, Empleado, Reserva, Sala};
+use std::fmt;
+
+/// Instantánea completa del sistema: todo lo necesario para reconstruirlo
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Respaldo {
+    pub empleados: Vec<Empleado>,
+    pub salas: Vec<Sala>,
+    pub reservas: Vec<Reserva>,
+}
+
+/// Cuántas entidades de cada tipo se han restaurado (o se restaurarían)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResumenRestauracion {
+    pub empleados: usize,
+    pub salas: usize,
+    pub reservas: usize,
+}
+
+/// Error al restaurar un respaldo
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorRespaldo {
+    /// El resultado de restaurarlo no tendría integridad referencial
+    Inconsistente(Vec<String>),
+    /// Quien actúa no tiene permiso para la operación
+    Denegado(String),
+    /// Cualquier otro fallo (p. ej. del repositorio)
+    Otro(String),
+}
+
+impl fmt::Display for ErrorRespaldo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorRespaldo::Inconsistente(errores) => {
+                write!(f, "Respaldo inconsistente: {}", errores.join("; "))
+            }
+            ErrorRespaldo::Denegado(mensaje) | ErrorRespaldo::Otro(mensaje) => f.write_str(mensaje),
+        }
+    }
+}
+
+impl std::error::Error for ErrorRespaldo {}
+
+impl From<String> for ErrorRespaldo {
+    fn from(mensaje: String) -> Self {
+        ErrorRespaldo::Otro(mensaje)
+    }
+}
+
+impl From<ErrorServicio> for ErrorRespaldo {
+    fn from(error: ErrorServicio) -> Self {
+        match error {
+            ErrorServicio::Denegado(mensaje) => ErrorRespaldo::Denegado(mensaje),
+            ErrorServicio::Otro(mensaje) => ErrorRespaldo::Otro(mensaje),
+        }
+    }
+}
+
+/// Puerto de entrada: copia de seguridad y restauración de todo el sistema.
+/// Solo para administradores.
+#[async_trait]
+pub trait RespaldoService: Send + Sync {
+    /// Lee todas las entidades a través de los repositorios
+    async fn exportar(&self, actor: &Actor) -> Result<Respaldo, ErrorServicio>;
+
+    /// Valida la integridad referencial del respaldo y lo escribe en los
+    /// repositorios (crea lo que falta y sobrescribe lo existente). Las
+    /// reservas se escriben a través del servicio de reservas, que espera a
+    /// las escrituras en curso y avisa de cada cambio.
+    /// Con `simular` solo valida, sin escribir nada.
+    async fn restaurar(
+        &self,
+        actor: &Actor,
+        respaldo: Respaldo,
+        simular: bool,
+    ) -> Result<ResumenRestauracion, ErrorRespaldo>;
+}
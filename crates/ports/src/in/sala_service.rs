@@ -1,11 +1,53 @@
+use crate::consulta::{ConsultaSalas, Pagina};
+use crate::error::ErrorServicio;
 use async_trait::async_trait;
-use reservas_domain::Sala;
+use chrono::NaiveDate;
+use reservas_domain::{Actor, MatrizDisponibilidad, Periodo, Sala};
 
+/// Campos a modificar de una sala (`None` = no cambia)
+#[derive(Debug, Clone, Default)]
+pub struct CambiosSala {
+    pub nombre: Option<String>,
+    pub capacidad: Option<u32>,
+}
+
+/// Salas libres en una franja de un día con sitio para un grupo
+#[derive(Debug, Clone)]
+pub struct BusquedaSalas {
+    pub fecha: NaiveDate,
+    /// Hora del primer slot
+    pub hora_inicio: u32,
+    /// Hora en la que termina la franja (no incluida)
+    pub hora_fin: u32,
+    pub capacidad_minima: u32,
+}
+
+/// Las operaciones que modifican salas exigen el rol `admin-salas` (o `admin`)
 #[async_trait]
 pub trait SalaService: Send + Sync {
-    async fn crear_sala(&self, nombre: String, capacidad: u32) -> Result<Sala, String>;
+    async fn crear_sala(
+        &self,
+        actor: &Actor,
+        nombre: String,
+        capacidad: u32,
+    ) -> Result<Sala, ErrorServicio>;
     async fn listar_salas(&self) -> Result<Vec<Sala>, String>;
+    async fn buscar_salas(&self, consulta: ConsultaSalas) -> Result<Pagina<Sala>, String>;
     async fn obtener_sala(&self, id: &str) -> Result<Option<Sala>, String>;
-    async fn activar_sala(&self, id: &str) -> Result<(), String>;
-    async fn desactivar_sala(&self, id: &str) -> Result<(), String>;
+    async fn activar_sala(&self, actor: &Actor, id: &str) -> Result<(), ErrorServicio>;
+    async fn desactivar_sala(&self, actor: &Actor, id: &str) -> Result<(), ErrorServicio>;
+    /// Aplica los cambios validando de nuevo. `None` si la sala no existe.
+    async fn actualizar_sala(
+        &self,
+        actor: &Actor,
+        id: &str,
+        cambios: CambiosSala,
+    ) -> Result<Option<Sala>, ErrorServicio>;
+    /// Borra la sala; `false` si no existía. Falla si alguna reserva la usa.
+    async fn eliminar_sala(&self, actor: &Actor, id: &str) -> Result<bool, ErrorServicio>;
+    /// Ocupación de las salas activas en cada slot laboral del periodo
+    async fn disponibilidad_salas(&self, periodo: Periodo) -> Result<MatrizDisponibilidad, String>;
+    /// Salas activas con capacidad suficiente y libres en toda la franja, de
+    /// la más pequeña a la más grande. Falla si la franja no es válida.
+    async fn buscar_salas_libres(&self, busqueda: BusquedaSalas) -> Result<Vec<Sala>, String>;
 }
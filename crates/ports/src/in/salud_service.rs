@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+/// Resultado de comprobar una dependencia del servicio
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstadoComponente {
+    /// `empleados`, `salas`, `reservas`...
+    pub nombre: String,
+    /// Por qué no responde; `None` si responde
+    pub error: Option<String>,
+}
+
+/// Si el servicio puede atender peticiones
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstadoPreparacion {
+    pub componentes: Vec<EstadoComponente>,
+}
+
+impl EstadoPreparacion {
+    /// Preparado si responden todos los componentes
+    pub fn preparado(&self) -> bool {
+        self.componentes.iter().all(|c| c.error.is_none())
+    }
+}
+
+/// Puerto de entrada: comprobaciones de salud para orquestadores y balanceadores
+#[async_trait]
+pub trait SaludService: Send + Sync {
+    /// Comprueba que los repositorios responden
+    async fn preparacion(&self) -> EstadoPreparacion;
+}
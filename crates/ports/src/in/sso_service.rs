@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use reservas_domain::Actor;
+
+/// Inicio de sesión en marcha con el proveedor de identidad
+#[derive(Debug, Clone)]
+pub struct InicioSso {
+    /// URL del proveedor a la que se redirige el navegador
+    pub url: String,
+    /// Valor `state` que el proveedor devolverá en la redirección de vuelta
+    pub estado: String,
+}
+
+/// Puerto de entrada: inicio de sesión único con un proveedor externo.
+/// Las identidades se asocian a empleados por email; si no existe el
+/// empleado, se crea en el primer inicio de sesión.
+#[async_trait]
+pub trait SsoService: Send + Sync {
+    /// Prepara un inicio de sesión (estado, nonce y verificador PKCE)
+    async fn iniciar(&self) -> Result<InicioSso, String>;
+
+    /// Completa el inicio de sesión con el código que devuelve el proveedor.
+    /// Cada `estado` sirve una sola vez.
+    async fn completar(&self, estado: &str, codigo: &str) -> Result<Actor, String>;
+
+    /// Autentica una petición con un access token del proveedor
+    async fn autenticar_token(&self, token: &str) -> Result<Actor, String>;
+}
@@ -0,0 +1,47 @@
+use crate::error::ErrorServicio;
+use crate::out::notificador::TipoCambio;
+use crate::out::webhooks::{Entrega, Webhook};
+use async_trait::async_trait;
+use reservas_domain::Actor;
+
+/// Datos para registrar un webhook
+#[derive(Debug, Clone, Default)]
+pub struct NuevoWebhook {
+    pub url: String,
+    /// Vacío = todos los eventos
+    pub eventos: Vec<TipoCambio>,
+    /// Si no se da, se genera uno
+    pub secreto: Option<String>,
+}
+
+/// Puerto de entrada: gestión de webhooks y de su registro de entregas.
+/// Solo para administradores.
+#[async_trait]
+pub trait WebhookService: Send + Sync {
+    /// Devuelve el webhook con su secreto, la única vez que se muestra
+    async fn crear(&self, actor: &Actor, nuevo: NuevoWebhook) -> Result<Webhook, ErrorServicio>;
+
+    async fn listar(&self, actor: &Actor) -> Result<Vec<Webhook>, ErrorServicio>;
+
+    async fn obtener(&self, actor: &Actor, id: &str) -> Result<Option<Webhook>, ErrorServicio>;
+
+    /// `false` si no existía
+    async fn eliminar(&self, actor: &Actor, id: &str) -> Result<bool, ErrorServicio>;
+
+    /// `None` si el webhook no existe
+    async fn listar_entregas(
+        &self,
+        actor: &Actor,
+        webhook_id: &str,
+    ) -> Result<Option<Vec<Entrega>>, ErrorServicio>;
+
+    /// Vuelve a enviar ya una entrega (un solo intento) y devuelve cómo
+    /// queda. `None` si no existe o no es de ese webhook. Si se entrega,
+    /// los reintentos automáticos que quedaran ya no se hacen.
+    async fn reenviar(
+        &self,
+        actor: &Actor,
+        webhook_id: &str,
+        entrega_id: &str,
+    ) -> Result<Option<Entrega>, ErrorServicio>;
+}
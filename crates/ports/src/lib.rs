@@ -7,5 +7,12 @@
 // - INPUT PORTS: Cómo usar el sistema (casos de uso)
 // - OUTPUT PORTS: Qué necesita el sistema (repositorios, etc.)
 
+pub mod consulta;
+pub mod error;
 pub mod r#in;
 pub mod out;
+
+// Suite de conformidad: cualquier implementación de los puertos de salida
+// debe pasar estas pruebas (activar con la feature `testing`)
+#[cfg(feature = "testing")]
+pub mod testing;
@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reservas_domain::Accion;
+
+/// Operación rechazada por falta de permisos
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntentoDenegado {
+    pub fecha: DateTime<Utc>,
+    pub sujeto: String,
+    pub accion: Accion,
+    /// Entidad afectada (`reserva:<id>`, `sala:<id>`...)
+    pub recurso: String,
+}
+
+/// Puerto de salida: dónde queda constancia de los accesos denegados
+#[async_trait]
+pub trait RegistroAuditoria: Send + Sync {
+    async fn registrar(&self, intento: IntentoDenegado) -> Result<(), String>;
+
+    /// Intentos registrados, del más antiguo al más reciente
+    async fn listar(&self) -> Result<Vec<IntentoDenegado>, String>;
+}
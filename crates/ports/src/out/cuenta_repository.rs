@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use reservas_domain::Cuenta;
+
+/// Puerto de salida: dónde se guardan las cuentas locales de la interfaz web.
+/// El usuario es único.
+#[async_trait]
+pub trait CuentaRepository: Send + Sync {
+    /// Falla si el usuario ya existe
+    async fn guardar(&self, cuenta: &Cuenta) -> Result<(), String>;
+
+    async fn obtener(&self, usuario: &str) -> Result<Option<Cuenta>, String>;
+
+    /// Falla si la cuenta no existe
+    async fn actualizar(&self, cuenta: &Cuenta) -> Result<(), String>;
+
+    async fn listar(&self) -> Result<Vec<Cuenta>, String>;
+}
@@ -1,16 +1,64 @@
+use crate::consulta::{ConsultaEmpleados, Pagina};
 use async_trait::async_trait;
-use reservas_domain::Empleado;
+use reservas_domain::{Email, Empleado};
+use std::sync::Arc;
 
-/// Puerto de salida (OUTPUT PORT): Define cómo persistir empleados
+/// Puerto de salida (OUTPUT PORT): Define cómo persistir empleados.
+/// El email es único: `guardar` y `actualizar` fallan si otro empleado ya lo usa.
 #[async_trait]
 pub trait EmpleadoRepository {
     async fn guardar(&self, empleado: &Empleado) -> Result<(), String>;
 
     async fn obtener(&self, id: &str) -> Result<Option<Empleado>, String>;
 
+    /// Busca por email (ya normalizado por `Email`)
+    async fn obtener_por_email(&self, email: &Email) -> Result<Option<Empleado>, String>;
+
     async fn listar(&self) -> Result<Vec<Empleado>, String>;
 
+    /// Lista filtrando y paginando según la consulta
+    async fn buscar(&self, consulta: &ConsultaEmpleados) -> Result<Pagina<Empleado>, String>;
+
     async fn actualizar(&self, empleado: &Empleado) -> Result<(), String>;
 
+    /// Borra el empleado. Falla si no existe.
+    async fn eliminar(&self, id: &str) -> Result<(), String>;
+
     async fn existe(&self, id: &str) -> Result<bool, String>;
 }
+
+/// Permite compartir un mismo repositorio entre varios casos de uso
+#[async_trait]
+impl<T: EmpleadoRepository + Send + Sync + ?Sized> EmpleadoRepository for Arc<T> {
+    async fn guardar(&self, empleado: &Empleado) -> Result<(), String> {
+        (**self).guardar(empleado).await
+    }
+
+    async fn obtener(&self, id: &str) -> Result<Option<Empleado>, String> {
+        (**self).obtener(id).await
+    }
+
+    async fn obtener_por_email(&self, email: &Email) -> Result<Option<Empleado>, String> {
+        (**self).obtener_por_email(email).await
+    }
+
+    async fn listar(&self) -> Result<Vec<Empleado>, String> {
+        (**self).listar().await
+    }
+
+    async fn buscar(&self, consulta: &ConsultaEmpleados) -> Result<Pagina<Empleado>, String> {
+        (**self).buscar(consulta).await
+    }
+
+    async fn actualizar(&self, empleado: &Empleado) -> Result<(), String> {
+        (**self).actualizar(empleado).await
+    }
+
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        (**self).eliminar(id).await
+    }
+
+    async fn existe(&self, id: &str) -> Result<bool, String> {
+        (**self).existe(id).await
+    }
+}
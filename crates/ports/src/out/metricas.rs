@@ -0,0 +1,45 @@
+use crate::out::notificador::TipoCambio;
+use std::time::Duration;
+
+/// Qué ocupaba ya el slot cuando se rechazó una reserva
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecursoEnConflicto {
+    Empleado,
+    Sala,
+}
+
+impl RecursoEnConflicto {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Empleado => "empleado",
+            Self::Sala => "sala",
+        }
+    }
+}
+
+/// Puerto de salida: dónde se acumulan las métricas de funcionamiento.
+/// Como avisar de un cambio, medir no puede fallar ni bloquear la operación
+/// medida.
+pub trait Metricas: Send + Sync {
+    /// Petición HTTP atendida. `ruta` es la plantilla de la ruta
+    /// (`/api/reservas/:id`), no la URL, para no crear una serie por ID.
+    fn peticion_http(&self, metodo: &str, ruta: &str, estado: u16, duracion: Duration);
+
+    /// Cambio ya persistido de una reserva
+    fn cambio_reserva(&self, tipo: TipoCambio);
+
+    /// Reserva rechazada porque el slot ya estaba ocupado
+    fn conflicto_rechazado(&self, recurso: RecursoEnConflicto);
+
+    /// Operación de un repositorio (`reservas`, `listar`...) y si tuvo éxito
+    fn operacion_repositorio(
+        &self,
+        repositorio: &str,
+        operacion: &str,
+        duracion: Duration,
+        exito: bool,
+    );
+
+    /// Todas las métricas en el formato de exposición de texto de Prometheus
+    fn exponer(&self) -> String;
+}
@@ -1,3 +1,10 @@
+pub mod auditoria;
+pub mod cuenta_repository;
 pub mod empleado_repository;
+pub mod metricas;
+pub mod notificador;
+pub mod proveedor_identidad;
 pub mod reserva_repository;
 pub mod sala_repository;
+pub mod version_calendario_repository;
+pub mod webhooks;
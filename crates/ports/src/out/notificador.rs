@@ -0,0 +1,65 @@
+use reservas_domain::{Reserva, Slot};
+
+/// Qué le ha pasado a una reserva
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoCambio {
+    Creada,
+    Confirmada,
+    Cancelada,
+    Modificada,
+    Reprogramada,
+    /// Ha pasado a otro empleado (p. ej. al desactivar a su titular)
+    Reasignada,
+    Eliminada,
+}
+
+impl TipoCambio {
+    pub const TODOS: [TipoCambio; 7] = [
+        Self::Creada,
+        Self::Confirmada,
+        Self::Cancelada,
+        Self::Modificada,
+        Self::Reprogramada,
+        Self::Reasignada,
+        Self::Eliminada,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Creada => "creada",
+            Self::Confirmada => "confirmada",
+            Self::Cancelada => "cancelada",
+            Self::Modificada => "modificada",
+            Self::Reprogramada => "reprogramada",
+            Self::Reasignada => "reasignada",
+            Self::Eliminada => "eliminada",
+        }
+    }
+
+    /// Nombre del evento para sistemas externos (`reserva.creada`...)
+    pub fn evento(self) -> String {
+        format!("reserva.{}", self.as_str())
+    }
+
+    pub fn desde_evento(evento: &str) -> Option<Self> {
+        Self::TODOS.into_iter().find(|tipo| tipo.evento() == evento)
+    }
+}
+
+/// Cambio ya persistido de una reserva
+#[derive(Debug, Clone, PartialEq)]
+pub struct CambioReserva {
+    pub tipo: TipoCambio,
+    /// Estado de la reserva tras el cambio (el último conocido si se eliminó)
+    pub reserva: Reserva,
+    /// Slot que ocupaba antes de reprogramarla
+    pub slot_anterior: Option<Slot>,
+    /// Titular que tenía antes de reasignarla, que ha quedado libre
+    pub empleado_anterior: Option<String>,
+}
+
+/// Puerto de salida: a quién avisar de los cambios en las reservas.
+/// No puede fallar: el cambio ya está guardado y avisar es secundario.
+pub trait NotificadorCambios: Send + Sync {
+    fn notificar(&self, cambio: CambioReserva);
+}
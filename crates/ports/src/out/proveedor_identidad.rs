@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use reservas_domain::Rol;
+
+/// Identidad que un proveedor externo (OIDC) ha verificado
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentidadExterna {
+    /// `sub` del proveedor
+    pub sujeto: String,
+    /// Email con el que se busca al empleado
+    pub email: Option<String>,
+    /// El proveedor afirma (`email_verified`) que el email es del usuario.
+    /// Sin esa garantía no se asocia a un empleado que ya existe.
+    pub email_verificado: bool,
+    pub nombre: Option<String>,
+    /// Roles que envía el proveedor (los desconocidos ya se han descartado)
+    pub roles: Vec<Rol>,
+}
+
+/// Puerto de salida: proveedor de identidad externo con el flujo
+/// authorization code + PKCE (S256)
+#[async_trait]
+pub trait ProveedorIdentidad: Send + Sync {
+    /// URL del proveedor a la que se redirige el navegador para iniciar sesión
+    fn url_autorizacion(&self, estado: &str, nonce: &str, reto_pkce: &str) -> String;
+
+    /// Canjea el código de autorización y valida el ID token (firma, emisor,
+    /// audiencia y `nonce`)
+    async fn canjear_codigo(
+        &self,
+        codigo: &str,
+        verificador_pkce: &str,
+        nonce: &str,
+    ) -> Result<IdentidadExterna, String>;
+
+    /// Valida un access token emitido por el proveedor
+    async fn validar_token(&self, token: &str) -> Result<IdentidadExterna, String>;
+}
@@ -1,5 +1,7 @@
+use crate::consulta::{ConsultaReservas, Pagina};
 use async_trait::async_trait;
 use reservas_domain::{Reserva, Slot};
+use std::sync::Arc;
 
 /// Puerto de salida (OUTPUT PORT): Define cómo persistir reservas
 #[async_trait]
@@ -10,12 +12,19 @@ pub trait ReservaRepository {
 
     async fn listar(&self) -> Result<Vec<Reserva>, String>;
 
+    /// Lista filtrando, ordenando y paginando según la consulta.
+    /// Un cursor mal formado o de otro orden es un error.
+    async fn buscar(&self, consulta: &ConsultaReservas) -> Result<Pagina<Reserva>, String>;
+
     async fn listar_por_empleado(&self, empleado_id: &str) -> Result<Vec<Reserva>, String>;
 
     async fn listar_por_slot(&self, slot: &Slot) -> Result<Vec<Reserva>, String>;
 
     async fn actualizar(&self, reserva: &Reserva) -> Result<(), String>;
 
+    /// Borra la reserva. Falla si no existe.
+    async fn eliminar(&self, id: &str) -> Result<(), String>;
+
     async fn existe(&self, id: &str) -> Result<bool, String>;
 
     async fn existe_para_empleado_en_slot(
@@ -24,3 +33,53 @@ pub trait ReservaRepository {
         slot: &Slot,
     ) -> Result<bool, String>;
 }
+
+/// Permite compartir un mismo repositorio entre varios casos de uso
+#[async_trait]
+impl<T: ReservaRepository + Send + Sync + ?Sized> ReservaRepository for Arc<T> {
+    async fn guardar(&self, reserva: &Reserva) -> Result<(), String> {
+        (**self).guardar(reserva).await
+    }
+
+    async fn obtener(&self, id: &str) -> Result<Option<Reserva>, String> {
+        (**self).obtener(id).await
+    }
+
+    async fn listar(&self) -> Result<Vec<Reserva>, String> {
+        (**self).listar().await
+    }
+
+    async fn buscar(&self, consulta: &ConsultaReservas) -> Result<Pagina<Reserva>, String> {
+        (**self).buscar(consulta).await
+    }
+
+    async fn listar_por_empleado(&self, empleado_id: &str) -> Result<Vec<Reserva>, String> {
+        (**self).listar_por_empleado(empleado_id).await
+    }
+
+    async fn listar_por_slot(&self, slot: &Slot) -> Result<Vec<Reserva>, String> {
+        (**self).listar_por_slot(slot).await
+    }
+
+    async fn actualizar(&self, reserva: &Reserva) -> Result<(), String> {
+        (**self).actualizar(reserva).await
+    }
+
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        (**self).eliminar(id).await
+    }
+
+    async fn existe(&self, id: &str) -> Result<bool, String> {
+        (**self).existe(id).await
+    }
+
+    async fn existe_para_empleado_en_slot(
+        &self,
+        empleado_id: &str,
+        slot: &Slot,
+    ) -> Result<bool, String> {
+        (**self)
+            .existe_para_empleado_en_slot(empleado_id, slot)
+            .await
+    }
+}
@@ -1,10 +1,38 @@
+use crate::consulta::{ConsultaSalas, Pagina};
 use async_trait::async_trait;
 use reservas_domain::Sala;
+use std::sync::Arc;
 
 #[async_trait]
 pub trait SalaRepository {
     async fn guardar(&self, sala: &Sala) -> Result<(), String>;
     async fn obtener(&self, id: &str) -> Result<Option<Sala>, String>;
     async fn listar(&self) -> Result<Vec<Sala>, String>;
+    async fn buscar(&self, consulta: &ConsultaSalas) -> Result<Pagina<Sala>, String>;
     async fn actualizar(&self, sala: &Sala) -> Result<(), String>;
+    /// Borra la sala. Falla si no existe.
+    async fn eliminar(&self, id: &str) -> Result<(), String>;
+}
+
+/// Permite compartir un mismo repositorio entre varios casos de uso
+#[async_trait]
+impl<T: SalaRepository + Send + Sync + ?Sized> SalaRepository for Arc<T> {
+    async fn guardar(&self, sala: &Sala) -> Result<(), String> {
+        (**self).guardar(sala).await
+    }
+    async fn obtener(&self, id: &str) -> Result<Option<Sala>, String> {
+        (**self).obtener(id).await
+    }
+    async fn listar(&self) -> Result<Vec<Sala>, String> {
+        (**self).listar().await
+    }
+    async fn buscar(&self, consulta: &ConsultaSalas) -> Result<Pagina<Sala>, String> {
+        (**self).buscar(consulta).await
+    }
+    async fn actualizar(&self, sala: &Sala) -> Result<(), String> {
+        (**self).actualizar(sala).await
+    }
+    async fn eliminar(&self, id: &str) -> Result<(), String> {
+        (**self).eliminar(id).await
+    }
 }
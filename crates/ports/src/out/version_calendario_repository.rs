@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+
+/// Puerto de salida: versión de la URL privada de cada calendario. El token
+/// la incluye, así que cambiarla invalida la URL de ese calendario sin tocar
+/// las demás.
+#[async_trait]
+pub trait VersionCalendarioRepository: Send + Sync {
+    /// Versión actual del calendario (`empleado:<id>`, `sala:<id>`); 0 si
+    /// nunca se ha cambiado
+    async fn version(&self, calendario: &str) -> Result<u64, String>;
+
+    /// Pasa a la versión siguiente y la devuelve
+    async fn incrementar(&self, calendario: &str) -> Result<u64, String>;
+}
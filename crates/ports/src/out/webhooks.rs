@@ -0,0 +1,88 @@
+use crate::out::notificador::TipoCambio;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+/// Suscripción de un sistema externo a los cambios en las reservas
+#[derive(Debug, Clone, PartialEq)]
+pub struct Webhook {
+    pub id: String,
+    /// URL http(s) a la que se envían los eventos por POST
+    pub url: String,
+    /// Eventos que interesan; vacío = todos
+    pub eventos: Vec<TipoCambio>,
+    /// Clave de la firma HMAC-SHA256 de cada entrega
+    pub secreto: String,
+    pub creado: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn acepta(&self, tipo: TipoCambio) -> bool {
+        self.eventos.is_empty() || self.eventos.contains(&tipo)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstadoEntrega {
+    /// Quedan reintentos
+    Pendiente,
+    Entregada,
+    /// Agotados los reintentos; se puede reenviar a mano
+    Fallida,
+}
+
+/// Resultado de un intento de entrega
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntentoEntrega {
+    pub fecha: DateTime<Utc>,
+    /// Código HTTP de la respuesta, si la hubo
+    pub codigo: Option<u16>,
+    /// Error de red o respuesta no 2xx
+    pub error: Option<String>,
+}
+
+/// Envío de un evento a un webhook, con todos sus intentos
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entrega {
+    pub id: String,
+    pub webhook_id: String,
+    pub evento: TipoCambio,
+    /// Cuerpo JSON enviado; se reenvía tal cual
+    pub cuerpo: String,
+    pub estado: EstadoEntrega,
+    pub intentos: Vec<IntentoEntrega>,
+    pub creada: DateTime<Utc>,
+}
+
+/// Puerto de salida: webhooks registrados y registro de entregas
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    async fn guardar(&self, webhook: &Webhook) -> Result<(), String>;
+
+    async fn obtener(&self, id: &str) -> Result<Option<Webhook>, String>;
+
+    async fn listar(&self) -> Result<Vec<Webhook>, String>;
+
+    /// Borra el webhook y sus entregas; `false` si no existía
+    async fn eliminar(&self, id: &str) -> Result<bool, String>;
+
+    /// Guarda una entrega nueva o sustituye la que tenga el mismo id
+    async fn guardar_entrega(&self, entrega: &Entrega) -> Result<(), String>;
+
+    async fn obtener_entrega(&self, id: &str) -> Result<Option<Entrega>, String>;
+
+    /// Entregas de un webhook, de la más antigua a la más reciente
+    async fn listar_entregas(&self, webhook_id: &str) -> Result<Vec<Entrega>, String>;
+}
+
+/// Puerto de salida: transporte HTTP de las entregas
+#[async_trait]
+pub trait ClienteWebhook: Send + Sync {
+    /// POST de `cuerpo` (JSON) con las cabeceras dadas. Devuelve el código de
+    /// respuesta, sea cual sea; `Err` solo si no hubo respuesta.
+    async fn enviar(
+        &self,
+        url: &str,
+        cabeceras: &[(String, String)],
+        cuerpo: &str,
+    ) -> Result<u16, String>;
+}
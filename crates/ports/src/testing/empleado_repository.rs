@@ -0,0 +1,208 @@
+use crate::consulta::ConsultaEmpleados;
+use crate::out::empleado_repository::EmpleadoRepository;
+use reservas_domain::{Email, Empleado};
+
+fn empleado(id: &str, nombre: &str) -> Empleado {
+    Empleado::new(
+        id.to_string(),
+        nombre.to_string(),
+        format!("{}@empresa.com", id),
+    )
+    .unwrap()
+}
+
+/// Ejecuta todas las verificaciones del contrato de `EmpleadoRepository`
+pub async fn verificar_contrato<R, F>(crear: F)
+where
+    R: EmpleadoRepository,
+    F: Fn() -> R,
+{
+    guardar_y_obtener(&crear()).await;
+    obtener_inexistente_devuelve_none(&crear()).await;
+    actualizar_inexistente_falla(&crear()).await;
+    actualizar_persiste_cambios(&crear()).await;
+    existe(&crear()).await;
+    obtener_por_email(&crear()).await;
+    email_duplicado_falla(&crear()).await;
+    eliminar_borra(&crear()).await;
+    eliminar_inexistente_falla(&crear()).await;
+    listar_incluye_inactivos(&crear()).await;
+    orden_por_nombre(&crear()).await;
+    buscar_filtra_y_pagina(&crear()).await;
+}
+
+/// Un empleado guardado se recupera idéntico por su ID
+pub async fn guardar_y_obtener<R: EmpleadoRepository>(repo: &R) {
+    let e = empleado("emp-001", "Juan");
+    repo.guardar(&e).await.unwrap();
+
+    assert_eq!(repo.obtener("emp-001").await.unwrap(), Some(e));
+}
+
+/// Buscar un ID desconocido no es un error, devuelve `None`
+pub async fn obtener_inexistente_devuelve_none<R: EmpleadoRepository>(repo: &R) {
+    assert_eq!(repo.obtener("no-existe").await.unwrap(), None);
+}
+
+/// Actualizar un empleado que nunca se guardó falla y no lo crea
+pub async fn actualizar_inexistente_falla<R: EmpleadoRepository>(repo: &R) {
+    let e = empleado("emp-001", "Juan");
+
+    assert!(repo.actualizar(&e).await.is_err());
+    assert!(!repo.existe("emp-001").await.unwrap());
+}
+
+/// Los cambios se persisten al actualizar
+pub async fn actualizar_persiste_cambios<R: EmpleadoRepository>(repo: &R) {
+    let mut e = empleado("emp-001", "Juan");
+    repo.guardar(&e).await.unwrap();
+
+    e.desactivar();
+    repo.actualizar(&e).await.unwrap();
+
+    let obtenido = repo.obtener("emp-001").await.unwrap().unwrap();
+    assert!(!obtenido.activo);
+}
+
+/// `existe` refleja si el ID se ha guardado
+pub async fn existe<R: EmpleadoRepository>(repo: &R) {
+    assert!(!repo.existe("emp-001").await.unwrap());
+    repo.guardar(&empleado("emp-001", "Juan")).await.unwrap();
+    assert!(repo.existe("emp-001").await.unwrap());
+}
+
+/// Se encuentra por email; `None` si nadie lo usa
+pub async fn obtener_por_email<R: EmpleadoRepository>(repo: &R) {
+    let e = empleado("emp-001", "Juan");
+    repo.guardar(&e).await.unwrap();
+
+    let email = Email::new("EMP-001@empresa.com").unwrap();
+    assert_eq!(repo.obtener_por_email(&email).await.unwrap(), Some(e));
+    let otro = Email::new("nadie@empresa.com").unwrap();
+    assert_eq!(repo.obtener_por_email(&otro).await.unwrap(), None);
+}
+
+/// Dos empleados no pueden compartir email, ni al guardar ni al actualizar
+pub async fn email_duplicado_falla<R: EmpleadoRepository>(repo: &R) {
+    repo.guardar(&empleado("emp-001", "Juan")).await.unwrap();
+    let mut maria = empleado("emp-002", "María");
+    repo.guardar(&maria).await.unwrap();
+
+    let mut copia = empleado("emp-003", "Juan");
+    copia.email = Email::new("emp-001@empresa.com").unwrap();
+    assert!(repo.guardar(&copia).await.is_err());
+    assert!(!repo.existe("emp-003").await.unwrap());
+
+    maria.email = Email::new("emp-001@empresa.com").unwrap();
+    assert!(repo.actualizar(&maria).await.is_err());
+
+    // Guardar de nuevo el mismo empleado no choca consigo mismo
+    let mut juan = empleado("emp-001", "Juan");
+    juan.nombre = "Juan López".to_string();
+    repo.guardar(&juan).await.unwrap();
+    repo.actualizar(&juan).await.unwrap();
+}
+
+/// Un empleado eliminado deja de existir y de listarse
+pub async fn eliminar_borra<R: EmpleadoRepository>(repo: &R) {
+    repo.guardar(&empleado("emp-001", "Juan")).await.unwrap();
+    repo.guardar(&empleado("emp-002", "María")).await.unwrap();
+
+    repo.eliminar("emp-001").await.unwrap();
+
+    assert!(!repo.existe("emp-001").await.unwrap());
+    assert_eq!(repo.obtener("emp-001").await.unwrap(), None);
+    assert_eq!(repo.listar().await.unwrap().len(), 1);
+}
+
+/// Eliminar un ID desconocido es un error
+pub async fn eliminar_inexistente_falla<R: EmpleadoRepository>(repo: &R) {
+    assert!(repo.eliminar("no-existe").await.is_err());
+}
+
+/// `listar` devuelve también los empleados desactivados
+pub async fn listar_incluye_inactivos<R: EmpleadoRepository>(repo: &R) {
+    let mut inactivo = empleado("emp-001", "Juan");
+    inactivo.desactivar();
+    repo.guardar(&inactivo).await.unwrap();
+    repo.guardar(&empleado("emp-002", "María")).await.unwrap();
+
+    assert_eq!(repo.listar().await.unwrap().len(), 2);
+}
+
+/// `listar` ordena por nombre (y por ID a igualdad de nombre)
+pub async fn orden_por_nombre<R: EmpleadoRepository>(repo: &R) {
+    repo.guardar(&empleado("emp-003", "Carlos")).await.unwrap();
+    repo.guardar(&empleado("emp-002", "Ana")).await.unwrap();
+    repo.guardar(&empleado("emp-001", "Carlos")).await.unwrap();
+
+    let ids: Vec<_> = repo
+        .listar()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(ids, vec!["emp-002", "emp-001", "emp-003"]);
+}
+
+/// `buscar` filtra por texto y estado y pagina con cursor
+pub async fn buscar_filtra_y_pagina<R: EmpleadoRepository>(repo: &R) {
+    let mut inactivo = empleado("emp-004", "Juana");
+    inactivo.desactivar();
+    repo.guardar(&inactivo).await.unwrap();
+    repo.guardar(&empleado("emp-001", "Juan")).await.unwrap();
+    repo.guardar(&empleado("emp-002", "Ana")).await.unwrap();
+    repo.guardar(&empleado("emp-003", "Luis")).await.unwrap();
+
+    let primera = repo
+        .buscar(&ConsultaEmpleados {
+            activo: Some(true),
+            limite: Some(2),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let nombres: Vec<_> = primera
+        .elementos
+        .iter()
+        .map(|e| e.nombre.as_str())
+        .collect();
+    assert_eq!(nombres, vec!["Ana", "Juan"]);
+
+    let segunda = repo
+        .buscar(&ConsultaEmpleados {
+            activo: Some(true),
+            limite: Some(2),
+            cursor: primera.siguiente_cursor,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let nombres: Vec<_> = segunda
+        .elementos
+        .iter()
+        .map(|e| e.nombre.as_str())
+        .collect();
+    assert_eq!(nombres, vec!["Luis"]);
+    assert_eq!(segunda.siguiente_cursor, None);
+
+    // El texto busca en nombre y email sin distinguir mayúsculas
+    let por_texto = repo
+        .buscar(&ConsultaEmpleados {
+            texto: Some("JUAN".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(por_texto.elementos.len(), 2);
+
+    let por_email = repo
+        .buscar(&ConsultaEmpleados {
+            texto: Some("emp-003@".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(por_email.elementos[0].nombre, "Luis");
+}
@@ -0,0 +1,26 @@
+// 🧪 SUITE DE CONFORMIDAD - Contrato de comportamiento de los puertos de salida
+//
+// Los traits solo definen la FIRMA de los repositorios; estas funciones
+// definen su COMPORTAMIENTO esperado. Cualquier adaptador (en memoria,
+// Postgres, ...) debe pasar la suite completa desde sus propios tests:
+//
+//     #[tokio::test]
+//     async fn test_contrato() {
+//         reserva_repository::verificar_contrato(MiRepositorio::new).await;
+//     }
+//
+// Cada verificación recibe una factoría para trabajar sobre un repositorio vacío.
+
+pub mod empleado_repository;
+pub mod reserva_repository;
+pub mod sala_repository;
+
+use chrono::{Datelike, Utc};
+use reservas_domain::Slot;
+
+/// Slot de mañana a la hora indicada (siempre en el futuro)
+pub(crate) fn slot_manyana(hora: u32) -> Slot {
+    let manyana = Utc::now() + chrono::Duration::days(1);
+    Slot::from_date_and_hour(manyana.year(), manyana.month(), manyana.day(), hora)
+        .expect("hora válida")
+}
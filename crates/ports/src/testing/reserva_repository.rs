@@ -0,0 +1,376 @@
+use super::slot_manyana;
+use crate::consulta::{ConsultaReservas, Direccion, OrdenReservas};
+use crate::out::reserva_repository::ReservaRepository;
+use reservas_domain::{EstadoReserva, Reserva, Slot};
+
+fn reserva(id: &str, empleado_id: &str, slot: Slot) -> Reserva {
+    Reserva::new(
+        id.to_string(),
+        empleado_id.to_string(),
+        slot,
+        format!("Reserva {}", id),
+    )
+    .expect("reserva válida")
+}
+
+/// Ejecuta todas las verificaciones del contrato de `ReservaRepository`
+pub async fn verificar_contrato<R, F>(crear: F)
+where
+    R: ReservaRepository,
+    F: Fn() -> R,
+{
+    guardar_y_obtener(&crear()).await;
+    obtener_inexistente_devuelve_none(&crear()).await;
+    actualizar_inexistente_falla(&crear()).await;
+    actualizar_persiste_cambios(&crear()).await;
+    existe(&crear()).await;
+    eliminar_borra(&crear()).await;
+    eliminar_inexistente_falla(&crear()).await;
+    listar_incluye_canceladas(&crear()).await;
+    listar_por_empleado_excluye_canceladas(&crear()).await;
+    listar_por_slot_excluye_canceladas(&crear()).await;
+    conflicto_de_slot(&crear()).await;
+    orden_cronologico(&crear()).await;
+    buscar_aplica_filtros(&crear()).await;
+    buscar_pagina_con_cursor(&crear()).await;
+    buscar_orden_descendente(&crear()).await;
+    buscar_cursor_invalido_falla(&crear()).await;
+    buscar_cursor_sobrevive_cambios(&crear()).await;
+}
+
+/// Una reserva guardada se recupera idéntica por su ID
+pub async fn guardar_y_obtener<R: ReservaRepository>(repo: &R) {
+    let r = reserva("r1", "emp-001", slot_manyana(10));
+    repo.guardar(&r).await.unwrap();
+
+    assert_eq!(repo.obtener("r1").await.unwrap(), Some(r));
+}
+
+/// Buscar un ID desconocido no es un error, devuelve `None`
+pub async fn obtener_inexistente_devuelve_none<R: ReservaRepository>(repo: &R) {
+    assert_eq!(repo.obtener("no-existe").await.unwrap(), None);
+}
+
+/// Actualizar una reserva que nunca se guardó falla y no la crea
+pub async fn actualizar_inexistente_falla<R: ReservaRepository>(repo: &R) {
+    let r = reserva("r1", "emp-001", slot_manyana(10));
+
+    assert!(repo.actualizar(&r).await.is_err());
+    assert!(!repo.existe("r1").await.unwrap());
+}
+
+/// Los cambios de estado se persisten al actualizar
+pub async fn actualizar_persiste_cambios<R: ReservaRepository>(repo: &R) {
+    let mut r = reserva("r1", "emp-001", slot_manyana(10));
+    repo.guardar(&r).await.unwrap();
+
+    r.confirmar();
+    repo.actualizar(&r).await.unwrap();
+
+    let obtenida = repo.obtener("r1").await.unwrap().unwrap();
+    assert_eq!(obtenida.estado, EstadoReserva::Confirmada);
+}
+
+/// `existe` refleja si el ID se ha guardado
+pub async fn existe<R: ReservaRepository>(repo: &R) {
+    assert!(!repo.existe("r1").await.unwrap());
+    repo.guardar(&reserva("r1", "emp-001", slot_manyana(10)))
+        .await
+        .unwrap();
+    assert!(repo.existe("r1").await.unwrap());
+}
+
+/// Una reserva eliminada desaparece también de las consultas por slot
+/// y libera el slot del empleado
+pub async fn eliminar_borra<R: ReservaRepository>(repo: &R) {
+    let slot = slot_manyana(10);
+    repo.guardar(&reserva("r1", "emp-001", slot.clone()))
+        .await
+        .unwrap();
+
+    repo.eliminar("r1").await.unwrap();
+
+    assert!(!repo.existe("r1").await.unwrap());
+    assert!(repo.listar().await.unwrap().is_empty());
+    assert!(repo.listar_por_slot(&slot).await.unwrap().is_empty());
+    assert!(!repo
+        .existe_para_empleado_en_slot("emp-001", &slot)
+        .await
+        .unwrap());
+}
+
+/// Eliminar un ID desconocido es un error
+pub async fn eliminar_inexistente_falla<R: ReservaRepository>(repo: &R) {
+    assert!(repo.eliminar("no-existe").await.is_err());
+}
+
+/// `listar` devuelve TODAS las reservas, también las canceladas
+pub async fn listar_incluye_canceladas<R: ReservaRepository>(repo: &R) {
+    let mut cancelada = reserva("r1", "emp-001", slot_manyana(10));
+    cancelada.cancelar();
+    repo.guardar(&cancelada).await.unwrap();
+    repo.guardar(&reserva("r2", "emp-001", slot_manyana(11)))
+        .await
+        .unwrap();
+
+    assert_eq!(repo.listar().await.unwrap().len(), 2);
+}
+
+/// `listar_por_empleado` solo devuelve reservas activas de ese empleado
+pub async fn listar_por_empleado_excluye_canceladas<R: ReservaRepository>(repo: &R) {
+    let mut cancelada = reserva("r1", "emp-001", slot_manyana(10));
+    cancelada.cancelar();
+    repo.guardar(&cancelada).await.unwrap();
+    repo.guardar(&reserva("r2", "emp-001", slot_manyana(11)))
+        .await
+        .unwrap();
+    repo.guardar(&reserva("r3", "emp-002", slot_manyana(11)))
+        .await
+        .unwrap();
+
+    let reservas = repo.listar_por_empleado("emp-001").await.unwrap();
+    let ids: Vec<_> = reservas.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, vec!["r2"]);
+}
+
+/// `listar_por_slot` solo devuelve reservas activas en ese slot
+pub async fn listar_por_slot_excluye_canceladas<R: ReservaRepository>(repo: &R) {
+    let slot = slot_manyana(10);
+    let mut cancelada = reserva("r1", "emp-001", slot.clone());
+    cancelada.cancelar();
+    repo.guardar(&cancelada).await.unwrap();
+    repo.guardar(&reserva("r2", "emp-002", slot.clone()))
+        .await
+        .unwrap();
+    repo.guardar(&reserva("r3", "emp-002", slot_manyana(11)))
+        .await
+        .unwrap();
+
+    let reservas = repo.listar_por_slot(&slot).await.unwrap();
+    let ids: Vec<_> = reservas.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, vec!["r2"]);
+}
+
+/// Un slot está ocupado solo por reservas activas del mismo empleado
+pub async fn conflicto_de_slot<R: ReservaRepository>(repo: &R) {
+    let slot = slot_manyana(10);
+    let mut r = reserva("r1", "emp-001", slot.clone());
+    repo.guardar(&r).await.unwrap();
+
+    assert!(repo
+        .existe_para_empleado_en_slot("emp-001", &slot)
+        .await
+        .unwrap());
+    assert!(!repo
+        .existe_para_empleado_en_slot("emp-002", &slot)
+        .await
+        .unwrap());
+    assert!(!repo
+        .existe_para_empleado_en_slot("emp-001", &slot_manyana(11))
+        .await
+        .unwrap());
+
+    // Al cancelar, el slot queda libre de nuevo
+    r.cancelar();
+    repo.actualizar(&r).await.unwrap();
+    assert!(!repo
+        .existe_para_empleado_en_slot("emp-001", &slot)
+        .await
+        .unwrap());
+}
+
+/// Los listados se devuelven en orden cronológico (y por ID a igualdad de slot)
+pub async fn orden_cronologico<R: ReservaRepository>(repo: &R) {
+    repo.guardar(&reserva("r3", "emp-001", slot_manyana(15)))
+        .await
+        .unwrap();
+    repo.guardar(&reserva("r2", "emp-002", slot_manyana(9)))
+        .await
+        .unwrap();
+    repo.guardar(&reserva("r1", "emp-001", slot_manyana(12)))
+        .await
+        .unwrap();
+    repo.guardar(&reserva("r0", "emp-001", slot_manyana(9)))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        ids(repo.listar().await.unwrap()),
+        vec!["r0", "r2", "r1", "r3"]
+    );
+    assert_eq!(
+        ids(repo.listar_por_empleado("emp-001").await.unwrap()),
+        vec!["r0", "r1", "r3"]
+    );
+    assert_eq!(
+        ids(repo.listar_por_slot(&slot_manyana(9)).await.unwrap()),
+        vec!["r0", "r2"]
+    );
+}
+
+fn ids(reservas: Vec<Reserva>) -> Vec<String> {
+    reservas.into_iter().map(|r| r.id).collect()
+}
+
+/// `buscar` combina todos los filtros de la consulta
+pub async fn buscar_aplica_filtros<R: ReservaRepository>(repo: &R) {
+    let mut cancelada = reserva("r1", "emp-001", slot_manyana(10));
+    cancelada.cancelar();
+    repo.guardar(&cancelada).await.unwrap();
+    repo.guardar(&reserva("r2", "emp-001", slot_manyana(11)).en_sala("s1".to_string()))
+        .await
+        .unwrap();
+    let mut confirmada = reserva("r3", "emp-002", slot_manyana(12));
+    confirmada.descripcion = "Demo con CLIENTE".to_string();
+    confirmada.confirmar();
+    repo.guardar(&confirmada).await.unwrap();
+
+    let buscar = |consulta: ConsultaReservas| async move {
+        ids(repo.buscar(&consulta).await.unwrap().elementos)
+    };
+
+    assert_eq!(
+        buscar(ConsultaReservas::default()).await,
+        vec!["r1", "r2", "r3"]
+    );
+    assert_eq!(
+        buscar(ConsultaReservas {
+            estado: Some(EstadoReserva::Cancelada),
+            ..Default::default()
+        })
+        .await,
+        vec!["r1"]
+    );
+    assert_eq!(
+        buscar(ConsultaReservas {
+            empleado_id: Some("emp-001".to_string()),
+            ..Default::default()
+        })
+        .await,
+        vec!["r1", "r2"]
+    );
+    assert_eq!(
+        buscar(ConsultaReservas {
+            sala_id: Some("s1".to_string()),
+            ..Default::default()
+        })
+        .await,
+        vec!["r2"]
+    );
+    assert_eq!(
+        buscar(ConsultaReservas {
+            texto: Some("cliente".to_string()),
+            ..Default::default()
+        })
+        .await,
+        vec!["r3"]
+    );
+    assert_eq!(
+        buscar(ConsultaReservas {
+            desde: Some(slot_manyana(11).inicio),
+            hasta: Some(slot_manyana(12).inicio),
+            ..Default::default()
+        })
+        .await,
+        vec!["r2"]
+    );
+}
+
+/// Recorrer las páginas con el cursor devuelve todo una sola vez y en orden
+pub async fn buscar_pagina_con_cursor<R: ReservaRepository>(repo: &R) {
+    for (id, hora) in [("r4", 13), ("r1", 10), ("r3", 12), ("r0", 9), ("r2", 11)] {
+        repo.guardar(&reserva(id, "emp-001", slot_manyana(hora)))
+            .await
+            .unwrap();
+    }
+
+    let mut consulta = ConsultaReservas {
+        limite: Some(2),
+        ..Default::default()
+    };
+    let mut paginas = Vec::new();
+    loop {
+        let pagina = repo.buscar(&consulta).await.unwrap();
+        paginas.push(ids(pagina.elementos));
+        match pagina.siguiente_cursor {
+            Some(cursor) => consulta.cursor = Some(cursor),
+            None => break,
+        }
+    }
+
+    assert_eq!(
+        paginas,
+        vec![vec!["r0", "r1"], vec!["r2", "r3"], vec!["r4"]]
+    );
+}
+
+/// El orden puede pedirse por otro campo y en sentido descendente
+pub async fn buscar_orden_descendente<R: ReservaRepository>(repo: &R) {
+    repo.guardar(&reserva("r1", "emp-001", slot_manyana(10)))
+        .await
+        .unwrap();
+    repo.guardar(&reserva("r2", "emp-002", slot_manyana(9)))
+        .await
+        .unwrap();
+    repo.guardar(&reserva("r3", "emp-001", slot_manyana(11)))
+        .await
+        .unwrap();
+
+    let pagina = repo
+        .buscar(&ConsultaReservas {
+            orden: OrdenReservas::Empleado,
+            direccion: Direccion::Descendente,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(ids(pagina.elementos), vec!["r2", "r3", "r1"]);
+}
+
+/// El cursor guarda la posición, no la reserva: si la última reserva de la
+/// página cambia de estado o se borra, la siguiente página sigue donde
+/// estaba sin saltarse ni repetir reservas
+pub async fn buscar_cursor_sobrevive_cambios<R: ReservaRepository>(repo: &R) {
+    for (id, hora) in [("r0", 9), ("r1", 10), ("r2", 11), ("r3", 12)] {
+        repo.guardar(&reserva(id, "emp-001", slot_manyana(hora)))
+            .await
+            .unwrap();
+    }
+
+    let mut consulta = ConsultaReservas {
+        orden: OrdenReservas::Estado,
+        limite: Some(2),
+        ..Default::default()
+    };
+    let primera = repo.buscar(&consulta).await.unwrap();
+    assert_eq!(ids(primera.elementos), vec!["r0", "r1"]);
+
+    // r1 pasa al final del orden por estado
+    let mut r1 = repo.obtener("r1").await.unwrap().unwrap();
+    r1.confirmar();
+    repo.actualizar(&r1).await.unwrap();
+    consulta.cursor = primera.siguiente_cursor;
+    let segunda = repo.buscar(&consulta).await.unwrap();
+    assert_eq!(ids(segunda.elementos), vec!["r2", "r3"]);
+
+    // Y si se borra, el cursor sigue valiendo
+    repo.eliminar("r3").await.unwrap();
+    consulta.limite = Some(1);
+    consulta.cursor = None;
+    let primera = repo.buscar(&consulta).await.unwrap();
+    repo.eliminar("r0").await.unwrap();
+    consulta.cursor = primera.siguiente_cursor;
+    let segunda = repo.buscar(&consulta).await.unwrap();
+    assert_eq!(ids(segunda.elementos), vec!["r2"]);
+}
+
+/// Un cursor que no ha generado el repositorio es un error
+pub async fn buscar_cursor_invalido_falla<R: ReservaRepository>(repo: &R) {
+    let consulta = ConsultaReservas {
+        cursor: Some("no-existe".to_string()),
+        ..Default::default()
+    };
+
+    assert!(repo.buscar(&consulta).await.is_err());
+}
@@ -0,0 +1,142 @@
+use crate::consulta::ConsultaSalas;
+use crate::out::sala_repository::SalaRepository;
+use reservas_domain::Sala;
+
+fn sala(id: &str, nombre: &str, capacidad: u32) -> Sala {
+    Sala::new(id.to_string(), nombre.to_string(), capacidad).expect("sala válida")
+}
+
+/// Ejecuta todas las verificaciones del contrato de `SalaRepository`
+pub async fn verificar_contrato<R, F>(crear: F)
+where
+    R: SalaRepository,
+    F: Fn() -> R,
+{
+    guardar_y_obtener(&crear()).await;
+    obtener_inexistente_devuelve_none(&crear()).await;
+    actualizar_inexistente_falla(&crear()).await;
+    actualizar_persiste_cambios(&crear()).await;
+    eliminar_borra(&crear()).await;
+    eliminar_inexistente_falla(&crear()).await;
+    listar_incluye_inactivas(&crear()).await;
+    orden_por_nombre(&crear()).await;
+    buscar_filtra_y_pagina(&crear()).await;
+}
+
+/// Una sala guardada se recupera con los mismos datos
+pub async fn guardar_y_obtener<R: SalaRepository>(repo: &R) {
+    repo.guardar(&sala("s1", "Sala Azul", 8)).await.unwrap();
+
+    let obtenida = repo.obtener("s1").await.unwrap().unwrap();
+    assert_eq!(obtenida.id, "s1");
+    assert_eq!(obtenida.nombre, "Sala Azul");
+    assert_eq!(obtenida.capacidad, 8);
+    assert!(obtenida.activa);
+}
+
+/// Buscar un ID desconocido no es un error, devuelve `None`
+pub async fn obtener_inexistente_devuelve_none<R: SalaRepository>(repo: &R) {
+    assert!(repo.obtener("no-existe").await.unwrap().is_none());
+}
+
+/// Actualizar una sala que nunca se guardó falla y no la crea
+pub async fn actualizar_inexistente_falla<R: SalaRepository>(repo: &R) {
+    assert!(repo.actualizar(&sala("s1", "Sala Azul", 8)).await.is_err());
+    assert!(repo.obtener("s1").await.unwrap().is_none());
+}
+
+/// Los cambios se persisten al actualizar
+pub async fn actualizar_persiste_cambios<R: SalaRepository>(repo: &R) {
+    let mut s = sala("s1", "Sala Azul", 8);
+    repo.guardar(&s).await.unwrap();
+
+    s.desactivar();
+    repo.actualizar(&s).await.unwrap();
+
+    assert!(!repo.obtener("s1").await.unwrap().unwrap().activa);
+}
+
+/// Una sala eliminada deja de obtenerse y de listarse
+pub async fn eliminar_borra<R: SalaRepository>(repo: &R) {
+    repo.guardar(&sala("s1", "Sala Azul", 8)).await.unwrap();
+    repo.guardar(&sala("s2", "Sala Roja", 4)).await.unwrap();
+
+    repo.eliminar("s1").await.unwrap();
+
+    assert!(repo.obtener("s1").await.unwrap().is_none());
+    assert_eq!(repo.listar().await.unwrap().len(), 1);
+}
+
+/// Eliminar un ID desconocido es un error
+pub async fn eliminar_inexistente_falla<R: SalaRepository>(repo: &R) {
+    assert!(repo.eliminar("no-existe").await.is_err());
+}
+
+/// `listar` devuelve también las salas desactivadas
+pub async fn listar_incluye_inactivas<R: SalaRepository>(repo: &R) {
+    let mut inactiva = sala("s1", "Sala Azul", 8);
+    inactiva.desactivar();
+    repo.guardar(&inactiva).await.unwrap();
+    repo.guardar(&sala("s2", "Sala Roja", 4)).await.unwrap();
+
+    assert_eq!(repo.listar().await.unwrap().len(), 2);
+}
+
+/// `listar` ordena por nombre (y por ID a igualdad de nombre)
+pub async fn orden_por_nombre<R: SalaRepository>(repo: &R) {
+    repo.guardar(&sala("s3", "Sala Roja", 4)).await.unwrap();
+    repo.guardar(&sala("s2", "Sala Azul", 8)).await.unwrap();
+    repo.guardar(&sala("s1", "Sala Roja", 6)).await.unwrap();
+
+    let ids: Vec<_> = repo
+        .listar()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    assert_eq!(ids, vec!["s2", "s1", "s3"]);
+}
+
+/// `buscar` filtra por texto, estado y capacidad y pagina con cursor
+pub async fn buscar_filtra_y_pagina<R: SalaRepository>(repo: &R) {
+    let mut inactiva = sala("s4", "Sala Verde", 20);
+    inactiva.desactivar();
+    repo.guardar(&inactiva).await.unwrap();
+    repo.guardar(&sala("s1", "Sala Azul", 8)).await.unwrap();
+    repo.guardar(&sala("s2", "Sala Roja", 4)).await.unwrap();
+    repo.guardar(&sala("s3", "Auditorio", 50)).await.unwrap();
+
+    let primera = repo
+        .buscar(&ConsultaSalas {
+            activa: Some(true),
+            capacidad_minima: Some(6),
+            limite: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(primera.elementos[0].id, "s3");
+
+    let segunda = repo
+        .buscar(&ConsultaSalas {
+            activa: Some(true),
+            capacidad_minima: Some(6),
+            limite: Some(1),
+            cursor: primera.siguiente_cursor,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(segunda.elementos[0].id, "s1");
+    assert_eq!(segunda.siguiente_cursor, None);
+
+    let por_texto = repo
+        .buscar(&ConsultaSalas {
+            texto: Some("sala".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(por_texto.elementos.len(), 3);
+}
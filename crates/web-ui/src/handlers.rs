@@ -2,56 +2,395 @@
 
 use askama_axum::IntoResponse;
 use axum::{
-    extract::{Form, Path},
+    extract::{Form, Multipart, Path, Query},
     http::StatusCode,
-    response::Redirect,
+    http::{header, HeaderMap},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Redirect, Response,
+    },
     Extension,
 };
-use reservas_domain::reserva::EstadoReserva;
-use reservas_ports::r#in::reserva_service::ReservaService;
-use serde::Deserialize;
+use chrono::{Datelike, Days, NaiveDate, Utc};
+use futures_util::stream;
+use reservas_domain::empleado::Empleado;
+use reservas_domain::informe::DIAS_MAS_OCUPADOS;
+use reservas_domain::reserva::{EstadoReserva, Reserva};
+use reservas_domain::sala::Sala;
+use reservas_domain::{
+    DisponibilidadService, DisponibilidadSlot, InformeOcupacion, Periodo, Rol, Slot, UsoRecurso,
+};
+use reservas_ports::consulta::{ConsultaEmpleados, ConsultaReservas, ConsultaSalas};
+use reservas_ports::error::ErrorServicio;
+use reservas_ports::r#in::reserva_service::{CambiosReserva, ReservaService};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use reservas_ports::r#in::empleado_service::EmpleadoService;
-use reservas_ports::r#in::sala_service::SalaService;
+use hojas_calculo::Formato;
+use reservas_ports::r#in::carga_masiva_service::{
+    CargaMasivaService, Entidad, InformeCarga, Mapeo, ResultadoFila,
+};
+use reservas_ports::r#in::cuenta_service::{CuentaService, NuevaCuenta};
+use reservas_ports::r#in::disponibilidad_en_vivo_service::{
+    DisponibilidadEnVivoService, EventoDisponibilidad, FiltroDisponibilidad,
+};
+use reservas_ports::r#in::empleado_service::{CambiosEmpleado, EmpleadoService, ErrorEmpleado};
+use reservas_ports::r#in::informe_service::{Desglose, InformeService};
+use reservas_ports::r#in::sala_service::{BusquedaSalas, CambiosSala, SalaService};
+use reservas_ports::r#in::sso_service::SsoService;
 
+use crate::sesion::{estado_oidc_valido, id_de_cookie, AlmacenSesiones, Sesion};
 use crate::templates::*;
 
+// ============= Paginación =============
+
+#[derive(Deserialize)]
+pub struct CursorQuery {
+    cursor: Option<String>,
+}
+
+/// Enlace a la siguiente página conservando los filtros actuales
+fn enlace_siguiente(
+    ruta: &str,
+    filtros: &impl Serialize,
+    cursor: Option<String>,
+) -> Option<String> {
+    let cursor = cursor?;
+    let filtros = serde_urlencoded::to_string(filtros).unwrap_or_default();
+    let cursor = serde_urlencoded::to_string([("cursor", cursor)]).unwrap_or_default();
+    Some(format!("{}?{}&{}", ruta, filtros, cursor))
+}
+
+fn no_vacio(valor: &str) -> Option<String> {
+    let valor = valor.trim();
+    (!valor.is_empty()).then(|| valor.to_string())
+}
+
+// ============= Vistas =============
+
+fn vista_empleado(e: Empleado) -> EmpleadoView {
+    EmpleadoView {
+        id: e.id,
+        nombre: e.nombre,
+        email: e.email.to_string(),
+        activo: e.activo,
+    }
+}
+
+fn vista_sala(s: Sala) -> SalaView {
+    SalaView {
+        id: s.id,
+        nombre: s.nombre,
+        capacidad: s.capacidad,
+        activa: s.activa,
+    }
+}
+
+fn vista_reserva(r: Reserva) -> ReservaView {
+    let estado_str = match r.estado {
+        EstadoReserva::Pendiente => "pendiente",
+        EstadoReserva::Confirmada => "confirmada",
+        EstadoReserva::Cancelada => "cancelada",
+    };
+    ReservaView {
+        id: r.id,
+        empleado_id: r.empleado_id,
+        slot_inicio: r.slot.inicio,
+        slot_fin: r.slot.fin(),
+        descripcion: r.descripcion,
+        estado: estado_str.to_string(),
+        sala_id: r.sala_id,
+    }
+}
+
+/// Separa los errores por campo del error general del formulario
+fn errores_formulario(error: ErrorEmpleado) -> (ErroresFormulario, Option<String>) {
+    match error {
+        ErrorEmpleado::Validacion(campos) => (ErroresFormulario::new(campos), None),
+        ErrorEmpleado::Denegado(mensaje) | ErrorEmpleado::Otro(mensaje) => {
+            (ErroresFormulario::default(), Some(mensaje))
+        }
+    }
+}
+
+/// 403 si se denegó por permisos; si no, el código indicado
+fn status_de(error: &ErrorServicio, por_defecto: StatusCode) -> StatusCode {
+    match error {
+        ErrorServicio::Denegado(_) => StatusCode::FORBIDDEN,
+        ErrorServicio::Otro(_) => por_defecto,
+    }
+}
+
+// ============= Sesión =============
+
+pub async fn login_form(
+    Extension(sso): Extension<Option<Arc<dyn SsoService>>>,
+) -> impl IntoResponse {
+    LoginTemplate {
+        sso: sso.is_some(),
+        ..LoginTemplate::default()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct LoginForm {
+    usuario: String,
+    password: String,
+}
+
+/// Abre una sesión nueva y guarda su identificador en la cookie
+pub async fn login_submit(
+    Extension(cuentas): Extension<Arc<dyn CuentaService>>,
+    Extension(sesiones): Extension<Arc<AlmacenSesiones>>,
+    Extension(sso): Extension<Option<Arc<dyn SsoService>>>,
+    Form(form): Form<LoginForm>,
+) -> Response {
+    let error = match cuentas.autenticar(&form.usuario, &form.password).await {
+        Ok(Some(cuenta)) => {
+            let sesion = sesiones.crear(cuenta.actor());
+            let mut response = Redirect::to("/").into_response();
+            response
+                .headers_mut()
+                .insert(header::SET_COOKIE, sesiones.cookie(&sesion));
+            return response;
+        }
+        Ok(None) => "Usuario o contraseña incorrectos".to_string(),
+        Err(e) => e,
+    };
+    let pagina = LoginTemplate {
+        usuario: form.usuario,
+        error: Some(error),
+        sso: sso.is_some(),
+    };
+    (StatusCode::UNAUTHORIZED, pagina).into_response()
+}
+
+/// Redirige al proveedor OIDC. El `state` queda en una cookie para
+/// comprobar a la vuelta que es el mismo navegador.
+pub async fn login_oidc(
+    Extension(sesiones): Extension<Arc<AlmacenSesiones>>,
+    Extension(sso): Extension<Option<Arc<dyn SsoService>>>,
+) -> Response {
+    let Some(sso) = sso else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match sso.iniciar().await {
+        Ok(inicio) => {
+            let mut response = Redirect::to(&inicio.url).into_response();
+            response
+                .headers_mut()
+                .insert(header::SET_COOKIE, sesiones.cookie_oidc(&inicio.estado));
+            response
+        }
+        Err(e) => error_login_oidc(e),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct VueltaOidc {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// Vuelta del proveedor OIDC: canjea el código y abre la sesión
+pub async fn login_oidc_callback(
+    Extension(sesiones): Extension<Arc<AlmacenSesiones>>,
+    Extension(sso): Extension<Option<Arc<dyn SsoService>>>,
+    cabeceras: HeaderMap,
+    Query(vuelta): Query<VueltaOidc>,
+) -> Response {
+    let Some(sso) = sso else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if let Some(error) = vuelta.error {
+        let detalle = vuelta.error_description.unwrap_or(error);
+        return error_login_oidc(format!("El proveedor rechazó el acceso: {}", detalle));
+    }
+    let (Some(codigo), Some(estado)) = (vuelta.code, vuelta.state) else {
+        return error_login_oidc("Respuesta del proveedor incompleta".to_string());
+    };
+    if !estado_oidc_valido(&cabeceras, &estado) {
+        return error_login_oidc("El inicio de sesión no corresponde a este navegador".to_string());
+    }
+
+    match sso.completar(&estado, &codigo).await {
+        Ok(actor) => {
+            let sesion = sesiones.crear(actor);
+            let mut response = Redirect::to("/").into_response();
+            let cabeceras = response.headers_mut();
+            cabeceras.append(header::SET_COOKIE, sesiones.cookie(&sesion));
+            cabeceras.append(header::SET_COOKIE, sesiones.cookie_oidc_borrada());
+            response
+        }
+        Err(e) => error_login_oidc(e),
+    }
+}
+
+fn error_login_oidc(error: String) -> Response {
+    let pagina = LoginTemplate {
+        error: Some(error),
+        sso: true,
+        ..LoginTemplate::default()
+    };
+    (StatusCode::UNAUTHORIZED, pagina).into_response()
+}
+
+pub async fn logout(
+    Extension(sesiones): Extension<Arc<AlmacenSesiones>>,
+    cabeceras: HeaderMap,
+) -> Response {
+    if let Some(id) = id_de_cookie(&cabeceras) {
+        sesiones.eliminar(&id);
+    }
+    let mut response = Redirect::to("/login").into_response();
+    response
+        .headers_mut()
+        .insert(header::SET_COOKIE, sesiones.cookie_borrada());
+    response
+}
+
+pub async fn nueva_cuenta_form(sesion: Sesion) -> impl IntoResponse {
+    CuentaFormTemplate {
+        sesion: sesion.vista(),
+        usuario: String::new(),
+        empleado_id: String::new(),
+        rol: "empleado".to_string(),
+        roles: ROLES_CUENTA,
+        error: None,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CrearCuentaForm {
+    usuario: String,
+    password: String,
+    #[serde(default)]
+    empleado_id: String,
+    rol: String,
+}
+
+/// Alta de una cuenta local (solo administradores)
+pub async fn crear_cuenta_submit(
+    sesion: Sesion,
+    Extension(cuentas): Extension<Arc<dyn CuentaService>>,
+    Form(form): Form<CrearCuentaForm>,
+) -> Response {
+    let resultado = match form.rol.parse::<Rol>() {
+        Ok(rol) => {
+            let nueva = NuevaCuenta {
+                usuario: form.usuario.clone(),
+                password: form.password,
+                empleado_id: no_vacio(&form.empleado_id),
+                roles: vec![rol],
+            };
+            cuentas.registrar(&sesion.actor, nueva).await
+        }
+        Err(e) => Err(ErrorServicio::Otro(e)),
+    };
+    match resultado {
+        Ok(_) => Redirect::to("/").into_response(),
+        Err(e) => {
+            let pagina = CuentaFormTemplate {
+                sesion: sesion.vista(),
+                usuario: form.usuario,
+                empleado_id: form.empleado_id,
+                rol: form.rol,
+                roles: ROLES_CUENTA,
+                error: Some(e.to_string()),
+            };
+            (status_de(&e, StatusCode::BAD_REQUEST), pagina).into_response()
+        }
+    }
+}
+
+pub async fn password_form(sesion: Sesion) -> impl IntoResponse {
+    PasswordTemplate {
+        sesion: sesion.vista(),
+        error: None,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PasswordForm {
+    actual: String,
+    nueva: String,
+}
+
+/// Cambio de la contraseña propia; pide la actual por si la sesión quedó abierta
+pub async fn password_submit(
+    sesion: Sesion,
+    Extension(cuentas): Extension<Arc<dyn CuentaService>>,
+    Form(form): Form<PasswordForm>,
+) -> Response {
+    let usuario = &sesion.actor.sujeto;
+    let error = match cuentas.autenticar(usuario, &form.actual).await {
+        Ok(Some(_)) => {
+            match cuentas
+                .cambiar_password(&sesion.actor, usuario, &form.nueva)
+                .await
+            {
+                Ok(()) => return Redirect::to("/").into_response(),
+                Err(e) => e.to_string(),
+            }
+        }
+        Ok(None) => "La contraseña actual no es correcta".to_string(),
+        Err(e) => e,
+    };
+    let pagina = PasswordTemplate {
+        sesion: sesion.vista(),
+        error: Some(error),
+    };
+    (StatusCode::BAD_REQUEST, pagina).into_response()
+}
+
 // ============= Handlers de páginas =============
 
-pub async fn index() -> impl IntoResponse {
-    IndexTemplate
+pub async fn index(sesion: Sesion) -> impl IntoResponse {
+    IndexTemplate {
+        sesion: sesion.vista(),
+    }
 }
 
 pub async fn listar_empleados_page(
+    sesion: Sesion,
     Extension(service): Extension<Arc<dyn EmpleadoService>>,
+    Query(filtro): Query<FiltroTexto>,
+    Query(pagina): Query<CursorQuery>,
 ) -> Result<impl IntoResponse, StatusCode> {
-    let empleados = service
-        .listar_empleados()
+    let consulta = ConsultaEmpleados {
+        texto: no_vacio(&filtro.q),
+        cursor: pagina.cursor,
+        ..Default::default()
+    };
+    let pagina = service
+        .buscar_empleados(consulta)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
 
-    let empleados_view: Vec<EmpleadoView> = empleados
-        .into_iter()
-        .map(|e| EmpleadoView {
-            id: e.id,
-            nombre: e.nombre,
-            email: e.email,
-            activo: e.activo,
-        })
-        .collect();
+    let empleados_view: Vec<EmpleadoView> =
+        pagina.elementos.into_iter().map(vista_empleado).collect();
 
     Ok(EmpleadosTemplate {
+        sesion: sesion.vista(),
         empleados: empleados_view,
+        siguiente: enlace_siguiente("/empleados", &filtro, pagina.siguiente_cursor),
+        filtro,
     })
 }
 
-pub async fn nuevo_empleado_form() -> impl IntoResponse {
-    EmpleadoFormTemplate
+pub async fn nuevo_empleado_form(sesion: Sesion) -> impl IntoResponse {
+    EmpleadoFormTemplate {
+        sesion: sesion.vista(),
+        ..Default::default()
+    }
 }
 
-pub async fn nuevo_sala_form() -> impl IntoResponse {
-    SalaFormTemplate
+pub async fn nuevo_sala_form(sesion: Sesion) -> impl IntoResponse {
+    SalaFormTemplate {
+        sesion: sesion.vista(),
+    }
 }
 
 #[derive(Deserialize)]
@@ -67,152 +406,1029 @@ pub struct CrearSalaForm {
 }
 
 pub async fn crear_empleado_submit(
+    sesion: Sesion,
     Extension(service): Extension<Arc<dyn EmpleadoService>>,
     Form(form): Form<CrearEmpleadoForm>,
-) -> Result<Redirect, StatusCode> {
-    service
-        .crear_empleado(form.nombre, form.email)
+) -> Response {
+    match service
+        .crear_empleado(&sesion.actor, form.nombre.clone(), form.email.clone())
         .await
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
-
-    Ok(Redirect::to("/empleados"))
+    {
+        Ok(_) => Redirect::to("/empleados").into_response(),
+        Err(e) => {
+            let (errores, error) = errores_formulario(e);
+            let pagina = EmpleadoFormTemplate {
+                sesion: sesion.vista(),
+                nombre: form.nombre,
+                email: form.email,
+                errores,
+                error,
+            };
+            (StatusCode::BAD_REQUEST, pagina).into_response()
+        }
+    }
 }
 
 pub async fn crear_sala_submit(
+    sesion: Sesion,
     Extension(service): Extension<Arc<dyn SalaService>>,
     Form(form): Form<CrearSalaForm>,
 ) -> Result<Redirect, StatusCode> {
     service
-        .crear_sala(form.nombre, form.capacidad)
+        .crear_sala(&sesion.actor, form.nombre, form.capacidad)
         .await
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|e| status_de(&e, StatusCode::BAD_REQUEST))?;
 
     Ok(Redirect::to("/salas"))
 }
 
 pub async fn activar_empleado(
+    sesion: Sesion,
     Extension(service): Extension<Arc<dyn EmpleadoService>>,
     Path(id): Path<String>,
 ) -> Result<Redirect, StatusCode> {
     service
-        .activar_empleado(&id)
+        .activar_empleado(&sesion.actor, &id)
         .await
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|e| status_de(&e, StatusCode::BAD_REQUEST))?;
 
     Ok(Redirect::to("/empleados"))
 }
 
 pub async fn desactivar_empleado(
+    sesion: Sesion,
     Extension(service): Extension<Arc<dyn EmpleadoService>>,
     Path(id): Path<String>,
 ) -> Result<Redirect, StatusCode> {
     service
-        .desactivar_empleado(&id)
+        .desactivar_empleado(&sesion.actor, &id)
         .await
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|e| status_de(&e, StatusCode::BAD_REQUEST))?;
 
     Ok(Redirect::to("/empleados"))
 }
 
+pub async fn editar_empleado_form(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn EmpleadoService>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let empleado = service
+        .obtener_empleado(&id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(EmpleadoEditarTemplate {
+        sesion: sesion.vista(),
+        empleado: vista_empleado(empleado),
+        errores: ErroresFormulario::default(),
+        error: None,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct EditarEmpleadoForm {
+    nombre: String,
+    email: String,
+}
+
+pub async fn editar_empleado_submit(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn EmpleadoService>>,
+    Path(id): Path<String>,
+    Form(form): Form<EditarEmpleadoForm>,
+) -> Result<Response, StatusCode> {
+    let cambios = CambiosEmpleado {
+        nombre: Some(form.nombre.clone()),
+        email: Some(form.email.clone()),
+        responsable_id: None,
+    };
+    match service
+        .actualizar_empleado(&sesion.actor, &id, cambios)
+        .await
+    {
+        Ok(Some(_)) => Ok(Redirect::to("/empleados").into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        // Se vuelve a mostrar el formulario con lo que escribió el usuario
+        Err(e) => {
+            let actual = service
+                .obtener_empleado(&id)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .ok_or(StatusCode::NOT_FOUND)?;
+            let empleado = EmpleadoView {
+                nombre: form.nombre,
+                email: form.email,
+                ..vista_empleado(actual)
+            };
+            let (errores, error) = errores_formulario(e);
+            let pagina = EmpleadoEditarTemplate {
+                sesion: sesion.vista(),
+                empleado,
+                errores,
+                error,
+            };
+            Ok((StatusCode::BAD_REQUEST, pagina).into_response())
+        }
+    }
+}
+
+pub async fn eliminar_empleado(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn EmpleadoService>>,
+    Path(id): Path<String>,
+) -> Result<Response, StatusCode> {
+    match service.eliminar_empleado(&sesion.actor, &id).await {
+        Ok(true) => Ok(Redirect::to("/empleados").into_response()),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            let empleado = service
+                .obtener_empleado(&id)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .ok_or(StatusCode::NOT_FOUND)?;
+            let pagina = EmpleadoEditarTemplate {
+                sesion: sesion.vista(),
+                empleado: vista_empleado(empleado),
+                errores: ErroresFormulario::default(),
+                error: Some(e.to_string()),
+            };
+            Ok((StatusCode::CONFLICT, pagina).into_response())
+        }
+    }
+}
+
 pub async fn listar_reservas_page(
+    sesion: Sesion,
     Extension(service): Extension<Arc<dyn ReservaService>>,
+    Query(filtros): Query<FiltrosReservas>,
+    Query(pagina): Query<CursorQuery>,
 ) -> Result<impl IntoResponse, StatusCode> {
-    let reservas = service
-        .listar_reservas()
+    let fecha = |valor: &str| NaiveDate::parse_from_str(valor, "%Y-%m-%d").ok();
+    let estado = match filtros.estado.as_str() {
+        "pendiente" => Some(EstadoReserva::Pendiente),
+        "confirmada" => Some(EstadoReserva::Confirmada),
+        "cancelada" => Some(EstadoReserva::Cancelada),
+        _ => None,
+    };
+
+    let consulta = ConsultaReservas {
+        desde: fecha(&filtros.desde).map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc()),
+        // `hasta` incluye el día completo
+        hasta: fecha(&filtros.hasta)
+            .map(|d| (d + Days::new(1)).and_hms_opt(0, 0, 0).unwrap().and_utc()),
+        estado,
+        empleado_id: no_vacio(&filtros.empleado_id),
+        texto: no_vacio(&filtros.q),
+        cursor: pagina.cursor,
+        ..Default::default()
+    };
+    let pagina = service
+        .buscar_reservas(consulta)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
 
-    let reservas_view: Vec<ReservaView> = reservas
-        .into_iter()
-        .map(|r| {
-            let estado_str = match r.estado {
-                EstadoReserva::Pendiente => "pendiente",
-                EstadoReserva::Confirmada => "confirmada",
-                EstadoReserva::Cancelada => "cancelada",
-            };
-            ReservaView {
-                id: r.id,
-                empleado_id: r.empleado_id,
-                slot_inicio: r.slot.inicio,
-                slot_fin: r.slot.fin(),
-                descripcion: r.descripcion,
-                estado: estado_str.to_string(),
-            }
-        })
-        .collect();
+    let reservas_view: Vec<ReservaView> = pagina.elementos.into_iter().map(vista_reserva).collect();
 
     Ok(ReservasTemplate {
+        sesion: sesion.vista(),
         reservas: reservas_view,
+        siguiente: enlace_siguiente("/reservas", &filtros, pagina.siguiente_cursor),
+        filtros,
     })
 }
 
 pub async fn confirmar_reserva(
+    sesion: Sesion,
     Extension(service): Extension<Arc<dyn ReservaService>>,
     Path(id): Path<String>,
 ) -> Result<Redirect, StatusCode> {
     service
-        .confirmar_reserva(&id)
+        .confirmar_reserva(&sesion.actor, &id)
         .await
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|e| status_de(&e, StatusCode::BAD_REQUEST))?;
 
     Ok(Redirect::to("/reservas"))
 }
 
 pub async fn cancelar_reserva(
+    sesion: Sesion,
     Extension(service): Extension<Arc<dyn ReservaService>>,
     Path(id): Path<String>,
 ) -> Result<Redirect, StatusCode> {
     service
-        .cancelar_reserva(&id)
+        .cancelar_reserva(&sesion.actor, &id)
         .await
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|e| status_de(&e, StatusCode::BAD_REQUEST))?;
 
     Ok(Redirect::to("/reservas"))
 }
 
-pub async fn disponibilidad_page() -> impl IntoResponse {
-    DisponibilidadTemplate
+pub async fn editar_reserva_form(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn ReservaService>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let reserva = service
+        .obtener_reserva(&id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(ReservaEditarTemplate {
+        sesion: sesion.vista(),
+        reserva: vista_reserva(reserva),
+        error: None,
+    })
 }
 
-pub async fn listar_salas_page(
-    Extension(service): Extension<Arc<dyn SalaService>>,
+#[derive(Deserialize)]
+pub struct EditarReservaForm {
+    descripcion: String,
+}
+
+pub async fn editar_reserva_submit(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn ReservaService>>,
+    Path(id): Path<String>,
+    Form(form): Form<EditarReservaForm>,
+) -> Result<Response, StatusCode> {
+    let cambios = CambiosReserva {
+        descripcion: Some(form.descripcion.clone()),
+        ..Default::default()
+    };
+    match service
+        .actualizar_reserva(&sesion.actor, &id, cambios)
+        .await
+    {
+        Ok(Some(_)) => Ok(Redirect::to("/reservas").into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            let actual = service
+                .obtener_reserva(&id)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .ok_or(StatusCode::NOT_FOUND)?;
+            let reserva = ReservaView {
+                descripcion: form.descripcion,
+                ..vista_reserva(actual)
+            };
+            let pagina = ReservaEditarTemplate {
+                sesion: sesion.vista(),
+                reserva,
+                error: Some(e.to_string()),
+            };
+            Ok((StatusCode::BAD_REQUEST, pagina).into_response())
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ReprogramarReservaForm {
+    fecha: String,
+    hora: u32,
+}
+
+pub async fn reprogramar_reserva(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn ReservaService>>,
+    Path(id): Path<String>,
+    Form(form): Form<ReprogramarReservaForm>,
+) -> Result<Response, StatusCode> {
+    let slot = NaiveDate::parse_from_str(&form.fecha, "%Y-%m-%d")
+        .ok()
+        .and_then(|fecha| {
+            Slot::from_date_and_hour(fecha.year(), fecha.month(), fecha.day(), form.hora)
+        })
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    match service.reprogramar_reserva(&sesion.actor, &id, slot).await {
+        Ok(Some(_)) => Ok(Redirect::to("/reservas").into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            let reserva = service
+                .obtener_reserva(&id)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .ok_or(StatusCode::NOT_FOUND)?;
+            let pagina = ReservaEditarTemplate {
+                sesion: sesion.vista(),
+                reserva: vista_reserva(reserva),
+                error: Some(e.to_string()),
+            };
+            Ok((StatusCode::CONFLICT, pagina).into_response())
+        }
+    }
+}
+
+pub async fn eliminar_reserva(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn ReservaService>>,
+    Path(id): Path<String>,
+) -> Result<Redirect, StatusCode> {
+    match service.eliminar_reserva(&sesion.actor, &id).await {
+        Ok(true) => Ok(Redirect::to("/reservas")),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => Err(status_de(&e, StatusCode::INTERNAL_SERVER_ERROR)),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FechaQuery {
+    fecha: Option<String>,
+}
+
+impl FechaQuery {
+    /// Día pedido; hoy si no se indica
+    fn fecha(&self) -> Result<NaiveDate, StatusCode> {
+        match self.fecha.as_deref().filter(|f| !f.is_empty()) {
+            Some(fecha) => {
+                NaiveDate::parse_from_str(fecha, "%Y-%m-%d").map_err(|_| StatusCode::BAD_REQUEST)
+            }
+            None => Ok(Utc::now().date_naive()),
+        }
+    }
+}
+
+fn vista_celda(d: DisponibilidadSlot) -> CeldaDisponibilidadView {
+    CeldaDisponibilidadView {
+        empleado_id: d.empleado_id,
+        inicio: d.slot.inicio.format("%Y-%m-%dT%H:%M").to_string(),
+        disponible: d.disponible,
+        descripcion: d.descripcion_reserva,
+    }
+}
+
+pub async fn disponibilidad_page(
+    sesion: Sesion,
+    Extension(empleado_service): Extension<Arc<dyn EmpleadoService>>,
+    Extension(reserva_service): Extension<Arc<dyn ReservaService>>,
+    Query(query): Query<FechaQuery>,
 ) -> Result<impl IntoResponse, StatusCode> {
-    let salas = service
-        .listar_salas()
+    let fecha = query.fecha()?;
+    let empleados = empleado_service
+        .listar_empleados()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let reservas = reserva_service
+        .listar_reservas()
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let slots: Vec<Slot> = (9..18)
+        .filter_map(|h| Slot::from_date_and_hour(fecha.year(), fecha.month(), fecha.day(), h))
+        .collect();
+    let tabla = DisponibilidadService::generar_tabla_disponibilidad(&empleados, &slots, &reservas);
 
-    let salas_view: Vec<SalaView> = salas
-        .into_iter()
-        .map(|s| SalaView {
-            id: s.id,
-            nombre: s.nombre,
-            capacidad: s.capacidad,
-            activa: s.activa,
+    // La tabla viene ordenada por empleado y, dentro de cada uno, por slot
+    let filas = tabla
+        .empleados
+        .iter()
+        .zip(tabla.disponibilidad.chunks(slots.len().max(1)))
+        .map(|(empleado, celdas)| FilaDisponibilidadView {
+            empleado_nombre: empleado.nombre.clone(),
+            celdas: celdas.iter().cloned().map(vista_celda).collect(),
         })
         .collect();
 
-    Ok(SalasTemplate { salas: salas_view })
+    Ok(DisponibilidadTemplate {
+        sesion: sesion.vista(),
+        fecha: fecha.format("%Y-%m-%d").to_string(),
+        horas: slots
+            .iter()
+            .map(|s| s.inicio.format("%H:%M").to_string())
+            .collect(),
+        filas,
+    })
+}
+
+/// Cambios de disponibilidad del día para la página, por Server-Sent Events.
+/// Cada evento `disponibilidad` lleva las celdas que cambian.
+pub async fn eventos_disponibilidad(
+    _sesion: Sesion,
+    Extension(en_vivo): Extension<Arc<dyn DisponibilidadEnVivoService>>,
+    Query(query): Query<FechaQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let filtro = FiltroDisponibilidad {
+        fecha: Some(query.fecha()?),
+        ..Default::default()
+    };
+    let eventos = stream::unfold(en_vivo.suscribir(filtro), |mut suscripcion| async move {
+        let evento = match suscripcion.siguiente().await? {
+            EventoDisponibilidad::Actualizacion(actualizacion) => {
+                let celdas: Vec<CeldaDisponibilidadView> =
+                    actualizacion.slots.into_iter().map(vista_celda).collect();
+                Event::default()
+                    .event("disponibilidad")
+                    .json_data(serde_json::json!({ "slots": celdas }))
+                    .expect("las celdas siempre se pueden serializar")
+            }
+            EventoDisponibilidad::Resincronizar => {
+                Event::default().event("resincronizar").data("{}")
+            }
+        };
+        Some((Ok::<_, std::convert::Infallible>(evento), suscripcion))
+    });
+    Ok(Sse::new(eventos).keep_alive(KeepAlive::default()))
+}
+
+pub async fn listar_salas_page(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    Query(filtro): Query<FiltroTexto>,
+    Query(pagina): Query<CursorQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let consulta = ConsultaSalas {
+        texto: no_vacio(&filtro.q),
+        cursor: pagina.cursor,
+        ..Default::default()
+    };
+    let pagina = service
+        .buscar_salas(consulta)
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let salas_view: Vec<SalaView> = pagina.elementos.into_iter().map(vista_sala).collect();
+
+    Ok(SalasTemplate {
+        sesion: sesion.vista(),
+        salas: salas_view,
+        siguiente: enlace_siguiente("/salas", &filtro, pagina.siguiente_cursor),
+        filtro,
+    })
 }
 
 pub async fn activar_sala(
+    sesion: Sesion,
     Extension(service): Extension<Arc<dyn SalaService>>,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, StatusCode> {
     service
-        .activar_sala(&id)
+        .activar_sala(&sesion.actor, &id)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|e| status_de(&e, StatusCode::INTERNAL_SERVER_ERROR))?;
     Ok(Redirect::to("/salas"))
 }
 
 pub async fn desactivar_sala(
+    sesion: Sesion,
     Extension(service): Extension<Arc<dyn SalaService>>,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, StatusCode> {
     service
-        .desactivar_sala(&id)
+        .desactivar_sala(&sesion.actor, &id)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|e| status_de(&e, StatusCode::INTERNAL_SERVER_ERROR))?;
     Ok(Redirect::to("/salas"))
 }
+
+#[derive(Deserialize)]
+pub struct BusquedaSalasQuery {
+    fecha: Option<String>,
+    #[serde(default)]
+    desde: String,
+    #[serde(default)]
+    hasta: String,
+    #[serde(default)]
+    capacidad: String,
+}
+
+fn leer_numero(campo: &str, valor: &str) -> Result<u32, String> {
+    valor
+        .parse()
+        .map_err(|_| format!("{} no válida: {}", campo, valor))
+}
+
+impl BusquedaSalasQuery {
+    /// Búsqueda del formulario; `None` si no se ha pedido ninguna franja.
+    /// Sin hora de fin se busca una hora; sin capacidad, una persona.
+    fn busqueda(&self, fecha: NaiveDate) -> Result<Option<BusquedaSalas>, String> {
+        let Some(desde) = no_vacio(&self.desde) else {
+            return Ok(None);
+        };
+        let hora_inicio = leer_numero("Hora de inicio", &desde)?;
+        let hora_fin = match no_vacio(&self.hasta) {
+            Some(hasta) => leer_numero("Hora de fin", &hasta)?,
+            None => hora_inicio + 1,
+        };
+        let capacidad_minima = match no_vacio(&self.capacidad) {
+            Some(capacidad) => leer_numero("Capacidad", &capacidad)?,
+            None => 1,
+        };
+        Ok(Some(BusquedaSalas {
+            fecha,
+            hora_inicio,
+            hora_fin,
+            capacidad_minima,
+        }))
+    }
+}
+
+/// Ocupación de las salas en un día y, si se indica una franja, las salas
+/// libres en ella para el número de personas pedido
+pub async fn disponibilidad_salas_page(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    Query(query): Query<BusquedaSalasQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let fecha = FechaQuery {
+        fecha: query.fecha.clone(),
+    }
+    .fecha()?;
+    let matriz = service
+        .disponibilidad_salas(Periodo::dia(fecha))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let filas = matriz
+        .filas
+        .into_iter()
+        .map(|fila| FilaOcupacionSalaView {
+            sala_nombre: fila.nombre,
+            celdas: fila
+                .celdas
+                .into_iter()
+                .next()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|estado| CeldaOcupacionSalaView {
+                    libre: estado.is_none(),
+                    estado: match estado {
+                        None => "Libre",
+                        Some(EstadoReserva::Confirmada) => "Confirmada",
+                        Some(_) => "Pendiente",
+                    },
+                })
+                .collect(),
+        })
+        .collect();
+
+    let (libres, error) = match query.busqueda(fecha) {
+        Ok(None) => (None, None),
+        Err(e) => (None, Some(e)),
+        Ok(Some(busqueda)) => match service.buscar_salas_libres(busqueda).await {
+            Ok(salas) => (Some(salas.into_iter().map(vista_sala).collect()), None),
+            Err(e) => (None, Some(e)),
+        },
+    };
+
+    let pagina = SalasDisponibilidadTemplate {
+        sesion: sesion.vista(),
+        fecha: fecha.format("%Y-%m-%d").to_string(),
+        horas: matriz
+            .horas
+            .iter()
+            .map(|h| format!("{:02}:00", h))
+            .collect(),
+        filas,
+        desde: query.desde,
+        hasta: query.hasta,
+        capacidad: query.capacidad,
+        libres,
+        error: error.clone(),
+    };
+    let status = if error.is_some() {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, pagina).into_response())
+}
+
+pub async fn editar_sala_form(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let sala = service
+        .obtener_sala(&id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(SalaEditarTemplate {
+        sesion: sesion.vista(),
+        sala: vista_sala(sala),
+        error: None,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct EditarSalaForm {
+    nombre: String,
+    capacidad: u32,
+}
+
+pub async fn editar_sala_submit(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    Path(id): Path<String>,
+    Form(form): Form<EditarSalaForm>,
+) -> Result<Response, StatusCode> {
+    let cambios = CambiosSala {
+        nombre: Some(form.nombre.clone()),
+        capacidad: Some(form.capacidad),
+    };
+    match service.actualizar_sala(&sesion.actor, &id, cambios).await {
+        Ok(Some(_)) => Ok(Redirect::to("/salas").into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            let actual = service
+                .obtener_sala(&id)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .ok_or(StatusCode::NOT_FOUND)?;
+            let sala = SalaView {
+                nombre: form.nombre,
+                capacidad: form.capacidad,
+                ..vista_sala(actual)
+            };
+            let pagina = SalaEditarTemplate {
+                sesion: sesion.vista(),
+                sala,
+                error: Some(e.to_string()),
+            };
+            Ok((StatusCode::BAD_REQUEST, pagina).into_response())
+        }
+    }
+}
+
+pub async fn eliminar_sala(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn SalaService>>,
+    Path(id): Path<String>,
+) -> Result<Response, StatusCode> {
+    match service.eliminar_sala(&sesion.actor, &id).await {
+        Ok(true) => Ok(Redirect::to("/salas").into_response()),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            let sala = service
+                .obtener_sala(&id)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .ok_or(StatusCode::NOT_FOUND)?;
+            let pagina = SalaEditarTemplate {
+                sesion: sesion.vista(),
+                sala: vista_sala(sala),
+                error: Some(e.to_string()),
+            };
+            Ok((StatusCode::CONFLICT, pagina).into_response())
+        }
+    }
+}
+
+// ============= Cargas masivas =============
+
+/// Entidad de la ruta; las reservas solo se exportan
+fn entidad_de(nombre: &str, importar: bool) -> Result<(Entidad, &'static str), StatusCode> {
+    match nombre {
+        "empleados" => Ok((Entidad::Empleados, "empleados")),
+        "salas" => Ok((Entidad::Salas, "salas")),
+        "reservas" if !importar => Ok((Entidad::Reservas, "reservas")),
+        _ => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+fn vista_informe(informe: InformeCarga, simulado: bool) -> InformeCargaView {
+    InformeCargaView {
+        creadas: informe.contar(ResultadoFila::Creada),
+        actualizadas: informe.contar(ResultadoFila::Actualizada),
+        sin_cambios: informe.contar(ResultadoFila::SinCambios),
+        errores: informe.contar(ResultadoFila::Error),
+        simulado,
+        filas: informe
+            .filas
+            .into_iter()
+            .map(|f| {
+                let (resultado, clase) = match f.resultado {
+                    ResultadoFila::Creada => ("creada", "badge-success"),
+                    ResultadoFila::Actualizada => ("actualizada", "badge-success"),
+                    ResultadoFila::SinCambios => ("sin cambios", "badge-warning"),
+                    ResultadoFila::Error => ("error", "badge-danger"),
+                };
+                FilaCargaView {
+                    fila: f.fila,
+                    clave: f.clave,
+                    resultado,
+                    clase,
+                    errores: f
+                        .errores
+                        .into_iter()
+                        .map(|e| format!("{}: {}", e.campo, e.mensaje))
+                        .collect(),
+                }
+            })
+            .collect(),
+    }
+}
+
+fn campos_carga(entidad: Entidad, mapeo: &Mapeo) -> Vec<CampoCargaView> {
+    entidad
+        .campos()
+        .iter()
+        .map(|campo| CampoCargaView {
+            nombre: campo,
+            obligatorio: entidad.obligatorios().contains(campo),
+            columna: mapeo.get(*campo).cloned().unwrap_or_default(),
+        })
+        .collect()
+}
+
+pub async fn importar_form(
+    sesion: Sesion,
+    Path(nombre): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (entidad, nombre) = entidad_de(&nombre, true)?;
+    Ok(CargaMasivaTemplate {
+        sesion: sesion.vista(),
+        entidad: nombre,
+        campos: campos_carga(entidad, &Mapeo::new()),
+        simular: true,
+        informe: None,
+        error: None,
+    })
+}
+
+/// Campos del formulario de carga: el fichero, `simular` y una
+/// `columna_<campo>` por campo
+struct FormularioCarga {
+    fichero: Vec<u8>,
+    nombre_fichero: Option<String>,
+    mapeo: Mapeo,
+    simular: bool,
+}
+
+async fn leer_formulario_carga(mut multipart: Multipart) -> Result<FormularioCarga, String> {
+    let mut formulario = FormularioCarga {
+        fichero: Vec::new(),
+        nombre_fichero: None,
+        mapeo: Mapeo::new(),
+        simular: false,
+    };
+    let error =
+        |e: axum::extract::multipart::MultipartError| format!("Formulario no válido: {}", e);
+    while let Some(campo) = multipart.next_field().await.map_err(error)? {
+        let nombre = campo.name().unwrap_or_default().to_string();
+        if nombre == "fichero" {
+            formulario.nombre_fichero = campo.file_name().map(str::to_string);
+            formulario.fichero = campo.bytes().await.map_err(error)?.to_vec();
+        } else if nombre == "simular" {
+            formulario.simular = true;
+        } else if let Some(campo_carga) = nombre.strip_prefix("columna_") {
+            let columna = campo.text().await.map_err(error)?;
+            if let Some(columna) = no_vacio(&columna) {
+                formulario.mapeo.insert(campo_carga.to_string(), columna);
+            }
+        }
+    }
+    Ok(formulario)
+}
+
+pub async fn importar_submit(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn CargaMasivaService>>,
+    Path(nombre): Path<String>,
+    multipart: Multipart,
+) -> Result<Response, StatusCode> {
+    let (entidad, nombre) = entidad_de(&nombre, true)?;
+    let formulario = match leer_formulario_carga(multipart).await {
+        Ok(formulario) => formulario,
+        Err(e) => {
+            let pagina = CargaMasivaTemplate {
+                sesion: sesion.vista(),
+                entidad: nombre,
+                campos: campos_carga(entidad, &Mapeo::new()),
+                simular: true,
+                informe: None,
+                error: Some(e),
+            };
+            return Ok((StatusCode::BAD_REQUEST, pagina).into_response());
+        }
+    };
+
+    let formato = formulario
+        .nombre_fichero
+        .as_deref()
+        .and_then(Formato::desde_nombre)
+        .unwrap_or(Formato::Csv);
+    let resultado = match hojas_calculo::leer(&formulario.fichero, formato) {
+        Ok(tabla) => service
+            .importar(
+                &sesion.actor,
+                entidad,
+                tabla,
+                &formulario.mapeo,
+                formulario.simular,
+            )
+            .await
+            .map_err(|e| (status_de(&e, StatusCode::BAD_REQUEST), e.to_string())),
+        Err(e) => Err((StatusCode::BAD_REQUEST, e)),
+    };
+
+    let mut pagina = CargaMasivaTemplate {
+        sesion: sesion.vista(),
+        entidad: nombre,
+        campos: campos_carga(entidad, &formulario.mapeo),
+        simular: formulario.simular,
+        informe: None,
+        error: None,
+    };
+    match resultado {
+        Ok(informe) => {
+            pagina.informe = Some(vista_informe(informe, formulario.simular));
+            Ok(pagina.into_response())
+        }
+        Err((status, error)) => {
+            pagina.error = Some(error);
+            Ok((status, pagina).into_response())
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ExportarQuery {
+    formato: Option<String>,
+}
+
+pub async fn exportar(
+    Extension(service): Extension<Arc<dyn CargaMasivaService>>,
+    Path(nombre): Path<String>,
+    Query(query): Query<ExportarQuery>,
+) -> Result<Response, StatusCode> {
+    let (entidad, nombre) = entidad_de(&nombre, false)?;
+    let formato: Formato = query
+        .formato
+        .as_deref()
+        .unwrap_or("csv")
+        .parse()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let tabla = service
+        .exportar(entidad)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let contenido =
+        hojas_calculo::escribir(&tabla, formato).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let fichero = format!(
+        "attachment; filename=\"{}-{}.{}\"",
+        nombre,
+        Utc::now().format("%Y%m%d-%H%M%S"),
+        formato.extension()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, formato.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, fichero),
+        ],
+        contenido,
+    )
+        .into_response())
+}
+
+// ============= Informes =============
+
+#[derive(Deserialize)]
+pub struct InformeQuery {
+    desde: Option<String>,
+    hasta: Option<String>,
+    desglose: Option<String>,
+    formato: Option<String>,
+}
+
+impl InformeQuery {
+    /// Sin fechas, el mes en curso; sin `hasta`, solo el día `desde`
+    fn periodo(&self) -> Result<Periodo, String> {
+        let fecha = |valor: &str| {
+            NaiveDate::parse_from_str(valor, "%Y-%m-%d")
+                .map_err(|_| format!("Fecha inválida: {}", valor))
+        };
+        match (
+            self.desde.as_deref().and_then(no_vacio),
+            self.hasta.as_deref().and_then(no_vacio),
+        ) {
+            (None, None) => Ok(Periodo::mes(Utc::now().date_naive())),
+            (Some(desde), hasta) => {
+                Periodo::para_informe(fecha(&desde)?, fecha(hasta.as_deref().unwrap_or(&desde))?)
+            }
+            (None, Some(_)) => Err("Indique también la fecha de inicio".to_string()),
+        }
+    }
+}
+
+fn porcentaje(fraccion: f64) -> String {
+    format!("{:.1}", fraccion * 100.0)
+}
+
+fn vista_uso(uso: UsoRecurso) -> UsoView {
+    UsoView {
+        etiqueta: uso.nombre,
+        reservas: uso.reservas,
+        canceladas: Some(uso.canceladas),
+        porcentaje: porcentaje(uso.ocupacion),
+    }
+}
+
+fn vista_ocupacion(informe: InformeOcupacion) -> InformeOcupacionView {
+    let dias_mas_ocupados = informe
+        .dias_mas_ocupados(DIAS_MAS_OCUPADOS)
+        .into_iter()
+        .map(|dia| UsoView {
+            etiqueta: dia.fecha.format("%d/%m/%Y").to_string(),
+            reservas: dia.reservas,
+            canceladas: None,
+            porcentaje: porcentaje(dia.ocupacion),
+        })
+        .collect();
+    InformeOcupacionView {
+        total: informe.total,
+        pendientes: informe.pendientes,
+        confirmadas: informe.confirmadas,
+        canceladas: informe.canceladas,
+        no_presentadas: informe.no_presentadas,
+        tasa_cancelacion: porcentaje(informe.tasa_cancelacion),
+        tasa_no_presentacion: porcentaje(informe.tasa_no_presentacion),
+        por_empleado: informe.por_empleado.into_iter().map(vista_uso).collect(),
+        por_sala: informe.por_sala.into_iter().map(vista_uso).collect(),
+        por_hora: informe
+            .por_hora
+            .into_iter()
+            .map(|hora| UsoView {
+                etiqueta: format!("{:02}:00", hora.hora),
+                reservas: hora.reservas,
+                canceladas: None,
+                porcentaje: porcentaje(hora.ocupacion),
+            })
+            .collect(),
+        dias_mas_ocupados,
+    }
+}
+
+/// Panel de ocupación de un periodo (por defecto, el mes en curso)
+pub async fn informes_page(
+    sesion: Sesion,
+    Extension(service): Extension<Arc<dyn InformeService>>,
+    Query(query): Query<InformeQuery>,
+) -> Result<Response, StatusCode> {
+    let mut pagina = InformesTemplate {
+        sesion: sesion.vista(),
+        desde: query.desde.clone().unwrap_or_default(),
+        hasta: query.hasta.clone().unwrap_or_default(),
+        informe: None,
+        error: None,
+    };
+    let periodo = match query.periodo() {
+        Ok(periodo) => periodo,
+        Err(e) => {
+            pagina.error = Some(e);
+            return Ok((StatusCode::BAD_REQUEST, pagina).into_response());
+        }
+    };
+    let informe = service
+        .informe_ocupacion(periodo)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    pagina.desde = periodo.desde.format("%Y-%m-%d").to_string();
+    pagina.hasta = periodo.hasta.format("%Y-%m-%d").to_string();
+    pagina.informe = Some(vista_ocupacion(informe));
+    Ok(pagina.into_response())
+}
+
+/// Descarga una parte del informe en CSV (por defecto) o XLSX
+pub async fn exportar_informe(
+    Extension(service): Extension<Arc<dyn InformeService>>,
+    Query(query): Query<InformeQuery>,
+) -> Result<Response, StatusCode> {
+    let periodo = query.periodo().map_err(|_| StatusCode::BAD_REQUEST)?;
+    let desglose: Desglose = query
+        .desglose
+        .as_deref()
+        .unwrap_or("resumen")
+        .parse()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let formato: Formato = query
+        .formato
+        .as_deref()
+        .unwrap_or("csv")
+        .parse()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let tabla = service
+        .exportar_informe(periodo, desglose)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let contenido =
+        hojas_calculo::escribir(&tabla, formato).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let fichero = format!(
+        "attachment; filename=\"ocupacion-{:?}-{}-{}.{}\"",
+        desglose,
+        periodo.desde.format("%Y%m%d"),
+        periodo.hasta.format("%Y%m%d"),
+        formato.extension()
+    )
+    .to_lowercase();
+    Ok((
+        [
+            (header::CONTENT_TYPE, formato.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, fichero),
+        ],
+        contenido,
+    )
+        .into_response())
+}
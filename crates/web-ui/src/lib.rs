@@ -3,6 +3,8 @@
 
 pub mod handlers;
 pub mod routes;
+pub mod sesion;
 pub mod templates;
 
 pub use routes::crear_router_web;
+pub use sesion::AlmacenSesiones;
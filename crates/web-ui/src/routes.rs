@@ -1,23 +1,72 @@
 // Configuración de rutas para la interfaz web
 
 use crate::handlers;
+use crate::sesion::{self, AlmacenSesiones, LIMITE_FICHERO};
 use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{get, post},
     Router,
 };
+use reservas_ports::r#in::carga_masiva_service::CargaMasivaService;
+use reservas_ports::r#in::cuenta_service::CuentaService;
+use reservas_ports::r#in::disponibilidad_en_vivo_service::DisponibilidadEnVivoService;
 use reservas_ports::r#in::empleado_service::EmpleadoService;
+use reservas_ports::r#in::informe_service::InformeService;
 use reservas_ports::r#in::reserva_service::ReservaService;
 use reservas_ports::r#in::sala_service::SalaService;
+use reservas_ports::r#in::sso_service::SsoService;
 use std::sync::Arc;
 use tower_http::services::ServeDir;
 
-/// Crea el router con todas las rutas de la interfaz web
+/// Crea el router con todas las rutas de la interfaz web.
+/// Solo el login y los estáticos son públicos; el resto exige sesión.
+/// Con `sso` se puede iniciar sesión también con el proveedor OIDC.
+/// La página de disponibilidad se actualiza con los cambios de `en_vivo`.
+#[allow(clippy::too_many_arguments)]
 pub fn crear_router_web(
     empleado_service: Arc<dyn EmpleadoService>,
     reserva_service: Arc<dyn ReservaService>,
     sala_service: Arc<dyn SalaService>,
+    cuenta_service: Arc<dyn CuentaService>,
+    carga_masiva_service: Arc<dyn CargaMasivaService>,
+    informe_service: Arc<dyn InformeService>,
+    en_vivo: Arc<dyn DisponibilidadEnVivoService>,
+    sesiones: Arc<AlmacenSesiones>,
+    sso: Option<Arc<dyn SsoService>>,
 ) -> Router {
     Router::new()
+        .route(
+            "/login",
+            get(handlers::login_form).post(handlers::login_submit),
+        )
+        .route("/login/oidc", get(handlers::login_oidc))
+        .route("/login/oidc/callback", get(handlers::login_oidc_callback))
+        .merge(rutas_protegidas(Arc::clone(&sesiones)))
+        // Archivos estáticos (CSS, imágenes, etc.)
+        .nest_service("/static", ServeDir::new("crates/web-ui/static"))
+        // Inyectar servicios
+        .layer(axum::Extension(empleado_service))
+        .layer(axum::Extension(reserva_service))
+        .layer(axum::Extension(sala_service))
+        .layer(axum::Extension(cuenta_service))
+        .layer(axum::Extension(carga_masiva_service))
+        .layer(axum::Extension(informe_service))
+        .layer(axum::Extension(en_vivo))
+        .layer(axum::Extension(sesiones))
+        .layer(axum::Extension(sso))
+}
+
+/// Páginas que exigen sesión; sus POST además exigen el token CSRF
+fn rutas_protegidas(sesiones: Arc<AlmacenSesiones>) -> Router {
+    Router::new()
+        .route("/logout", post(handlers::logout))
+        .route(
+            "/cuenta/password",
+            get(handlers::password_form).post(handlers::password_submit),
+        )
+        .route("/cuentas/nueva", get(handlers::nueva_cuenta_form))
+        .route("/cuentas/crear", post(handlers::crear_cuenta_submit))
         // Página principal
         .route("/", get(handlers::index))
         // Rutas de empleados
@@ -29,22 +78,58 @@ pub fn crear_router_web(
             "/empleados/:id/desactivar",
             post(handlers::desactivar_empleado),
         )
+        .route(
+            "/empleados/:id/editar",
+            get(handlers::editar_empleado_form).post(handlers::editar_empleado_submit),
+        )
+        .route("/empleados/:id/eliminar", post(handlers::eliminar_empleado))
         // Rutas de reservas
         .route("/reservas", get(handlers::listar_reservas_page))
         .route("/reservas/:id/confirmar", post(handlers::confirmar_reserva))
         .route("/reservas/:id/cancelar", post(handlers::cancelar_reserva))
+        .route(
+            "/reservas/:id/editar",
+            get(handlers::editar_reserva_form).post(handlers::editar_reserva_submit),
+        )
+        .route(
+            "/reservas/:id/reprogramar",
+            post(handlers::reprogramar_reserva),
+        )
+        .route("/reservas/:id/eliminar", post(handlers::eliminar_reserva))
         // Disponibilidad
         .route("/disponibilidad", get(handlers::disponibilidad_page))
+        .route(
+            "/disponibilidad/eventos",
+            get(handlers::eventos_disponibilidad),
+        )
         // Salas
         .route("/salas", get(handlers::listar_salas_page))
+        .route(
+            "/salas/disponibilidad",
+            get(handlers::disponibilidad_salas_page),
+        )
         .route("/salas/nuevo", get(handlers::nuevo_sala_form))
         .route("/salas/crear", post(handlers::crear_sala_submit))
+        .route(
+            "/salas/:id/editar",
+            get(handlers::editar_sala_form).post(handlers::editar_sala_submit),
+        )
+        .route("/salas/:id/eliminar", post(handlers::eliminar_sala))
+        // Cargas masivas y exportaciones
+        .route(
+            "/importar/:entidad",
+            get(handlers::importar_form)
+                .post(handlers::importar_submit)
+                .layer(DefaultBodyLimit::max(LIMITE_FICHERO)),
+        )
+        .route("/exportar/:entidad", get(handlers::exportar))
+        // Informes
+        .route("/informes", get(handlers::informes_page))
+        .route("/informes/exportar", get(handlers::exportar_informe))
         // .route("/salas/:id/activar", post(handlers::activar_sala))
         // .route("/salas/:id/desactivar", post(handlers::desactivar_sala))
-        // Archivos estáticos (CSS, imágenes, etc.)
-        .nest_service("/static", ServeDir::new("crates/web-ui/static"))
-        // Inyectar servicios
-        .layer(axum::Extension(empleado_service))
-        .layer(axum::Extension(reserva_service))
-        .layer(axum::Extension(sala_service))
+        .route_layer(middleware::from_fn_with_state(
+            sesiones,
+            sesion::exigir_sesion,
+        ))
 }
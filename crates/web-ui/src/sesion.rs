@@ -0,0 +1,387 @@
+// Sesiones de la interfaz web y protección CSRF
+//
+// La cookie solo lleva un identificador aleatorio; la sesión (quién es el
+// usuario y su token CSRF) se guarda en el servidor. Todo POST de una sesión
+// debe incluir el token, en el campo `csrf` del formulario o en la cabecera
+// `X-CSRF-Token`.
+
+use axum::{
+    async_trait,
+    body::{to_bytes, Body, Bytes},
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Redirect, Response},
+};
+use futures_util::stream;
+use reservas_domain::Actor;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::templates::SesionView;
+
+/// Nombre de la cookie con el identificador de sesión
+pub const COOKIE_SESION: &str = "reservas_sesion";
+
+/// Cookie con el `state` del inicio de sesión OIDC en curso; liga la vuelta
+/// del proveedor al navegador que lo empezó
+pub const COOKIE_OIDC: &str = "reservas_oidc";
+
+/// Tiempo para completar el inicio de sesión en el proveedor
+const DURACION_INICIO_OIDC: u64 = 10 * 60;
+
+/// Cabecera alternativa al campo `csrf` (para peticiones desde JavaScript)
+pub const CABECERA_CSRF: &str = "x-csrf-token";
+
+/// Duración por defecto de una sesión sin actividad
+pub const DURACION_SESION: Duration = Duration::from_secs(8 * 60 * 60);
+
+/// Tamaño máximo de un formulario (para leer el token CSRF)
+const LIMITE_FORMULARIO: usize = 1024 * 1024;
+
+/// Tamaño máximo de un formulario con ficheros (`multipart/form-data`)
+pub const LIMITE_FICHERO: usize = 16 * 1024 * 1024;
+
+/// Usuario que ha iniciado sesión
+#[derive(Debug, Clone)]
+pub struct Sesion {
+    pub id: String,
+    pub actor: Actor,
+    /// Token que deben llevar los formularios de esta sesión
+    pub csrf: String,
+    expira: Instant,
+}
+
+impl Sesion {
+    /// Datos de la sesión que usan las plantillas
+    pub fn vista(&self) -> SesionView {
+        SesionView {
+            usuario: self.actor.sujeto.clone(),
+            csrf: self.csrf.clone(),
+        }
+    }
+}
+
+/// Los handlers piden la sesión como parámetro. Solo está disponible en las
+/// rutas protegidas por `exigir_sesion`.
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Sesion {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Sesion>()
+            .cloned()
+            .ok_or_else(|| Redirect::to("/login").into_response())
+    }
+}
+
+/// Sesiones activas, en memoria
+pub struct AlmacenSesiones {
+    sesiones: RwLock<HashMap<String, Sesion>>,
+    duracion: Duration,
+    /// Añade `Secure` a la cookie (solo se envía por HTTPS)
+    cookie_segura: bool,
+}
+
+impl Default for AlmacenSesiones {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Identificador aleatorio de 244 bits
+fn token_aleatorio() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+impl AlmacenSesiones {
+    pub fn new() -> Self {
+        Self {
+            sesiones: RwLock::new(HashMap::new()),
+            duracion: DURACION_SESION,
+            cookie_segura: false,
+        }
+    }
+
+    pub fn con_duracion(mut self, duracion: Duration) -> Self {
+        self.duracion = duracion;
+        self
+    }
+
+    pub fn con_cookie_segura(mut self, segura: bool) -> Self {
+        self.cookie_segura = segura;
+        self
+    }
+
+    /// Abre una sesión nueva (siempre con identificador nuevo)
+    pub fn crear(&self, actor: Actor) -> Sesion {
+        let ahora = Instant::now();
+        let sesion = Sesion {
+            id: token_aleatorio(),
+            actor,
+            csrf: token_aleatorio(),
+            expira: ahora + self.duracion,
+        };
+        let mut sesiones = self.sesiones.write().expect("sesiones envenenadas");
+        sesiones.retain(|_, s| s.expira > ahora);
+        sesiones.insert(sesion.id.clone(), sesion.clone());
+        sesion
+    }
+
+    /// Sesión vigente con ese identificador. Cada uso renueva la caducidad.
+    pub fn obtener(&self, id: &str) -> Option<Sesion> {
+        let mut sesiones = self.sesiones.write().expect("sesiones envenenadas");
+        let ahora = Instant::now();
+        match sesiones.get_mut(id) {
+            Some(sesion) if sesion.expira > ahora => {
+                sesion.expira = ahora + self.duracion;
+                Some(sesion.clone())
+            }
+            Some(_) => {
+                sesiones.remove(id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn eliminar(&self, id: &str) {
+        self.sesiones
+            .write()
+            .expect("sesiones envenenadas")
+            .remove(id);
+    }
+
+    /// Cabecera `Set-Cookie` que guarda la sesión en el navegador
+    pub fn cookie(&self, sesion: &Sesion) -> HeaderValue {
+        self.cabecera_cookie(COOKIE_SESION, &sesion.id, self.duracion.as_secs())
+    }
+
+    /// Cabecera `Set-Cookie` que borra la sesión del navegador
+    pub fn cookie_borrada(&self) -> HeaderValue {
+        self.cabecera_cookie(COOKIE_SESION, "", 0)
+    }
+
+    /// Cabecera `Set-Cookie` con el `state` del inicio de sesión OIDC
+    pub fn cookie_oidc(&self, estado: &str) -> HeaderValue {
+        self.cabecera_cookie(COOKIE_OIDC, estado, DURACION_INICIO_OIDC)
+    }
+
+    pub fn cookie_oidc_borrada(&self) -> HeaderValue {
+        self.cabecera_cookie(COOKIE_OIDC, "", 0)
+    }
+
+    fn cabecera_cookie(&self, nombre: &str, valor: &str, max_age: u64) -> HeaderValue {
+        let segura = if self.cookie_segura { "; Secure" } else { "" };
+        HeaderValue::from_str(&format!(
+            "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}{}",
+            nombre, valor, max_age, segura
+        ))
+        .expect("la cookie solo tiene caracteres ASCII")
+    }
+}
+
+fn valor_cookie(cabeceras: &HeaderMap, cookie: &str) -> Option<String> {
+    cabeceras
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|valor| valor.to_str().ok())
+        .flat_map(|valor| valor.split(';'))
+        .filter_map(|par| par.trim().split_once('='))
+        .find(|(nombre, _)| *nombre == cookie)
+        .map(|(_, valor)| valor.to_string())
+        .filter(|valor| !valor.is_empty())
+}
+
+/// Identificador de sesión de la cabecera `Cookie`
+pub fn id_de_cookie(cabeceras: &HeaderMap) -> Option<String> {
+    valor_cookie(cabeceras, COOKIE_SESION)
+}
+
+/// Comprueba que el `state` que devuelve el proveedor OIDC es el de la
+/// cookie de este navegador
+pub fn estado_oidc_valido(cabeceras: &HeaderMap, estado: &str) -> bool {
+    valor_cookie(cabeceras, COOKIE_OIDC)
+        .is_some_and(|cookie| iguales_tiempo_constante(cookie.as_bytes(), estado.as_bytes()))
+}
+
+fn iguales_tiempo_constante(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Token CSRF enviado en la petición: la cabecera o el campo `csrf` del
+/// formulario. Devuelve también el cuerpo, ya leído, para reconstruir la petición.
+async fn token_csrf(request: Request) -> Result<(Option<String>, Request), Response> {
+    let (parts, body) = request.into_parts();
+    if let Some(token) = parts.headers.get(CABECERA_CSRF) {
+        let token = token.to_str().ok().map(str::to_string);
+        return Ok((token, Request::from_parts(parts, body)));
+    }
+
+    let frontera = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|valor| valor.to_str().ok())
+        .and_then(|valor| multer::parse_boundary(valor).ok());
+    let limite = if frontera.is_some() {
+        LIMITE_FICHERO
+    } else {
+        LIMITE_FORMULARIO
+    };
+    let bytes = to_bytes(body, limite)
+        .await
+        .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE.into_response())?;
+    let token = match frontera {
+        Some(frontera) => token_multipart(bytes.clone(), frontera).await,
+        None => serde_urlencoded::from_bytes::<Vec<(String, String)>>(&bytes)
+            .ok()
+            .and_then(|campos| campos.into_iter().find(|(nombre, _)| nombre == "csrf"))
+            .map(|(_, valor)| valor),
+    };
+    Ok((token, Request::from_parts(parts, Body::from(bytes))))
+}
+
+/// Campo `csrf` de un formulario con ficheros (`multipart/form-data`)
+async fn token_multipart(bytes: Bytes, frontera: String) -> Option<String> {
+    let cuerpo = stream::once(async move { Ok::<_, Infallible>(bytes) });
+    let mut multipart = multer::Multipart::new(cuerpo, frontera);
+    while let Ok(Some(campo)) = multipart.next_field().await {
+        if campo.name() == Some("csrf") {
+            return campo.text().await.ok();
+        }
+    }
+    None
+}
+
+/// Middleware de las páginas protegidas: sin sesión se redirige a `/login`
+/// y los POST sin el token CSRF de la sesión se rechazan con 403.
+pub(crate) async fn exigir_sesion(
+    State(almacen): State<Arc<AlmacenSesiones>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(sesion) = id_de_cookie(request.headers()).and_then(|id| almacen.obtener(&id)) else {
+        return Redirect::to("/login").into_response();
+    };
+
+    let mut request = if request.method() == Method::POST {
+        let (token, request) = match token_csrf(request).await {
+            Ok(resultado) => resultado,
+            Err(response) => return response,
+        };
+        let valido = token.is_some_and(|token| {
+            iguales_tiempo_constante(token.as_bytes(), sesion.csrf.as_bytes())
+        });
+        if !valido {
+            return (
+                StatusCode::FORBIDDEN,
+                "Token CSRF ausente o inválido. Recarga la página e inténtalo de nuevo.",
+            )
+                .into_response();
+        }
+        request
+    } else {
+        request
+    };
+
+    request.extensions_mut().insert(sesion);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crear_obtener_y_eliminar() {
+        let almacen = AlmacenSesiones::new();
+        let sesion = almacen.crear(Actor::sistema());
+        assert_eq!(sesion.id.len(), 64);
+        assert_ne!(sesion.id, sesion.csrf);
+
+        assert_eq!(almacen.obtener(&sesion.id).unwrap().csrf, sesion.csrf);
+        almacen.eliminar(&sesion.id);
+        assert!(almacen.obtener(&sesion.id).is_none());
+    }
+
+    #[test]
+    fn test_sesion_caducada() {
+        let almacen = AlmacenSesiones::new().con_duracion(Duration::ZERO);
+        let sesion = almacen.crear(Actor::sistema());
+        assert!(almacen.obtener(&sesion.id).is_none());
+    }
+
+    #[test]
+    fn test_cookie() {
+        let almacen = AlmacenSesiones::new().con_cookie_segura(true);
+        let sesion = almacen.crear(Actor::sistema());
+        let cookie = almacen.cookie(&sesion);
+        let cookie = cookie.to_str().unwrap();
+        assert!(cookie.starts_with(&format!("{}={};", COOKIE_SESION, sesion.id)));
+        assert!(cookie.contains("HttpOnly"));
+        assert!(cookie.ends_with("; Secure"));
+
+        let mut cabeceras = HeaderMap::new();
+        cabeceras.insert(
+            header::COOKIE,
+            HeaderValue::from_str(&format!("otra=1; {}={}", COOKIE_SESION, sesion.id)).unwrap(),
+        );
+        assert_eq!(id_de_cookie(&cabeceras), Some(sesion.id));
+        assert_eq!(id_de_cookie(&HeaderMap::new()), None);
+    }
+
+    #[tokio::test]
+    async fn test_token_csrf_en_formularios() {
+        let formulario = Request::post("/salas/crear")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from("nombre=Sala+1&csrf=abc"))
+            .unwrap();
+        let (token, _) = token_csrf(formulario).await.unwrap();
+        assert_eq!(token.as_deref(), Some("abc"));
+
+        let cuerpo = "--xyz\r\n\
+            Content-Disposition: form-data; name=\"csrf\"\r\n\r\n\
+            abc\r\n\
+            --xyz\r\n\
+            Content-Disposition: form-data; name=\"fichero\"; filename=\"salas.csv\"\r\n\
+            Content-Type: text/csv\r\n\r\n\
+            nombre,capacidad\r\nSala 1,8\r\n\
+            --xyz--\r\n";
+        let multipart = Request::post("/importar/salas")
+            .header(header::CONTENT_TYPE, "multipart/form-data; boundary=xyz")
+            .body(Body::from(cuerpo))
+            .unwrap();
+        let (token, request) = token_csrf(multipart).await.unwrap();
+        assert_eq!(token.as_deref(), Some("abc"));
+        // El cuerpo se conserva para el handler
+        let leido = to_bytes(request.into_body(), LIMITE_FICHERO).await.unwrap();
+        assert_eq!(leido, cuerpo.as_bytes());
+    }
+
+    #[test]
+    fn test_estado_oidc() {
+        let almacen = AlmacenSesiones::new();
+        let cookie = almacen.cookie_oidc("estado-1");
+        assert!(cookie
+            .to_str()
+            .unwrap()
+            .starts_with("reservas_oidc=estado-1;"));
+
+        let mut cabeceras = HeaderMap::new();
+        cabeceras.insert(
+            header::COOKIE,
+            HeaderValue::from_static("reservas_oidc=estado-1"),
+        );
+        assert!(estado_oidc_valido(&cabeceras, "estado-1"));
+        assert!(!estado_oidc_valido(&cabeceras, "estado-2"));
+        assert!(!estado_oidc_valido(&HeaderMap::new(), "estado-1"));
+    }
+}
@@ -2,9 +2,18 @@
 
 use askama::Template;
 use chrono::{DateTime, Utc};
+use reservas_domain::ErrorCampo;
+use serde::{Deserialize, Serialize};
 
 // ============= DTOs para templates =============
 
+/// Usuario conectado y token CSRF que llevan todos los formularios
+#[derive(Debug, Default)]
+pub struct SesionView {
+    pub usuario: String,
+    pub csrf: String,
+}
+
 #[derive(Debug)]
 pub struct EmpleadoView {
     pub id: String,
@@ -21,6 +30,29 @@ pub struct ReservaView {
     pub slot_fin: DateTime<Utc>,
     pub descripcion: String,
     pub estado: String,
+    pub sala_id: Option<String>,
+}
+
+/// Valores actuales del formulario de filtros de reservas
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct FiltrosReservas {
+    #[serde(default)]
+    pub desde: String,
+    #[serde(default)]
+    pub hasta: String,
+    #[serde(default)]
+    pub estado: String,
+    #[serde(default)]
+    pub empleado_id: String,
+    #[serde(default)]
+    pub q: String,
+}
+
+/// Valores actuales del formulario de búsqueda de empleados y salas
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct FiltroTexto {
+    #[serde(default)]
+    pub q: String,
 }
 
 #[derive(Debug)]
@@ -31,38 +63,281 @@ pub struct SalaView {
     pub activa: bool,
 }
 
+/// Errores de validación de un formulario, para mostrarlos junto a cada campo
+#[derive(Debug, Default)]
+pub struct ErroresFormulario(Vec<ErrorCampo>);
+
+impl ErroresFormulario {
+    pub fn new(errores: Vec<ErrorCampo>) -> Self {
+        Self(errores)
+    }
+
+    /// Mensaje del error del campo, si lo tiene
+    pub fn de(&self, campo: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|e| e.campo == campo)
+            .map(|e| e.mensaje.as_str())
+    }
+}
+
 // ============= Templates =============
 
+#[derive(Template, Default)]
+#[template(path = "login.html")]
+pub struct LoginTemplate {
+    /// Usuario enviado, para no perderlo si se rechaza
+    pub usuario: String,
+    pub error: Option<String>,
+    /// Mostrar el acceso con el proveedor OIDC
+    pub sso: bool,
+}
+
 #[derive(Template)]
 #[template(path = "index.html")]
-pub struct IndexTemplate;
+pub struct IndexTemplate {
+    pub sesion: SesionView,
+}
 
 #[derive(Template)]
 #[template(path = "empleados.html")]
 pub struct EmpleadosTemplate {
+    pub sesion: SesionView,
     pub empleados: Vec<EmpleadoView>,
+    pub filtro: FiltroTexto,
+    /// Enlace a la siguiente página, si la hay
+    pub siguiente: Option<String>,
 }
 
-#[derive(Template)]
+#[derive(Template, Default)]
 #[template(path = "empleado_form.html")]
-pub struct EmpleadoFormTemplate;
+pub struct EmpleadoFormTemplate {
+    pub sesion: SesionView,
+    /// Valores enviados, para no perderlos si se rechaza el formulario
+    pub nombre: String,
+    pub email: String,
+    pub errores: ErroresFormulario,
+    pub error: Option<String>,
+}
+
+/// Roles que se pueden asignar desde el formulario de cuentas: (valor, nombre)
+pub const ROLES_CUENTA: [(&str, &str); 4] = [
+    ("empleado", "Empleado"),
+    ("responsable", "Responsable"),
+    ("admin-salas", "Administrador de salas"),
+    ("admin", "Administrador"),
+];
+
+#[derive(Template)]
+#[template(path = "cuenta_form.html")]
+pub struct CuentaFormTemplate {
+    pub sesion: SesionView,
+    pub usuario: String,
+    pub empleado_id: String,
+    pub rol: String,
+    pub roles: [(&'static str, &'static str); 4],
+    pub error: Option<String>,
+}
+
+impl CuentaFormTemplate {
+    fn es_rol(&self, valor: &str) -> bool {
+        self.rol == valor
+    }
+}
+
+#[derive(Template)]
+#[template(path = "password.html")]
+pub struct PasswordTemplate {
+    pub sesion: SesionView,
+    pub error: Option<String>,
+}
 
 #[derive(Template)]
 #[template(path = "salas_form.html")]
-pub struct SalaFormTemplate;
+pub struct SalaFormTemplate {
+    pub sesion: SesionView,
+}
+
+#[derive(Template)]
+#[template(path = "empleado_editar.html")]
+pub struct EmpleadoEditarTemplate {
+    pub sesion: SesionView,
+    pub empleado: EmpleadoView,
+    pub errores: ErroresFormulario,
+    /// Motivo por el que se rechazó el último envío del formulario
+    pub error: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "sala_editar.html")]
+pub struct SalaEditarTemplate {
+    pub sesion: SesionView,
+    pub sala: SalaView,
+    /// Motivo por el que se rechazó el último envío del formulario
+    pub error: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "reserva_editar.html")]
+pub struct ReservaEditarTemplate {
+    pub sesion: SesionView,
+    pub reserva: ReservaView,
+    /// Motivo por el que se rechazó el último envío del formulario
+    pub error: Option<String>,
+}
 
 #[derive(Template)]
 #[template(path = "reservas.html")]
 pub struct ReservasTemplate {
+    pub sesion: SesionView,
     pub reservas: Vec<ReservaView>,
+    pub filtros: FiltrosReservas,
+    /// Enlace a la siguiente página, si la hay
+    pub siguiente: Option<String>,
+}
+
+/// Celda de la tabla de disponibilidad; la actualizan los eventos en vivo
+#[derive(Debug, Serialize)]
+pub struct CeldaDisponibilidadView {
+    pub empleado_id: String,
+    /// Inicio del slot (`%Y-%m-%dT%H:%M`), clave de la celda en la página
+    pub inicio: String,
+    pub disponible: bool,
+    pub descripcion: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct FilaDisponibilidadView {
+    pub empleado_nombre: String,
+    pub celdas: Vec<CeldaDisponibilidadView>,
 }
 
 #[derive(Template)]
 #[template(path = "disponibilidad.html")]
-pub struct DisponibilidadTemplate;
+pub struct DisponibilidadTemplate {
+    pub sesion: SesionView,
+    /// Día consultado (YYYY-MM-DD)
+    pub fecha: String,
+    /// Cabeceras de las columnas (`09:00`...)
+    pub horas: Vec<String>,
+    pub filas: Vec<FilaDisponibilidadView>,
+}
 
 #[derive(Template)]
 #[template(path = "salas.html")]
 pub struct SalasTemplate {
+    pub sesion: SesionView,
     pub salas: Vec<SalaView>,
+    pub filtro: FiltroTexto,
+    /// Enlace a la siguiente página, si la hay
+    pub siguiente: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CeldaOcupacionSalaView {
+    pub libre: bool,
+    /// `Libre`, `Pendiente` o `Confirmada`
+    pub estado: &'static str,
+}
+
+#[derive(Debug)]
+pub struct FilaOcupacionSalaView {
+    pub sala_nombre: String,
+    pub celdas: Vec<CeldaOcupacionSalaView>,
+}
+
+#[derive(Template)]
+#[template(path = "salas_disponibilidad.html")]
+pub struct SalasDisponibilidadTemplate {
+    pub sesion: SesionView,
+    /// Día consultado (YYYY-MM-DD)
+    pub fecha: String,
+    /// Cabeceras de las columnas (`09:00`...)
+    pub horas: Vec<String>,
+    pub filas: Vec<FilaOcupacionSalaView>,
+    /// Franja y capacidad de la búsqueda, tal como se enviaron
+    pub desde: String,
+    pub hasta: String,
+    pub capacidad: String,
+    /// Salas libres en la franja, si se ha buscado
+    pub libres: Option<Vec<SalaView>>,
+    pub error: Option<String>,
+}
+
+/// Uso de un empleado, una sala, una hora o un día en el informe
+#[derive(Debug)]
+pub struct UsoView {
+    /// Nombre, hora (`09:00`) o fecha
+    pub etiqueta: String,
+    pub reservas: usize,
+    pub canceladas: Option<usize>,
+    /// Ocupación en tanto por ciento, con un decimal
+    pub porcentaje: String,
+}
+
+#[derive(Debug)]
+pub struct InformeOcupacionView {
+    pub total: usize,
+    pub pendientes: usize,
+    pub confirmadas: usize,
+    pub canceladas: usize,
+    pub no_presentadas: usize,
+    pub tasa_cancelacion: String,
+    pub tasa_no_presentacion: String,
+    pub por_empleado: Vec<UsoView>,
+    pub por_sala: Vec<UsoView>,
+    pub por_hora: Vec<UsoView>,
+    pub dias_mas_ocupados: Vec<UsoView>,
+}
+
+#[derive(Template)]
+#[template(path = "informes.html")]
+pub struct InformesTemplate {
+    pub sesion: SesionView,
+    /// Periodo consultado (YYYY-MM-DD)
+    pub desde: String,
+    pub hasta: String,
+    pub informe: Option<InformeOcupacionView>,
+    pub error: Option<String>,
+}
+
+/// Campo de la carga masiva y columna de la hoja de la que se lee
+#[derive(Debug)]
+pub struct CampoCargaView {
+    pub nombre: &'static str,
+    pub obligatorio: bool,
+    /// Columna indicada en el formulario; vacía, la del mismo nombre
+    pub columna: String,
+}
+
+#[derive(Debug)]
+pub struct FilaCargaView {
+    pub fila: usize,
+    pub clave: String,
+    pub resultado: &'static str,
+    /// Clase del badge según el resultado
+    pub clase: &'static str,
+    pub errores: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct InformeCargaView {
+    pub creadas: usize,
+    pub actualizadas: usize,
+    pub sin_cambios: usize,
+    pub errores: usize,
+    pub simulado: bool,
+    pub filas: Vec<FilaCargaView>,
+}
+
+#[derive(Template)]
+#[template(path = "carga_masiva.html")]
+pub struct CargaMasivaTemplate {
+    pub sesion: SesionView,
+    /// `empleados` o `salas`, para las rutas
+    pub entidad: &'static str,
+    pub campos: Vec<CampoCargaView>,
+    pub simular: bool,
+    pub informe: Option<InformeCargaView>,
+    pub error: Option<String>,
 }
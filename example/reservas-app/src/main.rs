@@ -6,12 +6,16 @@
 // 3. Conectamos todo mediante inyección de dependencias
 
 use chrono::{Datelike, Timelike, Utc};
-use reservas_adapters::{InMemoryEmpleadoRepository, InMemoryReservaRepository};
+use reservas_adapters::{
+    InMemoryEmpleadoRepository, InMemoryReservaRepository, InMemorySalaRepository,
+};
 use reservas_application::{EmpleadoServiceImpl, ReservaServiceImpl};
-use reservas_domain::{DisponibilidadService, Slot};
+use reservas_domain::{Actor, DisponibilidadService, Slot};
 use reservas_ports::r#in::reserva_service::ReservaService;
 
 use reservas_ports::r#in::empleado_service::EmpleadoService;
+use std::sync::Arc;
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🎯 Sistema de Reservas de Empleados - Arquitectura Hexagonal\n");
@@ -22,21 +26,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!();
 
     // 1. Creamos los adaptadores
-    let empleado_repo = InMemoryEmpleadoRepository::new();
-    let reserva_repo = InMemoryReservaRepository::new();
+    // (compartidos con Arc: varios servicios usan el mismo repositorio)
+    let empleado_repo = Arc::new(InMemoryEmpleadoRepository::new());
+    let reserva_repo = Arc::new(InMemoryReservaRepository::new());
+    let sala_repo = InMemorySalaRepository::new();
 
     // 2. Creamos los servicios de aplicación
-    let empleado_service = EmpleadoServiceImpl::new(empleado_repo);
-    let reserva_service = ReservaServiceImpl::new(reserva_repo);
+    let reserva_service: Arc<dyn ReservaService> = Arc::new(ReservaServiceImpl::new(
+        reserva_repo,
+        Arc::clone(&empleado_repo),
+        sala_repo,
+    ));
+    let empleado_service = EmpleadoServiceImpl::new(empleado_repo, Arc::clone(&reserva_service));
+    // El ejemplo actúa como administrador
+    let admin = Actor::sistema();
 
     // 3. Creamos empleados
     println!("👥 Creando empleados...");
     let emp1 = empleado_service
-        .crear_empleado("Juan López".to_string(), "juan@empresa.com".to_string())
+        .crear_empleado(
+            &admin,
+            "Juan López".to_string(),
+            "juan@empresa.com".to_string(),
+        )
         .await?;
 
     let emp2 = empleado_service
-        .crear_empleado("María García".to_string(), "maria@empresa.com".to_string())
+        .crear_empleado(
+            &admin,
+            "María García".to_string(),
+            "maria@empresa.com".to_string(),
+        )
         .await?;
 
     println!("   ✓ {} creado", emp1.nombre);
@@ -55,9 +75,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     match reserva_service
         .crear_reserva(
+            &admin,
             emp1.id.clone(),
             slot_10.clone(),
             "Reunión con cliente importante".to_string(),
+            None,
         )
         .await
     {
@@ -74,9 +96,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     match reserva_service
         .crear_reserva(
+            &admin,
             emp2.id.clone(),
             slot_11.clone(),
             "Entrevista con candidato".to_string(),
+            None,
         )
         .await
     {
@@ -95,7 +119,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 6. Intentar crear reserva duplicada (debe fallar)
     println!("🔒 Probando validación: reserva duplicada...");
     match reserva_service
-        .crear_reserva(emp1.id.clone(), slot_10.clone(), "Otra reunión".to_string())
+        .crear_reserva(
+            &admin,
+            emp1.id.clone(),
+            slot_10.clone(),
+            "Otra reunión".to_string(),
+            None,
+        )
         .await
     {
         Ok(_) => println!("   ✗ ERROR: No debería haber permitido esto!"),